@@ -62,13 +62,67 @@ void low_level_interrupt_enable(void);
  */
 void low_level_interrupt_disable(void);
 
+/**
+ * Mevcut CPU'da kesmeler açıksa sıfırdan farklı döndürür.
+ */
+int low_level_interrupts_enabled(void);
+
+/**
+ * Kesmeleri kapatır ve önceki kesme durumunu döndürür. Dönen değer mimariye özeldir ve yalnızca
+ * low_level_interrupt_restore'a geri verilmelidir (amd64: RFLAGS.IF, armv9: DAIF,
+ * rv64i: sstatus.SIE, loongarch64: CRMD.IE, powerpc64: MSR[EE], sparcv9: PSTATE.IE).
+ * İç içe çağrılabilir; ksync::Spinlock::lock_irqsave bu çifti kullanır.
+ */
+uint64_t low_level_interrupt_save(void);
+
+/**
+ * low_level_interrupt_save ile kaydedilen kesme durumunu geri yükler.
+ * Kaydedilen durum "kapalı" ise kesmeler kapalı kalır.
+ */
+void low_level_interrupt_restore(uint64_t flags);
+
 /**
  * CPU'yu bekletir (genellikle bir kesme olana kadar).
  */
 void low_level_cpu_halt(void);
 
+/**
+ * Çağıran işlemcinin 0 tabanlı mantıksal indeksini döndürür
+ * (amd64: LAPIC ID eşlemesi, armv9: MPIDR_EL1, rv64i: hart ID (tp), mips64: EBase.CPUNum,
+ *  loongarch64: CPUID CSR, powerpc64: PIR, sparcv9: UPA_CONFIG.MID).
+ * Donanım kimliği, ktask CPU kaydıyla (ktask::cpu_index) indekse çevrilir; boot CPU'su 0'dır.
+ */
+uint32_t low_level_cpu_id(void);
+
+/**
+ * Boot'tan beri geçen monoton süreyi nanosaniye cinsinden döndürür.
+ * (amd64: TSC, armv9: CNTPCT_EL0, rv64i: mtime, mips64: CP0 Count, loongarch64: sabit sayaç,
+ *  powerpc64: Time Base, sparcv9: %tick)
+ */
+uint64_t low_level_monotonic_time_ns(void);
+
+// --- Bellek Yönetim Birimi ---
+// Sayfa tablosu işlemleri (kullanıcı adresi çevirisi dahil) C kancası değildir; her port
+// kmemory::ArchMmu trait'ini Rust'ta implemente edip kmemory::register_arch_mmu ile kaydeder
+// (bkz. src/memory/srcvmspace.rs).
+
+// --- Bağlam Değiştirme ---
+// Bağlam kurma, bağlam değiştirme ve adres alanı etkinleştirme C kancası değildir; her port
+// ktask::ArchTask trait'ini Rust'ta implemente edip boot sırasında ktask::register_arch ile kaydeder
+// (bkz. src/task/srctask.rs ve src/arch/<arch>/srctask_<arch>.rs).
+
+// --- Hata Ayıklama ---
+
+/**
+ * Çağıranın yığınındaki dönüş adreslerini çerçeve işaretçisi zinciri üzerinden toplar.
+ * Kilit sırası doğrulayıcısı (lockdep) raporları için kullanılır.
+ * @param frames Dönüş adreslerinin yazılacağı dizi.
+ * @param max_frames Dizinin kapasitesi.
+ * @return Yazılan adres sayısı.
+ */
+size_t low_level_stack_trace(uint64_t* frames, size_t max_frames);
+
 // TODO: Mimariye özel register okuma/yazma fonksiyonları veya makroları
-// TODO: Bağlam değiştirme (context switching) ile ilgili assembly/intrinsics arayüzleri
 
 #ifdef __cplusplus
 } // extern "C"
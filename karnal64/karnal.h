@@ -29,8 +29,10 @@ typedef int64_t kerror_t;
 #define KERROR_OUT_OF_MEMORY    -12
 #define KERROR_BAD_ADDRESS      -14
 #define KERROR_ALREADY_EXISTS   -17
+#define KERROR_DEADLOCK         -35
 #define KERROR_NOT_SUPPORTED    -38
 #define KERROR_NO_MESSAGE       -61
+#define KERROR_TIMED_OUT       -110
 #define KERROR_INTERNAL_ERROR  -255
 // ... Rust KError enum'undaki diğer hatalar buraya eklenmeli ...
 
@@ -62,7 +64,8 @@ void karnal_init(void);
 // --- Bellek Yönetimi ---
 
 /**
- * Kullanıcı alanı için bellek tahsis eder.
+ * Kullanıcı alanı için bellek tahsis eder. Boyutun tamamı tahsis anında görevin bellek
+ * kotasından (KARNAL_QUOTA_MEMORY) düşülür; sayfalar ilk erişimde sıfırlanıp eşlenir.
  * @param size Tahsis edilecek bellek boyutu.
  * @return Başarı durumunda tahsis edilen bellek adresinin u64'e dönüştürülmüş hali (>=0), hata durumunda negatif kerror_t döner.
  */
@@ -79,15 +82,6 @@ int64_t karnal_memory_release(uint64_t ptr, size_t size); // ptr artık u64 olar
 
 // --- Görev (Task) Yönetimi ---
 
-/**
- * Yeni bir görev (task) başlatır.
- * @param code_handle_value Çalıştırılabilir kod kaynağının handle değeri.
- * @param args_ptr Kullanıcı alanındaki argüman verisi pointer'ı.
- * @param args_len Argüman verisi uzunluğu.
- * @return Başarı durumunda yeni görevin ktid_t değerinin i64'e dönüştürülmüş hali (>=0), hata durumunda negatif kerror_t döner.
- */
-int64_t karnal_task_spawn(khandle_t code_handle_value, const uint8_t* args_ptr, size_t args_len); // Pointerlar kullanıcı adresinde, içeride doğrulanmalı
-
 /**
  * Mevcut görevi belirtilen çıkış koduyla sonlandırır. Geri dönmez.
  * @param code Çıkış kodu.
@@ -110,20 +104,291 @@ int64_t karnal_task_current_id(void); // Karnal64 fns are called by handler, the
 int64_t karnal_task_sleep(uint64_t milliseconds);
 
 /**
- * Yeni bir iş parçacığı (thread) oluşturur.
+ * Mevcut göreve, adres alanını ve handle'ları paylaşan yeni bir iş parçacığı (thread) ekler.
  * @param entry_point Yeni iş parçacığının başlangıç fonksiyon adresinin u64'e dönüştürülmüş hali.
- * @param stack_size Yeni iş parçacığı için ayrılacak yığın boyutu.
+ * @param stack_size Yeni iş parçacığının yığın boyutu (0: çekirdeğin varsayılanı).
  * @param arg Başlangıç fonksiyonuna geçirilecek argümanın u64'e dönüştürülmüş hali.
+ * @param tls İş parçacığının TLS işaretçisi (fs_base/tpidr_el0/tp); 0 olabilir.
+ * @param stack_base Çağıranın ayırdığı yığının tabanı; 0 ise yığını çekirdek ayırır ve iş parçacığı sonlanınca bırakır.
  * @return Başarı durumunda yeni iş parçacığının kthread_id_t değerinin i64'e dönüştürülmüş hali (>=0), hata durumunda negatif kerror_t döner.
  */
-int64_t karnal_thread_create(uint64_t entry_point, size_t stack_size, uint64_t arg);
+int64_t karnal_thread_create(uint64_t entry_point, size_t stack_size, uint64_t arg, uint64_t tls, uint64_t stack_base);
 
 /**
  * Mevcut iş parçacığını belirtilen çıkış koduyla sonlandırır. Geri dönmez.
+ * Görevin son iş parçacığıysa görev de bu kodla sonlanır.
  * @param code Çıkış kodu.
  */
 void karnal_thread_exit(int32_t code) __attribute__((noreturn));
 
+// --- CPU Afinitesi ve Topoloji ---
+
+/** Bir CPU'nun topolojideki yeri (ktask::CpuTopology ile aynı düzen). */
+typedef struct {
+    uint64_t hw_id;    // Donanım kimliği (hart ID, APIC ID, MPIDR afinitesi)
+    uint32_t package;  // Fiziksel paket (soket)
+    uint32_t cluster;  // Paket içindeki küme (big.LITTLE); kümesiz sistemlerde 0
+    uint32_t core;     // Küme içindeki çekirdek
+    uint32_t thread;   // Çekirdek içindeki donanım iş parçacığı (SMT)
+    uint32_t capacity; // Göreli işlem kapasitesi (1..1024); küçük çekirdeklerde daha düşük
+    uint32_t online;   // CPU çevrimiçiyse 1
+} karnal_cpu_topology_t;
+
+/**
+ * Bir iş parçacığının çalışabileceği CPU'ları belirler. Yeni iş parçacıkları ve görevler
+ * maskeyi oluşturan iş parçacığından devralır.
+ * @param thread Çağıranın görevindeki iş parçacığı (0: çağıran iş parçacığı).
+ * @param mask Bit n: n. mantıksal CPU (karnal_cpu_topology ile sorgulanır). Çevrimiçi CPU'larla kesişimi boş olmamalıdır.
+ * @return Başarı durumunda 0, hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_thread_set_affinity(kthread_id_t thread, uint64_t mask);
+
+/**
+ * Bir iş parçacığının afinite maskesini döner.
+ * @param thread Çağıranın görevindeki iş parçacığı (0: çağıran iş parçacığı).
+ * @return Başarı durumunda maske (>=0), hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_thread_get_affinity(kthread_id_t thread);
+
+/**
+ * Bir CPU'nun topolojisini (DTB cpu-map / ACPI MADT) okur.
+ * @param cpu Mantıksal CPU indeksi (0'dan başlar).
+ * @param topology Kullanıcı alanındaki çıktı tamponu.
+ * @return Başarı durumunda kayıtlı CPU sayısı (>0), hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_cpu_topology(uint32_t cpu, karnal_cpu_topology_t* topology);
+
+// --- CPU Süresi ve Zamanlama İstatistikleri ---
+
+/** İş parçacığı/görev zamanlama sayaçları (ktask::ThreadStats ile aynı düzen). Süreler nanosaniyedir. */
+typedef struct {
+    uint64_t user_ns;                 // Kullanıcı modunda geçen CPU süresi
+    uint64_t kernel_ns;               // Çekirdek modunda (sistem çağrıları) geçen CPU süresi
+    uint64_t voluntary_switches;      // Bloklanarak, yield veya çıkışla bırakılan CPU sayısı
+    uint64_t involuntary_switches;    // Kesintiye uğratılarak kaybedilen CPU sayısı
+    uint64_t wakeups;                 // Bloklanmışken uyandırılma sayısı
+    uint64_t wakeup_latency_total_ns; // Uyandırılmadan çalışmaya kadar geçen sürelerin toplamı
+    uint64_t wakeup_latency_max_ns;   // ... ve en büyüğü
+    uint64_t run_wait_ns;             // Hazır kuyruğunda beklenen toplam süre
+} karnal_thread_stats_t;
+
+/**
+ * Bir iş parçacığının zamanlama sayaçlarını okur.
+ * @param thread Çağıranın görevindeki iş parçacığı (0: çağıran iş parçacığı).
+ * @param stats Kullanıcı alanındaki çıktı tamponu.
+ * @return Başarı durumunda 0, hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_thread_stats(kthread_id_t thread, karnal_thread_stats_t* stats);
+
+/**
+ * Bir görevin sonlanmış ve yaşayan tüm iş parçacıklarının toplam sayaçlarını okur.
+ * @param task Çağıran görev veya onun bir çocuğu (0: çağıran görev).
+ * @param stats Kullanıcı alanındaki çıktı tamponu.
+ * @return Başarı durumunda 0, hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_task_stats(ktid_t task, karnal_thread_stats_t* stats);
+
+// --- Deadline (EDF) Zamanlama ---
+
+/**
+ * Bir iş parçacığını deadline sınıfına alır: her `period_ns`'te, periyot başından itibaren
+ * `deadline_ns` içinde `runtime_ns` CPU süresi garanti edilir. Kabul edilen iş parçacığı bir
+ * CPU'ya bağlanır; bütçesini bitirince bir sonraki periyoda kadar çalışmaz.
+ * @param thread Çağıranın görevindeki iş parçacığı (0: çağıran iş parçacığı).
+ * @param runtime_ns Periyot başına çalışma süresi (en az 100 µs). 0: öncelik sınıfına geri dön.
+ * @param deadline_ns Göreli son tarih; runtime_ns <= deadline_ns <= period_ns olmalıdır.
+ * @param period_ns Periyot (en fazla 1 s).
+ * @return Başarı durumunda 0; hiçbir CPU'da yer yoksa KERROR_BUSY, diğer hatalarda negatif kerror_t döner.
+ */
+int64_t karnal_thread_set_deadline(kthread_id_t thread, uint64_t runtime_ns, uint64_t deadline_ns, uint64_t period_ns);
+
+// --- Görev Kotaları ---
+
+#define KARNAL_QUOTA_MEMORY    0 // Kaydedilmiş kullanıcı belleği (bayt; eşleme anında sayılır)
+#define KARNAL_QUOTA_HANDLES   1 // Açık handle sayısı
+#define KARNAL_QUOTA_IPC_BYTES 2 // Kuyruklarda bekleyen IPC mesaj baytları
+#define KARNAL_QUOTA_THREADS   3 // Yaşayan iş parçacığı sayısı
+#define KARNAL_QUOTA_CPU       4 // CPU payı (bir CPU'nun yüzdesi, 100 ms pencerelerde)
+#define KARNAL_QUOTA_COUNT     5
+#define KARNAL_QUOTA_UNLIMITED UINT64_MAX
+
+/** Görev sınırları ve kullanımı (ktask::TaskQuota ile aynı düzen); indeksler KARNAL_QUOTA_*. */
+typedef struct {
+    uint64_t limit[KARNAL_QUOTA_COUNT];
+    uint64_t usage[KARNAL_QUOTA_COUNT]; // CPU için son tamamlanan penceredeki yüzde
+} karnal_task_quota_t;
+
+/**
+ * Bir görevin kaynak sınırını belirler. Yeni görevler sınırları oluşturan görevden devralır.
+ * Sınırı aşan bellek/IPC ayırmaları KERROR_OUT_OF_MEMORY, handle/iş parçacığı ayırmaları
+ * KERROR_BUSY ile reddedilir; CPU payını tüketen görev pencere sonuna kadar bekletilir.
+ * @param task Çağıranın çocuğu (sınır çağıranın kendi sınırını aşamaz) veya 0 (çağıran görev; yalnızca düşürülebilir).
+ * @param resource KARNAL_QUOTA_* kaynağı.
+ * @param limit Yeni sınır veya KARNAL_QUOTA_UNLIMITED.
+ * @return Başarı durumunda 0, hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_task_set_limit(ktid_t task, uint32_t resource, uint64_t limit);
+
+/**
+ * Bir görevin sınırlarını ve kullanımını okur.
+ * @param task Çağıran görev (0) veya onun bir çocuğu.
+ * @param quota Kullanıcı alanındaki çıktı tamponu.
+ * @return Başarı durumunda 0, hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_task_quota(ktid_t task, karnal_task_quota_t* quota);
+
+// --- Görev Başlatma ---
+// Çekirdek isteği doğrular ve yeni görevin ilk iş parçacığının yığın tepesine bir başlangıç bloğu
+// yerleştirir; iş parçacığı yığın işaretçisi ve ilk argüman olarak bloğun adresiyle başlar:
+//   karnal_spawn_start_info_t, argv[argc] + NULL, envp[envc] + NULL ("ANAHTAR=değer"),
+//   karnal_spawn_start_handle_t[handle_count], NUL ile biten dizeler, yığın tepesi.
+
+#define KARNAL_SPAWN_MAX_ARGS    128
+#define KARNAL_SPAWN_MAX_ENV     128
+#define KARNAL_SPAWN_MAX_HANDLES 16
+#define KARNAL_SPAWN_MAX_SLOT    64        // Yuva numaraları bundan küçük olmalıdır
+#define KARNAL_SPAWN_BLOCK_MAX   (16 * 1024) // Başlangıç bloğunun (dizeler dahil) en büyük boyutu
+#define KARNAL_SPAWN_START_MAGIC 0x4B53504E00000001ULL
+
+// Geleneksel yuva numaraları; çekirdek için anlamsızdır.
+#define KARNAL_SPAWN_SLOT_STDIN  0
+#define KARNAL_SPAWN_SLOT_STDOUT 1
+#define KARNAL_SPAWN_SLOT_STDERR 2
+#define KARNAL_SPAWN_SLOT_PARENT 3 // Ebeveyne açılan kanal
+
+/** Kullanıcı alanındaki bir dize (NUL gerekmez). */
+typedef struct {
+    uint64_t ptr;
+    uint64_t len;
+} karnal_spawn_string_t;
+
+/** Ortam değişkeni; anahtar boş olamaz ve '=' içeremez. */
+typedef struct {
+    karnal_spawn_string_t key;
+    karnal_spawn_string_t value;
+} karnal_spawn_env_t;
+
+/** Yeni göreve devredilecek handle ve hedef yuvası. */
+typedef struct {
+    khandle_t handle;
+    uint32_t slot;
+    uint32_t flags; // Ayrılmış, 0 olmalı
+} karnal_spawn_handle_t;
+
+/** karnal_task_spawn isteği (ktask::SpawnRequest ile aynı düzen). */
+typedef struct {
+    khandle_t code_handle;              // Çalıştırılabilir kod kaynağı
+    const karnal_spawn_string_t* argv;
+    uint64_t argc;
+    const karnal_spawn_env_t* envp;
+    uint64_t envc;
+    const karnal_spawn_handle_t* handles;
+    uint64_t handle_count;
+    uint64_t priority;                  // 0: varsayılan, aksi halde 1..=kullanıcı üst sınırı
+    uint64_t limit_mask;                // Bit i ayarlıysa limits[i] yeni görevin KARNAL_QUOTA_i sınırı
+    uint64_t limits[KARNAL_QUOTA_COUNT]; // Çağıranın sınırını aşamaz; diğerleri devralınır
+} karnal_spawn_request_t;
+
+/** Başlangıç bloğunun başı (yeni iş parçacığının yığın işaretçisindeki yapı). */
+typedef struct {
+    uint64_t magic; // KARNAL_SPAWN_START_MAGIC
+    ktid_t parent;
+    uint64_t argc;
+    const char* const* argv;
+    uint64_t envc;
+    const char* const* envp;
+    uint64_t handle_count;
+    const struct karnal_spawn_start_handle* handles;
+} karnal_spawn_start_info_t;
+
+/** Yeni görevde geçerli devredilmiş handle. */
+typedef struct karnal_spawn_start_handle {
+    uint32_t slot;
+    uint32_t reserved;
+    khandle_t handle;
+} karnal_spawn_start_handle_t;
+
+/**
+ * Yeni bir görev (task) başlatır. İş handle'ları yeni görev adına yeniden açılır ve onun handle
+ * kotasından sayılır; yeni görev çağıranın işine girer.
+ * @param request Kullanıcı alanındaki istek.
+ * @param request_len sizeof(karnal_spawn_request_t).
+ * @return Başarı durumunda yeni görevin ktid_t değeri (>=0); başlangıç bloğu KARNAL_SPAWN_BLOCK_MAX'ı
+ *         aşarsa KERROR_INVALID_ARGUMENT, diğer hatalarda negatif kerror_t döner.
+ */
+int64_t karnal_task_spawn(const karnal_spawn_request_t* request, size_t request_len);
+
+// --- İşler (Görev Grupları) ---
+// Her görev bir işe aittir ve oluşturan görevin işine girer; işler iç içedir. İş handle'ları
+// karnal_resource_acquire ile alınır: "karnal://job/self" (çağıranın işi), "karnal://job/new"
+// (çağıranın işinin yeni bir alt işi) veya "karnal://job/<id>" (çağıranın işinin alt ağacındaki iş).
+// İşlemler karnal_resource_control ile yapılır ve işin tüm alt ağacına uygulanır.
+
+#define KARNAL_JOB_CONTROL_ID           0     // İşin ID'sini döner
+#define KARNAL_JOB_CONTROL_KILL         1     // arg: çıkış kodu; çağıran da içindeyse geri dönmez
+#define KARNAL_JOB_CONTROL_SUSPEND      2     // Yalnızca çağıranın işinin alt işleri
+#define KARNAL_JOB_CONTROL_RESUME       3
+#define KARNAL_JOB_CONTROL_STATS        4     // arg: karnal_thread_stats_t* (alt ağacın toplamı)
+#define KARNAL_JOB_CONTROL_QUOTA        5     // arg: karnal_task_quota_t*
+#define KARNAL_JOB_CONTROL_ADD_TASK     6     // arg: görev ID'si (0: çağıran görev) veya çağıranın çocuğu
+#define KARNAL_JOB_CONTROL_CREATE_CHILD 7     // Yeni alt işin handle'ını döner
+#define KARNAL_JOB_CONTROL_SET_LIMIT    0x100 // + KARNAL_QUOTA_*; arg: sınır. Yalnızca alt işler
+
+// --- İstisna Portları ---
+
+#define KARNAL_FAULT_GPR_COUNT 32
+
+// karnal_exception_reply eylemleri
+#define KARNAL_EXCEPTION_RESUME 0 // İş parçacığını (varsa yeni yazmaçlarla) devam ettir
+#define KARNAL_EXCEPTION_KILL   1 // İş parçacığını öldür (son iş parçacığıysa görev hata nedeniyle sonlanır)
+
+// karnal_exception_receive bayrakları
+#define KARNAL_EXCEPTION_RECEIVE_NOHANG (1 << 0)
+
+/** Hatalı iş parçacığının kullanıcı modu yazmaçları (ktask::FaultRegisters ile aynı düzen). */
+typedef struct {
+    uint64_t pc;
+    uint64_t sp;
+    uint64_t status; // Mimariye özel durum yazmacı; yalnızca kullanıcının değiştirebileceği bitleri geri yazılır
+    uint64_t gpr[KARNAL_FAULT_GPR_COUNT]; // Mimarinin yazmaç numaralandırması
+} karnal_fault_regs_t;
+
+/** İşleyici göreve iletilen hata mesajı (ktask::FaultMessage ile aynı düzen). */
+typedef struct {
+    ktid_t task;
+    kthread_id_t thread;
+    kerror_t reason;   // Hata nedeni (ör. KERROR_BAD_ADDRESS)
+    uint64_t cause;    // Mimariye özel istisna kodu
+    uint64_t address;  // Hatalı adres (adres/sayfa hatalarında)
+    karnal_fault_regs_t regs;
+} karnal_fault_message_t;
+
+/**
+ * Bir görevin kullanıcı modu hatalarını işleyici göreve yönlendirir. Hatalı iş parçacığı
+ * işleyici yanıt verene kadar askıya alınır. Yeni görevler ebeveynin portunu devralır.
+ * @param task Hedef görev (0: çağıran görev); çağıranın kendisi veya çocuğu olmalıdır.
+ * @param handler İşleyici görev (0: portu kaldır, hatalar görevi sonlandırır).
+ * @return Başarı durumunda 0, hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_exception_port_set(ktid_t task, ktid_t handler);
+
+/**
+ * Çağıran göreve iletilmiş bir hata gelene kadar bekler ve mesajı tampona kopyalar.
+ * @param message Kullanıcı alanındaki mesaj tamponu.
+ * @param flags KARNAL_EXCEPTION_RECEIVE_* bayrakları.
+ * @return Başarı durumunda hatalı iş parçacığının ID'si (>=0), NOHANG ile hata yoksa KERROR_BUSY, hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_exception_receive(karnal_fault_message_t* message, uint64_t flags);
+
+/**
+ * karnal_exception_receive ile alınan bir hatayı yanıtlar.
+ * @param thread Hatalı iş parçacığının ID'si.
+ * @param action KARNAL_EXCEPTION_RESUME veya KARNAL_EXCEPTION_KILL.
+ * @param regs RESUME için yeni yazmaçlar (ör. PC'yi hatalı komutun ötesine almak için); NULL ise değişmez.
+ * @return Başarı durumunda 0, hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_exception_reply(kthread_id_t thread, uint64_t action, const karnal_fault_regs_t* regs);
+
 /**
  * CPU'yu gönüllü olarak başka bir çalıştırılabilir göreve/iş parçacığına bırakır.
  * @return Başarı durumunda 0, hata durumunda negatif kerror_t döner.
@@ -204,18 +469,129 @@ int64_t karnal_sync_lock_create(void);
 
 /**
  * Kilidi almaya çalışır. Başka bir görev/iş parçacığı tutuyorsa bloklar.
+ * Bekleme süresince kilidin sahibi, bekleyenin önceliğini miras alır.
  * @param handle_value Kilit handle değeri.
- * @return Başarı durumunda 0, hata durumunda negatif kerror_t döner.
+ * @return Başarı durumunda 0; çağıran kilidi zaten tutuyorsa KERROR_DEADLOCK, hata durumunda negatif kerror_t döner.
  */
 int64_t karnal_sync_lock_acquire(khandle_t handle_value);
 
 /**
  * Kilidi serbest bırakır. Çağıranın kilidi tutuyor olması gerekir.
  * @param handle_value Kilit handle değeri.
- * @return Başarı durumunda 0, hata durumunda negatif kerror_t döner.
+ * @return Başarı durumunda 0; çağıran sahip değilse KERROR_PERMISSION_DENIED, hata durumunda negatif kerror_t döner.
  */
 int64_t karnal_sync_lock_release(khandle_t handle_value);
 
+/** karnal_sync_futex_wait için sonsuz bekleme değeri. */
+#define KARNAL_FUTEX_WAIT_FOREVER UINT64_MAX
+
+/**
+ * `addr`'deki 32-bit değer hâlâ `expected` ise, aynı adreste karnal_sync_futex_wake çağrılana
+ * veya zaman aşımı dolana kadar bekler. Adres fiziksel sayfa + ofset ile eşleştirilir; farklı
+ * görevler paylaşılan bir sayfa üzerinden aynı futex'i kullanabilir.
+ * @param addr Kullanıcı alanında 4 byte hizalı adres.
+ * @param expected Beklenen değer.
+ * @param timeout_ns Nanosaniye cinsinden zaman aşımı (KARNAL_FUTEX_WAIT_FOREVER: sınırsız).
+ * @return Uyandırılınca 0; değer farklıysa KERROR_BUSY, süre dolarsa KERROR_TIMED_OUT, geçersiz adreste KERROR_BAD_ADDRESS döner.
+ */
+int64_t karnal_sync_futex_wait(const uint32_t* addr, uint32_t expected, uint64_t timeout_ns);
+
+/**
+ * `addr`'de bekleyen en fazla `count` iş parçacığını uyandırır.
+ * @param addr Kullanıcı alanında 4 byte hizalı adres.
+ * @param count Uyandırılacak en fazla iş parçacığı sayısı.
+ * @return Başarı durumunda uyandırılan iş parçacığı sayısı (>=0), hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_sync_futex_wake(const uint32_t* addr, uint64_t count);
+
+/** Zaman aşımlı bekleme çağrıları için sonsuz bekleme değeri. */
+#define KARNAL_WAIT_FOREVER UINT64_MAX
+
+/** karnal_sync_wait_many'de tek çağrıda beklenebilecek en fazla handle sayısı. */
+#define KARNAL_MAX_WAIT_HANDLES 16
+
+/**
+ * Sayan bir semafor oluşturur.
+ * @param initial_count Başlangıç sayacı (max_count'u aşamaz).
+ * @param max_count Sayacın alabileceği en büyük değer (>0).
+ * @return Başarı durumunda semafor handle'ı (>=0), hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_sync_semaphore_create(uint32_t initial_count, uint32_t max_count);
+
+/**
+ * Semafor sayacını `n` artırır ve en fazla `n` bekleyeni uyandırır.
+ * Semafor beklemesi karnal_sync_wait_many ile yapılır.
+ * @return Başarı durumunda 0; sayaç max_count'u aşacaksa KERROR_INVALID_ARGUMENT döner.
+ */
+int64_t karnal_sync_semaphore_post(khandle_t handle_value, uint32_t n);
+
+/**
+ * Bir kilit handle'ı ile eşlenmiş koşul değişkeni oluşturur.
+ * Beklemek için, eşli kilit tutulurken karnal_sync_wait_many çağrılır: kilit bekleme süresince
+ * bırakılır ve dönüşte (zaman aşımında da) tekrar tutulur. Kilit tutulmuyorsa KERROR_PERMISSION_DENIED döner.
+ * @param lock_handle_value karnal_sync_lock_create ile oluşturulmuş kilit.
+ * @return Başarı durumunda koşul değişkeni handle'ı (>=0), hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_sync_condvar_create(khandle_t lock_handle_value);
+
+/**
+ * Koşul değişkeninde bekleyenleri uyandırır.
+ * @param notify_all Sıfırdan farklıysa tüm bekleyenler, değilse biri uyandırılır.
+ * @return Başarı durumunda 0, hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_sync_condvar_notify(khandle_t handle_value, int notify_all);
+
+/**
+ * Okuyucu-yazıcı kilidi oluşturur. Bekleyen yazıcılar yeni okuyuculara göre önceliklidir.
+ * Yazma (özel) kilidi karnal_sync_wait_many ile alınır.
+ * @return Başarı durumunda rwlock handle'ı (>=0), hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_sync_rwlock_create(void);
+
+/**
+ * Okuma (paylaşımlı) kilidini alır, gerekirse bloklar.
+ * @return Başarı durumunda 0; çağıran yazma kilidini tutuyorsa KERROR_DEADLOCK döner.
+ */
+int64_t karnal_sync_rwlock_acquire_read(khandle_t handle_value);
+
+/**
+ * Tutulan okuma veya yazma kilidini bırakır.
+ * @return Başarı durumunda 0; hiçbir kilit tutulmuyorsa KERROR_PERMISSION_DENIED döner.
+ */
+int64_t karnal_sync_rwlock_release(khandle_t handle_value);
+
+/**
+ * Olay nesnesi oluşturur.
+ * @param manual_reset Sıfırdan farklıysa olay karnal_sync_event_reset çağrılana kadar sinyalli kalır
+ *                     ve tüm bekleyenleri geçirir; değilse tek bir bekleyeni geçirip kendiliğinden sıfırlanır.
+ * @param initially_set Sıfırdan farklıysa olay sinyalli başlar.
+ * @return Başarı durumunda olay handle'ı (>=0), hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_sync_event_create(int manual_reset, int initially_set);
+
+/** Olayı sinyaller. @return Başarı durumunda 0, hata durumunda negatif kerror_t döner. */
+int64_t karnal_sync_event_set(khandle_t handle_value);
+
+/** Olayı sinyalsiz duruma getirir. @return Başarı durumunda 0, hata durumunda negatif kerror_t döner. */
+int64_t karnal_sync_event_reset(khandle_t handle_value);
+
+/**
+ * Verilen handle'lardan herhangi biri sinyallenene kadar bekler ve onu tüketir:
+ * semafor sayacı azaltılır, otomatik sıfırlamalı olay sıfırlanır, kilit ve rwlock (yazma) alınır,
+ * koşul değişkeni için bildirim tüketilir. Birden fazlası hazırsa en düşük indeksli seçilir.
+ * @param handles Kullanıcı alanında handle dizisi (kilit ve senkronizasyon nesnesi handle'ları karışık olabilir).
+ * @param count Handle sayısı (1..KARNAL_MAX_WAIT_HANDLES).
+ * @param timeout_ns Nanosaniye cinsinden zaman aşımı (0: yalnızca yokla, KARNAL_WAIT_FOREVER: sınırsız).
+ * @return Başarı durumunda sinyallenen handle'ın indeksi (>=0); süre dolarsa KERROR_TIMED_OUT, hata durumunda negatif kerror_t döner.
+ */
+int64_t karnal_sync_wait_many(const khandle_t* handles, size_t count, uint64_t timeout_ns);
+
+/**
+ * Semafor, koşul değişkeni, rwlock veya olay handle'ını yok eder.
+ * @return Başarı durumunda 0; nesnede bekleyen varsa KERROR_BUSY döner.
+ */
+int64_t karnal_sync_destroy(khandle_t handle_value);
+
 
 // --- Mesajlaşma / IPC ---
 
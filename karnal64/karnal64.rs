@@ -1,8 +1,51 @@
-#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz, çekirdek alanında çalışırız
+#![cfg_attr(not(test), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz, çekirdek alanında çalışırız
 
 // Geliştirme sırasında kullanılmayan kod veya argümanlar için izinler
 #![allow(dead_code)]
 #![allow(unused_variables)]
+// Çekirdek koduna özgü clippy istisnaları: tablolar atomik alanlı sabitlerle ilklendirilir,
+// sistem çağrısı giriş noktaları ham kullanıcı işaretçilerini kmemory ile doğrular, unsafe
+// fonksiyonların güvenlik koşulları Türkçe belgelenir ve hizalama denetimleri `%` ile yazılır.
+#![allow(clippy::declare_interior_mutable_const, clippy::not_unsafe_ptr_arg_deref)]
+#![allow(clippy::missing_safety_doc, clippy::manual_is_multiple_of)]
+
+// `feature = "alloc"`: Box/Vec/BTreeMap çekirdek yığınından (kmemory slab ayırıcısı) karşılanır.
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+// --- Çekirdek Konsolu ---
+// Çekirdek modüllerinin println! çıktısı düşük seviyeli konsola yazılır (bkz. hardware_specific.h).
+// Barındırıcıda (host) çalışan birim testleri std'nin println!'ini kullanır.
+
+#[cfg(not(test))]
+extern "C" {
+    /// Bir karakteri düşük seviyeli konsola (örn. seri port) yazar.
+    fn low_level_console_putc(c: core::ffi::c_char);
+}
+
+/// `core::fmt` çıktısını `low_level_console_putc` ile bayt bayt yazan konsol yazıcısı.
+#[cfg(not(test))]
+pub struct ConsoleWriter;
+
+#[cfg(not(test))]
+impl core::fmt::Write for ConsoleWriter {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for byte in s.bytes() {
+            unsafe { low_level_console_putc(byte as core::ffi::c_char) };
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(test))]
+macro_rules! println {
+    () => {
+        println!("")
+    };
+    ($($arg:tt)*) => {{
+        let _ = core::fmt::Write::write_fmt(&mut $crate::ConsoleWriter, format_args!("{}\n", format_args!($($arg)*)));
+    }};
+}
 
 // Çekirdek içinde kullanılacak temel Karnal64 tipleri
 // Bunlar, kullanıcı alanındaki Sahne64 tiplerinden (Handle, TaskId, SahneError) ayrıdır.
@@ -31,10 +74,14 @@ pub enum KError {
     BadAddress = -14,
     /// Kaynak zaten mevcut (isim çakışması gibi)
     AlreadyExists = -17,
+    /// İşlem kilitlenmeye (deadlock) yol açardı (örn. sahip olunan kilidi tekrar almak)
+    Deadlock = -35,
     /// İşlem desteklenmiyor
     NotSupported = -38,
     /// Mesajlaşma için: Mesaj yok (non-blocking receive)
     NoMessage = -61,
+    /// Bekleme süresi doldu (örn. zaman aşımlı futex beklemesi)
+    TimedOut = -110,
     /// Dahili çekirdek hatası (normalde olmamalı)
     InternalError = -255,
     // İhtiyaç duyuldukça diğer çekirdek içi hata türleri eklenebilir
@@ -70,7 +117,8 @@ pub struct KHandle(u64);
 /// Bir kaynak (cihaz, dosya, IPC kanalı vb.) sağlayan çekirdek bileşenlerinin
 /// implemente etmesi gereken temel trait.
 /// Bu trait, Karnal64'ün diğer çekirdek modüllerinden beklediği arayüzdür.
-pub trait ResourceProvider {
+/// Sağlayıcılar tüm CPU'lar ve görevler arasında paylaşıldığından `Sync` olmalıdır.
+pub trait ResourceProvider: Sync {
     /// Kaynaktan veri okur.
     /// `offset`: Okumaya başlanacak ofset (kaynağa özel anlamı olabilir).
     /// `buffer`: Okunan verinin yazılacağı çekirdek alanı tamponu.
@@ -90,8 +138,8 @@ pub trait ResourceProvider {
     fn control(&self, request: u64, arg: u64) -> Result<i64, KError>;
 
     // İhtiyaca göre başka kaynak işlemleri eklenebilir (seek, stat, mmap_frame vb.)
-     fn seek(&self, position: KseekFrom) -> Result<u64, KError>;
-     fn get_status(&self) -> Result<KResourceStatus, KError>;
+    // fn seek(&self, position: KseekFrom) -> Result<u64, KError>;
+    // fn get_status(&self) -> Result<KResourceStatus, KError>;
 }
 
 /// Kilitleme (Lock) mekanizmaları sağlayan çekirdek bileşenlerinin implemente edeceği trait.
@@ -133,8 +181,8 @@ pub fn init() {
     // TODO: Temel çekirdek kaynaklarını (konsol, null cihaz, boot diski, vb.)
     //       ResourceProvider traitini implemente ederek Kaynak Kayıt Yöneticisine kaydet.
     // Örnek: Dummy konsol kaynağını kaydetme (ResourceProvider trait implementasyonunu ve kayıt mekanizmasını gerektirir)
-     let console_provider = Box::new(kresource::implementations::DummyConsole); // 'alloc' veya statik yönetim gerekir
-     kresource::register_provider("karnal://device/console", console_provider).expect("Failed to register console");
+    // let console_provider = Box::new(kresource::implementations::DummyConsole); // 'alloc' veya statik yönetim gerekir
+    // kresource::register_provider("karnal://device/console", console_provider).expect("Failed to register console");
 }
 
 
@@ -163,21 +211,22 @@ pub fn resource_acquire(resource_id_ptr: *const u8, resource_id_len: usize, mode
     // Bu, Kaynak Kayıt Yöneticisi aracılığıyla yapılır.
     let resource_name = core::str::from_utf8(id_slice).map_err(|_| KError::InvalidArgument)?; // ID'nin UTF8 isim olduğunu varsayalım
 
-    // TODO: Kaynak Kayıt Yöneticisinde `resource_name` ile ResourceProvider'ı ara.
-     let provider = kresource::lookup_provider_by_name(resource_name)?;
-
-    // TODO: Talep edilen `mode`'un, bulunan `provider` tarafından desteklenip desteklenmediğini kontrol et.
-     if !provider.supports_mode(mode) { return Err(KError::PermissionDenied); }
-
-    // TODO: Provider için yeni bir çekirdek Handle'ı (KHandle) oluştur ve yöneticiye kaydet.
-    // Handle Yöneticisi, kullanıcıya verilen ham u64 değerini, çekirdek içindeki bu provider instance'ına eşler.
-     let k_handle = kresource::issue_handle(provider, mode);
+    // Görev grupları (karnal://job/...) kaynak sağlayıcıları yerine ktask tarafından çözülür.
+    if let Some(job_name) = resource_name.strip_prefix(ktask::JOB_RESOURCE_PREFIX) {
+        return ktask::job_acquire(job_name);
+    }
+    // Paylaşımlı bellek nesneleri (karnal://shm/...) kmemory tarafından oluşturulur.
+    if let Some(shm_name) = resource_name.strip_prefix(kmemory::SHM_RESOURCE_PREFIX) {
+        return kmemory::shm_acquire(shm_name);
+    }
 
-    // Yer Tutucu: Dummy bir handle ve başarı döndür
-    let dummy_handle_value = 123; // Bu değer Handle Yöneticisinden gelmeli
-    Ok(KHandle(dummy_handle_value))
+    // Diğer isimler kayıtlı ResourceProvider'lara çözülür; handle çağıran göreve aittir.
+    kresource::issue_handle(resource_name, mode)
 }
 
+/// Kaynak okuma/yazmada kullanıcı tamponu ile sağlayıcı arasında bir seferde kopyalanan bayt sayısı.
+const RESOURCE_IO_CHUNK: usize = 256;
+
 /// Kullanıcı alanından gelen bir kaynak okuma (read) isteğini işler.
 /// `k_handle_value`: Kullanıcıdan gelen ham handle değeri.
 /// `user_buffer_ptr`: Kullanıcı alanındaki okuma tamponu pointer'ı.
@@ -194,42 +243,24 @@ pub fn resource_read(k_handle_value: u64, user_buffer_ptr: *mut u8, user_buffer_
         return Ok(0); // Sıfır byte okumak geçerli
     }
 
-    // TODO: Ham handle değerini (k_handle_value) kullanarak Handle Yöneticisinden ilgili KHandle'ı ve ResourceProvider'ı çöz.
-    // let k_handle = KHandle(k_handle_value);
-     let provider = kresource::get_provider_by_handle(&k_handle)?;
-
-    // TODO: Handle'ın okuma iznine sahip olup olmadığını kontrol et.
-     if !kresource::handle_has_permission(&k_handle, kresource::MODE_READ) { return Err(KError::PermissionDenied); }
-
-    // Çekirdek içinde, kullanıcı tamponuna doğrudan erişecek bir slice oluştur.
-    // Bu, kullanıcı belleğine erişimin çekirdek tarafından yönetildiğini varsayar.
-    let user_buffer_slice = unsafe {
-        // Güvenlik: user_buffer_ptr ve user_buffer_len'in geçerli kullanıcı alanı adreslerini gösterdiği ve yazılabilir olduğu varsayılır (veya doğrulanır).
-        core::slice::from_raw_parts_mut(user_buffer_ptr, user_buffer_len)
-    };
-
-    // TODO: ResourceProvider'ın `read` metodunu çağır.
-    // provider.read metodu çekirdek içi bir tampona okuma yapabilir,
-    // sonra bu tampon kullanıcı tamponuna kopyalanır VEYA,
-    // provider.read doğrudan kullanıcı tamponuna yazmak için user_buffer_slice'ı alabilir.
-    // İkinci yaklaşım daha direkt, ancak provider'ın kullanıcı belleğiyle etkileşimine dikkat etmek gerekir.
-    // Örnek olarak provider'ın doğrudan slice aldığını varsayalım:
-     let bytes_read = provider.read(user_buffer_slice, handle_current_offset)?; // Handle'ın güncel ofset bilgisi gerekiyorsa
-
-    // Yer Tutucu: Okuma işlemini simüle et
-    let simulated_read_bytes = core::cmp::min(user_buffer_len, 5); // 5 byte okuma simülasyonu
-    if simulated_read_bytes > 0 {
-        unsafe {
-            // Simüle edilmiş veri yaz (örn. 'K' harfi)
-            core::ptr::write_bytes(user_buffer_ptr, b'K', simulated_read_bytes);
+    // Sağlayıcı çekirdek içi bir tampona okur; veri parça parça kullanıcı tamponuna kopyalanır.
+    // Kısa bir okuma (kaynağın sonu) döngüyü bitirir; ofset handle'da ilerletilir.
+    let mut chunk = [0u8; RESOURCE_IO_CHUNK];
+    let mut done = 0;
+    while done < user_buffer_len {
+        let len = core::cmp::min(user_buffer_len - done, RESOURCE_IO_CHUNK);
+        let read = match kresource::handle_read(k_handle_value, &mut chunk[..len]) {
+            Ok(read) => read,
+            Err(_) if done > 0 => break,
+            Err(err) => return Err(err),
+        };
+        kmemory::copy_to_user(unsafe { user_buffer_ptr.add(done) }, chunk.as_ptr(), read)?;
+        done += read;
+        if read < len {
+            break;
         }
     }
-    let bytes_read = simulated_read_bytes;
-
-    // TODO: Handle'ın güncel ofsetini güncelle (kaynak seekable ise).
-     kresource::update_handle_offset(&k_handle, bytes_read);
-
-    Ok(bytes_read) // Başarı
+    Ok(done)
 }
 
 /// Kullanıcı alanından gelen bir kaynak yazma (write) isteğini işler.
@@ -248,31 +279,23 @@ pub fn resource_write(k_handle_value: u64, user_buffer_ptr: *const u8, user_buff
         return Ok(0); // Sıfır byte yazmak geçerli
     }
 
-    // TODO: Ham handle değerini kullanarak KHandle'ı ve ResourceProvider'ı çöz.
-     let k_handle = KHandle(k_handle_value);
-     let provider = kresource::get_provider_by_handle(&k_handle)?;
-
-    // TODO: Handle'ın yazma iznine sahip olup olmadığını kontrol et.
-     if !kresource::handle_has_permission(&k_handle, kresource::MODE_WRITE) { return Err(KError::PermissionDenied); }
-
-    let user_buffer_slice = unsafe {
-        // Güvenlik: user_buffer_ptr ve user_buffer_len'in geçerli kullanıcı alanı adreslerini gösterdiği ve okunabilir olduğu varsayılır (veya doğrulanır).
-        core::slice::from_raw_parts(user_buffer_ptr, user_buffer_len)
-    };
-
-    // TODO: ResourceProvider'ın `write` metodunu çağır.
-    // provider.write metodu, kullanıcı tamponundaki veriyi alır ve kaynağa yazar.
-     let bytes_written = provider.write(user_buffer_slice, handle_current_offset)?; // Ofset bilgisi gerekiyorsa
-
-    // Yer Tutucu: Yazma işlemini simüle et
-    let simulated_write_bytes = core::cmp::min(user_buffer_len, 5); // 5 byte yazma simülasyonu
-    // Gerçek senaryoda user_buffer_slice içeriği kullanılacaktır.
-    let bytes_written = simulated_write_bytes;
-
-    // TODO: Handle'ın güncel ofsetini güncelle.
-     kresource::update_handle_offset(&k_handle, bytes_written);
-
-    Ok(bytes_written) // Başarı
+    // Kullanıcı verisi parça parça çekirdek tamponuna kopyalanıp sağlayıcıya yazılır.
+    let mut chunk = [0u8; RESOURCE_IO_CHUNK];
+    let mut done = 0;
+    while done < user_buffer_len {
+        let len = core::cmp::min(user_buffer_len - done, RESOURCE_IO_CHUNK);
+        kmemory::copy_from_user(chunk.as_mut_ptr(), unsafe { user_buffer_ptr.add(done) }, len)?;
+        let written = match kresource::handle_write(k_handle_value, &chunk[..len]) {
+            Ok(written) => written,
+            Err(_) if done > 0 => break,
+            Err(err) => return Err(err),
+        };
+        done += written;
+        if written < len {
+            break;
+        }
+    }
+    Ok(done)
 }
 
 
@@ -280,47 +303,69 @@ pub fn resource_write(k_handle_value: u64, user_buffer_ptr: *const u8, user_buff
 /// `k_handle_value`: Kullanıcıdan gelen ham handle değeri.
 /// Başarı veya KError döner.
 pub fn resource_release(k_handle_value: u64) -> Result<(), KError> {
-    // TODO: Ham handle değerini kullanarak Handle Yöneticisinden KHandle'ı çöz ve serbest bırak.
-    // Bu, Handle Yöneticisindeki ilgili kaydı geçersiz kılmalı ve gerekiyorsa
-    // alttaki ResourceProvider'a Handle'ın artık kullanılmadığı bilgisini iletmelidir.
-     let k_handle = KHandle(k_handle_value);
-     kresource::release_handle(&k_handle)?;
-
-    // Yer Tutucu: Serbest bırakmayı simüle et
-     if k_handle_value == 0 { // 0'ın geçersiz handle olduğunu varsayalım
-        Err(KError::BadHandle)
-    } else {
-         Ok(()) // Başarı
+    if ktask::is_job_handle(k_handle_value) {
+        return ktask::job_release(k_handle_value);
+    }
+    if kmemory::is_shm_handle(k_handle_value) {
+        return kmemory::shm_release(k_handle_value);
     }
+    // Kalan handle'lar kresource'un görev başına kaynak handle'larıdır; başka değerler BadHandle.
+    kresource::release_handle(k_handle_value)
 }
 
-// TODO: resource_control fonksiyonunu da benzer şekilde ResourceProvider'a dispatch edecek şekilde implemente et.
+/// Kullanıcı alanından gelen bir kaynak kontrol isteğini işler.
+/// `k_handle_value`: Kullanıcıdan gelen ham handle değeri.
+/// `request`, `arg`: Kaynağa özel komut kodu ve argümanı (iş handle'ları için ktask::JOB_CONTROL_*,
+/// paylaşımlı bellek handle'ları için kmemory::SHM_CONTROL_*; kaynak handle'larında sağlayıcıya iletilir).
+/// Başarı durumunda komutun sonucu, hata durumunda KError döner.
+pub fn resource_control(k_handle_value: u64, request: u64, arg: u64) -> Result<u64, KError> {
+    if ktask::is_job_handle(k_handle_value) {
+        return ktask::job_control(k_handle_value, request, arg);
+    }
+    if kmemory::is_shm_handle(k_handle_value) {
+        return kmemory::shm_control(k_handle_value, request, arg);
+    }
+    kresource::handle_control(k_handle_value, request, arg)
+}
 
 
-// TODO: memory_allocate, memory_release, shared_mem_create/map/unmap fonksiyonlarını implemente et.
-// Bu fonksiyonlar, çekirdeğin bellek yönetimi alt sistemiyle (kmemory modülü) etkileşime girer.
-// Kullanıcı alanına döndürülen pointer'lar (allocate, map) mutlaka kullanıcı alanında geçerli
-// adresleri işaret etmeli ve çekirdek tarafından yönetilmelidir.
- memory_allocate(size: usize) -> Result<*mut u8, KError>;
- memory_release(ptr: *mut u8, size: usize) -> Result<(), KError>;
- shared_mem_create(size: usize) -> Result<KHandle, KError>;
- shared_mem_map(k_handle_value: u64, offset: usize, size: usize) -> Result<*mut u8, KError>;
- shared_mem_unmap(ptr: *mut u8, size: usize) -> Result<(), KError>;
+// Bellek sistem çağrıları (memory_alloc, free, protect, map, unmap) kmemory modülünde implemente
+// edilir (bkz. src/memory/srcvmspace.rs). Her kullanıcı görevinin adres alanı bir VMA ağacıdır;
+// ayırma boş aralık arar, kaldırma ve koruma değişikliği VMA'ları aralık sınırlarında böler ve sayfa
+// tabloları portun kmemory::ArchMmu implementasyonuyla VMA'lardan doldurulur. Paylaşımlı bellek
+// nesneleri "karnal://shm/new/<boyut>" kaynak handle'larıyla oluşturulur ve MAP_SHARED ile eşlenir.
+
+/// Kullanıcı alanından gelen bir bellek eşleme isteğini işler.
+/// `prot_flags`: Alt 32 bit kmemory::PROT_*, üst 32 bit kmemory::MAP_* bayrakları.
+/// `k_handle_value`: MAP_ANONYMOUS için 0; aksi halde paylaşımlı bellek veya kaynak handle'ı.
+/// Başarı durumunda eşlemenin adresini döner.
+pub fn memory_map(addr: u64, size: u64, prot_flags: u64, k_handle_value: u64, offset: u64) -> Result<u64, KError> {
+    let prot = prot_flags as u32;
+    let flags = (prot_flags >> 32) as u32;
+    let source = if flags & kmemory::MAP_ANONYMOUS != 0 {
+        if k_handle_value != 0 {
+            return Err(KError::InvalidArgument);
+        }
+        kmemory::MapSource::Anonymous
+    } else if kmemory::is_shm_handle(k_handle_value) {
+        kmemory::MapSource::Shared(k_handle_value)
+    } else {
+        kmemory::MapSource::Resource(kresource::get_provider_by_handle(k_handle_value)?)
+    };
+    kmemory::memory_map(addr as usize, size as usize, prot, flags, source, offset).map(|addr| addr as u64)
+}
 
 
-// TODO: task_spawn, task_exit, get_task_id, task_sleep, task_yield, thread_create, thread_exit fonksiyonlarını implemente et.
-// Bu fonksiyonlar, çekirdeğin görev/iş parçacığı yönetimi ve zamanlayıcısı (ktask modülü) ile etkileşime girer.
-// task_spawn, çalıştırılabilir kod kaynağının handle'ını alıp, yeni görev/adres alanı oluşturup kodu yüklemeli,
-// başlangıç iş parçacığını yaratmalı ve zamanlayıcıya eklemelidir.
-// task_exit doğrudan zamanlayıcıyı çağırarak mevcut görevi sonlandırır, geri dönmez.
-// get_task_id mevcut görev/iş parçacığı kontrol bloğundan ID'yi okur.
+// Görev ve iş parçacığı sistem çağrıları (task_spawn, task_exit, task_wait, thread_create, thread_exit,
+// get_task_id, task_sleep, task_yield ve zamanlama/kota/iş denetimleri) mimariden bağımsız ktask
+// modülünde implemente edilir (bkz. src/task/srctask.rs); portlar yalnızca ktask::ArchTask sağlar.
+// TODO: task_spawn için çalıştırılabilir kod kaynağını yeni bir adres alanına yükleyen yükleyici.
 
 
-// TODO: sync_lock_create, acquire, release fonksiyonlarını implemente et.
-// Bu fonksiyonlar, çekirdeğin senkronizasyon primitifleri (ksync modülü) ile etkileşime girer.
-// Bunlar, kullanıcı alanındaki Sahne64 Lock API'sının altında yatan çekirdek mekanizmalarıdır.
-// lock_create dahili bir kilit nesnesi yaratıp buna bir KHandle atar.
-// acquire/release dahili kilit nesnesi üzerinde işlemleri yapar, gerekirse çağıran görevi bloklar/uyandırır.
+// Kilit sistem çağrıları (lock_create, acquire, release) ksync modülünde implemente edilir
+// (bkz. src/sync/srcmutex.rs). Bunlar, kullanıcı alanındaki Sahne64 Lock API'sının altında yatan
+// çekirdek mekanizmalarıdır: lock_create dahili bir RawMutex yuvası ayırıp buna bir KHandle atar,
+// acquire/release sahip takibi ve öncelik mirasıyla çağıran görevi bloklar/uyandırır.
 
 
 // TODO: messaging_send, messaging_receive fonksiyonlarını implemente et.
@@ -337,72 +382,29 @@ pub fn resource_release(k_handle_value: u64) -> Result<(), KError> {
 // Bu modüller, Karnal64 API fonksiyonları tarafından çağrılan asıl çekirdek mantığını içerir.
 // Gerçek bellek yöneticisi, zamanlayıcı, sürücü arayüzleri vb. burada implemente edilir.
 
-mod kresource {
-    use super::*;
-    // TODO: Kayıtlı ResourceProvider'ları, Handle-to-Provider eşleşmesini ve handle durumlarını (offset, izinler) yöneten yapılar ve fonksiyonlar.
-    // `no_std` uyumlu veri yapıları (fixed-size array, custom map) ve güvenli pointer yönetimi gerekir.
-
-    pub fn init_manager() {
-        // Placeholder başlatma
-         println!("Karnal64: Kaynak Yöneticisi Başlatıldı (Yer Tutucu)"); // Çekirdek içi print! gerektirir
-    }
+// Kaynak sağlayıcı kaydı ve görev başına kaynak handle'ları (bkz. src/srcresource.rs)
+#[path = "../src/srcresource.rs"]
+mod kresource;
 
-    // Örnek: Kaynak modları (Karnal64'ün kendi tanımları)
-    pub const MODE_READ: u32 = 1 << 0;
-    pub const MODE_WRITE: u32 = 1 << 1;
-    pub const MODE_CREATE: u32 = 1 << 2;
-    // TODO: Diğer modlar...
+// Görev/İş Parçacığı yönetimi ve zamanlayıcı (bkz. src/task/srctask.rs)
+#[path = "../src/task/srctask.rs"]
+pub mod ktask;
 
-    // TODO: Çekirdek bileşenlerinin ResourceProvider'larını kaydetmesi için fonksiyon:
-     pub fn register_provider(id: &str, provider: Box<dyn ResourceProvider>) -> Result<KHandle, KError> { ... }
+// Bellek yönetimi: kullanıcı adresi doğrulama, kopyalama, adres çevirisi, fiziksel çerçeve ayırıcı
+// (bkz. src/memory/srckmemory.rs)
+#[path = "../src/memory/srckmemory.rs"]
+pub mod kmemory;
 
-    // TODO: Kullanıcı handle değerini çözerek provider'a erişim için fonksiyon:
-     pub fn get_provider_by_handle(handle: u64) -> Result<&'static dyn ResourceProvider, KError> { ... }
-
-    // TODO: Handle durum yönetimi fonksiyonları:
-     pub fn issue_handle(provider: &dyn ResourceProvider, mode: u32) -> KHandle { ... }
-     pub fn handle_has_permission(handle: &KHandle, mode: u32) -> bool { ... }
-     pub fn release_handle(handle: u64) -> Result<(), KError> { ... }
-
-    // TODO: Dummy ResourceProvider implementasyonları (test veya çekirdek içi temel kaynaklar için)
-}
-
-mod ktask {
-    use super::*;
-    // TODO: Görev (Task) ve İş Parçacığı (Thread) kontrol blokları, zamanlayıcı kuyrukları, bağlam değiştirme mantığı.
-
-    pub fn init_manager() {
-        // Placeholder başlatma
-         println!("Karnal64: Görev Yöneticisi Başlatıldı (Yer Tutucu)");
-    }
-    // TODO: task/thread create, exit, schedule, sleep, yield, current_id implementasyonları
-}
-
-mod kmemory {
-     use super::*;
-    // TODO: Fiziksel bellek ayırıcı, sanal bellek yöneticisi, sayfa tabloları, kullanıcı alanı bellek haritaları.
-
-    pub fn init_manager() {
-        // Placeholder başlatma
-         println!("Karnal64: Bellek Yöneticisi Başlatıldı (Yer Tutucu)");
-    }
-    // TODO: allocate/free user memory, map/unmap shared memory implementasyonları
-}
-
-mod ksync {
-     use super::*;
-    // TODO: Dahili çekirdek kilitleri, semaforları, koşul değişkenleri gibi senkronizasyon primitiflerinin implementasyonu.
-
-    pub fn init_manager() {
-        // Placeholder başlatma
-         println!("Karnal64: Senkronizasyon Yöneticisi Başlatıldı (Yer Tutucu)");
-    }
-     // TODO: kilit create/acquire/release ve diğer primitifler implementasyonları
-}
+// Senkronizasyon primitifleri: Spinlock, WaitQueue (bkz. src/sync/srcsync.rs)
+#[path = "../src/sync/srcsync.rs"]
+pub mod ksync;
 
 mod kmessaging {
-    use super::*;
     // TODO: Görevler arası mesaj kuyrukları, mesaj kopyalama ve dağıtım mekanizmaları.
+    // Kanal ve kuyruk implementasyonu src/srcipc.rs'tedir: kanal handle'ı oluşturanın handle
+    // kotasına, kuyruğa konan mesaj baytları alıcı görevin IPC kotasına (ktask::QUOTA_IPC_BYTES)
+    // yazılır; mesaj alınınca veya kanal kapatılınca geri verilir, sınırı aşan gönderim
+    // KError::OutOfMemory ile reddedilir.
 
     pub fn init_manager() {
          // Placeholder başlatma
@@ -412,7 +414,6 @@ mod kmessaging {
 }
 
 mod kkernel {
-    use super::*;
     // TODO: Çekirdek durumu (versiyon, çalışma süresi, mimari vb.) bilgisini sağlayan yapı.
 
     pub fn init_manager() {
@@ -432,6 +433,50 @@ mod kkernel {
 // Kullanıcıdan gelen ham u64 argümanları alır, ilgili Karnal64 fonksiyonunu çağırır
 // ve KError sonuçlarını kullanıcı alanının beklediği negatif i64'e dönüştürür.
 
+// Sistem çağrısı numaraları (Sahne64 arch::SYSCALL_* ile eşleşmeli)
+pub const SYSCALL_MEMORY_ALLOC: u64 = 10;
+pub const SYSCALL_MEMORY_FREE: u64 = 11;
+pub const SYSCALL_MEMORY_PROTECT: u64 = 12;
+pub const SYSCALL_MEMORY_MAP: u64 = 13;
+pub const SYSCALL_MEMORY_UNMAP: u64 = 14;
+pub const SYSCALL_GET_TASK_ID: u64 = 60;
+pub const SYSCALL_TASK_SLEEP: u64 = 61;
+pub const SYSCALL_TASK_YIELD: u64 = 62;
+pub const SYSCALL_TASK_SET_PRIORITY: u64 = 63;
+pub const SYSCALL_TASK_WAIT: u64 = 64;
+pub const SYSCALL_THREAD_CREATE: u64 = 65;
+pub const SYSCALL_THREAD_EXIT: u64 = 66;
+pub const SYSCALL_EXCEPTION_PORT_SET: u64 = 67;
+pub const SYSCALL_EXCEPTION_RECEIVE: u64 = 68;
+pub const SYSCALL_EXCEPTION_REPLY: u64 = 69;
+pub const SYSCALL_LOCK_CREATE: u64 = 70;
+pub const SYSCALL_LOCK_ACQUIRE: u64 = 71;
+pub const SYSCALL_LOCK_RELEASE: u64 = 72;
+pub const SYSCALL_FUTEX_WAIT: u64 = 73;
+pub const SYSCALL_FUTEX_WAKE: u64 = 74;
+pub const SYSCALL_SEMAPHORE_CREATE: u64 = 75;
+pub const SYSCALL_SEMAPHORE_POST: u64 = 76;
+pub const SYSCALL_CONDVAR_CREATE: u64 = 77;
+pub const SYSCALL_CONDVAR_NOTIFY: u64 = 78;
+pub const SYSCALL_RWLOCK_CREATE: u64 = 79;
+pub const SYSCALL_RWLOCK_ACQUIRE_READ: u64 = 80;
+pub const SYSCALL_RWLOCK_RELEASE: u64 = 81;
+pub const SYSCALL_EVENT_CREATE: u64 = 82;
+pub const SYSCALL_EVENT_SET: u64 = 83;
+pub const SYSCALL_EVENT_RESET: u64 = 84;
+pub const SYSCALL_WAIT_MANY: u64 = 85;
+pub const SYSCALL_SYNC_DESTROY: u64 = 86;
+pub const SYSCALL_RWLOCK_ACQUIRE_WRITE: u64 = 87;
+pub const SYSCALL_THREAD_SET_AFFINITY: u64 = 88;
+pub const SYSCALL_THREAD_GET_AFFINITY: u64 = 89;
+pub const SYSCALL_CPU_TOPOLOGY: u64 = 90;
+pub const SYSCALL_THREAD_STATS: u64 = 91;
+pub const SYSCALL_TASK_STATS: u64 = 92;
+pub const SYSCALL_THREAD_SET_DEADLINE: u64 = 93;
+pub const SYSCALL_TASK_SET_LIMIT: u64 = 94;
+pub const SYSCALL_TASK_QUOTA: u64 = 95;
+pub const SYSCALL_RESOURCE_CONTROL: u64 = 96;
+
 #[no_mangle] // Düşük seviyeli işleyici tarafından çağrılabilmesi için isim düzenlemesi yapılmaz
 pub extern "C" fn handle_syscall(
     number: u64, // Sistem çağrısı numarası (Sahne64 SYSCALL_* ile aynı)
@@ -446,6 +491,9 @@ pub extern "C" fn handle_syscall(
     // kullanıcının bellek haritasına göre GEÇERLİ ve ERIŞILEBILIR (okunabilir/yazılabilir)
     // olduklarını doğrulamalıdır. Bu doğrulama, Karnal64 fonksiyonlarına geçirmeden önce yapılmalıdır.
 
+    // Buradan dönüşe kadar geçen süre çağıran iş parçacığının çekirdek süresine yazılır.
+    ktask::account_kernel_entry();
+
     let result: Result<u64, KError> = match number {
         // Sistem Çağrısı Numaraları (Sahne64 arch::SYSCALL_* ile eşleşmeli)
         1 => { // SYSCALL_MEMORY_ALLOCATE
@@ -461,12 +509,9 @@ pub extern "C" fn handle_syscall(
              kmemory::free_user_memory(ptr, size).map(|_| 0) // Başarı genellikle 0 döndürür
         }
         3 => { // SYSCALL_TASK_SPAWN
-             let code_handle_value = arg1; // Çalıştırılacak kod kaynağının handle'ı
-             let args_ptr = arg2 as *const u8; // Argüman verisi pointer'ı
-             let args_len = arg3 as usize; // Argüman verisi uzunluğu
-             // TODO: args_ptr/len'in geçerli kullanıcı alanı pointer'ları olduğunu doğrula
-             // TODO: Görev yöneticisinden yeni görev başlatma isteği gönder
-             ktask::task_spawn(code_handle_value, args_ptr, args_len).map(|tid| tid.0)
+             let request = arg1 as *const ktask::SpawnRequest; // Kullanıcı alanındaki başlatma isteği
+             let request_len = arg2 as usize; // İstek yapısının boyutu (sürüm denetimi)
+             ktask::task_spawn(request, request_len).map(|tid| tid.0)
         }
         4 => { // SYSCALL_TASK_EXIT
              let code = arg1 as i32; // Çıkış kodu
@@ -479,7 +524,7 @@ pub extern "C" fn handle_syscall(
              let mode = arg3 as u32; // Erişim modları
              // TODO: id_ptr/len'in geçerli kullanıcı alanı pointer'ları olduğunu doğrula
              // TODO: Kaynak yöneticisinden edinme isteği gönder
-             kresource::resource_acquire(id_ptr, id_len, mode).map(|handle| handle.0)
+             resource_acquire(id_ptr, id_len, mode).map(|handle| handle.0)
         }
         6 => { // SYSCALL_RESOURCE_READ
              let handle_value = arg1; // Kullanıcı handle değeri
@@ -487,7 +532,7 @@ pub extern "C" fn handle_syscall(
              let user_buffer_len = arg3 as usize; // Kullanıcı tamponu uzunluğu
              // TODO: user_buffer_ptr/len'in geçerli kullanıcı alanı pointer'ları ve YAZILABİLİR olduğunu doğrula
              // TODO: Kaynak yöneticisinden okuma isteği gönder
-             kresource::resource_read(handle_value, user_buffer_ptr, user_buffer_len).map(|bytes_read| bytes_read as u64)
+             resource_read(handle_value, user_buffer_ptr, user_buffer_len).map(|bytes_read| bytes_read as u64)
         }
         7 => { // SYSCALL_RESOURCE_WRITE
              let handle_value = arg1; // Kullanıcı handle değeri
@@ -495,23 +540,104 @@ pub extern "C" fn handle_syscall(
              let user_buffer_len = arg3 as usize; // Kullanıcı tamponu uzunluğu
               // TODO: user_buffer_ptr/len'in geçerli kullanıcı alanı pointer'ları ve OKUNABİLİR olduğunu doğrula
               // TODO: Kaynak yöneticisinden yazma isteği gönder
-             kresource::resource_write(handle_value, user_buffer_ptr, user_buffer_len).map(|bytes_written| bytes_written as u64)
+             resource_write(handle_value, user_buffer_ptr, user_buffer_len).map(|bytes_written| bytes_written as u64)
         }
         8 => { // SYSCALL_RESOURCE_RELEASE
              let handle_value = arg1; // Kullanıcı handle değeri
              // TODO: Kaynak yöneticisinden serbest bırakma isteği gönder
-             kresource::resource_release(handle_value).map(|_| 0) // Başarı genellikle 0 döndürür
+             resource_release(handle_value).map(|_| 0) // Başarı genellikle 0 döndürür
+        }
+        SYSCALL_RESOURCE_CONTROL => { // arg1: handle değeri, arg2: komut kodu, arg3: komut argümanı
+             resource_control(arg1, arg2, arg3)
+        }
+        SYSCALL_MEMORY_ALLOC => { // arg1: boyut, arg2: kmemory::PROT_* (dönüş: adres)
+             kmemory::memory_alloc(arg1 as usize, arg2 as u32).map(|addr| addr as u64)
+        }
+        SYSCALL_MEMORY_FREE => kmemory::memory_unmap(arg1 as usize, arg2 as usize).map(|_| 0), // arg1: adres, arg2: boyut
+        SYSCALL_MEMORY_PROTECT => { // arg1: adres, arg2: boyut, arg3: kmemory::PROT_*
+             kmemory::memory_protect(arg1 as usize, arg2 as usize, arg3 as u32).map(|_| 0)
+        }
+        SYSCALL_MEMORY_MAP => { // arg1: adres (ipucu veya MAP_FIXED), arg2: boyut, arg3: PROT_* | MAP_* << 32, arg4: handle (0: anonim), arg5: ofset
+             memory_map(arg1, arg2, arg3, arg4, arg5) // Eşlemenin adresini döner
         }
+        SYSCALL_MEMORY_UNMAP => kmemory::memory_unmap(arg1 as usize, arg2 as usize).map(|_| 0), // arg1: adres, arg2: boyut
         // TODO: Diğer tüm SYSCALL_ numaraları için eşleşmeleri ekle...
-         SYSCALL_GET_TASK_ID => ktask::get_current_task_id().map(|tid| tid.0)
-         SYSCALL_TASK_SLEEP => ktask::task_sleep(arg1).map(|_| 0)
-         SYSCALL_LOCK_CREATE => ksync::lock_create().map(|h| h.0)
-         SYSCALL_LOCK_ACQUIRE => ksync::lock_acquire(arg1).map(|_| 0)
-         SYSCALL_LOCK_RELEASE => ksync::lock_release(arg1).map(|_| 0)
-         SYSCALL_MESSAGE_SEND => kmessaging::send(arg1, arg2 as *const u8, arg3 as usize).map(|_| 0) // Pointer doğrulama gerekli!
-         SYSCALL_MESSAGE_RECEIVE => kmessaging::receive(arg1 as *mut u8, arg2 as usize).map(|n| n as u64) // Pointer doğrulama gerekli!
-         SYSCALL_GET_KERNEL_INFO => kkernel::get_info(arg1 as u32).map(|v| v as u64)
-         SYSCALL_TASK_YIELD => ktask::yield_now().map(|_| 0)
+        SYSCALL_GET_TASK_ID => ktask::get_current_task_id().map(|tid| tid.0),
+        SYSCALL_TASK_SLEEP => ktask::task_sleep(arg1).map(|_| 0),
+        SYSCALL_TASK_YIELD => ktask::yield_now().map(|_| 0),
+        SYSCALL_TASK_SET_PRIORITY => { // arg1: görev ID'si (0: çağıran görev), arg2: öncelik (1..=PRIORITY_USER_MAX)
+             match u8::try_from(arg2) {
+                 Ok(priority) => ktask::set_task_priority(KTaskId(arg1), priority).map(|_| 0),
+                 Err(_) => Err(KError::InvalidArgument),
+             }
+        }
+        SYSCALL_TASK_WAIT => { // arg1: çocuk görev ID'si (0: herhangi bir çocuk), arg2: ktask::TASK_WAIT_* bayrakları
+             // Dönüş: ktask::ExitStatus::encode (çıkış kodu veya hata nedeni, hata bayrağı, görev ID'si)
+             ktask::task_wait(KTaskId(arg1), arg2).map(|status| status.encode())
+        }
+        SYSCALL_THREAD_CREATE => { // arg1: giriş, arg2: yığın boyutu (0: varsayılan), arg3: argüman, arg4: TLS işaretçisi, arg5: yığın tabanı (0: çekirdek ayırır)
+             ktask::thread_create(arg1, arg2 as usize, arg3, arg4, arg5).map(|tid| tid.0)
+        }
+        SYSCALL_THREAD_EXIT => {
+             let code = arg1 as i32;
+             ktask::thread_exit(code); // Geri dönmez; görevin son iş parçacığıysa görev de sonlanır
+        }
+        SYSCALL_EXCEPTION_PORT_SET => { // arg1: görev ID'si (0: çağıran görev), arg2: işleyici görev ID'si (0: portu kaldır)
+             ktask::exception_port_set(KTaskId(arg1), KTaskId(arg2)).map(|_| 0)
+        }
+        SYSCALL_EXCEPTION_RECEIVE => { // arg1: ktask::FaultMessage tamponu, arg2: ktask::EXCEPTION_RECEIVE_* bayrakları
+             ktask::exception_receive(arg1 as *mut ktask::FaultMessage, arg2).map(|tid| tid.0) // Hatalı iş parçacığının ID'sini döner
+        }
+        SYSCALL_EXCEPTION_REPLY => { // arg1: iş parçacığı ID'si, arg2: ktask::EXCEPTION_RESUME/KILL, arg3: yeni yazmaçlar (0: değişmez)
+             ktask::exception_reply(KThreadId(arg1), arg2, arg3 as *const ktask::FaultRegisters).map(|_| 0)
+        }
+        SYSCALL_THREAD_SET_AFFINITY => { // arg1: iş parçacığı ID'si (0: çağıran iş parçacığı), arg2: CPU maskesi
+             ktask::set_thread_affinity(KThreadId(arg1), arg2).map(|_| 0)
+        }
+        SYSCALL_THREAD_GET_AFFINITY => ktask::thread_affinity(KThreadId(arg1)), // arg1: iş parçacığı ID'si (0: çağıran iş parçacığı)
+        SYSCALL_CPU_TOPOLOGY => { // arg1: mantıksal CPU indeksi, arg2: ktask::CpuTopology tamponu
+             ktask::cpu_topology_to_user(arg1 as usize, arg2 as *mut ktask::CpuTopology).map(|n| n as u64) // Kayıtlı CPU sayısını döner
+        }
+        SYSCALL_THREAD_STATS => { // arg1: iş parçacığı ID'si (0: çağıran iş parçacığı), arg2: ktask::ThreadStats tamponu
+             ktask::thread_stats_to_user(KThreadId(arg1), arg2 as *mut ktask::ThreadStats).map(|_| 0)
+        }
+        SYSCALL_TASK_STATS => { // arg1: görev ID'si (0: çağıran görev), arg2: ktask::ThreadStats tamponu
+             ktask::task_stats_to_user(KTaskId(arg1), arg2 as *mut ktask::ThreadStats).map(|_| 0)
+        }
+        SYSCALL_THREAD_SET_DEADLINE => { // arg1: iş parçacığı ID'si (0: çağıran iş parçacığı), arg2: çalışma süresi, arg3: son tarih, arg4: periyot (ns)
+             ktask::set_thread_deadline(KThreadId(arg1), arg2, arg3, arg4).map(|_| 0)
+        }
+        SYSCALL_TASK_SET_LIMIT => { // arg1: görev ID'si (0: çağıran görev), arg2: ktask::QUOTA_* kaynağı, arg3: sınır
+             ktask::set_task_limit(KTaskId(arg1), arg2 as usize, arg3).map(|_| 0)
+        }
+        SYSCALL_TASK_QUOTA => { // arg1: görev ID'si (0: çağıran görev), arg2: ktask::TaskQuota tamponu
+             ktask::task_quota_to_user(KTaskId(arg1), arg2 as *mut ktask::TaskQuota).map(|_| 0)
+        }
+        SYSCALL_LOCK_CREATE => ksync::lock_create().map(|h| h.0),
+        SYSCALL_LOCK_ACQUIRE => ksync::lock_acquire(arg1).map(|_| 0), // Çekişme varsa çağıranı bloklar
+        SYSCALL_LOCK_RELEASE => ksync::lock_release(arg1).map(|_| 0),
+        SYSCALL_FUTEX_WAIT => { // arg1: kullanıcı adresi, arg2: beklenen değer, arg3: zaman aşımı (ns)
+             ksync::futex_wait(arg1, arg2 as u32, arg3).map(|_| 0)
+        }
+        SYSCALL_FUTEX_WAKE => ksync::futex_wake(arg1, arg2), // Uyandırılan iş parçacığı sayısını döner
+        SYSCALL_SEMAPHORE_CREATE => ksync::semaphore_create(arg1 as u32, arg2 as u32).map(|h| h.0), // arg1: başlangıç, arg2: en fazla
+        SYSCALL_SEMAPHORE_POST => ksync::semaphore_post(arg1, arg2 as u32).map(|_| 0),
+        SYSCALL_CONDVAR_CREATE => ksync::condvar_create(arg1).map(|h| h.0), // arg1: eşli kilit handle'ı
+        SYSCALL_CONDVAR_NOTIFY => ksync::condvar_notify(arg1, arg2 != 0).map(|_| 0), // arg2 != 0: hepsini uyandır
+        SYSCALL_RWLOCK_CREATE => ksync::rwlock_create().map(|h| h.0),
+        SYSCALL_RWLOCK_ACQUIRE_READ => ksync::rwlock_acquire_read(arg1).map(|_| 0),
+        SYSCALL_RWLOCK_ACQUIRE_WRITE => ksync::rwlock_acquire_write(arg1, arg2).map(|_| 0), // arg2: zaman aşımı (ns)
+        SYSCALL_RWLOCK_RELEASE => ksync::rwlock_release(arg1).map(|_| 0),
+        SYSCALL_EVENT_CREATE => ksync::event_create(arg1 != 0, arg2 != 0).map(|h| h.0), // arg1: manuel sıfırlama, arg2: başlangıçta sinyalli
+        SYSCALL_EVENT_SET => ksync::event_set(arg1).map(|_| 0),
+        SYSCALL_EVENT_RESET => ksync::event_reset(arg1).map(|_| 0),
+        SYSCALL_WAIT_MANY => { // arg1: handle dizisi pointer'ı, arg2: handle sayısı, arg3: zaman aşımı (ns)
+             ksync::wait_many(arg1 as *const u64, arg2 as usize, arg3) // Sinyallenen handle'ın indeksini döner
+        }
+        SYSCALL_SYNC_DESTROY => ksync::sync_object_destroy(arg1).map(|_| 0),
+        // SYSCALL_MESSAGE_SEND => kmessaging::send(arg1, arg2 as *const u8, arg3 as usize).map(|_| 0) // Pointer doğrulama gerekli!
+        // SYSCALL_MESSAGE_RECEIVE => kmessaging::receive(arg1 as *mut u8, arg2 as usize).map(|n| n as u64) // Pointer doğrulama gerekli!
+        // SYSCALL_GET_KERNEL_INFO => kkernel::get_info(arg1 as u32).map(|v| v as u64)
 
 
         _ => {
@@ -524,6 +650,7 @@ pub extern "C" fn handle_syscall(
     // beklediği i64 formatına dönüştür.
     // Başarı -> pozitif veya sıfır sonuç değeri
     // Hata -> negatif hata kodu
+    ktask::account_user_return();
     match result {
         Ok(value) => value as i64, // Başarı değeri (u64 -> i64 dönüşümü dikkatli yapılmalı, taşma?)
         Err(err) => err as i64,    // Hata kodu (KError i64 olarak tanımlandı)
@@ -534,59 +661,3 @@ pub extern "C" fn handle_syscall(
 // Sahne64'teki `arch::SYSCALL_*` sabitleri ve `map_kernel_error` fonksiyonu tarafından
 // beklenen değerlerle KESİNLİKLE eşleşmelidir. Bu, kullanıcı alanı ile çekirdek
 // implementasyonu arasındaki ABI sözleşmesidir.
-*/
-
-
-// --- Yer Tutucu Dahili Çekirdek Yönetim Modülleri ---
-// Bu modüller, yukarıdaki Karnal64 API fonksiyonlarının çağıracağı asıl düşük seviye
-// çekirdek mantığını (bellek yönetimi, zamanlayıcı, sürücü arayüzleri, IPC implementasyonu vb.)
-// içerecektir. Karnal64 API'sı bu modüllere bağımlıdır.
-
-mod kresource {
-    use super::*; // karnal64.rs scope'undaki tipleri kullan
-
-    // TODO: Kaynak Yöneticisi veri yapıları ve fonksiyonları
-    pub fn init_manager() { /* Gerçek başlatma mantığı */ }
-    // TODO: ResourceProvider traitini implemente eden dummy/gerçek kaynaklar
-    // TODO: Kaynak kaydı, handle yönetimi, provider lookup fonksiyonları
-}
-
-mod ktask {
-    use super::*; // karnal64.rs scope'undaki tipleri kullan
-
-    // TODO: Görev ve İş Parçacığı Yönetimi, Zamanlayıcı implementasyonu
-    pub fn init_manager() { /* Gerçek başlatma mantığı */ }
-    // TODO: create, exit, schedule, sleep, yield, current_id implementasyonları
-}
-
-mod kmemory {
-     use super::*; // karnal64.rs scope'undaki tipleri kullan
-
-    // TODO: Bellek Yönetim Birimi (MMU) etkileşimi, fiziksel/sanal bellek ayırıcılar, sayfa tabloları.
-    pub fn init_manager() { /* Gerçek başlatma mantığı */ }
-     // TODO: allocate_user_memory, free_user_memory, map_shared, unmap_shared implementasyonları
-}
-
-mod ksync {
-     use super::*; // karnal64.rs scope'undaki tipleri kullan
-
-    // TODO: Çekirdek senkronizasyon primitifleri (mutex, semaphore, condition variable) implementasyonu.
-    pub fn init_manager() { /* Gerçek başlatma mantığı */ }
-     // TODO: lock_create, acquire, release ve diğer primitifler implementasyonları
-}
-
-mod kmessaging {
-    use super::*; // karnal64.rs scope'undaki tipleri kullan
-
-    // TODO: Görevler arası mesaj kuyrukları, mesaj kopyalama, gönderme/alma implementasyonu.
-    pub fn init_manager() { /* Gerçek başlatma mantığı */ }
-    // TODO: send, receive implementasyonları
-}
-
-mod kkernel {
-    use super::*; // karnal64.rs scope'undaki tipleri kullan
-
-    // TODO: Çekirdek durumu (versiyon, çalışma süresi, donanım bilgisi) yönetim yapısı.
-    pub fn init_manager() { /* Gerçek başlatma mantığı */ }
-     // TODO: get_info, get_time implementasyonları
-}
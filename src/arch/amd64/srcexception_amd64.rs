@@ -13,10 +13,12 @@
 // Örneğin, handle_syscall fonksiyonu ve KError gibi tipler.
 // Gerçek implementasyonda bu 'extern crate' veya 'use' ifadeleri çekirdek yapısına göre değişir.
  use crate::karnal64::{handle_syscall, KError, KTaskId};
- use crate::ktask; // Görev yönetimi için
- use crate::kmemory; // Bellek yönetimi için
+ use crate::karnal64::ktask; // Görev yönetimi ve istisna portları için
+ use crate::karnal64::ksync; // lockdep kesme bağlamı izleme (irq_enter/irq_exit)
+ use crate::karnal64::kmemory; // Bellek yönetimi ve talep üzerine sayfalama için
  use crate::kkernel; // Çekirdek bilgisi/durdurma için
  use crate::klog; // Geçici loglama/debug çıktısı için
+use core::arch::global_asm;
 
 // --- Sabitler ve Tanımlar ---
 
@@ -46,13 +48,18 @@ const CONTROL_PROTECTION_VECTOR: u8 = 0x15;
 // Genellikle PIC (Programmable Interrupt Controller) veya APIC (Advanced PIC) tarafından yönetilir.
 // Yaygın olarak IRQ'lar 0x20'den başlayarak vektörlere haritalanır (örn. PIC offset 0x20).
 const PIC_1_OFFSET: u8 = 0x20;
-const TIMER_INTERRUPT_VECTOR: u8 = PIC_1_OFFSET + 0; // IRQ0
+const TIMER_INTERRUPT_VECTOR: u8 = PIC_1_OFFSET + 0; // Yerel APIC zamanlayıcısı (IRQ0'ın vektörü, PIT maskeli)
 const KEYBOARD_INTERRUPT_VECTOR: u8 = PIC_1_OFFSET + 1; // IRQ1
 // ... diğer IRQ'lar ...
 
 // Sistem Çağrısı (Syscall) Vektör Numarası (yaygın olarak kullanılır)
 const SYSCALL_VECTOR: u8 = 0x80;
 
+/// RFLAGS'ta kullanıcının değiştirebileceği bitler (CF, PF, AF, ZF, SF, TF, DF, OF).
+const USER_RFLAGS_MASK: u64 = 0xDD5;
+/// RFLAGS.IF: kesmeler açık.
+const RFLAGS_IF: u64 = 1 << 9;
+
 // --- Çekirdek Bellek Düzeni ve Stack Yapısı (Kavramsal) ---
 // İstisna/Kesme meydana geldiğinde, CPU mevcut görev/iş parçacığının stack'ine
 // belirli bilgileri (RIP, CS, RFLAGS, RSP, SS ve bazı istisnalar için hata kodu) push eder.
@@ -90,6 +97,81 @@ pub struct InterruptStackFrame {
     // RegisterState gibi bir struct burada veya ayrı bir argüman olarak olmalıdır.
 }
 
+/// Page Fault ve GP Fault giriş stub'larının yığında oluşturduğu çerçeve: stub'ın kaydettiği genel
+/// amaçlı yazmaçlar, ardından CPU'nun push ettiği hata kodu ve iretq çerçevesi.
+/// `gpr` sıralaması ktask::FaultRegisters ile aynıdır: rax, rbx, rcx, rdx, rsi, rdi, rbp, r8-r15.
+#[repr(C)]
+pub struct FaultTrapFrame {
+    pub gpr: [u64; FAULT_TRAP_GPR_COUNT],
+    pub error_code: u64,
+    pub instruction_pointer: u64,
+    pub code_segment: u64,
+    pub cpu_flags: u64,
+    pub stack_pointer: u64,
+    pub stack_segment: u64,
+}
+
+const FAULT_TRAP_GPR_COUNT: usize = 15;
+
+// Hata kodu push eden #PF/#GP için giriş stub'ları. Tüm genel amaçlı yazmaçlar kaydedilir ve
+// Rust işleyicisine çerçevenin adresi verilir; işleyicinin çerçevede yaptığı değişiklikler
+// (istisna portunun yanıtı) iretq'dan önce yazmaçlara geri yüklenir. CPU 64-bit kipte yığını
+// 16 bayta hizalayıp 6 kelime push ettiğinden 15 push sonrası çağrı için 8 bayt eklenir.
+global_asm!(
+    r#"
+    .section .text
+    .macro FAULT_ENTRY name, handler
+    .global \name
+\name:
+    push r15
+    push r14
+    push r13
+    push r12
+    push r11
+    push r10
+    push r9
+    push r8
+    push rbp
+    push rdi
+    push rsi
+    push rdx
+    push rcx
+    push rbx
+    push rax
+    mov rdi, rsp
+    sub rsp, 8
+    call \handler
+    add rsp, 8
+    pop rax
+    pop rbx
+    pop rcx
+    pop rdx
+    pop rsi
+    pop rdi
+    pop rbp
+    pop r8
+    pop r9
+    pop r10
+    pop r11
+    pop r12
+    pop r13
+    pop r14
+    pop r15
+    // Hata kodunu atla.
+    add rsp, 8
+    iretq
+    .endm
+
+    FAULT_ENTRY x86_64_page_fault_entry, x86_64_page_fault
+    FAULT_ENTRY x86_64_gp_fault_entry, x86_64_gp_fault
+"#
+);
+
+extern "C" {
+    fn x86_64_page_fault_entry();
+    fn x86_64_gp_fault_entry();
+}
+
 // --- İstisna/Kesme İşleyicileri (Handlers) ---
 
 // x86-interrupt çağırma kuralı, bu fonksiyonların CPU tarafından doğru şekilde
@@ -220,15 +302,15 @@ pub extern "C" fn common_interrupt_handler(stack_frame: InterruptStackFrame, vec
         }
         TIMER_INTERRUPT_VECTOR => {
              klog::trace!("TIMER INTERRUPT");
-             // Zamanlayıcı kesmesi, zamanlama ve görev değişimi için kullanılır.
-             // Zamanlayıcı sayacını güncelle.
-              ktimer::tick(); // Kavramsal
-             // Zamanlayıcıyı sıfırla (eğer periyodik ise).
-              ktimer::reset_timer(); // Kavramsal
-             // Görev zamanlayıcıyı çalıştır (gerekiyorsa görev değişimi yapar).
-              ktask::schedule(); // Kavramsal
-             // Donanım kesmesini onayla (PIC/APIC'e EOI gönder).
-              src_interrupts::acknowledge_irq(0); // Kavramsal (başka bir modülde)
+             ksync::lockdep::irq_enter();
+             // Süresi dolan bekleyenleri uyandır, zaman dilimini hesapla; tek seferlik zamanlayıcıyı
+             // timer_tick bir sonraki olaya yeniden kurar (srctask_amd64.rs::program_timer).
+             ktask::timer_tick(ktask::now_ns());
+             // Yerel APIC'e EOI gönder.
+             crate::smp::lapic_eoi();
+             ksync::lockdep::irq_exit();
+             // Gerekirse görev değişimi kesme bağlamından çıkarken yapılır.
+             ktask::preempt_on_interrupt_exit();
         }
         BREAKPOINT_VECTOR => {
             klog::debug!("BREAKPOINT at RIP: {:#x}", stack_frame.instruction_pointer);
@@ -243,13 +325,16 @@ pub extern "C" fn common_interrupt_handler(stack_frame: InterruptStackFrame, vec
             kkernel::panic("DOUBLE FAULT"); // Yer Tutucu Panik
         }
         // Diğer istisnalar ve IRQ'lar için işleyiciler eklenecek...
-        // Örneğin klavye kesmesi:
          KEYBOARD_INTERRUPT_VECTOR => {
-             klog::debug!("KEYBOARD INTERRUPT");
-        //     // Klavye sürücüsüne danış.
-              src_drivers::keyboard::handle_interrupt(); // Kavramsal
-        //     // Donanım kesmesini onayla.
+             ksync::lockdep::irq_enter();
+             // Üst yarı: yalnızca tarama kodunu oku (okunmazsa denetleyici yeni tuş bildirmez)
+             // ve kesmeyi onayla. Çözümleme ve sürücüye iletme, ktask iş kuyruğunda yapılır.
+             let scancode = unsafe { read_keyboard_data() };
+             if ktask::queue_work(keyboard_bottom_half, scancode as usize).is_err() {
+                 klog::warn!("KEYBOARD: iş kuyruğu dolu, tarama kodu {:#x} düşürüldü", scancode);
+             }
               src_interrupts::acknowledge_irq(1); // Kavramsal
+             ksync::lockdep::irq_exit();
          }
 
         _ => {
@@ -268,30 +353,95 @@ pub extern "C" fn common_interrupt_handler(stack_frame: InterruptStackFrame, vec
      ktask::restore_task_state(&mut stack_frame, &mut saved_registers); // Kavramsal
 }
 
-/// Page Fault işleyicisinin yüksek seviye mantığı.
-#[allow(unused_variables)]
-fn handle_page_fault(stack_frame: &InterruptStackFrame, error_code: u64, faulting_address: u64) {
-    klog::error!("PAGE FAULT [{:#x}] at {:#x} during access from {:#x}",
-                error_code, faulting_address, stack_frame.instruction_pointer);
+/// PS/2 denetleyicisinin veri portu; klavye kesmesinde tarama kodu buradan okunur.
+const KEYBOARD_DATA_PORT: u16 = 0x60;
+
+unsafe fn read_keyboard_data() -> u8 {
+    let value: u8;
+    core::arch::asm!("in al, dx", in("dx") KEYBOARD_DATA_PORT, out("al") value, options(nomem, nostack));
+    value
+}
+
+/// Alt yarının biriktirdiği, henüz okunmamış tarama kodlarının en fazla sayısı.
+const SCANCODE_BUFFER_SIZE: usize = 64;
+
+/// Tarama kodu halka tamponu: klavye alt yarısı yazar, klavye sürücüsü `read_scancodes` ile okur.
+struct ScancodeBuffer {
+    codes: [u8; SCANCODE_BUFFER_SIZE],
+    head: usize,
+    len: usize,
+}
 
-    use PageFaultErrorCode; // Tanımlanacak bir enum varsayımı
+static SCANCODES: ksync::Spinlock<ScancodeBuffer> = ksync::Spinlock::named(
+    ScancodeBuffer { codes: [0; SCANCODE_BUFFER_SIZE], head: 0, len: 0 },
+    "amd64::SCANCODES",
+);
+
+/// Klavye kesmesinin alt yarısı: CPU'nun iş kuyruğu çalışanında, kesmeler açıkken çalışır.
+/// Tarama kodunu sürücünün okuyacağı tampona ekler; tampon doluysa kod düşürülür.
+fn keyboard_bottom_half(scancode: usize) {
+    let mut buffer = SCANCODES.lock();
+    if buffer.len == SCANCODE_BUFFER_SIZE {
+        drop(buffer);
+        klog::warn!("KEYBOARD: tarama kodu tamponu dolu, {:#x} düşürüldü", scancode);
+        return;
+    }
+    let tail = (buffer.head + buffer.len) % SCANCODE_BUFFER_SIZE;
+    buffer.codes[tail] = scancode as u8;
+    buffer.len += 1;
+}
 
+/// Biriken tarama kodlarını geliş sırasıyla `out`'a taşır ve taşınan sayıyı döner (klavye sürücüsü için).
+pub fn read_scancodes(out: &mut [u8]) -> usize {
+    let mut buffer = SCANCODES.lock();
+    let count = out.len().min(buffer.len);
+    for byte in out.iter_mut().take(count) {
+        *byte = buffer.codes[buffer.head];
+        buffer.head = (buffer.head + 1) % SCANCODE_BUFFER_SIZE;
+        buffer.len -= 1;
+    }
+    count
+}
+
+/// Page Fault işleyicisinin yüksek seviye mantığı.
+/// Hata önce görevin VMA'larına göre çözülmeye çalışılır (kmemory::handle_page_fault): ilk
+/// dokunulan anonim sayfa sıfırlanıp, kaynak sayfası sağlayıcıdan okunup eşlenir ve komut yeniden
+/// çalışır. Çözülemeyen kullanıcı modu hataları görevin istisna portuna iletilir.
+fn handle_page_fault(frame: &mut FaultTrapFrame, faulting_address: u64) {
+    let error_code = frame.error_code;
     let pf_error = PageFaultErrorCode(error_code);
 
+    // Ayrılmış bit hatası bozuk bir sayfa tablosu girdisidir; VMA'lardan çözülmez. Kesmeler
+    // kapalıyken (spinlock altında) oluşan çekirdek hatası da uyumadan çözülemez.
+    let result = if pf_error.reserved() || frame.cpu_flags & RFLAGS_IF == 0 {
+        Err(KError::BadAddress)
+    } else {
+        let access = if pf_error.instruction_fetch() {
+            kmemory::PROT_EXEC
+        } else if pf_error.write() {
+            kmemory::PROT_WRITE
+        } else {
+            kmemory::PROT_READ
+        };
+        // VMA ağacı uyuyan bir kilitle korunur ve sağlayıcıdan okuma bloklayabilir.
+        unsafe { core::arch::asm!("sti", options(nomem, nostack)) };
+        kmemory::handle_page_fault(faulting_address as usize, access)
+    };
+    let reason = match result {
+        Ok(()) => return,
+        Err(err) => err,
+    };
+
+    klog::error!("PAGE FAULT [{:#x}] at {:#x} during access from {:#x}: {:?}",
+                error_code, faulting_address, frame.instruction_pointer, reason);
     klog::error!("Error Code Flags: Present={}, Write={}, User={}, Reserved={}",
                 pf_error.present(), pf_error.write(), pf_error.user(), pf_error.reserved());
-    // Daha fazla bayrak olabilir (Instruction Fetch, Protection Key, SGX)
-
-    // Örnek Mantık:
-    // 1. Hatanın türünü belirle (sayfa yok, izin hatası vb.)
-    // 2. Faulting Address (CR2) hangi bellek bölgesinde? (Kernel, User, Heap, Stack, Mapped File vb.)
-    // 3. Eğer sayfa yoksa ve bu adres geçerli bir sanal bellek bölgesindeyse:
-    //    - kmemory::handle_page_fault(faulting_address, error_code) çağrısı yap.
-    //    - Bellek yöneticisi, fiziksel bir sayfa bulup eşlemeyi deneyebilir.
-    //    - Başarılı olursa fonksiyondan normal dönülür, CPU eşlenen sayfaya erişmeyi tekrar dener.
-    // 4. Eğer sayfa yoksa ve adres geçersizse veya izin hatasıysa:
-    //    - Bu genellikle ölümcül bir hata. İlgili görevi sonlandır.
-        ktask::terminate_current_task(KError::BadAddress); // Kavramsal
+
+    // Kullanıcı modundaysa görevin istisna portuna ilet; port yoksa görev sonlandırılır.
+    if pf_error.user() {
+        deliver_user_fault(reason, PAGE_FAULT_VECTOR, faulting_address, frame);
+        return;
+    }
 
     // Yer Tutucu: Şimdilik sadece panik yapıyoruz.
     kkernel::panic("UNHANDLED PAGE FAULT");
@@ -299,18 +449,39 @@ fn handle_page_fault(stack_frame: &InterruptStackFrame, error_code: u64, faultin
 
 /// General Protection Fault işleyicisinin yüksek seviye mantığı.
 #[allow(unused_variables)]
-fn handle_gp_fault(stack_frame: &InterruptStackFrame, error_code: u64) {
+fn handle_gp_fault(frame: &mut FaultTrapFrame) {
     klog::error!("GENERAL PROTECTION FAULT [{:#x}] at {:#x}",
-                error_code, stack_frame.instruction_pointer);
+                frame.error_code, frame.instruction_pointer);
 
     // Bu genellikle kurtarılamaz bir hatadır (segmentasyon hatası, izin ihlali vb.).
-    // İlgili görevi sonlandır.
-     ktask::terminate_current_task(KError::PermissionDenied); // Kavramsal
+    // Kullanıcı modundaysa görevin istisna portuna ilet; port yoksa görev sonlandırılır.
+    if frame.code_segment & 3 == 3 {
+        deliver_user_fault(KError::PermissionDenied, GENERAL_PROTECTION_FAULT_VECTOR, 0, frame);
+        return;
+    }
 
     // Yer Tutucu: Şimdilik sadece panik yapıyoruz.
     kkernel::panic("UNHANDLED GENERAL PROTECTION FAULT");
 }
 
+/// Çözülemeyen bir kullanıcı modu istisnasını ktask istisna portuna iletir. İşleyici iş parçacığını
+/// devam ettirirse (değiştirmiş olabileceği) genel amaçlı yazmaçlar, RIP, RSP ve RFLAGS'ın kullanıcı
+/// bitleri çerçeveye geri yazılır; giriş stub'ı bunları iretq'dan önce geri yükler.
+fn deliver_user_fault(reason: KError, vector: u8, address: u64, frame: &mut FaultTrapFrame) {
+    let mut regs = ktask::FaultRegisters {
+        pc: frame.instruction_pointer,
+        sp: frame.stack_pointer,
+        status: frame.cpu_flags,
+        ..Default::default()
+    };
+    regs.gpr[..FAULT_TRAP_GPR_COUNT].copy_from_slice(&frame.gpr);
+    ktask::deliver_fault(reason, vector as u64, address, &mut regs);
+    frame.gpr.copy_from_slice(&regs.gpr[..FAULT_TRAP_GPR_COUNT]);
+    frame.instruction_pointer = regs.pc;
+    frame.stack_pointer = regs.sp;
+    frame.cpu_flags = (frame.cpu_flags & !USER_RFLAGS_MASK) | (regs.status & USER_RFLAGS_MASK);
+}
+
 /// Sistem Çağrısı işleyicisinin yüksek seviye mantığı.
 /// Syscall Convention: Yaygın olarak syscall numarası RAX'ta, argümanlar RDI, RSI, RDX, RCX, R8, R9'dadır.
 /// Sonuç RAX'a konur.
@@ -367,24 +538,19 @@ pub extern "x86-interrupt" fn syscall_entry(stack_frame: InterruptStackFrame) {
 #[no_mangle] // Eğer assembly stub tarafından doğrudan çağrılıyorsa
 pub extern "x86-interrupt" fn timer_interrupt_handler(_stack_frame: InterruptStackFrame) {
     klog::trace!("Timer interrupt!");
+    ksync::lockdep::irq_enter();
 
-    // Zamanlayıcı sayacını artır.
-     kkernel::increment_timer(); // Kavramsal
-
-    // Görev zamanlayıcıyı çalıştır, görev değişimi gerekebilir.
-     ktask::schedule(); // Kavramsal
+    // Süresi dolan bekleyenleri uyandır, zaman dilimini hesapla. Zamanlayıcı tek seferliktir;
+    // timer_tick onu bir sonraki olaya yeniden kurar (srctask_amd64.rs::program_timer).
+    ktask::timer_tick(ktask::now_ns());
 
-    // PIC (Programmable Interrupt Controller) veya APIC'e End Of Interrupt (EOI) sinyali gönder.
-    // Bu, aynı kesmenin tekrar gelmesini sağlar. Eğer bunu yapmazsanız, timer kesmesi durur!
-    // Eğer 8259 PIC kullanılıyorsa:
-     const PIC_1_COMMAND: u16 = 0x20;
-     const PIC_2_COMMAND: u16 = 0xA0;
-     unsafe { x86_64::instructions::port::Port::new(PIC_1_COMMAND).write(0x20u8); } // EOI komutu
-    // Eğer APIC kullanılıyorsa, APIC'e EOI yazılır.
-     src_interrupts::send_eoi(TIMER_INTERRUPT_VECTOR - PIC_1_OFFSET); // Kavramsal helper
+    // Yerel APIC'e End Of Interrupt (EOI) gönder; göndermezsek bu ve daha düşük öncelikli
+    // kesmeler bir daha gelmez. PIT (8259 IRQ0) maskelidir, PIC'e EOI gerekmez.
+    crate::smp::lapic_eoi();
+    ksync::lockdep::irq_exit();
 
-    // Yer Tutucu EOI simülasyonu:
-      src_interrupts::send_eoi(0); // IRQ0 için
+    // Görev değişimi gerekiyorsa kesme bağlamından çıkarken yapılır.
+    ktask::preempt_on_interrupt_exit();
 }
 
 /// Bilinmeyen veya henüz özel işleyicisi olmayan kesme/istisna.
@@ -501,8 +667,8 @@ pub fn init() {
         IDT[DIVIDE_ERROR_VECTOR as usize].set_handler_fn(divide_error_handler as u64, KERNEL_CODE_SELECTOR, None);
         IDT[BREAKPOINT_VECTOR as usize].set_handler_fn(breakpoint_handler as u64, KERNEL_CODE_SELECTOR, None);
         IDT[INVALID_OPCODE_VECTOR as usize].set_handler_fn(invalid_opcode_handler as u64, KERNEL_CODE_SELECTOR, None);
-        IDT[GENERAL_PROTECTION_FAULT_VECTOR as usize].set_handler_fn(gp_fault_handler as u64, KERNEL_CODE_SELECTOR, None);
-        IDT[PAGE_FAULT_VECTOR as usize].set_handler_fn(page_fault_handler as u64, KERNEL_CODE_SELECTOR, None);
+        IDT[GENERAL_PROTECTION_FAULT_VECTOR as usize].set_handler_fn(x86_64_gp_fault_entry as u64, KERNEL_CODE_SELECTOR, None);
+        IDT[PAGE_FAULT_VECTOR as usize].set_handler_fn(x86_64_page_fault_entry as u64, KERNEL_CODE_SELECTOR, None);
 
         // Double Fault için özel IST (Interrupt Stack Table) kullanılır.
         // IST indeksi (örneğin 1) IDT girişinde belirtilir.
@@ -564,14 +730,17 @@ extern "x86-interrupt" fn invalid_opcode_handler(stack_frame: InterruptStackFram
      kkernel::panic("Invalid Opcode");
 }
 
-extern "x86-interrupt" fn gp_fault_handler(stack_frame: InterruptStackFrame, error_code: u64) {
-    handle_gp_fault(&stack_frame, error_code); // Yüksek seviye handler'ı çağır
+// #GP ve #PF, yazmaçları kaydeden global_asm giriş stub'larından çağrılır (FaultTrapFrame).
+#[no_mangle]
+extern "C" fn x86_64_gp_fault(frame: &mut FaultTrapFrame) {
+    handle_gp_fault(frame); // Yüksek seviye handler'ı çağır
 }
 
-extern "x86-interrupt" fn page_fault_handler(stack_frame: InterruptStackFrame, error_code: u64) {
+#[no_mangle]
+extern "C" fn x86_64_page_fault(frame: &mut FaultTrapFrame) {
     // CR2 yazmacı faulting adresi içerir.
     let faulting_address: u64 = unsafe { core::arch::x86_64::_read_cr2() };
-    handle_page_fault(&stack_frame, error_code, faulting_address); // Yüksek seviye handler'ı çağır
+    handle_page_fault(frame, faulting_address); // Yüksek seviye handler'ı çağır
 }
 
 // Double Fault kurtarılamaz, özel bir stack (IST) kullanır ve panik yapmalıdır.
@@ -658,31 +827,6 @@ mod kkernel {
      }
 }
 
-// Bellek yönetimi placeholder
-#[allow(dead_code)]
-mod kmemory {
-     use super::*; // karnal64.rs scope'undaki tipleri kullan
-     // Kavramsal sayfa hatası işleyici
-     pub fn handle_page_fault(address: u64, error_code: u64) -> Result<(), KError> {
-         klog::debug!("kmemory::handle_page_fault(addr={:#x}, code={:#x}) - Placeholder", address, error_code);
-         // Gerçek implementasyon: sayfa tablosunu kontrol et, yeni sayfa ayır/eşle vb.
-         Err(KError::BadAddress) // Şimdilik başarısız olduğunu varsayalım
-     }
-}
-
-// Görev yönetimi placeholder
-#[allow(dead_code)]
-mod ktask {
-      use super::*; // Karnal64 tipleri
-     // Kavramsal görev zamanlayıcı ve sonlandırma
-     pub fn schedule() { klog::trace!("ktask::schedule() - Placeholder"); }
-      pub fn terminate_current_task(error: KError) {
-          klog::error!("ktask::terminate_current_task with error: {:?}", error);
-     //     // Gerçek implementasyon: Görevi sonlandır, kaynakları temizle, zamanlayıcıyı çalıştır.
-          loop { core::arch::asm!("hlt"); } // Basit durdurma
-      }
-}
-
 // Interrupts placeholder (EOI göndermek gibi)
 #[allow(dead_code)]
 mod src_interrupts {
@@ -717,8 +861,8 @@ const UNKNOWN_HANDLER_PTR: u64 = unknown_interrupt_handler as u64;
 const DIVIDE_ERROR_HANDLER_PTR: u64 = divide_error_handler as u64;
 const BREAKPOINT_HANDLER_PTR: u64 = breakpoint_handler as u64;
 const INVALID_OPCODE_HANDLER_PTR: u64 = invalid_opcode_handler as u64;
-const GP_FAULT_HANDLER_PTR: u64 = gp_fault_handler as u64;
-const PAGE_FAULT_HANDLER_PTR: u64 = page_fault_handler as u64;
+const GP_FAULT_HANDLER_PTR: u64 = x86_64_gp_fault_entry as u64;
+const PAGE_FAULT_HANDLER_PTR: u64 = x86_64_page_fault_entry as u64;
 const DOUBLE_FAULT_HANDLER_PTR: u64 = double_fault_handler as u64;
 const SYSCALL_ENTRY_PTR: u64 = syscall_entry as u64;
 const TIMER_INTERRUPT_HANDLER_PTR: u64 = timer_interrupt_handler as u64;
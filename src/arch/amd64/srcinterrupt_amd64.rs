@@ -50,27 +50,31 @@ extern "x86-interrupt" fn exception_handler(stack_frame: InterruptStackFrame, ve
 }
 
 extern "x86-interrupt" fn timer_interrupt_handler(_stack_frame: InterruptStackFrame) {
-    // Zamanlayıcı kesme işleyicisi (IRQ0 / Vektör 32)
-    // Bu kesme, işletim sisteminin zamanlayıcısı (scheduler) tarafından kullanılır.
-    // Genellikle bir görev değişimi (context switch) burada tetiklenir.
-
-    // TODO: Karnal64'ün görev yöneticisini (ktask) çağırarak zamanlayıcıyı çalıştır.
-     ktask::schedule();
+    // Yerel APIC zamanlayıcı kesmesi (Vektör 32, srcsmp_amd64.rs::TIMER_VECTOR; PIT maskelidir).
+    // Zamanlayıcı tek seferliktir: timer_tick süresi dolan bekleyenleri uyandırır, zaman dilimini
+    // hesaplar ve zamanlayıcıyı bir sonraki olaya yeniden kurar (srctask_amd64.rs::program_timer).
+    karnal64::ksync::lockdep::irq_enter();
+    karnal64::ktask::timer_tick(karnal64::ktask::now_ns());
+    crate::smp::lapic_eoi();
+    karnal64::ksync::lockdep::irq_exit();
+    // Gerekirse bağlam değişimi burada, kesme bağlamından çıkarken yapılır.
+    karnal64::ktask::preempt_on_interrupt_exit();
+}
 
-    // PIC'e (Programlanabilir Kesme Denetleyicisi) sinyal gönder: Kesmeyi işledik.
-    // Bu, PIC'in aynı kesmeyi tekrar göndermesini engeller.
-    // Bu PIC yönetimi src/pic.rs gibi ayrı bir modülde olabilir.
-    unsafe {
-         pic::notify_end_of_interrupt(PIC_1_OFFSET); // PIC1'den geldi
-         // Yer tutucu PIC sinyali
-        print!("."); // Zamanlayıcının çalıştığını göstermek için basit bir çıktı
-    }
-    // Zamanlayıcı kesmesi oldukça sık gerçekleşebilir, dikkatli loglama yapılmalı.
+extern "x86-interrupt" fn ipi_interrupt_handler(_stack_frame: InterruptStackFrame) {
+    // İşlemciler arası kesme (Vektör 0xF0): başka bir CPU'dan yeniden zamanlama veya
+    // TLB temizleme isteği (srcsmp_amd64.rs::send_ipi).
+    karnal64::ksync::lockdep::irq_enter();
+    karnal64::ktask::handle_ipi();
+    crate::smp::lapic_eoi();
+    karnal64::ksync::lockdep::irq_exit();
+    karnal64::ktask::preempt_on_interrupt_exit();
 }
 
 extern "x86-interrupt" fn keyboard_interrupt_handler(_stack_frame: InterruptStackFrame) {
     // Klavye kesme işleyicisi (IRQ1 / Vektör 33)
     // Klavye denetleyicisinden gelen tuş basma/bırakma olaylarını işler.
+    karnal64::ksync::lockdep::irq_enter();
 
     // TODO: Klavye donanımından veriyi oku.
     // TODO: Okunan veriyi Karnal64'ün kaynak yöneticisi (kresource) üzerinden konsol kaynağına veya bir girdi kuyruğuna ilet.
@@ -81,6 +85,7 @@ extern "x86-interrupt" fn keyboard_interrupt_handler(_stack_frame: InterruptStac
          // Yer tutucu PIC sinyali
         print!("K"); // Klavye kesmesinin geldiğini göstermek için basit bir çıktı
     }
+    karnal64::ksync::lockdep::irq_exit();
 }
 
 extern "x86-interrupt" fn page_fault_handler(stack_frame: InterruptStackFrame, error_code: PageFaultErrorCode) {
@@ -198,11 +203,13 @@ pub fn init_idt() {
     idt.general_protection_fault.set_handler_fn(general_protection_fault_handler);
 
     // Donanım kesme işleyicilerini kur (PIC üzerinden gelenler)
-    // Vektör 32: Zamanlayıcı (Timer - IRQ0)
-    idt[PIC_1_OFFSET].set_handler_fn(timer_interrupt_handler);
+    // Vektör 32: Yerel APIC zamanlayıcısı (IRQ0'ın vektörü; PIT maskelidir)
+    idt[crate::smp::TIMER_VECTOR].set_handler_fn(timer_interrupt_handler);
     // Vektör 33: Klavye (Keyboard - IRQ1)
     idt[PIC_1_OFFSET + 1].set_handler_fn(keyboard_interrupt_handler);
     // ... Diğer donanım kesmeleri ...
+    // IPI: yerel APIC üzerinden CPU'lar arası yeniden zamanlama/TLB temizleme
+    idt[crate::smp::IPI_VECTOR].set_handler_fn(ipi_interrupt_handler);
 
     // Sistem Çağrısı işleyicisini kur
     // Vektör 128 (0x80) genellikle syscall için kullanılır
@@ -244,3 +251,65 @@ pub fn enable_interrupts() {
 extern "x86-interrupt" fn breakpoint_handler(stack_frame: InterruptStackFrame) {
     println!("EXCEPTION: BREAKPOINT\n{:#?}", stack_frame);
 }
+
+// --- Karnal64 Kesme Maskesi Kancaları (hardware_specific.h) ---
+// ksync::Spinlock::lock_irqsave ve çekirdek genelindeki kesme aç/kapa işlemleri bunları kullanır.
+// Kaydedilen durum RFLAGS.IF bitidir (bit 9).
+
+const RFLAGS_IF: u64 = 1 << 9;
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_enable() {
+    x86_64::instructions::interrupts::enable(); // STI
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_disable() {
+    x86_64::instructions::interrupts::disable(); // CLI
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupts_enabled() -> i32 {
+    x86_64::instructions::interrupts::are_enabled() as i32
+}
+
+/// Kesmeleri kapatır ve önceki IF durumunu döndürür.
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_save() -> u64 {
+    let flags = if x86_64::instructions::interrupts::are_enabled() { RFLAGS_IF } else { 0 };
+    x86_64::instructions::interrupts::disable();
+    flags
+}
+
+/// `low_level_interrupt_save` ile kaydedilen durumu geri yükler.
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_restore(flags: u64) {
+    if flags & RFLAGS_IF != 0 {
+        x86_64::instructions::interrupts::enable();
+    }
+}
+
+// --- Karnal64 Yığın İzi Kancası (hardware_specific.h) ---
+// lockdep raporlarında kullanılır; çekirdek çerçeve işaretçisiyle (force-frame-pointers) derlenmelidir.
+
+/// RBP zincirini yürür: [rbp] önceki RBP, [rbp + 8] dönüş adresi.
+/// Zincir sıfıra, hizasız bir adrese veya yığında aşağı doğru bir çerçeveye ulaşınca durur.
+#[no_mangle]
+pub extern "C" fn low_level_stack_trace(frames: *mut u64, max_frames: usize) -> usize {
+    let mut fp: u64;
+    unsafe { core::arch::asm!("mov {0}, rbp", out(reg) fp, options(nomem, nostack, preserves_flags)) };
+    let mut count = 0;
+    while count < max_frames && fp != 0 && fp % 8 == 0 {
+        let (prev, ret) = unsafe { (*(fp as *const u64), *((fp + 8) as *const u64)) };
+        if ret == 0 {
+            break;
+        }
+        unsafe { *frames.add(count) = ret };
+        count += 1;
+        if prev <= fp {
+            break;
+        }
+        fp = prev;
+    }
+    count
+}
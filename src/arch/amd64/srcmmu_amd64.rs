@@ -207,52 +207,13 @@ impl fmt::Debug for PageTableEntry {
     }
 }
 
-// --- Fiziksel Frame Ayırıcı Trait'i ---
+// --- Fiziksel Frame Ayırıcı ---
 // MMU kodunun yeni sayfa tablosu sayfaları için fiziksel bellek talep etmesi gerekir.
-// Bu trait, çekirdeğin fiziksel bellek yöneticisinin sağlaması gereken arayüzü tanımlar.
-pub trait FrameAllocator {
-    /// Tek bir fiziksel frame (genellikle 4KB) tahsis eder ve fiziksel adresini döndürür.
-    /// Uygun frame yoksa `None` döner.
-    fn allocate_frame(&mut self) -> Option<PhysAddr>;
-
-    /// Daha önce tahsis edilmiş bir fiziksel frame'i iade eder.
-    /// # Güvenlik (Safety)
-    /// Çağıran, `frame_addr`'nin geçerli bir fiziksel adres olduğundan ve
-    /// daha önce bu ayırıcıdan alındığından ve artık kullanımda olmadığından emin olmalıdır.
-    unsafe fn deallocate_frame(&mut self, frame_addr: PhysAddr);
-}
-
-// --- Yer Tutucu Frame Ayırıcı Implementasyonu ---
-// Gerçek bir çekirdekte, bu, bootloader'dan alınan bellek haritasına göre implemente edilmelidir.
-struct PlaceholderFrameAllocator;
-
-impl FrameAllocator for PlaceholderFrameAllocator {
-    fn allocate_frame(&mut self) -> Option<PhysAddr> {
-        // Burası gerçek bir tahsis mantığı içermelidir.
-        // Örneğin:
-         static mut NEXT_FREE_FRAME: PhysAddr = 0x1000000; // Örnek başlangıç adresi
-         static mut REMAINING_FRAMES: usize = 100; // Örnek kalan frame sayısı
-         unsafe {
-             if REMAINING_FRAMES > 0 {
-                 let addr = NEXT_FREE_FRAME;
-                 NEXT_FREE_FRAME += PAGE_SIZE_4K;
-                 REMAINING_FRAMES -= 1;
-                  println!("PlaceholderFrameAllocator: Allocated frame 0x{:x}", addr); // Çekirdek içi print!
-                 Some(addr)
-             } else {
-                  println!("PlaceholderFrameAllocator: Out of frames"); // Çekirdek içi print!
-                 None
-             }
-         }
-        // Şimdilik her zaman None döndürerek bellek yokmuş gibi simüle edelim.
-        None
-    }
-
-    unsafe fn deallocate_frame(&mut self, frame_addr: PhysAddr) {
-         // Burası gerçek bir iade mantığı içermelidir.
-          println!("PlaceholderFrameAllocator: Deallocated frame 0x{:x}", frame_addr); // Çekirdek içi print!
-    }
-}
+// Çerçeveler kmemory'nin buddy ayırıcısından, ortak `FrameAllocator` trait'i üzerinden alınır
+// (bkz. src/memory/srcframe.rs). Ayırıcı kendi kilidini tuttuğundan `&dyn` ile paylaşılır.
+pub use crate::karnal64::kmemory::FrameAllocator;
+use crate::karnal64::kmemory::{frame_allocator, register_arch_mmu, ArchMmu, PROT_EXEC, PROT_NONE, PROT_WRITE};
+use crate::karnal64::KError;
 
 
 // --- x86_64 MMU Yöneticisi ---
@@ -262,14 +223,14 @@ impl FrameAllocator for PlaceholderFrameAllocator {
 pub struct X86MmuManager {
     // MMU yöneticisi, fiziksel frame ayırıcısına bir referans veya sahip olabilir.
     // Genellikle kernel başlangıcında statik bir ayırıcı kurulur ve buraya geçirilir.
-    // allocator: &'static mut dyn FrameAllocator, // Örnek alan
+    // allocator: &'static dyn FrameAllocator, // Örnek alan
 }
 
 impl X86MmuManager {
     /// Yeni bir MMU yöneticisi instance'ı oluşturur.
     /// Kernel başlangıcında çağrılmalıdır. Fiziksel frame ayırıcısı burada veya
     /// init_manager'da ayarlanmalıdır.
-    pub fn new(/* allocator: &'static mut dyn FrameAllocator */) -> Self {
+    pub fn new(/* allocator: &'static dyn FrameAllocator */) -> Self {
         X86MmuManager {
             // allocator: allocator
         }
@@ -295,7 +256,7 @@ impl X86MmuManager {
         &self, // Eğer allocator alan olarak tutuluyorsa &self yerine &mut self olabilir
         page_table_root_phys: PhysAddr,
         virt_addr: VirtAddr,
-        allocator: &mut dyn FrameAllocator,
+        allocator: &dyn FrameAllocator,
         create_intermediate: bool,
     ) -> Result<(&'a mut PageTableEntry, PhysAddr), MmuError> {
 
@@ -358,7 +319,7 @@ impl X86MmuManager {
                     if create_intermediate {
                         // Sonraki düzey için yeni bir fiziksel frame (sayfa tablosu sayfası) tahsis et.
                         let new_table_phys_addr = allocator.allocate_frame()
-                            .ok_or(MmuError::OutOfMemory)?;
+                            .map_err(|_| MmuError::OutOfMemory)?;
 
                         // Yeni sayfa tablosu sayfasını sıfırla (ÇOK ÖNEMLİ!).
                         // Aksi takdirde rastgele değerler güvenlik açığı oluşturabilir.
@@ -412,7 +373,7 @@ impl X86MmuManager {
         virt_addr: VirtAddr,
         phys_addr: PhysAddr,
         flags: PageFlags,
-        allocator: &mut dyn FrameAllocator,
+        allocator: &dyn FrameAllocator,
     ) -> Result<(), MmuError> {
         // Temel adres hizalama kontrolleri
         if virt_addr % PAGE_SIZE_4K != 0 || phys_addr % PAGE_SIZE_4K != 0 {
@@ -465,7 +426,7 @@ impl X86MmuManager {
         &self, // Eğer allocator alan olarak tutuluyorsa &mut self olabilir
         page_table_root_phys: PhysAddr,
         virt_addr: VirtAddr,
-        allocator: &mut dyn FrameAllocator,
+        allocator: &dyn FrameAllocator,
     ) -> Result<(), MmuError> {
          // Temel adres hizalama kontrolü
         if virt_addr % PAGE_SIZE_4K != 0 {
@@ -499,7 +460,7 @@ impl X86MmuManager {
         // girdiye sahiptiyse ve tamamen boşaldıysa, bu fiziksel frame iade edilebilir.
         // Bu, yürüyüşü geri takip etmeyi ve her düzeyde tablonun boş olup olmadığını
         // kontrol etmeyi gerektirir. Basitlik için şimdilik bu adımı atlayabiliriz.
-        // Implement edilirse, `allocator.deallocate_frame(tablo_phys_addr)` çağrılmalıdır.
+        // Implement edilirse, `allocator.free_frame(tablo_phys_addr)` çağrılmalıdır.
 
         Ok(())
     }
@@ -577,9 +538,9 @@ impl X86MmuManager {
     /// Görev (Task) oluşturulurken kendi adres alanını tanımlamak için kullanılır.
     /// # Güvenlik (Safety)
     /// Çağıran, dönen fiziksel adresin geçerli ve güvenli olduğundan emin olmalıdır.
-    pub unsafe fn create_new_page_table(&self, allocator: &mut dyn FrameAllocator) -> Result<PhysAddr, MmuError> {
+    pub unsafe fn create_new_page_table(&self, allocator: &dyn FrameAllocator) -> Result<PhysAddr, MmuError> {
         // Yeni bir PML4 fiziksel frame tahsis et
-        let pml4_phys_addr = allocator.allocate_frame().ok_or(MmuError::OutOfMemory)?;
+        let pml4_phys_addr = allocator.allocate_frame().map_err(|_| MmuError::OutOfMemory)?;
 
         // PML4 tablosu sayfasını sıfırla
         ptr::write_bytes(pml4_phys_addr as *mut u8, 0, PAGE_SIZE_4K as usize); // Identity map varsayılıyor
@@ -605,7 +566,7 @@ impl X86MmuManager {
         phys_start: PhysAddr,
         size: usize,
         flags: PageFlags,
-        allocator: &mut dyn FrameAllocator,
+        allocator: &dyn FrameAllocator,
     ) -> Result<(), MmuError> {
         let mut current_virt = virt_start;
         let mut current_phys = phys_start;
@@ -634,7 +595,7 @@ impl X86MmuManager {
         page_table_root_phys: PhysAddr,
         virt_start: VirtAddr,
         size: usize,
-        allocator: &mut dyn FrameAllocator,
+        allocator: &dyn FrameAllocator,
     ) -> Result<(), MmuError> {
         let mut current_virt = virt_start;
         let size_pages = (size + PAGE_SIZE_4K as usize - 1) / PAGE_SIZE_4K as usize;
@@ -676,13 +637,132 @@ impl X86MmuManager {
          println!("MMU: Switched to page table at 0x{:x}", pml4_phys_addr); // Çekirdek içi print!
     }
 
-    // TODO: Sayfa tablosu hiyerarşisini yok etme (tüm sayfaları iade etme).
     // TODO: Sayfa tablosu hiyerarşisini kopyalama (fork için).
     // TODO: Büyük sayfalar (2MB/1GB) için haritalama/kaldırma fonksiyonları (şu anki map_page sadece 4K'yı hedefliyor).
     // TODO: TLB yönetimi fonksiyonları (global flush, specific address flush).
 }
 
 
+// --- kmemory Entegrasyonu ---
+// Görev adres alanları (VMA'lar, boş aralık arama, çerçevelerin sahipliği) mimariden bağımsız
+// kmemory modülündedir (bkz. src/memory/srcvmspace.rs). Bu port yalnızca sayfa tablosu
+// işlemlerini `kmemory::ArchMmu` üzerinden sağlar; TLB temizliği ktask::tlb_shootdown ile yapılır.
+
+/// PML4'ün kullanıcı yarısı girdileri; 256..512 çekirdeğe aittir ve her tabloda paylaşılır.
+const USER_PML4_ENTRIES: usize = 256;
+/// Kullanıcı yarısının sonu: her PML4 girdisi 512GB kapsar (alt yarı kanonik aralık, 2^47).
+const USER_VA_LIMIT: u64 = (USER_PML4_ENTRIES as u64) << 39;
+
+/// Tablo başına giriş sayısı.
+const ENTRIES_PER_TABLE: usize = 512;
+
+/// PROT_* korumasını son düzey PTE bayraklarına çevirir. PROT_NONE sayfa yalnızca çekirdekten
+/// erişilebilir kalır.
+fn prot_to_flags(prot: u32) -> PageFlags {
+    let mut flags = PageFlags::PRESENT;
+    if prot != PROT_NONE {
+        flags = flags.with(PageFlags::USER_ACCESSIBLE);
+    }
+    if prot & PROT_WRITE != 0 {
+        flags = flags.with(PageFlags::WRITABLE);
+    }
+    if prot & PROT_EXEC == 0 {
+        flags = flags.with(PageFlags::NO_EXECUTE);
+    }
+    flags
+}
+
+/// `table` ara tablosunu ve altındaki tabloları bırakır. `depth`: altında kalan ara tablo düzeyi
+/// sayısı (PDPT için 2, PT için 0). Son düzey girdiler önceden kaldırılmış olmalıdır.
+unsafe fn free_table(table: PhysAddr, depth: usize) {
+    if depth > 0 {
+        for i in 0..ENTRIES_PER_TABLE {
+            let entry = &*(table as *const PageTableEntry).add(i); // Identity map varsayılıyor
+            if entry.is_present() && !entry.is_huge_page() {
+                free_table(entry.physical_address(PAGE_SIZE_4K), depth - 1);
+            }
+        }
+    }
+    frame_allocator().free_frame(table);
+}
+
+impl ArchMmu for X86MmuManager {
+    unsafe fn create_page_tables(&self) -> Result<u64, KError> {
+        self.create_new_page_table(frame_allocator()).map_err(map_mmu_error)
+    }
+
+    unsafe fn map_page(&self, root: u64, vaddr: u64, paddr: u64, prot: u32) -> Result<(), KError> {
+        X86MmuManager::map_page(self, root, vaddr, paddr, prot_to_flags(prot), frame_allocator()).map_err(map_mmu_error)
+    }
+
+    unsafe fn unmap_page(&self, root: u64, vaddr: u64) -> Option<u64> {
+        let (pte, _) = self.walk_page_table_mut(root, vaddr, frame_allocator(), false).ok()?;
+        if !pte.is_present() {
+            return None;
+        }
+        let paddr = pte.physical_address(PAGE_SIZE_4K);
+        pte.clear();
+        Some(paddr)
+    }
+
+    unsafe fn protect_page(&self, root: u64, vaddr: u64, prot: u32) -> bool {
+        match self.walk_page_table_mut(root, vaddr, frame_allocator(), false) {
+            Ok((pte, _)) if pte.is_present() => {
+                let paddr = pte.physical_address(PAGE_SIZE_4K);
+                pte.set_entry(paddr, prot_to_flags(prot));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    unsafe fn translate(&self, root: u64, vaddr: u64, write_access: bool) -> Option<u64> {
+        let (pte, _) = self.walk_page_table_mut(root, vaddr, frame_allocator(), false).ok()?;
+        let flags = pte.flags();
+        if !pte.is_present() || !flags.contains(PageFlags::USER_ACCESSIBLE) {
+            return None;
+        }
+        if write_access && !flags.contains(PageFlags::WRITABLE) {
+            return None;
+        }
+        Some(pte.physical_address(PAGE_SIZE_4K) + (vaddr & (PAGE_SIZE_4K - 1)))
+    }
+
+    fn user_space_end(&self) -> usize {
+        USER_VA_LIMIT as usize
+    }
+
+    unsafe fn destroy_page_tables(&self, root: u64) {
+        let pml4 = root as *mut PageTableEntry; // Identity map varsayılıyor
+        for i in 0..USER_PML4_ENTRIES {
+            let entry = &mut *pml4.add(i);
+            if entry.is_present() {
+                free_table(entry.physical_address(PAGE_SIZE_4K), 2);
+                entry.clear();
+            }
+        }
+    }
+}
+
+static X86_MMU: X86MmuManager = X86MmuManager {};
+
+/// Sayfa tablosu işlemlerini kmemory'ye kaydeder; ilk kullanıcı görevi oluşturulmadan önce çağrılır.
+pub fn init() {
+    register_arch_mmu(&X86_MMU);
+}
+
+// MMU Hatalarını Karnal64 Hatalarına Çeviren Yardımcı Fonksiyon
+fn map_mmu_error(mmu_err: MmuError) -> KError {
+    match mmu_err {
+        MmuError::OutOfMemory => KError::OutOfMemory,
+        MmuError::InvalidArgument => KError::InvalidArgument,
+        MmuError::AlreadyMapped => KError::AlreadyExists, // Zaten haritalı -> Zaten mevcut gibi düşünülebilir
+        MmuError::NotMapped => KError::NotFound, // Haritalı değil -> Bulunamadı gibi düşünülebilir
+        _ => KError::InternalError, // Diğer MMU hatalarını dahili hata olarak raporla
+    }
+}
+
+
 // --- Kavramsal Entegrasyon Notları (kmemory modülü içinde) ---
 
 // karnal64.rs dosyası içindeki kmemory modülü şu şekilde srcmmu_x86'ı kullanabilir:
@@ -690,80 +770,44 @@ impl X86MmuManager {
 mod kmemory {
     use super::*; // karnal64.rs kapsamındaki tipleri kullan (KError, KHandle vb.)
     use crate::srcmmu_x86::{X86MmuManager, FrameAllocator, PhysAddr, VirtAddr, PageFlags, MmuError}; // srcmmu_x86.rs'dan import
+    use crate::karnal64::kmemory::{add_physical_memory_region, frame_allocator, init_frame_allocator, reserve_physical_region};
 
-    // Kernel'ın global fiziksel frame ayırıcısı (başlangıçta initialize edilmeli)
-    static mut GLOBAL_FRAME_ALLOCATOR: Option<MyKernelFrameAllocatorImpl> = None; // Çekirdeğinizin gerçek ayırıcısı
+    // Fiziksel frame ayırıcı kmemory'nin global buddy ayırıcısıdır (`frame_allocator()`).
     // Kernel'ın MMU yöneticisi instance'ı
     static mut X86_MMU_MANAGER: Option<X86MmuManager> = None;
 
     // Başlangıçta çağrılan fonksiyon
     pub fn init_manager(/* boot_info: &BootInfo */) { // Boot bilgisini alarak ayırıcıyı başlatabiliriz
         unsafe {
-            // TODO: GLOBAL_FRAME_ALLOCATOR'ı boot_info'ya göre başlatın
-             GLOBAL_FRAME_ALLOCATOR = Some(MyKernelFrameAllocatorImpl::new(boot_info.memory_map));
+            // Boot bellek haritasındaki kullanılabilir bölgeleri buddy ayırıcıya ver; diğer türler
+            // (ACPI tabloları, firmware) zaten bölge olarak eklenmediği için ayırıcının dışında kalır.
+             for entry in boot_info.memory_map.iter().filter(|e| e.kind == MemoryKind::Usable) {
+                 add_physical_memory_region(entry.base, entry.length).expect("Bellek bölgesi eklenemedi");
+             }
+             if let Some((initrd_base, initrd_size)) = boot_info.initrd {
+                 reserve_physical_region(initrd_base, initrd_size).expect("initrd ayrılamadı");
+             }
+             init_frame_allocator().expect("Fiziksel çerçeve ayırıcı başlatılamadı");
 
             // MMU yöneticisini başlatın
             X86_MMU_MANAGER = Some(X86MmuManager::new());
 
             // TODO: Kernel'ın kendi adres alanını ve ilk görev'in (örneğin bootstrapper) adres alanını kurun.
             // Bu, `create_new_page_table` ve `map_range` fonksiyonlarını kullanır.
-             let kernel_pml4_phys = setup_initial_kernel_mapping(GLOBAL_FRAME_ALLOCATOR.as_mut().unwrap());
+             let kernel_pml4_phys = setup_initial_kernel_mapping(frame_allocator());
              X86_MMU_MANAGER.as_ref().unwrap().switch_page_table(kernel_pml4_phys); // Çekirdek sayfalarına geçiş
              // Kaydedin: struct Task { pml4_phys: PhysAddr, ... }
 
              // TODO: İlk kullanıcı alanı görevini (örn: init prosesi) başlatın
              // Yeni bir sayfa tablosu oluşturun, kodunu haritalayın, yığınını haritalayın vb.
-              let init_task_pml4_phys = X86_MMU_MANAGER.as_ref().unwrap().create_new_page_table(GLOBAL_FRAME_ALLOCATOR.as_mut().unwrap()).expect("Failed to create init task page table");
+              let init_task_pml4_phys = X86_MMU_MANAGER.as_ref().unwrap().create_new_page_table(frame_allocator()).expect("Failed to create init task page table");
              // Map init code, stack etc. into init_task_pml4_phys...
 
         }
          println!("Karnal64: Bellek Yöneticisi Başlatıldı (x86_64 MMU entegrasyonu bekleniyor)"); // Çekirdek içi print!
     }
 
-    // Karnal64 API fonksiyonlarının implementasyonu (srcmmu_x86'ı kullanarak)
-
-    // Örnek: Kullanıcı alanı bellek tahsisi (bir görev için sanal alan bulma ve fiziksel frame haritalama)
-    pub fn memory_allocate(size: usize) -> Result<*mut u8, KError> {
-        // TODO: Mevcut görevin adres alanında yeterli boyutta boş sanal adres aralığı bul.
-        // TODO: İstenen `size` kadar fiziksel frame tahsis et (GLOBAL_FRAME_ALLOCATOR kullanarak).
-        // TODO: Bulunan sanal aralığı tahsis edilen fiziksel frame'lere haritala (`X86_MMU_MANAGER.map_range` kullanarak).
-        // TODO: Başarı durumunda tahsis edilen sanal adresin başlangıcını *kullanıcı alanına ait* pointer olarak döndür.
-
-        // Yer tutucu: Her zaman hata dön
-         Err(KError::NotSupported) // Implemente edilmediği için
-    }
-
-    // Örnek: Haritalanmış paylaşımlı belleği kaldırma (unmap)
-    pub fn shared_mem_unmap(ptr: *mut u8, size: usize) -> Result<(), KError> {
-        // TODO: `ptr`'nin geçerli bir kullanıcı alanı pointer'ı ve görev'in adres alanında olduğunu doğrula.
-        // TODO: Mevcut görevin PML4 fiziksel adresini al.
-        let mmu = unsafe { X86_MMU_MANAGER.as_ref().ok_or(KError::InternalError)? };
-        let allocator = unsafe { GLOBAL_FRAME_ALLOCATOR.as_mut().ok_or(KError::InternalError)? };
-
-        // Assuming we have the current task's PML4 physical address
-         let current_task_pml4_phys = get_current_task_pml4_phys(); // TODO: Task yöneticisinden al
-
-        unsafe {
-              mmu.unmap_range(current_task_pml4_phys, ptr as VirtAddr, size, allocator)
-                 .map_err(|mmu_err| map_mmu_error(mmu_err)) // MMU hatasını KError'a çevir
-        }
-         Err(KError::NotSupported) // Yer tutucu
-    }
-
-
-    // MMU Hatalarını Karnal64 Hatalarına Çeviren Yardımcı Fonksiyon
-    fn map_mmu_error(mmu_err: MmuError) -> KError {
-        match mmu_err {
-            MmuError::OutOfMemory => KError::OutOfMemory,
-            MmuError::InvalidArgument => KError::InvalidArgument,
-            MmuError::AlreadyMapped => KError::AlreadyExists, // Zaten haritalı -> Zaten mevcut gibi düşünülebilir
-            MmuError::NotMapped => KError::NotFound, // Haritalı değil -> Bulunamadı gibi düşünülebilir
-            _ => KError::InternalError, // Diğer MMU hatalarını dahili hata olarak raporla
-        }
-    }
-
-    // Diğer kmemory API fonksiyonlarını implemente edin...
-    // memory_release, shared_mem_create, shared_mem_map vb.
-    // Hepsi X86MmuManager'ı ve GLOBAL_FRAME_ALLOCATOR'ı kullanmalıdır.
+    // Kullanıcı belleği ve paylaşımlı bellek API'si (memory_alloc/map/unmap/protect) mimariden
+    // bağımsız kmemory modülündedir; bu port ona yukarıdaki `ArchMmu` implementasyonunu kaydeder.
 
 }
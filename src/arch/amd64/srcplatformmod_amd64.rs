@@ -17,6 +17,18 @@ extern crate alloc; // Karnal64 içinde Box veya Vec gibi türler kullanılıyor
 #[path = "../../karnal64.rs"] // Geçici olarak dosya yolunu belirtiyorum, gerçekte modül import edilir.
 mod karnal64;
 
+// ktask mimari katmanı (bağlam kurma/değiştirme, adres alanı etkinleştirme)
+#[path = "srctask_amd64.rs"]
+mod task;
+
+// kmemory sayfa tablosu işlemleri (görev adres alanlarının PML4 hiyerarşileri)
+#[path = "srcmmu_amd64.rs"]
+mod mmu;
+
+// SMP: AP'leri başlatma (INIT-SIPI), yerel APIC IPI'leri ve low_level_cpu_id
+#[path = "srcsmp_amd64.rs"]
+mod smp;
+
 use karnal64::{KError, KHandle}; // Karnal64 API'sından hata ve handle tiplerini kullanıyoruz
 
 // --- x86_64 Platformuna Özgü Veri Yapıları ---
@@ -81,6 +93,12 @@ pub extern "C" fn platform_init() {
     // Genel Karnal64 çekirdek başlatma fonksiyonunu çağır.
     // Bu fonksiyon, resource manager, task manager gibi Karnal64 iç modüllerini başlatır.
     karnal64::init();
+    // Zamanlayıcı bağlam değiştirebilsin diye mimari katmanı kaydet.
+    task::init();
+    // Görev adres alanlarının sayfa tablolarını kurabilmesi için kmemory'ye kaydet.
+    mmu::init();
+    // Boot CPU'sunu kaydet ve diğer CPU'ları (AP) başlat.
+    smp::init();
 
     // TODO: Daha sonraki başlatma adımları
     // - Cihaz sürücülerini kaydetme (platforma özgü cihazlar için ResourceProvider implementasyonları)
@@ -162,10 +180,9 @@ pub extern "C" fn x86_64_syscall_handler(
 // Ancak sayfa tablosu manipülasyonu, TLB temizleme gibi mimariye özgü işlemler
 // için bu platform modülünü çağırır.
 
+// Sayfa tablosu işlemleri srcmmu_amd64.rs'de (`mod mmu`), CR3 yükleme ve TLB temizleme
+// srctask_amd64.rs'dedir (ktask::ArchTask).
 mod mmu_x86_64 {
-    // TODO: x86_64 Sayfa Tablosu yapıları (PML4, PDPT, PD, PT) ile etkileşim
-    // TODO: CR3 registerını yükleme (aktif sayfa tablosunu değiştirme)
-    // TODO: TLB girdisini geçersiz kılma (invlpg talimatı)
     // TODO: Sayfalama (Paging) özelliğini etkinleştirme (CR0, CR4 registerları)
 }
 
@@ -0,0 +1,457 @@
+// Karnal64 SMP için x86_64 mimari katmanı.
+// Uygulama işlemcileri (AP) yerel APIC üzerinden INIT-SIPI-SIPI dizisiyle başlatılır. AP gerçek
+// modda `TRAMPOLINE_BASE`'ten başlar; trampolin kodu (aşağıdaki global_asm) oraya kopyalanır ve
+// AP'yi doğrudan uzun moda geçirip `prepare_cpu`'nun verdiği yığınla `amd64_ap_main`'e atlar.
+// IPI'ler yerel APIC ICR ile `IPI_VECTOR`'e gönderilir (srcinterrupt_amd64.rs → ktask::handle_ipi).
+// CPU'lar ACPI MADT'deki yerel APIC/x2APIC girdilerinden kaydedilir; paket/çekirdek/SMT
+// topolojisi APIC ID'nin CPUID yaprak 0xB'nin bildirdiği alanlarından türetilir. RSDP veya MADT
+// bulunamazsa CPUID yaprak 1'in bildirdiği mantıksal işlemci sayısı kadar ardışık APIC ID
+// varsayılır (QEMU `-smp N`).
+// Varsayımlar: yerel APIC, ilk 1 MiB ve ACPI tabloları kimlik eşlemeli; çekirdek PML4'ü 4 GiB'ın
+// altında ve trampolin sayfasını da kimlik eşliyor.
+
+use core::arch::{asm, global_asm};
+use core::ptr::{read_volatile, write_volatile};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::karnal64::ktask;
+
+/// AP'lerin başlayacağı fiziksel adres (SIPI vektörü = adres >> 12).
+const TRAMPOLINE_BASE: u64 = 0x8000;
+
+/// Yerel APIC MMIO tabanı ve yazmaç ofsetleri.
+const LAPIC_BASE: u64 = 0xFEE0_0000;
+const LAPIC_ID: u64 = 0x20;
+const LAPIC_EOI: u64 = 0xB0;
+const LAPIC_SVR: u64 = 0xF0;
+const LAPIC_ICR_LOW: u64 = 0x300;
+const LAPIC_ICR_HIGH: u64 = 0x310;
+const LAPIC_LVT_TIMER: u64 = 0x320;
+
+const SVR_APIC_ENABLE: u32 = 1 << 8;
+const SPURIOUS_VECTOR: u32 = 0xFF;
+const ICR_DELIVERY_PENDING: u32 = 1 << 12;
+const ICR_INIT: u32 = 0x4500; // INIT, level assert
+const ICR_STARTUP: u32 = 0x4600; // Start-up IPI
+/// LVT zamanlayıcı kipi: TSC-deadline (CPUID.01H:ECX[24] desteği varsayılır).
+const LVT_TIMER_TSC_DEADLINE: u32 = 0b10 << 17;
+
+/// Yerel APIC zamanlayıcısının vektörü: IRQ0'ınkiyle (PIC_1_OFFSET) aynıdır, bu yüzden PIT maskelenir.
+pub const TIMER_VECTOR: u8 = 0x20;
+/// 8259 ana PIC'in veri (maske) portu.
+const PIC1_DATA_PORT: u16 = 0x21;
+
+/// Yeniden zamanlama/TLB temizleme IPI'lerinin vektörü.
+pub const IPI_VECTOR: u8 = 0xF0;
+
+/// Bir AP'nin trampolini geçip `amd64_ap_main`'e ulaşmasını bekleme süresi.
+const AP_BOOT_TIMEOUT_NS: u64 = 100_000_000;
+
+/// ACPI tablo imzaları ve ortak SDT başlığının boyutu.
+const RSDP_SIGNATURE: &[u8; 8] = b"RSD PTR ";
+const MADT_SIGNATURE: &[u8; 4] = b"APIC";
+const SDT_HEADER_LEN: u64 = 36;
+
+/// MADT girdi türleri ve yerel APIC bayrakları. MADT girdileri SDT başlığından sonraki yerel
+/// APIC adresi ve bayraklar alanını (8 bayt) izler.
+const MADT_ENTRIES_OFFSET: u64 = SDT_HEADER_LEN + 8;
+const MADT_LOCAL_APIC: u8 = 0;
+const MADT_LOCAL_X2APIC: u8 = 9;
+const MADT_LAPIC_ENABLED: u32 = 1 << 0;
+
+/// Trampolinin sonundaki parametre bloğu (`amd64_ap_params`). AP'ler sırayla başlatıldığı için
+/// tek blok yeterlidir.
+#[repr(C)]
+struct ApParams {
+    cr3: u64,
+    stack: u64,
+    entry: u64,
+    cpu: u64,
+}
+
+/// AP, trampolini geçip parametre bloğunu okuduğunda işaretler.
+static AP_BOOTED: AtomicBool = AtomicBool::new(false);
+
+// BSP'nin IDTR değeri; AP'ler aynı tabloyu yükler. GDT/TSS srctask_amd64.rs::load_cpu_tables'tadır.
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+struct DescriptorTablePointer {
+    limit: u16,
+    base: u64,
+}
+
+static mut BSP_IDTR: DescriptorTablePointer = DescriptorTablePointer { limit: 0, base: 0 };
+
+global_asm!(
+    r#"
+    .section .text
+    .global amd64_ap_trampoline_start
+    .global amd64_ap_trampoline_end
+    .global amd64_ap_params
+    .code16
+    // TRAMPOLINE_BASE'e kopyalanıp gerçek modda çalışır; adresler bu yüzden mutlak hesaplanır.
+amd64_ap_trampoline_start:
+    cli
+    cld
+    xorw %ax, %ax
+    movw %ax, %ds
+    lgdtl {base} + ap_gdtr - amd64_ap_trampoline_start
+
+    // Gerçek moddan doğrudan uzun moda: PAE, CR3, EFER.LME, ardından CR0.PG|PE.
+    movl %cr4, %eax
+    orl $0x20, %eax
+    movl %eax, %cr4
+    movl {base} + amd64_ap_params - amd64_ap_trampoline_start, %eax
+    movl %eax, %cr3
+    movl $0xC0000080, %ecx
+    rdmsr
+    orl $0x100, %eax
+    wrmsr
+    movl %cr0, %eax
+    orl $0x80000001, %eax
+    movl %eax, %cr0
+    ljmpl $0x08, ${base} + ap_long_mode - amd64_ap_trampoline_start
+
+    .code64
+ap_long_mode:
+    movw $0x10, %ax
+    movw %ax, %ds
+    movw %ax, %es
+    movw %ax, %ss
+    xorw %ax, %ax
+    movw %ax, %fs
+    movw %ax, %gs
+    movq {base} + amd64_ap_params - amd64_ap_trampoline_start + 8, %rsp
+    movq {base} + amd64_ap_params - amd64_ap_trampoline_start + 16, %rax
+    movq {base} + amd64_ap_params - amd64_ap_trampoline_start + 24, %rdi
+    callq *%rax
+1:
+    hlt
+    jmp 1b
+
+    .balign 8
+amd64_ap_params:
+    .quad 0 // cr3
+    .quad 0 // stack
+    .quad 0 // entry
+    .quad 0 // cpu
+ap_gdt:
+    .quad 0
+    .quad 0x00AF9A000000FFFF // 0x08: 64 bit çekirdek kodu
+    .quad 0x00CF92000000FFFF // 0x10: çekirdek verisi
+ap_gdtr:
+    .word ap_gdtr - ap_gdt - 1
+    .long {base} + ap_gdt - amd64_ap_trampoline_start
+amd64_ap_trampoline_end:
+    "#,
+    base = const TRAMPOLINE_BASE,
+    options(att_syntax)
+);
+
+extern "C" {
+    static amd64_ap_trampoline_start: u8;
+    static amd64_ap_trampoline_end: u8;
+    static amd64_ap_params: u8;
+}
+
+unsafe fn lapic_read(reg: u64) -> u32 {
+    read_volatile((LAPIC_BASE + reg) as *const u32)
+}
+
+unsafe fn lapic_write(reg: u64, value: u32) {
+    write_volatile((LAPIC_BASE + reg) as *mut u32, value);
+}
+
+fn lapic_id() -> u64 {
+    (unsafe { lapic_read(LAPIC_ID) } >> 24) as u64
+}
+
+/// Yerel APIC'e kesmenin bittiğini bildirir (IPI işleyicisi çağırır).
+pub fn lapic_eoi() {
+    unsafe { lapic_write(LAPIC_EOI, 0) };
+}
+
+/// Bu CPU'nun yerel APIC'ini yazılımca etkinleştirir ve zamanlayıcısını TSC-deadline kipine alır.
+/// Zamanlayıcı, ktask ilk kez kurana kadar (srctask_amd64.rs::program_timer) kesme üretmez.
+fn lapic_enable() {
+    unsafe {
+        lapic_write(LAPIC_SVR, lapic_read(LAPIC_SVR) | SVR_APIC_ENABLE | SPURIOUS_VECTOR);
+        lapic_write(LAPIC_LVT_TIMER, LVT_TIMER_TSC_DEADLINE | TIMER_VECTOR as u32);
+    }
+}
+
+/// PIT'in IRQ0'ını 8259'da maskeler; zamanlayıcı vektörünü yalnızca yerel APIC zamanlayıcısı kullanır.
+/// Diğer IRQ'lar (klavye vb.) açık kalır.
+fn mask_pit_irq() {
+    unsafe {
+        let mask: u8;
+        asm!("in al, dx", in("dx") PIC1_DATA_PORT, out("al") mask, options(nomem, nostack));
+        asm!("out dx, al", in("dx") PIC1_DATA_PORT, in("al") mask | 1, options(nomem, nostack));
+    }
+}
+
+/// ICR'ye bir komut yazar ve teslim edilene kadar bekler. ICR iki yazmaçtan oluştuğundan
+/// kesmeler kapalıyken çağrılmalıdır.
+unsafe fn lapic_send(apic_id: u64, command: u32) {
+    while lapic_read(LAPIC_ICR_LOW) & ICR_DELIVERY_PENDING != 0 {
+        core::hint::spin_loop();
+    }
+    lapic_write(LAPIC_ICR_HIGH, (apic_id as u32) << 24);
+    lapic_write(LAPIC_ICR_LOW, command);
+    while lapic_read(LAPIC_ICR_LOW) & ICR_DELIVERY_PENDING != 0 {
+        core::hint::spin_loop();
+    }
+}
+
+fn delay_ns(ns: u64) {
+    let end = ktask::now_ns() + ns;
+    while ktask::now_ns() < end {
+        core::hint::spin_loop();
+    }
+}
+
+/// Çağıran CPU'nun mantıksal indeksi (hardware_specific.h): APIC ID, ktask kaydıyla eşlenir.
+/// Kayıtsız APIC ID'ler 0 döner.
+#[no_mangle]
+pub extern "C" fn low_level_cpu_id() -> u32 {
+    ktask::cpu_index(lapic_id()).unwrap_or(0) as u32
+}
+
+/// APIC ID'si `apic_id` olan CPU'ya `IPI_VECTOR` gönderir (srctask_amd64.rs::send_ipi).
+pub fn send_ipi(apic_id: u64) {
+    let _irq = crate::karnal64::ksync::irq_save();
+    unsafe { lapic_send(apic_id, IPI_VECTOR as u32) };
+}
+
+/// CPUID `leaf`/`subleaf` sonucu (eax, ebx, ecx, edx).
+fn cpuid(leaf: u32, subleaf: u32) -> (u32, u32, u32, u32) {
+    let (eax, ebx, ecx, edx): (u32, u64, u32, u32);
+    unsafe {
+        // rbx LLVM tarafından ayrıldığından cpuid sonrası geçici yazmaca taşınır.
+        asm!(
+            "mov {tmp:r}, rbx",
+            "cpuid",
+            "xchg {tmp:r}, rbx",
+            tmp = out(reg) ebx,
+            inout("eax") leaf => eax,
+            inout("ecx") subleaf => ecx,
+            out("edx") edx,
+            options(nostack, preserves_flags)
+        );
+    }
+    (eax, ebx as u32, ecx, edx)
+}
+
+/// MADT bulunamadıysa CPUID yaprak 1'den mantıksal işlemci sayısını okuyup ardışık APIC ID'leri kaydeder.
+fn probe_cpus() {
+    let (_, ebx, _, edx) = cpuid(1, 0);
+    // EDX.HTT (bit 28) yoksa paket tek mantıksal işlemcilidir.
+    let count = if edx & (1 << 28) != 0 { ((ebx >> 16) & 0xFF) as u64 } else { 1 };
+    let boot = lapic_id();
+    for apic_id in (0..count).filter(|&id| id != boot) {
+        if ktask::add_cpu(apic_id).is_err() {
+            println!("SMP: MAX_CPUS aşıldı, APIC ID {} ve sonrası kullanılmayacak", apic_id);
+            break;
+        }
+    }
+}
+
+unsafe fn read_u8(addr: u64) -> u8 {
+    read_volatile(addr as *const u8)
+}
+
+// ACPI tablolarındaki alanlar hizalı olmak zorunda değildir.
+unsafe fn read_u32(addr: u64) -> u32 {
+    core::ptr::read_unaligned(addr as *const u32)
+}
+
+unsafe fn read_u64(addr: u64) -> u64 {
+    core::ptr::read_unaligned(addr as *const u64)
+}
+
+/// ACPI yapılarının baytları toplamı (mod 256) sıfırdır.
+unsafe fn acpi_checksum_ok(addr: u64, len: u64) -> bool {
+    (0..len).fold(0u8, |sum, i| sum.wrapping_add(read_u8(addr + i))) == 0
+}
+
+/// RSDP'yi EBDA'nın ilk 1 KiB'ında ve BIOS alanında (0xE0000-0xFFFFF) 16 bayt sınırlarında arar.
+fn find_rsdp() -> Option<u64> {
+    let ebda = (unsafe { read_volatile(0x40E as *const u16) } as u64) << 4;
+    [(ebda, ebda + 1024), (0xE0000, 0x10_0000)]
+        .iter()
+        .filter(|&&(start, _)| start != 0)
+        .find_map(|&(start, end)| {
+            (start..end).step_by(16).find(|&addr| unsafe {
+                core::slice::from_raw_parts(addr as *const u8, 8) == RSDP_SIGNATURE && acpi_checksum_ok(addr, 20)
+            })
+        })
+}
+
+/// RSDP'den XSDT'yi (ACPI 2.0+) veya RSDT'yi izleyerek imzası `signature` olan tabloyu bulur.
+fn find_acpi_table(rsdp: u64, signature: &[u8; 4]) -> Option<u64> {
+    unsafe {
+        let xsdt = if read_u8(rsdp + 15) >= 2 { read_u64(rsdp + 24) } else { 0 };
+        let (root, entry_size) = if xsdt != 0 { (xsdt, 8) } else { (read_u32(rsdp + 16) as u64, 4) };
+        let count = (read_u32(root + 4) as u64).saturating_sub(SDT_HEADER_LEN) / entry_size;
+        (0..count)
+            .map(|i| root + SDT_HEADER_LEN + i * entry_size)
+            .map(|entry| if entry_size == 8 { read_u64(entry) } else { read_u32(entry) as u64 })
+            .find(|&table| {
+                core::slice::from_raw_parts(table as *const u8, 4) == signature
+                    && acpi_checksum_ok(table, read_u32(table + 4) as u64)
+            })
+    }
+}
+
+/// APIC ID'deki SMT ve (SMT dahil) çekirdek alanlarının bit genişlikleri, CPUID yaprak 0xB'den.
+/// Yaprak yoksa SMT'siz ve 8 bitlik APIC ID'lerin tek pakette olduğu varsayılır.
+fn apic_id_shifts() -> (u32, u32) {
+    if cpuid(0, 0).0 < 0xB {
+        return (0, 8);
+    }
+    let (mut smt, mut core) = (0, 0);
+    for level in 0..8 {
+        let (eax, _, ecx, _) = cpuid(0xB, level);
+        match (ecx >> 8) & 0xFF {
+            0 => break,
+            1 => smt = eax & 0x1F,
+            2 => core = eax & 0x1F,
+            _ => {}
+        }
+    }
+    (smt, core.max(smt))
+}
+
+/// ACPI MADT'deki etkin yerel APIC/x2APIC girdilerinden CPU'ları ve APIC ID'lerinden türetilen
+/// topolojilerini kaydeder. RSDP veya MADT bulunamazsa `false` döner.
+fn register_cpus_from_madt() -> bool {
+    let madt = match find_rsdp().and_then(|rsdp| find_acpi_table(rsdp, MADT_SIGNATURE)) {
+        Some(madt) => madt,
+        None => return false,
+    };
+    let (smt_shift, core_shift) = apic_id_shifts();
+    let end = madt + unsafe { read_u32(madt + 4) } as u64;
+    let mut entry = madt + MADT_ENTRIES_OFFSET;
+    while entry + 2 <= end {
+        let (kind, len) = unsafe { (read_u8(entry), read_u8(entry + 1) as u64) };
+        if len < 2 {
+            break;
+        }
+        let cpu = unsafe {
+            match kind {
+                // Tür 0: ACPI işlemci kimliği (u8), APIC ID (u8), bayraklar (u32).
+                MADT_LOCAL_APIC => Some((read_u8(entry + 3) as u64, read_u32(entry + 4))),
+                // Tür 9: ayrılmış (u16), x2APIC ID (u32), bayraklar (u32), ACPI UID (u32).
+                MADT_LOCAL_X2APIC => Some((read_u32(entry + 4) as u64, read_u32(entry + 8))),
+                _ => None,
+            }
+        };
+        entry += len;
+        let apic_id = match cpu {
+            Some((apic_id, flags)) if flags & MADT_LAPIC_ENABLED != 0 => apic_id,
+            _ => continue,
+        };
+        if ktask::add_cpu(apic_id).is_err() {
+            println!("SMP: MAX_CPUS aşıldı, APIC ID {} ve sonrası kullanılmayacak", apic_id);
+            break;
+        }
+        let topology = ktask::CpuTopology {
+            hw_id: apic_id,
+            package: (apic_id >> core_shift) as u32,
+            cluster: 0,
+            core: ((apic_id >> smt_shift) & ((1 << (core_shift - smt_shift)) - 1)) as u32,
+            thread: (apic_id & ((1 << smt_shift) - 1)) as u32,
+            capacity: 0,
+            online: 0,
+        };
+        let _ = ktask::set_cpu_topology(topology);
+    }
+    true
+}
+
+fn start_cpu(cpu: usize, cr3: u64) {
+    let apic_id = match ktask::cpu_hw_id(cpu) {
+        Some(id) => id,
+        None => return,
+    };
+    let stack = match ktask::prepare_cpu(cpu) {
+        Ok(stack) => stack,
+        Err(e) => {
+            println!("SMP: CPU {} (APIC ID {}) hazırlanamadı: {:?}", cpu, apic_id, e);
+            return;
+        }
+    };
+    unsafe {
+        let offset = &amd64_ap_params as *const u8 as u64 - &amd64_ap_trampoline_start as *const u8 as u64;
+        let params = (TRAMPOLINE_BASE + offset) as *mut ApParams;
+        write_volatile(
+            params,
+            ApParams { cr3, stack, entry: amd64_ap_main as usize as u64, cpu: cpu as u64 },
+        );
+    }
+    AP_BOOTED.store(false, Ordering::Release);
+
+    // INIT, 10 ms bekle, ardından iki kez SIPI (Intel MP başlatma dizisi).
+    {
+        let _irq = crate::karnal64::ksync::irq_save();
+        unsafe { lapic_send(apic_id, ICR_INIT) };
+    }
+    delay_ns(10_000_000);
+    for _ in 0..2 {
+        {
+            let _irq = crate::karnal64::ksync::irq_save();
+            unsafe { lapic_send(apic_id, ICR_STARTUP | (TRAMPOLINE_BASE >> 12) as u32) };
+        }
+        delay_ns(200_000);
+        if AP_BOOTED.load(Ordering::Acquire) {
+            break;
+        }
+    }
+
+    // Parametre bloğu bir sonraki AP için yeniden yazılmadan önce bu AP onu okumuş olmalı.
+    let deadline = ktask::now_ns() + AP_BOOT_TIMEOUT_NS;
+    while !AP_BOOTED.load(Ordering::Acquire) {
+        if ktask::now_ns() >= deadline {
+            println!("SMP: CPU {} (APIC ID {}) yanıt vermedi", cpu, apic_id);
+            return;
+        }
+        core::hint::spin_loop();
+    }
+}
+
+/// AP'nin Rust girişi; trampolin, `prepare_cpu` yığınına geçtikten sonra çağırır.
+extern "C" fn amd64_ap_main(cpu: u64) -> ! {
+    AP_BOOTED.store(true, Ordering::Release);
+    // Paylaşılan GDT'ye (trampolin GDT'si ile aynı çekirdek seçicileri) ve bu CPU'nun TSS'ine geç.
+    crate::task::load_cpu_tables(cpu as usize);
+    unsafe {
+        let idtr = BSP_IDTR;
+        asm!("lidt [{0}]", in(reg) &idtr, options(readonly, nostack));
+    }
+    // TODO: SYSCALL MSR'ları; kullanıcı kodu çalıştırmadan önce gerekir.
+    lapic_enable();
+    ktask::cpu_online()
+}
+
+/// Boot CPU'sunu kaydeder, yerel APIC'i açar ve kayıtlı tüm AP'leri başlatır.
+/// Platform başlatma kodu task::init()'ten sonra çağırır.
+pub fn init() {
+    ktask::register_boot_cpu(lapic_id());
+    mask_pit_irq();
+    lapic_enable();
+    if ktask::possible_cpus() == 1 && !register_cpus_from_madt() {
+        probe_cpus();
+    }
+    let cr3: u64;
+    unsafe {
+        asm!("sidt [{0}]", in(reg) core::ptr::addr_of_mut!(BSP_IDTR), options(nostack));
+        asm!("mov {0}, cr3", out(reg) cr3, options(nomem, nostack));
+
+        let start = &amd64_ap_trampoline_start as *const u8;
+        let len = &amd64_ap_trampoline_end as *const u8 as usize - start as usize;
+        core::ptr::copy_nonoverlapping(start, TRAMPOLINE_BASE as *mut u8, len);
+    }
+    for cpu in 1..ktask::possible_cpus() {
+        start_cpu(cpu, cr3);
+    }
+}
@@ -1,151 +1,293 @@
-#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz, çekirdek alanında çalışırız
+// x86_64 için ktask mimari katmanı (ktask::ArchTask):
+// - bağlam kurma: yeni iş parçacığı `x86_64_thread_trampoline` üzerinden başlar
+// - bağlam değiştirme: SysV ABI'de çağrılan tarafından korunan yazmaçlar (rbx, rbp, r12-r15),
+//   rsp ve devam adresi kaydedilir/yüklenir
+// - adres alanı: CR3 yazılır (TLB, global olmayan girdiler için donanımca temizlenir)
+// - TLS: kullanıcı fs_base'i bir MSR olduğundan tuzak çerçevesinde taşınmaz; iş parçacığı başına
+//   bağlamda tutulur ve bağlam değişiminde kaydedilip yüklenir
+// - SMP: IPI yerel APIC ile gönderilir (srcsmp_amd64.rs), idle bekleme `sti; hlt` ile yapılır
+// - zamanlayıcı: tek seferlik, yerel APIC zamanlayıcısının TSC-deadline kipiyle (IA32_TSC_DEADLINE) kurulur
+// - GDT/TSS: tüm CPU'lar tek GDT'yi paylaşır, her CPU'nun kendi TSS'i vardır. Kullanıcı modundan
+//   gelen kesmeler TSS.rsp0 yığınına geçer; bağlam değişiminde bir sonraki iş parçacığının çekirdek
+//   yığın tepesi bu CPU'nun rsp0'ına yazılır.
 
-// Geliştirme sırasında kullanılmayan kod veya argümanlar için izinler
-#![allow(dead_code)]
-#![allow(unused_variables)]
-#![allow(improper_ctypes_definitions)] // Bağlam değiştirme fonksiyonu için gerekli olabilir
+use core::arch::{asm, global_asm};
+use core::mem::{size_of, size_of_val};
+use core::ptr::{addr_of, addr_of_mut};
 
-// Karnal64'ün temel tiplerini kullanabiliriz
-// Özellikle KThreadId, KError gibi tipler burada tanımlanan yapılarla ilişkilendirilebilir.
- use crate::karnal64::{KThreadId, KError}; // Örnek kullanım, gerçek import yolu kernel yapısına göre değişir.
-// Şu an için doğrudan temel Rust tiplerini kullanacağız.
+use crate::karnal64::ktask::{self, ArchTask, ThreadStart};
 
-/// x86_64 iş parçacığı/görev bağlamını (context) temsil eden yapı.
-/// Kaydedilmesi ve geri yüklenmesi gereken CPU yazmaçlarını (register) içerir.
-/// Bağlam değiştirme (context switch) sırasında bu yapı kullanılır.
+/// Kullanıcı kod/veri segment seçicileri (GDT düzenine göre, RPL = 3).
+const USER_CODE_SELECTOR: u64 = 0x23;
+const USER_DATA_SELECTOR: u64 = 0x1b;
+/// Kullanıcı moduna geçerken RFLAGS: IF (bit 9) ve her zaman 1 olan bit 1.
+const USER_RFLAGS: u64 = 0x202;
+
+const PAGE_SIZE: u64 = 4096;
+/// Bundan büyük aralıklar sayfa sayfa değil, TLB'nin tamamı temizlenerek silinir.
+const TLB_FLUSH_ALL_THRESHOLD: u64 = 64 * PAGE_SIZE;
+/// CR4.PGE: değiştirilmesi global girdiler dahil tüm TLB'yi temizler.
+const CR4_PGE: u64 = 1 << 7;
+/// IA32_FS_BASE MSR'ı.
+const MSR_FS_BASE: u32 = 0xC000_0100;
+/// IA32_TSC_DEADLINE MSR'ı: TSC bu değere ulaşınca yerel APIC zamanlayıcı kesmesi üretilir.
+const MSR_TSC_DEADLINE: u32 = 0x6E0;
+/// TSC frekansı (srctime_amd64.rs ile aynı varsayım; kalibrasyon henüz yok).
+const TSC_FREQUENCY_HZ: u64 = 1_000_000_000;
+
+/// x86_64 iş parçacığı bağlamı.
+/// Yalnızca çağrılan tarafından korunması gereken yazmaçlar tutulur; geri kalanı
+/// x86_64_switch_context çağrısı sırasında zaten çağıranın yığınındadır.
 #[derive(Debug, Copy, Clone)]
-#[repr(C)] // C uyumlu bellek düzeni sağlamak için, genellikle assembly ile etkileşimde önemlidir.
+#[repr(C)] // Assembly'deki ofsetlerle aynı düzen
 pub struct ThreadContext {
-    /// Geri yüklenecek genel amaçlı yazmaçlar (r15, r14, r13, r12, rbp, rbx, rflags)
-    pub regs: [u64; 7],
-    /// Instruction Pointer (Sonraki çalıştırılacak komutun adresi)
-    pub rip: u64,
-    /// Stack Pointer (Yığıtın güncel tepesi)
     pub rsp: u64,
-    /// Segment yazmaçları (cs, ss - genellikle kullanıcı/kernel modları için)
-    pub cs: u64,
-    pub ss: u64,
-    /// Bazı hata kodları veya diğer özel yazmaçlar da eklenebilir (örneğin, exception stack frame için)
+    /// rbx, rbp, r12, r13, r14, r15. İlk geçişte r12-r15 trampoline başlangıç bilgisini taşır.
+    pub regs: [u64; 6],
+    /// Bağlam tekrar yüklendiğinde atlanacak adres.
+    pub rip: u64,
+    /// Bu iş parçacığının çekirdek yığınının tepesi (TSS.rsp0).
+    pub kernel_stack_top: u64,
+    /// Kullanıcı TLS tabanı (IA32_FS_BASE).
+    pub fs_base: u64,
+}
+
+const _: () = assert!(size_of::<ThreadContext>() <= ktask::ARCH_CONTEXT_SIZE);
+
+/// 64 bit TSS. Yalnızca rsp0 kullanılır; IST yığınları ve G/Ç izin haritası yoktur.
+#[repr(C, packed(4))]
+struct TaskStateSegment {
+    reserved0: u32,
+    rsp: [u64; 3],
+    reserved1: u64,
+    ist: [u64; 7],
+    reserved2: u64,
+    reserved3: u16,
+    iomap_base: u16,
+}
+
+const EMPTY_TSS: TaskStateSegment = TaskStateSegment {
+    reserved0: 0,
+    rsp: [0; 3],
+    reserved1: 0,
+    ist: [0; 7],
+    reserved2: 0,
+    reserved3: 0,
+    // Segment sınırının ötesi: G/Ç izin haritası yok.
+    iomap_base: size_of::<TaskStateSegment>() as u16,
+};
+
+/// CPU başına TSS; rsp0 o CPU'da çalışan iş parçacığının çekirdek yığın tepesidir.
+static mut TSS: [TaskStateSegment; ktask::MAX_CPUS] = [EMPTY_TSS; ktask::MAX_CPUS];
+
+/// GDT'de ilk TSS tanımlayıcısının indeksi. Her TSS tanımlayıcısı iki girdi kaplar.
+const GDT_TSS_FIRST: usize = 5;
+const KERNEL_CODE_SELECTOR: u64 = 0x08;
+const KERNEL_DATA_SELECTOR: u64 = 0x10;
+
+/// Paylaşılan GDT: 0x08 çekirdek kodu ve 0x10 çekirdek verisi (AP trampolini ile aynı),
+/// 0x18 kullanıcı verisi, 0x20 kullanıcı kodu, 0x28'den itibaren CPU başına TSS.
+static mut GDT: [u64; GDT_TSS_FIRST + 2 * ktask::MAX_CPUS] = {
+    let mut gdt = [0; GDT_TSS_FIRST + 2 * ktask::MAX_CPUS];
+    gdt[1] = 0x00AF_9A00_0000_FFFF;
+    gdt[2] = 0x00CF_9200_0000_FFFF;
+    gdt[3] = 0x00CF_F200_0000_FFFF;
+    gdt[4] = 0x00AF_FA00_0000_FFFF;
+    gdt
+};
+
+global_asm!(
+    r#"
+    .section .text
+    .global x86_64_switch_context
+    // rdi: önceki bağlam (*mut ThreadContext), rsi: sonraki bağlam
+x86_64_switch_context:
+    mov [rdi + 0x00], rsp
+    mov [rdi + 0x08], rbx
+    mov [rdi + 0x10], rbp
+    mov [rdi + 0x18], r12
+    mov [rdi + 0x20], r13
+    mov [rdi + 0x28], r14
+    mov [rdi + 0x30], r15
+    lea rax, [rip + 2f]
+    mov [rdi + 0x38], rax
+
+    mov rsp, [rsi + 0x00]
+    mov rbx, [rsi + 0x08]
+    mov rbp, [rsi + 0x10]
+    mov r12, [rsi + 0x18]
+    mov r13, [rsi + 0x20]
+    mov r14, [rsi + 0x28]
+    mov r15, [rsi + 0x30]
+    jmp qword ptr [rsi + 0x38]
+2:
+    ret
+
+    .global x86_64_thread_trampoline
+    // r12: giriş, r13: argüman, r14: kullanıcı yığını, r15: kullanıcı modu mu
+x86_64_thread_trampoline:
+    mov rdi, r13
+    test r15, r15
+    jnz 1f
+    // Çekirdek iş parçacığı: kesmeleri aç ve girişe atla (geri dönmez).
+    sti
+    jmp r12
+1:
+    // Kullanıcı iş parçacığı: iretq çerçevesi kur (ss, rsp, rflags, cs, rip).
+    push {user_ds}
+    push r14
+    push {user_rflags}
+    push {user_cs}
+    push r12
+    xor eax, eax
+    iretq
+"#,
+    user_ds = const USER_DATA_SELECTOR,
+    user_rflags = const USER_RFLAGS,
+    user_cs = const USER_CODE_SELECTOR,
+);
+
+extern "C" {
+    fn x86_64_switch_context(prev: *mut ThreadContext, next: *const ThreadContext);
+    fn x86_64_thread_trampoline();
+}
+
+unsafe fn read_fs_base() -> u64 {
+    let (low, high): (u32, u32);
+    asm!("rdmsr", in("ecx") MSR_FS_BASE, out("eax") low, out("edx") high, options(nomem, nostack));
+    ((high as u64) << 32) | low as u64
 }
 
-/// Yeni bir iş parçacığı/görev için başlangıç bağlamını hazırlar.
-///
-/// Bu fonksiyon, iş parçacığı ilk kez zamanlandığında çalışacak olan
-/// entry point fonksiyonuna kontrollü bir şekilde dallanabilmek için
-/// yığıtı (stack) manipüle eder.
-///
-/// # Argümanlar
-/// * `stack_top`: İş parçacığına ayrılan yığıt alanının en üst (en yüksek adresli) pointer'ı.
-/// * `entry_point`: İş parçacığı çalışmaya başladığında çağrılacak olan fonksiyonun adresi.
-/// * `arg`: Entry point fonksiyonuna geçirilecek argüman (isteğe bağlı, u64).
-///
-/// # Dönüş Değeri
-/// Başlangıç için ayarlanmış bir `ThreadContext` yapısı.
-pub unsafe fn create_initial_context(
-    stack_top: *mut u8,
-    entry_point: extern "C" fn(u64) -> !, // !: Asla geri dönmeyen fonksiyon (task_exit gibi)
-    arg: u64,
-) -> ThreadContext {
-    // Yığıtın en üstünden (stack_top) aşağı doğru (düşük adreslere) yazacağız.
-    // x86-64 SysV ABI (genellikle Unix benzeri sistemlerde kullanılır, çekirdeklerde de popülerdir)
-    // veya özel bir çekirdek ABI'sine göre yığıt çerçevesi oluşturulur.
-    // Basit bir yaklaşım: Bağlam değiştirme fonksiyonu geri döndüğünde,
-    // RIP olarak entry_point'e, RSP olarak başlangıç yığıt tepesine gidecek şekilde yığıtı ayarla.
-    // Ayrıca, entry_point'e argümanı geçirmek için çağrı kuralına (calling convention) uygun yazmaçları (rdi) ayarla.
-
-    // Güvenli olmayan (unsafe) blok içinde pointer aritmetiği yapacağız.
-    // `stack_top`, ayrılan alanın *sonrasını* gösteriyor olabilir, bu durumda önce biraz çıkarmak gerekir.
-    // Veya ayrılan alanın en üstünü gösteriyorsa, doğrudan çıkarma ile kullanırız.
-    // Basitlik adına, stack_top'ın kullanılabilecek en yüksek adresi temsil ettiğini varsayalım.
-
-    // Bağlam değiştirme rutininin beklediği kaydedilmiş yazmaçları simüle edin.
-    // Bunlar, ThreadContext yapısındaki sırayla yığıta itilebilir.
-    // Ancak, context switch assembly kodu genellikle belirli bir sırayı bekler.
-    // Biz ThreadContext yapısını doğrudan assembly ile senkronize edeceğimizi varsayalım.
-
-    // Yığıta konulacak ilk şey, bağlam değiştirme rutini "geri döndüğünde"
-    // dallanılacak adres olmalıdır (yani entry_point).
-    // Ardından, context switch rutini pop edeceği diğer yazmaçlar.
-    // rdi, rsi, rdx, rcx, r8, r9 (ilk 6 integer argüman SysV ABI'de)
-    // Bizim entry_point'imiz tek argüman alıyor (arg), bu rdi'ye gidecek.
-
-    // Örnek yığıt düzeni (basitten karmaşığa):
-    // 1. Sadece entry_point adresini yığıta koy. Context switch geri dönünce buraya dallanır. (Basit ama argüman geçişi yok)
-    // 2. Çağrı kuralına (ABI) uygun bir stack frame oluştur. (Daha doğru)
-
-    // SysV ABI'ye uygun minimalist stack frame oluşturma
-    // Normal bir fonksiyon çağrısında return adresi yığıta konulur.
-    // Bağlam değiştirme rutini, geri dönerken bu adresi RIP'e yükleyecektir.
-    // entry_point fonksiyonumuzun yığıtın temiz halini beklemesi için,
-    // initial RSP'yi entry_point'in hemen öncesini gösterecek şekilde ayarlayabiliriz.
-
-    let stack_ptr = (stack_top as usize as u64); // Yığıtın başlangıç adresi
-
-    // Entry point'e geçeceğimiz argümanı kaydetmemiz lazım.
-    // Bu, genellikle ThreadContext yapısının bir parçası olmaz,
-    // assembly'deki context switch rutini tarafından entry point'e dallanmadan önce
-    // rdi yazmacına yüklenir.
-
-    // ThreadContext yapısını, context switch assembly kodumuzun yığıta iteceği/alacağı
-    // yazmaçları temsil edecek şekilde dolduralım.
-    // Bu, assembly kodunun implementasyonuna sıkı sıkıya bağlıdır!
-    // Varsayılan olarak 0 ile başlatıyoruz, sadece rip ve rsp'yi doğru ayarlıyoruz.
-    // rdi'yi (argüman) ayarlamak assembly switch fonksiyonunun görevidir.
-
-    let mut initial_context = ThreadContext {
-        regs: [0; 7],
-        rip: entry_point as u64, // İş parçacığı başladığında buraya gidecek
-        rsp: stack_ptr,         // İş parçacığı başladığında yığıt burayı gösterecek
-        cs: 0, // TODO: Doğru kod segmenti seçici (selector)
-        ss: 0, // TODO: Doğru yığıt segmenti seçici (selector)
-    };
-
-    // SysV ABI'de ilk argüman rdi'ye gider.
-    // Bağlam değiştirme rutininiz muhtemelen ThreadContext yapısını yükler,
-    // ardından entry_point'e dallanmadan önce rdi'yi ayarlar.
-    // Bu argümanın ThreadContext içinde saklanması yaygın değildir.
-    // Eğer argümanı context içinde saklamak isteseydiniz, 'regs' dizisine ekleyip
-    // context switch assembly'sini buna göre ayarlamanız gerekirdi.
-     ThreadContext::regs[0] = rdi // olsun derseniz, assembly rdi'yi buradan alır.
-
-    // Basitlik adına, entry point argümanının assembly tarafından rdi'ye yükleneceğini varsayıyoruz.
-    // Yani, create_initial_context fonksiyonu sadece context yapısını dolduruyor,
-    // argümanı kullanıcının sağladığı entry_point fonksiyonuna nasıl ileteceği
-    // context switch assembly fonksiyonunun detayıdır.
-    // Ya da ThreadContext yapısını, argümanı (rdi yazmacını) içerecek şekilde genişletip
-    // regs dizisini 8 elemanlı yapabilirsiniz.
-    // Örnek olarak regs[0] rdi olsun dersek:
-     initial_context.regs[0] = arg; // rdi yazmacına argümanı koy
-
-    // Şu anki ThreadContext tanımına göre, entry_point'e argüman geçişi
-    // bu fonksiyondan bağımsız bir mekanizma gerektirir (örn. context switch assembly'si).
-
-    initial_context
+unsafe fn write_fs_base(value: u64) {
+    asm!("wrmsr", in("ecx") MSR_FS_BASE, in("eax") value as u32, in("edx") (value >> 32) as u32, options(nostack));
+}
+
+/// ktask::ArchTask'ın x86_64 implementasyonu.
+pub struct X86_64Task;
+
+pub static ARCH_TASK: X86_64Task = X86_64Task;
+
+impl ArchTask for X86_64Task {
+    unsafe fn init_context(&self, ctx: *mut u8, start: &ThreadStart) {
+        let ctx = &mut *(ctx as *mut ThreadContext);
+        // Girişte yığın, bir `call` sonrasındaki gibi 16n+8 hizasında olmalıdır.
+        ctx.rsp = start.kernel_stack_top - 8;
+        ctx.regs[2] = start.entry; // r12
+        ctx.regs[3] = start.arg; // r13
+        ctx.regs[4] = start.user_stack_top; // r14
+        ctx.regs[5] = start.user as u64; // r15
+        ctx.rip = x86_64_thread_trampoline as usize as u64;
+        ctx.kernel_stack_top = start.kernel_stack_top;
+        // ktask, TLS değerinin kanonik bir kullanıcı adresi olduğunu doğrular (wrmsr #GP vermez).
+        ctx.fs_base = start.tls;
+    }
+
+    unsafe fn switch_context(&self, prev: *mut u8, next: *const u8) {
+        let prev = prev as *mut ThreadContext;
+        let next = next as *const ThreadContext;
+        // Sıradaki iş parçacığı kullanıcı modundan kesildiğinde kendi çekirdek yığınına geçsin.
+        addr_of_mut!(TSS[ktask::current_cpu()].rsp[0]).write_unaligned((*next).kernel_stack_top);
+        // Kullanıcı FSGSBASE ile fs_base'i değiştirmiş olabilir; güncel değer kaydedilir.
+        (*prev).fs_base = read_fs_base();
+        if (*next).fs_base != (*prev).fs_base {
+            write_fs_base((*next).fs_base);
+        }
+        x86_64_switch_context(prev, next);
+    }
+
+    unsafe fn activate_address_space(&self, root: u64) {
+        // Çekirdek eşlemeleri her PML4'ün üst yarısında bulunur.
+        if root == ktask::KERNEL_ADDRESS_SPACE {
+            return;
+        }
+        asm!("mov cr3, {0}", in(reg) root, options(nostack));
+    }
+
+    unsafe fn flush_tlb(&self, _root: u64, start: u64, len: u64) {
+        if len == 0 || len > TLB_FLUSH_ALL_THRESHOLD {
+            // CR4.PGE'yi kapatıp açmak global (çekirdek) girdiler dahil her şeyi temizler.
+            let cr4: u64;
+            asm!("mov {0}, cr4", out(reg) cr4, options(nomem, nostack));
+            asm!("mov cr4, {0}", "mov cr4, {1}", in(reg) cr4 & !CR4_PGE, in(reg) cr4, options(nostack));
+            return;
+        }
+        let mut va = start & !(PAGE_SIZE - 1);
+        while va < start + len {
+            asm!("invlpg [{0}]", in(reg) va, options(nostack));
+            va += PAGE_SIZE;
+        }
+    }
+
+    unsafe fn send_ipi(&self, hw_id: u64) {
+        super::smp::send_ipi(hw_id);
+    }
+
+    unsafe fn program_timer(&self, deadline_ns: u64) {
+        // Yukarı yuvarlanır: kesme `deadline_ns`'ten önce gelmemeli. 0 zamanlayıcıyı durdurur,
+        // geçmişteki bir değer ise hemen kesme üretir; bu yüzden en az 1 yazılır.
+        let ticks = (deadline_ns as u128 * TSC_FREQUENCY_HZ as u128)
+            .div_ceil(1_000_000_000)
+            .clamp(1, u64::MAX as u128) as u64;
+        asm!("wrmsr", in("ecx") MSR_TSC_DEADLINE, in("eax") ticks as u32, in("edx") (ticks >> 32) as u32, options(nostack));
+    }
+
+    unsafe fn wait_for_interrupt(&self) {
+        // hlt kesmeler kapalıyken uyanmaz; `sti` bir sonraki komuta kadar etkisiz olduğundan
+        // `sti; hlt` arasında kesme kaçmaz. Kesme burada işlenir, dönüşte kesmeler yine kapanır.
+        asm!("sti", "hlt", "cli", options(nomem, nostack));
+    }
+}
+
+/// Bu CPU'da çalışan iş parçacığının çekirdek yığın tepesi (TSS.rsp0).
+pub fn current_kernel_stack_top() -> u64 {
+    unsafe { addr_of!(TSS[ktask::current_cpu()].rsp[0]).read_unaligned() }
+}
+
+#[repr(C, packed)]
+struct DescriptorTablePointer {
+    limit: u16,
+    base: u64,
+}
+
+/// Paylaşılan GDT'yi ve mantıksal `cpu`'nun TSS'ini bu CPU'ya yükler. Kullanıcı kodu
+/// çalıştırmadan önce her CPU'da bir kez çağrılır: boot CPU'su `init`'te, AP'ler
+/// srcsmp_amd64.rs::amd64_ap_main'de.
+pub fn load_cpu_tables(cpu: usize) {
+    unsafe {
+        let gdt = &mut *addr_of_mut!(GDT);
+        // 64 bit kullanılabilir TSS tanımlayıcısı (tip 0x9, P = 1).
+        let base = addr_of!(TSS[cpu]) as u64;
+        let limit = size_of::<TaskStateSegment>() as u64 - 1;
+        gdt[GDT_TSS_FIRST + 2 * cpu] = (limit & 0xFFFF)
+            | (base & 0xFF_FFFF) << 16
+            | 0x89 << 40
+            | (limit >> 16 & 0xF) << 48
+            | (base >> 24 & 0xFF) << 56;
+        gdt[GDT_TSS_FIRST + 2 * cpu + 1] = base >> 32;
+
+        let gdtr = DescriptorTablePointer { limit: (size_of_val(gdt) - 1) as u16, base: gdt.as_ptr() as u64 };
+        asm!("lgdt [{0}]", in(reg) &gdtr, options(readonly, nostack));
+        // CS uzak dönüşle yeniden yüklenir; fs/gs'ye dokunulmaz (taban MSR'ları korunur).
+        asm!(
+            "push {cs}",
+            "lea {tmp}, [rip + 2f]",
+            "push {tmp}",
+            "retfq",
+            "2:",
+            "mov ds, {sel:x}",
+            "mov es, {sel:x}",
+            "mov ss, {sel:x}",
+            cs = const KERNEL_CODE_SELECTOR,
+            sel = in(reg) KERNEL_DATA_SELECTOR,
+            tmp = out(reg) _,
+        );
+        asm!("ltr {0:x}", in(reg) ((GDT_TSS_FIRST + 2 * cpu) * 8) as u64, options(nomem, nostack));
+    }
 }
 
-/// İş Parçacığı Bağlamını Değiştirme Fonksiyonu.
-/// Mevcut iş parçacığının bağlamını kaydeder ve belirtilen yeni iş parçacığının
-/// bağlamını yükler.
-///
-/// Bu fonksiyon genellikle saf Rust'ta yazılamaz ve assembly dilinde implemente edilir.
-/// Karnal64'ün `ktask` modülü bu fonksiyonu çağırarak görevler/iş parçacıkları arasında geçiş yapar.
-///
-/// # Argümanlar
-/// * `old_context`: Mevcut iş parçacığının bağlamının kaydedileceği `ThreadContext` yapısının pointer'ı.
-/// * `new_context`: Geçiş yapılacak yeni iş parçacığının yüklenecek bağlamının `ThreadContext` yapısının pointer'ı.
-///
-/// # Güvenli Olmayan (unsafe)
-/// Bu fonksiyon doğrudan bellek adresleri ve CPU yazmaçları ile etkileşime girdiği için
-/// güvenli değildir ve dikkatli kullanılmalıdır. Çağıranın geçerli pointer'lar sağlaması
-/// beklenir.
-///
-/// NOT: Bu sadece fonksiyon imzasıdır, gerçek implementasyon assembly'de olmalıdır.
-#[no_mangle] // Assembly kodundan çağrılabilmesi için isim düzenlemesi yapılmaz
-pub unsafe extern "C" fn x86_64_context_switch(
-    old_context: *mut ThreadContext,
-    new_context: *const ThreadContext,
-) {   
-    // Şimdilik sadece bir placeholder bırakıyoruz.
-    // Gerçek bir kernelde bu fonksiyonun içi assembly koduyla doldurulacaktır.
-     println!("Karnal64/x86: Bağlam Değiştirme Simülasyonu"); // Çekirdek içi print! gerektirir
+/// x86_64 mimari katmanını ktask'a kaydeder ve boot CPU'sunun (mantıksal 0) GDT/TSS'ini yükler.
+/// Platform başlatma kodu karnal64::init()'ten sonra çağırır.
+pub fn init() {
+    load_cpu_tables(0);
+    ktask::register_arch(&ARCH_TASK);
 }
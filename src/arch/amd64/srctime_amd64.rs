@@ -30,6 +30,10 @@ static SYSTEM_TIME: Mutex<SystemTimeState> = Mutex::new(SystemTimeState {
      last_time_ns: AtomicU64::new(0),
 });
 
+// SYSTEM_TIME.tsc_freq_hz'in kilitsiz kopyası: low_level_monotonic_time_ns zamanlayıcı kilidi
+// altında ve kesme bağlamında çağrılır, orada SYSTEM_TIME kilidi alınamaz.
+static TSC_FREQ_HZ: AtomicU64 = AtomicU64::new(0);
+
 
 // --- Time Source Initialization ---
 
@@ -45,6 +49,7 @@ pub fn init() {
     // Şimdilik 1GHz (1_000_000_000 Hz) varsayalım (yaygın bir değer ama doğru olmayabilir!)
     let assumed_tsc_freq_hz = 1_000_000_000; // placeholder - needs real calibration
     SYSTEM_TIME.lock().tsc_freq_hz.store(assumed_tsc_freq_hz, Ordering::SeqCst);
+    TSC_FREQ_HZ.store(assumed_tsc_freq_hz, Ordering::SeqCst);
 
 
     // TODO: ResourceProvider olarak bir timer cihazı kaydetmek istenirse burada yapılabilir.
@@ -100,6 +105,25 @@ pub fn get_monotonic_time_ns() -> u64 {
     }
 }
 
+/// Boot'tan beri geçen monoton süre (hardware_specific.h). ktask zamanlayıcısı ve CPU süresi
+/// muhasebesi bu kancayı kullanır. `get_monotonic_time_ns` ile aynı dönüşüm, ancak kilitsiz;
+/// TSC kalibre edilmeden önce 0 döner.
+#[no_mangle]
+pub extern "C" fn low_level_monotonic_time_ns() -> u64 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        let freq = TSC_FREQ_HZ.load(Ordering::Relaxed);
+        if freq == 0 {
+            return 0;
+        }
+        ((Tsc::read() as u128 * 1_000_000_000) / freq as u128) as u64
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        0
+    }
+}
+
 // --- İsteğe Bağlı: ResourceProvider Implementasyonu (Örn: Bir Timer Cihazı) ---
 // Eğer çekirdek, kullanıcı alanına "zaman" veya "timer" gibi bir kaynağı
 // handle üzerinden sunmak isterse bu trait implemente edilebilir.
@@ -45,6 +45,14 @@ pub struct DtbDeviceInfo {
     // DTB formatına göre buraya daha fazla alan eklenebilir (örn. çocuk düğümler, phandle'lar)
 }
 
+/// /cpus altındaki bir CPU düğümü ve /cpus/cpu-map'te ona (phandle ile) başvuran yaprağın konumu.
+#[derive(Debug, Copy, Clone)]
+pub struct DtbCpuInfo {
+    pub mpidr: u64, // "reg" özelliği: MPIDR_EL1 afinite alanları
+    pub capacity_dmips_mhz: Option<u32>, // big.LITTLE'da çekirdeğin göreli performansı
+    pub topology: Option<(u32, u32, u32, u32)>, // cpu-map'teki (socketN, clusterN, coreN, threadN); cpu-map yoksa None
+}
+
 // Varsayımsal DTB özellik değeri türü
  #[derive(Debug, Clone)]
  pub enum DtbProperty {
@@ -60,12 +68,51 @@ pub struct DtbDeviceInfo {
 // DTB blob'unu okuyup yukarıdaki yapılara dönüştürecek asıl ayrıştırma mantığı
 // Bu kısım genellikle karmaşıktır ve harici bir DTB ayrıştırma kütüphanesi (örn. `dtb-rs`)
 // kullanılabilir veya elle yazılmış FDT ayrıştırma kodu gerekebilir.
+// Ayrıştırma iki aşamalıdır: bellek haritası (/memory, /reserved-memory, /chosen initrd) çekirdek
+// yığını yokken, sabit boyutlu dizilere okunur; yığın çerçeve ayırıcının üzerinde durduğundan
+// cihazlar ve CPU'lar (Vec/String kullanır) ancak `init_frame_allocator`'dan sonra ayrıştırılır.
+
+/// Bellek haritasında tutulabilecek en fazla bölge (kmemory'nin bölge sınırıyla aynı).
+const MAX_DTB_REGIONS: usize = kmemory::MAX_MEMORY_REGIONS;
+
+/// Yığın gerektirmeyen, sabit kapasiteli bölge listesi.
+#[derive(Debug, Copy, Clone)]
+struct DtbRegionList {
+    entries: [DtbMemoryRegion; MAX_DTB_REGIONS],
+    len: usize,
+}
+
+impl DtbRegionList {
+    const fn new() -> Self {
+        DtbRegionList { entries: [DtbMemoryRegion { base_address: 0, size: 0 }; MAX_DTB_REGIONS], len: 0 }
+    }
+
+    /// Bölgeyi ekler; liste doluysa `KError::OutOfMemory`.
+    fn push(&mut self, region: DtbMemoryRegion) -> Result<(), KError> {
+        let slot = self.entries.get_mut(self.len).ok_or(KError::OutOfMemory)?;
+        *slot = region;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &DtbMemoryRegion> {
+        self.entries[..self.len].iter()
+    }
+}
 
-/// Bootloader tarafından sağlanan ham DTB pointer'ını alıp ayrıştırır.
-/// Başarı durumunda çıkarılan donanım bilgilerini döndürür.
+/// Çerçeve ayırıcıdan önce, yığın kullanmadan okunan bellek haritası.
+struct DtbMemoryMap {
+    memory_regions: DtbRegionList,
+    /// /reserved-memory düğümleri ve DTB'nin kendisi.
+    reserved_regions: DtbRegionList,
+    /// /chosen'daki initrd aralığı.
+    initrd: Option<DtbMemoryRegion>,
+}
+
+/// Ham DTB pointer'ını doğrular ve slice olarak döndürür.
 /// Güvenlik Notu: `dtb_ptr` ve `dtb_size` mutlaka güvenli bir şekilde doğrulanmalı,
 /// bellek haritasında geçerli ve okunabilir oldukları teyit edilmelidir.
-fn parse_dtb_from_ptr(dtb_ptr: *const u8, dtb_size: usize) -> Result<ParsedDtbInfo, KError> {
+fn dtb_bytes<'a>(dtb_ptr: *const u8, dtb_size: usize) -> Result<&'a [u8], KError> {
     // TODO: dtb_ptr ve dtb_size'ın geçerli ve güvenli kullanıcı alanı (veya bootloader)
     // belleğinde olduğunu doğrulayın. MMU'yu kullanarak bu bölgeyi çekirdek alanına
     // geçici olarak haritalamak veya fiziksel adres ise doğrudan kullanmak gerekebilir.
@@ -75,23 +122,49 @@ fn parse_dtb_from_ptr(dtb_ptr: *const u8, dtb_size: usize) -> Result<ParsedDtbIn
     }
 
     // UNSAFE: Ham pointer'dan slice oluşturuluyor. Güvenlik doğrulaması ÇOK ÖNEMLİ!
-    let dtb_slice = unsafe {
-        core::slice::from_raw_parts(dtb_ptr, dtb_size)
-    };
+    Ok(unsafe { core::slice::from_raw_parts(dtb_ptr, dtb_size) })
+}
+
+/// DTB'den bellek haritasını okur. Çekirdek yığını henüz yokken çağrılır; yığın kullanmaz.
+fn parse_dtb_memory_map(dtb_ptr: *const u8, dtb_size: usize) -> Result<DtbMemoryMap, KError> {
+    let dtb_slice = dtb_bytes(dtb_ptr, dtb_size)?;
+
+    // TODO: Burada asıl DTB ayrıştırma mantığı yer alacak: /memory düğümlerinin reg'leri,
+    // /reserved-memory alt düğümleri ve /chosen initrd özellikleri.
+
+    println!("DTB: Bellek haritası ayrıştırılıyor... (Yer Tutucu)"); // Çekirdek içi print!
+
+    // --- Yer Tutucu Ayrıştırma Sonuçları (Simülasyon) ---
+    let mut memory_regions = DtbRegionList::new();
+    memory_regions.push(DtbMemoryRegion { base_address: 0x40000000, size: 0x40000000 })?; // Örn: 1GB RAM
+    // Diğer bellek bölgeleri...
+
+    // Çerçeve ayırıcının dağıtmaması gereken aralıklar: DTB'nin kendisi (kimlik eşlemesi, bu
+    // yüzden pointer = fiziksel adres) ve /reserved-memory alt düğümlerinin reg'leri.
+    let mut reserved_regions = DtbRegionList::new();
+    reserved_regions.push(DtbMemoryRegion { base_address: dtb_ptr as u64, size: dtb_size as u64 })?;
+    // /reserved-memory/*: reg (örn. TF-A/OP-TEE bölgeleri, paylaşımlı bellek havuzları)...
+
+    // /chosen: linux,initrd-start / linux,initrd-end (bootloader initrd yüklediyse)
+    let initrd = None;
+    // --- Yer Tutucu Bitiş ---
+
+    Ok(DtbMemoryMap { memory_regions, reserved_regions, initrd })
+}
+
+/// DTB'den cihazları ve CPU'ları okur. Vec/String kullandığından çerçeve ayırıcı (ve üzerindeki
+/// çekirdek yığını) hazır olduktan sonra çağrılmalıdır.
+fn parse_dtb_from_ptr(dtb_ptr: *const u8, dtb_size: usize) -> Result<ParsedDtbInfo, KError> {
+    let dtb_slice = dtb_bytes(dtb_ptr, dtb_size)?;
 
     // TODO: Burada asıl DTB ayrıştırma mantığı yer alacak.
-    // dtb_slice'ı ayrıştırarak memory_regions ve device_infos yapılarını doldurun.
+    // dtb_slice'ı ayrıştırarak device_infos ve cpus yapılarını doldurun.
     // Örnek: dtb-rs kütüphanesi kullanılabilir veya elle FDT ayrıştırma kodu yazılır.
 
     println!("DTB: Ham veri ayrıştırılıyor... (Yer Tutucu)"); // Çekirdek içi print!
 
     // --- Yer Tutucu Ayrıştırma Sonuçları (Simülasyon) ---
     // Gerçek ayrıştırma yapılana kadar kullanılacak örnek veriler
-    let memory_regions = alloc::vec![
-        DtbMemoryRegion { base_address: 0x40000000, size: 0x40000000 }, // Örn: 1GB RAM
-        // Diğer bellek bölgeleri...
-    ];
-
     let device_infos = alloc::vec![
         DtbDeviceInfo {
             name: alloc::string::String::from("uart0"),
@@ -111,21 +184,38 @@ fn parse_dtb_from_ptr(dtb_ptr: *const u8, dtb_size: usize) -> Result<ParsedDtbIn
         },
         // Diğer cihazlar (GIC, diğer UART'lar, disk denetleyicileri vb.)
     ];
+
+    let cpus = alloc::vec![
+        // Örn: QEMU virt boot CPU'su; diğer CPU'lar /cpus/cpu@N düğümlerinden eklenir.
+        DtbCpuInfo { mpidr: 0x0, capacity_dmips_mhz: None, topology: Some((0, 0, 0, 0)) },
+    ];
+
+    // /psci: "method" özelliği ("hvc" QEMU virt, "smc" EL3 firmware'i olan donanım)
+    let psci_method = Some(alloc::string::String::from("hvc"));
+    // compatible = "arm,gic-v3" düğümü: reg'in ilk aralığı dağıtıcı, ikincisi yeniden dağıtıcılar
+    let gic_redistributors = Some(DtbMemoryRegion { base_address: 0x080A_0000, size: 0xF6_0000 });
     // --- Yer Tutucu Bitiş ---
 
     // Ayrıştırılan bilgileri içeren yapı
     Ok(ParsedDtbInfo {
-        memory_regions,
         device_infos,
+        cpus,
+        psci_method,
+        gic_redistributors,
         // Diğer DTB bilgileri (CPU sayısı, bootargs vb.)
     })
 }
 
-/// Ayrıştırılmış DTB'den çıkarılan donanım bilgilerini tutan ana yapı.
+/// Ayrıştırılmış DTB'den çıkarılan donanım bilgilerini tutan ana yapı (bellek haritası hariç,
+/// bkz. `DtbMemoryMap`).
 struct ParsedDtbInfo {
-    memory_regions: alloc::vec::Vec<DtbMemoryRegion>,
     device_infos: alloc::vec::Vec<DtbDeviceInfo>,
-    // TODO: CPU bilgileri, bootargs, vb.
+    cpus: alloc::vec::Vec<DtbCpuInfo>,
+    /// /psci "method" özelliği.
+    psci_method: Option<alloc::string::String>,
+    /// GICv3 düğümünün yeniden dağıtıcı bölgesi (reg'in ikinci aralığı).
+    gic_redistributors: Option<DtbMemoryRegion>,
+    // TODO: bootargs, vb.
 }
 
 
@@ -133,21 +223,29 @@ struct ParsedDtbInfo {
 // Bu fonksiyon, ayrıştırılmış DTB bilgilerini alarak Karnal64'ün
 // ilgili yöneticilerini (kmemory, kresource) başlatır ve yapılandırır.
 
-/// Ayrıştırılmış DTB bilgilerini kullanarak Karnal64'ün donanıma bağımlı
-/// bileşenlerini yapılandırır ve temel kaynakları kaydeder.
-/// Karnal64'ün ana `init()` fonksiyonu tarafından çağrılmalıdır.
-pub fn initialize_karnal64_from_dtb(dtb_info: ParsedDtbInfo) -> Result<(), KError> {
-    println!("DTB: Karnal64 bileşenleri yapılandırılıyor..."); // Çekirdek içi print!
-
-    // 1. Bellek Bölgelerini Kaydet
+/// DTB'den okunan bellek haritasını kmemory'ye bildirir ve çerçeve ayırıcıyı (dolayısıyla
+/// çekirdek yığınını) başlatır. Yığın gerektiren her şeyden önce çağrılmalıdır.
+fn initialize_memory_from_dtb(memory_map: &DtbMemoryMap) -> Result<(), KError> {
     // Bellek Yöneticisi'ne (kmemory) DTB'den okunan fiziksel bellek bölgelerini bildirir.
-    for region in dtb_info.memory_regions {
+    for region in memory_map.memory_regions.iter() {
         println!("DTB: Bellek bölgesi kaydı: 0x{:x} - 0x{:x} ({} MB)",
                  region.base_address, region.base_address + region.size, region.size / (1024*1024));
-
-        // TODO: kmemory modülünün API'sını kullanarak bu bölgeyi fiziksel ayırıcıya kaydet.
-         kmemory::register_physical_region(region.base_address, region.size, MemoryRegionKind::Ram)?;
+        kmemory::add_physical_memory_region(region.base_address, region.size)?;
     }
+    for region in memory_map.reserved_regions.iter().chain(memory_map.initrd.iter()) {
+        println!("DTB: Ayrılmış bölge: 0x{:x} - 0x{:x}", region.base_address, region.base_address + region.size);
+        kmemory::reserve_physical_region(region.base_address, region.size)?;
+    }
+    // Çekirdek imajı (bağlayıcı sembolleri) ayırıcı tarafından kendisi çıkarılır.
+    kmemory::init_frame_allocator()
+}
+
+/// Ayrıştırılmış DTB bilgilerini kullanarak Karnal64'ün donanıma bağımlı
+/// bileşenlerini yapılandırır ve temel kaynakları kaydeder. Bellek haritası
+/// `initialize_memory_from_dtb` ile önceden kaydedilmiş olmalıdır.
+/// Karnal64'ün ana `init()` fonksiyonu tarafından çağrılmalıdır.
+pub fn initialize_karnal64_from_dtb(dtb_info: ParsedDtbInfo) -> Result<(), KError> {
+    println!("DTB: Karnal64 bileşenleri yapılandırılıyor..."); // Çekirdek içi print!
 
     // 2. Cihazları Kaydet ve Sürücüleri Bağla
     // Kaynak Yöneticisi'ne (kresource) DTB'den okunan cihazları kaydeder.
@@ -199,7 +297,44 @@ pub fn initialize_karnal64_from_dtb(dtb_info: ParsedDtbInfo) -> Result<(), KErro
         }
     }
 
-    // TODO: Diğer DTB bilgileri (CPU sayısı, bootargslar) ile Task Yöneticisi (ktask) vb. yapılandırılabilir.
+    // 3. CPU'ları ve Topolojilerini Kaydet
+    // Görev Yöneticisi'ne (ktask) CPU'ları bildirir; srcsmp_armv9.rs bunları PSCI ile başlatır.
+    // Kapasiteler en büyük capacity-dmips-mhz değerine göre ktask::CPU_CAPACITY_MAX ölçeğine çevrilir.
+    let max_dmips = dtb_info.cpus.iter().filter_map(|c| c.capacity_dmips_mhz).max().unwrap_or(0);
+    for cpu in &dtb_info.cpus {
+        if ktask::add_cpu(cpu.mpidr).is_err() {
+            println!("DTB: MAX_CPUS aşıldı, MPIDR {:#x} ve sonrası kullanılmayacak", cpu.mpidr);
+            break;
+        }
+        // cpu-map yoksa afinite alanları kullanılır: Aff2 paket, Aff1 küme, Aff0 çekirdek.
+        let (package, cluster, core, thread) = cpu.topology.unwrap_or((
+            ((cpu.mpidr >> 16) & 0xFF) as u32,
+            ((cpu.mpidr >> 8) & 0xFF) as u32,
+            (cpu.mpidr & 0xFF) as u32,
+            0,
+        ));
+        let capacity = match cpu.capacity_dmips_mhz {
+            Some(dmips) if max_dmips > 0 => ((dmips as u64 * ktask::CPU_CAPACITY_MAX as u64) / max_dmips as u64).max(1) as u32,
+            _ => ktask::CPU_CAPACITY_MAX,
+        };
+        println!("DTB: CPU MPIDR {:#x} → paket {} küme {} çekirdek {} iş parçacığı {} (kapasite {})",
+                 cpu.mpidr, package, cluster, core, thread, capacity);
+        ktask::set_cpu_topology(ktask::CpuTopology { hw_id: cpu.mpidr, package, cluster, core, thread, capacity, online: 0 })?;
+    }
+
+    // 4. PSCI Çağrı Yöntemi ve GIC Yeniden Dağıtıcıları
+    // srcsmp_armv9.rs ikincil CPU'ları bu yöntemle başlatır ve her CPU'nun yeniden dağıtıcısını
+    // bu bölgede arar; DTB'de yoksa QEMU virt varsayılanları kalır.
+    if let Some(method) = &dtb_info.psci_method {
+        if crate::smp::set_psci_method(method).is_err() {
+            println!("DTB: Bilinmeyen PSCI yöntemi \"{}\", HVC kullanılacak", method);
+        }
+    }
+    if let Some(gicr) = dtb_info.gic_redistributors {
+        crate::smp::set_gic_redistributor_base(gicr.base_address);
+    }
+
+    // TODO: Diğer DTB bilgileri (bootargslar) ile ilgili yöneticiler yapılandırılabilir.
 
     println!("DTB: Karnal64 donanım yapılandırması tamamlandı.");
 
@@ -232,7 +367,15 @@ pub extern "C" fn initialize_dtb_and_karnal64(dtb_physical_address: u64, dtb_max
 
     println!("DTB: Başlatılıyor. Ham DTB adresi: 0x{:x}", dtb_physical_address);
 
-    // Ham DTB'yi ayrıştır
+    // 1. Bellek haritası: yığın yokken sabit dizilere okunur, ardından çerçeve ayırıcı kurulur.
+    let memory_result = parse_dtb_memory_map(dtb_ptr, dtb_max_size)
+        .and_then(|memory_map| initialize_memory_from_dtb(&memory_map));
+    if let Err(err) = memory_result {
+        eprintln!("DTB: Bellek haritası hatası: {:?}", err);
+        return err as i64;
+    }
+
+    // Ham DTB'nin geri kalanını ayrıştır (artık çekirdek yığını kullanılabilir)
     let parse_result = parse_dtb_from_ptr(dtb_ptr, dtb_max_size);
 
     match parse_result {
@@ -9,6 +9,7 @@
 // Karnal64 API'nıza erişim için (karnal64.rs dosyanızdaki tipleri/fonksiyonları kullanacak)
 // Bu, karnal64.rs'nin bu modül tarafından erişilebilir olması gerektiğini varsayar.
 use crate::karnal64::{handle_syscall, KError}; // handle_syscall'ı ve KError'u import edin
+use core::sync::atomic::{AtomicU64, Ordering};
 
 // --- İstisna Bağlamını Saklamak İçin Yapı (Trap Frame) ---
 // İstisna/sistem çağrısı gerçekleştiğinde CPU'nun durumunu (register'ları)
@@ -330,12 +331,32 @@ extern "C" fn handle_sync_curr_el_spx_rust(frame: &mut TrapFrame) {
     }
 }
 
+/// Sürücüsü olmayan (veya sahte) kesme sayısı.
+static UNHANDLED_IRQS: AtomicU64 = AtomicU64::new(0);
+
+/// Şimdiye kadar işlenmeden onaylanan kesme sayısı (hata ayıklama için).
+pub fn unhandled_irq_count() -> u64 {
+    UNHANDLED_IRQS.load(Ordering::Relaxed)
+}
+
 #[no_mangle]
 extern "C" fn handle_irq_curr_el_spx_rust(frame: &mut TrapFrame) {
-    // TODO: Çekirdek içinde oluşan kesmeleri işle (zamanlayıcı kesmesi vb.)
-     // Genellikle GIC (Generic Interrupt Controller) ile etkileşime girilir.
-    println!("Çekirdek İçi IRQ alındı! (Yer Tutucu)");
-    // TODO: GIC'ten kesme ID'sini oku, ilgili işleyiciye yönlendir, kesmeyi onayla.
+    // Çekirdek içinde oluşan kesmeler (zamanlayıcı, IPI vb.)
+    // GIC'ten kesme numarasını al; IPI'ler (SGI) zamanlayıcıya gider.
+    crate::karnal64::ksync::lockdep::irq_enter();
+    let intid = crate::smp::acknowledge_interrupt();
+    if intid == crate::smp::IPI_SGI {
+        crate::karnal64::ktask::handle_ipi();
+    } else if intid == crate::task::TIMER_INTID {
+        crate::task::handle_timer_interrupt();
+    } else {
+        // TODO: Diğer kesme numaralarını ilgili aygıt sürücüsüne yönlendir. Kesme bağlamında
+        // yazdırılmaz; yalnızca sayılır (bkz. `unhandled_irq_count`).
+        UNHANDLED_IRQS.fetch_add(1, Ordering::Relaxed);
+    }
+    crate::smp::end_of_interrupt(intid);
+    crate::karnal64::ksync::lockdep::irq_exit();
+    crate::karnal64::ktask::preempt_on_interrupt_exit();
 }
 
 #[no_mangle]
@@ -407,14 +428,22 @@ extern "C" fn handle_sync_lower_el_aarch64_rust(frame: &mut TrapFrame) {
 /// --- Kullanıcı Alanından Gelen Kesmeleri İşleyici (Donanım Kesmeleri Buradan Geçer) ---
 #[no_mangle]
 extern "C" fn handle_irq_lower_el_aarch64_rust(frame: &mut TrapFrame) {
-    // Donanım kesmesi!
-    // Genellikle GIC (Generic Interrupt Controller) ile etkileşime girilir.
-    println!("IRQ alındı! (Yer Tutucu)");
-
-    // TODO:
-    // 1. GIC'ten hangi kesmenin geldiğini öğren (örneğin, GIC CPU arayüzünden).
-    // 2. Kesmenin ID'sine göre ilgili aygıt sürücüsünün işleyicisini çağır.
-    // 3. GIC'e kesmenin işlendiğini bildir (acknowledge).
+    // Donanım kesmesi (kullanıcı alanı kesintiye uğradı).
+    // GIC'ten kesme numarasını al; IPI'ler (SGI) zamanlayıcıya gider.
+    crate::karnal64::ksync::lockdep::irq_enter();
+    let intid = crate::smp::acknowledge_interrupt();
+    if intid == crate::smp::IPI_SGI {
+        crate::karnal64::ktask::handle_ipi();
+    } else if intid == crate::task::TIMER_INTID {
+        crate::task::handle_timer_interrupt();
+    } else {
+        // TODO: Diğer kesme numaralarını ilgili aygıt sürücüsüne yönlendir. Kesme bağlamında
+        // yazdırılmaz; yalnızca sayılır (bkz. `unhandled_irq_count`).
+        UNHANDLED_IRQS.fetch_add(1, Ordering::Relaxed);
+    }
+    crate::smp::end_of_interrupt(intid);
+    crate::karnal64::ksync::lockdep::irq_exit();
+    crate::karnal64::ktask::preempt_on_interrupt_exit();
 }
 
 #[no_mangle]
@@ -488,3 +517,72 @@ macro_rules! println {
 
 // TODO: GIC (Generic Interrupt Controller) etkileşimi için kodlar (IRQ işleyicisi için).
 // Kesmeleri kaydetme, etkinleştirme, devre dışı bırakma, onayla vb. fonksiyonlar.
+
+// --- Karnal64 Kesme Maskesi Kancaları (hardware_specific.h) ---
+// ksync::Spinlock::lock_irqsave ve çekirdek genelindeki kesme aç/kapa işlemleri bunları kullanır.
+// Kaydedilen durum DAIF register'ının kendisidir; yalnızca I (IRQ) biti değiştirilir.
+
+const DAIF_I: u64 = 1 << 7;
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_enable() {
+    unsafe { core::arch::asm!("msr daifclr, #2", options(nomem, nostack)) };
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_disable() {
+    unsafe { core::arch::asm!("msr daifset, #2", options(nomem, nostack)) };
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupts_enabled() -> i32 {
+    let daif: u64;
+    unsafe { core::arch::asm!("mrs {0}, daif", out(reg) daif, options(nomem, nostack)) };
+    (daif & DAIF_I == 0) as i32
+}
+
+/// IRQ'ları maskeler ve önceki DAIF değerini döndürür.
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_save() -> u64 {
+    let daif: u64;
+    unsafe {
+        core::arch::asm!(
+            "mrs {0}, daif",
+            "msr daifset, #2",
+            out(reg) daif,
+            options(nomem, nostack)
+        )
+    };
+    daif
+}
+
+/// `low_level_interrupt_save` ile kaydedilen DAIF değerini geri yükler.
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_restore(daif: u64) {
+    unsafe { core::arch::asm!("msr daif, {0}", in(reg) daif, options(nomem, nostack)) };
+}
+
+// --- Karnal64 Yığın İzi Kancası (hardware_specific.h) ---
+// lockdep raporlarında kullanılır; AAPCS64 çerçeve kaydı zinciri (x29) yürünür.
+
+/// Çerçeve kaydı: [x29] önceki x29, [x29 + 8] kaydedilen LR.
+/// Zincir sıfıra, hizasız bir adrese veya yığında aşağı doğru bir çerçeveye ulaşınca durur.
+#[no_mangle]
+pub extern "C" fn low_level_stack_trace(frames: *mut u64, max_frames: usize) -> usize {
+    let mut fp: u64;
+    unsafe { core::arch::asm!("mov {0}, x29", out(reg) fp, options(nomem, nostack)) };
+    let mut count = 0;
+    while count < max_frames && fp != 0 && fp % 16 == 0 {
+        let (prev, lr) = unsafe { (*(fp as *const u64), *((fp + 8) as *const u64)) };
+        if lr == 0 {
+            break;
+        }
+        unsafe { *frames.add(count) = lr };
+        count += 1;
+        if prev <= fp {
+            break;
+        }
+        fp = prev;
+    }
+    count
+}
@@ -14,6 +14,8 @@ use core::arch::asm;
 use alloc::vec::Vec;
 use alloc::boxed::Box; // SharedMemObject gibi yapıları heap'te tutmak için
 use core::sync::atomic::{AtomicU64, Ordering}; // Basit sayaçlar veya handle üretimi için
+use crate::karnal64::kmemory::{register_arch_mmu, ArchMmu, PROT_EXEC, PROT_NONE, PROT_WRITE};
+use crate::karnal64::KError as KarnalError;
 
 // Karnal64 core tiplerini burada yeniden tanımlıyoruz veya erişilebilir varsayıyoruz.
 // Gerçek projede 'use crate::karnal64::...' şeklinde dahil edilmelidir.
@@ -53,47 +55,27 @@ pub struct KHandle(u64);
 
 // --- Fiziksel Çerçeve Ayırıcı (Physical Frame Allocator - PFA) ---
 // Bellek yöneticisinin fiziksel belleği tahsis etmek için kullandığı katman.
-// Bu modül sadece bir yer tutucudur. Gerçek bir çekirdekte çok daha karmaşıktır.
+// Çerçeveler kmemory'nin buddy ayırıcısından alınır (bkz. src/memory/srcframe.rs); ayırıcı DTB
+// /memory ve /reserved-memory düğümleriyle srcdtb_armv9.rs'de başlatılır. Bu modül MMU kodunun
+// kullandığı pointer tabanlı arayüzü korur; fiziksel bellek kimlik eşlemeli olduğundan fiziksel
+// adres doğrudan pointer olarak kullanılır.
 mod pfa {
-    use super::*;
-    use alloc::vec::Vec;
-
-    // Çok basit bir 'bump' ayırıcı (fiziksel belleği sırayla dağıtır, serbest bırakmayı desteklemez)
-    // Gerçek bir PFA boş çerçevelerin listesini tutar ve eşzamanlı erişimi yönetir.
-    static mut HEAP_START: usize = 0;
-    static mut HEAP_END: usize = 0;
-    static mut NEXT_FREE: usize = 0;
-
-    // Bootloader veya erken boot kodu tarafından çağrılmalıdır.
-    pub fn init(heap_start: usize, heap_size: usize) {
-        unsafe {
-            HEAP_START = heap_start;
-            HEAP_END = heap_start + heap_size;
-            NEXT_FREE = heap_start;
-        }
-         println!("PFA initialized: {:x} to {:x}", heap_start, HEAP_END); // Çekirdek içi print! gerekli
-    }
+    use crate::karnal64::kmemory::{frame_allocator, FrameAllocator};
 
     // 4KB'lik fiziksel bir çerçeve (sayfa) tahsis eder.
     pub fn allocate_frame() -> Option<*mut u8> {
-        unsafe {
-            let frame_addr = NEXT_FREE;
-            if frame_addr + super::PAGE_SIZE <= HEAP_END {
-                 NEXT_FREE += super::PAGE_SIZE;
-                 // println!("PFA: Allocated frame {:x}", frame_addr);
-                 Some(frame_addr as *mut u8)
-            } else {
+        match frame_allocator().allocate_frame() {
+            Ok(paddr) => Some(paddr as usize as *mut u8),
+            Err(_) => {
                  println!("PFA: Out of memory");
                 None // Fiziksel bellek tükendi
             }
         }
     }
 
-    // Fiziksel bir çerçeveyi serbest bırakır.
-    // Bu 'bump' ayırıcıda bu fonksiyon aslında hiçbir şey yapmaz.
+    // Fiziksel bir çerçeveyi buddy ayırıcıya geri verir.
     pub fn free_frame(frame_addr: *mut u8) {
-         println!("PFA: Frame {:p} conceptually freed", frame_addr);
-        // Gerçek bir PFA'da frame_addr boş listeye eklenir.
+        unsafe { frame_allocator().free_frame(frame_addr as usize as u64) }
     }
 }
 
@@ -122,6 +104,9 @@ const L3_INDEX_SHIFT: usize = PAGE_SIZE_BITS;                              // 12
 
 const INDEX_MASK: usize = PAGE_TABLE_ENTRIES - 1; // 511 (0b111111111)
 
+// Girdideki çıkış adresi (bit [47:12]); üst öznitelikler (PXN, UXN) ve alt bayraklar hariç.
+const PHYS_ADDR_MASK: u64 = 0x0000_FFFF_FFFF_F000;
+
 // Bir sayfa tablosu seviyesini temsil eden yapı (sadece 64-bit girdilerin dizisi)
 #[repr(align(4096))] // Sayfa tabloları 4KB'ye hizalanmalıdır (ARMv8-A kısıtlaması)
 #[derive(Copy, Clone)] // Kopyalanabilir olması init gibi durumlar için pratik
@@ -195,138 +180,9 @@ mod pte_flags {
 }
 
 
-// --- Global MMU Durumu ve Yardımcı Yapılar ---
-
-// Kullanıcı alanı için Sanal Adres (VA) Ayırıcı (her görev için ayrı olmalıdır gerçek çekirdekte)
-// Bu sadece bir yer tutucudur, gerçek bir ayırıcı boş aralıkları takip eder.
-struct VmspaceAllocator {
-    next_vaddr: usize, // Bir sonraki tahsisin başlayabileceği yer (basit bump için)
-    end_vaddr: usize, // VA alanının sonu (hariç)
-     start_vaddr_range: usize, // VA aralığının başlangıcı
-}
-
-impl VmspaceAllocator {
-    // Belirli bir VA aralığı için yeni bir ayırıcı oluşturur.
-    const fn new_with_range(start: usize, end: usize) -> Self {
-        VmspaceAllocator {
-            next_vaddr: start,
-            end_vaddr: end,
-            start_vaddr_range: start,
-        }
-    }
-
-     // Kullanıcı VA alanının başlangıcı ve sonu (örnek değerler)
-     const USER_VA_START: usize = 0x1000_0000; // Kullanıcı alanı 256MB'den başlar
-     const USER_VA_END: usize   = 0x8000_0000; // Kullanıcı alanı 2GB'de biter (örnek)
-
-
-    // Belirtilen sayfaları (`num_pages`) haritalamak için bitişik bir sanal adres bloğu bulur ve tahsis eder.
-    // Bu, çok basitleştirilmiş bir doğrusal aramadır ve parçalanmayı iyi yönetmez.
-    // Gerçek bir ayırıcı boş liste veya benzeri teknikler kullanır.
-     // Sayfa tablosu (`l1_table_ptr`) kullanılır çünkü VA'nın zaten haritalı olup olmadığını kontrol etmemiz gerekir.
-    fn allocate_pages(&mut self, num_pages: usize, l1_table_ptr: *mut PageTable) -> Result<usize, KError> {
-        if num_pages == 0 { return Ok(0); }
-        let size = num_pages * PAGE_SIZE;
-
-        // Basit bir 'bump' tahsis denemesi (son tahsisin bitişinden başla)
-        let mut current_attempt = self.next_vaddr;
-        if current_attempt + size > self.end_vaddr {
-             // Aralığın sonuna geldiysek, başa dön (parçalanmış alanları kontrol etmek için)
-             current_attempt = self.start_vaddr_range;
-        }
-
-        // Tam VA aralığında arama yap
-        while current_attempt + size <= self.end_vaddr {
-             // Bu aralığın haritalı olup olmadığını kontrol et (çok verimsiz - sayfa tablosunu yürür)
-             let mut conflict = false;
-             for i in 0..num_pages {
-                 let vaddr = current_attempt + i * PAGE_SIZE;
-                 // `lookup_page` kullanarak adresin haritalı olup olmadığını kontrol et.
-                 // Hata yoksa, adres haritalıdır (çünkü `lookup_page` sadece geçerli L3 girdilerini döndürür).
-                 if lookup_page(l1_table_ptr, vaddr).is_ok() {
-                     conflict = true;
-                     // Çakışma varsa, aramayı çakışan sayfanın bir sonraki sayfasından devam ettir.
-                     current_attempt = vaddr + PAGE_SIZE;
-                     break; // İç döngüyü kır, arama döngüsünü devam ettir.
-                 }
-             }
-
-             if !conflict {
-                 // Bitişik ve boş bir blok bulundu
-                 self.next_vaddr = current_attempt + size; // Bir sonraki tahsis noktasını güncelle
-                 return Ok(current_attempt); // Başlangıç sanal adresini döndür
-             }
-
-             // Eğer çakışma olduysa, current_attempt zaten bir sonraki arama noktasına güncellendi.
-             // İç döngü kırılmadıysa (yani çakışma yoksa), döngü zaten ilerliyor demektir.
-             if !conflict {
-                  current_attempt += PAGE_SIZE; // Çakışma yoksa sayfalar halinde ilerle
-             }
-        }
-
-
-        Err(KError::OutOfMemory) // Yeterince büyük bitişik boş blok bulunamadı
-    }
-
-}
-
-
-// Gerçek bir çekirdekte, her Görev Kontrol Bloğunun (TCB) kendi kullanıcı sayfa tablosu işaretçisi ve VA ayırıcısı vardır.
-// Bu basitleştirilmiş örnek için, tek bir global kullanıcı sayfa tablosu ve ayırıcı kullanacağız.
-// BU ÇOKLU GÖREV ORTAMLARI İÇİN UYGUN DEĞİLDİR ve Senkronizasyon Gerekir!
-static mut SINGLE_USER_PAGE_TABLE: Option<*mut PageTable> = None;
-static mut SINGLE_USER_VA_ALLOCATOR: VmspaceAllocator = VmspaceAllocator::new_with_range(VmspaceAllocator::USER_VA_START, VmspaceAllocator::USER_VA_END);
-
-// ktask modülünün bir kısmıymış gibi davranan yer tutucu.
-mod ktask {
-    use super::*;
-    // Mevcut görevin kullanıcı sayfa tablosu işaretçisini döndüren yer tutucu fonksiyon.
-    // Gerçek bir çekirdekte bu, mevcut TCB'den okunur.
-    pub fn get_current_user_page_table() -> Option<*mut PageTable> {
-        unsafe {
-            if SINGLE_USER_PAGE_TABLE.is_none() {
-                // Eğer sayfa tablosu henüz tahsis edilmemişse, şimdi et.
-                 if let Some(frame) = pfa::allocate_frame() {
-                     let pt_ptr = frame as *mut PageTable;
-                     // volatile yazma kullanarak derleyicinin yazmayı optimize etmediğinden emin ol.
-                     ptr::write_volatile(pt_ptr, PageTable::new()); // Sıfırlarla başlat
-                     SINGLE_USER_PAGE_TABLE = Some(pt_ptr);
-                      println!("Allocated initial user page table at {:p}", pt_ptr);
-                 } else {
-                      println!("Failed to allocate initial user page table!");
-                     return None; // Sayfa tablosu olmadan devam edilemez
-                 }
-            }
-            SINGLE_USER_PAGE_TABLE
-        }
-    }
-
-     // Mevcut görevin VA ayırıcısını döndüren yer tutucu fonksiyon.
-     pub fn get_current_user_va_allocator() -> Option<&'static mut VmspaceAllocator> {
-         unsafe {
-              // Gerçek çekirdekte bu, TCB'den allocator referansını alır.
-             Some(&mut SINGLE_USER_VA_ALLOCATOR)
-         }
-     }
-
-    // Mevcut görev ID'sini döndüren yer tutucu.
-    pub fn get_current_task_id() -> KTaskId {
-        // Çok basit bir dummy ID döndür.
-        KTaskId(unsafe { SINGLE_USER_PAGE_TABLE.is_some() as u64 }) // Sayfa tablosu varsa ID 1, yoksa 0
-    }
-
-     // Başka bir görevin sayfa tablosuna geçiş yapan yer tutucu.
-     // Zamanlayıcı bağlam değiştirirken çağırır.
-     pub fn switch_to_task_page_table(l1_table_phys_addr: u64) {
-         unsafe {
-             // ARM sistem registerına (TTBR0_EL1) yeni L1 sayfa tablosunun fiziksel adresini yaz.
-             // Bu, CPU'nun bundan sonra bu sayfa tablosunu kullanmasını sağlar.
-              println!("Switching TTBR0_EL1 to {:x}", l1_table_phys_addr);
-              asm!("msr ttbr0_el1, {}", in(reg) l1_table_phys_addr);
-              asm!("isb"); // Talimat Senkronizasyon Bariyeri
-         }
-     }
-}
+// Görev başına kullanıcı sayfa tabloları ve sanal adres aralıkları kmemory'nin görev adres alanı
+// yöneticisi tarafından tutulur (bkz. dosya sonundaki kmemory entegrasyonu). Etkin görevin tablosunu
+// TTBR0_EL1'e ktask yükler (srctask_armv9.rs, activate_address_space).
 
 
 // --- Sayfa Tablosu Yönetimi için Yardımcı Fonksiyonlar ---
@@ -512,7 +368,7 @@ fn unmap_page(l1_table_ptr: *mut PageTable, virt_addr: usize) -> Result<*mut u8,
     }
 
     // Girdiyi temizlemeden önce fiziksel adresi al
-    let phys_frame_addr = (l3_entry & !((PAGE_SIZE as u64) - 1)) as *mut u8;
+    let phys_frame_addr = (l3_entry & PHYS_ADDR_MASK) as *mut u8;
 
     // L3 girdisini temizleyerek haritalamayı kaldır (INVALID olarak işaretle)
     unsafe { ptr::write_volatile(l3_entry_ptr, 0); }
@@ -536,11 +392,9 @@ fn unmap_page(l1_table_ptr: *mut PageTable, virt_addr: usize) -> Result<*mut u8,
 
 // Bu `init_manager` fonksiyonu `karnal64::init()` tarafından çağrılacaktır.
 // Çekirdek boot sürecinde MMU donanımını ve bellek yöneticisinin yapılarını başlatır.
-// physical_memory_start ve size: PFA'nın kullanabileceği fiziksel bellek aralığı.
-pub fn init_manager(physical_memory_start: usize, physical_memory_size: usize) {
-    // Fiziksel çerçeve ayırıcıyı başlat (yer tutucu)
-    pfa::init(physical_memory_start, physical_memory_size);
-
+// Fiziksel çerçeve ayırıcı bundan önce DTB bellek düğümleriyle başlatılmış olmalıdır
+// (srcdtb_armv9.rs, `kmemory::init_frame_allocator`).
+pub fn init_manager() {
     // TODO: ARM MMU donanımını başlat (TTBR0_EL1, TTBR1_EL1, TCR_EL1, MAIR_EL1, vb.)
     // Bu, sistem registerlarına yazmayı gerektirir. Mimariye özgü assembly veya crate gerekli.
 
@@ -571,16 +425,8 @@ pub fn init_manager(physical_memory_start: usize, physical_memory_size: usize) {
          asm!("msr tcr_el1, {}", in(reg) TCR_EL1, options(nostack, preserves_flags));
 
 
-        // TTBR0_EL1'i ilk kullanıcı alanı sayfa tablosu için ayarla.
-        // İlk kullanıcı sayfa tablosunu şimdi tahsis et.
-        let initial_user_pt_ptr = ktask::get_current_user_page_table().expect("Failed to allocate initial user page table during MMU init");
-        // Sayfa tablosunun fiziksel adresi (çekirdek alanında identity mapping varsayımıyla)
-        // Gerçek bir çekirdekte, çekirdek VA'sının fiziksel PA'ya çevrilmesi gerekir.
-        let initial_user_pt_phys_addr = initial_user_pt_ptr as u64;
-
-        // Kullanıcı L1 tablosunun fiziksel adresini TTBR0_EL1'e yaz.
-         asm!("msr ttbr0_el1, {}", in(reg) initial_user_pt_phys_addr, options(nostack, preserves_flags));
-         println!("Initialized TTBR0_EL1 with user page table at {:x}", initial_user_pt_phys_addr);
+        // TTBR0_EL1'e burada bir tablo yüklenmez: görev adres alanları kmemory::create_address_space
+        // ile kurulur ve ktask görev değiştirirken yükler.
 
 
         // MMU'yu etkinleştir (SCTLR_EL1.M bitini set et)
@@ -597,367 +443,128 @@ pub fn init_manager(physical_memory_start: usize, physical_memory_size: usize) {
 }
 
 
-// `kmemory::allocate_user_memory` fonksiyonunu implemente eder.
-// Kullanıcı alanı sanal bellekte `size` byte yer tahsis eder ve karşılık gelen fiziksel çerçeveleri ayırır.
-// Tahsis edilen sanal adresin başlangıcını döner.
-pub fn allocate_user_memory(size: usize) -> Result<*mut u8, KError> {
-    if size == 0 { return Ok(ptr::null_mut()); }
-
-    let num_pages = (size + PAGE_SIZE - 1) / PAGE_SIZE; // Kaç sayfa gerektiğini hesapla
-    let user_l1_table_ptr = ktask::get_current_user_page_table().ok_or(KError::InternalError)?; // Mevcut görevin sayfa tablosunu al
-    let user_va_allocator = ktask::get_current_user_va_allocator().ok_or(KError::InternalError)?; // Mevcut görevin VA ayırıcısını al
+// --- kmemory Entegrasyonu ---
+// Görev adres alanları (VMA'lar, boş aralık arama, paylaşımlı bellek nesneleri, çerçevelerin
+// sahipliği) mimariden bağımsız kmemory modülündedir (bkz. src/memory/srcvmspace.rs). Bu port
+// kmemory'ye yalnızca TTBR0_EL1 sayfa tablosu işlemlerini `kmemory::ArchMmu` üzerinden sağlar.
+// Yukarıdaki yardımcılar yalnızca bu CPU'nun TLB'sini temizler; diğer CPU'lar ktask::tlb_shootdown
+// ile temizlenir.
+
+/// TTBR0_EL1 ile çevrilebilen kullanıcı VA aralığının sonu (T0SZ = 25, 39 bit).
+const USER_VA_LIMIT: u64 = 1 << 39;
+
+/// PROT_* korumasını L3 sayfa girdisi bayraklarına çevirir. PROT_NONE sayfa yalnızca EL1'den
+/// erişilebilir kalır; kullanıcı sayfaları EL1'de hiçbir zaman yürütülmez.
+fn prot_to_flags(prot: u32) -> u64 {
+    use pte_flags::*;
+    let ap = if prot == PROT_NONE {
+        AP_EL1_RW_EL0_NO
+    } else if prot & PROT_WRITE != 0 {
+        AP_EL1_RW_EL0_RW
+    } else {
+        AP_EL1_RO_EL0_RO
+    };
+    let xn = if prot & PROT_EXEC != 0 { PXN } else { PXN | UXN };
+    VALID | PAGE | ATTR_INDEX_1_CACHED | SH_INNER | AF | ap | xn
+}
 
-    // 1. `num_pages` kadar bitişik boş sanal adres alanı bul.
-    let start_vaddr = user_va_allocator.allocate_pages(num_pages, user_l1_table_ptr)?;
-     println!("Allocated VA range: {:x} - {:x}", start_vaddr, start_vaddr + size);
+/// Bu dosyanın hata kodlarını Karnal64 hata kodlarına çevirir.
+fn to_karnal_error(err: KError) -> KarnalError {
+    match err {
+        KError::OutOfMemory | KError::PageTableFull => KarnalError::OutOfMemory,
+        KError::AlreadyExists => KarnalError::AlreadyExists,
+        KError::BadAddress => KarnalError::BadAddress,
+        KError::InvalidArgument | KError::AddressNotPageAligned => KarnalError::InvalidArgument,
+        _ => KarnalError::InternalError,
+    }
+}
 
-    let mut allocated_phys_frames: Vec<*mut u8> = Vec::new(); // Ayrılan fiziksel çerçeveleri tutacak vektör
+struct Armv9Mmu;
 
-    // 2. Sanal adres aralığı için fiziksel çerçeveleri tahsis et.
-    for _ in 0..num_pages {
-        let phys_frame = pfa::allocate_frame().ok_or_else(|| {
-            // Eğer tahsis ortasında başarısız olursa, daha önce tahsis edilen çerçeveleri temizle.
-            for frame in allocated_phys_frames.drain(..) { // drain() kullanarak vektörü tüketirken yinele
-                pfa::free_frame(frame);
-            }
-            KError::OutOfMemory // Bellek yetersiz hatası dön
-        })?;
-        allocated_phys_frames.push(phys_frame); // Başarılı olursa listeye ekle
+impl ArchMmu for Armv9Mmu {
+    unsafe fn create_page_tables(&self) -> Result<u64, KarnalError> {
+        // Çekirdek eşlemeleri TTBR1_EL1'de olduğundan yeni L1 tablosu boş başlar.
+        let frame = pfa::allocate_frame().ok_or(KarnalError::OutOfMemory)?;
+        ptr::write_volatile(frame as *mut PageTable, PageTable::new());
+        Ok(frame as u64)
     }
-     println!("Allocated {} physical frames", allocated_phys_frames.len());
-
-
-    // 3. Tahsis edilen fiziksel çerçeveleri bulunan sanal adres aralığına haritala.
-    for (i, phys_frame) in allocated_phys_frames.into_iter().enumerate() {
-        let virt_addr = start_vaddr + i * PAGE_SIZE; // Haritalanacak sanal adres
-        // Kullanıcı R/W izinleri, Önbelleklenebilir Normal Bellek öznitelikleri kullan.
-        let flags = pte_flags::USER_DATA_FLAGS;
-
-        // Sayfayı haritala.
-        if map_page(user_l1_table_ptr, virt_addr, phys_frame, flags).is_err() {
-            // Haritalama başarısız oldu - Bu, VA bulma doğruysa ve haritalama mantığı sağlamsa olmamalı.
-            // Ciddi temizlik gerektirir: daha önce haritalanmış sayfaları kaldır, tahsis edilen tüm fiziksel çerçeveleri serbest bırak.
-            // Bu hata işleme karmaşıktır. Basitlik için sadece hata dönüyoruz.
-             // Gerçek bir çekirdekte, hata durumunda daha önce başarıyla haritalanmış sayfaları kaldırmaya çalışılır.
-             let mut cleanup_vaddr = start_vaddr;
-             for j in 0..i { // Hatadan önce başarıyla haritalanmış sayfaları kaldır
-                 if unmap_page(user_l1_table_ptr, cleanup_vaddr).is_ok() {
-                      // Temizlik sırasında başarıyla kaldırıldı
-                 } else {
-                     // Temizlik sırasında hata! Bu kötü. Logla.
-                       println!("Cleanup error: Failed to unmap VA {:x} during allocate_user_memory error", cleanup_vaddr);
-                 }
-                 cleanup_vaddr += PAGE_SIZE;
-             }
-              // Kalan tahsis edilmiş fiziksel çerçeveleri serbest bırak.
-              for frame in allocated_phys_frames.drain(..) { // Vektör artık boş olabilir, ama drain güvenlidir.
-                  pfa::free_frame(frame);
-              }
-               println!("allocate_user_memory failed at VA {:x}", virt_addr);
-             return Err(KError::InternalError); // Ya da daha spesifik bir hata
+
+    unsafe fn map_page(&self, root: u64, vaddr: u64, paddr: u64, prot: u32) -> Result<(), KarnalError> {
+        if vaddr >= USER_VA_LIMIT {
+            return Err(KarnalError::BadAddress);
         }
-         println!("Mapped VA {:x} to PA {:p}", virt_addr, phys_frame);
+        map_page(root as *mut PageTable, vaddr as usize, paddr as usize as *mut u8, prot_to_flags(prot))
+            .map_err(to_karnal_error)
     }
 
-    Ok(start_vaddr as *mut u8) // Tahsis edilen sanal adresin başlangıcını döndür
-}
-
-
-// `kmemory::free_user_memory` fonksiyonunu implemente eder.
-// Kullanıcı alanı sanal bellek aralığındaki haritalamayı kaldırır ve karşılık gelen fiziksel çerçeveleri serbest bırakır.
-// Belirtilen işaretçinin geçerli, tahsis edilmiş bir kullanıcı alanı işaretçisi olması gerekir.
-pub fn free_user_memory(ptr: *mut u8, size: usize) -> Result<(), KError> {
-    if ptr.is_null() || size == 0 { return Ok(()); }
-
-    let start_vaddr = ptr as usize;
-    // Başlangıç adresi sayfa sınırına hizalı mı?
-    if start_vaddr % PAGE_SIZE != 0 { return Err(KError::AddressNotPageAligned); }
-
-    let num_pages = (size + PAGE_SIZE - 1) / PAGE_SIZE; // Kaç sayfa serbest bırakılacak
-    let user_l1_table_ptr = ktask::get_current_user_page_table().ok_or(KError::InternalError)?; // Mevcut görevin sayfa tablosunu al
-
-    // TODO: Sanal adres aralığındaki haritalamaları kaldır ve fiziksel çerçeveleri serbest bırak.
-    for i in 0..num_pages {
-        let virt_addr = start_vaddr + i * PAGE_SIZE; // Haritalaması kaldırılacak sanal adres
-        // Sayfanın haritalamasını kaldır ve ilişkili fiziksel adresi al.
-        match unmap_page(user_l1_table_ptr, virt_addr) {
-            Ok(phys_frame_addr) => {
-                 // Başarıyla haritalama kaldırıldı, şimdi fiziksel çerçeveyi serbest bırak.
-                 pfa::free_frame(phys_frame_addr);
-                  println!("Unmapped VA {:x}, freed PA {:p}", virt_addr, phys_frame_addr);
-            }
-            Err(KError::BadAddress) => {
-                // Haritalı olmayan veya beklendiği gibi haritalı olmayan belleği serbest bırakmaya çalışılıyor.
-                // Bu, çift serbest bırakma veya geçersiz işaretçi kullanımı olabilir.
-                // Gerçek bir çekirdekte bu muhtemelen bir panic veya ciddi bir hata olmalıdır.
-                 // Bu örnek için, ilk haritalı olmayan sayfada hata dönüyoruz.
-                 // Daha sağlam bir yaklaşım, aralıktaki tüm olası sayfaları kaldırmaya çalışıp
-                 // sonra herhangi biri bulunamazsa raporlamak olurdu.
-                   println!("free_user_memory error: VA {:x} not mapped", virt_addr);
-                 return Err(KError::BadAddress);
-            }
-            Err(e) => {
-                 // Diğer haritalama kaldırma hatası
-                   println!("free_user_memory error: Failed to unmap VA {:x}: {:?}", virt_addr, e);
-                 return Err(e);
-            }
+    unsafe fn unmap_page(&self, root: u64, vaddr: u64) -> Option<u64> {
+        if vaddr >= USER_VA_LIMIT {
+            return None;
         }
+        unmap_page(root as *mut PageTable, vaddr as usize).ok().map(|frame| frame as u64)
     }
 
-    // TODO: Eğer bu silme sonucu üst seviye sayfa tabloları boşalırsa, onları serbest bırakabiliriz. (Karmaşık, atlandı)
-
-    Ok(()) // Başarı
-}
-
-// --- Paylaşımlı Bellek Yönetimi ---
-// Görevler arasında bellek alanlarını paylaşmak için.
-
-// Paylaşımlı Bellek Yöneticisi Yer Tutucu
-// Gerçek bir yöneticide eşzamanlı erişim kontrolü (örn. Mutex) ve daha sofistike yapı gerekir.
-mod shared_mem_manager {
-     use super::*;
-     use alloc::vec::Vec;
-     use alloc::boxed::Box; // SharedMemObject'i heap'te tutmak için
-     use core::sync::atomic::{AtomicU64, Ordering}; // Handle üretimi için
-
-     // Bir paylaşımlı bellek nesnesinin detaylarını tutan yapı
-     struct SharedMemObject {
-         frames: Vec<*mut u8>, // Bu nesneye ait fiziksel çerçeveler
-         // Birden fazla görev haritalayabilirse, ne zaman çerçeveleri serbest bırakacağımızı bilmek için referans sayacı eklenmeli.
-          ref_count: AtomicUsize,
-     }
+    unsafe fn protect_page(&self, root: u64, vaddr: u64, prot: u32) -> bool {
+        if vaddr >= USER_VA_LIMIT {
+            return false;
+        }
+        let l3_entry_ptr = match walk_page_table_mut(root as *mut PageTable, vaddr as usize, false) {
+            Ok(entry) => entry,
+            Err(_) => return false,
+        };
+        let l3_entry = ptr::read_volatile(l3_entry_ptr);
+        if (l3_entry & pte_flags::VALID) == 0 {
+            return false;
+        }
+        ptr::write_volatile(l3_entry_ptr, (l3_entry & PHYS_ADDR_MASK) | prot_to_flags(prot));
+        asm!("dsb ishst", options(nostack, preserves_flags));
+        true
+    }
 
-     // Paylaşımlı bellek nesnelerini tutmak için static bir Option dizisi kullanıyoruz.
-     // Gerçek bir çekirdekte bu, dinamik bir yapı veya daha sofistike bir yönetici olurdu.
-     const MAX_SHARED_MEM_OBJECTS: usize = 32; // Maksimum paylaşımlı bellek nesnesi sayısı
-     static mut SHARED_MEM_OBJECTS: [Option<Box<SharedMemObject>>; MAX_SHARED_MEM_OBJECTS] = {
-         // Diziyi None ile başlat
-         let mut objects: [Option<Box<SharedMemObject>>; MAX_SHARED_MEM_OBJECTS] = [None; MAX_SHARED_MEM_OBJECTS];
-         // Dizi elemanlarını manuel olarak initialize etmemiz gerekebilir const fn içinde Box::new kullanamadığımız için.
-         // Şimdilik bu şekilde bırakalım, ilk kullanımdan önce dolaylı olarak None olacak varsayımıyla.
-         objects
-     };
-     static NEXT_HANDLE_VALUE: AtomicU64 = AtomicU64::new(1000); // Handle değerlerini çakışmaması için yüksekten başlatalım.
-
-     // Yeni bir paylaşımlı bellek nesnesini kaydeder ve bir KHandle döner.
-     pub fn register_object(frames: Vec<*mut u8>) -> Result<KHandle, KError> {
-         let new_object = Box::new(SharedMemObject { frames });
-
-         // Boş bir slot bul (basitleştirilmiş, gerçek çekirdekte kilitleme gerekli)
-         unsafe {
-             for i in 0..MAX_SHARED_MEM_OBJECTS {
-                 if SHARED_MEM_OBJECTS[i].is_none() {
-                      SHARED_MEM_OBJECTS[i] = Some(new_object); // Nesneyi slot'a yerleştir
-                      // Slot indeksi ve benzersiz bir değer içeren bir handle oluştur.
-                      // Gerçek handle güvenlik için rastgele bir bileşen de içerebilir.
-                      let handle_value = NEXT_HANDLE_VALUE.fetch_add(1, Ordering::SeqCst); // Benzersiz değeri al ve artır
-                      let k_handle = KHandle(((i as u64) << 32) | (handle_value & 0xFFFFFFFF)); // İndeks ve değeri birleştir
-                       println!("Registered shared memory object in slot {} with handle {:x}", i, k_handle.0);
-                      return Ok(k_handle); // Handle'ı dön
-                 }
-             }
-         }
-         // Boş slot yok
-         Err(KError::OutOfMemory) // Ya da paylaşımlı bellek nesnelerine özel bir hata
-     }
+    unsafe fn translate(&self, root: u64, vaddr: u64, write_access: bool) -> Option<u64> {
+        if vaddr >= USER_VA_LIMIT {
+            return None;
+        }
+        let page = vaddr as usize & !(PAGE_SIZE - 1);
+        let l3_entry_ptr = walk_page_table_mut(root as *mut PageTable, page, false).ok()?;
+        let l3_entry = ptr::read_volatile(l3_entry_ptr);
+        // AP[1] (bit 6): EL0 erişimi, AP[2] (bit 7): salt okunur.
+        if (l3_entry & pte_flags::VALID) == 0 || (l3_entry & pte_flags::AP_EL1_RW_EL0_RW) == 0 {
+            return None;
+        }
+        if write_access && (l3_entry & pte_flags::AP_EL1_RO_EL0_NO) != 0 {
+            return None;
+        }
+        Some((l3_entry & PHYS_ADDR_MASK) | (vaddr & (PAGE_SIZE as u64 - 1)))
+    }
 
-     // Bir KHandle'dan ilişkili fiziksel çerçevelerin listesini al.
-     // Kullanıcının handle'a erişim izni olduğu varsayılır (kresource tarafından kontrol edilebilir).
-     pub fn get_object_frames(handle: &KHandle) -> Result<&'static Vec<*mut u8>, KError> {
-          let index = (handle.0 >> 32) as usize; // Handle'dan slot indeksini çıkar
-          let uniqueifier = (handle.0 & 0xFFFFFFFF); // Handle'dan benzersiz değeri çıkar
-
-         unsafe {
-              if index < MAX_SHARED_MEM_OBJECTS {
-                   if let Some(obj) = SHARED_MEM_OBJECTS[index].as_ref() {
-                       // Gerçek sistemde, handle'ın benzersiz değerini kontrol ederek handle'ın geçerli olduğundan emin ol.
-                       // Bu demo için, şimdilik sadece indekse güveniyoruz.
-                        println!("Looked up shared memory object in slot {} from handle {:x}", index, handle.0);
-                        Ok(&obj.frames) // Çerçevelerin referansını dön
-                   } else {
-                       // Slot boş, handle geçersiz.
-                        println!("Shared memory handle {:x} -> Slot {} is empty", handle.0, index);
-                       Err(KError::BadHandle)
-                   }
-              } else {
-                   // İndeks aralık dışında, handle geçersiz.
-                     println!("Shared memory handle {:x} -> Index {} out of bounds", handle.0, index);
-                   Err(KError::BadHandle)
-              }
-         }
-     }
+    fn user_space_end(&self) -> usize {
+        USER_VA_LIMIT as usize
+    }
 
-     // Paylaşımlı bellek nesnesini serbest bırakır (ref sayısını azaltır, 0 olursa çerçeveleri serbest bırakır).
-     // Bu, KHandle kullanıcı tarafından serbest bırakıldığında çağrılır.
-     pub fn release_object(handle: &KHandle) -> Result<(), KError> {
-         let index = (handle.0 >> 32) as usize; // İndeksi çıkar
-         let uniqueifier = (handle.0 & 0xFFFFFFFF); // Benzersiz değeri çıkar
-
-          unsafe {
-               if index < MAX_SHARED_MEM_OBJECTS {
-                    // Option'dan Box'ı al (move) - bu, slot'u None yapar.
-                    if let Some(obj_box) = SHARED_MEM_OBJECTS[index].take() {
-                        // Gerçek sistemde uniqueifier'ı kontrol et ve referans sayısını azalt.
-                        // Referans sayısı 0 olursa, çerçeveleri serbest bırak.
-                        // Bu demo için, serbest bırakıldığında çerçeveleri doğrudan serbest bırakıyoruz (basitlik).
-                         println!("Releasing shared memory object in slot {} from handle {:x}", index, handle.0);
-                         let mut obj = Box::into_inner(obj_box); // Box içindeki SharedMemObject'i al
-                        for frame in obj.frames.drain(..) { // Çerçeveleri serbest bırak
-                            pfa::free_frame(frame);
-                        }
-                        // 'obj' burada scope dışına çıkar ve düşer (drop olur), SharedMemObject yapısı serbest kalır.
-                         Ok(())
-                    } else {
-                        // Slot zaten boştu (çift serbest bırakma?)
-                         println!("Shared memory handle {:x} -> Slot {} was already empty on release", handle.0, index);
-                        Err(KError::BadHandle)
+    unsafe fn destroy_page_tables(&self, root: u64) {
+        let l1_table = &mut *(root as *mut PageTable);
+        for l1_entry in l1_table.entries.iter_mut() {
+            if (*l1_entry & pte_flags::VALID) != 0 && (*l1_entry & pte_flags::TABLE) != 0 {
+                let l2_table = (*l1_entry & PHYS_ADDR_MASK) as *mut PageTable;
+                for &l2_entry in (*l2_table).entries.iter() {
+                    if (l2_entry & pte_flags::VALID) != 0 && (l2_entry & pte_flags::TABLE) != 0 {
+                        pfa::free_frame((l2_entry & PHYS_ADDR_MASK) as *mut u8);
                     }
-               } else {
-                    // İndeks aralık dışında
-                      println!("Shared memory handle {:x} -> Index {} out of bounds on release", handle.0, index);
-                    Err(KError::BadHandle)
-               }
-          }
-     }
-}
-
-
-// `kmemory::shared_mem_create` fonksiyonunu implemente eder.
-// `size` boyutunda bir paylaşımlı bellek bölgesi oluşturur. Fiziksel çerçeveleri tahsis eder ancak henüz haritalamaz.
-// Bu paylaşımlı bellek nesnesini temsil eden bir KHandle döner.
-pub fn shared_mem_create(size: usize) -> Result<KHandle, KError> {
-     if size == 0 { return Err(KError::InvalidArgument); }
-
-     let num_pages = (size + PAGE_SIZE - 1) / PAGE_SIZE; // Kaç sayfa gerekli
-     let mut allocated_phys_frames: Vec<*mut u8> = Vec::new(); // Ayrılan fiziksel çerçeveler
-
-     // Paylaşımlı bellek bölgesi için fiziksel çerçeveleri tahsis et
-     for _ in 0..num_pages {
-         let phys_frame = pfa::allocate_frame().ok_or_else(|| {
-             // Tahsis sırasında hata olursa, daha önce tahsis edilenleri temizle
-             for frame in allocated_phys_frames.drain(..) {
-                 pfa::free_frame(frame);
-             }
-             KError::OutOfMemory
-         })?;
-         allocated_phys_frames.push(phys_frame); // Listeye ekle
-     }
-
-     // Ayrılan çerçeveleri paylaşımlı bellek yöneticisine kaydet ve handle al.
-     shared_mem_manager::register_object(allocated_phys_frames)
-}
-
-
-// `kmemory::shared_mem_map` fonksiyonunu implemente eder.
-// Bir paylaşımlı bellek bölgesini (`k_handle_value` ile tanımlanan), mevcut görevin sanal adres alanına haritalar.
-// Haritalama, paylaşımlı bellek nesnesindeki `offset` adresinden başlayıp `size` byte sürer.
-// Haritalamanın başladığı kullanıcı alanı sanal adresini döner.
-pub fn shared_mem_map(k_handle_value: u64, offset: usize, size: usize) -> Result<*mut u8, KError> {
-     if size == 0 { return Ok(ptr::null_mut()); }
-     // Offset sayfa sınırına hizalı olmalı
-     if offset % PAGE_SIZE != 0 { return Err(KError::AddressNotPageAligned); }
-
-     let handle = KHandle(k_handle_value); // Handle değerinden KHandle oluştur
-
-     // Yöneticiden paylaşımlı bellek nesnesine ait fiziksel çerçeveleri al.
-     let shared_mem_frames = shared_mem_manager::get_object_frames(&handle)?;
-
-     // Offset ve size'ı paylaşımlı bellek nesnesinin boyutuyla doğrula.
-     let object_size_pages = shared_mem_frames.len();
-     let offset_pages = offset / PAGE_SIZE; // Ofsetin sayfa cinsinden karşılığı
-     let num_pages_to_map = (size + PAGE_SIZE - 1) / PAGE_SIZE; // Kaç sayfa haritalanacak
-
-     // Haritalama aralığı nesnenin sınırları içinde mi?
-     if offset_pages >= object_size_pages || offset_pages + num_pages_to_map > object_size_pages {
-          println!("shared_mem_map: Invalid offset ({}) or size ({}) for object with {} pages", offset, size, object_size_pages);
-         return Err(KError::InvalidArgument); // Haritalama aralığı nesne sınırları dışında
-     }
-
-     let user_l1_table_ptr = ktask::get_current_user_page_table().ok_or(KError::InternalError)?; // Mevcut görevin sayfa tablosunu al
-     let user_va_allocator = ktask::get_current_user_va_allocator().ok_or(KError::InternalError)?; // Mevcut görevin VA ayırıcısını al
-
-     // 1. Haritalanacak `num_pages_to_map` kadar sayfa için bitişik boş sanal adres alanı bul.
-     let map_start_vaddr = user_va_allocator.allocate_pages(num_pages_to_map, user_l1_table_ptr)?;
-      println!("shared_mem_map: Found VA space at {:x} for {} pages", map_start_vaddr, num_pages_to_map);
-
-     // 2. Paylaşımlı bellek nesnesindeki ilgili fiziksel çerçeveleri bulunan VA aralığına haritala.
-     // Paylaşımlı bellek genellikle kullanıcılar için R/W'dir.
-     let flags = pte_flags::USER_DATA_FLAGS; // Ya da USER_CODE_FLAGS yürütülebilir kütüphaneyse.
-     // Kullanıcı haritalama sırasında izinleri belirtebilir mi? Şimdilik varsayılan R/W.
-
-
-     for i in 0..num_pages_to_map {
-         let virt_addr = map_start_vaddr + i * PAGE_SIZE; // Haritalanacak sanal adres
-         let phys_frame = shared_mem_frames[offset_pages + i]; // Nesneden belirli fiziksel çerçeveyi al
-
-          println!("shared_mem_map: Mapping VA {:x} to PA {:p} with flags {:x}", virt_addr, phys_frame, flags);
-
-         // Sayfayı haritala.
-         if map_page(user_l1_table_ptr, virt_addr, phys_frame, flags).is_err() {
-              // Haritalama başarısız - temizlik gerekli (bu aralıkta daha önce haritalanmış sayfaları kaldır).
-              // Bu karmaşıktır. Basitlik için sadece hata dönüyoruz.
-              // Hata oluşmadan önce başarıyla haritalanmış sayfaları kaldırmaya çalış.
-             let mut cleanup_vaddr = map_start_vaddr;
-              for j in 0..i {
-                  if unmap_page(user_l1_table_ptr, cleanup_vaddr).is_ok() {
-                       // Temizlik sırasında başarıyla haritalama kaldırıldı
-                  } else {
-                      // Temizlik sırasında hata! Logla.
-                        println!("Cleanup error: Failed to unmap VA {:x} during shared_mem_map error", cleanup_vaddr);
-                  }
-                  cleanup_vaddr += PAGE_SIZE;
-              }
-              // Paylaşımlı bellek nesnesi (çerçeveler) burada serbest BIRAKILMAZ, yöneticinin mülkiyetindedir.
-              return Err(KError::InternalError); // Ya da daha spesifik bir hata
-         }
-     }
-
-     Ok(map_start_vaddr as *mut u8) // Haritalanan sanal adresin başlangıcını dön
-}
-
-
-// `kmemory::shared_mem_unmap` fonksiyonunu implemente eder.
-// Bir paylaşımlı bellek bölgesini, mevcut görevin sanal adres alanından kaldırır.
-// Fiziksel çerçeveleri serbest BIRAKMAZ (çerçeveler paylaşımlı bellek nesnesine aittir).
-pub fn shared_mem_unmap(ptr: *mut u8, size: usize) -> Result<(), KError> {
-    if ptr.is_null() || size == 0 { return Ok(()); }
-
-    let start_vaddr = ptr as usize;
-    // Başlangıç adresi sayfa sınırına hizalı mı?
-    if start_vaddr % PAGE_SIZE != 0 { return Err(KError::AddressNotPageAligned); } // Sayfa hizalı olmalı
-
-    let num_pages = (size + PAGE_SIZE - 1) / PAGE_SIZE; // Kaç sayfa kaldırılacak
-    let user_l1_table_ptr = ktask::get_current_user_page_table().ok_or(KError::InternalError)?; // Mevcut görevin sayfa tablosunu al
-
-    // TODO: Sanal adres aralığındaki haritalamaları kaldır.
-    for i in 0..num_pages {
-        let virt_addr = start_vaddr + i * PAGE_SIZE; // Haritalaması kaldırılacak sanal adres
-        // Sayfanın haritalamasını kaldır. Dönen fiziksel adresi kullanmıyoruz çünkü çerçeveyi burada serbest bırakmıyoruz.
-        match unmap_page(user_l1_table_ptr, virt_addr) {
-            Ok(_) => {
-                 // Başarıyla haritalama kaldırıldı.
-                  println!("shared_mem_unmap: Unmapped VA {:x}", virt_addr);
-            }
-             Err(KError::BadAddress) => {
-                // Bu sanal adreste haritalı olmayan belleği kaldırmaya çalışılıyor.
-                // Bu, free_user_memory'deki duruma göre daha az kritiktir, belki sadece uyar veya OK dön.
-                // Sağlamlık için, beklenen bir sayfayı kaldıramazsa ilk hatada hata dönelim.
-                 // Daha sağlam bir yaklaşım, aralıktaki tüm olası sayfaları kaldırmaya çalışıp
-                 // sonra herhangi biri bulunamazsa raporlamak olurdu.
-                   println!("shared_mem_unmap: VA {:x} not mapped or not found", virt_addr);
-                 return Err(KError::BadAddress);
-             }
-            Err(e) => {
-                 // Diğer haritalama kaldırma hatası
-                   println!("shared_mem_unmap: Error unmapping VA {:x}: {:?}", virt_addr, e);
-                 return Err(e);
+                }
+                pfa::free_frame(l2_table as *mut u8);
             }
+            *l1_entry = 0;
         }
+        asm!("dsb ishst", options(nostack, preserves_flags));
     }
+}
 
-    // TODO: Eğer bu silme sonucu üst seviye sayfa tabloları boşalırsa, onları serbest bırakabiliriz. (Karmaşık, atlandı)
+static ARMV9_MMU: Armv9Mmu = Armv9Mmu;
 
-    Ok(()) // Başarı
+/// Sayfa tablosu işlemlerini kmemory'ye kaydeder; ilk kullanıcı görevi oluşturulmadan önce çağrılır.
+pub fn init() {
+    register_arch_mmu(&ARMV9_MMU);
 }
 
 
@@ -9,6 +9,18 @@
 // Proje yapınıza göre 'crate::karnal64' veya sadece 'karnal64' kullanabilirsiniz.
 use karnal64;
 
+// ktask mimari katmanı (bağlam kurma/değiştirme, adres alanı etkinleştirme)
+#[path = "srctask_armv9.rs"]
+mod task;
+
+// kmemory sayfa tablosu işlemleri (görev adres alanlarının TTBR0_EL1 tabloları)
+#[path = "srcmmu_armv9.rs"]
+mod mmu;
+
+// SMP: ikincil CPU'ları başlatma (PSCI CPU_ON), GICv3 SGI IPI'leri ve low_level_cpu_id
+#[path = "srcsmp_armv9.rs"]
+mod smp;
+
 // --- Platforma Özgü Yardımcı Fonksiyonlar ve Makrolar ---
 
 // Çekirdek içi basit çıktı (print) mekanizması için yer tutucu.
@@ -67,6 +79,12 @@ pub extern "C" fn arm_platform_init() {
     // Platforma özgü temel kurulumlar tamamlandıktan sonra,
     // Karnal64'ün genel çekirdek yöneticilerini (kaynak, görev, bellek vb.) başlatıyoruz.
     karnal64::init();
+    // Zamanlayıcı bağlam değiştirebilsin diye mimari katmanı kaydet.
+    task::init();
+    // Görev adres alanlarının sayfa tablolarını kurabilmesi için kmemory'ye kaydet.
+    mmu::init();
+    // Boot CPU'sunu kaydet ve diğer CPU'ları başlat.
+    smp::init();
 
     platform_println!("Karnal64: Generic Çekirdek Başlatma Tamamlandı.");
     platform_println!("Karnal64: ARM Platformu Başlatma Tamamlandı.");
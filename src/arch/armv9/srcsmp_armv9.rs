@@ -0,0 +1,289 @@
+// Karnal64 SMP için AArch64 (ARMv9) mimari katmanı.
+// İkincil CPU'lar PSCI CPU_ON ile başlatılır; CPU, EL1'de MMU kapalı olarak
+// `armv9_secondary_entry`'den başlar, boot CPU'sunun MMU/vektör ayarlarını yükler ve
+// `prepare_cpu`'nun verdiği yığınla `armv9_secondary_main`'e geçer.
+// IPI'ler GICv3 SGI'larıdır (ICC_SGI1R_EL1); IRQ işleyicisi `IPI_SGI`'yi ktask::handle_ipi'ye
+// yönlendirir (srcinterrupt_armv9.rs).
+// CPU kimliği MPIDR_EL1 afinite alanlarıdır (Aff3.Aff2.Aff1.Aff0). CPU'lar DTB'deki /cpus
+// düğümlerinden, topolojileri /cpus/cpu-map'ten kaydedilir (srcdtb_armv9.rs); kayıt yoksa küme
+// 0'daki Aff0 değerleri PSCI AFFINITY_INFO ile yoklanır (QEMU virt `-smp N`).
+// PSCI çağrı yöntemi (DTB /psci "method") ve GICv3 yeniden dağıtıcı (redistributor) bölgesi
+// DTB'den okunur (`set_psci_method`, `set_gic_redistributor_base`); DTB vermezse QEMU virt
+// varsayılanları (HVC, 0x080A_0000) kullanılır. Çekirdek görüntüsünün kimlik eşlemeli olduğu
+// varsayılır (CPU_ON giriş adresi fizikseldir).
+
+use core::arch::{asm, global_asm};
+use core::ptr::{read_volatile, write_volatile};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use karnal64::{ktask, KError};
+
+// PSCI fonksiyon numaraları (SMC64/HVC64 çağrı kuralı).
+const PSCI_CPU_ON: u64 = 0xC400_0003;
+const PSCI_AFFINITY_INFO: u64 = 0xC400_0004;
+const PSCI_SUCCESS: i64 = 0;
+const PSCI_ALREADY_ON: i64 = -4;
+/// AFFINITY_INFO: 0 = ON, 1 = OFF, 2 = ON_PENDING; negatif değerler geçersiz hedeftir.
+const PSCI_AFFINITY_ON_PENDING: i64 = 2;
+
+/// PSCI çağrılarında `hvc` yerine `smc` kullan (EL3 firmware'i olan gerçek donanım).
+static PSCI_USE_SMC: AtomicBool = AtomicBool::new(false);
+
+/// MPIDR_EL1'in afinite alanları (Aff3: 39-32, Aff2: 23-16, Aff1: 15-8, Aff0: 7-0).
+const MPIDR_AFFINITY_MASK: u64 = 0xFF_00FF_FFFF;
+
+/// DTB yokken yoklanacak Aff0 değerleri (küme 0).
+const MAX_AFF0_PROBE: u64 = 16;
+
+/// Yeniden zamanlama/TLB temizleme IPI'si olarak kullanılan SGI numarası.
+pub const IPI_SGI: u32 = 0;
+
+/// GICv3 yeniden dağıtıcı bölgesinin başı (varsayılan QEMU virt) ve ofsetleri. Her CPU'nun
+/// RD + SGI çerçevesi 128 KiB'tır.
+static GICR_BASE: AtomicU64 = AtomicU64::new(0x080A_0000);
+const GICR_STRIDE: u64 = 0x2_0000;
+const GICR_WAKER: u64 = 0x14;
+const GICR_TYPER: u64 = 0x08;
+const GICR_SGI_BASE: u64 = 0x1_0000;
+const GICR_ISENABLER0: u64 = 0x100;
+const GICR_TYPER_LAST: u64 = 1 << 4;
+const GICR_WAKER_PROCESSOR_SLEEP: u32 = 1 << 1;
+const GICR_WAKER_CHILDREN_ASLEEP: u32 = 1 << 2;
+
+/// Boot CPU'sunun EL1 MMU ve vektör ayarları; ikincil CPU'lar girişte aynılarını yükler.
+/// Düzen `armv9_secondary_entry`'deki ofsetlerle aynıdır; tek önbellek satırına sığar.
+#[repr(C, align(64))]
+struct BootState {
+    mair: u64,
+    tcr: u64,
+    ttbr0: u64,
+    ttbr1: u64,
+    vbar: u64,
+    sctlr: u64,
+}
+
+#[no_mangle]
+static mut ARMV9_BOOT_STATE: BootState = BootState { mair: 0, tcr: 0, ttbr0: 0, ttbr1: 0, vbar: 0, sctlr: 0 };
+
+global_asm!(
+    r#"
+    .section .text
+    .global armv9_secondary_entry
+    // PSCI CPU_ON girişi: x0 = context_id (çekirdek yığın tepesi). EL1, MMU ve kesmeler kapalı.
+armv9_secondary_entry:
+    adrp x1, ARMV9_BOOT_STATE
+    add x1, x1, :lo12:ARMV9_BOOT_STATE
+    ldp x2, x3, [x1, #0]
+    msr mair_el1, x2
+    msr tcr_el1, x3
+    ldp x2, x3, [x1, #16]
+    msr ttbr0_el1, x2
+    msr ttbr1_el1, x3
+    ldp x2, x3, [x1, #32]
+    msr vbar_el1, x2
+    tlbi vmalle1
+    dsb nsh
+    isb
+    msr sctlr_el1, x3
+    isb
+    mov sp, x0
+    mrs x0, mpidr_el1
+    bl armv9_secondary_main
+1:
+    wfi
+    b 1b
+"#
+);
+
+extern "C" {
+    fn armv9_secondary_entry();
+}
+
+/// DTB'deki /psci "method" değerini uygular ("smc" veya "hvc"); başka bir değer `KError::NotSupported`.
+/// `init`'ten önce çağrılmalıdır.
+pub fn set_psci_method(method: &str) -> Result<(), KError> {
+    match method {
+        "smc" => PSCI_USE_SMC.store(true, Ordering::Relaxed),
+        "hvc" => PSCI_USE_SMC.store(false, Ordering::Relaxed),
+        _ => return Err(KError::NotSupported),
+    }
+    Ok(())
+}
+
+/// DTB'deki GICv3 düğümünün yeniden dağıtıcı bölgesini (`reg`'in ikinci aralığı) ayarlar.
+/// `init`'ten önce çağrılmalıdır.
+pub fn set_gic_redistributor_base(base: u64) {
+    GICR_BASE.store(base, Ordering::Relaxed);
+}
+
+unsafe fn psci_call(function: u64, arg0: u64, arg1: u64, arg2: u64) -> i64 {
+    let ret: i64;
+    if PSCI_USE_SMC.load(Ordering::Relaxed) {
+        asm!("smc #0", inlateout("x0") function as i64 => ret, in("x1") arg0, in("x2") arg1, in("x3") arg2, options(nostack));
+    } else {
+        asm!("hvc #0", inlateout("x0") function as i64 => ret, in("x1") arg0, in("x2") arg1, in("x3") arg2, options(nostack));
+    }
+    ret
+}
+
+fn mpidr() -> u64 {
+    let mpidr: u64;
+    unsafe { asm!("mrs {0}, mpidr_el1", out(reg) mpidr, options(nomem, nostack)) };
+    mpidr & MPIDR_AFFINITY_MASK
+}
+
+/// Çağıran CPU'nun mantıksal indeksi (hardware_specific.h): MPIDR afinitesi, ktask kaydıyla eşlenir.
+/// Kayıtsız CPU'lar 0 döner.
+#[no_mangle]
+pub extern "C" fn low_level_cpu_id() -> u32 {
+    ktask::cpu_index(mpidr()).unwrap_or(0) as u32
+}
+
+/// Afinitesi `target` olan CPU'ya `IPI_SGI` gönderir (srctask_armv9.rs::send_ipi).
+pub fn send_ipi(target: u64) {
+    let aff0 = target & 0xFF;
+    let aff1 = (target >> 8) & 0xFF;
+    let aff2 = (target >> 16) & 0xFF;
+    let aff3 = (target >> 32) & 0xFF;
+    // TargetList 16 CPU'luk bir aralığı kapsar; RS aralığı seçer.
+    let sgi1r = (aff3 << 48)
+        | ((aff0 / 16) << 44)
+        | (aff2 << 32)
+        | ((IPI_SGI as u64) << 24)
+        | (aff1 << 16)
+        | (1 << (aff0 % 16));
+    unsafe { asm!("msr icc_sgi1r_el1, {0}", "isb", in(reg) sgi1r, options(nostack)) };
+}
+
+/// Bu CPU'nun GICv3 yeniden dağıtıcısını uyandırır, `IPI_SGI`'yi ve zamanlayıcı PPI'sini açar ve CPU arayüzünü
+/// (ICC_* sistem yazmaçları) etkinleştirir. Her CPU kendi için çağırır.
+fn gic_cpu_init() {
+    let me = mpidr();
+    // GICR_TYPER[63:32] = Aff3.Aff2.Aff1.Aff0
+    let me_aff = (me & 0xFF_FFFF) | ((me >> 32) & 0xFF) << 24;
+    unsafe {
+        let mut rd = GICR_BASE.load(Ordering::Relaxed);
+        loop {
+            let typer = read_volatile((rd + GICR_TYPER) as *const u64);
+            if typer >> 32 == me_aff {
+                break;
+            }
+            if typer & GICR_TYPER_LAST != 0 {
+                platform_println!("SMP: MPIDR {:#x} için GIC yeniden dağıtıcısı bulunamadı", me);
+                return;
+            }
+            rd += GICR_STRIDE;
+        }
+        let waker = (rd + GICR_WAKER) as *mut u32;
+        write_volatile(waker, read_volatile(waker) & !GICR_WAKER_PROCESSOR_SLEEP);
+        while read_volatile(waker) & GICR_WAKER_CHILDREN_ASLEEP != 0 {
+            core::hint::spin_loop();
+        }
+        write_volatile(
+            (rd + GICR_SGI_BASE + GICR_ISENABLER0) as *mut u32,
+            (1 << IPI_SGI) | (1 << crate::task::TIMER_INTID),
+        );
+
+        asm!(
+            "mrs {tmp}, icc_sre_el1",
+            "orr {tmp}, {tmp}, #1",
+            "msr icc_sre_el1, {tmp}",
+            "isb",
+            "mov {tmp}, #0xff",
+            "msr icc_pmr_el1, {tmp}",
+            "mov {tmp}, #1",
+            "msr icc_igrpen1_el1, {tmp}",
+            "isb",
+            tmp = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+/// IRQ işleyicisinden çağrılır: bekleyen kesmeyi onaylar (ICC_IAR1_EL1) ve kesme numarasını döner.
+/// İşleyici işini bitirince `end_of_interrupt` ile tamamlamalıdır.
+pub fn acknowledge_interrupt() -> u32 {
+    let intid: u64;
+    unsafe { asm!("mrs {0}, icc_iar1_el1", out(reg) intid, options(nomem, nostack)) };
+    intid as u32
+}
+
+/// `acknowledge_interrupt` ile alınan kesmeyi tamamlar (ICC_EOIR1_EL1).
+pub fn end_of_interrupt(intid: u32) {
+    unsafe { asm!("msr icc_eoir1_el1, {0}", "isb", in(reg) intid as u64, options(nostack)) };
+}
+
+/// DTB taraması CPU kaydetmediyse küme 0'daki CPU'ları PSCI AFFINITY_INFO ile bulur.
+fn probe_cpus() {
+    let cluster = mpidr() & !0xFF;
+    for aff0 in 0..MAX_AFF0_PROBE {
+        let target = cluster | aff0;
+        let state = unsafe { psci_call(PSCI_AFFINITY_INFO, target, 0, 0) };
+        if !(0..=PSCI_AFFINITY_ON_PENDING).contains(&state) {
+            continue;
+        }
+        if ktask::add_cpu(target).is_err() {
+            platform_println!("SMP: MAX_CPUS aşıldı, MPIDR {:#x} ve sonrası kullanılmayacak", target);
+            break;
+        }
+    }
+}
+
+fn start_cpu(cpu: usize) {
+    let target = match ktask::cpu_hw_id(cpu) {
+        Some(target) => target,
+        None => return,
+    };
+    let stack = match ktask::prepare_cpu(cpu) {
+        Ok(stack) => stack,
+        Err(e) => {
+            platform_println!("SMP: CPU {} (MPIDR {:#x}) hazırlanamadı: {:?}", cpu, target, e);
+            return;
+        }
+    };
+    let ret = unsafe { psci_call(PSCI_CPU_ON, target, armv9_secondary_entry as usize as u64, stack) };
+    if ret != PSCI_SUCCESS && ret != PSCI_ALREADY_ON {
+        platform_println!("SMP: CPU {} (MPIDR {:#x}) başlatılamadı (PSCI hata {})", cpu, target, ret);
+    }
+}
+
+/// İkincil CPU'nun Rust girişi; armv9_secondary_entry, `prepare_cpu` yığınına geçtikten sonra çağırır.
+#[no_mangle]
+extern "C" fn armv9_secondary_main(_mpidr: u64) -> ! {
+    gic_cpu_init();
+    // Zamanlayıcı, idle döngüsüne girerken ktask tarafından tek seferlik kurulur (program_timer).
+    ktask::cpu_online()
+}
+
+/// Boot CPU'sunu kaydeder, GIC CPU arayüzünü açar ve kayıtlı tüm ikincil CPU'ları başlatır.
+/// Platform başlatma kodu task::init()'ten sonra çağırır.
+pub fn init() {
+    ktask::register_boot_cpu(mpidr());
+    gic_cpu_init();
+    if ktask::possible_cpus() == 1 {
+        probe_cpus();
+    }
+    unsafe {
+        let state = &mut *core::ptr::addr_of_mut!(ARMV9_BOOT_STATE);
+        asm!(
+            "mrs {0}, mair_el1",
+            "mrs {1}, tcr_el1",
+            "mrs {2}, ttbr0_el1",
+            "mrs {3}, ttbr1_el1",
+            "mrs {4}, vbar_el1",
+            "mrs {5}, sctlr_el1",
+            out(reg) state.mair,
+            out(reg) state.tcr,
+            out(reg) state.ttbr0,
+            out(reg) state.ttbr1,
+            out(reg) state.vbar,
+            out(reg) state.sctlr,
+            options(nomem, nostack),
+        );
+        // Giriş kodu bu bloğu MMU kapalıyken okur.
+        asm!("dc cvac, {0}", "dsb sy", in(reg) state as *mut BootState, options(nostack));
+    }
+    for cpu in 1..ktask::possible_cpus() {
+        start_cpu(cpu);
+    }
+}
@@ -42,12 +42,37 @@ pub fn ticks() -> u64 {
     TICKS.load(Ordering::SeqCst) // Tick sayısını güvenli bir şekilde oku.
 }
 
+// **Açıklama**: Boot'tan beri geçen monoton süre (hardware_specific.h). Fiziksel sayaç (CNTPCT_EL0),
+// frekans register'ı (CNTFRQ_EL0) ile nanosaniyeye çevrilir. ktask zamanlayıcısı ve CPU süresi
+// muhasebesi bu kancayı kullanır; tek seferlik zamanlayıcı (srctask_armv9.rs::program_timer)
+// CNTP_CVAL_EL0'ı aynı sayaca göre kurar.
+#[no_mangle]
+pub extern "C" fn low_level_monotonic_time_ns() -> u64 {
+    let count: u64;
+    let freq: u64;
+    unsafe {
+        // `isb`: sayaç okumasının önceki komutlardan önce yapılmasını engeller.
+        asm!("isb", "mrs {0}, cntpct_el0", out(reg) count, options(nomem, nostack));
+        asm!("mrs {0}, cntfrq_el0", out(reg) freq, options(nomem, nostack));
+    }
+    if freq == 0 {
+        return 0; // Firmware CNTFRQ_EL0'ı ayarlamamış
+    }
+    ((count as u128 * 1_000_000_000) / freq as u128) as u64
+}
+
 // **Açıklama**: Belirtilen milisaniye kadar bekleyen (gecikme) fonksiyonu.
+// Çağıran iş parçacığı ktask zamanlayıcı kuyruğunda uyur; CPU bu sürede başka işleri çalıştırır.
+// Zamanlayıcı başlamadan önce (erken boot) sayaç üzerinde meşgul bekleme yapılır.
 pub fn delay(ms: u64) {
-    let target_ticks = ticks() + ms; // Hedef tick sayısını hesapla.
-    while ticks() < target_ticks {} // Hedef tick sayısına ulaşılana kadar bekle.
-                                     // **Dikkat**: Bu bloklayıcı (blocking) bir gecikmedir.
-                                     // Gerçek zamanlı sistemlerde dikkatli kullanılmalıdır.
+    if karnal64::ktask::can_block() {
+        let _ = karnal64::ktask::task_sleep(ms);
+        return;
+    }
+    let target = low_level_monotonic_time_ns() + ms * 1_000_000; // Hedef anı hesapla.
+    while low_level_monotonic_time_ns() < target {
+        core::hint::spin_loop();
+    }
 }
 
 // **Açıklama**: Zamanlayıcı değerini ayarlayan fonksiyon.
@@ -2,6 +2,10 @@
 #![allow(dead_code)] // Geliştirme sırasında kullanılmayan kodlar için izin
 #![allow(unused_variables)] // Kullanılmayan değişkenler için izin
 
+// Görev sonlandırma, zamanlayıcı ve istisna portları gerçek Karnal64 ktask'tan gelir
+// (bkz. src/task/srctask.rs, src/task/srcexcport.rs).
+use karnal64::{kmemory, ktask, KError};
+
 // LoongArch 64-bit (LA64) mimarisine özgü yardımcılar ve yapılar
 // Bunlar gerçek bir çekirdekte mimariye özel bir modülden gelirdi (örn. crate::arch::loongarch)
 mod loongarch_regs {
@@ -19,6 +23,7 @@ mod loongarch_regs {
     pub const EXCODE_SYSCALL: u64 = 0x0C; // Örnek: Sistem çağrısı istisna kodu
     pub const EXCODE_PAGE_FAULT_LOAD: u64 = 0x01; // Örnek: Yükleme hatası (TLB/sayfa hatası)
     pub const EXCODE_PAGE_FAULT_STORE: u64 = 0x02; // Örnek: Yazma hatası (TLB/sayfa hatası)
+    pub const EXCODE_PAGE_FAULT_FETCH: u64 = 0x03; // Komut getirme hatası (TLB/sayfa hatası)
     pub const EXCODE_TLB_REFILL: u64 = 0x07; // Örnek: TLB doldurma hatası
     pub const EXCODE_INTERRUPT: u64 = 0x80; // Örnek: Kesme kodu (genellikle farklı bir mekanizma olabilir)
     // ... diğer istisna kodları
@@ -60,20 +65,6 @@ mod loongarch_regs {
 // Bu modüllerin Karnal64 API'sını implemente eden gerçek kodları içerdiği varsayılır.
 // Bu dosyada sadece bu modüllerin handler fonksiyonlarına çağrı yapılır.
 mod kresource { /* ... */ }
-mod ktask { /* ... */ }
-mod kmemory {
-    use super::*; // karnal64.rs ve loongarch_regs scope'undaki tipleri kullan
-
-    // Sayfa hatası/TLB hatası işleyici fonksiyonu için yer tutucu
-    // Gerçek implementasyon MMU yönetimi, sayfa tablosu güncellemeleri vb. yapar.
-    pub fn handle_page_fault(fault_addr: u64, exception_code: u64, context: &mut TaskContext) -> Result<(), KError> {
-        // TODO: Hatanın türüne (load/store/refill), adresine ve ilgili görevin bellek haritasına göre
-        // sayfa hatasını çözmeye çalış (örn. COW, lazy allocation, mmap fault).
-        // Başarılı olursa Ok(()), aksi takdirde Err(KError::BadAddress) veya başka bir hata döner.
-        println!("Karnal64: Sayfa Hatası! Adres: {:#x}, Kod: {}", fault_addr, exception_code);
-        Err(KError::BadAddress) // Şimdilik hata döndürelim
-    }
-}
 mod ksync { /* ... */ }
 mod kmessaging { /* ... */ }
 mod kinterrupt {
@@ -96,7 +87,8 @@ mod kinterrupt {
 // İstisna/Tuzak oluştuğunda kaydedilen CPU yazmaçlarını tutan yapı.
 // Düşük seviyeli assembly handler'ı bu yapıyı yığına kaydeder ve bu yapıya bir işaretçi
 // veya kendisini yüksek seviyeli Rust handler'ına (exception_handler) geçirir.
-// Tüm genel amaçlı yazmaçlar (r0-r31) ve bazı özel yazmaçlar dahil edilmelidir.
+// Tüm genel amaçlı yazmaçlar (r0-r31) ve bazı özel yazmaçlar dahil edilmelidir: handler
+// ERA (csrrd 0x6), BADV (csrrd 0x7) ve PRMD (csrrd 0x1) değerlerini de ilgili alanlara yazar.
 #[repr(C)] // C uyumlu bellek düzeni (assembly ile etkileşim için gerekli)
 #[derive(Debug, Default)] // Hata ayıklama ve varsayılan değer için
 pub struct TaskContext {
@@ -122,10 +114,20 @@ pub struct TaskContext {
     pub pc: u64, // EPC (Exception Program Counter) - İstisnanın oluştuğu adres
     pub crmd: u64, // CRMD (Control and Mode Register) - İstisna anındaki durum/mod
     pub erbaddr: u64, // ERBADDR (Exception Relevant Bad Address Register) - Sayfa hatası adresi vb.
-    // TODO: Diğer ilgili CSR'lar eklenebilir (ESTAT, ERA, PRMD vb.)
+    pub prmd: u64, // PRMD (Pre-exception Mode) - Donanımın istisna girişinde CRMD'den kopyaladığı PPLV/PIE
+    // TODO: Diğer ilgili CSR'lar eklenebilir (ESTAT, ERA vb.)
 }
 
 
+/// CRMD.IE: kesmeler açık.
+const CRMD_IE: u64 = 1 << 2;
+/// PRMD.PIE: istisnadan önce kesmeler açıktı. İstisna girişinde donanım CRMD.IE'yi sıfırlar,
+/// bu yüzden kesintiye uğrayan bağlamın durumu yalnızca burada görülür.
+const PRMD_PIE: u64 = 1 << 2;
+/// ESTAT.IS: bekleyen kesme kaynakları (bit 12:0). Bit 11 sabit zamanlayıcıdır.
+const ESTAT_IS_MASK: u64 = 0x1FFF;
+const ESTAT_IS_TIMER: u64 = 1 << 11;
+
 // --- Yüksek Seviyeli İstisna İşleyici Giriş Noktası ---
 // Düşük seviyeli assembly istisna/tuzak vektör işleyicisi tarafından çağrılır.
 // Kaydedilmiş yazmaç bağlamını içeren TaskContext yapısına bir işaretçi alır.
@@ -175,37 +177,62 @@ pub extern "C" fn exception_handler(tf: &mut TaskContext) {
             // User space'e dönerken bir sonraki talimattan devam etmek için EPC'yi ilerletmeliyiz.
             tf.pc += 4;
         }
-        loongarch_regs::EXCODE_PAGE_FAULT_LOAD | loongarch_regs::EXCODE_PAGE_FAULT_STORE | loongarch_regs::EXCODE_TLB_REFILL => {
-            // Sayfa Hatası veya TLB Hatası (Yükleme/Yazma)
+        loongarch_regs::EXCODE_PAGE_FAULT_LOAD
+        | loongarch_regs::EXCODE_PAGE_FAULT_STORE
+        | loongarch_regs::EXCODE_PAGE_FAULT_FETCH
+        | loongarch_regs::EXCODE_TLB_REFILL => {
+            // Sayfa Hatası veya TLB Hatası (Yükleme/Yazma/Komut getirme)
             // Hataya neden olan adresi (ERBADDR) oku
             let fault_addr = tf.erbaddr; // Veya loongarch_regs::read_erbaddr()
-            println!("Page/TLB Fault at {:#x}, EC={}", fault_addr, excode); // Hata ayıklama çıktısı
 
-            // Bellek yöneticisinin sayfa hatası işleyicisini çağır
-            match kmemory::handle_page_fault(fault_addr, excode, tf) {
+            // Bellek yöneticisi hatayı görevin VMA'larına göre çözer: ilk dokunulan anonim sayfa
+            // sıfırlanıp, kaynak sayfası sağlayıcıdan okunup eşlenir.
+            let access = match excode {
+                loongarch_regs::EXCODE_PAGE_FAULT_STORE => kmemory::PROT_WRITE,
+                loongarch_regs::EXCODE_PAGE_FAULT_FETCH => kmemory::PROT_EXEC,
+                _ => kmemory::PROT_READ,
+            };
+            // Çözüm uyuyabilir; yalnızca istisnadan önce kesmeler açıksa denenir.
+            let result = if tf.prmd & PRMD_PIE != 0 {
+                unsafe {
+                    core::arch::asm!("csrxchg {0}, {1}, 0x0", inout(reg) CRMD_IE => _, in(reg) CRMD_IE, options(nomem, nostack));
+                }
+                kmemory::handle_page_fault(fault_addr as usize, access)
+            } else {
+                Err(KError::BadAddress)
+            };
+
+            match result {
                 Ok(_) => {
                     // Sayfa hatası başarıyla çözüldü. İşlem devam edebilir.
                     // EPC zaten istisna oluştuğu yeri işaret ediyor, geri dönünce tekrar denenir.
                 }
                 Err(err) => {
-                    // Sayfa hatası çözülemedi (geçersiz adres, izin yok vb.).
-                    // Görev sonlandırılmalı veya bir sinyal gönderilmeli.
+                    // Sayfa hatası çözülemedi (adres hiçbir VMA'da değil, izin yok, bellek yok vb.).
                     println!("Fatal Page Fault: Failed to handle {:#x}, EC={}: {:?}", fault_addr, excode, err);
-                    // TODO: Görevi sonlandırma mekanizmasını çağır
-                    ktask::terminate_current_task(KError::BadAddress); // Varsayımsal fonksiyon
-                     // terminate_current_task geri dönmez, bu yüzden buraya ulaşılmamalı.
+                    // Görevin istisna portuna ilet; port yoksa görev sonlandırılır.
+                    // İşleyici devam ettirirse tf onun yazmaçlarıyla güncellenmiştir.
+                    deliver_user_fault(err, excode, fault_addr, tf);
                 }
             }
         }
         loongarch_regs::EXCODE_INTERRUPT => {
             // Donanım Kesmesi (Interrupt)
-            // Kesme kaynağını belirlemek için ilgili yazmaçlar okunmalı (örn. ESTAT).
-            // Basitlik için şimdilik excode'u neden olarak kullanalım, gerçekte farklıdır.
-            let interrupt_cause = excode; // Yer tutucu: gerçekte başka bir yazmaç okunur.
-             println!("Hardware Interrupt, Cause={}", interrupt_cause); // Hata ayıklama çıktısı
+            // Bekleyen kesme kaynakları ESTAT.IS'tedir.
+            let estat: u64;
+            unsafe { core::arch::asm!("csrrd {0}, 0x5", out(reg) estat, options(nomem, nostack)) };
+            let interrupt_cause = estat & ESTAT_IS_MASK & !ESTAT_IS_TIMER;
 
-            // Kesme işleyicisini çağır
-            match kinterrupt::handle_interrupt(interrupt_cause, tf) {
+            // Kesme işleyicisini çağır. `ksync` burada yer tutucu modüldür, lockdep gerçek modülden gelir.
+            karnal64::ksync::lockdep::irq_enter();
+            if estat & ESTAT_IS_TIMER != 0 {
+                // Tek seferlik zamanlayıcıyı timer_tick bir sonraki olaya yeniden kurar
+                // (srctask_loongarch64.rs::program_timer).
+                crate::task::handle_timer_interrupt();
+            }
+            let result = if interrupt_cause != 0 { kinterrupt::handle_interrupt(interrupt_cause, tf) } else { Ok(()) };
+            karnal64::ksync::lockdep::irq_exit();
+            match result {
                 Ok(_) => {
                     // Kesme başarıyla işlendi.
                 }
@@ -216,6 +243,9 @@ pub extern "C" fn exception_handler(tf: &mut TaskContext) {
                     // TODO: Hata raporlama veya panik mekanizması
                 }
             }
+            // Gerekirse bağlam değişimi kesme bağlamından çıkarken yapılır; bu iş parçacığı tekrar
+            // seçildiğinde kendi çekirdek yığınındaki tf ile kesilen yere döner.
+            ktask::preempt_on_interrupt_exit();
         }
         // TODO: Diğer önemli LoongArch istisna türlerini (alignman hatası, yetkisiz talimat vb.) ele al
          loongarch_regs::EXCODE_... => { /* İlgili handler'ı çağır */ }
@@ -249,16 +279,26 @@ extern "C" {
     fn handle_syscall(number: u64, arg1: u64, arg2: u64, arg3: u64, arg4: u64, arg5: u64) -> i64;
 }
 
-// Görev sonlandırma fonksiyonu (ktask modülünde tanımlı varsayılır)
-// Bu fonksiyon geri dönmez.
-mod ktask {
-    use super::KError;
-    pub fn terminate_current_task(reason: KError) -> ! {
-        // TODO: Mevcut görevi sonlandırma, kaynaklarını temizleme, zamanlayıcıdan kaldırma mantığı
-        println!("Karnal64: Görev Sonlandırılıyor! Neden: {:?}", reason);
-        // Gerçek bir implementasyonda bağlam değiştirerek veya durarak sonlanır.
-        loop {} // Fonksiyon geri dönmez
+/// Çözülemeyen bir kullanıcı modu istisnasını ktask istisna portuna iletir. İşleyici iş parçacığını
+/// devam ettirirse (değiştirmiş olabileceği) yazmaçlar tf'ye geri yazılır. CRMD geri yazılmaz:
+/// ayrıcalık seviyesi ve kesme biti çekirdeğin denetimindedir.
+fn deliver_user_fault(reason: KError, excode: u64, address: u64, tf: &mut TaskContext) {
+    // Çekirdek modundaki bir hatanın yazmaçları kullanıcı işleyicisine gösterilmez.
+    if tf.pc >= kmemory::user_space_end() as u64 {
+        ktask::terminate_current_task(reason);
     }
+    // TaskContext repr(C) ve r1-r31 ile başlar; gpr[n] = rn (r0 her zaman sıfır).
+    let saved = unsafe { &mut *(tf as *mut TaskContext as *mut [u64; 31]) };
+    let mut regs = ktask::FaultRegisters::default();
+    regs.gpr[1..].copy_from_slice(saved);
+    regs.pc = tf.pc;
+    regs.sp = tf.r22;
+    regs.status = tf.crmd;
+    ktask::deliver_fault(reason, excode, address, &mut regs);
+    let saved = unsafe { &mut *(tf as *mut TaskContext as *mut [u64; 31]) };
+    saved.copy_from_slice(&regs.gpr[1..]);
+    tf.pc = regs.pc;
+    tf.r22 = regs.sp;
 }
 
 // Çekirdek panik fonksiyonu (kkernel modülünde tanımlı varsayılır)
@@ -271,25 +311,3 @@ mod kkernel {
         loop {} // Fonksiyon geri dönmez
     }
 }
-
-
-// --- Diğer Karnal64 Tipleri (exception_handler'da kullanılanlar) ---
-// Bunlar karnal64.rs dosyasından alınmış gibi düşünülmelidir.
-// Buraya kopyalanmaları, bu dosyanın tek başına derlenebilir olmasını sağlar
-// ancak gerçek projede `use crate::karnal64::{KError, KHandle, ...};` şeklinde olmalıdır.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-#[repr(i64)]
-pub enum KError {
-    PermissionDenied = -1,
-    NotFound = -2,
-    InvalidArgument = -3,
-    Interrupted = -4,
-    BadHandle = -9,
-    Busy = -11,
-    OutOfMemory = -12,
-    BadAddress = -14,
-    AlreadyExists = -17,
-    NotSupported = -38,
-    NoMessage = -61,
-    InternalError = -255,
-}
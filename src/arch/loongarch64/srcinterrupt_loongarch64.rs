@@ -109,18 +109,22 @@ pub extern "C" fn handle_trap(tf: &mut TrapFrame, exception_code: u64) -> *mut T
         }
         LOONGARCH_INTCODE_TIMER => {
             // Zamanlayıcı Kesmesi İşleme
-            // TODO: Zamanlayıcı kesmesi sayacını veya ilgili donanımı resetle/yapılandır.
-            // TODO: Görev zamanlayıcısına (Scheduler) zamanlayıcı kesmesi olduğunu bildir.
-             ktask::handle_timer_interrupt();
-            // TODO: Eğer zamanlayıcı kesmesi bağlam değişimini tetiklediyse,
-            // Scheduler'dan bir sonraki görevin trapframe pointer'ını al.
+            // Kesmeyi temizle, süresi dolan bekleyenleri uyandır. Tek seferlik zamanlayıcıyı
+            // timer_tick bir sonraki olaya yeniden kurar (srctask_loongarch64.rs::program_timer).
+            karnal64::ksync::lockdep::irq_enter();
+            crate::task::handle_timer_interrupt();
+            karnal64::ksync::lockdep::irq_exit();
+            // Gerekirse bağlam değişimi kesme bağlamından çıkarken yapılır.
+            karnal64::ktask::preempt_on_interrupt_exit();
         }
         LOONGARCH_INTCODE_EXTERNAL => {
             // Harici Donanım Kesmesi İşleme
+            karnal64::ksync::lockdep::irq_enter();
             // TODO: Hangi donanımın kesme ürettiğini belirle (IRQ kontrolcüsünden oku).
             // TODO: İlgili sürücünün kesme işleyicisini çağır.
              driver_manager::handle_irq(irq_number);
             // TODO: IRQ kontrolcüsünde kesmeyi onayla (ACK).
+            karnal64::ksync::lockdep::irq_exit();
         }
         LOONGARCH_EXCODE_TLB_MISS_LOAD | LOONGARCH_EXCODE_TLB_MISS_STORE => {
             // Sayfa Hatası (Page Fault) İşleme
@@ -157,16 +161,10 @@ pub extern "C" fn handle_trap(tf: &mut TrapFrame, exception_code: u64) -> *mut T
         }
     }
 
-    // TODO: Zamanlayıcının (Scheduler) bir bağlam değişimine karar verip vermediğini kontrol et.
-    // Eğer karar verdiyse, bir sonraki görevin trapframe pointer'ını döndür.
-    // Yoksa, mevcut trapframe pointer'ını döndür (aynı göreve geri dönecek).
-    // Örneğin:
-     let next_task_tf = ktask::schedule();
-     if let Some(next_tf_ptr) = next_task_tf {
-         next_tf_ptr
-     } else {
-         tf // Aynı göreve geri dön
-     }
+    // Bağlam değişimi yukarıda (preempt_on_interrupt_exit) yapılır: bu iş parçacığı tekrar
+    // seçildiğinde buradan devam eder. tf kendi çekirdek yığınında olduğundan her zaman aynı
+    // trapframe'e dönülür.
+    tf
 }
 
 /// Kernel başlatılırken kesme/tuzak vektörünü kuracak fonksiyon (Kavramsal).
@@ -194,6 +192,92 @@ pub extern "C" fn init_trap() {
 }
 
 
+// --- Karnal64 Kesme Maskesi Kancaları (hardware_specific.h) ---
+// ksync::Spinlock::lock_irqsave ve çekirdek genelindeki kesme aç/kapa işlemleri bunları kullanır.
+// Kaydedilen durum CRMD.IE bitidir (CSR 0x0, bit 2). csrxchg yalnızca maskelenen biti değiştirir.
+
+const CRMD_IE: u64 = 1 << 2;
+
+/// CRMD'nin IE bitini `value` ile değiştirir ve önceki CRMD değerini döndürür.
+#[inline(always)]
+fn crmd_exchange_ie(value: u64) -> u64 {
+    let mut crmd = value;
+    unsafe {
+        core::arch::asm!(
+            "csrxchg {0}, {1}, 0x0",
+            inout(reg) crmd,
+            in(reg) CRMD_IE,
+            options(nomem, nostack)
+        )
+    };
+    crmd
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_enable() {
+    crmd_exchange_ie(CRMD_IE);
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_disable() {
+    crmd_exchange_ie(0);
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupts_enabled() -> i32 {
+    let crmd: u64;
+    unsafe { core::arch::asm!("csrrd {0}, 0x0", out(reg) crmd, options(nomem, nostack)) };
+    (crmd & CRMD_IE != 0) as i32
+}
+
+/// Kesmeleri kapatır ve önceki IE bitini döndürür.
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_save() -> u64 {
+    crmd_exchange_ie(0) & CRMD_IE
+}
+
+/// `low_level_interrupt_save` ile kaydedilen IE bitini geri yükler.
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_restore(flags: u64) {
+    crmd_exchange_ie(flags & CRMD_IE);
+}
+
+// --- Karnal64 CPU Kimliği Kancası (hardware_specific.h) ---
+
+/// Çağıran CPU'nun mantıksal indeksi: CPUID CSR'si (0x20, bit 8:0), ktask kaydıyla eşlenir.
+/// Kayıtsız CPU'lar 0 döner.
+#[no_mangle]
+pub extern "C" fn low_level_cpu_id() -> u32 {
+    let cpuid: u64;
+    unsafe { core::arch::asm!("csrrd {0}, 0x20", out(reg) cpuid, options(nomem, nostack)) };
+    karnal64::ktask::cpu_index(cpuid & 0x1FF).unwrap_or(0) as u32
+}
+
+// --- Karnal64 Yığın İzi Kancası (hardware_specific.h) ---
+// lockdep raporlarında kullanılır; çekirdek çerçeve işaretçisiyle derlenmelidir.
+
+/// $fp ($r22) çerçevenin üstünü gösterir: [fp - 8] $ra, [fp - 16] önceki $fp.
+/// Zincir sıfıra, hizasız bir adrese veya yığında aşağı doğru bir çerçeveye ulaşınca durur.
+#[no_mangle]
+pub extern "C" fn low_level_stack_trace(frames: *mut u64, max_frames: usize) -> usize {
+    let mut fp: u64;
+    unsafe { core::arch::asm!("move {0}, $fp", out(reg) fp, options(nomem, nostack)) };
+    let mut count = 0;
+    while count < max_frames && fp >= 16 && fp % 8 == 0 {
+        let (ra, prev) = unsafe { (*((fp - 8) as *const u64), *((fp - 16) as *const u64)) };
+        if ra == 0 {
+            break;
+        }
+        unsafe { *frames.add(count) = ra };
+        count += 1;
+        if prev <= fp {
+            break;
+        }
+        fp = prev;
+    }
+    count
+}
+
 Aşağıdaki assembly kodu (veya Rust inline assembly), çekirdeğin giriş noktasında
 veya ayrı bir assembly dosyasında yer alacak ve `handle_trap` fonksiyonunu çağıracaktır.
 Rust tarafından doğrudan yazılmaz ama Rust kodu bu assembly'nin beklediği arayüzü sağlar.
@@ -8,6 +8,10 @@
 // Özellikle sistem çağrılarını dağıtan ana fonksiyonu ve hata tipini kullanacağız.
 use karnal64::{handle_syscall, KError};
 
+// ktask mimari katmanı (bağlam kurma/değiştirme, adres alanı etkinleştirme)
+#[path = "srctask_loongarch64.rs"]
+mod task;
+
 // Mimarinin alt bileşenleri için alt modüller
 pub mod cpu;       // CPU özgü işlemler (kayıtlar, bağlam değiştirme vb.)
 pub mod interrupt; // Kesme ve istisna işleme
@@ -38,6 +42,8 @@ pub fn init() {
     // Platforma özel başlatma bittikten sonra, genel Karnal64 çekirdek API'sını başlat.
     // Bu fonksiyon Karnal64'ün iç yöneticilerini (kaynak, görev vb.) başlatır.
     karnal64::init();
+    // Zamanlayıcı bağlam değiştirebilsin diye mimari katmanı kaydet.
+    task::init();
 
     // TODO: Platform başlatmasının başarılı olduğunu belirten bir mesaj yazdırma (konsol sürücüsü gerektirir)
      println!("LoongArch platform başlatıldı.");
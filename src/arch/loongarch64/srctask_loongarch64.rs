@@ -1,274 +1,188 @@
-#![no_std] // Bu da bir çekirdek bileşeni olduğu için standart kütüphaneye ihtiyaç duymaz.
-#![allow(dead_code)] // Geliştirme sırasında kullanılmayan kodlar için izin
-#![allow(unused_variables)] // Geliştirme sırasında kullanılmayan değişkenler için izin
-
-// Karnal64 API'sından temel tipleri içe aktar.
-// Varsayım: Karnal64.rs'teki ilgili tipler 'pub' olarak işaretlenmiştir.
-// 'super::' kullanarak aynı crate içindeki üst modüldeki (varsayımsal olarak karnal64.rs) öğelere erişiyoruz.
-use super::KError;
-use super::KTaskId;
-use super::KThreadId;
-use super::KHandle;
-
-// TODO: LoongArch 64-bit mimarisine özgü register setini tanımla.
-// Bu yapı, bir iş parçacığının veya görevin kaydedilmesi/geri yüklenmesi gereken
-// CPU registerlarının tamamını veya bir kısmını (genellikle kaydedici-çağıran anlaşmasına göre) saklar.
-// Tam register seti ve bunların bellekteki düzeni, LoongArch 64-bit ABI (Application Binary Interface) dokümantasyonuna göre doğru şekilde doldurulmalıdır.
-#[repr(C)] // C uyumluluğu, düşük seviye assembly kodları ile etkileşimde önemlidir.
+// LoongArch64 için ktask mimari katmanı (ktask::ArchTask):
+// - bağlam kurma: yeni iş parçacığı `loongarch_thread_trampoline` üzerinden başlar
+// - bağlam değiştirme: LP64 ABI'de çağrılan tarafından korunan yazmaçlar (s0-s8, fp),
+//   sp ve ra kaydedilir/yüklenir
+// - adres alanı: kullanıcı yarısının sayfa tablosu kökü PGDL'ye yazılır ve TLB temizlenir
+// - TLS: kullanıcı TLS işaretçisi $tp'dir (r2); genel yazmaç olarak istisna çerçevesinde taşınır,
+//   yalnızca kullanıcıya ilk geçişte trampolin yükler
+// - zamanlayıcı: tek seferlik, sabit zamanlayıcı (TCFG) sabit sayaç frekansında geri sayar
+// Kullanıcı modundayken SAVE0 (CSR 0x30), iş parçacığının çekirdek yığınının tepesini tutar;
+// istisna girişi buradan çekirdek yığınına geçer. Kullanıcıya ilk dönüşte SAVE0'ı trampolin,
+// sonrakilerde istisna çıkışı yazar.
+
+use core::arch::{asm, global_asm};
+use core::mem::size_of;
+
+use karnal64::ktask::{self, ArchTask, ThreadStart};
+
+/// Kullanıcı moduna geçişte PRMD: PPLV = 3 (kullanıcı), PIE = 1 (ertn sonrası kesmeler açık).
+const PRMD_USER: u64 = 0x7;
+/// CRMD.IE biti.
+const CRMD_IE: u64 = 1 << 2;
+/// ECFG.LIE'de sabit zamanlayıcı kesmesinin biti (ESTAT.IS ile aynı konum).
+const ECFG_LIE_TIMER: u64 = 1 << 11;
+/// TCFG: En (bit 0) zamanlayıcıyı başlatır; Periodic (bit 1) sıfırdır, yani tek seferlik.
+/// InitVal bit 2'den başlar, bu yüzden 4'ün katı olmalıdır.
+const TCFG_EN: u64 = 1 << 0;
+const TCFG_INITVAL_MIN: u64 = 4;
+/// TICLR.CLR: bekleyen zamanlayıcı kesmesini temizler.
+const TICLR_CLR: u64 = 1 << 0;
+
+/// LoongArch iş parçacığı bağlamı.
+/// Yalnızca çağrılan tarafından korunması gereken yazmaçlar tutulur; geri kalanı
+/// loongarch_switch_context çağrısı sırasında zaten çağıranın yığınındadır.
+#[repr(C)] // Assembly'deki ofsetlerle aynı düzen
 #[derive(Debug, Copy, Clone)]
 pub struct LoongArchContext {
-    // --- Genel Amaçlı Registerlar (GPRs) ---
-    // LoongArch'ın 32 adet 64-bit genel amaçlı registerı (r0-r31).
-    // Genellikle r0 sabittir (sıfır), r1 stack pointer (sp) vb.
-    // Bağlam değiştirme sırasında hangilerinin kaydedilip hangilerinin kaydedilmeyeceği ABI'ye bağlıdır.
-    // Çoğu OS çekirdeği, istisna/sistem çağrısı sırasında CPU tarafından kaydedilenlere ek olarak,
-    // zamanlama kesmelerinde tüm GPR'ları kaydetmeyi tercih eder.
-    gprs: [u64; 32], // r0-r31 için yer tutucu
-
-    // --- Kontrol ve Durum Registerları (CSRs) ---
-    // Görev/iş parçacığına özgü olması gereken kritik CSR'lar.
-    csr_era: u64,  // Exception Return Address - İstisna dönüş adresi (sistem çağrısı sonrası veya bağlam değiştirme sonrası devam edilecek adres)
-    csr_crmd: u64, // Current Mode - Mevcut işlem modu (Kernel/User) ve ilgili bayraklar
-    // TODO: LoongArch'a özgü kaydedilmesi gereken diğer kritik CSR'lar (örneğin, MMU ile ilgili, kesmelerle ilgili durumlar)
-
-    // --- FPU/SIMD Registerları (Eğer kullanılıyorsa) ---
-    // Eğer görevler kayan nokta veya SIMD talimatları kullanıyorsa, bu registerlar da kaydedilmelidir.
-    // LoongArch'ın VPU (Vector Processing Unit) registerları.
-     vregs: [u128; 32], // vr0-vr31 için yer tutucu (tam tip ve boyut LoongArch'a göre belirlenmeli)
-     fcsr: u32,        // Floating-point Control and Status Register
-
-    // TODO: Diğer mimariye özgü durumlar veya registerlar
+    pub ra: u64, // r1 (ilk geçişte trampolin)
+    pub sp: u64, // r3
+    pub fp: u64, // r22
+    /// s0-s8 (r23-r31). İlk geçişte s0-s5 trampoline başlangıç bilgisini taşır.
+    pub s: [u64; 9],
 }
 
-impl LoongArchContext {
-    /// Yeni bir görev/iş parçacığı için başlangıç yürütme bağlamını (context) oluşturur.
-    /// Bu bağlam, görev ilk kez zamanlandığında CPU'ya yüklenecek register değerlerini içerir.
-    ///
-    /// # Arguments
-    /// * `entry_point`: Görevin çalışmaya başlayacağı sanal adres.
-    /// * `stack_top`: Görevin kullanacağı kullanıcı stack'inin en üst adresi (stack genellikle yukarıdan aşağı doğru büyür).
-    /// * `arg`: Göreve (genellikle ilk argüman olarak) geçirilecek 64-bitlik değer.
-    /// * `is_user`: Bu bağlamın kullanıcı modunda mı (true) yoksa çekirdek modunda mı (false) çalışacağını belirtir.
-    pub fn create_initial_context(
-        entry_point: u64, // LoongArch'ta adres büyüklüğü neyse o kullanılmalı (u64 varsayımı)
-        stack_top: u64,
-        arg: u64,
-        is_user: bool,
-    ) -> Self {
-        // TODO: LoongArch ABI'sine göre başlangıç register değerlerini ayarla.
-        let mut context = Self {
-            gprs: [0; 32], // Tüm GPR'ları sıfırla (veya ABI'ye göre uygun başlangıç değerlerini ver)
-            csr_era: entry_point,
-            csr_crmd: 0, // Varsayılan olarak sıfırla, sonra mod bayraklarını ayarla
-            // Diğer CSR'ları sıfırla veya başlangıç değerlerini ver
-        };
-
-        // Stack Pointer (sp) genellikle r3 veya r4 olarak kullanılır, ABI'ye bakılmalı. Varsayımsal olarak r3.
-        // LoongArch ABI'ye göre sp'nin ilk değeri ayarlanmalı. Stack top'ı kullanıyoruz.
-        if let Some(sp_reg_idx) = LoongArchContext::get_sp_register_index() {
-             context.gprs[sp_reg_idx] = stack_top;
-        } else {
-             // TODO: SP register indeksi bulunamadı, hata durumu veya panic yönetimi
-             panic!("LoongArch SP register index not defined!");
-        }
-
-
-        // Göreve geçirilecek argüman genellikle ilk argüman registerına konur (örn. r4 veya r5, ABI'ye bakılmalı). Varsayımsal olarak r4.
-        if let Some(arg0_reg_idx) = LoongArchContext::get_arg0_register_index() {
-             context.gprs[arg0_reg_idx] = arg;
-        } else {
-             // TODO: Arg0 register indeksi bulunamadı, hata durumu veya panic yönetimi
-        }
-
+const _: () = assert!(size_of::<LoongArchContext>() <= ktask::ARCH_CONTEXT_SIZE);
+
+global_asm!(
+    r#"
+    .section .text
+    .global loongarch_switch_context
+    # a0: önceki bağlam (*mut LoongArchContext), a1: sonraki bağlam
+loongarch_switch_context:
+    st.d $ra, $a0, 0
+    st.d $sp, $a0, 8
+    st.d $fp, $a0, 16
+    st.d $s0, $a0, 24
+    st.d $s1, $a0, 32
+    st.d $s2, $a0, 40
+    st.d $s3, $a0, 48
+    st.d $s4, $a0, 56
+    st.d $s5, $a0, 64
+    st.d $s6, $a0, 72
+    st.d $s7, $a0, 80
+    st.d $s8, $a0, 88
+
+    ld.d $ra, $a1, 0
+    ld.d $sp, $a1, 8
+    ld.d $fp, $a1, 16
+    ld.d $s0, $a1, 24
+    ld.d $s1, $a1, 32
+    ld.d $s2, $a1, 40
+    ld.d $s3, $a1, 48
+    ld.d $s4, $a1, 56
+    ld.d $s5, $a1, 64
+    ld.d $s6, $a1, 72
+    ld.d $s7, $a1, 80
+    ld.d $s8, $a1, 88
+    jr $ra
+
+    .global loongarch_thread_trampoline
+    # s0: giriş, s1: argüman, s2: kullanıcı yığını, s3: çekirdek yığın tepesi, s4: kullanıcı modu mu,
+    # s5: kullanıcı TLS işaretçisi
+loongarch_thread_trampoline:
+    move $a0, $s1
+    bnez $s4, 1f
+    # Çekirdek iş parçacığı: CRMD.IE'yi aç ve girişe atla (geri dönmez).
+    li.d $t0, {crmd_ie}
+    csrxchg $t0, $t0, 0x0
+    jr $s0
+1:
+    # Kullanıcı iş parçacığı: SAVE0 = çekirdek yığını, PRMD = kullanıcı + PIE, ERA = giriş, sonra ertn.
+    csrwr $s3, 0x30
+    li.d $t0, {prmd}
+    csrwr $t0, 0x1
+    csrwr $s0, 0x6
+    move $sp, $s2
+    move $tp, $s5
+    ertn
+"#,
+    crmd_ie = const CRMD_IE,
+    prmd = const PRMD_USER,
+);
+
+extern "C" {
+    fn loongarch_switch_context(prev: *mut LoongArchContext, next: *const LoongArchContext);
+    fn loongarch_thread_trampoline();
+}
 
-        // Dönüş adresi (ra) genellikle r1 olarak kullanılır, ABI'ye bakılmalı.
-        // Görev ana fonksiyonundan dönüldüğünde çalışacak bir çekirdek fonksiyonunun adresi buraya konulmalıdır
-        // (örneğin görev sonlandırma işleyicisi).
-        if let Some(ra_reg_idx) = LoongArchContext::get_ra_register_index() {
-            // TODO: Gerçek görev sonlandırma çekirdek fonksiyonunun adresini al
-            let task_exit_kernel_handler_address: u64 = 0xTODO_GET_TASK_EXIT_HANDLER_ADDRESS;
-            context.gprs[ra_reg_idx] = task_exit_kernel_handler_address;
-        } else {
-             // TODO: RA register indeksi bulunamadı
-        }
+/// ktask::ArchTask'ın LoongArch64 implementasyonu.
+pub struct LoongArchTask;
+
+pub static ARCH_TASK: LoongArchTask = LoongArchTask;
+
+impl ArchTask for LoongArchTask {
+    unsafe fn init_context(&self, ctx: *mut u8, start: &ThreadStart) {
+        let ctx = &mut *(ctx as *mut LoongArchContext);
+        ctx.ra = loongarch_thread_trampoline as usize as u64;
+        ctx.sp = start.kernel_stack_top;
+        ctx.s[0] = start.entry;
+        ctx.s[1] = start.arg;
+        ctx.s[2] = start.user_stack_top;
+        ctx.s[3] = start.kernel_stack_top;
+        ctx.s[4] = start.user as u64;
+        ctx.s[5] = start.tls;
+    }
 
+    unsafe fn switch_context(&self, prev: *mut u8, next: *const u8) {
+        loongarch_switch_context(prev as *mut LoongArchContext, next as *const LoongArchContext);
+    }
 
-        // Ayrıcalık seviyesini ayarla (CSR_CRMD veya benzeri register).
-        // LoongArch'ta kullanıcı modu ve çekirdek modu için uygun bitleri ayarla.
-        // TODO: LoongArch CSR_CRMD (veya ilgili) registerındaki kullanıcı/çekirdek mod bayraklarını ayarla
-        if is_user {
-            // Kullanıcı modu bayraklarını ayarla
-             context.csr_crmd |= LOONGARCH_CRMD_USER_MODE_BITS; // Yer tutucu
-        } else {
-            // Çekirdek modu bayraklarını ayarla (genellikle zaten çekirdek modunda başlarız)
-             context.csr_crmd |= LOONGARCH_CRMD_KERNEL_MODE_BITS; // Yer tutucu
+    unsafe fn activate_address_space(&self, root: u64) {
+        // Çekirdek eşlemeleri PGDH'de (veya doğrudan eşleme pencerelerinde); PGDL yalnızca kullanıcı yarısıdır.
+        if root == ktask::KERNEL_ADDRESS_SPACE {
+            return;
         }
-
-        // TODO: Kesme durumunu ayarla (genellikle başlangıçta kesmeler kapalı olmalı)
-         context.csr_crmd &= !LOONGARCH_CRMD_INTERRUPT_ENABLE_BIT; // Yer tutucu
-
-        // TODO: Eğer FPU/VPU kullanılıyorsa, ilgili durum registerlarını ayarla
-
-        context
+        asm!(
+            "csrwr {0}, 0x19",
+            "invtlb 0x0, $zero, $zero",
+            "dbar 0",
+            inout(reg) root => _,
+            options(nostack),
+        );
     }
 
-    /// LoongArch ABI'ye göre SP register indeksini döndürür (Yer Tutucu).
-    #[inline(always)]
-    fn get_sp_register_index() -> Option<usize> {
-        // TODO: LoongArch ABI'ye göre SP register indeksini (0-31 arası) döndür.
-        // LoongArch Dokümantasyonuna bakın (genellikle r3 veya r4).
-         Some(3) // Örnek olarak 3 diyelim, doğru indeksi kontrol edin.
+    unsafe fn program_timer(&self, deadline_ns: u64) {
+        // TCFG göreli sayar: InitVal sıfıra inince kesme üretir. Geçmişteki bir an için en küçük
+        // değer yazılır; yukarı yuvarlanır, kesme `deadline_ns`'ten önce gelmemeli.
+        let delta_ns = deadline_ns.saturating_sub(ktask::now_ns());
+        let ticks = (delta_ns as u128 * stable_counter_hz() as u128)
+            .div_ceil(1_000_000_000)
+            .clamp(TCFG_INITVAL_MIN as u128, timer_initval_max() as u128) as u64;
+        asm!("csrwr {0}, 0x41", inout(reg) (ticks & !0x3) | TCFG_EN => _, options(nostack));
     }
+}
 
-     /// LoongArch ABI'ye göre RA register indeksini döndürür (Yer Tutucu).
-     #[inline(always)]
-     fn get_ra_register_index() -> Option<usize> {
-         // TODO: LoongArch ABI'ye göre RA register indeksini (0-31 arası) döndür.
-         // LoongArch Dokümantasyonuna bakın (genellikle r1).
-          Some(1) // Örnek olarak 1 diyelim, doğru indeksi kontrol edin.
-     }
-
-     /// LoongArch ABI'ye göre ilk argüman register indeksini döndürür (Yer Tutucu).
-     #[inline(always)]
-     fn get_arg0_register_index() -> Option<usize> {
-         // TODO: LoongArch ABI'ye göre ilk argüman register indeksini (0-31 arası) döndür.
-         // LoongArch Dokümantasyonuna bakın (genellikle r4 veya r5).
-          Some(4) // Örnek olarak 4 diyelim, doğru indeksi kontrol edin.
-     }
-
-
-    // TODO: Gerçek bağlam değiştirme assembly fonksiyonunu dışarıdan tanımla.
-    // Bu fonksiyon, bu dosyanın yanında veya ayrı bir assembly dosyasında (örn. `loongarch_asm.S`) implemente edilir.
-    // Parametreleri, mevcut bağlam yapısının pointer'ı ve geçiş yapılacak bağlam yapısının pointer'ı olmalıdır.
-    // Fonksiyon, mevcut bağlamı ilk parametredeki adrese kaydetmeli, ikinci parametredeki adresten yeni bağlamı yüklemeli
-    // ve yeni bağlamın `csr_era` adresine atlamalıdır.
-    extern "C" {
-        /// LoongArch'a özgü düşük seviye bağlam değiştirme fonksiyonu.
-        /// Mevcut bağlamı `current` pointer'ına kaydeder, `next` pointer'ından yeni bağlamı yükler
-        /// ve yeni bağlama atlar.
-        ///
-        /// # Safety
-        /// Bu fonksiyonun çağrılması **güvenli değildir** ve sadece çekirdek zamanlayıcısı gibi
-        /// düşük seviye kodlardan, sağlanan pointer'ların geçerli `LoongArchContext` yapılarına
-        /// işaret ettiği garanti edildiğinde çağrılmalıdır.
-        fn loongarch_context_switch(current: *mut LoongArchContext, next: *const LoongArchContext);
-
-        /// LoongArch'a özgü düşük seviye kullanıcı moduna geçiş fonksiyonu.
-        /// Çekirdek modundan kullanıcı moduna ilk geçişi yapar.
-        /// Verilen bağlamın stack'ini, entry point'ini ve ayrıcalık seviyesini kurar ve o adrese atlar.
-        /// Bu fonksiyon geri dönmez (`!` dönüş tipi).
-        ///
-        /// # Safety
-        /// Bu fonksiyonun çağrılması **güvenli değildir** ve sadece çekirdek görev başlatma
-        /// kodu gibi düşük seviye kodlardan çağrılmalıdır. Bağlamın doğru kurulduğundan,
-        /// MMU'nun doğru ayarlandığından (eğer sanal adres kullanılıyorsa) emin olunmalıdır.
-        fn loongarch_enter_user_mode(initial_context: *const LoongArchContext) -> !;
+/// Sabit sayacın (ve sabit zamanlayıcının) frekansı; srctime_loongarch64.rs::stable_counter_hz ile
+/// aynı hesap: CPUCFG 4 (CC_FREQ) × CPUCFG 5 bit 15:0 (CC_MUL) / bit 31:16 (CC_DIV).
+fn stable_counter_hz() -> u64 {
+    let (base, ratio): (u64, u64);
+    unsafe {
+        asm!("cpucfg {0}, {1}", out(reg) base, in(reg) 4u64, options(nomem, nostack));
+        asm!("cpucfg {0}, {1}", out(reg) ratio, in(reg) 5u64, options(nomem, nostack));
     }
+    let (mul, div) = (ratio & 0xFFFF, (ratio >> 16) & 0xFFFF);
+    if mul == 0 || div == 0 { base } else { base * mul / div }
 }
 
-
-// --- Çekirdek Ktask Modülü Tarafından Kullanılan LoongArch Spesifik Fonksiyonlar ---
-// Bu fonksiyonlar, karnal64.rs içindeki ktask modülünün (veya genel görev yöneticisinin)
-// mimariye bağımlı işlemleri gerçekleştirmek için çağıracağı arayüzü oluşturur.
-
-/// Yeni bir LoongArch görev/iş parçacığı için başlangıç bağlamını hazırlar.
-/// Genel görev yöneticisi bu fonksiyonu çağırır.
-///
-/// # Arguments
-/// * `entry_point`: Görev kodunun başlayacağı sanal adres.
-/// * `stack_base`: Görev stack'inin ayrıldığı bellek alanının başlangıcı.
-/// * `stack_size`: Görev stack'inin boyutu.
-/// * `arg`: Göreve geçirilecek argüman.
-/// * `is_user`: Kullanıcı (true) veya çekirdek (false) görevi mi olduğunu belirtir.
-///
-/// # Returns
-/// Başarılı olursa ilk `LoongArchContext` yapısını, hata durumunda `KError` döner.
-pub fn arch_create_task_context(
-    entry_point: u64,
-    stack_base: u64,
-    stack_size: usize,
-    arg: u64,
-    is_user: bool,
-) -> Result<LoongArchContext, KError> {
-    // TODO: stack_base ve stack_size'ın geçerli bellek alanlarını temsil ettiğini doğrula?
-    // Bu doğrulama genellikle bellek yöneticisi (kmemory) veya daha üst katmanda yapılır.
-    // TODO: entry_point adresinin geçerli ve yürütülebilir bir alanda olduğunu doğrula?
-
-    let stack_top = stack_base.checked_add(stack_size as u64) // Stack yukarıdan aşağı büyüyorsa son adresi
-        .ok_or(KError::InvalidArgument)?; // Taşma kontrolü
-
-    // Stack pointer genellikle stack top'a veya biraz altına ayarlanır.
-    // ABI'ye göre tam başlangıç değeri belirlenmelidir.
-    // LoongArchContext::create_initial_context stack_top'ı bekliyor.
-
-    Ok(LoongArchContext::create_initial_context(entry_point, stack_top, arg, is_user))
+/// TCFG.InitVal'ın alabileceği en büyük değer. Sayaç genişliği PRCFG1.TimerBits'tedir (bit 12:4, genişlik - 1).
+fn timer_initval_max() -> u64 {
+    let prcfg1: u64;
+    unsafe { asm!("csrrd {0}, 0x21", out(reg) prcfg1, options(nomem, nostack)) };
+    let bits = ((prcfg1 >> 4) & 0x1FF) + 1;
+    if bits >= 64 { u64::MAX & !0x3 } else { ((1 << bits) - 1) & !0x3 }
 }
 
-/// İki LoongArch görevi/iş parçacığı arasında bağlam değiştirmeyi gerçekleştirir.
-/// Genel zamanlayıcı tarafından, hangi görevin durdurulup hangisinin çalıştırılacağına karar verildikten sonra çağrılır.
-///
-/// # Arguments
-/// * `current_context`: Mevcut (ayrılacak) görevin `LoongArchContext` yapısının mutable pointer'ı.
-///                                                    Mevcut görev durdurulduğunda registerlar bu adrese kaydedilecektir.
-/// * `next_context`: Çalıştırılacak görevin `LoongArchContext` yapısının immutable pointer'ı.
-///                                                    Yeni görev başladığında registerlar buradan yüklenecektir.
-///
-/// # Safety
-/// Bu fonksiyon **güvenli değildir**. Çağıran kod, sağlanan pointer'ların geçerli,
-/// tahsis edilmiş ve doğru `LoongArchContext` yapılarına işaret ettiğinden ve bu yapılara
-/// eş zamanlı erişim sorunları olmayacağından emin olmalıdır (genellikle zamanlayıcı kilitleri altında çalışır).
-#[inline] // Bağlam değiştirme kritik yol olduğu için inlining faydalı olabilir
-pub unsafe fn arch_switch_context(
-    current_context: *mut LoongArchContext,
-    next_context: *const LoongArchContext,
-) {
-    // TODO: Düşük seviye assembly bağlam değiştirme fonksiyonunu çağır.
-    // loongarch_context_switch fonksiyonu ayrı bir assembly dosyasında implemente edilmelidir.
-    // Bu çağrı, mevcut görev bağlamını kaydeder ve yeni görev bağlamını yükleyip yeni göreve atlar.
-    loongarch_context_switch(current_context, next_context);
-
-    // Bu noktaya sadece bir başka görev (daha önce durdurulan 'current_context' görevi)
-    // bu fonksiyona yapılan bir çağrı ile geri döndüğünde ulaşılır.
+/// Sabit zamanlayıcı kesmesi (ESTAT.IS bit 11). Bekleyen kesme TICLR.CLR ile temizlenir;
+/// ktask::timer_tick zamanlayıcıyı bir sonraki olaya yeniden kurar.
+pub fn handle_timer_interrupt() {
+    unsafe { asm!("csrwr {0}, 0x44", inout(reg) TICLR_CLR => _, options(nostack)) };
+    ktask::timer_tick(ktask::now_ns());
 }
 
-// TODO: İlk görev başlatılırken kullanılan özel fonksiyon.
-// Normal bağlam değiştirmeden farklı olabilir çünkü çekirdek stack'inden
-// kullanıcı stack'ine ve çekirdek modundan kullanıcı moduna geçiş içerir.
-/// İlk kez bir görevi çalıştırmak için LoongArch donanım bağlamını kurar ve kullanıcı moduna geçer.
-/// Bu fonksiyon çekirdek zamanlayıcısı tarafından, ilk defa bir kullanıcı görevini başlatırken çağrılır.
-///
-/// # Arguments
-/// * `initial_context`: Çalıştırılacak görevin `arch_create_task_context` ile hazırlanmış ilk bağlam yapısı.
-///
-/// # Safety
-/// Bu fonksiyon **güvenli değildir**. Çekirdekten kullanıcı alanına son derece dikkatli bir geçiş yapar.
-/// Bağlam yapısının doğruluğu, MMU ayarları (varsa), ayrıcalık seviyesinin doğru ayarlanması ve
-/// kesme/istisna vektörlerinin doğru kurulmuş olması kritiktir. Yanlış implementasyonlar güvenlik açıklarına yol açar.
-pub unsafe fn arch_enter_user_mode(
-    initial_context: LoongArchContext // Bağlamı değer olarak alıyoruz, çünkü assembly fonksiyonuna pointer'ını vereceğiz
-) -> ! // Bu fonksiyon başarılı olursa geri dönmez
-{
-    // TODO: Düşük seviye assembly kullanıcı moduna geçiş fonksiyonunu çağır.
-    // loongarch_enter_user_mode fonksiyonu ayrı bir assembly dosyasında implemente edilmelidir.
-    // Bu fonksiyon, sağlanan bağlamdaki stack, entry point ve CSR'ları kullanarak
-    // kullanıcı moduna geçişi ve ilk talimata atlamayı yapar.
-    loongarch_enter_user_mode(&initial_context as *const LoongArchContext);
-
-    // Bu noktaya asla ulaşılmamalıdır. Eğer ulaşılırsa bir hata var demektir.
-     panic!("arch_enter_user_mode returned!"); // Geliştirme sırasında hata ayıklama için eklenebilir
+/// LoongArch mimari katmanını ktask'a kaydeder ve sabit zamanlayıcı kesmesini açar. Zamanlayıcı
+/// ktask ilk kez kurana kadar (program_timer) kesme üretmez. Platform başlatma kodu
+/// karnal64::init()'ten sonra çağırır.
+pub fn init() {
+    unsafe { asm!("csrxchg {0}, {1}, 0x4", inout(reg) ECFG_LIE_TIMER => _, in(reg) ECFG_LIE_TIMER, options(nostack)) };
+    ktask::register_arch(&ARCH_TASK);
 }
-
-
-// TODO: Diğer LoongArch spesifik task/thread yönetimi fonksiyonları (gereksinime göre eklenebilir)
-// - `arch_get_current_thread_id() -> KThreadId`: Mevcut LoongArch Thread ID'sini döndürür.
-// - `arch_setup_kernel_thread_context(...) -> LoongArchContext`: Çekirdek modunda çalışan bir iş parçacığı için bağlam hazırlar.
-// - `arch_setup_syscall_stack(...)`: Sistem çağrısı sırasında kullanıcı stack'inin çekirdek stack'ine nasıl kaydedileceğini veya erişileceğini yönetir.
-// - `arch_restore_syscall_stack(...)`: Sistem çağrısı dönüşünde stack'i geri yükler.
-// - `arch_yield()`: Mevcut iş parçacığının bilerek CPU'yu bırakmasını sağlar (zamanlayıcıyı çağırır).
-// - Kesme işleyicileri içinde bağlam kaydetme/geri yükleme fonksiyonları.
-
-// --- Yardımcı Sabitler (LoongArch ABI'ye göre doldurulmalı) ---
-// TODO: LoongArch CRMD registerındaki bit bayrakları gibi mimariye özgü sabitler
- pub const LOONGARCH_CRMD_USER_MODE_BITS: u64 = ...;
- pub const LOONGARCH_CRMD_KERNEL_MODE_BITS: u64 = ...;
- pub const LOONGARCH_CRMD_INTERRUPT_ENABLE_BIT: u64 = ...;
@@ -52,6 +52,41 @@ fn read_loongarch_hardware_timer() -> u64 {
     }
 }
 
+// Sabit sayacın frekansı: CPUCFG 4 (CC_FREQ, temel frekans) × CPUCFG 5 bit 15:0 (CC_MUL) /
+// bit 31:16 (CC_DIV). İlk okumada hesaplanıp saklanır.
+static STABLE_COUNTER_HZ: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+
+fn stable_counter_hz() -> u64 {
+    use core::sync::atomic::Ordering;
+
+    let cached = STABLE_COUNTER_HZ.load(Ordering::Relaxed);
+    if cached != 0 {
+        return cached;
+    }
+    let (base, ratio): (u64, u64);
+    unsafe {
+        core::arch::asm!("cpucfg {0}, {1}", out(reg) base, in(reg) 4u64, options(nomem, nostack));
+        core::arch::asm!("cpucfg {0}, {1}", out(reg) ratio, in(reg) 5u64, options(nomem, nostack));
+    }
+    let (mul, div) = (ratio & 0xFFFF, (ratio >> 16) & 0xFFFF);
+    let hz = if mul == 0 || div == 0 { base } else { base * mul / div };
+    STABLE_COUNTER_HZ.store(hz, Ordering::Relaxed);
+    hz
+}
+
+/// Boot'tan beri geçen monoton süre (hardware_specific.h): sabit sayaç (`rdtime.d`), nanosaniyeye
+/// çevrilir. ktask zamanlayıcısı ve CPU süresi muhasebesi bu kancayı kullanır.
+#[no_mangle]
+pub extern "C" fn low_level_monotonic_time_ns() -> u64 {
+    let hz = stable_counter_hz();
+    if hz == 0 {
+        return 0; // CPUCFG sayaç frekansını bildirmiyor
+    }
+    let (count, _counter_id): (u64, u64);
+    unsafe { core::arch::asm!("rdtime.d {0}, {1}", out(reg) count, out(reg) _counter_id, options(nomem, nostack)) };
+    ((count as u128 * 1_000_000_000) / hz as u128) as u64
+}
+
 // --- Karnal64 ResourceProvider Implementasyonu ---
 
 /// LoongArchTimeSource için ResourceProvider trait'ini implemente ediyoruz.
@@ -76,6 +76,9 @@ const CAUSE_EXCCODE_SHIFT: usize = 2;
 const CAUSE_EXCCODE_MASK: u64 = 0x1F; // 5 bit exception code
 const CAUSE_INTERRUPT_PENDING_MASK: u64 = 0xFF00; // IP0-IP7
 
+// Status registerında KSU alanının kullanıcı modu biti (KSU = 0b10)
+const STATUS_KSU_USER: u64 = 1 << 4;
+
 // Önemli MIPS Exception Kodları (Cause Register)
 const EXCCODE_INTERRUPT: u64 = 0; // Interrupt
 const EXCCODE_TLB_MODIFIED: u64 = 1; // TLB Modified
@@ -112,6 +115,8 @@ pub extern "C" fn handle_exception(frame: *mut TrapFrame) {
         EXCCODE_INTERRUPT => {
             // Kesme (Interrupt) İşleyici
             let pending_interrupts = (tf.cause & CAUSE_INTERRUPT_PENDING_MASK) >> 8;
+            // `ksync` burada yer tutucu modüldür, lockdep gerçek modülden gelir.
+            crate::karnal64::ksync::lockdep::irq_enter();
 
             // TODO: Donanım platformuna özel kesme denetleyicisi (interrupt controller)
             // ile etkileşime girerek kesmenin kaynağını (timer, IO, vb.) belirle.
@@ -123,15 +128,18 @@ pub extern "C" fn handle_exception(frame: *mut TrapFrame) {
 
                 // TODO: Zamanlayıcı donanımını tekrar ayarla (next tick)
 
-                // Karnal64 zamanlayıcısını çağır. Bu bağlam değiştirmeye neden olabilir.
-                // schedule fonksiyonu, dönecek TrapFrame'i güncelleyebilir.
-                // Örneğin, mevcut task'ın frame'ini kaydedip, bir sonraki task'ın frame'ini yükleyebilir.
-                ktask::schedule(tf); // tf'yi schedule'a geçirerek bağlam değişimini yönetmesini sağla
+                // Süresi dolan bekleyenleri uyandır, zaman dilimini/öncelikleri kontrol et.
+                ktask::timer_tick(ktask::now_ns());
             }
 
             // TODO: Diğer kesme seviyeleri (IP0-IP6) için işleyicileri çağır
 
             // EPC'yi artırmaya gerek yok, kesme aynı instruksiyonu tekrar çalıştırır.
+            crate::karnal64::ksync::lockdep::irq_exit();
+
+            // Gerekirse daha önemli bir iş parçacığına burada geçilir; tf bu iş parçacığının
+            // çekirdek yığınında olduğundan araya giren bağlam değişimi onu bozmaz.
+            ktask::preempt_on_interrupt_exit();
         }
         EXCCODE_SYSCALL => {
             // Sistem Çağrısı (Syscall) İşleyici
@@ -267,16 +275,12 @@ pub extern "C" fn handle_exception(frame: *mut TrapFrame) {
                 }
                 Err(e) => {
                     // Hata düzeltilemedi (geçersiz adres, izin yok vb.).
-                    // Bu genellikle mevcut görevin sonlandırılması anlamına gelir.
-                    // Karnal64 görev yöneticisini çağırarak görevi sonlandır.
-                    // terminate_current_task fonksiyonu normalde geri dönmez.
-                    ktask::terminate_current_task(e);
-                    // Eğer terminate_current_task geri dönerse (ki dönmemeli),
-                    // burada bir panic veya kurtarılamaz hata işleme yapılmalıdır.
-                     loop {} // Beklenmedik dönüş durumunda sonsuz döngü/kernel panic
+                    // Görevin istisna portuna ilet; port yoksa görev sonlandırılır.
+                    // İşleyici devam ettirirse tf onun yazmaçlarıyla güncellenmiştir.
+                    deliver_user_fault(e, exception_code, bad_vaddr, tf);
                 }
             }
-            // EPC artırılmaz, faulted instruksiyon tekrar denenecek.
+            // EPC artırılmaz (işleyici değiştirmediyse), faulted instruksiyon tekrar denenecek.
         }
         EXCCODE_ADDRESS_ERROR_LOAD_FETCH | EXCCODE_ADDRESS_ERROR_STORE |
         EXCCODE_BUS_ERROR_FETCH | EXCCODE_BUS_ERROR_DATA => {
@@ -287,12 +291,8 @@ pub extern "C" fn handle_exception(frame: *mut TrapFrame) {
              println!("Address/Bus Error at EPC: {:#x}, BadVAddr: {:#x}, Cause: {:#x}",
                       tf.epc, tf.bad_vaddr, tf.cause); // Çekirdek içi print!
 
-            // Hata genellikle mevcut görevin sonlandırılmasıyla sonuçlanır.
-            // Karnal64 görev yöneticisini çağırarak görevi sonlandır.
-            // Uygun bir hata kodu kullanın.
-            ktask::terminate_current_task(KError::BadAddress);
-            // terminate_current_task geri dönmez.
-             loop {} // Beklenmedik dönüş durumunda sonsuz döngü/kernel panic
+            // Görevin istisna portuna ilet; port yoksa görev sonlandırılır.
+            deliver_user_fault(KError::BadAddress, exception_code, bad_vaddr, tf);
         }
         EXCCODE_BREAKPOINT | EXCCODE_RESERVED_INSTRUCTION |
         EXCCODE_COPROCESSOR_UNUSABLE | EXCCODE_OVERFLOW | EXCCODE_TRAP |
@@ -303,11 +303,9 @@ pub extern "C" fn handle_exception(frame: *mut TrapFrame) {
              println!("Unhandled Exception Code {} at EPC: {:#x}, Cause: {:#x}",
                       exception_code, tf.epc, tf.cause); // Çekirdek içi print!
 
-            // Bu istisnalar genellikle programlama hatası veya donanım sorunudur.
-            // Mevcut görevi sonlandır.
-            ktask::terminate_current_task(KError::InternalError); // Veya daha spesifik bir hata kodu
-            // terminate_current_task geri dönmez.
-             loop {} // Beklenmedik dönüş durumunda sonsuz döngü/kernel panic
+            // Bu istisnalar genellikle programlama hatası veya donanım sorunudur (breakpoint
+            // hata ayıklayıcılar içindir). Görevin istisna portuna ilet; port yoksa görev sonlandırılır.
+            deliver_user_fault(KError::InternalError, exception_code, 0, tf);
         }
         _ => {
             // Bilinmeyen Exception Kodu
@@ -328,6 +326,22 @@ pub extern "C" fn handle_exception(frame: *mut TrapFrame) {
     // Eğer bir görev sonlandırıldıysa, buraya asla ulaşılmaz.
 }
 
+/// Çözülemeyen bir kullanıcı modu istisnasını ktask istisna portuna iletir. İşleyici iş parçacığını
+/// devam ettirirse (değiştirmiş olabileceği) yazmaçlar tf'ye geri yazılır. Status geri yazılmaz:
+/// KSU/EXL/IE alanları çekirdeğin denetimindedir.
+fn deliver_user_fault(reason: KError, exception_code: u64, address: u64, tf: &mut TrapFrame) {
+    // Çekirdek modundaki bir hatanın yazmaçları kullanıcı işleyicisine gösterilmez.
+    if tf.status & STATUS_KSU_USER == 0 {
+        ktask::terminate_current_task(reason);
+    }
+    let mut regs = ktask::FaultRegisters { pc: tf.epc, sp: tf.regs[29], status: tf.status, gpr: tf.regs };
+    ktask::deliver_fault(reason, exception_code, address, &mut regs);
+    tf.epc = regs.pc;
+    tf.regs = regs.gpr;
+    tf.regs[0] = 0; // $zero
+    tf.regs[29] = regs.sp;
+}
+
 // --- Placeholder Çekirdek Modülü Fonksiyonları (Çağrılacak olanlar) ---
 // Bu fonksiyonların Karnal64 modülleri içinde tanımlı ve public olması gerekir.
 
@@ -353,41 +367,8 @@ mod kmemory {
     // ama Karnal64 API'sı içinde tanımlı olmalılar.
 }
 
-// ktask module:
-mod ktask {
-    use super::*; // srcexception_mips.rs scope'undaki tipleri kullan
-
-    // Görev zamanlayıcısını çalıştırır.
-    // Gerekirse bağlam değişimini yönetir.
-    // frame pointer'ını alması, mevcut task'ın durumunu kaydetmesi ve
-    // bir sonraki task'ın durumunu yüklemesi için gereklidir.
-    pub fn schedule(frame: *mut TrapFrame) {
-        // TODO: Görev zamanlayıcı algoritmasını çalıştır (round-robin, priority vb.).
-        // Mevcut task'ın durumunu frame'den kendi task_control_block'una kaydet.
-        // Çalıştırılacak bir sonraki task'ı seç.
-        // Seçilen task'ın durumunu (kayıtlı TrapFrame'ini) frame'e yükle.
-        // Bu fonksiyon döndüğünde, assembly kodu frame'deki yeni durumu restore edecek.
-         println!("Ktask: Scheduling..."); // Yer Tutucu
-        // Bağlam değişimi simülasyonu (gerçekte burada task'lar değişir ve frame güncellenir)
-    }
-
-    // Mevcut görevi sonlandırır.
-    // Normalde geri dönmez.
-    pub fn terminate_current_task(error_code: KError) -> ! {
-        // TODO: Mevcut task'ı sonlandırma mantığı.
-        // Görevi zamanlayıcı kuyruklarından kaldır.
-        // Sahip olduğu kaynakları (handle'lar, bellek) serbest bırak.
-        // Çıkış kodunu (error_code) ata.
-        // Bir sonraki task'ı zamanlayıcıdan seç ve ona geçiş yap.
-         println!("Ktask: Terminating current task with error: {:?}", error_code); // Yer Tutucu
-        // Normalde burada bir bağlam değişimi olur ve bu fonksiyon asla dönmez.
-        // Simülasyon için bir panik veya sonsuz döngü koyabiliriz.
-         loop {} // Görev sonlandığında bu noktaya gelinmemeli, sonsuz döngüye girilmeli
-    }
-
-    // Diğer ktask fonksiyonları (spawn, exit - bu syscall handler'da kullanılır, get_id vb.)
-    // burada kullanılmayabilir ama Karnal64 API'sı içinde tanımlı olmalılar.
-}
+// ktask: görev sonlandırma, zamanlayıcı ve istisna portları gerçek karnal64::ktask'tan gelir
+// (bkz. src/task/srctask.rs, src/task/srcexcport.rs).
 
 // kkernel module:
 mod kkernel {
@@ -219,3 +219,61 @@ pub extern "C" fn handle_exception(frame: &mut TrapFrame) {
         }
     }
 }
+
+// --- Karnal64 Kesme Maskesi Kancaları (hardware_specific.h) ---
+// ksync::Spinlock::lock_irqsave ve çekirdek genelindeki kesme aç/kapa işlemleri bunları kullanır.
+// Kaydedilen durum Status.IE bitidir (CP0 $12, bit 0). MIPS64r2 `di`/`ei` yalnızca IE'yi değiştirir;
+// `ehb` değişikliğin sonraki komutlardan önce görünmesini sağlar.
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_enable() {
+    unsafe { core::arch::asm!("ei", "ehb", options(nomem, nostack)) };
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_disable() {
+    unsafe { core::arch::asm!("di", "ehb", options(nomem, nostack)) };
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupts_enabled() -> i32 {
+    let status: u64;
+    unsafe { core::arch::asm!("mfc0 {0}, $12", out(reg) status, options(nomem, nostack)) };
+    (status & SR_IE != 0) as i32
+}
+
+/// Kesmeleri kapatır ve önceki IE bitini döndürür (`di` eski Status'u döndürür).
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_save() -> u64 {
+    let status: u64;
+    unsafe { core::arch::asm!("di {0}", "ehb", out(reg) status, options(nomem, nostack)) };
+    status & SR_IE
+}
+
+/// `low_level_interrupt_save` ile kaydedilen IE bitini geri yükler.
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_restore(flags: u64) {
+    if flags & SR_IE != 0 {
+        low_level_interrupt_enable();
+    }
+}
+
+// --- Karnal64 CPU Kimliği Kancası (hardware_specific.h) ---
+
+/// Çağıran CPU'nun mantıksal indeksi: EBase.CPUNum (CP0 $15 seçim 1, bit 9:0), ktask kaydıyla
+/// eşlenir. Kayıtsız CPU'lar 0 döner.
+#[no_mangle]
+pub extern "C" fn low_level_cpu_id() -> u32 {
+    let ebase: u64;
+    unsafe { core::arch::asm!("mfc0 {0}, $15, 1", out(reg) ebase, options(nomem, nostack)) };
+    karnal64::ktask::cpu_index(ebase & 0x3FF).unwrap_or(0) as u32
+}
+
+// --- Karnal64 Yığın İzi Kancası (hardware_specific.h) ---
+// MIPS n64 çerçevelerinde $fp/$ra'nın yeri sabit değildir (fonksiyon önsözüne göre değişir),
+// güvenilir bir yürüyüş için önsöz çözümlemesi gerekir. O zamana kadar lockdep raporları yığın
+// izi olmadan yazılır.
+#[no_mangle]
+pub extern "C" fn low_level_stack_trace(_frames: *mut u64, _max_frames: usize) -> usize {
+    0
+}
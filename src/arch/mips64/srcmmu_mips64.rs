@@ -122,13 +122,19 @@ impl PageFlags {
 
 // --- MIPS MMU Yönetim Fonksiyonları ---
 
-// Kernel'in fiziksel bellek ayırıcısından (kmemory'den gelmeli) sayfa çerçevesi almak için yer tutucu trait.
-// kmemory modülü bu trait'i implemente eden bir nesne sağlamalıdır.
-pub trait PhysFrameAllocator {
-    /// Boş bir fiziksel sayfa çerçevesi tahsis eder.
-    fn allocate_frame(&self) -> Result<PhysicalAddress, MipsMmuError>;
-    /// Daha önce tahsis edilmiş bir fiziksel sayfa çerçevesini serbest bırakır.
-    fn free_frame(&self, paddr: PhysicalAddress) -> Result<(), MipsMmuError>;
+// Sayfa çerçeveleri kmemory'nin buddy ayırıcısından, ortak `FrameAllocator` trait'i üzerinden alınır
+// (bkz. src/memory/srcframe.rs; sistem ayırıcısı `frame_allocator()`). Ayırıcı kendi kilidini
+// tuttuğundan `&dyn` ile paylaşılır.
+pub use crate::karnal64::kmemory::{frame_allocator, FrameAllocator};
+
+impl From<crate::karnal64::KError> for MipsMmuError {
+    fn from(err: crate::karnal64::KError) -> Self {
+        match err {
+            crate::karnal64::KError::OutOfMemory => MipsMmuError::OutOfMemory,
+            crate::karnal64::KError::InvalidArgument => MipsMmuError::InvalidArgument,
+            _ => MipsMmuError::InternalError,
+        }
+    }
 }
 
 // Sayfa tablosu yapısı (MIPS genellikle tersine sayfa tabloları veya segment/sayfa hiyerarşisi kullanabilir)
@@ -11,6 +11,10 @@ use crate::karnal64::{self, KError, KHandle, KTaskId};
 // Belki de Karnal64'ün ResourceProvider gibi trait'lerine de ihtiyacımız olur
 use crate::karnal64::ResourceProvider; // Eğer platforma özgü kaynakları Karnal64'e kaydedeceksek
 
+// ktask mimari katmanı (bağlam kurma/değiştirme, adres alanı etkinleştirme)
+#[path = "srctask_mips64.rs"]
+mod task;
+
 // TODO: MIPS mimarisine özgü donanım registerlarına veya bellek adreslerine
 // erişmek için düşük seviye (unsafe) fonksiyonlar veya makrolar burada tanımlanabilir.
 // Örnek: CP0 registerları, MMU kontrol, kesme kontrol registerları vb.
@@ -74,6 +78,8 @@ pub extern "C" fn mips_boot_init() {
     // Genel Karnal64 çekirdek alt sistemlerini başlat.
     // Karnal64'ün iç yöneticileri (kaynak, görev, bellek vb.) burada başlar.
     karnal64::init();
+    // Zamanlayıcı bağlam değiştirebilsin diye mimari katmanı kaydet.
+    task::init();
 
     println!("Karnal64 init complete. Setting up exception vector...");
 
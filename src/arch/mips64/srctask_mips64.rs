@@ -1,172 +1,198 @@
-#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz.
-
-// Karnal64 API'sından gerekli temel tipleri içe aktar
-// Çekirdeğinizin modül yapısına bağlı olarak 'crate::karnal64' veya 'super::super::karnal64' gibi path değişebilir.
-// Örnek olarak 'crate::karnal64' kullandım, bu karnal64.rs'nin kök seviyede olduğunu varsayar.
-use crate::karnal64::{KTaskId, KThreadId, KError, KHandle};
-// Bellek yönetimi modülünden Task'ın bellek alanını temsil edecek tipi içe aktarabiliriz (varsayımsal)
-use crate::kmemory::TaskMemoryContext; // Karnal64'teki kmemory modülünün bir parçası olduğu varsayılır.
-
-// TODO: MIPS mimarisine özgü register setini tanımlayın.
-// Bağlam değiştirmede (context switching) bu registerların kaydedilmesi ve geri yüklenmesi gerekir.
-#[repr(C)] // C uyumluluğu gerekebilir, mimariye özgü
-#[derive(Debug, Copy, Clone, Default)] // Varsayılan değerler genellikle sıfır olmalı
+// MIPS64 (n64 ABI) için ktask mimari katmanı (ktask::ArchTask):
+// - bağlam kurma: yeni iş parçacığı `mips64_thread_trampoline` üzerinden başlar
+// - bağlam değiştirme: n64'te çağrılan tarafından korunan yazmaçlar (s0-s7, gp, sp, fp, ra)
+//   kaydedilir/yüklenir
+// - adres alanı: TLB refill işleyicisinin yürüdüğü sayfa tablosu kökü `CURRENT_PGD`'ye yazılır
+//   ve eski çeviriler TLB'den silinir
+// - TLS: kullanıcı TLS işaretçisi CP0 UserLocal'dır ($4, sel 2; `rdhwr $29` ile okunur);
+//   bağlamda tutulur ve bağlam değişiminde kaydedilip yüklenir
+// MIPS donanımı kullanıcı modundan gelen istisnada yığın değiştirmez; istisna girişi
+// `KERNEL_STACK_TOP`'taki çekirdek yığınına kendisi geçer.
+
+use core::arch::{asm, global_asm};
+use core::mem::size_of;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::karnal64::ktask::{self, ArchTask, ThreadStart};
+
+/// Kullanıcı moduna geçişte CP0 Status: KSU = kullanıcı (0b10), EXL = 1 (eret temizler), IE = 1.
+const STATUS_USER_BITS: u64 = 0x13;
+/// Status'ta KSU/ERL/EXL/IE alanlarının maskesi.
+const STATUS_MODE_MASK: u64 = 0x1f;
+/// TLB'yi temizlerken kullanılan, eşlenmemiş (KSEG0) benzersiz EntryHi tabanı.
+const TLB_FLUSH_ENTRYHI_BASE: u64 = 0xFFFF_FFFF_8000_0000;
+
+/// MIPS iş parçacığı bağlamı.
+/// Yalnızca çağrılan tarafından korunması gereken yazmaçlar tutulur; geri kalanı
+/// mips64_switch_context çağrısı sırasında zaten çağıranın yığınındadır.
+#[repr(C)] // Assembly'deki ofsetlerle aynı düzen
+#[derive(Debug, Copy, Clone, Default)]
 pub struct MipsRegisters {
-    // Genel amaçlı registerlar (R0-R31) - MIPS'in konvansiyonlarına göre düzenlenmeli
-    // Örneğin, R1 (at), R2-R3 (v0-v1), R4-R7 (a0-a3), vb.
-    // Bağlam değiştirmede kaydedilmesi gereken s-registerları (s0-s7) ve gp, sp, fp, ra gibi registerlar
-    pub gp: u33, // Global Pointer
-    pub sp: u33, // Stack Pointer
-    pub fp: u33, // Frame Pointer / s8
-    pub ra: u33, // Return Address
-
-    // Saved registers (s0-s7) - Bağlam değiştirmede kaydedilir
-    pub s0: u33,
-    pub s1: u33,
-    pub s2: u33,
-    pub s3: u33,
-    pub s4: u33,
-    pub s5: u33,
-    pub s6: u33,
-    pub s7: u33,
-
-    // Caller-saved registers (a0-a3, t0-t9 vb.) - Çağıran tarafından kaydedilmesi beklenir,
-    // ancak thread bağlamında tutulmaları gerekir.
-    // Tam liste ve sıra MIPS ABI'ye göre ayarlanmalı.
-    pub a0: u33,
-    pub a1: u33,
-    pub a2: u33,
-    pub a3: u33,
-    // ... diğer gerekli registerlar (t0-t9, k0-k1, at, v0-v1, zero, vb.)
-    // CP0 registerları: EPC (Exception Program Counter), Status, Cause vb.
-    pub epc: u33,
-    pub status: u32, // COP0 Status Register
-    pub cause: u32,  // COP0 Cause Register
-    // Diğer gerekli COP0 registerları...
-
-    // Floating Point registers (FPRs) - Eğer FPU destekleniyorsa
-     pub fregs: [f64; 32], // fp0-fp31
-     pub fcsr: u32, // FP Control Status Register
+    /// s0-s7. İlk geçişte s0-s4 trampoline başlangıç bilgisini taşır.
+    pub s: [u64; 8],
+    pub gp: u64, // Global Pointer
+    pub sp: u64, // Stack Pointer
+    pub fp: u64, // Frame Pointer / s8
+    pub ra: u64, // Return Address (ilk geçişte trampolin)
+    /// Bu iş parçacığının çekirdek yığınının tepesi.
+    pub kernel_stack_top: u64,
+    /// Kullanıcı TLS işaretçisi (CP0 UserLocal).
+    pub user_local: u64,
 }
 
-// İş Parçacığı Kontrol Bloğu (Thread Control Block - TCB)
-// Kernel'in bir iş parçacığının durumunu izlemek için kullandığı yapı.
-#[derive(Debug)]
-pub struct ThreadControlBlock {
-    pub thread_id: KThreadId,      // Karnal64 tarafından atanan İş Parçacığı ID
-    pub parent_task_id: KTaskId,   // Ait olduğu Görev (Task) ID
-    pub state: ThreadState,        // İş parçacığının mevcut durumu (Running, Blocked, Ready, vb.)
-    pub registers: MipsRegisters,  // Kaydedilmiş CPU registerları (bağlam değiştirmede kullanılır)
-    pub stack_base: *mut u8,       // İş parçacığı yığın (stack) alanının başlangıcı
-    pub stack_size: usize,         // İş parçacığı yığın alanının boyutu
-    pub stack_pointer: u33,        // Yığının mevcut üst noktası (register.sp ile aynı olabilir)
-    // TODO: Zamanlayıcıya özel alanlar (öncelik, kuantum bilgisi vb.)
-    // TODO: Bloklanmışsa beklediği kaynak veya olay bilgisi
-    // TODO: İş parçacığı yerel depolama (Thread Local Storage) bilgisi
+const _: () = assert!(size_of::<MipsRegisters>() <= ktask::ARCH_CONTEXT_SIZE);
+
+/// Çalışan iş parçacığının çekirdek yığın tepesi. Kullanıcıdan gelen istisna girişi buna geçer.
+static KERNEL_STACK_TOP: AtomicU64 = AtomicU64::new(0);
+
+/// TLB refill işleyicisinin yürüdüğü sayfa tablosunun kökü.
+static CURRENT_PGD: AtomicU64 = AtomicU64::new(0);
+
+global_asm!(
+    r#"
+    .section .text
+    .set noreorder
+    .global mips64_switch_context
+    # a0: önceki bağlam (*mut MipsRegisters), a1: sonraki bağlam
+mips64_switch_context:
+    sd $s0, 0($a0)
+    sd $s1, 8($a0)
+    sd $s2, 16($a0)
+    sd $s3, 24($a0)
+    sd $s4, 32($a0)
+    sd $s5, 40($a0)
+    sd $s6, 48($a0)
+    sd $s7, 56($a0)
+    sd $gp, 64($a0)
+    sd $sp, 72($a0)
+    sd $fp, 80($a0)
+    sd $ra, 88($a0)
+
+    ld $s0, 0($a1)
+    ld $s1, 8($a1)
+    ld $s2, 16($a1)
+    ld $s3, 24($a1)
+    ld $s4, 32($a1)
+    ld $s5, 40($a1)
+    ld $s6, 48($a1)
+    ld $s7, 56($a1)
+    ld $gp, 64($a1)
+    ld $sp, 72($a1)
+    ld $fp, 80($a1)
+    ld $ra, 88($a1)
+    jr $ra
+    nop
+
+    .global mips64_thread_trampoline
+    # s0: giriş, s1: argüman, s2: kullanıcı yığını, s3: çekirdek yığın tepesi, s4: kullanıcı modu mu
+mips64_thread_trampoline:
+    move $a0, $s1
+    bnez $s4, 1f
+    nop
+    # Çekirdek iş parçacığı: Status.IE'yi aç ve girişe atla (PIC için t9 = giriş, geri dönmez).
+    mfc0 $t0, $12
+    ori $t0, $t0, 1
+    mtc0 $t0, $12
+    ehb
+    move $t9, $s0
+    jr $t9
+    nop
+1:
+    # Kullanıcı iş parçacığı: EPC = giriş, Status = kullanıcı modu + EXL + IE, sonra eret.
+    mfc0 $t0, $12
+    li $t1, ~{mode_mask}
+    and $t0, $t0, $t1
+    ori $t0, $t0, {user_bits}
+    mtc0 $t0, $12
+    dmtc0 $s0, $14
+    ehb
+    move $sp, $s2
+    eret
+    .set reorder
+"#,
+    mode_mask = const STATUS_MODE_MASK,
+    user_bits = const STATUS_USER_BITS,
+);
+
+extern "C" {
+    fn mips64_switch_context(prev: *mut MipsRegisters, next: *const MipsRegisters);
+    fn mips64_thread_trampoline();
 }
 
-// Görev Kontrol Bloğu (Task Control Block - TCB) - Genellikle Görev = Adres Alanı
-// Kernel'in bir görevin (genellikle bir proses/uygulama) durumunu izlemek için kullandığı yapı.
-#[derive(Debug)]
-pub struct TaskControlBlock {
-    pub task_id: KTaskId,          // Karnal64 tarafından atanan Görev ID
-    // TODO: Göreve ait iş parçacıklarının listesi (LinkedList, Vec gibi no_std uyumlu bir yapı)
-    // pub threads: Vec<KThreadId>,
-    pub memory_context: TaskMemoryContext, // Görevin sanal bellek haritası/bağlamı (Page Table Base gibi)
-    pub exit_code: Option<i32>,    // Eğer görev sonlandıysa çıkış kodu
-    // TODO: Kaynak handle tablosu veya referansı (göreve ait açık handle'lar)
-    // TODO: IPC (Görevler Arası İletişim) bilgisi (mesaj kuyrukları, portlar vb.)
-    // TODO: Güvenlik bağlamı, kullanıcı/grup ID'leri vb.
+/// Tüm TLB girdilerini eşlenmemiş benzersiz adreslerle geçersiz kılar.
+unsafe fn flush_tlb() {
+    let config1: u64;
+    asm!("mfc0 {0}, $16, 1", out(reg) config1, options(nomem, nostack));
+    // Config1.MMUSize (bit 25-30) = girdi sayısı - 1.
+    let entries = ((config1 >> 25) & 0x3f) + 1;
+    for index in 0..entries {
+        let entry_hi = TLB_FLUSH_ENTRYHI_BASE + (index << 13);
+        asm!(
+            "dmtc0 $zero, $2",
+            "dmtc0 $zero, $3",
+            "dmtc0 {hi}, $10",
+            "mtc0 {idx}, $0",
+            "ehb",
+            "tlbwi",
+            hi = in(reg) entry_hi,
+            idx = in(reg) index,
+            options(nostack),
+        );
+    }
+    asm!("ehb", options(nomem, nostack));
 }
 
-// İş Parçacığı Durumları
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum ThreadState {
-    Ready,     // Çalışmaya hazır, zamanlayıcı tarafından seçilmeyi bekliyor
-    Running,   // Şu anda CPU üzerinde çalışıyor (sadece 1 thread/çekirdek aynı anda)
-    Blocked,   // Bir kaynak (kilit, mesaj, I/O vb.) bekliyor
-    Sleeping,  // Belirli bir süre uyuyor
-    Terminated, // Çalışması tamamlandı veya sonlandırıldı
-    // TODO: Diğer durumlar (örn. Suspended)
+/// ktask::ArchTask'ın MIPS64 implementasyonu.
+pub struct Mips64Task;
+
+pub static ARCH_TASK: Mips64Task = Mips64Task;
+
+impl ArchTask for Mips64Task {
+    unsafe fn init_context(&self, ctx: *mut u8, start: &ThreadStart) {
+        let ctx = &mut *(ctx as *mut MipsRegisters);
+        ctx.s[0] = start.entry;
+        ctx.s[1] = start.arg;
+        ctx.s[2] = start.user_stack_top;
+        ctx.s[3] = start.kernel_stack_top;
+        ctx.s[4] = start.user as u64;
+        // Çekirdek iş parçacıkları çekirdeğin gp'sini kullanır.
+        asm!("move {0}, $gp", out(reg) ctx.gp, options(nomem, nostack));
+        ctx.sp = start.kernel_stack_top;
+        ctx.ra = mips64_thread_trampoline as usize as u64;
+        ctx.kernel_stack_top = start.kernel_stack_top;
+        ctx.user_local = start.tls;
+    }
+
+    unsafe fn switch_context(&self, prev: *mut u8, next: *const u8) {
+        let (prev, next) = (prev as *mut MipsRegisters, next as *const MipsRegisters);
+        KERNEL_STACK_TOP.store((*next).kernel_stack_top, Ordering::Relaxed);
+        asm!("dmfc0 {}, $4, 2", out(reg) (*prev).user_local, options(nomem, nostack));
+        asm!("dmtc0 {}, $4, 2", in(reg) (*next).user_local, options(nomem, nostack));
+        mips64_switch_context(prev, next);
+    }
+
+    unsafe fn activate_address_space(&self, root: u64) {
+        // Çekirdek KSEG0/XKPHYS'te eşlemesiz çalışır; çekirdek görevine geçişte kullanıcı çevirileri kalabilir.
+        if root == ktask::KERNEL_ADDRESS_SPACE {
+            return;
+        }
+        if CURRENT_PGD.swap(root, Ordering::Relaxed) != root {
+            flush_tlb();
+        }
+    }
 }
 
-// --- Dahili MIPS Görev/İş Parçacığı Yönetim Fonksiyonları ---
-// Bu fonksiyonlar, karnal64.rs'teki ktask modülünün PUBLIC fonksiyonları tarafından çağırılabilir.
-// Gerçek MIPS donanımıyla etkileşim (register yükleme/kaydetme, bağlam değiştirme) burada olur.
-
-/// Yeni bir görev ve başlangıç iş parçacığı oluşturur.
-/// `code_handle`: Çalıştırılacak kodun bulunduğu kaynak handle'ı.
-/// `args_ptr`, `args_len`: Kullanıcı alanından gelen argüman verisi pointer'ı ve uzunluğu (doğrulama çekirdek API katmanında yapılmalı).
-/// Başarı durumunda yeni görevin ID'sini döner.
-pub fn spawn_new_task_mips(
-    code_handle: KHandle,
-    args_ptr: *const u8, // Kullanıcı alanı pointer'ı
-    args_len: usize,
-) -> Result<KTaskId, KError> {
-    // TODO: Bellek yöneticisinden (kmemory) yeni bir adres alanı (TaskMemoryContext) oluştur.
-    // TODO: code_handle'ı kullanarak kod kaynağını oku ve yeni adres alanına yükle.
-    // TODO: Başlangıç yığını için bellek ayır.
-    // TODO: Başlangıç argümanlarını yığına kopyala.
-    // TODO: Yeni bir KTaskId ve KThreadId oluştur (ktask yöneticisinden alınmalı).
-    // TODO: Bir ThreadControlBlock (TCB) oluştur, başlangıç registerlarını ayarla (stack pointer, entry point/ra, a0-a3/argümanlar).
-    // TODO: Bir TaskControlBlock (TSKCB) oluştur, TCB'yi TSKCB'ye ekle.
-    // TODO: Yeni TCB'yi 'Ready' durumuna getir ve zamanlayıcı kuyruğuna ekle.
-
-    // Yer tutucu: Dummy ID döndür
-    println!("srctask_mips: Yeni görev oluşturma simülasyonu"); // Kernel içi print! gerektirir
-    let dummy_task_id = KTaskId(100 + core::sync::atomic::AtomicU64::new(0).fetch_add(1, core::sync::atomic::Ordering::SeqCst)); // Basit dummy ID
-    Ok(dummy_task_id)
+/// Çalışan iş parçacığının çekirdek yığın tepesi (kullanıcı istisna girişi için).
+pub fn current_kernel_stack_top() -> u64 {
+    KERNEL_STACK_TOP.load(Ordering::Relaxed)
 }
 
-/// Mevcut iş parçacığının bağlamını kaydeder ve çalıştırılacak bir sonraki iş parçacığına geçer.
-/// Bu, zamanlayıcı tarafından çağrılan temel bağlam değiştirme işlevidir.
-/// Mevcut iş parçacığının TCB'sini alır, registerlarını kaydeder.
-/// Zamanlayıcıdan bir sonraki TCB'yi alır.
-/// Yeni TCB'nin registerlarını yükler ve o iş parçacığına sıçrar (jump).
-/// NOT: Bu fonksiyon genellikle doğrudan Rust'tan çağrılmaz, mimariye özgü assembly ile birlikte çalışır.
-pub fn switch_context_mips(
-    current_thread: &mut ThreadControlBlock,
-    next_thread: &ThreadControlBlock,
-) {
-    // TODO: Mevcut (current_thread) iş parçacığının CPU registerlarını (MipsRegisters yapısına) kaydet.
-    // Bu kısım genellikle inline assembly veya ayrı bir .S dosyasında MIPS'e özgü komutlarla yapılır.
-    // Örnek (Pseudo-kod/Açıklama):
-    // - Mevcut stack pointer'ı (sp) current_thread.registers.sp'ye kaydet.
-    // - ra, gp, fp, s0-s7 gibi kaydedilmesi gereken diğer registerları current_thread.registers içine kaydet.
-    // - Current_thread'in durumunu uygun şekilde güncelle (örn. Ready veya Blocked).
-
-    // TODO: Bir sonraki (next_thread) iş parçacığının bağlamını yükle.
-    // Bu kısım da assembly gerektirir.
-    // Örnek (Pseudo-kod/Açıklama):
-    // - next_thread.registers.sp değerini CPU'nun sp register'ına yükle.
-    // - next_thread.registers'tan ra, gp, fp, s0-s7 gibi registerları CPU'ya yükle.
-    // - Eğer görev değişiyorsa, bellek bağlamını (page table base register gibi) next_thread.parent_task.memory_context'tan yükle.
-    // - CPU'nun Program Counter'ını (PC) ayarlamak için next_thread.registers.epc veya başka bir giriş noktasına sıçra.
-    // - next_thread'in durumunu 'Running' olarak güncelle.
-
-    // Not: Bu fonksiyonun başarılı dönüşü, artık next_thread'in bağlamında çalışıyor olmak anlamına gelir.
-    println!("srctask_mips: Bağlam değiştirme simülasyonu"); // Kernel içi print! gerektirir
-    // Gerçek implementasyonda buradan dönülmez, yeni iş parçacığına sıçranır.
+/// TLB refill işleyicisinin kullanacağı sayfa tablosu kökü.
+pub fn current_page_table_root() -> u64 {
+    CURRENT_PGD.load(Ordering::Relaxed)
 }
 
- task_exit_mips(task_id: KTaskId, exit_code: i32) -> !
-// Bir görevi ve ona ait tüm iş parçacıklarını sonlandırma mantığı. Kaynakları (bellek, handle'lar) serbest bırakır.
-
- thread_exit_mips(thread_id: KThreadId, exit_code: i32) -> !
-// Tek bir iş parçacığını sonlandırma mantığı. Görevdeki son thread ise görevi de sonlandırabilir.
-
- sleep_mips(duration_ms: u64)
-// Mevcut iş parçacığını belirtilen süre uykuya yatırır. Zamanlayıcı ile etkileşim kurar.
-
- yield_mips()
-// Mevcut iş parçacığını Ready durumuna alıp CPU'yu zamanlayıcıya bırakır.
-
- get_current_task_id_mips() -> KTaskId
-// Şu anda çalışan iş parçacığının ait olduğu görevin ID'sini bulur. Thread Local Storage veya TCB pointerı gerektirir.
-
- get_current_thread_id_mips() -> KThreadId
-// Şu anda çalışan iş parçacığının ID'sini bulur. Thread Local Storage veya TCB pointerı gerektirir.
-
-// TODO: MIPS kesme/trap işleyicisinden bağlam kaydetme ve zamanlayıcıyı çağırma mantığı.
-// Bu genellikle assembly ve Rust/C karışımı olur.
- exception_handler(frame: &mut MipsRegisters) { ... save registers to current TCB ... call scheduler ... load registers from next TCB ... return from exception ... }
+/// MIPS mimari katmanını ktask'a kaydeder. Platform başlatma kodu karnal64::init()'ten sonra çağırır.
+pub fn init() {
+    ktask::register_arch(&ARCH_TASK);
+}
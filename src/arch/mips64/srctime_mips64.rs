@@ -119,6 +119,38 @@ pub fn counts_to_microseconds(counts: u64) -> u64 {
     (counts * 1_000_000) / MIPS_TIMER_FREQUENCY_HZ
 }
 
+// CP0 Count 32 bittir ve MIPS_TIMER_FREQUENCY_HZ'de yaklaşık 43 saniyede taşar. Son okunan değer
+// 64 bite genişletilmiş olarak saklanır; ktask zamanlayıcısı saati her tick'te okuduğundan
+// taşma kaçırılmaz.
+static COUNT_EXTENDED: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+
+/// Boot'tan beri geçen monoton süre (hardware_specific.h): 64 bite genişletilmiş CP0 Count,
+/// nanosaniyeye çevrilir. ktask zamanlayıcısı ve CPU süresi muhasebesi bu kancayı kullanır.
+#[no_mangle]
+pub extern "C" fn low_level_monotonic_time_ns() -> u64 {
+    use core::sync::atomic::Ordering;
+
+    let count: u64;
+    unsafe { core::arch::asm!("mfc0 {0}, $9", out(reg) count, options(nomem, nostack)) };
+    let count = count & 0xFFFF_FFFF; // mfc0 işaret genişletir
+    let mut last = COUNT_EXTENDED.load(Ordering::Relaxed);
+    let extended = loop {
+        let mut next = (last & !0xFFFF_FFFF) | count;
+        if next < last {
+            // Küçük geri gidiş başka bir CPU'nun daha yeni okumasıdır; büyükse sayaç taşmıştır.
+            if last - next < 1 << 31 {
+                break last;
+            }
+            next += 1 << 32;
+        }
+        match COUNT_EXTENDED.compare_exchange_weak(last, next, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => break next,
+            Err(current) => last = current,
+        }
+    };
+    ((extended as u128 * 1_000_000_000) / MIPS_TIMER_FREQUENCY_HZ as u128) as u64
+}
+
 // TODO: İhtiyaca göre başka zaman fonksiyonları eklenebilir:
 // - Sistem boot zamanından itibaren geçen süreyi döndüren fonksiyon (Unix epoch gibi değil)
 // - Belirli bir süre beklemek için delay/sleep fonksiyonları (yield veya kesme tabanlı)
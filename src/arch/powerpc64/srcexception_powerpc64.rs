@@ -153,33 +153,37 @@ fn handle_syscall_trap(tf: &mut TrapFrame, is_from_user: bool) {
     tf.srr0 += 4; // SYSCALL komutunun uzunluğunu ekle (mimariye/komut setine bağlı olabilir)
 }
 
+/// MSR.EE: harici kesmeler açık.
+const MSR_EE: u64 = 1 << 15;
+
+/// Kesilen bağlamda kesmeler açıksa bu CPU'da yeniden açar ve `true` döner. Sayfa hatası çözümü
+/// uyuyabildiğinden yalnızca kesmeler açıkken denenir.
+fn enable_interrupts_if_enabled_in(msr: u64) -> bool {
+    if msr & MSR_EE == 0 {
+        return false;
+    }
+    unsafe {
+        core::arch::asm!("mfmsr {0}", "ori {0}, {0}, 0x8000", "mtmsrd {0}, 1", out(reg) _, options(nomem, nostack));
+    }
+    true
+}
+
 /// Veri Depolama İstisnası (Data Storage Interrupt - DSI) işleyicisi.
 /// Sayfa hataları, hizalama hataları vb. durumları ele alır. Bellek yöneticisine yönlendirir.
 fn handle_data_storage_trap(tf: &mut TrapFrame, is_from_user: bool) {
-    // TODO: DSISR (Data Storage Interrupt Status Register) ve DAR (Data Address Register) oku.
-     unsafe {
-        let dsisr: u64 = core::arch::asm!("mfspr {0}, 0x3F0", out(reg) _); // DSISR SPR numarası PowerPC alt ailesine göre değişir (örn. 0x3F0 PowerPC 64-bit)
-        let dar: u64 = core::arch::asm!("mfspr {0}, 0x3F1", out(reg) _); // DAR SPR numarası
-     }
-     println!("Karnal64: Veri Depolama İstisnası alındı! DAR: {:#x}, DSISR: {:#x}", dar, dsisr);
-
-    // TODO: Hatayı analiz et (DSISR'ye bakarak okuma/yazma/yürütme, sayfa hatası türü vb.).
-    // TODO: Karnal64 bellek yöneticisine (kmemory) page_fault veya benzeri bir fonksiyonla haber ver.
-     let result = kmemory::handle_data_storage_exception(tf, dar, dsisr, is_from_user);
-
-    // İşlem başarılı olursa (örn. sayfa map edildi), srr0'ı istisnaya neden olan komuta ayarlayarak
-    // o komutun yeniden denenmesini sağla.
-     tf.srr0 = srr0_at_exception; // İstisna anındaki SRR0 değeri (TrapFrame'de mevcut)
-
-    // İşlem başarısız olursa (geçersiz erişim), görevi sonlandır veya sinyal gönder.
-     if result.is_err() {
-        println!("Karnal64: Geçersiz bellek erişimi! Görev sonlandırılıyor...");
-        ktask::terminate_task(ktask::get_current_task_id(), -KError::BadAddress as i32); // Örnek kullanım
-    //    // terminate_task geri dönmez
-     }
+    // DSISR (SPR 18) hatanın nedenini, DAR (SPR 19) hatalı adresi tutar.
+    let (dsisr, dar): (u64, u64);
+    unsafe {
+        core::arch::asm!("mfspr {0}, 18", out(reg) dsisr, options(nomem, nostack));
+        core::arch::asm!("mfspr {0}, 19", out(reg) dar, options(nomem, nostack));
+    }
 
-    // Placeholder: Şimdilik sadece hata yazıp panik yapalım.
-    println!("Karnal64: İşlenmemiş Veri Depolama İstisnası! Görev sonlandırılıyor.");
+    // Sayfa hatası görevin VMA'larına göre çözülürse SRR0 değiştirilmeden dönülür ve komut
+    // yeniden çalışır.
+    if enable_interrupts_if_enabled_in(tf.srr1) && super::mmu::handle_page_fault(dar, dsisr, false).is_ok() {
+        return;
+    }
+    println!("Karnal64: Veri Depolama İstisnası çözülemedi! DAR: {:#x}, DSISR: {:#x}", dar, dsisr);
     // Gerçek kodda burada panic yerine daha kontrollü bir görev sonlandırma olur.
     if is_from_user {
        // Kullanıcı görevini sonlandır
@@ -193,17 +197,15 @@ fn handle_data_storage_trap(tf: &mut TrapFrame, is_from_user: bool) {
 /// Komut Depolama İstisnası (Instruction Storage Interrupt - ISI) işleyicisi.
 /// Komut sayfa hataları, yasadışı komutlar vb. durumları ele alır. Bellek/görev yöneticisine yönlendirir.
 fn handle_instruction_storage_trap(tf: &mut TrapFrame, is_from_user: bool) {
+    // Komut sayfa hatası görevin VMA'larına göre çözülürse komut yeniden getirilir.
+    if enable_interrupts_if_enabled_in(tf.srr1) && super::mmu::handle_page_fault(tf.srr0, 0, true).is_ok() {
+        return;
+    }
+
     // TODO: SRR0'daki adresteki komutu veya durumu analiz et.
-    // Yasadışı komut, ayrıcalıklı komut hatası, komut sayfa hatası vb. olabilir.
+    // Yasadışı komut, ayrıcalıklı komut hatası vb. olabilir.
      println!("Karnal64: Komut Depolama İstisnası alındı! SRR0: {:#x}, SRR1: {:#x}", tf.srr0, tf.srr1);
 
-    // TODO: Hatayı analiz et.
-    // TODO: Karnal64 bellek yöneticisine (kmemory) instruction_page_fault veya görev yöneticisine (ktask) illegal_instruction gibi haber ver.
-     let result = kmemory::handle_instruction_storage_exception(tf, is_from_user);
-
-     // İşlem başarılı olursa (örn. sayfa map edildi), srr0'ı istisnaya neden olan komuta ayarlayarak yeniden dene.
-      tf.srr0 = srr0_at_exception;
-
      // İşlem başarısız olursa, görevi sonlandır.
 
      // Placeholder
@@ -218,6 +220,7 @@ fn handle_instruction_storage_trap(tf: &mut TrapFrame, is_from_user: bool) {
 /// Harici Kesme (External Interrupt) işleyicisi.
 /// Donanım kesmelerini (disk, ağ, klavye vb.) ele alır. Kesme denetleyicisine ve sürücülere yönlendirir.
 fn handle_external_interrupt(tf: &mut TrapFrame, is_from_user: bool) {
+    crate::karnal64::ksync::lockdep::irq_enter();
     // TODO: Kesme denetleyicisinden (örn. PIC, APIC benzeri donanım) kesme numarasını (IRQ) oku.
      let irq = pic::get_pending_irq(); // Varsayımsal PIC modülü
 
@@ -231,26 +234,26 @@ fn handle_external_interrupt(tf: &mut TrapFrame, is_from_user: bool) {
 
     // Placeholder
      println!("Karnal64: Harici Kesme alındı! IRQ: (Bilinmiyor, placeholder)");
+    crate::karnal64::ksync::lockdep::irq_exit();
 }
 
 /// Azaltıcı (Decrementer - Timer) Kesmesi işleyicisi.
-/// Periyodik zamanlayıcı kesmelerini ele alır. Görev zamanlayıcısına yönlendirir.
+/// Tek seferlik zamanlayıcı kesmelerini ele alır. Görev zamanlayıcısına yönlendirir.
 fn handle_decrementer_interrupt(tf: &mut TrapFrame, is_from_user: bool) {
      // println!("Karnal64: Zamanlayıcı Kesmesi alındı!");
-
-     // TODO: Karnal64 görev zamanlayıcısına (ktask) bir zamanlayıcı tikinin gerçekleştiğini bildir.
-      ktask::timer_tick(tf);
-
-     // Zamanlayıcı genellikle görev değiştirmeyi (preemption) tetikler.
-     // ktask::timer_tick fonksiyonu, eğer bir görev değişimi gerekiyorsa,
-     // TrapFrame'i güncelleyebilir (örneğin srr0/srr1 ve yığın işaretçisi r1'i)
-     // böylece istisnadan dönüldüğünde farklı bir göreve geçilir.
-
-     // PowerPC'de azaltıcıyı yeniden yüklemek gerekebilir.
-      unsafe {
-           let new_decrementer_value = calculate_next_tick_value(); // Sonraki kesme zamanını hesapla
-           core::arch::asm!("mtspr 0x00E, {0}", in(reg) new_decrementer_value); // Decrementer SPR numarası (0x00E)
-      }
+     crate::karnal64::ksync::lockdep::irq_enter();
+
+     // Süresi dolan bekleyenleri uyandır, zaman dilimini/öncelikleri kontrol et.
+     // Gerekirse CPU yeniden zamanlama için işaretlenir; geçiş işleyicinin sonunda yapılır.
+     // Decrementer'ı bir sonraki olaya (uyanma, zaman dilimi sonu, yük dengeleme) timer_tick yeniden
+     // kurar (srctask_powerpc64.rs::program_timer).
+     crate::karnal64::ktask::timer_tick(crate::karnal64::ktask::now_ns());
+     crate::karnal64::ksync::lockdep::irq_exit();
+
+     // Kesilen bağlam kesintiye uğratılabilirse (spinlock tutmuyorsa) ve daha önemli bir iş parçacığı
+     // hazırsa ona geçilir. Bu iş parçacığı tekrar seçildiğinde buradan devam eder; tf kendi çekirdek
+     // yığınında olduğundan istisna dönüşü yine kesilen yere yapılır.
+     crate::karnal64::ktask::preempt_on_interrupt_exit();
 }
 
 
@@ -141,3 +141,90 @@ pub extern "C" fn handle_exception(ctx: *mut CpuContext) {
  pub static CPU_CONTEXT_SIZE: usize = core::mem::size_of::<CpuContext>();
  #[no_mangle]
  pub static CPU_CONTEXT_ALIGN: usize = core::mem::align_of::<CpuContext>();
+
+// --- Karnal64 Kesme Maskesi Kancaları (hardware_specific.h) ---
+// ksync::Spinlock::lock_irqsave ve çekirdek genelindeki kesme aç/kapa işlemleri bunları kullanır.
+// Kaydedilen durum MSR[EE] bitidir. `mtmsrd rS, 1` yalnızca EE ve RI bitlerini yazar.
+
+const MSR_EE: u64 = 1 << 15;
+
+#[inline(always)]
+fn read_msr() -> u64 {
+    let msr: u64;
+    unsafe { core::arch::asm!("mfmsr {0}", out(reg) msr, options(nomem, nostack)) };
+    msr
+}
+
+#[inline(always)]
+fn write_msr_ee_ri(msr: u64) {
+    unsafe { core::arch::asm!("mtmsrd {0}, 1", in(reg) msr, options(nomem, nostack)) };
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_enable() {
+    write_msr_ee_ri(read_msr() | MSR_EE);
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_disable() {
+    write_msr_ee_ri(read_msr() & !MSR_EE);
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupts_enabled() -> i32 {
+    (read_msr() & MSR_EE != 0) as i32
+}
+
+/// Harici kesmeleri kapatır ve önceki EE bitini döndürür.
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_save() -> u64 {
+    let msr = read_msr();
+    write_msr_ee_ri(msr & !MSR_EE);
+    msr & MSR_EE
+}
+
+/// `low_level_interrupt_save` ile kaydedilen EE bitini geri yükler.
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_restore(flags: u64) {
+    if flags & MSR_EE != 0 {
+        write_msr_ee_ri(read_msr() | MSR_EE);
+    }
+}
+
+// --- Karnal64 CPU Kimliği Kancası (hardware_specific.h) ---
+
+/// Çağıran CPU'nun mantıksal indeksi: PIR (SPR 1023, donanım iş parçacığı kimliği), ktask kaydıyla
+/// eşlenir. Kayıtsız CPU'lar 0 döner.
+#[no_mangle]
+pub extern "C" fn low_level_cpu_id() -> u32 {
+    let pir: u64;
+    unsafe { core::arch::asm!("mfspr {0}, 1023", out(reg) pir, options(nomem, nostack)) };
+    karnal64::ktask::cpu_index(pir).unwrap_or(0) as u32
+}
+
+// --- Karnal64 Yığın İzi Kancası (hardware_specific.h) ---
+// lockdep raporlarında kullanılır. ELFv2'de her çerçeve bir geri zincir tutar, ayrıca
+// çerçeve işaretçisi gerekmez.
+
+/// [r1] çağıranın çerçevesini gösterir; bir fonksiyonun LR'si çağıranın çerçevesinde
+/// (geri zincir + 16) saklanır. Zincir sıfıra veya yığında aşağı doğru bir çerçeveye ulaşınca durur.
+#[no_mangle]
+pub extern "C" fn low_level_stack_trace(frames: *mut u64, max_frames: usize) -> usize {
+    let mut sp: u64;
+    unsafe { core::arch::asm!("mr {0}, 1", out(reg) sp, options(nomem, nostack)) };
+    let mut count = 0;
+    while count < max_frames && sp != 0 && sp % 16 == 0 {
+        let prev = unsafe { *(sp as *const u64) };
+        if prev <= sp {
+            break;
+        }
+        let lr = unsafe { *((prev + 16) as *const u64) };
+        if lr == 0 {
+            break;
+        }
+        unsafe { *frames.add(count) = lr };
+        count += 1;
+        sp = prev;
+    }
+    count
+}
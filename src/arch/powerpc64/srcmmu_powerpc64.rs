@@ -5,6 +5,7 @@
 use crate::karnal64::{KError, KHandle}; // Karnal64 crate'inden gerekli tipleri import ediyoruz.
 // Muhtemelen fiziksel bellek yönetimi için bir modül de gerekecek.
  use crate::karnal64::kmemory::physical; // Yer tutucu: Fiziksel bellek yöneticisi
+use crate::karnal64::kmemory; // Talep üzerine sayfalama (handle_page_fault)
 
 // Güvenlik: Düşük seviye donanım erişimi ve pointer manipülasyonu için unsafe kaçınılmazdır.
 // Ancak mümkün olduğunca unsafe bloğunu küçük tutmaya çalışacağız.
@@ -243,31 +244,33 @@ pub fn unmap_page(as_id: AddressSpaceId, virtual_address: u64) -> Result<(), KEr
     Ok(())
 }
 
-/// Bir sayfa hatası (Page Fault) istisnasını işler (Konseptel).
-/// Gerçekte bu bir kesme işleyicisinin parçası olacaktır, ancak MMU mantığıyla etkileşir.
-/// Çekirdeğin istisna işleyicisi tarafından çağrılacağı varsayılır.
+/// DSISR: erişim bir yazmaydı (store).
+const DSISR_STORE: u64 = 1 << 25;
+/// DSISR: adres eşlenmemiş (hash tablosunda/sayfa tablosunda çeviri yok).
+const DSISR_NO_TRANSLATION: u64 = 1 << 30;
+/// DSISR: erişim sayfa korumasına takıldı.
+const DSISR_PROTECTION: u64 = 1 << 27;
+
+/// Bir sayfa hatası (DSI veya ISI) istisnasını mevcut görevin VMA'larına göre çözer.
+/// İlk dokunulan anonim sayfa sıfırlanarak, kaynak sayfası sağlayıcıdan okunarak eşlenir
+/// (`kmemory::handle_page_fault`). `Ok` dönerse istisnaya neden olan komut SRR0 değiştirilmeden
+/// yeniden çalıştırılır; `Err` erişimin gerçekten geçersiz olduğunu bildirir.
+/// İstisna işleyicisi tarafından, kesilen bağlamda kesmeler açıksa onları açtıktan sonra çağrılır.
 ///
-/// `exception_frame`: İstisna sırasında kaydedilen işlemci durumu (yer tutucu).
-/// `fault_address`: Hataya neden olan sanal adres.
-/// `error_code`: Hata nedeni (okuma/yazma hatası, izin hatası vb.) (yer tutucu).
- pub fn handle_page_fault(exception_frame: &mut ExceptionFrame, fault_address: u64, error_code: u64) {
-     println!("PowerPC MMU: Sayfa Hatası Oluştu! VA: {:#x}, Hata Kodu: {:#x}", fault_address, error_code);
-
-//     // TODO: Hata adresini ve kodunu analiz et.
-//     // TODO: Hangi adres alanına ait olduğunu belirle (exception_frame'den).
-      let as_id = get_address_space_id_from_exception_frame(exception_frame); // Yer tutucu
-
-//     // Örnek Senaryolar:
-//     // 1. İstenen sayfa bellekte değilse (swapped out):
-//     //    - Sayfayı diskten yüklemek için scheduler'a bir istek gönder.
-//     //    - Mevcut görevi blokla.
-//     // 2. Copy-on-Write sayfa hatası:
-//     //    - Orijinal fiziksel sayfayı kopyala.
-//     //    - Yeni görevin sayfa tablosunda sanal adresi yeni fiziksel sayfaya eşle (yazma izni ile).
-//     // 3. Geçersiz bellek erişimi (segmentation fault):
-//     //    - Göreve bir sinyal gönder (veya sonlandır).
-
-//     // Basit Yer Tutucu: Hatayı her zaman BadAddress olarak döndür (gerçekte görevi sonlandırırız)
-     println!("PowerPC MMU: Sayfa Hatası İşlenemedi, görevi sonlandır.");
-      ktask::terminate_current_task(KError::BadAddress as i32); // Yer tutucu: Görev sonlandırma API'si
- }
+/// `fault_address`: Hataya neden olan sanal adres (DSI'da DAR, ISI'da SRR0).
+/// `dsisr`: DSI'da DSISR; ISI için kullanılmaz.
+/// `instruction`: Hata komut getirirken (ISI) mi oluştu.
+pub fn handle_page_fault(fault_address: u64, dsisr: u64, instruction: bool) -> Result<(), KError> {
+    let access = if instruction {
+        kmemory::PROT_EXEC
+    } else if dsisr & DSISR_STORE != 0 {
+        kmemory::PROT_WRITE
+    } else {
+        kmemory::PROT_READ
+    };
+    // Çeviri yokluğu veya koruma dışındaki nedenler (hizalama, eşzamanlılık vb.) sayfa hatası değildir.
+    if !instruction && dsisr & (DSISR_NO_TRANSLATION | DSISR_PROTECTION) == 0 {
+        return Err(KError::BadAddress);
+    }
+    kmemory::handle_page_fault(fault_address as usize, access)
+}
@@ -21,6 +21,10 @@ extern crate alloc; // Karnal64 içinde Box veya Vec gibi türler kullanılıyor
 #[path = "../../karnal64.rs"] // Geçici olarak dosya yolunu belirtiyorum, gerçekte modül import edilir.
 mod karnal64;
 
+// ktask mimari katmanı (bağlam kurma/değiştirme, adres alanı etkinleştirme)
+#[path = "srctask_powerpc64.rs"]
+mod task;
+
 use karnal64::{KError, KHandle}; // Karnal64 API'sından hata ve handle tiplerini kullanıyoruz
 
 // --- PowerPC Platformuna Özgü Başlatma ---
@@ -48,6 +52,8 @@ pub extern "C" fn platform_init() {
     // Genel Karnal64 çekirdek başlatma fonksiyonunu çağır.
     // Bu fonksiyon, resource manager, task manager gibi Karnal64 iç modüllerini başlatır.
     karnal64::init();
+    // Zamanlayıcı bağlam değiştirebilsin diye mimari katmanı kaydet.
+    task::init();
 
     // TODO: Daha sonraki başlatma adımları
     // - Cihaz sürücülerini kaydetme (platforma özgü cihazlar için ResourceProvider implementasyonları)
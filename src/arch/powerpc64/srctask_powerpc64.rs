@@ -1,220 +1,179 @@
-#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz, çekirdek alanında çalışırız
-
-// Geliştirme sırasında kullanılmayan kod veya argümanlar için izinler
-#![allow(dead_code)]
-#![allow(unused_variables)]
-
-// Karnal64 API'sından gerekli tipleri ve trait'leri içe aktar
-// Kendi Karnal64 implementasyonunuzdaki pub kullanımlarına göre bu yol değişebilir.
-// Genellikle çekirdek içi modüller birbirine 'super' ile erişir.
-use super::karnal64::{KError, KTaskId, KThreadId}; // Veya 'use super::*' diyebilirsiniz
-use super::karnal64::{kresource, kmemory, ksync}; // Diğer modüllere ihtiyaç duyulabilir (örn. bellek tahsisi için)
-
-// --- PowerPC'ye Özgü Görev ve İş Parçacığı Yapıları ---
-// Bu yapılar, PowerPC'deki bir görev/iş parçacığının durumunu (kayıtçılar, yığın işaretçisi vb.) tutar.
-// Tam kayıtçı kümesi PowerPC ABI'sine ve kullanılan bağlam değiştirme tekniğine göre değişir.
-
-#[repr(C)] // C uyumluluğu gerekebilir, özellikle bağlam değiştirme assembly kodu ile etkileşimde
-pub struct PowerPCTaskControlBlock {
-    // TODO: PowerPC'ye özgü görev durumu bilgileri (eğer gerekiyorsa)
-    // Genellikle görev düzeyi durum (bellek haritası, açık kaynaklar vb.) generic ktask modülünde tutulur.
-    // Bu yapı daha çok mimariye özgü thread koleksiyonunu yönetebilir.
-    // Bu taslakta görev durumu yönetimini daha çok thread düzeyinde odaklayacağız.
-    pub id: KTaskId,
-    // Göreve ait threadlerin listesi veya referansı tutulabilir.
-    // TODO: thread_list: Vec<KThreadId> gibi bir yapı (no_std uyumlu bir koleksiyon gerekir)
+// PowerPC64 (ELFv2 ABI) için ktask mimari katmanı (ktask::ArchTask):
+// - bağlam kurma: yeni iş parçacığı `ppc64_thread_trampoline` üzerinden başlar
+// - bağlam değiştirme: ELFv2'de çağrılan tarafından korunan yazmaçlar (r14-r31), r1 (SP),
+//   r2 (TOC), LR ve CR kaydedilir/yüklenir
+// - adres alanı: radix MMU'da süreç kimliği PIDR'ye yazılır; TLB girdileri PID ile etiketli
+//   olduğundan temizlik gerekmez
+// - TLS: ELFv2'de kullanıcı TLS işaretçisi r13'tür; genel yazmaç olarak kesme çerçevesinde taşınır,
+//   yalnızca kullanıcıya ilk geçişte trampolin yükler
+// - zamanlayıcı: decrementer (DEC) bir sonraki olaya kalan süreyle tek seferlik kurulur
+// Kullanıcı modundayken SPRG1, iş parçacığının çekirdek yığınının tepesini tutar; kesme girişi
+// buradan çekirdek yığınına geçer. Kullanıcıya ilk dönüşte SPRG1'i trampolin, sonrakilerde kesme çıkışı yazar.
+
+use core::arch::{asm, global_asm};
+use core::mem::size_of;
+
+use super::karnal64::ktask::{self, ArchTask, ThreadStart};
+
+/// MSR.EE (harici kesmeler) ve MSR.PR (problem/kullanıcı durumu) bitleri.
+const MSR_EE: u64 = 1 << 15;
+const MSR_PR: u64 = 1 << 14;
+
+/// Time Base (ve decrementer) frekansı (DTB /cpus `timebase-frequency`; POWER8/9: 512 MHz).
+const TIMEBASE_HZ: u64 = 512_000_000;
+/// Decrementer'a yazılabilecek en büyük pozitif değer (32 bit kip); daha uzak olaylar için
+/// kesme erken gelir ve ktask zamanlayıcıyı yeniden kurar.
+const DEC_MAX: u64 = 0x7FFF_FFFF;
+
+/// PowerPC iş parçacığı bağlamı.
+/// Yalnızca çağrılan tarafından korunması gereken yazmaçlar tutulur; geri kalanı
+/// ppc64_switch_context çağrısı sırasında zaten çağıranın yığınındadır.
+#[repr(C)] // Assembly'deki ofsetlerle aynı düzen
+#[derive(Debug, Copy, Clone)]
+pub struct PowerPCContext {
+    /// r14-r31. İlk geçişte r14-r19 trampoline başlangıç bilgisini taşır.
+    pub gpr: [u64; 18],
+    pub r1: u64, // Yığın işaretçisi
+    pub r2: u64, // TOC işaretçisi
+    pub lr: u64, // Link register (ilk geçişte trampolin)
+    pub cr: u64,
 }
 
-#[repr(C)] // C uyumluluğu, özellikle bağlam değiştirme assembly kodu ile etkileşimde
-pub struct PowerPCThreadControlBlock {
-    pub id: KThreadId,
-    pub task_id: KTaskId, // Hangi göreve ait olduğu
-
-    // TODO: PowerPC kayıtçıları için yer tutucular.
-    // Bağlam değiştirme sırasında kaydedilip geri yüklenecek kayıtçılar buraya eklenmeli.
-    // Örnekler (gerçek PowerPC kayıtçı adları ve yapıları mimariye göre değişir):
-    // Genel amaçlı kayıtçılar (r0-r31), Durum Kayıtçısı (CR), Link Kayıtçısı (LR),
-    // Sayım Kayıtçısı (CTR), Sıfır Durum Kayıtçısı (XER), Vektör Kayıtçıları (alt mimarilere göre), vb.
-    pub saved_gprs: [u64; 32], // r0-r31 için yer tutucu
-    pub saved_lr: u64,
-    pub saved_cr: u32,
-    // ... diğer önemli kayıtçılar ...
-
-    // Yığın (Stack) bilgileri
-    pub stack_top: *mut u8,    // Yığın bloğunun başlangıcı (düşük adres)
-    pub stack_pointer: *mut u8, // Kaydedilmiş yığın işaretçisi (bağlam değiştirme sırasında güncellenir)
-    pub stack_size: usize,     // Yığın bloğunun boyutu
-
-    // Thread durumu (Running, Ready, Blocked, vb.) - Bu genellikle generic ktask katmanında tutulur,
-    // ancak mimariye özgü yapı da bir kopyasını tutabilir.
-     pub state: ThreadState, // Örnek enum
+const _: () = assert!(size_of::<PowerPCContext>() <= ktask::ARCH_CONTEXT_SIZE);
+
+global_asm!(
+    r#"
+    .section .text
+    .global ppc64_switch_context
+    # r3: önceki bağlam (*mut PowerPCContext), r4: sonraki bağlam
+ppc64_switch_context:
+    std 14, 0(3)
+    std 15, 8(3)
+    std 16, 16(3)
+    std 17, 24(3)
+    std 18, 32(3)
+    std 19, 40(3)
+    std 20, 48(3)
+    std 21, 56(3)
+    std 22, 64(3)
+    std 23, 72(3)
+    std 24, 80(3)
+    std 25, 88(3)
+    std 26, 96(3)
+    std 27, 104(3)
+    std 28, 112(3)
+    std 29, 120(3)
+    std 30, 128(3)
+    std 31, 136(3)
+    std 1, 144(3)
+    std 2, 152(3)
+    mflr 0
+    std 0, 160(3)
+    mfcr 0
+    std 0, 168(3)
+
+    ld 14, 0(4)
+    ld 15, 8(4)
+    ld 16, 16(4)
+    ld 17, 24(4)
+    ld 18, 32(4)
+    ld 19, 40(4)
+    ld 20, 48(4)
+    ld 21, 56(4)
+    ld 22, 64(4)
+    ld 23, 72(4)
+    ld 24, 80(4)
+    ld 25, 88(4)
+    ld 26, 96(4)
+    ld 27, 104(4)
+    ld 28, 112(4)
+    ld 29, 120(4)
+    ld 30, 128(4)
+    ld 31, 136(4)
+    ld 1, 144(4)
+    ld 2, 152(4)
+    ld 0, 160(4)
+    mtlr 0
+    ld 0, 168(4)
+    mtcr 0
+    blr
+
+    .global ppc64_thread_trampoline
+    # r14: giriş, r15: argüman, r16: kullanıcı yığını, r17: çekirdek yığın tepesi, r18: kullanıcı modu mu,
+    # r19: kullanıcı TLS işaretçisi
+ppc64_thread_trampoline:
+    mr 3, 15
+    mr 12, 14
+    cmpdi 18, 0
+    bne 1f
+    # Çekirdek iş parçacığı: MSR.EE'yi aç ve girişe atla (ELFv2: r12 = giriş, geri dönmez).
+    mfmsr 0
+    ori 0, 0, {msr_ee}
+    mtmsrd 0, 1
+    mtctr 12
+    bctr
+1:
+    # Kullanıcı iş parçacığı: SPRG1 = çekirdek yığını, SRR0 = giriş, SRR1 = MSR | PR | EE, sonra rfid.
+    mtspr 273, 17
+    mtsrr0 14
+    mfmsr 0
+    ori 0, 0, {msr_user}
+    mtsrr1 0
+    mr 1, 16
+    mr 13, 19
+    rfid
+"#,
+    msr_ee = const MSR_EE,
+    msr_user = const MSR_EE | MSR_PR,
+);
+
+extern "C" {
+    fn ppc64_switch_context(prev: *mut PowerPCContext, next: *const PowerPCContext);
+    fn ppc64_thread_trampoline();
 }
 
-// --- Çekirdek İçi Statikler veya Yöneticiler ---
-// Mevcut çalışan thread'in TCB'sine erişim için mimariye özgü bir mekanizma gerekir.
-// Bu genellikle bir kayıtçı (örn. r2) veya çekirdek veri alanında tutulan bir işaretçi ile yapılır.
-
-// TODO: Mevcut çalışan thread'in TCB işaretçisini tutacak mimariye özgü mekanizma
-// Bu genellikle assembly veya düşük seviye kodla ayarlanır.
- pub static mut CURRENT_THREAD_TCB: *mut PowerPCThreadControlBlock = core::ptr::null_mut();
-
-// --- PowerPC'ye Özgü Görev/İş Parçacığı API Fonksiyonları ---
-// Bu fonksiyonlar, Karnal64'ün generic `ktask` modülü tarafından çağrılacaktır.
-
-/// PowerPC mimarisine özgü görev başlatma hazırlıkları.
-/// Çekirdek tarafından çağrılır.
-pub fn arch_task_create(task_id: KTaskId) -> Result<PowerPCTaskControlBlock, KError> {
-    // TODO: PowerPC'ye özgü görev düzeyinde başlatma (örn. adres alanı setup'ı, eğer görevler arası adres alanı ayrımı varsa)
-    // Basit bir taslakta görev yapısı oluşturulur.
-    println!("srctask_powerpc: Yeni görev {} için mimari yapı oluşturuluyor.", task_id.0); // Çekirdek içi print! gerekir
-    Ok(PowerPCTaskControlBlock {
-        id: task_id,
-        // TODO: Diğer alanları başlat
-    })
+/// ktask::ArchTask'ın PowerPC64 implementasyonu.
+pub struct PowerPCTask;
+
+pub static ARCH_TASK: PowerPCTask = PowerPCTask;
+
+impl ArchTask for PowerPCTask {
+    unsafe fn init_context(&self, ctx: *mut u8, start: &ThreadStart) {
+        let ctx = &mut *(ctx as *mut PowerPCContext);
+        ctx.gpr[0] = start.entry; // r14
+        ctx.gpr[1] = start.arg; // r15
+        ctx.gpr[2] = start.user_stack_top; // r16
+        ctx.gpr[3] = start.kernel_stack_top; // r17
+        ctx.gpr[4] = start.user as u64; // r18
+        ctx.gpr[5] = start.tls; // r19
+        // ELFv2: çağrılan fonksiyon için en az 32 baytlık bir çerçeve ayrılır.
+        ctx.r1 = start.kernel_stack_top - 32;
+        asm!("mr {0}, 2", out(reg) ctx.r2, options(nomem, nostack));
+        ctx.lr = ppc64_thread_trampoline as usize as u64;
+    }
+
+    unsafe fn switch_context(&self, prev: *mut u8, next: *const u8) {
+        ppc64_switch_context(prev as *mut PowerPCContext, next as *const PowerPCContext);
+    }
+
+    /// `root` bu portta radix MMU süreç kimliğidir (PID); sayfa tablosu kökü süreç tablosundaki girdidedir.
+    unsafe fn activate_address_space(&self, root: u64) {
+        // Çekirdek eşlemeleri dörtlü 3 (0xC...) altında ve PID 0'da; çekirdek görevinde PIDR değişmez.
+        if root == ktask::KERNEL_ADDRESS_SPACE {
+            return;
+        }
+        asm!("mtspr 48, {0}", "isync", in(reg) root, options(nostack));
+    }
+
+    unsafe fn program_timer(&self, deadline_ns: u64) {
+        // DEC göreli sayar: sıfırın altına inince kesme üretir. Geçmişteki bir an için 0 yazılır.
+        let delta_ns = deadline_ns.saturating_sub(ktask::now_ns());
+        let ticks = (delta_ns as u128 * TIMEBASE_HZ as u128).div_ceil(1_000_000_000).min(DEC_MAX as u128) as u64;
+        asm!("mtspr 22, {0}", in(reg) ticks, options(nomem, nostack));
+    }
 }
 
-/// PowerPC mimarisine özgü iş parçacığı (thread) oluşturma.
-/// Yığın tahsis eder, başlangıç bağlamını (kayıtçıları) ayarlar.
-/// Çekirdek tarafından generic ktask::create_thread çağrısı sırasında çağrılır.
-pub fn arch_thread_create(
-    thread_id: KThreadId,
-    task_id: KTaskId,
-    entry_point: extern "C" fn(u64), // İş parçacığı başlangıç fonksiyonu
-    arg: u64, // Başlangıç fonksiyonuna iletilecek argüman
-    stack_size: usize,
-) -> Result<PowerPCThreadControlBlock, KError> {
-
-    // TODO: Yığın için bellek tahsis et. kmemory modülünü kullan.
-    // Bu bellek kullanıcı alanında veya çekirdek alanında olabilir, kernel tasarımına bağlıdır.
-    // Varsayım: kmemory::allocate_kernel_stack gibi bir fonksiyon var.
-    let stack_base = unsafe { kmemory::allocate_kernel_stack(stack_size).map_err(|_| KError::OutOfMemory)? };
-    let stack_top = unsafe { stack_base.add(stack_size) };
-
-    // TODO: İş parçacığının ilk bağlamını (kayıtçıları) ayarla.
-    // Bu, iş parçacığı ilk kez zamanlandığında `entry_point` fonksiyonundan başlayacak şekilde yapılmalıdır.
-    // Yığın işaretçisi, dönüş adresi (Link Register), argüman kayıtçıları (PowerPC ABI'sine göre) ayarlanır.
-    println!("srctask_powerpc: Yeni thread {} için mimari yapı ve bağlam oluşturuluyor.", thread_id.0);
-
-    let mut tcb = PowerPCThreadControlBlock {
-        id: thread_id,
-        task_id: task_id,
-        // TODO: Kayıtçıları sıfırla veya varsayılan değerlerle doldur
-        saved_gprs: [0; 32],
-        saved_lr: 0, // Başlangıçta 0 veya özel bir değer olabilir
-        saved_cr: 0,
-        // ...
-
-        stack_top: stack_top,
-        stack_pointer: stack_top, // Geçici yığın işaretçisi, ilk bağlam kurulurken ayarlanacak
-        stack_size: stack_size,
-    };
-
-    // TODO: İş parçacığının başlangıç bağlamını TCB içine yaz.
-    // Bu genellikle assembly veya çok düşük seviye C/Rust kodu ile yapılır.
-    // Amaç, bağlam değiştirme fonksiyonu bu TCB'yi yüklediğinde, execution'ın `entry_point`'ten başlamasını sağlamaktır.
-    // Örnek:
-     tcb.saved_lr = entry_point as u64; // Link Register başlangıç fonksiyonunu işaret etmeli
-     tcb.saved_gprs[3] = arg; // PowerPC genellikle ilk argümanı r3'e koyar
-
-    // TODO: Başlangıç yığın çerçevesini ayarla (gerekiyorsa).
-
-    // Önemli Not: Gerçek implementasyonda, `arch_thread_create` genellikle
-    // `save_context` ve `restore_context` gibi düşük seviye mimari fonksiyonları
-    // çağırarak bir yığın çerçevesi kurar ve ilk bağlamı kaydeder.
-
-    Ok(tcb)
+/// PowerPC mimari katmanını ktask'a kaydeder. Platform başlatma kodu karnal64::init()'ten sonra çağırır.
+pub fn init() {
+    ktask::register_arch(&ARCH_TASK);
 }
-
-/// PowerPC mimarisine özgü bağlam değiştirme (context switch).
-/// Bu fonksiyon mevcut çalışan thread'in bağlamını kaydeder ve bir sonraki thread'in bağlamını yükler.
-/// Çekirdeğin zamanlayıcısı (scheduler) tarafından çağrılır.
-/// ASSEMBLEY KODU GEREKİR.
-#[naked] // Bu fonksiyonun Rust'ın standart prologue/epilogue'unu kullanmayacağını belirtir
-#[inline(never)] // Derleyici optimizasyonlarının bu fonksiyonu bozmasını engellemek için
-pub unsafe extern "C" fn arch_context_switch(
-    current_tcb_ptr: *mut PowerPCThreadControlBlock, // Mevcut thread'in TCB'si
-    next_tcb_ptr: *const PowerPCThreadControlBlock,  // Bir sonraki thread'in TCB'si
-) {
-    // TODO: BURAYA POWERPC ASSEMBLY KODU GELECEK.
-    // Bu assembly kodu şunları yapmalıdır:
-    // 1. Mevcut thread'in kayıtçılarını `current_tcb_ptr` tarafından işaret edilen TCB'ye kaydet.
-    // 2. Yığın işaretçisini kaydet.
-    // 3. Yığın işaretçisini `next_tcb_ptr` tarafından işaret edilen TCB'deki değere ayarla.
-    // 4. `next_tcb_ptr` tarafından işaret edilen TCB'den kayıtçıları yükle.
-    // 5. `next_tcb_ptr` tarafından işaret edilen TCB'de kaydedilmiş Link Register'a (veya dönüş adresine) dallan.
-    //    Bu dallanma, yeni thread'in ya daha önce bağlam değiştirme yaptığı yerden devam etmesini ya da
-    //    ilk kez çalışıyorsa `entry_point` fonksiyonundan başlamasını sağlar.
-
-    // Bu fonksiyon ASLA geri dönmez (çünkü execution yeni thread'e geçer).
-    // Derleyiciye bunu belirtmek için bir 'unreachable' ipucu kullanılabilir (ancak #![naked] ile bu farklı olabilir).
-
-    // Örnek assembly yapısı (sentaks PowerPC assembler'ına göre değişir!):
-    
-    asm!(
-        // Kayıtçıları kaydet
-        "stw r1, 0(r3)",  // r1 (SP) kaydedilir (r3 = current_tcb_ptr arg1)
-        // ... diğer GPR'leri kaydet ...
-         "mfcr rX", "stw rX, offset(r3)", // CR kaydet
-         "mflr rX", "stw rX, offset(r3)", // LR kaydet
-        // ...
-
-        // Mevcut TCB'deki yığın işaretçisini (r1) güncelle (eğer farklı kaydediliyorsa)
-         "stw r1, SP_OFFSET(r3)",
-
-        // Sonraki TCB'deki yığın işaretçisini (r1) yükle (arg2 = next_tcb_ptr)
-        "lwz r1, SP_OFFSET(r4)", // r4 = next_tcb_ptr arg2
-
-        // Kaydedilmiş kayıtçıları yükle
-        // ... GPR'leri yükle ...
-         "lwz rX, offset(r4)", "mtcrf mask, rX", // CR yükle
-         "lwz rX, offset(r4)", "mtlr rX", // LR yükle
-
-        // Yeni thread'in execution'ına dallan (LR'ye dallanmak yaygındır)
-        "mtctr rX", // Veya mtlr'den ctr'ye taşıyıp bctr kullan
-        "bctr",
-
-        options(noreturn) // Bu fonksiyonun geri dönmeyeceğini belirtir
-    );
-    
-    unreachable!(); // Bu satıra asla ulaşılmamalıdır
-}
-
-/// Mevcut çalışan iş parçacığının PowerPC'ye özgü TCB'sine bir referans döndürür.
-/// Çekirdek içi kullanım içindir.
-/// Mimariye özgü bir mekanizma ile TCB işaretçisini bulmalıdır.
-pub fn arch_get_current_thread_tcb() -> &'static mut PowerPCThreadControlBlock {
-    // TODO: Mimariye özgü olarak mevcut TCB işaretçisini al.
-    // Bu, bir çekirdek kaydını okumak, bir özel amaçlı kayıtçıya bakmak
-    // veya CPU local storage gibi bir mekanizma olabilir.
-    // Örnek (varsayımsal):
-     unsafe { &mut *CURRENT_THREAD_TCB }
-
-    // Şimdilik bir yer tutucu panik döndürelim veya dummy bir referans (güvenli değil)
-    panic!("srctask_powerpc: arch_get_current_thread_tcb henüz implemente edilmedi");
-}
-
-/// Mevcut iş parçacığının ID'sini döndürür.
-/// arch_get_current_thread_tcb'yi kullanır.
-pub fn arch_get_current_thread_id() -> KThreadId {
-     arch_get_current_thread_tcb().id
-}
-
-/// Mevcut görevin ID'sini döndürür.
-/// arch_get_current_thread_tcb'yi kullanır.
-pub fn arch_get_current_task_id() -> KTaskId {
-     arch_get_current_thread_tcb().task_id
-}
-
-
-// TODO: Diğer PowerPC'ye özgü görev/thread fonksiyonları:
-arch_task_exit(task: &mut PowerPCTaskControlBlock) // Görev kaynaklarını temizler.
-arch_thread_exit(thread: &mut PowerPCThreadControlBlock) // İş parçacığı kaynaklarını (yığın dahil) temizler.
-arch_setup_initial_task() // Çekirdek ilk başladığında çalışacak ilk görevin TCB'sini kurar.
-arch_enter_first_task(first_tcb: &PowerPCThreadControlBlock) // Çekirdek başlatma bittikten sonra ilk kullanıcı/çekirdek görevine geçiş yapar. (Bağlam değiştirmeye benzer ama ilk geçiş)
-
-
-// TODO: Testler veya örnek kullanımlar (isteğe bağlı, çekirdek test framework'üne bağlı)
- #[cfg(test)]
- mod tests {
-//     // ...
- }
@@ -7,8 +7,9 @@ use core::arch::asm; // Donanım registerlarını okumak için inline assembly k
 // TODO: PowerPC Time Base frekansını buradan veya mimariye özel bir init fonksiyonundan almalısınız.
 // Bu değer, donanıma ve platforma özgüdür. Genellikle bootloader veya çekirdek init sırasında
 // bulunur ve global/statik bir değişkende saklanır.
-// Örnek olarak 1 GHz frekans (saniyede 1_000_000_000 tick) kullanalım.
-const TIME_BASE_FREQUENCY: u64 = 1_000_000_000; // Hz
+// DTB /cpus `timebase-frequency`; POWER8/9: 512 MHz. Decrementer da aynı frekansta sayar
+// (srctask_powerpc64.rs::TIMEBASE_HZ ile aynı tutulmalı).
+const TIME_BASE_FREQUENCY: u64 = 512_000_000; // Hz
 
 /// PowerPC Time Base registerlarının (TBU ve TBL) 64-bit değerini güvenli bir şekilde okur.
 ///
@@ -71,6 +72,13 @@ pub fn get_uptime_ns() -> u64 {
     total_nanoseconds as u64
 }
 
+/// Boot'tan beri geçen monoton süre (hardware_specific.h): Time Base, nanosaniyeye çevrilir.
+/// ktask zamanlayıcısı ve CPU süresi muhasebesi bu kancayı kullanır.
+#[no_mangle]
+pub extern "C" fn low_level_monotonic_time_ns() -> u64 {
+    ((read_time_base() as u128 * 1_000_000_000) / TIME_BASE_FREQUENCY as u128) as u64
+}
+
 /// PowerPC zaman kaynağı modülünü başlatır.
 ///
 /// Şu anda sadece bir yer tutucu, ancak gerçek implementasyonda Time Base frekansını
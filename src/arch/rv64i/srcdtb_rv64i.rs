@@ -9,8 +9,8 @@ use super::{KError, KHandle, ResourceProvider, KseekFrom, KResourceStatus}; // K
 // Bunların da super:: altında tanımlı olduğunu varsayıyoruz.
 // Dummy implementasyonları aşağıda gösterilmiştir.
 mod kresource { pub const MODE_READ: u32 = 1; pub const MODE_WRITE: u32 = 2; pub const MODE_CONTROL: u32 = 4; /* ... diğer modlar */ #![allow(unused)] use super::*; // Import needed types // Dummy struct implementing ResourceProvider for a UART pub struct DummyUartDriver { base_address: usize, } impl ResourceProvider for DummyUartDriver { fn read(&self, buffer: &mut [u8], offset: u664) -> Result<usize, KError> { /* Okuma implementasyonu */ Err(KError::NotSupported) } fn write(&self, buffer: &[u8], offset: u64) -> Result<usize, KError> { /* Yazma implementasyonu */ Err(KError::NotSupported) } fn control(&self, request: u64, arg: u64) -> Result<i64, KError> { /* Kontrol implementasyonu */ Err(KError::NotSupported) } fn seek(&self, position: KseekFrom) -> Result<u64, KError> { /* Seek implementasyonu */ Err(KError::NotSupported) } fn get_status(&self) -> Result<KResourceStatus, KError> { /* Durum sorgulama */ Err(KError::NotSupported) } } // Dummy registration function pub fn register_provider(_id: &str, _provider: Box<dyn ResourceProvider>) -> Result<KHandle, KError> { /* Kaynak kayıt mantığı */ super::kernel_println!("Kresource: Kaynak kaydedildi (Yer Tutucu)"); Ok(KHandle(100)) } // Dummy lookup (DTB parser kullanmaz) // pub fn lookup_provider_by_name(name: &str) -> Result<&'static dyn ResourceProvider, KError> { Err(KError::NotFound) } }
-mod kmemory { #![allow(unused)] use super::KError; // Dummy function to add a physical memory region pub fn add_physical_memory_region(_start: usize, _size: usize) -> Result<(), KError> { // Bellek bölgesini çekirdek bellek yöneticisine ekle super::kernel_println!("Kmemory: Fiziksel bellek bölgesi eklendi (Yer Tutucu)"); Ok(()) } // Dummy functions needed by karnal64 API but not DTB parser // pub fn init_manager() { ... } // pub fn allocate_user_memory(...) -> Result<*mut u8, KError> { ... } // ... }
-mod ktask { #![allow(unused)] use super::{KError, KTaskId}; use super::dtb_parser::DtbNode; // Dummy function to add a CPU pub fn add_cpu(_cpu_id: usize, _node: &DtbNode) -> Result<(), KError> { // CPU'yu görev zamanlayıcıya/yöneticisine ekle super::kernel_println!("Ktask: CPU bulundu ve eklendi (Yer Tutucu)"); Ok(()) } // Dummy functions needed by karnal64 API but not DTB parser // pub fn init_manager() { ... } // pub fn task_spawn(...) -> Result<KTaskId, KError> { ... } // ... }
+use super::kmemory; // Fiziksel bellek: /memory bölgeleri ve ayrılmış aralıklar çerçeve ayırıcıya bildirilir (srcframe.rs)
+use super::ktask; // CPU kaydı: bulunan hart'lar ktask::add_cpu ile SMP başlatmaya bildirilir (srcsmp_rv64i.rs)
 // --- Konseptsel DTB Ayrıştırıcı Kütüphanesi / Modülü ---
 // Gerçek bir projede, #![no_std] uyumlu, DTB formatını ayrıştıran
 // bir kütüphane (crate) veya özel bir modül kullanılırdı.
@@ -25,6 +25,10 @@ mod dtb_parser {
         pub compatible: Option<&'a str>, // "compatible" özelliği değeri
         pub reg: Option<&'a [u8]>, // "reg" özelliği değeri (byte dizisi)
         pub interrupts: Option<&'a [u8]>, // "interrupts" özelliği değeri (byte dizisi)
+        pub phandle: Option<u32>, // "phandle" özelliği (diğer düğümlerin bu düğüme başvurusu)
+        pub cpu: Option<u32>, // "cpu" özelliği: cpu-map çekirdek/iş parçacığı düğümünün işaret ettiği CPU'nun phandle'ı
+        pub capacity_dmips_mhz: Option<u32>, // CPU düğümlerinin "capacity-dmips-mhz" özelliği (big.LITTLE)
+        pub initrd: Option<(u64, u64)>, // "linux,initrd-start" / "linux,initrd-end" (yalnızca /chosen)
         // TODO: Diğer yaygın özellikler (clocks, #address-cells, #size-cells vb.)
         pub children: DtbNodeChildren<'a>, // Alt düğümler
     }
@@ -75,15 +79,52 @@ mod dtb_parser {
             name: "cpu@0",
             compatible: Some("riscv"), // CPU tanımı
             reg: Some(&[0, 0, 0, 0]), // CPU ID'si 0 (32-bit için 4 bayt)
-            interrupts: None, children: DtbNodeChildren((&[]).iter())
+            interrupts: None, phandle: Some(1), cpu: None, capacity_dmips_mhz: Some(1024), initrd: None,
+            children: DtbNodeChildren((&[]).iter())
+        };
+        // /cpus/cpu-map: cluster0/core0 → cpu@0 (phandle 1)
+        static DUMMY_CORE_NODES: [DtbNode; 1] = [DtbNode {
+            name: "core0",
+            compatible: None, reg: None, interrupts: None, phandle: None, cpu: Some(1), capacity_dmips_mhz: None, initrd: None,
+            children: DtbNodeChildren((&[]).iter())
+        }];
+        static DUMMY_CLUSTER_NODES: [DtbNode; 1] = [DtbNode {
+            name: "cluster0",
+            compatible: None, reg: None, interrupts: None, phandle: None, cpu: None, capacity_dmips_mhz: None, initrd: None,
+            children: DtbNodeChildren(DUMMY_CORE_NODES.iter())
+        }];
+        static DUMMY_CPU_MAP_NODE: DtbNode = DtbNode {
+            name: "cpu-map",
+            compatible: None, reg: None, interrupts: None, phandle: None, cpu: None, capacity_dmips_mhz: None, initrd: None,
+            children: DtbNodeChildren(DUMMY_CLUSTER_NODES.iter())
         };
         static DUMMY_MEMORY_NODE: DtbNode = DtbNode {
             name: "memory@80000000",
             compatible: Some("memory"), // Bellek tanımı
             // reg: <başlangıç adresi> <boyut> (örneğin 0x8000_0000 0x4000_0000)
-            // 64-bit için 16 bayt (2 x u64) olurdu. Dummy reg değeri.
-            reg: Some(&[0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
-            interrupts: None, children: DtbNodeChildren((&[]).iter())
+            // 64-bit için 16 bayt (2 x u64, parse_reg_property gibi little-endian).
+            reg: Some(&[0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00]),
+            interrupts: None, phandle: None, cpu: None, capacity_dmips_mhz: None, initrd: None,
+            children: DtbNodeChildren((&[]).iter())
+        };
+        // /reserved-memory: OpenSBI'nin M-mode bölgesi (0x8000_0000, 512 KiB)
+        static DUMMY_RESERVED_CHILDREN: [DtbNode; 1] = [DtbNode {
+            name: "mmode_resv0@80000000",
+            compatible: None,
+            reg: Some(&[0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            interrupts: None, phandle: None, cpu: None, capacity_dmips_mhz: None, initrd: None,
+            children: DtbNodeChildren((&[]).iter())
+        }];
+        static DUMMY_RESERVED_MEMORY_NODE: DtbNode = DtbNode {
+            name: "reserved-memory",
+            compatible: None, reg: None, interrupts: None, phandle: None, cpu: None, capacity_dmips_mhz: None, initrd: None,
+            children: DtbNodeChildren(DUMMY_RESERVED_CHILDREN.iter())
+        };
+        // /chosen: bootloader initrd yüklediyse linux,initrd-start/-end burada bulunur.
+        static DUMMY_CHOSEN_NODE: DtbNode = DtbNode {
+            name: "chosen",
+            compatible: None, reg: None, interrupts: None, phandle: None, cpu: None, capacity_dmips_mhz: None, initrd: None,
+            children: DtbNodeChildren((&[]).iter())
         };
         static DUMMY_UART_NODE: DtbNode = DtbNode {
             name: "uart@10000000",
@@ -91,26 +132,32 @@ mod dtb_parser {
             // reg: <baz adres> <boyut> (örneğin 0x1000_0000 0x100)
              reg: Some(&[0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00]), // Dummy reg değeri
             interrupts: Some(&[/* PLIC interrupt specifier */]), // UART kesmesi
+            phandle: None, cpu: None, capacity_dmips_mhz: None, initrd: None,
             children: DtbNodeChildren((&[]).iter())
         };
          static DUMMY_CLINT_NODE: DtbNode = DtbNode {
              name: "clint@1000000",
              compatible: Some("riscv,clint0"), // CLINT (Core Local Interruptor) tanımı
              reg: Some(&[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, /* size */ 0x00, 0x00, 0x10, 0x00]), // Dummy reg (0x0100_0000 size 0x10000)
-             interrupts: None, children: DtbNodeChildren((&[]).iter())
+             interrupts: None, phandle: None, cpu: None, capacity_dmips_mhz: None, initrd: None,
+             children: DtbNodeChildren((&[]).iter())
          };
          static DUMMY_PLIC_NODE: DtbNode = DtbNode {
              name: "plic@c000000",
              compatible: Some("riscv,plic0"), // PLIC (Platform Level Interrupt Controller) tanımı
              reg: Some(&[0x0c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, /* size */ 0x00, 0x40, 0x00, 0x00]), // Dummy reg (0x0c00_0000 size 0x400000)
-             interrupts: None, children: DtbNodeChildren((&[]).iter())
+             interrupts: None, phandle: None, cpu: None, capacity_dmips_mhz: None, initrd: None,
+             children: DtbNodeChildren((&[]).iter())
          };
 
 
         // Dummy root düğümünün alt düğümleri
-        static DUMMY_ROOT_CHILDREN: [DtbNode; 5] = [
+        static DUMMY_ROOT_CHILDREN: [DtbNode; 8] = [
              DUMMY_CPU_NODE,
+             DUMMY_CPU_MAP_NODE,
              DUMMY_MEMORY_NODE,
+             DUMMY_RESERVED_MEMORY_NODE,
+             DUMMY_CHOSEN_NODE,
              DUMMY_UART_NODE,
              DUMMY_CLINT_NODE,
              DUMMY_PLIC_NODE,
@@ -119,7 +166,7 @@ mod dtb_parser {
         // Dummy root düğümü
         static DUMMY_ROOT_NODE: DtbNode = DtbNode {
             name: "/",
-            compatible: None, reg: None, interrupts: None,
+            compatible: None, reg: None, interrupts: None, phandle: None, cpu: None, capacity_dmips_mhz: None, initrd: None,
             children: DtbNodeChildren(DUMMY_ROOT_CHILDREN.iter()),
         };
 
@@ -180,6 +227,33 @@ pub fn parse_and_initialize(dtb_ptr: usize) -> Result<(), KError> {
     // Kök düğümünden başlayarak tüm düğümleri gez
     process_dtb_node(root)?;
 
+    // Fiziksel çerçeve ayırıcı: /memory bölgeleri process_dtb_node'da eklendi. /reserved-memory
+    // alt düğümleri (OpenSBI vb.), /chosen'daki initrd ve DTB'nin kendisi ayırıcının dışında
+    // tutulur; çekirdek imajını ayırıcı kendisi çıkarır.
+    if let Some(reserved) = find_node(root, &|n| n.name == "reserved-memory") {
+        for region in reserved.children.0.clone() {
+            let (start, size) = dtb_parser::parse_reg_property(region.reg.ok_or(KError::InvalidArgument)?)?;
+            super::kernel_println!("DTB: Ayrılmış bölge '{}': 0x{:x} boyut: 0x{:x}", region.name, start, size);
+            kmemory::reserve_physical_region(start as u64, size as u64)?;
+        }
+    }
+    if let Some((initrd_start, initrd_end)) = find_node(root, &|n| n.name == "chosen").and_then(|n| n.initrd) {
+        super::kernel_println!("DTB: initrd: 0x{:x} - 0x{:x}", initrd_start, initrd_end);
+        kmemory::reserve_physical_region(initrd_start, initrd_end.saturating_sub(initrd_start))?;
+    }
+    // FDT başlığındaki totalsize (ofset 4, big-endian)
+    let dtb_size = u32::from_be(unsafe { core::ptr::read_unaligned((dtb_ptr + 4) as *const u32) });
+    kmemory::reserve_physical_region(dtb_ptr as u64, dtb_size as u64)?;
+    kmemory::init_frame_allocator()?;
+
+    // CPU topolojisi: cpu-map düğümleri CPU düğümlerine phandle ile başvurduğundan,
+    // tüm CPU'lar kaydedildikten sonra ikinci bir geçişte işlenir.
+    if let Some(cpu_map) = find_node(root, &|n| n.name == "cpu-map") {
+        let max_dmips = max_capacity_dmips(root);
+        let partial = ktask::CpuTopology { hw_id: 0, package: 0, cluster: 0, core: 0, thread: 0, capacity: 0, online: 0 };
+        process_cpu_map(root, cpu_map, partial, max_dmips)?;
+    }
+
     super::kernel_println!("Karnal64 DTB RISC-V: Ayrıştırma ve başlatma tamamlandı.");
     Ok(())
 }
@@ -197,7 +271,7 @@ fn process_dtb_node(node: &dtb_parser::DtbNode) -> Result<(), KError> {
             super::kernel_println!("DTB: Bellek bölgesi: 0x{:x} boyut: 0x{:x}", start, size);
 
             // Bellek yöneticisine bu fiziksel bellek bölgesini bildir
-            kmemory::add_physical_memory_region(start, size)?;
+            kmemory::add_physical_memory_region(start as u64, size as u64)?;
         }
         Some("riscv") => { // Genellikle CPU düğümleri "riscv" compatible değerine sahiptir
              if node.name.starts_with("cpu@") { // İsim formatı da CPU'ları belirtebilir
@@ -207,8 +281,9 @@ fn process_dtb_node(node: &dtb_parser::DtbNode) -> Result<(), KError> {
 
                  super::kernel_println!("DTB: CPU ID: {}", cpu_id);
 
-                 // Görev yöneticisine bu CPU'yu bildir
-                 ktask::add_cpu(cpu_id, node)?;
+                 // Görev yöneticisine bu CPU'yu bildir (hart ID → mantıksal CPU indeksi)
+                 let cpu = ktask::add_cpu(cpu_id as u64)?;
+                 super::kernel_println!("DTB: hart {} → CPU {}", cpu_id, cpu);
              } else {
                  // Diğer "riscv" compatible düğümleri (örneğin riscv,isa) şimdilik atla
                  super::kernel_println!("DTB: İşlenmeyen RISC-V düğümü: '{}'", node.name);
@@ -271,6 +346,61 @@ fn process_dtb_node(node: &dtb_parser::DtbNode) -> Result<(), KError> {
     Ok(())
 }
 
+/// `node` altında (kendisi dahil) `pred`'i sağlayan ilk düğümü derinlik öncelikli arar.
+fn find_node<'a>(node: &'a dtb_parser::DtbNode<'a>, pred: &dyn Fn(&dtb_parser::DtbNode) -> bool) -> Option<&'a dtb_parser::DtbNode<'a>> {
+    if pred(node) {
+        return Some(node);
+    }
+    node.children.0.clone().find_map(|child| find_node(child, pred))
+}
+
+/// CPU düğümlerindeki en büyük "capacity-dmips-mhz" değeri; kapasiteler buna göre
+/// ktask::CPU_CAPACITY_MAX ölçeğine çevrilir.
+fn max_capacity_dmips(node: &dtb_parser::DtbNode) -> u32 {
+    let own = if node.name.starts_with("cpu@") { node.capacity_dmips_mhz.unwrap_or(0) } else { 0 };
+    node.children.0.clone().map(max_capacity_dmips).fold(own, u32::max)
+}
+
+/// cpu-map alt ağacını gezer (socketN/clusterN/coreN/threadN) ve `cpu` phandle'ı taşıyan her
+/// yaprak için başvurulan CPU'nun topolojisini ktask'a bildirir.
+fn process_cpu_map(
+    root: &dtb_parser::DtbNode,
+    node: &dtb_parser::DtbNode,
+    topology: ktask::CpuTopology,
+    max_dmips: u32,
+) -> Result<(), KError> {
+    for child in node.children.0.clone() {
+        let index = |prefix: &str| child.name.strip_prefix(prefix).and_then(|n| n.parse::<u32>().ok());
+        let mut topology = topology;
+        if let Some(n) = index("socket") {
+            topology.package = n;
+        } else if let Some(n) = index("cluster") {
+            topology.cluster = n;
+        } else if let Some(n) = index("core") {
+            topology.core = n;
+            topology.thread = 0;
+        } else if let Some(n) = index("thread") {
+            topology.thread = n;
+        }
+        if let Some(phandle) = child.cpu {
+            let cpu_node = find_node(root, &|n| n.name.starts_with("cpu@") && n.phandle == Some(phandle))
+                .ok_or(KError::NotFound)?;
+            topology.hw_id = dtb_parser::parse_cpu_id(cpu_node.reg)? as u64;
+            topology.capacity = match (cpu_node.capacity_dmips_mhz, max_dmips) {
+                (Some(dmips), max) if max > 0 => ((dmips as u64 * ktask::CPU_CAPACITY_MAX as u64) / max as u64).max(1) as u32,
+                _ => ktask::CPU_CAPACITY_MAX,
+            };
+            super::kernel_println!(
+                "DTB: hart {} → paket {} küme {} çekirdek {} iş parçacığı {} (kapasite {})",
+                topology.hw_id, topology.package, topology.cluster, topology.core, topology.thread, topology.capacity
+            );
+            ktask::set_cpu_topology(topology)?;
+        }
+        process_cpu_map(root, child, topology, max_dmips)?;
+    }
+    Ok(())
+}
+
 // --- Kernel Print Macro (Yer Tutucu) ---
 // Kernel alanında çalışan #![no_std] kodları için `println!` gibi
 // çıktı almak amacıyla özel bir makro veya fonksiyon gerekir.
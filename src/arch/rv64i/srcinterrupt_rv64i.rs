@@ -77,6 +77,12 @@ pub extern "C" fn trap_handler(trap_frame: *mut TrapFrame) {
     trap_frame.stval = stval_val;
     trap_frame.scause = cause.bits(); // scause'u usize olarak kaydetmek için bitlerini al
 
+    // Kesme bağlamını kilit doğrulayıcısına bildir (IRQ-güvensiz spinlock tespiti için).
+    let is_interrupt = cause.is_interrupt();
+    if is_interrupt {
+        ksync::lockdep::irq_enter();
+    }
+
     match cause.cause() {
         // --- Eşzamanlı İstisnalar (Exceptions) ---
         Trap::Exception(Exception::UserEnvCall) | Trap::Exception(Exception::SupervisorEnvCall) => {
@@ -124,46 +130,55 @@ pub extern "C" fn trap_handler(trap_frame: *mut TrapFrame) {
             // sepc kaydında hata oluşturan komutun adresi bulunur.
             let fault_pc = sepc_val;
 
-            // TODO: Bellek yöneticisini çağırarak sayfa hatasını çözmeye çalış.
-            // Karnal64'ün kmemory modülünü kullanın.
-            // Örnek: kmemory::handle_page_fault(fault_address, cause, trap_frame);
-            let handled = kmemory::handle_page_fault(fault_address, cause.bits(), trap_frame);
-
-            if !handled {
-                // Sayfa hatası çözülemedi, bu kritik bir hata.
-                // Görevi sonlandır veya panik yap.
-                println!("Page Fault: addr={:x}, pc={:x}, cause={:?}", fault_address, fault_pc, cause.cause());
-                // TODO: Karnal64'ün ktask modülünü kullanarak mevcut görevi sonlandır.
-                ktask::exit_current_task(KError::BadAddress as i64); // Örnek hata kodu
+            // Bellek yöneticisi hatayı görevin VMA'larına göre çözer: ilk dokunulan anonim sayfa
+            // sıfırlanıp, kaynak sayfası sağlayıcıdan okunup eşlenir.
+            let access = match cause.cause() {
+                Trap::Exception(Exception::StorePageFault) => kmemory::PROT_WRITE,
+                Trap::Exception(Exception::InstructionPageFault) => kmemory::PROT_EXEC,
+                _ => kmemory::PROT_READ,
+            };
+            // Çözüm uyuyabilir; yalnızca kesilen bağlamda kesmeler açıksa (SPIE) denenir.
+            let result = if trap_frame.sstatus & SSTATUS_SPIE != 0 {
+                unsafe { core::arch::asm!("csrsi sstatus, 2", options(nomem, nostack)) };
+                kmemory::handle_page_fault(fault_address, access)
+            } else {
+                Err(KError::BadAddress)
+            };
+
+            match result {
+                // sepc değişmez, aynı komut tekrar çalıştırılır. Yeni PTE'nin görülmesi için bu
+                // adresin (varsa geçersiz) çevirisi yerel TLB'den silinir.
+                Ok(()) => unsafe {
+                    core::arch::asm!("sfence.vma {0}, zero", in(reg) fault_address, options(nostack))
+                },
+                Err(reason) => {
+                    println!("Page Fault: addr={:x}, pc={:x}, cause={:?}", fault_address, fault_pc, cause.cause());
+                    // Görevin istisna portuna ilet; port yoksa görev sonlandırılır ve ebeveyni
+                    // task_wait ile hata nedenini alır.
+                    deliver_user_fault(reason, fault_address, trap_frame);
+                }
             }
-
-            // Sayfa hatası çözüldüyse (örneğin, sayfa eşlendi),
-            // sepc değişmez, aynı komut tekrar çalıştırılır ve bu sefer hata oluşmaz.
         }
         // TODO: Diğer istisna türlerini ekleyin (örn. hizalama hataları, geçersiz komut vb.)
         // Bunların çoğu görev sonlandırmayla sonuçlanabilir.
         Trap::Exception(_) => {
              // Bilinmeyen veya işlenmeyen istisna
              println!("Unhandled Exception: cause={:?}, pc={:x}, stval={:x}", cause.cause(), sepc_val, stval_val);
-             // TODO: Karnal64'ün ktask modülünü kullanarak mevcut görevi sonlandır.
-             ktask::exit_current_task(KError::InternalError as i64); // Örnek hata kodu
+             // Görevin istisna portuna ilet; port yoksa görev sonlandırılır ve ebeveyni
+             // task_wait ile hata nedenini alır.
+             deliver_user_fault(KError::InternalError, stval_val, trap_frame);
         }
 
         // --- Zaman Uyumsuz Kesmeler (Interrupts) ---
         Trap::Interrupt(Interrupt::SupervisorTimer) => {
             // Süpervizör Zamanlayıcı Kesmesi
-            // Bu kesme, zamanlayıcı (genellikle CLINT) tarafından periyodik olarak üretilir
-            // ve görev zamanlama için kullanılır.
-
-            // TODO: Zamanlayıcı kesmesini CLINT'te veya ilgili donanımda temizle.
-            // Bu çok önemli, yoksa kesme tekrar tekrar tetiklenir.
-             use riscv::register::mtimecmp; // CLINT için mtimecmp yazma
-             let next_timer = get_current_time() + TIMER_INTERVAL;
-             unsafe { mtimecmp::write(next_timer); }
+            // Zamanlayıcı tek seferliktir (SBI set_timer, bkz. srctask_rv64i.rs::program_timer).
+            // STIP, zamanlayıcı ileri bir ana kurulunca temizlenir; timer_tick bunu her zaman
+            // bir sonraki olay (uyanma, zaman dilimi sonu, yük dengeleme) için yapar.
 
-            // TODO: Zamanlayıcı tikini işle ve görev zamanlayıcıyı (scheduler) çalıştır.
-            // Karnal64'ün ktask modülünü kullanın.
-            ktask::timer_tick(trap_frame); // Scheduler bu fonksiyonda görev değiştirebilir
+            // Süresi dolan bekleyenleri uyandır, zaman dilimini/öncelikleri kontrol et.
+            // Gerekirse CPU yeniden zamanlama için işaretlenir; geçiş aşağıda, kesme çıkışında yapılır.
+            karnal64::ktask::timer_tick(karnal64::ktask::now_ns());
 
             // Kesme işleyiciden geri döndüğümüzde sepc'nin ayarlanmasına gerek yok,
             // çünkü kesintiye uğrayan komutun kaldığı yerden devam etmesi gerekir.
@@ -173,19 +188,24 @@ pub extern "C" fn trap_handler(trap_frame: *mut TrapFrame) {
             // Genellikle PLIC (Platform-Level Interrupt Controller) tarafından yönlendirilen
             // harici cihazlardan (UART, disk, ağ kartı vb.) gelen kesmeler.
 
-            // TODO: PLIC'i sorgulayarak hangi cihazın kesme ürettiğini bul.
-            // TODO: İlgili cihaz sürücüsünün kesme işleyicisini çağır.
-            // Sürücüler Karnal64'ün ResourceProvider traitini implemente edebilir ve
-            // kesmeleri işlemek için özel bir mekanizmaya sahip olabilirler.
-            // Örnek: let interrupt_id = plic::claim();
-                    resource_manager::dispatch_interrupt(interrupt_id); // ResourceProvider traitinin parçası olabilir
-                    plic::complete(interrupt_id); // Kesmeyi PLIC'te tamamla
-
-            println!("Unhandled Supervisor External Interrupt");
-            // İşlenmeyen kesmeler genellikle bir hata değildir, sadece göz ardı edilebilir
-            // veya bir debug mesajı verilebilir.
+            // Üst yarı: PLIC'ten kesme kimliğini al (claim). Kaynak, complete yazılana kadar bu
+            // hart'a tekrar gönderilmez; sürücüye iletme ve tamamlama alt yarıda, bu hart'ın
+            // iş kuyruğu çalışanında yapılır (claim/complete aynı PLIC bağlamında olmalıdır).
+            let interrupt_id = plic::claim();
+            if interrupt_id != 0 {
+                if ktask::queue_work(plic_bottom_half, interrupt_id as usize).is_err() {
+                    println!("PLIC: iş kuyruğu dolu, kesme {} işlenmeden tamamlandı", interrupt_id);
+                    plic::complete(interrupt_id);
+                }
+            }
+        }
+        Trap::Interrupt(Interrupt::SupervisorSoft) => {
+            // Süpervizör Yazılım Kesmesi: başka bir hart'tan SBI ile gönderilen IPI
+            // (yeniden zamanlama veya TLB temizleme isteği).
+            unsafe { core::arch::asm!("csrci sip, 2", options(nomem, nostack)) }; // SSIP'i temizle
+            karnal64::ktask::handle_ipi();
         }
-        // TODO: Diğer kesme türlerini ekleyin (örn. yazılım kesmesi, makine kesmeleri)
+        // TODO: Diğer kesme türlerini ekleyin (örn. makine kesmeleri)
         Trap::Interrupt(_) => {
              // Bilinmeyen veya işlenmeyen kesme
              println!("Unhandled Interrupt: cause={:?}", cause.cause());
@@ -193,10 +213,55 @@ pub extern "C" fn trap_handler(trap_frame: *mut TrapFrame) {
         }
     }
 
+    if is_interrupt {
+        ksync::lockdep::irq_exit();
+        // Kesilen bağlam kesintiye uğratılabilirse (spinlock tutmuyorsa) ve daha önemli bir
+        // iş parçacığı hazırsa burada ona geçilir. Bu iş parçacığı tekrar seçildiğinde
+        // buradan devam eder ve kendi trap_frame'ine döner.
+        karnal64::ktask::preempt_on_interrupt_exit();
+    }
+
     // Buraya ulaşıldığında, tuzak/kesme işlenmiş demektir.
     // Assembly kodu, trap_frame'deki kayıtlı durumları yükleyerek geri dönecektir.
-    // Eğer ktask::timer_tick veya handle_syscall içinde görev değişimi olduysa,
-    // assembly yeni görevin trap_frame'ini yükleyecektir.
+    // trap_frame mevcut iş parçacığının çekirdek yığınında olduğundan, araya giren bağlam
+    // değişimleri onu bozmaz.
+}
+
+/// Harici kesmenin alt yarısı: kesmeler açıkken kaynağın sürücüsünü çağırır ve kesmeyi
+/// PLIC'te tamamlar.
+fn plic_bottom_half(interrupt_id: usize) {
+    // Sürücüler Karnal64'ün ResourceProvider traitini implemente edebilir ve kesmeleri işlemek
+    // için özel bir mekanizmaya sahip olabilirler.
+    resource_manager::dispatch_interrupt(interrupt_id as u32); // ResourceProvider traitinin parçası olabilir
+    plic::complete(interrupt_id as u32); // Kesmeyi PLIC'te tamamla
+}
+
+/// sstatus.SPP: tuzak süpervizör modundan geldiyse 1.
+const SSTATUS_SPP: usize = 1 << 8;
+/// sstatus.SPIE: tuzaktan önce kesmeler açıktıysa 1.
+const SSTATUS_SPIE: usize = 1 << 5;
+
+/// Çözülemeyen bir kullanıcı modu istisnasını ktask istisna portuna iletir. İşleyici iş parçacığını
+/// devam ettirirse (değiştirmiş olabileceği) yazmaçlar tuzak çerçevesine geri yazılır.
+/// sstatus geri yazılmaz: SPP/SPIE bitleri çekirdeğin denetimindedir.
+fn deliver_user_fault(reason: KError, address: usize, trap_frame: &mut TrapFrame) {
+    // Çekirdek modundaki bir hatanın yazmaçları kullanıcı işleyicisine gösterilmez.
+    if trap_frame.sstatus & SSTATUS_SPP != 0 {
+        ktask::terminate_current_task(reason);
+    }
+    let mut regs = ktask::FaultRegisters::default();
+    regs.pc = trap_frame.sepc as u64;
+    regs.sp = trap_frame.regs[1] as u64; // x2
+    regs.status = trap_frame.sstatus as u64;
+    for (gpr, &value) in regs.gpr[1..].iter_mut().zip(trap_frame.regs.iter()) {
+        *gpr = value as u64;
+    }
+    ktask::deliver_fault(reason, trap_frame.scause as u64, address as u64, &mut regs);
+    trap_frame.sepc = regs.pc as usize;
+    for (value, &gpr) in trap_frame.regs.iter_mut().zip(regs.gpr[1..].iter()) {
+        *value = gpr as usize;
+    }
+    trap_frame.regs[1] = regs.sp as usize;
 }
 
 // --- Başlatma Fonksiyonu ---
@@ -236,42 +301,64 @@ pub fn init() {
     println!("RISC-V Tuzak İşleyici Başlatıldı.");
 }
 
-// --- Yardımcı/Placeholder Fonksiyonlar (Kendi Karnal64 modüllerinizde bulunmalı) ---
-
-// Karnal64 modülleri için dummy implementasyonlar (Bu dosyada bulunmaz, sadece referans amaçlıdır)
-mod kmemory {
-    use super::*;
-    // Bu fonksiyon kmemory modülünüzde olacak
-    #[allow(unused_variables)]
-    pub fn handle_page_fault(fault_address: usize, cause_bits: usize, trap_frame: &mut TrapFrame) -> bool {
-        // Gerçek sayfa hatası işleme mantığı (talep üzerine yükleme, copy-on-write vb.)
-        // Çözülürse true, çözülemezse false döner.
-        println!("KMemory: Sayfa hatası işlenmeye çalışılıyor (addr={:x})... (Yer Tutucu)", fault_address);
-        // Örnek: Her hatayı çözülememiş say
-        false // Gerçekte burada adres alanlarına bakıp sayfa ayırma/eşleme yapılır.
-    }
-     // kmemory diğer fonksiyonları...
+// --- Karnal64 Kesme Maskesi Kancaları (hardware_specific.h) ---
+// ksync::Spinlock::lock_irqsave ve çekirdek genelindeki kesme aç/kapa işlemleri bunları kullanır.
+// Kaydedilen durum sstatus.SIE bitidir (bit 1).
+
+const SSTATUS_SIE: u64 = 1 << 1;
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_enable() {
+    unsafe { core::arch::asm!("csrsi sstatus, 2", options(nomem, nostack)) };
 }
 
-mod ktask {
-    use super::*;
-     // Bu fonksiyonlar ktask modülünüzde olacak
-    #[allow(unused_variables)]
-    pub fn exit_current_task(exit_code: i64) -> ! {
-        // Mevcut görevi sonlandırma mantığı
-        // Bağlamı serbest bırak, kaynakları temizle, scheduler'a bildir.
-        // Bu fonksiyon asla geri dönmez.
-        println!("KTASK: Görev sonlandırılıyor (exit_code={})... (Yer Tutucu)", exit_code);
-        loop {} // Gerçekte scheduler başka bir göreve geçer.
-    }
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_disable() {
+    unsafe { core::arch::asm!("csrci sstatus, 2", options(nomem, nostack)) };
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupts_enabled() -> i32 {
+    let sstatus: u64;
+    unsafe { core::arch::asm!("csrr {0}, sstatus", out(reg) sstatus, options(nomem, nostack)) };
+    (sstatus & SSTATUS_SIE != 0) as i32
+}
+
+/// SIE'yi temizler ve önceki SIE bitini döndürür (tek atomik CSR komutu).
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_save() -> u64 {
+    let sstatus: u64;
+    unsafe { core::arch::asm!("csrrci {0}, sstatus, 2", out(reg) sstatus, options(nomem, nostack)) };
+    sstatus & SSTATUS_SIE
+}
 
-    #[allow(unused_variables)]
-    pub fn timer_tick(trap_frame: &mut TrapFrame) {
-        // Zamanlayıcı tikini işle, scheduler'ı çalıştır.
-        println!("KTASK: Zamanlayıcı tik işleniyor... (Yer Tutucu)");
-        // Scheduler burada görev değiştirmeye karar verebilir.
-        // Eğer görev değişirse, trap_frame pointer'ı scheduler tarafından
-        // yeni görevin çekirdek stack'indeki trap frame'ine ayarlanır.
+/// `low_level_interrupt_save` ile kaydedilen SIE bitini geri yükler.
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_restore(flags: u64) {
+    unsafe { core::arch::asm!("csrs sstatus, {0}", in(reg) flags & SSTATUS_SIE, options(nomem, nostack)) };
+}
+
+// --- Karnal64 Yığın İzi Kancası (hardware_specific.h) ---
+// lockdep raporlarında kullanılır; çekirdek -Cforce-frame-pointers ile derlenmelidir.
+
+/// s0 (fp) çerçevenin üstünü gösterir: [fp - 8] ra, [fp - 16] önceki fp.
+/// Zincir sıfıra, hizasız bir adrese veya yığında aşağı doğru bir çerçeveye ulaşınca durur.
+#[no_mangle]
+pub extern "C" fn low_level_stack_trace(frames: *mut u64, max_frames: usize) -> usize {
+    let mut fp: u64;
+    unsafe { core::arch::asm!("mv {0}, s0", out(reg) fp, options(nomem, nostack)) };
+    let mut count = 0;
+    while count < max_frames && fp >= 16 && fp % 8 == 0 {
+        let (ra, prev) = unsafe { (*((fp - 8) as *const u64), *((fp - 16) as *const u64)) };
+        if ra == 0 {
+            break;
+        }
+        unsafe { *frames.add(count) = ra };
+        count += 1;
+        if prev <= fp {
+            break;
+        }
+        fp = prev;
     }
-    // ktask diğer fonksiyonları...
+    count
 }
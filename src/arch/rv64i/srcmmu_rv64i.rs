@@ -5,6 +5,9 @@
 // use crate::karnal64::{KError, KHandle}; // Eğer karnal64 modülü crate kökünde ise
 use super::karnal64::{KError, KHandle}; // Eğer karnal64 modülü super modülde ise (yaygın kernel yapısı)
 
+use core::arch::asm;
+use core::sync::atomic::{AtomicU64, Ordering};
+
 // --- RISC-V MMU Sabitleri ---
 
 /// Sayfa boyutu (4KB)
@@ -14,11 +17,11 @@ const PAGE_SHIFT: usize = 12;
 
 /// Sanal adresin sayfa ofseti (VA[11:0])
 const VA_OFFSET_BITS: usize = 12;
-/// Sanal adresin 1. seviye dizini (VA[21:12])
-const VA_VPN0_BITS: usize = 10;
-/// Sanal adresin 2. seviye dizini (VA[30:22])
-const VA_VPN1_BITS: usize = 10;
-/// Sanal adresin 3. seviye dizini (VA[38:31]) - Sv39 için
+/// Sanal adresin 1. seviye dizini (VA[20:12])
+const VA_VPN0_BITS: usize = 9;
+/// Sanal adresin 2. seviye dizini (VA[29:21])
+const VA_VPN1_BITS: usize = 9;
+/// Sanal adresin 3. seviye dizini (VA[38:30]) - Sv39 için
 const VA_VPN2_BITS: usize = 9;
 
 /// Sayfa tablosu girişlerinin bir tablodaki sayısı (PAGE_SIZE / 8 byte/PTE)
@@ -136,26 +139,13 @@ impl PageTable {
 }
 
 
-// --- Fiziksel Sayfa Çerçeve Yöneticisi (Varsayımsal) ---
-// Gerçekte başka bir modülde implemente edilmesi gereken arayüz.
-// Basitlik adına burada sadece fonksiyon imzaları yer alıyor.
-
-/// Fiziksel bir sayfa çerçevesi (4KB) tahsis eder.
-/// Başarı durumunda tahsis edilen çerçevenin fiziksel adresini (sayfa hizalı),
-/// hata durumunda KError döndürür.
-fn allocate_physical_frame() -> Result<u64, KError> {
-    // TODO: Fiziksel bellek havuzundan boş bir sayfa çerçevesi bul ve işaretle
-    // Yer tutucu: Başarı durumunda 0x81000000 gibi dummy bir adres döndürsün
-    // (gerçek kernelde buradan bellek altyapısı devreye girer)
-    Err(KError::OutOfMemory) // Placeholder: Henüz implemente edilmedi
-}
-
-/// Fiziksel bir sayfa çerçevesini serbest bırakır.
-/// `paddr`: Serbest bırakılacak sayfa çerçevesinin fiziksel adresi (sayfa hizalı).
-fn free_physical_frame(paddr: u64) {
-    // TODO: Fiziksel bellek havuzunda ilgili çerçeveyi boş olarak işaretle
-    // Yer tutucu: Hiçbir şey yapmıyor
-}
+// --- Fiziksel Sayfa Çerçeve Yöneticisi ---
+// Çerçeveler kmemory'nin buddy ayırıcısından alınır (bkz. src/memory/srcframe.rs). Ayırıcı,
+// RiscvMemoryManager::init'ten önce DTB /memory ve /reserved-memory düğümleriyle başlatılır
+// (srcdtb_rv64i.rs). Dönen adresler sayfa hizalı fiziksel adreslerdir.
+use super::karnal64::kmemory::{
+    allocate_physical_frame, free_physical_frame, register_arch_mmu, ArchMmu, PROT_EXEC, PROT_NONE, PROT_WRITE,
+};
 
 
 // --- RISC-V MMU Yöneticisi ---
@@ -372,11 +362,11 @@ impl RiscvMemoryManager {
         Err(KError::InternalError)
     }
 
-     /// Sayfa tablosundan belirli bir sanal adrese ait eşleşmeyi kaldırır.
-     /// Gerekirse boşalan ara seviye sayfa tablolarını serbest bırakır (basit implementasyonda serbest bırakmayabiliriz).
+     /// Sayfa tablosundan belirli bir sanal adrese ait eşleşmeyi kaldırır ve eşlenmiş fiziksel
+     /// sayfanın adresini döner. Fiziksel sayfa serbest bırakılmaz; onun sahibi (kmemory) bırakır.
     /// `root_page_table_paddr`: Güncellenecek kök sayfa tablosunun fiziksel adresi.
     /// `vaddr`: Eşleşmesi kaldırılacak sanal adres (sayfa hizalı).
-    pub fn unmap_page_in_table(root_page_table_paddr: u64, vaddr: u64) -> Result<(), KError> {
+    pub fn unmap_page_in_table(root_page_table_paddr: u64, vaddr: u64) -> Result<u64, KError> {
         let mut current_pt_paddr = root_page_table_paddr;
         let mut current_vaddr = vaddr;
 
@@ -405,11 +395,10 @@ impl RiscvMemoryManager {
                       // Beklenmedik şekilde tablo PTE var
                      return Err(KError::InternalError);
                  }
-                 let freed_paddr = pte.ppn() << PAGE_SHIFT; // Serbest bırakılacak fiziksel sayfa
+                 let unmapped_paddr = pte.ppn() << PAGE_SHIFT; // Eşlenmiş fiziksel sayfa
                 *pte = PageTableEntry::empty(); // PTE'yi geçersiz yap
-                free_physical_frame(freed_paddr); // Fiziksel sayfayı serbest bırak
                 // TODO: sfence.vma ile TLB'yi temizle (bu vaddr için veya global)
-                return Ok(());
+                return Ok(unmapped_paddr);
             } else {
                 // Ara seviye
                  if pte.is_leaf() {
@@ -425,197 +414,168 @@ impl RiscvMemoryManager {
     }
 
 
-    // --- Karnal64 Memory API Karşılıkları ---
-    // Bu fonksiyonlar, karnal64.rs'deki public `memory_*` fonksiyonları tarafından çağrılmak üzere tasarlanmıştır.
-    // Buradaki implementasyonlar, yukarıda tanımlanan MMU operasyonlarını kullanır.
-    // Tek bir RiscvMemoryManager instance'ı olduğu varsayılır (singleton gibi).
-
-    // Not: Gerçek implementasyonda, bu fonksiyonlar hangi görevin (task) sanal alanında işlem
-    // yapacaklarını bilmelidir. Bu bilgi genellikle görev kontrol bloğundan (TCB) alınır
-    // ve TCB, aktif görevin kök sayfa tablosunun fiziksel adresini (SATP değeri) saklar.
-    // Basitlik adına, şimdilik sadece çekirdek sayfa tablosunu veya varsayılan aktif görevinkini kullandığımızı varsayalım.
-
-    /// Kullanıcı alanı için bellek tahsis eder.
-    /// `size`: Tahsis edilecek bellek boyutu (byte olarak).
-    /// Başarı durumunda kullanıcı alanında geçerli bir sanal adrese işaret eden pointer,
-    /// hata durumunda KError döner.
-    /// Güvenlik: Döndürülen pointer, çağrı yapan görev için geçerli ve erişilebilir olmalıdır.
-    pub fn allocate_user_memory(&mut self, size: usize) -> Result<*mut u8, KError> {
-        if size == 0 { return Ok(core::ptr::null_mut()); }
-        // Boyutu sayfa boyutuna yuvarla
-        let pages_needed = (size + PAGE_SIZE - 1) / PAGE_SIZE;
-
-        // TODO: Görev kontrol bloğundan (TCB) veya görev adres alanı yöneticisinden
-        // kullanıcı alanı için boş bir sanal adres aralığı bul.
-        let user_vaddr_start = 0x40000000; // Örnek: Kullanıcı alanı 1GB'dan başlasın
-        // TODO: Bu aralığın müsait olduğunu doğrula.
-        let mut current_vaddr = user_vaddr_start;
-
-        let current_task_root_pt_paddr = self.kernel_page_table_paddr; // TODO: Gerçek görevinki olmalı!
-
-        // Gerekli sayıda fiziksel sayfa tahsis et ve sanal adreslere haritala
-        for _ in 0..pages_needed {
-            let paddr = allocate_physical_frame()?; // Fiziksel sayfa tahsis et
-            // Kullanıcı için haritala (Okuma, Yazma, Kullanıcı izni)
-            Self::map_page_in_table(
-                current_task_root_pt_paddr,
-                current_vaddr,
-                paddr,
-                PteFlags::R.bits() | PteFlags::W.bits(), // RW izinleri (X hariç)
-                false // Kullanıcı sayfası
-            )?;
-            current_vaddr += PAGE_SIZE as u64;
-        }
+    // Kullanıcı belleği ayırma/bırakma ve paylaşımlı bellek kmemory'dedir (bkz. src/memory/srcvmspace.rs);
+    // kmemory bu tabloları aşağıdaki `ArchMmu` uygulaması üzerinden doldurur.
 
-        // Tahsis edilen aralığın başlangıç sanal adresini döndür (pointer olarak)
-        Ok(user_vaddr_start as *mut u8)
-    }
 
-    /// Kullanıcı alanı belleğini serbest bırakır.
-    /// `ptr`: Serbest bırakılacak bellek bloğunun başlangıç pointer'ı (kullanıcı alanı sanal adresi).
-    /// `size`: Serbest bırakılacak bellek bloğunun boyutu (byte olarak).
-    /// Başarı veya hata (geçersiz pointer gibi) döner.
-    /// Güvenlik: Verilen pointer'ın ve boyutun çağıran görev tarafından tahsis edilmiş geçerli bir bloğu temsil ettiğini doğrula.
-    pub fn free_user_memory(&mut self, ptr: *mut u8, size: usize) -> Result<(), KError> {
-         if ptr.is_null() || size == 0 { return Ok(()); }
-         let vaddr_start = ptr as u64;
-         // Boyutu sayfa boyutuna yuvarla
-        let pages_needed = (size + PAGE_SIZE - 1) / PAGE_SIZE;
-
-         // TODO: Verilen pointer'ın ve boyutun gerçekten daha önce bu görev için tahsis edilmiş bir blok olduğunu doğrula.
-         // Bu, görev adres alanı yöneticisinin takip etmesi gereken bir bilgidir.
-         // Basitlik için sadece unmap işlemi yapalım.
-
-        let current_task_root_pt_paddr = self.kernel_page_table_paddr; // TODO: Gerçek görevinki olmalı!
-        let mut current_vaddr = vaddr_start;
-
-        for _ in 0..pages_needed {
-             Self::unmap_page_in_table(current_task_root_pt_paddr, current_vaddr)?;
-            current_vaddr += PAGE_SIZE as u64;
+    // TODO: Diğer MMU/Bellek Yönetimi ile ilgili fonksiyonlar:
+    // - Adres alanları arasında geçiş (switch_to_address_space) - SATP kaydı güncelleme
+    // - Copy-on-Write implementasyonu
+    // - Page fault işleme
+    // - Büyük sayfa (superpage) desteği
+}
+
+// --- kmemory Entegrasyonu ---
+// Görev adres alanları (VMA'lar, boş aralık arama, paylaşımlı bellek nesneleri, çerçevelerin
+// sahipliği) mimariden bağımsız kmemory modülündedir (bkz. src/memory/srcvmspace.rs). Bu port
+// kmemory'ye yalnızca Sv39 sayfa tablosu işlemlerini `kmemory::ArchMmu` üzerinden sağlar.
+// Çekirdek kimlik eşlemesi de alt yarıda durduğundan her kullanıcı kök tablosu çekirdek kök
+// tablosunun girdilerini paylaşır: paylaşılan bir girdinin kapsadığı 1GB'lık aralığa kullanıcı
+// sayfası eşlenmez ve o girdinin tabloları adres alanıyla birlikte bırakılmaz.
+
+/// Sv39'da kullanıcı VA aralığının sonu (kök tablonun alt yarısı).
+const USER_VA_LIMIT: u64 = 1 << 38;
+/// Kullanıcı VA aralığını kapsayan kök tablo girdisi sayısı.
+const USER_ROOT_ENTRIES: usize = ENTRIES_PER_PAGE_TABLE / 2;
+/// satp.PPN alanı (44 bit).
+const SATP_PPN_MASK: u64 = (1 << 44) - 1;
+
+/// Çekirdek kök sayfa tablosunun fiziksel adresi (`init` sırasında satp'den okunur).
+static KERNEL_ROOT: AtomicU64 = AtomicU64::new(0);
+
+/// `vaddr`'ın `level` seviyesindeki (2 = kök) tablo dizini.
+fn vpn_index(vaddr: u64, level: usize) -> usize {
+    ((vaddr >> (PAGE_SHIFT + level * VA_VPN0_BITS)) & (ENTRIES_PER_PAGE_TABLE as u64 - 1)) as usize
+}
+
+/// Kök tablonun `index` girdisi çekirdek kök tablosundan kopyalanmış mı?
+unsafe fn is_kernel_entry(index: usize, entry: PageTableEntry) -> bool {
+    let kernel_root = KERNEL_ROOT.load(Ordering::Relaxed);
+    kernel_root != 0 && entry.is_valid() && (*(kernel_root as *const PageTable)).entries[index].0 == entry.0
+}
+
+/// `vaddr` sayfasının yaprak PTE'si; sayfa eşli değilse `None`.
+unsafe fn leaf_entry(root: u64, vaddr: u64) -> Option<&'static mut PageTableEntry> {
+    let mut table = root;
+    for level in [2, 1] {
+        let pte = (*(table as *const PageTable)).entries[vpn_index(vaddr, level)];
+        if !pte.is_table() {
+            return None;
         }
+        table = pte.ppn() << PAGE_SHIFT;
+    }
+    let pte = (*(table as *mut PageTable)).entry(vpn_index(vaddr, 0));
+    if pte.is_leaf() {
+        Some(pte)
+    } else {
+        None
+    }
+}
 
-        Ok(())
+/// PROT_* korumasını yaprak PTE bayraklarına çevirir; U bayrağını `map_page_in_table` ekler.
+/// Sv39'da R/W/X'siz geçerli girdi bir sonraki tabloyu gösterdiğinden (ve W, R'siz
+/// kullanılamadığından) her sayfa okunur eşlenir; PROT_NONE sayfa U'suz, yalnızca çekirdeğe
+/// açık kalır. A/D önceden kurulur, donanım bunları güncellemiyorsa hata üretilmez.
+fn prot_to_flags(prot: u32) -> u64 {
+    let mut flags = PteFlags::V.bits() | PteFlags::R.bits() | PteFlags::A.bits() | PteFlags::D.bits();
+    if prot & PROT_WRITE != 0 {
+        flags |= PteFlags::W.bits();
+    }
+    if prot & PROT_EXEC != 0 {
+        flags |= PteFlags::X.bits();
     }
+    flags
+}
+
+struct Rv64Mmu;
 
-    /// Paylaşımlı bellek alanı oluşturur.
-    /// `size`: Oluşturulacak paylaşımlı bellek boyutu (byte olarak).
-    /// Başarı durumunda paylaşımlı bellek alanını temsil eden bir Karnal64 handle'ı,
-    /// hata durumunda KError döner.
-    /// Paylaşımlı bellek, farklı görevler tarafından kendi adres alanlarına haritalanabilir.
-    pub fn create_shared_memory(&mut self, size: usize) -> Result<KHandle, KError> {
-        if size == 0 { return Err(KError::InvalidArgument); }
-         let pages_needed = (size + PAGE_SIZE - 1) / PAGE_SIZE;
-
-         // Paylaşımlı bellek için fiziksel sayfalar tahsis et
-         let mut physical_frames = Vec::new(); // Veya `no_std` uyumlu bir liste/dizi
-         for _ in 0..pages_needed {
-             let paddr = allocate_physical_frame()?;
-             physical_frames.push(paddr);
-         }
-
-         // TODO: Paylaşımlı bellek alanını çekirdek içinde bir nesne olarak kaydet.
-         // Bu nesne, tahsis edilen fiziksel sayfa listesini ve boyutu saklar.
-         // Kaynak yöneticisi (kresource) veya ayrı bir paylaşımlı bellek yöneticisi bunu yapabilir.
-         // Bu nesne için bir KHandle oluşturulur ve döndürülür.
-
-         // Yer tutucu: Dummy handle döndürelim ve fiziksel frame'leri hemen serbest bırakalım (yapılmamalı!)
-         for paddr in physical_frames {
-             free_physical_frame(paddr); // Gerçekte, handle serbest bırakılınca serbest bırakılmalı
-         }
-         Ok(KHandle(42)) // Dummy handle
+impl ArchMmu for Rv64Mmu {
+    unsafe fn create_page_tables(&self) -> Result<u64, KError> {
+        let root = allocate_physical_frame()?;
+        let kernel_root = KERNEL_ROOT.load(Ordering::Relaxed);
+        if kernel_root != 0 {
+            core::ptr::copy_nonoverlapping(kernel_root as *const u8, root as *mut u8, PAGE_SIZE);
+        } else {
+            core::ptr::write_bytes(root as *mut u8, 0, PAGE_SIZE);
+        }
+        Ok(root)
     }
 
+    unsafe fn map_page(&self, root: u64, vaddr: u64, paddr: u64, prot: u32) -> Result<(), KError> {
+        let index = vpn_index(vaddr, 2);
+        if vaddr >= USER_VA_LIMIT || is_kernel_entry(index, (*(root as *const PageTable)).entries[index]) {
+            return Err(KError::BadAddress);
+        }
+        RiscvMemoryManager::map_page_in_table(root, vaddr, paddr, prot_to_flags(prot), prot == PROT_NONE)
+    }
 
-    /// Mevcut görevin adres alanına paylaşımlı bellek alanını haritalar.
-    /// `shm_handle_value`: Paylaşımlı bellek alanının handle değeri.
-    /// `offset`: Paylaşımlı bellek alanının başlangıcından haritalanacak ofset (byte olarak).
-    /// `size`: Haritalanacak bölümün boyutu (byte olarak).
-    /// Başarı durumunda görevin adres alanında geçerli bir sanal adrese işaret eden pointer,
-    /// hata durumunda KError döner.
-    /// Güvenlik: Handle'ın geçerli bir paylaşımlı bellek nesnesine ait olduğunu ve çağıran görevin haritalama izni olduğunu doğrula.
-    pub fn map_shared_memory(&mut self, shm_handle_value: u64, offset: usize, size: usize) -> Result<*mut u8, KError> {
-        if size == 0 { return Ok(core::ptr::null_mut()); }
-         if offset % PAGE_SIZE != 0 || size % PAGE_SIZE != 0 {
-             // Ofset ve boyut sayfa hizalı olmalı
-             return Err(KError::InvalidArgument);
-         }
-
-         // TODO: shm_handle_value'yu kullanarak paylaşımlı bellek nesnesini bul.
-         // Bu nesneden fiziksel sayfa listesini ve toplam boyutu al.
-         // Offset ve size'ın geçerli aralıkta olduğunu doğrula.
-
-         // Yer tutucu: Dummy paylaşımlı bellek nesnesi varsayalım
-         let dummy_shm_physical_frames = vec![0x82000000, 0x82001000]; // Örnek: 2 sayfalık SHM
-         let total_shm_size = dummy_shm_physical_frames.len() * PAGE_SIZE;
-
-         if offset + size > total_shm_size {
-             return Err(KError::InvalidArgument); // Aralık SHM boyutunu aşıyor
-         }
-
-         // TODO: Görev kontrol bloğundan (TCB) veya görev adres alanı yöneticisinden
-         // kullanıcı alanı için boş bir sanal adres aralığı bul (haritalama için).
-         let user_vaddr_start = 0x50000000; // Örnek: Paylaşımlı bellek 1.25GB'dan başlasın
-         // TODO: Bu aralığın müsait olduğunu doğrula.
-         let mut current_vaddr = user_vaddr_start;
-
-         let current_task_root_pt_paddr = self.kernel_page_table_paddr; // TODO: Gerçek görevinki olmalı!
-         let start_page_index = offset / PAGE_SIZE;
-         let pages_to_map = size / PAGE_SIZE;
-
-         // Paylaşımlı belleğin ilgili fiziksel sayfalarını görevin sanal adres alanına haritala
-        for i in 0..pages_to_map {
-             let paddr_to_map = dummy_shm_physical_frames[start_page_index + i];
-             // Kullanıcı için haritala (Okuma, Yazma, Kullanıcı izni - X olmamalı)
-             Self::map_page_in_table(
-                 current_task_root_pt_paddr,
-                 current_vaddr,
-                 paddr_to_map,
-                 PteFlags::R.bits() | PteFlags::W.bits(), // RW izinleri
-                 false // Kullanıcı sayfası
-             )?;
-             current_vaddr += PAGE_SIZE as u64;
+    unsafe fn unmap_page(&self, root: u64, vaddr: u64) -> Option<u64> {
+        let index = vpn_index(vaddr, 2);
+        if vaddr >= USER_VA_LIMIT || is_kernel_entry(index, (*(root as *const PageTable)).entries[index]) {
+            return None;
         }
+        RiscvMemoryManager::unmap_page_in_table(root, vaddr).ok()
+    }
 
-         // Haritalanan aralığın başlangıç sanal adresini döndür (pointer olarak)
-        Ok(user_vaddr_start as *mut u8)
+    unsafe fn protect_page(&self, root: u64, vaddr: u64, prot: u32) -> bool {
+        let index = vpn_index(vaddr, 2);
+        if vaddr >= USER_VA_LIMIT || is_kernel_entry(index, (*(root as *const PageTable)).entries[index]) {
+            return false;
+        }
+        match leaf_entry(root, vaddr) {
+            Some(pte) => {
+                let user = if prot == PROT_NONE { 0 } else { PteFlags::U.bits() };
+                *pte = PageTableEntry::new(pte.ppn(), prot_to_flags(prot) | user);
+                true
+            }
+            None => false,
+        }
     }
 
-     /// Paylaşımlı bellek alanının görevin adres alanındaki eşleşmesini kaldırır.
-    /// `ptr`: Haritalanmış paylaşımlı bellek bloğunun başlangıç pointer'ı (kullanıcı alanı sanal adresi).
-    /// `size`: Haritalanmış bloğun boyutu (byte olarak).
-    /// Başarı veya hata (geçersiz pointer gibi) döner.
-    /// Güvenlik: Verilen pointer'ın ve boyutun çağıran görev tarafından haritalanmış geçerli bir paylaşımlı bellek bloğunu temsil ettiğini doğrula.
-     pub fn unmap_shared_memory(&mut self, ptr: *mut u8, size: usize) -> Result<(), KError> {
-         if ptr.is_null() || size == 0 { return Ok(()); }
-         let vaddr_start = ptr as u64;
-         // Boyutu sayfa boyutuna yuvarla
-        let pages_to_unmap = (size + PAGE_SIZE - 1) / PAGE_SIZE;
-
-         // TODO: Verilen pointer'ın ve boyutun gerçekten daha önce bu görev için haritalanmış bir SHM bloğu olduğunu doğrula.
-         // Bu, görev adres alanı yöneticisinin takip etmesi gereken bir bilgidir.
-         // Basitlik için sadece unmap işlemi yapalım.
-
-        let current_task_root_pt_paddr = self.kernel_page_table_paddr; // TODO: Gerçek görevinki olmalı!
-        let mut current_vaddr = vaddr_start;
-
-        for _ in 0..pages_to_unmap {
-             Self::unmap_page_in_table(current_task_root_pt_paddr, current_vaddr)?;
-            current_vaddr += PAGE_SIZE as u64;
+    unsafe fn translate(&self, root: u64, vaddr: u64, write_access: bool) -> Option<u64> {
+        let index = vpn_index(vaddr, 2);
+        if vaddr >= USER_VA_LIMIT || is_kernel_entry(index, (*(root as *const PageTable)).entries[index]) {
+            return None;
+        }
+        let pte = leaf_entry(root, vaddr)?;
+        if pte.0 & PteFlags::U.bits() == 0 || (write_access && pte.0 & PteFlags::W.bits() == 0) {
+            return None;
         }
+        Some((pte.ppn() << PAGE_SHIFT) | (vaddr & (PAGE_SIZE as u64 - 1)))
+    }
 
-         // NOT: unmap işlemi fiziksel sayfaları serbest BIRAKMAZ, sadece sanal eşleşmeyi kaldırır.
-         // Paylaşımlı bellek handle'ı serbest bırakıldığında (resource_release ile) fiziksel sayfalar serbest bırakılmalıdır.
+    fn user_space_end(&self) -> usize {
+        USER_VA_LIMIT as usize
+    }
 
-        Ok(())
-     }
+    unsafe fn destroy_page_tables(&self, root: u64) {
+        let root_table = &mut *(root as *mut PageTable);
+        for index in 0..USER_ROOT_ENTRIES {
+            let entry = root_table.entries[index];
+            if !entry.is_table() || is_kernel_entry(index, entry) {
+                continue;
+            }
+            let l1_table = (entry.ppn() << PAGE_SHIFT) as *const PageTable;
+            for l1_entry in (*l1_table).entries.iter() {
+                if l1_entry.is_table() {
+                    free_physical_frame(l1_entry.ppn() << PAGE_SHIFT);
+                }
+            }
+            free_physical_frame(l1_table as u64);
+            root_table.entries[index] = PageTableEntry::empty();
+        }
+    }
+}
 
+static RV64_MMU: Rv64Mmu = Rv64Mmu;
 
-    // TODO: Diğer MMU/Bellek Yönetimi ile ilgili fonksiyonlar:
-    // - Adres alanları arasında geçiş (switch_to_address_space) - SATP kaydı güncelleme
-    // - Sanal adres aralığı ayırma/takip etme (VM Area management)
-    // - Copy-on-Write implementasyonu
-    // - Page fault işleme
-    // - Büyük sayfa (superpage) desteği
+/// Sayfa tablosu işlemlerini kmemory'ye kaydeder. Çekirdek sayfa tablosu satp'ye yüklendikten
+/// sonra, ilk kullanıcı görevi oluşturulmadan önce çağrılır.
+pub fn init() {
+    let satp: u64;
+    unsafe { asm!("csrr {}, satp", out(reg) satp, options(nomem, nostack)) };
+    KERNEL_ROOT.store((satp & SATP_PPN_MASK) << PAGE_SHIFT, Ordering::Relaxed);
+    register_arch_mmu(&RV64_MMU);
 }
 
 // TODO: Physical Frame Allocator implementasyonu (başka bir dosyada/modülde olmalı)
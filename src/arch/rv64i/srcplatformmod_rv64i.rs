@@ -10,6 +10,18 @@ extern crate alloc; // Karnal64 içinde veya platformda Box/Vec kullanılıyorsa
 #[path = "../../karnal64.rs"] // Geçici olarak dosya yolunu belirtiyorum, gerçekte modül import edilir.
 mod karnal64;
 
+// ktask mimari katmanı (bağlam kurma/değiştirme, adres alanı etkinleştirme)
+#[path = "srctask_rv64i.rs"]
+mod task;
+
+// kmemory sayfa tablosu işlemleri (görev adres alanlarının Sv39 tabloları)
+#[path = "srcmmu_rv64i.rs"]
+mod mmu;
+
+// SMP: ikincil hart'ları başlatma (SBI HSM), IPI ve low_level_cpu_id
+#[path = "srcsmp_rv64i.rs"]
+mod smp;
+
 use karnal64::{KError, KHandle}; // Karnal64 API'sından temel tipleri kullanıyoruz
 use riscv::register::{
     mcause, // Trap nedenini (cause) okumak için
@@ -56,6 +68,12 @@ pub extern "C" fn platform_init() {
     // Genel Karnal64 çekirdek başlatma fonksiyonunu çağır.
     // Bu fonksiyon, resource manager, task manager gibi Karnal64 iç modüllerini başlatır.
     karnal64::init();
+    // Zamanlayıcı bağlam değiştirebilsin diye mimari katmanı kaydet.
+    task::init();
+    // Görev adres alanlarının sayfa tablolarını kurabilmesi için kmemory'ye kaydet.
+    mmu::init();
+    // Boot hart'ını kaydet ve diğer hart'ları başlat.
+    smp::init();
 
     // TODO: Daha sonraki başlatma adımları
     // - Cihaz sürücülerini kaydetme (platforma özgü cihazlar için ResourceProvider implementasyonları)
@@ -220,7 +238,7 @@ pub mod mmu_riscv {
         satp::write(satp_value);
         riscv::asm::sfence_vma_all(); // Tüm adres alanları için TLB temizleme
     }
-    // TODO: map_page, unmap_page fonksiyonları (kmemory tarafından kullanılır)
+    // Sayfa eşleme/kaldırma işlemleri srcmmu_rv64i.rs'de, kmemory::ArchMmu olarak.
 }
 
 
@@ -0,0 +1,173 @@
+// Karnal64 SMP için RISC-V (RV64, S-mode) mimari katmanı.
+// İkincil hart'lar SBI HSM eklentisiyle (hart_start) başlatılır; IPI'ler SBI IPI eklentisiyle
+// gönderilir ve hedef hart'ta süpervizör yazılım kesmesi (SSIP) olarak görülür
+// (srcinterrupt_rv64i.rs → ktask::handle_ipi).
+// Hart'lar DTB'deki /cpus düğümlerinden, topolojileri /cpus/cpu-map'ten kaydedilir
+// (srcdtb_rv64i.rs → ktask::add_cpu, ktask::set_cpu_topology). DTB taranmadıysa
+// 0..MAX_HART_PROBE aralığı HSM hart_get_status ile yoklanır (QEMU virt `-smp N`).
+// Her hart kendi hart ID'sini tp yazmacında tutar (bkz. srcplatformgeneric.rs::hart_id);
+// low_level_cpu_id bunu ktask'ın CPU kaydıyla mantıksal indekse çevirir.
+
+use core::arch::{asm, global_asm};
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::karnal64::ktask;
+
+// SBI eklenti (EID) ve fonksiyon (FID) numaraları.
+const SBI_EXT_HSM: usize = 0x48534D;
+const SBI_HSM_HART_START: usize = 0;
+const SBI_HSM_HART_GET_STATUS: usize = 2;
+const SBI_EXT_IPI: usize = 0x735049;
+const SBI_IPI_SEND_IPI: usize = 0;
+const SBI_EXT_TIME: usize = 0x54494D45;
+const SBI_TIME_SET_TIMER: usize = 0;
+const SBI_SUCCESS: isize = 0;
+const SBI_ERR_ALREADY_AVAILABLE: isize = -6;
+
+/// sie.SSIE (süpervizör yazılım kesmesi) ve sie.STIE (süpervizör zamanlayıcı kesmesi).
+const SIE_SSIE: u64 = 1 << 1;
+const SIE_STIE: u64 = 1 << 5;
+
+/// DTB yokken yoklanacak en yüksek hart ID'si (hariç).
+const MAX_HART_PROBE: usize = 64;
+
+/// İkincil hart'ların girişte yükleyeceği satp değeri (boot hart'ınınki). 0 ise MMU kapalı kalır.
+/// Giriş kodu MMU kapalıyken PC-göreli okuduğundan çekirdek görüntüsünün kimlik eşlemeli
+/// olduğu varsayılır (boot hart'ıyla aynı).
+#[no_mangle]
+static RV64I_BOOT_SATP: AtomicU64 = AtomicU64::new(0);
+
+global_asm!(
+    r#"
+    .section .text
+    .global rv64i_secondary_entry
+    // SBI hart_start girişi: a0 = hart ID, a1 = opaque (ktask::prepare_cpu'nun verdiği
+    // çekirdek yığın tepesi). MMU ve kesmeler kapalıdır.
+rv64i_secondary_entry:
+    mv tp, a0
+    la t0, RV64I_BOOT_SATP
+    ld t0, 0(t0)
+    beqz t0, 1f
+    csrw satp, t0
+    sfence.vma zero, zero
+1:
+    mv sp, a1
+    call rv64i_secondary_main
+2:
+    wfi
+    j 2b
+"#
+);
+
+extern "C" {
+    fn rv64i_secondary_entry();
+    fn trap_entry();
+}
+
+/// SBI çağrısı yapar ve hata kodunu (a0) döner; bu dosyadaki çağrıların değeri (a1) kullanılmaz.
+unsafe fn sbi_call(ext: usize, fid: usize, arg0: usize, arg1: usize, arg2: usize) -> isize {
+    let error: isize;
+    asm!(
+        "ecall",
+        inlateout("a0") arg0 => error,
+        inlateout("a1") arg1 => _,
+        in("a2") arg2,
+        in("a6") fid,
+        in("a7") ext,
+        options(nostack)
+    );
+    error
+}
+
+fn hart_id() -> u64 {
+    let hart: u64;
+    unsafe { asm!("mv {0}, tp", out(reg) hart, options(nomem, nostack)) };
+    hart
+}
+
+/// Çağıran hart'ın mantıksal CPU indeksi (hardware_specific.h). Kayıtsız hart'lar 0 döner.
+#[no_mangle]
+pub extern "C" fn low_level_cpu_id() -> u32 {
+    ktask::cpu_index(hart_id()).unwrap_or(0) as u32
+}
+
+/// `hart` ID'li hart'a SBI üzerinden yazılım kesmesi gönderir (srctask_rv64i.rs::send_ipi).
+pub fn send_ipi(hart: u64) {
+    // hart_mask = 1, hart_mask_base = hart
+    unsafe { sbi_call(SBI_EXT_IPI, SBI_IPI_SEND_IPI, 1, hart as usize, 0) };
+}
+
+/// Çağıran hart'ın zamanlayıcısını `time` sayacı `stime`'a ulaşınca süpervizör zamanlayıcı
+/// kesmesi üretecek şekilde kurar; bekleyen kesmeyi (STIP) de temizler (srctask_rv64i.rs::program_timer).
+pub fn set_timer(stime: u64) {
+    unsafe { sbi_call(SBI_EXT_TIME, SBI_TIME_SET_TIMER, stime as usize, 0, 0) };
+}
+
+/// DTB taraması CPU kaydetmediyse çevrimiçi/durmuş hart'ları HSM ile bulur.
+fn probe_harts() {
+    for hart in 0..MAX_HART_PROBE {
+        let error = unsafe { sbi_call(SBI_EXT_HSM, SBI_HSM_HART_GET_STATUS, hart, 0, 0) };
+        if error != SBI_SUCCESS {
+            continue;
+        }
+        if ktask::add_cpu(hart as u64).is_err() {
+            println!("SMP: MAX_CPUS aşıldı, hart {} ve sonrası kullanılmayacak", hart);
+            break;
+        }
+    }
+}
+
+fn start_cpu(cpu: usize) {
+    let hart = match ktask::cpu_hw_id(cpu) {
+        Some(hart) => hart,
+        None => return,
+    };
+    let stack = match ktask::prepare_cpu(cpu) {
+        Ok(stack) => stack,
+        Err(e) => {
+            println!("SMP: CPU {} (hart {}) hazırlanamadı: {:?}", cpu, hart, e);
+            return;
+        }
+    };
+    let error = unsafe {
+        sbi_call(
+            SBI_EXT_HSM,
+            SBI_HSM_HART_START,
+            hart as usize,
+            rv64i_secondary_entry as usize,
+            stack as usize,
+        )
+    };
+    if error != SBI_SUCCESS && error != SBI_ERR_ALREADY_AVAILABLE {
+        println!("SMP: hart {} başlatılamadı (SBI hata {})", hart, error);
+    }
+}
+
+/// İkincil hart'ın Rust girişi; rv64i_secondary_entry, `prepare_cpu` yığınına geçtikten sonra çağırır.
+#[no_mangle]
+extern "C" fn rv64i_secondary_main(_hart: u64) -> ! {
+    unsafe {
+        asm!("csrw stvec, {0}", in(reg) trap_entry as usize, options(nostack));
+        asm!("csrs sie, {0}", in(reg) SIE_SSIE | SIE_STIE, options(nostack));
+    }
+    // Zamanlayıcı, idle döngüsüne girerken ktask tarafından tek seferlik kurulur (program_timer).
+    ktask::cpu_online()
+}
+
+/// Boot hart'ını kaydeder, IPI'leri açar ve kayıtlı tüm ikincil hart'ları başlatır.
+/// Platform başlatma kodu task::init()'ten sonra çağırır.
+pub fn init() {
+    ktask::register_boot_cpu(hart_id());
+    if ktask::possible_cpus() == 1 {
+        probe_harts();
+    }
+    unsafe {
+        let satp: u64;
+        asm!("csrr {0}, satp", out(reg) satp, options(nomem, nostack));
+        RV64I_BOOT_SATP.store(satp, Ordering::Release);
+        asm!("csrs sie, {0}", in(reg) SIE_SSIE, options(nostack));
+    }
+    for cpu in 1..ktask::possible_cpus() {
+        start_cpu(cpu);
+    }
+}
@@ -1,239 +1,169 @@
-#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz, çekirdek alanında çalışırız
-
-// Geliştirme sırasında kullanılmayan kod veya argümanlar için izinler
-#![allow(dead_code)]
-#![allow(unused_variables)]
-
-// Karnal64 temel tiplerini ve hatalarını içe aktar
-// Buradaki yol projenizin modül yapısına göre değişebilir.
-// Karnal64.rs dosyasının projenin kökünde olduğunu varsayalım.
-use crate::karnal64::{KError, KHandle, KTaskId, KThreadId, ResourceProvider};
-use crate::karnal64::kresource; // Kaynak yönetimi ile etkileşim için
-use crate::karnal64::kmemory; // Bellek yönetimi ile etkileşim için (görev belleği, yığın)
-
-// RISC-V'ye özgü bağlam (context) yapısı
-// Bir iş parçacığının durumunu (kayıt defterleri, yığın işaretçisi vb.) kaydetmek için
-#[repr(C)] // C uyumlu bellek düzeni sağlamak için
+// RISC-V (RV64, S-mode) için ktask mimari katmanı (ktask::ArchTask):
+// - bağlam kurma: yeni iş parçacığı `rv64i_thread_trampoline` üzerinden başlar
+// - bağlam değiştirme: çağrı kuralına göre korunan yazmaçlar (ra, sp, s0-s11) kaydedilir/yüklenir
+// - adres alanı: satp (Sv39) yazılır ve TLB sfence.vma ile temizlenir
+// - SMP: IPI SBI üzerinden gönderilir (srcsmp_rv64i.rs), idle bekleme `wfi` ile yapılır
+// - zamanlayıcı: tek seferlik, SBI TIME eklentisiyle (set_timer → hart'ın mtimecmp'i) kurulur
+// - TLS: kullanıcı TLS işaretçisi tp'dir. Çekirdek tp'de hart ID'sini tuttuğundan kullanıcı tp'si
+//   yalnızca kullanıcıya ilk geçişte yüklenir; sonrasında tuzak girişi onu çerçeveye kaydedip
+//   hart ID'sini geri yüklemeli, tuzak çıkışı da tersini yapmalıdır
+// Kullanıcı modundayken sscratch, iş parçacığının çekirdek yığınının tepesini tutar; tuzak girişi
+// `csrrw sp, sscratch, sp` ile çekirdek yığınına geçer. Kullanıcıya ilk dönüşte sscratch'i trampolin,
+// sonrakilerde tuzak çıkışı yazar.
+
+use core::arch::{asm, global_asm};
+use core::mem::size_of;
+
+use crate::karnal64::ktask::{self, ArchTask, ThreadStart};
+
+/// satp MODE alanı: Sv39.
+const SATP_MODE_SV39: u64 = 8 << 60;
+
+const PAGE_SIZE: u64 = 4096;
+/// Bundan büyük aralıklar sayfa sayfa değil, tek `sfence.vma` ile temizlenir.
+const TLB_FLUSH_ALL_THRESHOLD: u64 = 64 * PAGE_SIZE;
+
+/// `time` sayacının frekansı (DTB /cpus `timebase-frequency`; srctime_rv64i.rs ile aynı varsayım).
+const TIMEBASE_HZ: u64 = 10_000_000;
+
+// RISC-V'ye özgü bağlam (context) yapısı.
+// Yalnızca çağrılan tarafından korunması gereken yazmaçlar tutulur; geri kalanı
+// rv64i_switch_context çağrısı sırasında zaten çağıranın yığınındadır.
+#[repr(C)] // Assembly'deki ofsetlerle aynı düzen
 #[derive(Debug, Copy, Clone)]
 pub struct SavedTaskContext {
-    // RISC-V çağrı kuralına göre kaydedilmesi gereken s-kayıtları (caller-saved)
-    // ve diğer gerekli durumlar. Gerçek implementasyon mimariye göre detaylandırılmalı.
-    ra: usize, // Dönüş adresi
-    sp: usize, // Yığın işaretçisi
-    s0: usize, // Kaydedilen kayıtlar (s0-s11)
-    s1: usize,
-    s2: usize,
-    s3: usize,
-    s4: usize,
-    s5: usize,
-    s6: usize,
-    s7: usize,
-    s8: usize,
-    s9: usize,
-    s10: usize,
-    s11: usize,
-    // Diğer çekirdeğin yönetmesi gereken bilgiler (örneğin sayfa tablosu adresi - SATP)
-    satp: usize, // Sayfa tablosu kontrol yazmacı
-    // ... diğer gerekli çekirdek durumları
-}
-
-impl SavedTaskContext {
-    /// Yeni bir görev için başlangıç bağlamını ayarlar.
-    /// `entry_point`: Görevin başlayacağı fonksiyon adresi.
-    /// `stack_top`: Görevin yığınının en üst adresi (genellikle yığın aşağı doğru büyür).
-    /// `satp_value`: Bu görevin kullanacağı sayfa tablosu değeri.
-    pub fn new(entry_point: usize, stack_top: usize, satp_value: usize) -> Self {
-        let mut ctx = SavedTaskContext {
-            ra: entry_point, // Görev başladığında ilk çalışacak yer entry_point
-            sp: stack_top,   // Yığın işaretçisi başlangıçta yığının tepesini gösterir
-            s0: 0, s1: 0, s2: 0, s3: 0, s4: 0, s5: 0,
-            s6: 0, s7: 0, s8: 0, s9: 0, s10: 0, s11: 0,
-            satp: satp_value,
-            // ... diğer alanlar varsayılan/sıfır değerleriyle başlatılır
-        };
-
-        // Bazı RISC-V ortamlarında başlangıç RA değeri farklı ayarlanabilir,
-        // örneğin bir "task start" wrapper fonksiyonunu işaret edebilir.
-        // Bu, çekirdek implementasyonunun tasarımına bağlıdır.
-         ctx.ra = ktask_start_wrapper as usize; // Örnek: Bir wrapper fonksiyonu
-
-        ctx
-    }
-
-    // TODO: RISC-V bağlam değiştirme (context switch) için harici assembly fonksiyonu
-     fn switch_context(old_ctx: *mut SavedTaskContext, new_ctx: *const SavedTaskContext);
+    ra: u64, // Dönüş adresi (ilk geçişte trampolin)
+    sp: u64, // Çekirdek yığın işaretçisi
+    s: [u64; 12], // s0-s11. İlk geçişte s0-s5 trampoline başlangıç bilgisini taşır.
 }
 
+const _: () = assert!(size_of::<SavedTaskContext>() <= ktask::ARCH_CONTEXT_SIZE);
+
+global_asm!(
+    r#"
+    .section .text
+    .global rv64i_switch_context
+    // a0: önceki bağlam (*mut SavedTaskContext), a1: sonraki bağlam
+rv64i_switch_context:
+    sd ra, 0(a0)
+    sd sp, 8(a0)
+    sd s0, 16(a0)
+    sd s1, 24(a0)
+    sd s2, 32(a0)
+    sd s3, 40(a0)
+    sd s4, 48(a0)
+    sd s5, 56(a0)
+    sd s6, 64(a0)
+    sd s7, 72(a0)
+    sd s8, 80(a0)
+    sd s9, 88(a0)
+    sd s10, 96(a0)
+    sd s11, 104(a0)
+
+    ld ra, 0(a1)
+    ld sp, 8(a1)
+    ld s0, 16(a1)
+    ld s1, 24(a1)
+    ld s2, 32(a1)
+    ld s3, 40(a1)
+    ld s4, 48(a1)
+    ld s5, 56(a1)
+    ld s6, 64(a1)
+    ld s7, 72(a1)
+    ld s8, 80(a1)
+    ld s9, 88(a1)
+    ld s10, 96(a1)
+    ld s11, 104(a1)
+    ret
+
+    .global rv64i_thread_trampoline
+    // s0: giriş, s1: argüman, s2: kullanıcı yığını, s3: çekirdek yığın tepesi, s4: kullanıcı modu mu,
+    // s5: kullanıcı TLS işaretçisi
+rv64i_thread_trampoline:
+    mv a0, s1
+    bnez s4, 1f
+    // Çekirdek iş parçacığı: kesmeleri aç ve girişe atla (geri dönmez).
+    csrsi sstatus, 2
+    jr s0
+1:
+    // Kullanıcı iş parçacığı: sret ile U-mode'a, kesmeler açık (SPIE) olarak geç.
+    csrw sscratch, s3
+    csrw sepc, s0
+    li t0, 0x100        // SPP = 0 (U-mode)
+    csrc sstatus, t0
+    li t0, 0x20         // SPIE = 1
+    csrs sstatus, t0
+    mv sp, s2
+    mv tp, s5
+    sret
+"#
+);
 
-// Görev Durumu Enum'u
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum TaskState {
-    Running,
-    Runnable,
-    Sleeping,
-    Blocked, // Örneğin bir kilidi beklerken
-    Exited,
-}
-
-// Görev Kontrol Bloğu (Task Control Block - TCB)
-#[derive(Debug)]
-pub struct TaskControlBlock {
-    pub task_id: KTaskId,
-    pub state: TaskState,
-    pub context: SavedTaskContext,
-    pub stack_base: *mut u8, // Yığın bölgesinin başlangıcı (bellek yönetimi için)
-    pub stack_size: usize,   // Yığın boyutu
-    pub page_table_root: usize, // Bu görevin kullandığı sayfa tablosunun fiziksel adresi
-    pub exit_code: Option<i32>,
-    // TODO: Diğer görevle ilgili bilgiler (parent/children, kaynak handle'ları listesi vb.)
-}
-
-// TODO: Global Görev Yöneticisi Veri Yapıları
-// Bu, tüm görevlerin listesini, zamanlama kuyruklarını vb. tutacaktır.
-// `no_std` ortamında uygun senkronizasyon (Spinlock) ve bellek yönetimi (fixed-size array, linked list, vb.) gerekir.
-static mut TASK_MANAGER: Option<TaskManager> = None; // Örnek: Global yönetici
-
-struct TaskManager {
-    // TODO: Görev listesi (örneğin bir Vec<TaskControlBlock> veya sabit boyutlu dizi)
-    // TODO: Çalıştırılabilir (Runnable) görev kuyruğu
-    // TODO: Uyuyan (Sleeping) görev listesi
-    // TODO: Kilitler/Senkronizasyon için bekleyen görev listeleri
-    next_task_id: u64,
-    current_task_id: KTaskId, // Şu anda çalışan görevin ID'si
-    // ... diğer yönetim verileri
+extern "C" {
+    fn rv64i_switch_context(prev: *mut SavedTaskContext, next: *const SavedTaskContext);
+    fn rv64i_thread_trampoline();
 }
 
-// `ktask` modülünün Karnal64 API'sı tarafından çağrılan fonksiyonları
-// Bunlar, `karnal64.rs` dosyasındaki `ktask::*` fonksiyonlarının karşılığıdır.
-// Bu dosya (`srctask_riscv.rs`), `ktask` modülünün RISC-V'ye özel implementasyonu olabilir.
-
-pub fn init_manager() {
-    // TODO: Görev yönetimi veri yapılarını başlat
-    // TODO: İlk görevi (genellikle "idle" veya "init" görevi) oluştur ve başlat
-    unsafe {
-        TASK_MANAGER = Some(TaskManager {
-            next_task_id: 1, // 0 genellikle geçersiz ID'dir
-            current_task_id: KTaskId(0), // Başlangıçta geçerli görev yok
-            // TODO: Veri yapıları başlatma
-        });
+/// ktask::ArchTask'ın RISC-V implementasyonu.
+pub struct Rv64iTask;
+
+pub static ARCH_TASK: Rv64iTask = Rv64iTask;
+
+impl ArchTask for Rv64iTask {
+    unsafe fn init_context(&self, ctx: *mut u8, start: &ThreadStart) {
+        let ctx = &mut *(ctx as *mut SavedTaskContext);
+        ctx.ra = rv64i_thread_trampoline as usize as u64;
+        ctx.sp = start.kernel_stack_top;
+        ctx.s[0] = start.entry;
+        ctx.s[1] = start.arg;
+        ctx.s[2] = start.user_stack_top;
+        ctx.s[3] = start.kernel_stack_top;
+        ctx.s[4] = start.user as u64;
+        ctx.s[5] = start.tls;
     }
-    println!("Karnal64: Görev Yöneticisi Başlatıldı (RISC-V)"); // Kernel içi print! kullanılıyor
-}
-
-// Karnal64 API fn task_spawn karşılığı
-// Çalıştırılabilir kodun handle'ını alır (örneğin bir dosya sistemi handle'ı)
-pub fn task_spawn(
-    code_handle_value: u64,
-    args_ptr: *const u8,
-    args_len: usize,
-) -> Result<KTaskId, KError> {
-    // TODO: code_handle_value'yu KHandle'a dönüştür ve kresource'tan provider'ı al.
-    // TODO: Provider'dan çalıştırılabilir kodu oku (belleğe yükle).
-    // TODO: kmemory'den yeni bir adres alanı/sayfa tablosu oluştur.
-    // TODO: kmemory'den görev için yığın tahsis et.
-    // TODO: Yeni bir TaskControlBlock oluştur.
-    // TODO: TCB'nin bağlamını (SavedTaskContext) ayarla (entry point, yığın işaretçisi, sayfa tablosu). Burası RISC-V'ye özeldir.
-    // TODO: Args pointer/len kullanıcı alanından geliyorsa doğrulama YAPILMALI. Argümanları yeni görev belleğine kopyala.
-    // TODO: Görev ID'sini ata.
-    // TODO: Görevi "Runnable" durumuna getir ve zamanlama kuyruğuna ekle.
-    // TODO: TASK_MANAGER'a yeni TCB'yi kaydet.
-
-    println!("TASK_SPAWN: code_handle={} args_ptr={:p} args_len={}",
-             code_handle_value, args_ptr, args_len);
-
-    // Yer Tutucu: Başarılı bir görev oluşturma simülasyonu
-    let new_task_id = KTaskId(unsafe {
-        let manager = TASK_MANAGER.as_mut().ok_or(KError::InternalError)?;
-        let id = manager.next_task_id;
-        manager.next_task_id += 1;
-        id
-    });
-
-    // TODO: Gerçek TCB oluşturma ve kaydetme
-
-    Ok(new_task_id) // Yeni görev ID'sini döndür
-}
-
-// Karnal64 API fn task_exit karşılığı
-// Mevcut görevi sonlandırır. Bu fonksiyon geri dönmez.
-pub fn task_exit(code: i32) {
-    // TODO: Mevcut görevi (current_task_id) bul.
-    // TODO: Görevin durumunu Exited olarak işaretle, çıkış kodunu kaydet.
-    // TODO: Görevin kaynaklarını (handle'lar, bellek) serbest bırak.
-    // TODO: Görevi zamanlayıcıdan kaldır.
-    // TODO: Bir sonraki çalıştırılabilir görevi seç ve bağlam değiştirerek ona geç.
-    // Bağlam değiştirme (context switch) RISC-V'ye özgü assembly/kod gerektirir.
-    println!("TASK_EXIT: code={}", code);
-
-    // Bu noktadan sonra, implementasyon bağlam değiştirmeli ve bu fonksiyondan
-    // bir daha dönmemelidir. Genellikle bir idle göreve veya zamanlayıcının
-    // kendisini çalıştırmaya geçilir.
-    loop { /* sonsuz döngü veya bağlam değiştirme çağrısı */ }
-}
 
-// Karnal64 API fn get_task_id karşılığı
-pub fn get_current_task_id() -> Result<KTaskId, KError> {
-    // TODO: Şu anda çalışan görevin ID'sini TASK_MANAGER'dan veya
-    // RISC-V'ye özel bir CPU yerel depolama alanından al.
-    unsafe {
-        let manager = TASK_MANAGER.as_ref().ok_or(KError::InternalError)?;
-        Ok(manager.current_task_id)
+    unsafe fn switch_context(&self, prev: *mut u8, next: *const u8) {
+        rv64i_switch_context(prev as *mut SavedTaskContext, next as *const SavedTaskContext);
     }
-}
 
-// Karnal64 API fn task_sleep karşılığı
-pub fn task_sleep(duration_millis: u64) -> Result<(), KError> {
-    // TODO: Mevcut görevi bul.
-    // TODO: Durumunu Sleeping olarak ayarla.
-    // TODO: Uyandırma zamanını hesapla (çekirdek saati + süre).
-    // TODO: Görevi uyuyanlar listesine ekle, çalıştırılabilir kuyruktan kaldır.
-    // TODO: Bir sonraki çalıştırılabilir görevi seç ve bağlam değiştirerek ona geç.
-    println!("TASK_SLEEP: duration={} ms", duration_millis);
-    // TODO: Gerçek uyku mantığı ve bağlam değiştirme
-    yield_now() // Basitlik için hemen yield yapalım
-}
+    unsafe fn activate_address_space(&self, root: u64) {
+        // Çekirdek eşlemeleri her sayfa tablosunun üst yarısında bulunur.
+        if root == ktask::KERNEL_ADDRESS_SPACE {
+            return;
+        }
+        let satp = SATP_MODE_SV39 | (root >> 12);
+        asm!("csrw satp, {0}", "sfence.vma zero, zero", in(reg) satp, options(nostack));
+    }
 
-// Karnal64 API fn task_yield karşılığı
-// Mevcut görevi zamanlayıcıya bırakır ve bir sonraki göreve geçiş yapılmasını sağlar.
-pub fn yield_now() -> Result<(), KError> {
-    // TODO: Mevcut görevin durumunu (eğer Running ise) Runnable olarak ayarla.
-    // TODO: Mevcut görevi çalıştırılabilir kuyruğunun sonuna ekle (veya zamanlayıcı politikasına göre bir yere).
-    // TODO: Çalıştırılabilir kuyruktan bir sonraki görevi seç.
-    // TODO: Seçilen göreve bağlam değiştirerek geçiş yap.
-    println!("TASK_YIELD");
-    // TODO: Gerçek zamanlama ve bağlam değiştirme
-    Ok(()) // Bağlam değiştikten sonra bu noktaya gelinmez, ancak Rust fonksiyon imzası için Ok dönülür
-}
+    unsafe fn flush_tlb(&self, _root: u64, start: u64, len: u64) {
+        // ASID kullanılmadığından yalnızca adres aralığı temizlenir; büyük aralıklarda hepsi.
+        if len == 0 || len > TLB_FLUSH_ALL_THRESHOLD {
+            asm!("sfence.vma zero, zero", options(nostack));
+            return;
+        }
+        let mut va = start & !(PAGE_SIZE - 1);
+        while va < start + len {
+            asm!("sfence.vma {0}, zero", in(reg) va, options(nostack));
+            va += PAGE_SIZE;
+        }
+    }
 
+    unsafe fn send_ipi(&self, hw_id: u64) {
+        super::smp::send_ipi(hw_id);
+    }
 
-// TODO: thread_create, thread_exit gibi iş parçacığı yönetimi fonksiyonları (eğer destekleniyorsa)
-// TODO: Kilitlenme/Engelleme (Blocking) mekanizmaları için fonksiyonlar (örneğin bir kaynak beklerken)
-// TODO: Çekirdek zamanlayıcı döngüsü veya kesme işleyicisi tarafından çağrılan
-// zamanlama (scheduling) ve bağlam değiştirme (context switching) mantığı.
+    unsafe fn program_timer(&self, deadline_ns: u64) {
+        // Yukarı yuvarlanır: kesme `deadline_ns`'ten önce gelmemeli.
+        let ticks = (deadline_ns as u128 * TIMEBASE_HZ as u128).div_ceil(1_000_000_000).min(u64::MAX as u128) as u64;
+        super::smp::set_timer(ticks);
+    }
 
-// RISC-V özel bağlam değiştirme fonksiyonu (Assembly'de implemente edilecek)
-// Genellikle extern "C" olarak tanımlanır ve assembly kodundan çağrılır.
-extern "C" {
-    /// Bağlam değiştirme işlemi.
-    /// Mevcut görevin bağlamını `old_ctx`'e kaydeder.
-    /// `new_ctx`'ten yeni görevin bağlamını yükler.
-    /// Kontrolü yeni göreve devreder. Bu fonksiyon çağıran görev için geri dönmez.
-    /// Daha sonra `new_ctx` görevi tekrar zamanlandığında bu fonksiyonun çağrı noktasından devam eder.
-    fn riscv_context_switch(old_ctx: *mut SavedTaskContext, new_ctx: *const SavedTaskContext);
+    unsafe fn wait_for_interrupt(&self) {
+        asm!("wfi", options(nomem, nostack));
+    }
 }
 
-// Örnek: Bir görev başlatma wrapper'ı (isteğe bağlı)
-// Yeni görev bu fonksiyonda başlayıp asıl görev kodunu çağırabilir.
-// Böylece görev sonlandığında (return ettiğinde) bu wrapper yakalayabilir.
-
-extern "C" fn ktask_start_wrapper(entry_point: usize, arg_ptr: usize) {
-    // TODO: Kullanıcı alanına geçerken gerekli hazırlıkları yap
-    // TODO: arg_ptr'yi uygun kullanıcı alanı pointer'ına dönüştür
-
-    // Görevin ana fonksiyonunu çağır
-    let task_main = unsafe { core::mem::transmute::<usize, fn(usize) -> i32>(entry_point) };
-    let exit_code = task_main(arg_ptr); // Görev çalışıyor
-
-    // Görev tamamlandı, task_exit'i çağır
-    task_exit(exit_code);
+/// RISC-V mimari katmanını ktask'a kaydeder. Platform başlatma kodu karnal64::init()'ten sonra çağırır.
+pub fn init() {
+    ktask::register_arch(&ARCH_TASK);
 }
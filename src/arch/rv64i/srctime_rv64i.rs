@@ -45,6 +45,16 @@ fn read_riscv_mtime() -> u64 {
     }
 }
 
+// MTIME sayacının frekansı (DTB /cpus `timebase-frequency`). QEMU virt ve çoğu SiFive kartı 10 MHz kullanır.
+const RISCV_TIMEBASE_HZ: u64 = 10_000_000;
+
+/// Boot'tan beri geçen monoton süre (hardware_specific.h): MTIME, nanosaniyeye çevrilir.
+/// ktask zamanlayıcısı ve CPU süresi muhasebesi bu kancayı kullanır.
+#[no_mangle]
+pub extern "C" fn low_level_monotonic_time_ns() -> u64 {
+    ((read_riscv_mtime() as u128 * 1_000_000_000) / RISCV_TIMEBASE_HZ as u128) as u64
+}
+
 // RISC-V zaman kaynağı için ResourceProvider implementasyonu yapacak yapı (struct)
 pub struct RiscvTimeProvider;
 
@@ -22,6 +22,11 @@ const TT_PRIVILEGED_OPCODE: u8 = 0x13;
 const TT_UNIMPLEMENTED_INSTRUCTION: u8 = 0x18;
 const TT_FP_DISABLED: u8 = 0x20;
 const TT_FP_EXCEPTION: u8 = 0x21;
+// Kesme seviyeleri 1-15 (interrupt_level_n, TT = 0x40 + n). Seviye 14 TICK/STICK karşılaştırma
+// kesmesidir ve zamanlayıcı olarak kullanılır.
+const TT_INTERRUPT_LEVEL_1: u8 = 0x41;
+const TT_INTERRUPT_LEVEL_15: u8 = 0x4F;
+const TT_TIMER_INTERRUPT: u8 = 0x4E;
 // ... daha birçok donanım tuzağı
 
 // Yazılım tuzakları (ST - Software Traps)
@@ -194,15 +199,19 @@ pub extern "C" fn sparc_trap_handler(trap_frame: *mut TrapFrame) {
             }
         }
 
-        // --- Zamanlayıcı Kesintisi ---
-        // Belirli bir TT değeri zamanlayıcı donanımı tarafından tetiklenecektir.
-        // Bu TT değeri donanıma/BSP'ye bağlıdır.
-         const TT_TIMER_INTERRUPT: u8 = 0xsomething; // Örnek
-         TT_TIMER_INTERRUPT => {
-        //     // TODO: Zamanlayıcı donanımını sıfırla/onayla.
-        //     // TODO: Zamanlayıcı yöneticisini veya zamanlayıcıyı tetikle (ktask).
-             super::ktask::handle_timer_interrupt(frame);
-         }
+        // --- Donanım Kesmeleri ---
+        TT_INTERRUPT_LEVEL_1..=TT_INTERRUPT_LEVEL_15 => {
+            super::karnal64::ksync::lockdep::irq_enter();
+            if tt == TT_TIMER_INTERRUPT {
+                // Kesmeyi onayla, süresi dolan bekleyenleri uyandır. Tek seferlik zamanlayıcıyı
+                // timer_tick bir sonraki olaya yeniden kurar (srctask_sparcv9.rs::program_timer).
+                super::task::handle_timer_interrupt();
+            }
+            // TODO: Diğer seviyeleri ilgili aygıt sürücüsüne yönlendir.
+            super::karnal64::ksync::lockdep::irq_exit();
+            // Gerekirse bağlam değişimi kesme bağlamından çıkarken yapılır.
+            super::karnal64::ktask::preempt_on_interrupt_exit();
+        }
 
         // --- Diğer Donanım/Yazılım Tuzakları ---
         _ => {
@@ -248,3 +257,97 @@ pub fn init_trap_handling() {
  fn read_sparc_fsr() -> u64 { unsafe { /* SPARC FSR okuma assembly */ } }
  #[inline]
  fn write_sparc_ttr(addr: u64) { unsafe { /* SPARC TTR yazma assembly */ } }
+
+// --- Karnal64 Kesme Maskesi Kancaları (hardware_specific.h) ---
+// ksync::Spinlock::lock_irqsave ve çekirdek genelindeki kesme aç/kapa işlemleri bunları kullanır.
+// Kaydedilen durum PSTATE.IE bitidir (bit 1).
+
+const PSTATE_IE: u64 = 1 << 1;
+
+#[inline(always)]
+fn read_pstate() -> u64 {
+    let pstate: u64;
+    unsafe { asm!("rdpr %pstate, {0}", out(reg) pstate, options(nomem, nostack)) };
+    pstate
+}
+
+#[inline(always)]
+fn write_pstate(pstate: u64) {
+    unsafe { asm!("wrpr {0}, 0, %pstate", in(reg) pstate, options(nomem, nostack)) };
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_enable() {
+    write_pstate(read_pstate() | PSTATE_IE);
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_disable() {
+    write_pstate(read_pstate() & !PSTATE_IE);
+}
+
+#[no_mangle]
+pub extern "C" fn low_level_interrupts_enabled() -> i32 {
+    (read_pstate() & PSTATE_IE != 0) as i32
+}
+
+/// Kesmeleri kapatır ve önceki IE bitini döndürür.
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_save() -> u64 {
+    let pstate = read_pstate();
+    write_pstate(pstate & !PSTATE_IE);
+    pstate & PSTATE_IE
+}
+
+/// `low_level_interrupt_save` ile kaydedilen IE bitini geri yükler.
+#[no_mangle]
+pub extern "C" fn low_level_interrupt_restore(flags: u64) {
+    if flags & PSTATE_IE != 0 {
+        write_pstate(read_pstate() | PSTATE_IE);
+    }
+}
+
+// --- Karnal64 CPU Kimliği Kancası (hardware_specific.h) ---
+
+/// sun4u UPA yapılandırma yazmacının ASI'si; MID (modül kimliği) alanı bit 21:17'dedir.
+const ASI_UPA_CONFIG: u8 = 0x4A;
+
+/// Çağıran CPU'nun mantıksal indeksi: UPA_CONFIG.MID, ktask kaydıyla eşlenir.
+/// Kayıtsız CPU'lar 0 döner.
+#[no_mangle]
+pub extern "C" fn low_level_cpu_id() -> u32 {
+    let config: u64;
+    unsafe { asm!("ldxa [%g0] {asi}, {0}", out(reg) config, asi = const ASI_UPA_CONFIG, options(nostack)) };
+    super::ktask::cpu_index((config >> 17) & 0x1F).unwrap_or(0) as u32
+}
+
+// --- Karnal64 Yığın İzi Kancası (hardware_specific.h) ---
+// lockdep raporlarında kullanılır. Yazmaç pencereleri önce `flushw` ile yığına dökülür.
+
+/// Yığın bias'ı (V9 ABI): %sp/%fp gerçek adresin 2047 altındadır.
+const STACK_BIAS: u64 = 2047;
+
+/// Her çerçevenin pencere kayıt alanında %i6 (önceki %fp) 14., %i7 (çağrı adresi) 15. sözcüktür.
+/// Dönüş adresi `call` + 8'dir. Zincir sıfıra veya yığında aşağı doğru bir çerçeveye ulaşınca durur.
+#[no_mangle]
+pub extern "C" fn low_level_stack_trace(frames: *mut u64, max_frames: usize) -> usize {
+    let (mut fp, mut pc): (u64, u64);
+    unsafe { asm!("flushw", "mov %fp, {0}", "mov %i7, {1}", out(reg) fp, out(reg) pc, options(nostack)) };
+    let mut count = 0;
+    while count < max_frames && pc != 0 {
+        unsafe { *frames.add(count) = pc + 8 };
+        count += 1;
+        if fp == 0 {
+            break;
+        }
+        let window = (fp + STACK_BIAS) as *const u64;
+        let prev = unsafe { *window.add(14) };
+        pc = unsafe { *window.add(15) };
+        // En dış çerçevenin %i6'sı sıfırdır; onun çağrı adresi bir sonraki turda yine yazılır.
+        if prev != 0 && prev <= fp {
+            break;
+        }
+        fp = prev;
+    }
+    count
+}
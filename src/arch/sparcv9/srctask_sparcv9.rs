@@ -1,318 +1,194 @@
-#![no_std] // Standart kütüphaneye ihtiyaç duymuyoruz
-
-// Karnal64 çekirdek API'sından temel tipleri ve traitleri kullanacağız.
-// Proje yapınıza bağlı olarak 'karnal64' crate'ini bu şekilde import etmeniz gerekebilir.
-// Eğer karnal64.rs aynı projenin bir parçasıysa ve modüller doğru ayarlandıysa
-// belki 'crate::karnal64' veya sadece 'super::' ile de erişilebilir.
-// Burada varsayımsal olarak 'karnal64' adında bir crate'e bağımlı olduğumuzu varsayıyorum.
-extern crate karnal64;
-use karnal64::{KError, KTaskId, KThreadId}; // Karnal64'ten kullanacağımız tipler
-
-// Geliştirme sırasında kullanılmayan kod veya argümanlar için izinler
-// Geliştikçe bu allow'ları kaldırmak iyi bir pratik olacaktır.
-#![allow(dead_code)]
-#![allow(unused_variables)]
-
-// --- SPARC Mimariye Özgü Görev/İş Parçacığı Yapıları ---
-
-/// SPARC CPU'nun bir iş parçacığı bağlamını (context) temsil eden yapı.
-/// Bağlam değiştirme sırasında kaydedilip geri yüklenmesi gereken tüm yazmaçları içerir.
-#[repr(C)] // C uyumluluğu genellikle bağlam değiştirme assembly kodları için önemlidir
-#[derive(Debug, Default, Copy, Clone)] // Geliştirme için Debug, Copy, Clone ekledik
+// SPARC V9 için ktask mimari katmanı (ktask::ArchTask):
+// - bağlam kurma: çekirdek yığınında iki pencere çerçevesi hazırlanır; ilk geçişteki
+//   `ret; restore` trampoline'e döner ve başlangıç bilgisini onun yerel yazmaçlarına (%l0-%l5) doldurur
+// - bağlam değiştirme: yazmaç pencereleri `flushw` ile yığına boşaltılır, yalnızca %sp kaydedilir;
+//   sonraki iş parçacığının pencereleri ilk `restore`da fill tuzağıyla yığınından geri yüklenir
+// - adres alanı: MMU birincil bağlam (primary context) yazmacı güncellenir
+// - TLS: kullanıcı TLS işaretçisi %g7'dir; genel yazmaç olarak tuzak çerçevesinde taşınır,
+//   yalnızca kullanıcıya ilk geçişte trampolin yükler
+// - zamanlayıcı: tek seferlik, %tick_cmpr ile kurulur (%tick eşleşince seviye 14 kesmesi)
+// Kullanıcı modundan gelen tuzaklar `KERNEL_STACK_TOP`'taki çekirdek yığınına geçer.
+
+use core::arch::{asm, global_asm};
+use core::mem::size_of;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use super::karnal64::ktask::{self, ArchTask, ThreadStart};
+
+/// SPARC V9 ABI yığın sapması: %sp ve %fp gerçek çerçeve adresinden bu kadar düşüktür.
+const STACK_BIAS: u64 = 2047;
+/// En küçük yığın çerçevesi (16 yazmaçlık pencere kayıt alanı + argüman alanı), 16 bayt hizalı.
+const MIN_FRAME: u64 = 176;
+/// Pencere kayıt alanında %i6 (%fp) ve %i7'nin ofsetleri.
+const FRAME_I6: u64 = 14 * 8;
+const FRAME_I7: u64 = 15 * 8;
+/// Kullanıcı moduna geçişte TSTATE: PSTATE.IE açık, PSTATE.PRIV kapalı (PSTATE alanı bit 8'den başlar).
+const TSTATE_USER: u64 = 0x2 << 8;
+/// ASI_DMMU ve birincil bağlam yazmacının adresi.
+const ASI_DMMU: u64 = 0x58;
+const PRIMARY_CONTEXT: u64 = 0x8;
+/// %tick sayacının frekansı (srctime_sparcv9.rs ile aynı varsayım).
+const TICK_FREQUENCY_HZ: u64 = 100_000_000;
+/// %tick'in NPT biti; sayaç değerine dahil değildir.
+const TICK_NPT: u64 = 1 << 63;
+/// %tick_cmpr'ın TICK_CMPR alanı (bit 62:0). Bit 63 (INT_DIS) sıfır bırakılır: kesme açık.
+const TICK_CMPR_MAX: u64 = !(1 << 63);
+/// SOFTINT.TICK_INT: %tick, %tick_cmpr'a eşit olunca kurulur ve seviye 14 kesmesi üretir.
+const SOFTINT_TICK_INT: u64 = 1 << 0;
+
+/// SPARC iş parçacığı bağlamı. Diğer tüm yazmaçlar yığındaki pencere çerçevelerindedir.
+#[repr(C)] // Assembly'deki ofsetlerle aynı düzen
+#[derive(Debug, Default, Copy, Clone)]
 pub struct SparcContext {
-    // TODO: SPARC mimarisine özgü tüm yazmaçları buraya ekleyin.
-    // Örnekler (gerçek SPARC yazmaçları farklı olabilir ve çok daha fazladır):
-    // - Genel amaçlı yazmaçlar (g0-g7, o0-o7, l0-l7, i0-i7)
-    // - Program Sayacı (PC)
-    // - Yığın İşaretçisi (Stack Pointer - SP)
-    // - Durum Yazmacı (Processor State Register - PSR)
-    // - Pencere Sayacı (Window Pointer)
-    // - Gecikme Yuvası (Delay Slot) yazmacı
-    // - FPU/SIMD yazmaçları (varsa)
-    // ... ve bağlam değiştirme için gerekli diğer tüm yazmaçlar.
-    g_registers: [u64; 8], // Örnek: Global yazmaçlar
-    o_registers: [u64; 8], // Örnek: Çıkış (Output) yazmaçları
-    l_registers: [u64; 8], // Örnek: Yerel (Local) yazmaçlar
-    i_registers: [u64; 8], // Örnek: Giriş (Input) yazmaçları
-    pc: u64, // Program Sayacı
-    sp: u64, // Yığın İşaretçisi (genellikle o6 veya i6 olarak kullanılır, mimariye bakılmalı)
-    psr: u64, // İşlemci Durum Yazmacı
-    // ... diğer SPARC yazmaçları ...
-}
-
-/// SPARC mimarisine özgü İş Parçacığı Kontrol Bloğu (Thread Control Block - ThCB)
-/// Karnal64'ün mimariden bağımsız ThCB'si (muhtemelen ktask modülünde olacak)
-/// bu yapıya bir işaretçi veya bu yapıyı içerebilir.
-#[derive(Debug)] // Geliştirme için Debug ekledik
-pub struct SparcThreadControlBlock {
-    /// İş parçacığının kaydedilmiş CPU bağlamı.
-    /// Bu, bağlam değiştirme sırasında kullanılacak.
-    pub cpu_context: SparcContext,
-
-    /// İş parçacığının yığınının başlangıç adresi (en yüksek adres).
-    /// Bu, yığın taşmalarını kontrol etmek veya yığıtı serbest bırakmak için gerekebilir.
-    pub stack_top: *mut u8,
-
-    /// İş parçacığının yığınının boyutu (byte cinsinden).
-    pub stack_size: usize,
-
-    // TODO: SPARC'a özgü, ancak Karnal64'ün genel ThCB'sinde olmayan ek alanlar
-    // Örneğin, mimariye özel zamanlayıcı bilgileri, hata işleyici bağlantıları vb.
-}
-
-/// SPARC mimarisine özgü Görev Kontrol Bloğu (Task Control Block - TCB)
-/// Karnal64'ün mimariden bağımsız TCB'si (muhtemelen ktask modülünde olacak)
-/// bu yapıya bir işaretçi veya bu yapıyı içerebilir. Bir görev birden çok iş parçacığına sahip olabilir.
-#[derive(Debug)] // Geliştirme için Debug ekledik
-pub struct SparcTaskControlBlock {
-    /// Göreve ait sanal adres alanı bilgisi (Sayfa Tabloları vb.)
-    /// SPARC'ta MMU (Memory Management Unit) bağlamı burada saklanabilir.
-    // TODO: SPARC MMU bağlam yapısını tanımlayın ve buraya ekleyin.
-    // pub mmu_context: SparcMmuContext,
-
-    // TODO: Bu göreve ait iş parçacıklarını yöneten bir yapı veya liste.
-    // Örneğin: pub threads: Vec<KThreadId>, (Vec 'alloc' gerektirir, no_std uyumlu bir yapı gerek)
-
-    // TODO: SPARC'a özgü, ancak Karnal64'ün genel TCB'sinde olmayan ek alanlar
-    // Örneğin, göreve özel sinyal işleyicileri, segment bilgileri vb.
-}
-
-
-// --- SPARC Mimariye Özgü Fonksiyonlar (ktask modülü tarafından çağrılacak) ---
-
-/// SPARC'a özgü görev/iş parçacığı yönetimi alt sistemini başlatır.
-/// Karnal64'ün genel ktask::init_manager() fonksiyonu tarafından çağrılmalıdır.
-pub fn init_arch_task_management() {
-    // TODO: SPARC bağlam değiştirme için başlangıç ayarlarını yapın.
-    // Örneğin, ilk görev/iş parçacığı için bir idle bağlamı oluşturma,
-    // zamanlayıcı kesmelerini yapılandırma (eğer SPARC'ta bu mimariye özgüyse).
-    // SPARC'ın pencere yazmaçları (register windows) yönetimi burada başlayabilir.
-    karnal64::println!("srctask_sparc: SPARC Görev Yönetimi Başlatılıyor (Yer Tutucu)"); // Kernel içi print!
-}
-
-/// Yeni bir iş parçacığı için başlangıç SPARC CPU bağlamını ayarlar.
-/// Karnal64'ün ktask::thread_create fonksiyonu tarafından çağrılacak.
-///
-/// `entry_point`: İş parçacığının yürütülmeye başlayacağı fonksiyonun adresi.
-/// `stack_top`: İş parçacığı yığınının en üst adresi (genellikle SP'nin başlayacağı yerin biraz yukarısı).
-/// `arg`: İş parçacığı giriş fonksiyonuna iletilecek tek bir argüman değeri.
-///
-/// Başarı durumunda yapılandırılmış `SparcContext`'i döner.
-pub fn create_initial_thread_context(
-    entry_point: extern "C" fn(u64) -> !, // Çoğu OS'te iş parçacığı girişleri '!' ile bitmez, burası örnek
-    stack_top: *mut u8,
-    arg: u64,
-) -> Result<SparcContext, KError> {
-    let mut context = SparcContext::default(); // Varsayılan değerlerle başlat
-
-    // TODO: SPARC mimarisine özgü olarak yeni iş parçacığının başlangıç bağlamını yapılandırın.
-    // Bu, iş parçacığı ilk kez zamanlandığında doğru yerden (entry_point) başlamasını
-    // ve doğru yığınla (stack_top) çalışmasını sağlar.
-    // Argümanlar genellikle belirli yazmaçlara (örn. o0) yerleştirilir.
-    // Dönüş adresi (return address) genellikle bir "exit" veya "cleanup" fonksiyonuna ayarlanır,
-    // böylece iş parçacığı bittiğinde sistem kilitlenmez.
-
-    // Örnek yapılandırma (SPARC'a özgü detaylar için SPARC ABI'sine bakılmalı):
-    context.pc = entry_point as u64; // Program Sayacı giriş fonksiyonuna ayarlanır
-    // SPARC'ta yığın genellikte o6 yazmacıdır. Yığın aşağı doğru büyür.
-    // Bu yüzden stack_top genellikle yığın için ayrılan alanın EN YÜKSEK adresidir.
-    // SP ilk bağlamda genellikle bu adresin biraz altına ayarlanır (örneğin pencere çerçevesi boyutu kadar).
-    context.sp = stack_top as u64; // Basitlik için şimdilik doğrudan stack_top kullanıyoruz, ABI'ye bakılmalı
-
-    // Argümanı genellikle o0 yazmacına koyarız (SPARC ABI kuralı olabilir)
-    context.o_registers[0] = arg;
-
-    // TODO: İş parçacığı normal şekilde sonlandığında çağrılacak bir fonksiyonun adresi (return address) ayarlanmalı.
-    // Bu fonksiyon (örn. `thread_exit_cleanup`), çekirdeğe iş parçacığının bittiğini bildirmeli.
-    // SPARC'ta return address genellikle i7 yazmacında tutulur (pencereleme nedeniyle karmaşıktır).
-    context.i_registers[7] = thread_exit_cleanup as u64; // Varsayımsal cleanup fonksiyonu
-
-    // TODO: İşlemci Durum Yazmacı (PSR) ve diğer kontrol yazmaçlarını ayarla.
-    // Örneğin, kesmelerin durumu, kullanıcı/çekirdek modu bayrağı (başlangıçta kullanıcı modu?).
-
-    karnal64::println!("srctask_sparc: İş parçacığı bağlamı oluşturuldu: PC={:x}, SP={:x}", context.pc, context.sp); // Kernel içi print!
-
-    Ok(context)
-}
-
-/// Mevcut SPARC CPU bağlamını kaydeder.
-/// Bağlam değiştirme öncesi çağrılır.
-///
-/// `context`: Mevcut bağlamın kaydedileceği `SparcContext` referansı.
-///
-/// **Güvenlik Notu:** Bu fonksiyonun implementasyonu, SPARC assembly dili kullanarak
-/// mevcut CPU yazmaçlarını `context` yapısına güvenli bir şekilde kopyalamalıdır.
-/// Bu, Rust'ın normal kuralları dışında düşük seviye, güvensiz (unsafe) kod gerektirir.
-#[inline(always)] // Bağlam değiştirme fonksiyonları genellikle inlined olur
-pub unsafe fn save_current_context(context: &mut SparcContext) {
-    // TODO: SPARC assembly kullanarak mevcut yazmaçları 'context' pointer'ının gösterdiği yere kaydet.
-    // Bu kısım tamamen mimariye bağımlıdır ve Rust'ın 'global_asm!' makrosu veya ayrı bir assembly dosyası gerektirir.
-    karnal64::println!("srctask_sparc: Bağlam kaydediliyor (Yer Tutucu Assembly)"); // Kernel içi print!
+    /// Sapmalı (biased) çekirdek yığın işaretçisi.
+    pub sp: u64,
+    /// Bu iş parçacığının çekirdek yığınının tepesi.
+    pub kernel_stack_top: u64,
 }
 
-/// Kaydedilmiş bir SPARC CPU bağlamını geri yükler ve o bağlama geçer.
-/// Bağlam değiştirme sonrası çağrılır. **Bu fonksiyon çağırdığı yerden geri dönmez.**
-/// Yürütme akışı `context` içinde kaydedilmiş PC'den devam eder.
-///
-/// `context`: Geri yüklenecek `SparcContext` referansı.
-///
-/// **Güvenlik Notu:** Bu fonksiyonun implementasyonu, SPARC assembly dili kullanarak
-/// `context` yapısındaki değerleri CPU yazmaçlarına güvenli bir şekilde yüklemelidir.
-/// Bu da düşük seviye, güvensiz (unsafe) kod gerektirir.
-#[inline(always)] // Bağlam değiştirme fonksiyonları genellikle inlined olur
-pub unsafe fn restore_context(context: &SparcContext) -> ! {
-    // TODO: SPARC assembly kullanarak 'context' pointer'ının gösterdiği yerden yazmaçları geri yükle.
-    // Yükleme bittikten sonra, execution 'context.pc' adresinden devam etmelidir.
-    // Bu fonksiyon asla geri dönmez.
-    karnal64::println!("srctask_sparc: Bağlam geri yükleniyor ve geçiliyor (Yer Tutucu Assembly)"); // Kernel içi print!
- 
-         loop {} // Buraya asla ulaşılmamalı
-         panic!("Bu noktaya ulaşılmamalı!"); // Asla çağrılmamalı
-    
-
-    // Gerçek kod burada olmayacak. Assembly fonksiyonu bağlamı değiştirecek.
-    // Assembly kodu yazılana kadar çekirdeğin kilitlenmesini önlemek için geçici bir panik ekleyelim.
-    karnal64::println!("srctask_sparc: HATA! SPARC bağlam geri yükleme assembly kodu implemente edilmedi!");
-    karnal64::panic!("SPARC bağlam değiştirme implemente edilmedi!");
-
-    // Bu satır asla çalışmaz, sadece fonksiyonun dönüş tipi '!' olduğu için eklenmiştir.
-    #[allow(unreachable_code)] { loop {} }
-}
-
-/// İki SPARC iş parçacığı bağlamı arasında bağlam değiştirme (context switch) yapar.
-/// Karnal64'ün ktask modülündeki zamanlayıcı tarafından çağrılacak.
-///
-/// `old_context`: Mevcut çalışan iş parçacığının bağlamının kaydedileceği yer.
-/// `new_context`: Çalıştırılacak bir sonraki iş parçacığının bağlamı.
-///
-/// Bu fonksiyon, mevcut bağlamı `old_context`'e kaydeder,
-/// ardından `new_context`'i geri yükler ve yürütmeyi o bağlamdan devam ettirir.
-/// Çağıran yerden geri dönmez.
-///
-/// **Güvenlik Notu:** `save_current_context` ve `restore_context` fonksiyonlarını
-/// doğru sırada ve güvenli bir şekilde çağıran güvensiz (unsafe) bir fonksiyondur.
-#[inline(always)] // Bağlam değiştirme fonksiyonları genellikle inlined olur
-pub unsafe fn switch_context(
-    old_context: &mut SparcContext,
-    new_context: &SparcContext,
-) -> ! {
-    // TODO: SPARC assembly kullanarak mevcut yazmaçları old_context'e kaydet.
-    save_current_context(old_context);
-
-    // TODO: SPARC assembly kullanarak new_context'ten yazmaçları geri yükle ve yeni bağlama atla.
-    restore_context(new_context);
-
-    // restore_context asla geri dönmez, bu koda asla ulaşılmaz.
-    #[allow(unreachable_code)] { loop {} }
-}
-
-// TODO: İş parçacığı sonlandığında temizlik yapacak ve çekirdeğe dönecek bir fonksiyon.
-// create_initial_thread_context içinde dönüş adresi olarak ayarlanabilir.
-
-extern "C" fn thread_exit_cleanup(exit_code: u64) {
-    // TODO: Mevcut iş parçacığını sonlandır.
-    // Bu, ktask::task_exit veya benzeri bir Karnal64 API çağrısı yapacaktır.
-    karnal64::println!("srctask_sparc: İş parçacığı {:?} çıkıyor, kod: {}",
-                       karnal64::ktask::get_current_thread_id(), exit_code); // Varsayımsal API çağrıları
-     ktask::thread_exit(exit_code); // Varsayımsal Karnal64 API çağrısı
-    loop {} // task_exit geri dönmez, ancak bu temizlik fonksiyonu da geri dönmemeli
+const _: () = assert!(size_of::<SparcContext>() <= ktask::ARCH_CONTEXT_SIZE);
+
+/// Çalışan iş parçacığının çekirdek yığın tepesi. Kullanıcıdan gelen tuzak girişi buna geçer.
+static KERNEL_STACK_TOP: AtomicU64 = AtomicU64::new(0);
+
+global_asm!(
+    r#"
+    .section .text
+    .global sparcv9_switch_context
+    ! %o0: önceki bağlam (*mut SparcContext), %o1: sonraki bağlam
+sparcv9_switch_context:
+    save %sp, -{frame}, %sp
+    flushw
+    ! flushw mevcut pencereyi boşaltmaz; dönüş için gereken %i6/%i7 elle yazılır.
+    stx %i6, [%sp + {bias} + {i6}]
+    stx %i7, [%sp + {bias} + {i7}]
+    stx %sp, [%i0]
+
+    ldx [%i1], %sp
+    ldx [%sp + {bias} + {i6}], %i6
+    ldx [%sp + {bias} + {i7}], %i7
+    ret
+     restore
+
+    .global sparcv9_thread_trampoline
+    ! %l0: giriş, %l1: argüman, %l2: kullanıcı yığını, %l3: çekirdek yığın tepesi, %l4: kullanıcı modu mu,
+    ! %l5: kullanıcı TLS işaretçisi
+sparcv9_thread_trampoline:
+    brnz %l4, 1f
+     mov %l1, %o0
+    ! Çekirdek iş parçacığı: kesmeleri aç ve girişe atla (geri dönmez).
+    rdpr %pstate, %g1
+    or %g1, 2, %g1
+    wrpr %g1, %pstate
+    jmpl %l0, %g0
+     nop
+1:
+    ! Kullanıcı iş parçacığı: TL=1'de tuzak durumunu kurup retry ile kullanıcı moduna geç.
+    wrpr %g0, 1, %tl
+    wrpr %l0, %tpc
+    add %l0, 4, %g1
+    wrpr %g1, %tnpc
+    rdpr %cwp, %g2
+    setx {tstate}, %g3, %g1
+    or %g1, %g2, %g1
+    wrpr %g1, %tstate
+    sub %l2, {bias}, %o6
+    mov %l5, %g7
+    retry
+"#,
+    frame = const MIN_FRAME,
+    bias = const STACK_BIAS,
+    i6 = const FRAME_I6,
+    i7 = const FRAME_I7,
+    tstate = const TSTATE_USER,
+);
+
+extern "C" {
+    fn sparcv9_switch_context(prev: *mut SparcContext, next: *const SparcContext);
+    fn sparcv9_thread_trampoline();
 }
 
-
-// TODO: SPARC'a özgü kesme işleyicileri (zamanlayıcı kesmesi, sayfa hatası kesmesi vb.)
-// Zamanlayıcı kesmesi işleyicisi genellikle 'ktask' modülündeki zamanlayıcıyı (scheduler)
-// çağırmalı ve gerekirse bağlam değiştirmelidir.
-
-
-// --- Karnal64 ktask modülünün kullanabileceği public arayüz ---
-// Bu fonksiyonlar, karnal64/ktask/mod.rs (veya her neredeyse) modülü tarafından çağrılabilir.
-// Bu katman, mimariye özgü detayları Karnal64'ün genel mantığından ayırır.
-
-// TODO: Karnal64'ün ktask modülünde, aşağıdaki gibi fonksiyonlar tanımlanmalı ve
-// bu srctask_sparc modülündeki implementasyonları çağırmalıdır:
-
-// ktask/mod.rs içinde (varsayımsal)
-
-pub struct Task { id: KTaskId, /* ... diğer alanlar ... */ }
-pub struct Thread { id: KThreadId, sparc_thcb: srctask_sparc::SparcThreadControlBlock, /* ... diğer alanlar ... */ }
-
-impl Task {
-    pub fn new_sparc_task(...) -> Result<KTaskId, KError> {
-        // SPARC'a özgü TCB oluştur
-        // KTaskID ver
-        // Kaynak yöneticisine kaydet?
-        Ok(KTaskId(0)) // Yer tutucu
+/// ktask::ArchTask'ın SPARC V9 implementasyonu.
+pub struct Sparcv9Task;
+
+pub static ARCH_TASK: Sparcv9Task = Sparcv9Task;
+
+impl ArchTask for Sparcv9Task {
+    unsafe fn init_context(&self, ctx: *mut u8, start: &ThreadStart) {
+        let ctx = &mut *(ctx as *mut SparcContext);
+        // Dış çerçeve trampoline'in penceresidir; iç çerçeve sparcv9_switch_context'in
+        // `ret; restore`u için %i6/%i7'yi taşır.
+        let outer = start.kernel_stack_top - MIN_FRAME;
+        let inner = outer - MIN_FRAME;
+        core::ptr::write_bytes(inner as *mut u8, 0, (2 * MIN_FRAME) as usize);
+
+        let locals = outer as *mut u64;
+        *locals.add(0) = start.entry;
+        *locals.add(1) = start.arg;
+        *locals.add(2) = start.user_stack_top;
+        *locals.add(3) = start.kernel_stack_top;
+        *locals.add(4) = start.user as u64;
+        *locals.add(5) = start.tls;
+
+        *((inner + FRAME_I6) as *mut u64) = outer - STACK_BIAS;
+        // `ret` %i7 + 8'e döner.
+        *((inner + FRAME_I7) as *mut u64) = sparcv9_thread_trampoline as usize as u64 - 8;
+
+        ctx.sp = inner - STACK_BIAS;
+        ctx.kernel_stack_top = start.kernel_stack_top;
     }
-}
-
-impl Thread {
-    pub fn new_sparc_thread(entry: extern "C" fn(u64) -> !, stack_size: usize, arg: u64) -> Result<KThreadId, KError> {
-        // Yığın için bellek ayır (kmemory modülü kullanılır)
-        let stack = kmemory::allocate_stack(stack_size)?; // Varsayımsal API
 
-        // SPARC bağlamını ayarla
-        let initial_context = srctask_sparc::create_initial_thread_context(entry, stack.top(), arg)?;
-
-        // SPARC'a özgü ThCB oluştur
-        let sparc_thcb = srctask_sparc::SparcThreadControlBlock {
-            cpu_context: initial_context,
-            stack_top: stack.top(),
-            stack_size: stack_size,
-            // ...
-        };
-
-        // Genel ThCB oluştur ve sparc_thcb'yi içine koy
-        // KThreadId ver
-        // Zamanlayıcının kuyruklarına ekle
-        // ...
-        Ok(KThreadId(0)) // Yer tutucu
+    unsafe fn switch_context(&self, prev: *mut u8, next: *const u8) {
+        let next = next as *const SparcContext;
+        KERNEL_STACK_TOP.store((*next).kernel_stack_top, Ordering::Relaxed);
+        sparcv9_switch_context(prev as *mut SparcContext, next);
     }
 
-    // Zamanlayıcı tarafından bağlam değiştirmek için kullanılır
-    pub(crate) fn switch_to(&mut self, current_thread_sparc_thcb: &mut srctask_sparc::SparcThreadControlBlock) -> ! {
-         // SPARC'a özgü switch fonksiyonunu çağır
-         unsafe {
-             srctask_sparc::switch_context(
-                 &mut current_thread_sparc_thcb.cpu_context,
-                 &self.sparc_thcb.cpu_context // Kendi yeni bağlamına geçiyor
-             )
-         }
+    /// `root` bu portta MMU bağlam numarasıdır; çeviriler TSB'de bağlam numarasıyla etiketlenir.
+    unsafe fn activate_address_space(&self, root: u64) {
+        // Çekirdek nucleus bağlamında çalışır; çekirdek görevine geçişte birincil bağlam değişmez.
+        if root == ktask::KERNEL_ADDRESS_SPACE {
+            return;
+        }
+        asm!(
+            "stxa {0}, [{1}] {asi}",
+            "membar #Sync",
+            in(reg) root,
+            in(reg) PRIMARY_CONTEXT,
+            asi = const ASI_DMMU,
+            options(nostack),
+        );
     }
-}
 
-// Karnal64 API'sı (handle_syscall tarafından çağrılanlar)
-pub fn task_spawn(code_handle_value: u64, args_ptr: *const u8, args_len: usize) -> Result<KTaskId, KError> {
-    // Kod handle'ını çöz (ResourceProvider'dan çalıştırılabilir kod al)
-    // Yeni bir Task oluştur (new_sparc_task kullanılabilir)
-    // Bu görevin başlangıç iş parçacığını oluştur (new_sparc_thread kullanılabilir)
-    // ...
-    Err(KError::NotSupported) // Yer tutucu
+    unsafe fn program_timer(&self, deadline_ns: u64) {
+        // Yukarı yuvarlanır: kesme `deadline_ns`'ten önce gelmemeli.
+        let ticks = (deadline_ns as u128 * TICK_FREQUENCY_HZ as u128)
+            .div_ceil(1_000_000_000)
+            .min(TICK_CMPR_MAX as u128) as u64;
+        asm!("wr {0}, 0, %tick_cmpr", in(reg) ticks, options(nomem, nostack));
+        // Karşılaştırma eşitlikle yapılır: %tick değeri zaten geçtiyse kesme hiç gelmez,
+        // bu yüzden TICK_INT elle kurulur.
+        let now: u64;
+        asm!("rd %tick, {0}", out(reg) now, options(nomem, nostack));
+        if now & !TICK_NPT >= ticks {
+            asm!("wr {0}, 0, %set_softint", in(reg) SOFTINT_TICK_INT, options(nomem, nostack));
+        }
+    }
 }
 
-pub fn task_exit(code: i32) -> ! {
-    // Mevcut iş parçacığını ve görevi sonlandır
-    // Zamanlayıcıyı çağırarak başka bir göreve geç
-    // Asla geri dönmez
-    loop {} // Yer tutucu
+/// %tick_cmpr zamanlayıcı kesmesi (seviye 14). SOFTINT.TICK_INT temizlenir;
+/// ktask::timer_tick zamanlayıcıyı bir sonraki olaya yeniden kurar.
+pub fn handle_timer_interrupt() {
+    unsafe { asm!("wr {0}, 0, %clear_softint", in(reg) SOFTINT_TICK_INT, options(nomem, nostack)) };
+    ktask::timer_tick(ktask::now_ns());
 }
 
-pub fn get_current_task_id() -> Result<KTaskId, KError> {
-    // Mevcut görev/iş parçacığı kontrol bloğundan ID'yi al
-    Err(KError::NotSupported) // Yer tutucu
+/// Çalışan iş parçacığının çekirdek yığın tepesi (kullanıcı tuzak girişi için).
+pub fn current_kernel_stack_top() -> u64 {
+    KERNEL_STACK_TOP.load(Ordering::Relaxed)
 }
 
-// ... diğer ktask API fonksiyonları ...
-
-
-
-// --- Yardımcı Fonksiyonlar / Makrolar (Gerekirse) ---
-
-// TODO: SPARC yazmaçlarına veya özel talimatlara erişim için yardımcı makrolar veya inline assembly fonksiyonları.
-
-// Örneğin:
-
-#[inline(always)]
-pub unsafe fn get_sp() -> u64 {
-    // TODO: SPARC assembly ile mevcut yığın işaretçisini (SP) al.
-    0 // Yer tutucu
+/// SPARC mimari katmanını ktask'a kaydeder. Platform başlatma kodu karnal64::init()'ten sonra çağırır.
+pub fn init() {
+    ktask::register_arch(&ARCH_TASK);
 }
@@ -25,6 +25,21 @@ fn read_sparc_timer_register() -> u64 {
     }
 }
 
+// %tick sayacının frekansı (OBP /cpus `clock-frequency`; QEMU sun4u: 100 MHz).
+// srctask_sparcv9.rs'deki %tick_cmpr programlaması da bu frekansı kullanır.
+const TICK_FREQUENCY_HZ: u64 = 100_000_000;
+/// %tick'in NPT (ayrıcalıksız erişimi engelle) biti; sayaç değerine dahil değildir.
+const TICK_NPT: u64 = 1 << 63;
+
+/// Boot'tan beri geçen monoton süre (hardware_specific.h): %tick, nanosaniyeye çevrilir.
+/// ktask zamanlayıcısı ve CPU süresi muhasebesi bu kancayı kullanır.
+#[no_mangle]
+pub extern "C" fn low_level_monotonic_time_ns() -> u64 {
+    let tick: u64;
+    unsafe { core::arch::asm!("rd %tick, {0}", out(reg) tick, options(nomem, nostack)) };
+    (((tick & !TICK_NPT) as u128 * 1_000_000_000) / TICK_FREQUENCY_HZ as u128) as u64
+}
+
 /// SPARC mimarisi için zaman kaynağı sağlayan yapı.
 /// Karnal64'ün ResourceProvider trait'ini implemente eder.
 pub struct SparcTimeSource;
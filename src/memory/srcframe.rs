@@ -0,0 +1,561 @@
+// Karnal64 kmemory: fiziksel çerçeve ayırıcı (buddy).
+// Fiziksel bellek, firmware'in bildirdiği bölgelerden (DTB /memory, amd64 boot bellek haritası)
+// `add_physical_memory_region` ile öğrenilir. Ayrılmış aralıklar (DTB /reserved-memory, initrd,
+// firmware tabloları) `reserve_physical_region` ile, çekirdek imajı ise `__kernel_start` /
+// `__kernel_end` bağlayıcı sembollerinden işaretlenir. `init_frame_allocator` kalan aralıkları
+// 2^order sayfalık bloklar halinde (order 0..=MAX_ORDER) serbest listelere koyar.
+// Her bölge ayrı bir "zone"dur: buddy'ler yalnızca aynı zone içinde birleşir; hizalama zone
+// tabanına göre değil, mutlak çerçeve numarasına (PFN) göredir, böylece 2^order'lık bloklar
+// fiziksel adreste de 2^order sayfaya hizalıdır. Serbest listeler boş sayfaların kendisinde tutulur (çift bağlı, fiziksel
+// adreslerle); ayrıca çerçeve başına bir bayt üst veri (serbest blok başı + order) boş
+// belleğin başından ayrılır ve çift bırakma / geçersiz bırakma tespiti için kullanılır.
+// Mimari MMU kodları çerçeveleri `FrameAllocator` trait'i üzerinden alır (bkz. `frame_allocator`).
+
+use super::super::ksync::Spinlock;
+use super::super::KError;
+use super::PAGE_SIZE;
+
+/// En büyük blok: 2^MAX_ORDER sayfa (4 KiB sayfalarla 4 MiB).
+pub const MAX_ORDER: usize = 10;
+/// `add_physical_memory_region` ile eklenebilecek en fazla bölge.
+pub const MAX_MEMORY_REGIONS: usize = 16;
+/// En fazla ayrılmış aralık (çekirdek imajı ve üst veri dahil).
+pub const MAX_RESERVED_REGIONS: usize = 32;
+
+/// Çekirdeğin fiziksel belleğe eriştiği doğrudan eşlemenin tabanı. Portlar erken boot'tan beri
+/// fiziksel belleği kimlik eşlemesiyle görür.
+pub const PHYS_MAP_OFFSET: u64 = 0;
+
+const PAGE: u64 = PAGE_SIZE as u64;
+/// Serbest liste sonu.
+const NIL: u64 = u64::MAX;
+/// Üst veri baytında serbest blok başı işareti; alt bitler blok order'ıdır.
+const FREE_HEAD: u8 = 0x80;
+
+extern "C" {
+    /// Bağlayıcı betiğinin tanımladığı çekirdek imajı sınırları (fiziksel; erken boot kimlik eşlemeli).
+    static __kernel_start: u8;
+    static __kernel_end: u8;
+}
+
+/// Mimari MMU kodlarının sayfa tablosu ve sayfa çerçevesi ayırmak için kullandığı arayüz.
+pub trait FrameAllocator: Sync {
+    /// 2^`order` ardışık, 2^`order` sayfaya hizalı çerçeve ayırır ve ilkinin fiziksel adresini döner.
+    /// Yer yoksa `KError::OutOfMemory`, `order > MAX_ORDER` ise `KError::InvalidArgument`.
+    /// Çerçevelerin içeriği tanımsızdır.
+    fn allocate_frames(&self, order: usize) -> Result<u64, KError>;
+
+    /// `allocate_frames(order)` ile alınmış bloğu geri verir.
+    ///
+    /// # Safety
+    /// Bloğa artık hiçbir eşleme veya çekirdek pointer'ı başvurmamalıdır.
+    unsafe fn free_frames(&self, paddr: u64, order: usize);
+
+    /// Tek çerçeve ayırır.
+    fn allocate_frame(&self) -> Result<u64, KError> {
+        self.allocate_frames(0)
+    }
+
+    /// Tek çerçeveyi geri verir.
+    ///
+    /// # Safety
+    /// Bkz. `free_frames`.
+    unsafe fn free_frame(&self, paddr: u64) {
+        self.free_frames(paddr, 0)
+    }
+}
+
+/// Çerçeve ayırıcı istatistikleri (`frame_stats`).
+#[derive(Debug, Copy, Clone, Default)]
+pub struct FrameStats {
+    /// Zone'lardaki tüm çerçeveler (ayrılmış aralıklar dahil).
+    pub total_frames: u64,
+    /// Serbest listelerdeki çerçeveler.
+    pub free_frames: u64,
+    /// Order başına serbest blok sayısı.
+    pub free_blocks: [u64; MAX_ORDER + 1],
+}
+
+/// [base, end) fiziksel aralığı; sayfa sınırlarına yuvarlanmış.
+#[derive(Copy, Clone)]
+struct Range {
+    base: u64,
+    end: u64,
+}
+
+#[derive(Copy, Clone)]
+struct Zone {
+    base: u64,
+    frames: u64,
+    /// Zone'un çerçeve başına üst veri dizisinin fiziksel adresi.
+    meta: u64,
+}
+
+/// Serbest bir bloğun ilk sayfasına yazılan liste düğümü.
+#[repr(C)]
+struct FreeNode {
+    next: u64,
+    prev: u64,
+}
+
+struct BuddyState {
+    memory: [Option<Range>; MAX_MEMORY_REGIONS],
+    reserved: [Option<Range>; MAX_RESERVED_REGIONS],
+    zones: [Option<Zone>; MAX_MEMORY_REGIONS],
+    free_lists: [u64; MAX_ORDER + 1],
+    stats: FrameStats,
+    ready: bool,
+}
+
+/// Fiziksel adresin çekirdekteki sanal karşılığı.
+pub fn phys_to_virt(paddr: u64) -> *mut u8 {
+    (paddr + PHYS_MAP_OFFSET) as usize as *mut u8
+}
+
+fn insert_range(list: &mut [Option<Range>], range: Range) -> Result<(), KError> {
+    if range.base >= range.end {
+        return Err(KError::InvalidArgument);
+    }
+    let slot = list.iter_mut().find(|r| r.is_none()).ok_or(KError::OutOfMemory)?;
+    *slot = Some(range);
+    Ok(())
+}
+
+fn node(paddr: u64) -> *mut FreeNode {
+    phys_to_virt(paddr) as *mut FreeNode
+}
+
+impl BuddyState {
+    const fn new() -> Self {
+        BuddyState {
+            memory: [None; MAX_MEMORY_REGIONS],
+            reserved: [None; MAX_RESERVED_REGIONS],
+            zones: [None; MAX_MEMORY_REGIONS],
+            free_lists: [NIL; MAX_ORDER + 1],
+            stats: FrameStats { total_frames: 0, free_frames: 0, free_blocks: [0; MAX_ORDER + 1] },
+            ready: false,
+        }
+    }
+
+    fn zone_of(&self, paddr: u64) -> Option<usize> {
+        self.zones.iter().position(|z| matches!(z, Some(z) if paddr >= z.base && paddr < z.base + z.frames * PAGE))
+    }
+
+    fn meta(&self, zone: usize, idx: u64) -> *mut u8 {
+        let zone = self.zones[zone].expect("buddy: zone yok");
+        phys_to_virt(zone.meta + idx)
+    }
+
+    fn frame_addr(&self, zone: usize, idx: u64) -> u64 {
+        self.zones[zone].expect("buddy: zone yok").base + idx * PAGE
+    }
+
+    /// Zone'un ilk çerçevesinin mutlak çerçeve numarası (PFN).
+    fn base_pfn(&self, zone: usize) -> u64 {
+        self.zones[zone].expect("buddy: zone yok").base / PAGE
+    }
+
+    fn list_push(&mut self, order: usize, paddr: u64) {
+        let head = self.free_lists[order];
+        unsafe {
+            *node(paddr) = FreeNode { next: head, prev: NIL };
+            if head != NIL {
+                (*node(head)).prev = paddr;
+            }
+        }
+        self.free_lists[order] = paddr;
+    }
+
+    fn list_remove(&mut self, order: usize, paddr: u64) {
+        let FreeNode { next, prev } = unsafe { core::ptr::read(node(paddr)) };
+        unsafe {
+            if prev != NIL {
+                (*node(prev)).next = next;
+            } else {
+                self.free_lists[order] = next;
+            }
+            if next != NIL {
+                (*node(next)).prev = prev;
+            }
+        }
+    }
+
+    fn mark_free(&mut self, zone: usize, idx: u64, order: usize) {
+        unsafe { *self.meta(zone, idx) = FREE_HEAD | order as u8 };
+        self.list_push(order, self.frame_addr(zone, idx));
+        self.stats.free_blocks[order] += 1;
+    }
+
+    fn unmark_free(&mut self, zone: usize, idx: u64, order: usize) {
+        unsafe { *self.meta(zone, idx) = 0 };
+        self.list_remove(order, self.frame_addr(zone, idx));
+        self.stats.free_blocks[order] -= 1;
+    }
+
+    /// Bloğu serbest bırakır; serbest buddy'si varsa birleştirerek bir üst order'a çıkar.
+    fn free_block(&mut self, zone: usize, mut idx: u64, mut order: usize) {
+        let frames = self.zones[zone].expect("buddy: zone yok").frames;
+        let base_pfn = self.base_pfn(zone);
+        self.stats.free_frames += 1 << order;
+        while order < MAX_ORDER {
+            // Buddy mutlak PFN'den hesaplanır; zone'un başından önceye düşen buddy yoktur.
+            let buddy_pfn = (base_pfn + idx) ^ (1 << order);
+            if buddy_pfn < base_pfn {
+                break;
+            }
+            let buddy = buddy_pfn - base_pfn;
+            if buddy + (1 << order) > frames || unsafe { *self.meta(zone, buddy) } != FREE_HEAD | order as u8 {
+                break;
+            }
+            self.unmark_free(zone, buddy, order);
+            idx = idx.min(buddy);
+            order += 1;
+        }
+        self.mark_free(zone, idx, order);
+    }
+
+    fn allocate(&mut self, order: usize) -> Result<u64, KError> {
+        if order > MAX_ORDER {
+            return Err(KError::InvalidArgument);
+        }
+        let mut found = (order..=MAX_ORDER).find(|&o| self.free_lists[o] != NIL).ok_or(KError::OutOfMemory)?;
+        let paddr = self.free_lists[found];
+        let zone = self.zone_of(paddr).ok_or(KError::InternalError)?;
+        let idx = (paddr - self.zones[zone].unwrap().base) / PAGE;
+        self.unmark_free(zone, idx, found);
+        // Büyük bloğu böl; üst yarılar serbest listelere döner.
+        while found > order {
+            found -= 1;
+            self.mark_free(zone, idx + (1 << found), found);
+        }
+        self.stats.free_frames -= 1 << order;
+        Ok(paddr)
+    }
+
+    fn free(&mut self, paddr: u64, order: usize) {
+        let zone = match self.zone_of(paddr) {
+            Some(zone) if order <= MAX_ORDER && paddr % PAGE == 0 => zone,
+            _ => {
+                println!("Karnal64: buddy: geçersiz çerçeve bırakma {:#x} (order {})", paddr, order);
+                return;
+            }
+        };
+        let idx = (paddr - self.zones[zone].unwrap().base) / PAGE;
+        if (self.base_pfn(zone) + idx) % (1 << order) != 0 || idx + (1 << order) > self.zones[zone].unwrap().frames {
+            println!("Karnal64: buddy: hizasız çerçeve bırakma {:#x} (order {})", paddr, order);
+            return;
+        }
+        // Blok veya içindeki herhangi bir çerçeve zaten serbest bir bloğun başıysa çift bırakmadır.
+        // (Daha büyük bir serbest bloğun içine düşen bırakmalar bu kontrolle yakalanmaz.)
+        if (idx..idx + (1 << order)).any(|i| unsafe { *self.meta(zone, i) } & FREE_HEAD != 0) {
+            println!("Karnal64: buddy: çift çerçeve bırakma {:#x} (order {})", paddr, order);
+            return;
+        }
+        self.free_block(zone, idx, order);
+    }
+
+    /// [cursor, end) içinde, `cursor`dan itibaren ayrılmış aralıklara değmeyen ilk serbest parça.
+    fn next_free_piece(&self, mut cursor: u64, end: u64) -> Option<(u64, u64)> {
+        loop {
+            if cursor >= end {
+                return None;
+            }
+            let overlap = self.reserved.iter().flatten().filter(|r| r.base < end && r.end > cursor).min_by_key(|r| r.base);
+            match overlap {
+                None => return Some((cursor, end)),
+                Some(r) if r.base > cursor => return Some((cursor, r.base)),
+                Some(r) => cursor = r.end,
+            }
+        }
+    }
+
+    /// `kernel_image` çekirdek imajının kapladığı aralıktır; ayrılmış aralıklara eklenir.
+    fn init(&mut self, kernel_image: Range) -> Result<(), KError> {
+        if self.ready {
+            return Err(KError::AlreadyExists);
+        }
+        insert_range(&mut self.reserved, kernel_image)?;
+
+        // Zone'ları kur ve üst veri için gereken toplam boyutu hesapla.
+        let mut meta_len = 0;
+        for i in 0..MAX_MEMORY_REGIONS {
+            let Some(region) = self.memory[i] else { continue };
+            let base = region.base.next_multiple_of(PAGE);
+            let end = region.end & !(PAGE - 1);
+            if end <= base {
+                continue;
+            }
+            let frames = (end - base) / PAGE;
+            self.zones[i] = Some(Zone { base, frames, meta: meta_len });
+            self.stats.total_frames += frames;
+            meta_len += frames;
+        }
+        if meta_len == 0 {
+            return Err(KError::NotFound);
+        }
+        let meta_len = meta_len.next_multiple_of(PAGE);
+
+        // Üst veriyi, yeterince büyük ilk serbest parçanın başına yerleştir.
+        let mut meta_base = None;
+        'search: for zone in self.zones.iter().flatten() {
+            let end = zone.base + zone.frames * PAGE;
+            let mut cursor = zone.base;
+            while let Some((start, piece_end)) = self.next_free_piece(cursor, end) {
+                if piece_end - start >= meta_len {
+                    meta_base = Some(start);
+                    break 'search;
+                }
+                cursor = piece_end;
+            }
+        }
+        let meta_base = meta_base.ok_or(KError::OutOfMemory)?;
+        insert_range(&mut self.reserved, Range { base: meta_base, end: meta_base + meta_len })?;
+        for zone in self.zones.iter_mut().flatten() {
+            zone.meta += meta_base;
+        }
+        unsafe { core::ptr::write_bytes(phys_to_virt(meta_base), 0, meta_len as usize) };
+
+        // Serbest parçaları mümkün olan en büyük hizalı bloklarla listelere koy.
+        for z in 0..MAX_MEMORY_REGIONS {
+            let Some(zone) = self.zones[z] else { continue };
+            let end = zone.base + zone.frames * PAGE;
+            let mut cursor = zone.base;
+            while let Some((start, piece_end)) = self.next_free_piece(cursor, end) {
+                let mut addr = start;
+                while addr < piece_end {
+                    let idx = (addr - zone.base) / PAGE;
+                    let pfn = addr / PAGE;
+                    let order = (0..=MAX_ORDER)
+                        .rev()
+                        .find(|&o| pfn % (1 << o) == 0 && addr + (PAGE << o) <= piece_end)
+                        .unwrap_or(0);
+                    self.free_block(z, idx, order);
+                    addr += PAGE << order;
+                }
+                cursor = piece_end;
+            }
+        }
+        self.ready = true;
+        Ok(())
+    }
+}
+
+/// Buddy çerçeve ayırıcı. Tek örneği `FRAME_ALLOCATOR`'dır.
+pub struct BuddyAllocator {
+    state: Spinlock<BuddyState>,
+}
+
+// Spinlock<T>: Sync için T: Send gerekir; serbest listeler fiziksel adres tutar, çekirdek
+// pointer'ı içermez.
+unsafe impl Send for BuddyState {}
+
+impl BuddyAllocator {
+    const fn new() -> Self {
+        BuddyAllocator { state: Spinlock::named(BuddyState::new(), "kmemory::FRAME_ALLOCATOR") }
+    }
+}
+
+impl FrameAllocator for BuddyAllocator {
+    fn allocate_frames(&self, order: usize) -> Result<u64, KError> {
+        self.state.lock_irqsave().allocate(order)
+    }
+
+    unsafe fn free_frames(&self, paddr: u64, order: usize) {
+        self.state.lock_irqsave().free(paddr, order)
+    }
+}
+
+pub static FRAME_ALLOCATOR: BuddyAllocator = BuddyAllocator::new();
+
+/// Sistem çerçeve ayırıcısı; mimari MMU kodlarına `&dyn FrameAllocator` olarak verilir.
+pub fn frame_allocator() -> &'static dyn FrameAllocator {
+    &FRAME_ALLOCATOR
+}
+
+/// Firmware'in bildirdiği kullanılabilir RAM bölgesini ekler. `init_frame_allocator`'dan önce
+/// çağrılmalıdır; bölgeler birbiriyle örtüşmemelidir.
+pub fn add_physical_memory_region(base: u64, size: u64) -> Result<(), KError> {
+    let end = base.checked_add(size).ok_or(KError::InvalidArgument)?;
+    let mut state = FRAME_ALLOCATOR.state.lock_irqsave();
+    if state.ready {
+        return Err(KError::Busy);
+    }
+    insert_range(&mut state.memory, Range { base, end })
+}
+
+/// [base, base + size) aralığını ayırıcının dışında tutar (sayfa sınırlarına dışa doğru yuvarlanır).
+/// `init_frame_allocator`'dan önce çağrılmalıdır.
+pub fn reserve_physical_region(base: u64, size: u64) -> Result<(), KError> {
+    let end = base.checked_add(size).ok_or(KError::InvalidArgument)?;
+    let mut state = FRAME_ALLOCATOR.state.lock_irqsave();
+    if state.ready {
+        return Err(KError::Busy);
+    }
+    insert_range(&mut state.reserved, Range { base: base & !(PAGE - 1), end: end.next_multiple_of(PAGE) })
+}
+
+/// Eklenen bölgelerden çekirdek imajını ve ayrılmış aralıkları çıkarıp kalan belleği serbest
+/// listelere koyar. Kayıtlı bölge yoksa `KError::NotFound`.
+pub fn init_frame_allocator() -> Result<(), KError> {
+    let (kstart, kend) = unsafe { (&__kernel_start as *const u8 as u64, &__kernel_end as *const u8 as u64) };
+    let kernel_image = Range { base: kstart & !(PAGE - 1), end: kend.next_multiple_of(PAGE) };
+    let stats = {
+        let mut state = FRAME_ALLOCATOR.state.lock_irqsave();
+        state.init(kernel_image)?;
+        state.stats
+    };
+    println!(
+        "Karnal64: Fiziksel çerçeve ayırıcı hazır: {} / {} çerçeve serbest",
+        stats.free_frames, stats.total_frames
+    );
+    Ok(())
+}
+
+/// Tek fiziksel çerçeve ayırır (`FRAME_ALLOCATOR.allocate_frame`).
+pub fn allocate_physical_frame() -> Result<u64, KError> {
+    FRAME_ALLOCATOR.allocate_frame()
+}
+
+/// `allocate_physical_frame` ile alınmış çerçeveyi geri verir.
+pub fn free_physical_frame(paddr: u64) {
+    unsafe { FRAME_ALLOCATOR.free_frame(paddr) }
+}
+
+pub fn frame_stats() -> FrameStats {
+    FRAME_ALLOCATOR.state.lock_irqsave().stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::alloc::{alloc_zeroed, dealloc, Layout};
+
+    /// Testlerin "fiziksel belleği": barındırıcı belleği, PHYS_MAP_OFFSET = 0 ile kimlik eşlemeli
+    /// kabul edilir. 2^MAX_ORDER sayfaya hizalıdır, böylece blok sınırları öngörülebilir.
+    struct TestMemory {
+        base: u64,
+        layout: Layout,
+    }
+
+    impl TestMemory {
+        fn new(pages: usize) -> Self {
+            let layout = Layout::from_size_align(pages * PAGE_SIZE, PAGE_SIZE << MAX_ORDER).unwrap();
+            let base = unsafe { alloc_zeroed(layout) } as u64;
+            assert_ne!(base, 0);
+            TestMemory { base, layout }
+        }
+
+        fn page(&self, n: u64) -> u64 {
+            self.base + n * PAGE
+        }
+
+        /// Belleği tek bölge olarak kaydetmiş, henüz ilklenmemiş ayırıcı.
+        fn buddy(&self) -> BuddyState {
+            let mut state = BuddyState::new();
+            let end = self.base + self.layout.size() as u64;
+            insert_range(&mut state.memory, Range { base: self.base, end }).unwrap();
+            state
+        }
+    }
+
+    impl Drop for TestMemory {
+        fn drop(&mut self) {
+            unsafe { dealloc(self.base as *mut u8, self.layout) };
+        }
+    }
+
+    /// Çekirdek imajı test belleğinin dışındadır (barındırıcı belleği 0. sayfada değildir).
+    const KERNEL_IMAGE: Range = Range { base: 0, end: PAGE };
+
+    #[test]
+    fn test_init_builds_aligned_blocks() {
+        let memory = TestMemory::new(16);
+        let mut buddy = memory.buddy();
+        buddy.init(KERNEL_IMAGE).unwrap();
+
+        // İlk sayfa üst veriye ayrılır; kalan 15 sayfa 1 + 2 + 4 + 8 sayfalık bloklara bölünür.
+        assert_eq!(buddy.stats.total_frames, 16);
+        assert_eq!(buddy.stats.free_frames, 15);
+        assert_eq!(&buddy.stats.free_blocks[..5], &[1, 1, 1, 1, 0]);
+        assert_eq!(buddy.init(KERNEL_IMAGE), Err(KError::AlreadyExists));
+    }
+
+    #[test]
+    fn test_allocate_splits_and_free_merges() {
+        let memory = TestMemory::new(16);
+        let mut buddy = memory.buddy();
+        buddy.init(KERNEL_IMAGE).unwrap();
+        let initial = buddy.stats;
+
+        let a = buddy.allocate(1).unwrap();
+        assert_eq!(a, memory.page(2));
+        // Order 1 listesi boşaldı: 4 sayfalık blok bölünür, üst yarısı serbest kalır.
+        let b = buddy.allocate(1).unwrap();
+        assert_eq!(b, memory.page(4));
+        assert_eq!(buddy.stats.free_blocks[1], 1);
+        assert_eq!(buddy.stats.free_blocks[2], 0);
+        assert_eq!(buddy.stats.free_frames, initial.free_frames - 4);
+
+        buddy.free(b, 1);
+        buddy.free(a, 1);
+        assert_eq!(buddy.stats.free_frames, initial.free_frames);
+        assert_eq!(buddy.stats.free_blocks, initial.free_blocks);
+    }
+
+    #[test]
+    fn test_invalid_free_is_ignored() {
+        let memory = TestMemory::new(16);
+        let mut buddy = memory.buddy();
+        buddy.init(KERNEL_IMAGE).unwrap();
+
+        let block = buddy.allocate(2).unwrap();
+        let free_frames = buddy.stats.free_frames;
+        // Hizasız, zone dışı ve büyük order'lı bırakmalar reddedilir.
+        buddy.free(block + PAGE, 2);
+        buddy.free(KERNEL_IMAGE.base, 0);
+        buddy.free(block, MAX_ORDER + 1);
+        assert_eq!(buddy.stats.free_frames, free_frames);
+
+        buddy.free(block, 2);
+        assert_eq!(buddy.stats.free_frames, free_frames + 4);
+        // Çift bırakma.
+        buddy.free(block, 2);
+        assert_eq!(buddy.stats.free_frames, free_frames + 4);
+    }
+
+    #[test]
+    fn test_allocate_limits() {
+        let memory = TestMemory::new(16);
+        let mut buddy = memory.buddy();
+        buddy.init(KERNEL_IMAGE).unwrap();
+
+        assert_eq!(buddy.allocate(MAX_ORDER + 1), Err(KError::InvalidArgument));
+        assert_eq!(buddy.allocate(4), Err(KError::OutOfMemory));
+
+        let mut frames = [0u64; 15];
+        for frame in frames.iter_mut() {
+            *frame = buddy.allocate(0).unwrap();
+            assert!(*frame > memory.page(0) && *frame < memory.page(16));
+            assert_eq!(*frame % PAGE, 0);
+        }
+        frames.sort_unstable();
+        assert!(frames.windows(2).all(|w| w[0] != w[1]));
+        assert_eq!(buddy.allocate(0), Err(KError::OutOfMemory));
+        assert_eq!(buddy.stats.free_frames, 0);
+    }
+
+    #[test]
+    fn test_reserved_region_is_skipped() {
+        let memory = TestMemory::new(16);
+        let mut buddy = memory.buddy();
+        insert_range(&mut buddy.reserved, Range { base: memory.page(5), end: memory.page(6) }).unwrap();
+        buddy.init(KERNEL_IMAGE).unwrap();
+
+        assert_eq!(buddy.stats.free_frames, 14);
+        while let Ok(frame) = buddy.allocate(0) {
+            assert_ne!(frame, memory.page(0));
+            assert_ne!(frame, memory.page(5));
+        }
+        assert_eq!(buddy.stats.free_frames, 0);
+    }
+}
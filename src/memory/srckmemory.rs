@@ -0,0 +1,184 @@
+// Geliştirme sırasında kullanılmayan kod veya argümanlar için izinler
+#![allow(dead_code)]
+#![allow(unused_variables)]
+
+// Karnal64 kmemory modülü: çekirdek bellek yönetimi.
+// - Kullanıcı alanı adres/tampon doğrulaması
+// - Kullanıcı alanı ile çekirdek arasında güvenli kopyalama
+// - Kullanıcı sanal adresinden fiziksel adrese çeviri (futex anahtarları vb. için)
+// - Fiziksel çerçeve ayırıcı (buddy, bkz. srcframe.rs)
+// - Çekirdek yığını: slab önbellekleri ve `feature = "alloc"` için global ayırıcı (bkz. srcslab.rs)
+// - Görev adres alanları: VMA ağaçları, boş aralık bulan ayırma, paylaşımlı bellek nesneleri ve
+//   sayfa hatasında talep üzerine sayfa doldurma (bkz. srcvmspace.rs, srcvma.rs, srcshm.rs)
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use super::KError;
+
+// Firmware bellek haritasından beslenen fiziksel çerçeve ayırıcı
+#[path = "srcframe.rs"]
+mod frame;
+pub use frame::{
+    add_physical_memory_region, allocate_physical_frame, frame_allocator, frame_stats, free_physical_frame,
+    init_frame_allocator, phys_to_virt, reserve_physical_region, BuddyAllocator, FrameAllocator, FrameStats,
+    FRAME_ALLOCATOR, MAX_MEMORY_REGIONS, MAX_ORDER, PHYS_MAP_OFFSET,
+};
+
+// Çerçeve ayırıcı üzerinde boyut sınıflı ve türlü slab önbellekleri (çekirdek yığını)
+#[path = "srcslab.rs"]
+mod slab;
+pub use slab::{
+    heap_stats, kfree, kmalloc, HeapStats, ObjectCache, SlabCache, SlabStats, KMALLOC_MAX_SIZE, MAGAZINE_SIZE,
+    SLAB_MAX_ALIGN,
+};
+
+// Görev adres alanı bölgeleri (VMA) ve onları tutan dengeli ağaç
+#[path = "srcvma.rs"]
+mod vma;
+pub use vma::{
+    Vma, VmaBacking, VmaTree, MAP_ANONYMOUS, MAP_FIXED, MAP_PRIVATE, MAP_SHARED, PROT_EXEC, PROT_NONE, PROT_READ,
+    PROT_WRITE,
+};
+
+// Görevler arasında eşlenebilen paylaşımlı bellek nesneleri
+#[path = "srcshm.rs"]
+mod shm;
+pub use shm::{
+    check_shm_handle, is_shm_handle, release_task_shm, share_shm_handle, shm_acquire, shm_control, shm_release,
+    MAX_SHM_OBJECTS, SHM_CONTROL_SIZE, SHM_RESOURCE_PREFIX,
+};
+
+// Görev başına adres alanları ve SYSCALL_MEMORY_* işlemleri
+#[path = "srcvmspace.rs"]
+mod vmspace;
+pub use vmspace::{
+    create_address_space, destroy_address_space, handle_page_fault, memory_alloc, memory_map, memory_protect,
+    memory_unmap, register_arch_mmu, user_mmap_base, user_space_end, ArchMmu, MapSource, MAX_ADDRESS_SPACES,
+};
+
+/// Tüm desteklenen mimarilerde kullanılan temel sayfa boyutu.
+pub const PAGE_SIZE: usize = 4096;
+
+/// Kullanıcı alanının başlangıcı. İlk sayfa, null pointer erişimlerini yakalamak için hiç eşlenmez.
+pub const USER_SPACE_START: usize = PAGE_SIZE;
+
+pub fn init_manager() {
+    println!("Karnal64: Bellek Yöneticisi Başlatıldı");
+}
+
+/// `[ptr, ptr + len)` aralığı tamamen kullanıcı alanı sınırları içinde mi?
+fn is_user_range(ptr: usize, len: usize) -> bool {
+    match ptr.checked_add(len) {
+        Some(end) => ptr >= USER_SPACE_START && end <= user_space_end(),
+        None => false,
+    }
+}
+
+/// Aralığın dokunduğu her sayfanın istenen erişimle eşlenmiş olduğunu doğrular; henüz
+/// dokunulmamış sayfalar `user_virt_to_phys` içinde doldurulur.
+fn is_user_range_mapped(ptr: usize, len: usize, write_access: bool) -> bool {
+    let first_page = ptr & !(PAGE_SIZE - 1);
+    let last_page = (ptr + len - 1) & !(PAGE_SIZE - 1);
+    (first_page..=last_page)
+        .step_by(PAGE_SIZE)
+        .all(|page| user_virt_to_phys(page, write_access).is_ok())
+}
+
+/// Kullanıcı tamponu, mevcut görev tarafından okunabilir mi?
+pub fn is_user_buffer_valid_and_readable(ptr: *const u8, len: usize) -> bool {
+    if len == 0 {
+        return true;
+    }
+    is_user_range(ptr as usize, len) && is_user_range_mapped(ptr as usize, len, false)
+}
+
+/// Kullanıcı tamponu, mevcut görev tarafından yazılabilir mi?
+pub fn is_user_buffer_valid_and_writable(ptr: *mut u8, len: usize) -> bool {
+    if len == 0 {
+        return true;
+    }
+    is_user_range(ptr as usize, len) && is_user_range_mapped(ptr as usize, len, true)
+}
+
+/// Kullanıcı sanal adresini mevcut adres alanında fiziksel adrese çevirir (sayfa + sayfa içi ofset).
+/// Sayfa henüz eşlenmemişse (talep üzerine sayfalama) bir sayfa hatası gibi doldurulur; bu yüzden
+/// bloklayabilir ve spinlock altında çağrılmamalıdır.
+/// Adres kullanıcı alanında değilse veya erişim geçerli değilse `KError::BadAddress` döner.
+pub fn user_virt_to_phys(vaddr: usize, write_access: bool) -> Result<u64, KError> {
+    if let Ok(paddr) = lookup_user_page(vaddr, write_access) {
+        return Ok(paddr);
+    }
+    let access = if write_access { PROT_WRITE } else { PROT_READ };
+    handle_page_fault(vaddr, access).map_err(|_| KError::BadAddress)?;
+    lookup_user_page(vaddr, write_access)
+}
+
+/// `user_virt_to_phys`'in sayfa doldurmayan hali: yalnızca portun sayfa tablosunu yürür
+/// (`ArchMmu::translate`). Kayıtlı `ArchMmu` yoksa her adres geçersizdir.
+fn lookup_user_page(vaddr: usize, write_access: bool) -> Result<u64, KError> {
+    if !is_user_range(vaddr, 1) {
+        return Err(KError::BadAddress);
+    }
+    vmspace::translate_user(vaddr, write_access)
+}
+
+/// Kullanıcı alanından çekirdek tamponuna `len` byte kopyalar.
+/// Kaynak aralık doğrulanır ve kopyalama bitene kadar adres alanı kilidi tutulur; başka bir iş
+/// parçacığının memory_unmap'i aralığı kopyalama sırasında kaldıramaz. Bloklayabilir.
+pub fn copy_from_user(dest: *mut u8, src: *const u8, len: usize) -> Result<(), KError> {
+    if len == 0 {
+        return Ok(());
+    }
+    if dest.is_null() {
+        return Err(KError::InvalidArgument);
+    }
+    if !is_user_range(src as usize, len) {
+        return Err(KError::BadAddress);
+    }
+    vmspace::with_user_range(src as usize, len, PROT_READ, || unsafe { core::ptr::copy_nonoverlapping(src, dest, len) })
+}
+
+/// Çekirdek tamponundan kullanıcı alanına `len` byte kopyalar (bkz. `copy_from_user`).
+pub fn copy_to_user(dest: *mut u8, src: *const u8, len: usize) -> Result<(), KError> {
+    if len == 0 {
+        return Ok(());
+    }
+    if src.is_null() {
+        return Err(KError::InvalidArgument);
+    }
+    if !is_user_range(dest as usize, len) {
+        return Err(KError::BadAddress);
+    }
+    vmspace::with_user_range(dest as usize, len, PROT_WRITE, || unsafe { core::ptr::copy_nonoverlapping(src, dest, len) })
+}
+
+/// Kullanıcı alanındaki hizalı 32-bit değeri atomik olarak okur (futex değerleri için).
+/// Sayfa doldurmaz, spinlock altında çağrılabilir; sayfa önceden `user_virt_to_phys` ile
+/// getirilmiş olmalıdır. Hizasız adreste `KError::InvalidArgument`, eşli olmayan adreste
+/// `KError::BadAddress` döner.
+pub fn read_user_u32(addr: usize) -> Result<u32, KError> {
+    if addr % core::mem::align_of::<u32>() != 0 {
+        return Err(KError::InvalidArgument);
+    }
+    // Hizalı değer sayfa sınırını aşmaz.
+    lookup_user_page(addr, false)?;
+    // Güvenlik: adres doğrulandı ve hizalı; kullanıcı alanı diğer iş parçacıklarınca
+    // eşzamanlı değiştirilebileceği için atomik okuma kullanılır.
+    Ok(unsafe { (*(addr as *const AtomicU32)).load(Ordering::SeqCst) })
+}
+
+/// Mevcut görevin adres alanında `size` baytlık (sayfaya yuvarlanmış), okunur/yazılır anonim
+/// bellek ayırır (SYSCALL_MEMORY_ALLOCATE, ktask::thread_create yığınları).
+/// Aralığın tamamı hemen görevin bellek kotasından düşülür (`ktask::QUOTA_MEMORY`); sayfalar
+/// ilk dokunuşta sıfırlanıp eşlenir.
+pub fn allocate_user_memory(size: usize) -> Result<*mut u8, KError> {
+    memory_alloc(size, PROT_READ | PROT_WRITE).map(|addr| addr as *mut u8)
+}
+
+/// `allocate_user_memory` ile ayrılmış [`ptr`, `ptr + size`) aralığını bırakır.
+pub fn free_user_memory(ptr: *mut u8, size: usize) -> Result<(), KError> {
+    if size == 0 || ptr as usize % PAGE_SIZE != 0 || !is_user_range(ptr as usize, size) {
+        return Err(KError::InvalidArgument);
+    }
+    memory_unmap(ptr as usize, size)
+}
@@ -21,6 +21,19 @@ pub enum MemoryError {
     InvalidParameter = -5,
 }
 
+impl MemoryError {
+    /// Çekirdeğin döndürdüğü negatif KError kodunu eşler.
+    fn from_code(code: i64) -> Self {
+        match code {
+            -12 => MemoryError::OutOfMemory,
+            -3 | -14 => MemoryError::InvalidParameter,
+            -1 | -17 => MemoryError::InvalidOperation,
+            -38 => MemoryError::NotSupported,
+            _ => MemoryError::InternalError,
+        }
+    }
+}
+
 // --- Bellek Koruma Bayrakları ---
 bitflags::bitflags! {
     pub struct MemoryProtection: u32 {
@@ -57,16 +70,11 @@ pub struct KarnalMemory;
 impl KarnalMemory {
     /// Bellek ayır
     pub fn alloc(size: usize, prot: MemoryProtection) -> Result<*mut u8, MemoryError> {
-        let mut addr: usize = 0;
-        let ret = unsafe {
-            sys_memory_alloc(size, prot.bits(), &mut addr as *mut usize)
-        };
-        match ret {
-            0 => Ok(addr as *mut u8),
-            -4 => Err(MemoryError::OutOfMemory),
-            -5 => Err(MemoryError::InvalidParameter),
-            _ => Err(MemoryError::InternalError),
+        let ret = unsafe { sys_memory_alloc(size, prot.bits()) };
+        if ret < 0 {
+            return Err(MemoryError::from_code(ret));
         }
+        Ok(ret as usize as *mut u8)
     }
 
     /// Bellek serbest bırak
@@ -74,8 +82,7 @@ impl KarnalMemory {
         let ret = unsafe { sys_memory_free(ptr as usize, size) };
         match ret {
             0 => Ok(()),
-            -5 => Err(MemoryError::InvalidParameter),
-            _ => Err(MemoryError::InternalError),
+            _ => Err(MemoryError::from_code(ret)),
         }
     }
 
@@ -84,38 +91,25 @@ impl KarnalMemory {
         let ret = unsafe { sys_memory_protect(ptr as usize, size, prot.bits()) };
         match ret {
             0 => Ok(()),
-            -5 => Err(MemoryError::InvalidParameter),
-            _ => Err(MemoryError::InternalError),
+            _ => Err(MemoryError::from_code(ret)),
         }
     }
 
     /// Bellek bölgesi haritala
+    /// `handle`: ANONYMOUS için 0; aksi halde paylaşımlı bellek (SHARED) veya kaynak (PRIVATE) handle'ı.
     pub fn map(
         addr: *mut u8,
         size: usize,
         prot: MemoryProtection,
         flags: MemoryMapFlags,
-        fd: i64,
+        handle: u64,
         offset: usize,
     ) -> Result<*mut u8, MemoryError> {
-        let mut mapped: usize = 0;
-        let ret = unsafe {
-            sys_memory_map(
-                addr as usize,
-                size,
-                prot.bits(),
-                flags.bits(),
-                fd,
-                offset,
-                &mut mapped as *mut usize,
-            )
-        };
-        match ret {
-            0 => Ok(mapped as *mut u8),
-            -4 => Err(MemoryError::OutOfMemory),
-            -5 => Err(MemoryError::InvalidParameter),
-            _ => Err(MemoryError::InternalError),
+        let ret = unsafe { sys_memory_map(addr as usize, size, prot.bits(), flags.bits(), handle, offset) };
+        if ret < 0 {
+            return Err(MemoryError::from_code(ret));
         }
+        Ok(ret as usize as *mut u8)
     }
 
     /// Bellek bölgesi haritalamayı kaldır
@@ -123,8 +117,7 @@ impl KarnalMemory {
         let ret = unsafe { sys_memory_unmap(ptr as usize, size) };
         match ret {
             0 => Ok(()),
-            -5 => Err(MemoryError::InvalidParameter),
-            _ => Err(MemoryError::InternalError),
+            _ => Err(MemoryError::from_code(ret)),
         }
     }
 
@@ -146,23 +139,21 @@ impl KarnalMemory {
 
 // --- Karnal64 Sistem Çağrısı Uyumlu Assembly (Kendi çekirdeğinize göre uyarlamalısınız) ---
 
-/// Bellek ayırma sistem çağrısı
-unsafe fn sys_memory_alloc(size: usize, prot: u32, out_addr: *mut usize) -> i64 {
+/// Bellek ayırma sistem çağrısı (dönüş: adres veya negatif hata kodu)
+unsafe fn sys_memory_alloc(size: usize, prot: u32) -> i64 {
     let mut ret: i64;
     core::arch::asm!(
-        // x0: size, x1: prot, x2: out_addr, x8: syscall no, svc #0, x0: return
+        // x0: size, x1: prot, x8: syscall no, svc #0, x0: return
         "mov x0, {0}",
         "mov x1, {1}",
-        "mov x2, {2}",
-        "mov x8, {3}",
+        "mov x8, {2}",
         "svc #0",
-        "mov {4}, x0",
+        "mov {3}, x0",
         in(reg) size,
         in(reg) prot,
-        in(reg) out_addr,
         const SYSCALL_MEMORY_ALLOC,
         lateout(reg) ret,
-        out("x0") _, out("x1") _, out("x2") _, out("x8") _,
+        out("x0") _, out("x1") _, out("x8") _,
         options(nostack)
     );
     ret
@@ -208,38 +199,28 @@ unsafe fn sys_memory_protect(ptr: usize, size: usize, prot: u32) -> i64 {
     ret
 }
 
-/// Bellek haritalama sistem çağrısı
-unsafe fn sys_memory_map(
-    addr: usize,
-    size: usize,
-    prot: u32,
-    flags: u32,
-    fd: i64,
-    offset: usize,
-    out_addr: *mut usize,
-) -> i64 {
+/// Bellek haritalama sistem çağrısı (dönüş: adres veya negatif hata kodu)
+unsafe fn sys_memory_map(addr: usize, size: usize, prot: u32, flags: u32, handle: u64, offset: usize) -> i64 {
     let mut ret: i64;
+    // Çekirdek beş argüman alır: koruma alt 32 bitte, bayraklar üst 32 bitte taşınır.
+    let prot_flags = prot as u64 | (flags as u64) << 32;
     core::arch::asm!(
         "mov x0, {0}",
         "mov x1, {1}",
         "mov x2, {2}",
         "mov x3, {3}",
         "mov x4, {4}",
-        "mov x5, {5}",
-        "mov x6, {6}",
-        "mov x8, {7}",
+        "mov x8, {5}",
         "svc #0",
-        "mov {8}, x0",
+        "mov {6}, x0",
         in(reg) addr,
         in(reg) size,
-        in(reg) prot,
-        in(reg) flags,
-        in(reg) fd,
+        in(reg) prot_flags,
+        in(reg) handle,
         in(reg) offset,
-        in(reg) out_addr,
         const SYSCALL_MEMORY_MAP,
         lateout(reg) ret,
-        out("x0") _, out("x1") _, out("x2") _, out("x3") _, out("x4") _, out("x5") _, out("x6") _, out("x8") _,
+        out("x0") _, out("x1") _, out("x2") _, out("x3") _, out("x4") _, out("x8") _,
         options(nostack)
     );
     ret
@@ -294,7 +275,7 @@ pub fn example_usage() {
             8192,
             MemoryProtection::READ | MemoryProtection::WRITE,
             MemoryMapFlags::PRIVATE | MemoryMapFlags::ANONYMOUS,
-            -1,
+            0,
             0,
         );
 
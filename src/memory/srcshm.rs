@@ -0,0 +1,265 @@
+// Karnal64 kmemory: paylaşımlı bellek nesneleri.
+// Bir nesne, çerçeve ayırıcıdan tek parça alınmış, sıfırlanmış 2^order sayfalık bir bloktur (en
+// fazla 2^MAX_ORDER sayfa). Görevler nesnelere kaynak handle'larıyla erişir:
+// `resource_acquire("karnal://shm/new/<boyut>")` yeni bir nesne oluşturur ve ilk handle'ını döner.
+// Handle, task_spawn ile çocuk görevlere devredilebilir; `memory_map(.., MAP_SHARED, handle, ..)`
+// nesnenin bir bölümünü adres alanına eşler ve tüm eşlemeler aynı çerçeveleri gösterir.
+// Handle'lar alan göreve aittir, onun handle kotasından sayılır ve görev toplanınca kapanır.
+// Nesne son handle'ı kapanıp son eşlemesi kaldırılınca çerçeve ayırıcıya geri verilir; belleği
+// oluşturan görevin bellek kotasından düşülür.
+
+use super::super::ksync::Spinlock;
+use super::super::ktask;
+use super::super::{KError, KHandle, KTaskId};
+use super::frame::{frame_allocator, phys_to_virt, MAX_ORDER};
+use super::PAGE_SIZE;
+
+/// Aynı anda var olabilecek en fazla paylaşımlı bellek nesnesi.
+pub const MAX_SHM_OBJECTS: usize = 32;
+/// Tüm görevlerin açık tutabileceği en fazla paylaşımlı bellek handle'ı.
+const MAX_SHM_HANDLES: usize = 64;
+
+/// Paylaşımlı bellek handle'larının `resource_acquire` adı öneki. Ardından `new/<boyut>` gelir.
+pub const SHM_RESOURCE_PREFIX: &str = "karnal://shm/";
+
+// resource_control istekleri (paylaşımlı bellek handle'ları için)
+/// Nesnenin (sayfaya yuvarlanmış) boyutunu döner.
+pub const SHM_CONTROL_SIZE: u64 = 0;
+
+const SHM_HANDLE_TAG: u64 = 0x4D << 56; // 'M'
+
+#[derive(Copy, Clone)]
+struct ShmObject {
+    /// Bloğun fiziksel adresi.
+    paddr: u64,
+    order: usize,
+    /// Sayfaya yuvarlanmış boyut.
+    size: usize,
+    /// Belleği kotasından düşülen görev.
+    creator: KTaskId,
+    handles: u32,
+    mappings: u32,
+}
+
+#[derive(Copy, Clone)]
+struct ShmHandle {
+    generation: u16,
+    owner: Option<KTaskId>,
+    object: usize,
+}
+
+struct ShmTable {
+    objects: [Option<ShmObject>; MAX_SHM_OBJECTS],
+    handles: [ShmHandle; MAX_SHM_HANDLES],
+}
+
+impl ShmTable {
+    /// Yeni bir handle açar; `owner`'ın handle kotasından düşülür.
+    fn handle_open(&mut self, owner: KTaskId, object: usize) -> Result<KHandle, KError> {
+        let index = self.handles.iter().position(|h| h.owner.is_none()).ok_or(KError::OutOfMemory)?;
+        ktask::quota_charge_task(owner, ktask::QUOTA_HANDLES, 1)?;
+        let handle = &mut self.handles[index];
+        handle.owner = Some(owner);
+        handle.object = object;
+        let generation = handle.generation;
+        if let Some(obj) = self.objects[object].as_mut() {
+            obj.handles += 1;
+        }
+        Ok(KHandle(SHM_HANDLE_TAG | ((generation as u64) << 16) | (index as u64 + 1)))
+    }
+
+    /// `handle_value`'yu `owner`'a ait bir handle'ın tablo indeksine çözer.
+    fn handle_index(&self, owner: KTaskId, handle_value: u64) -> Result<usize, KError> {
+        if !is_shm_handle(handle_value) {
+            return Err(KError::BadHandle);
+        }
+        let generation = ((handle_value >> 16) & 0xFFFF) as u16;
+        let index = (handle_value & 0xFFFF) as usize;
+        if index == 0 || index > MAX_SHM_HANDLES {
+            return Err(KError::BadHandle);
+        }
+        let handle = self.handles[index - 1];
+        if handle.owner != Some(owner) || handle.generation != generation {
+            return Err(KError::BadHandle);
+        }
+        Ok(index - 1)
+    }
+
+    /// Handle'ı kapatır. Bırakılması gereken nesne varsa döner.
+    fn handle_close(&mut self, index: usize) -> Option<ShmObject> {
+        let handle = self.handles[index];
+        let owner = handle.owner?;
+        self.handles[index] = ShmHandle { generation: handle.generation.wrapping_add(1), owner: None, object: 0 };
+        ktask::quota_uncharge_task(owner, ktask::QUOTA_HANDLES, 1);
+        if let Some(obj) = self.objects[handle.object].as_mut() {
+            obj.handles -= 1;
+        }
+        self.take_if_unused(handle.object)
+    }
+
+    fn take_if_unused(&mut self, object: usize) -> Option<ShmObject> {
+        match self.objects[object] {
+            Some(obj) if obj.handles == 0 && obj.mappings == 0 => self.objects[object].take(),
+            _ => None,
+        }
+    }
+}
+
+const NO_HANDLE: ShmHandle = ShmHandle { generation: 0, owner: None, object: 0 };
+static SHM: Spinlock<ShmTable> = Spinlock::named(
+    ShmTable { objects: [None; MAX_SHM_OBJECTS], handles: [NO_HANDLE; MAX_SHM_HANDLES] },
+    "kmemory::SHM",
+);
+
+/// Kullanılmayan nesnenin çerçevelerini ve kotasını geri verir. SHM kilidi dışında çağrılır.
+fn free_object(obj: ShmObject) {
+    unsafe { frame_allocator().free_frames(obj.paddr, obj.order) };
+    ktask::quota_uncharge_task(obj.creator, ktask::QUOTA_MEMORY, obj.size as u64);
+}
+
+/// `handle_value` bir paylaşımlı bellek handle'ı mı (geçerliliği kontrol edilmez).
+pub fn is_shm_handle(handle_value: u64) -> bool {
+    handle_value & (0xFF << 56) == SHM_HANDLE_TAG
+}
+
+/// `resource_acquire` yolu: SHM_RESOURCE_PREFIX'ten sonraki `name` ile bir handle açar.
+pub fn shm_acquire(name: &str) -> Result<KHandle, KError> {
+    let size: usize = name.strip_prefix("new/").ok_or(KError::NotFound)?.parse().map_err(|_| KError::InvalidArgument)?;
+    if size == 0 {
+        return Err(KError::InvalidArgument);
+    }
+    let pages = size.div_ceil(PAGE_SIZE);
+    let order = pages.next_power_of_two().trailing_zeros() as usize;
+    if order > MAX_ORDER {
+        return Err(KError::InvalidArgument);
+    }
+    let size = pages * PAGE_SIZE;
+    let me = ktask::current_task_id();
+    ktask::quota_charge(ktask::QUOTA_MEMORY, size as u64)?;
+    let paddr = match frame_allocator().allocate_frames(order) {
+        Ok(paddr) => paddr,
+        Err(err) => {
+            ktask::quota_uncharge(ktask::QUOTA_MEMORY, size as u64);
+            return Err(err);
+        }
+    };
+    unsafe { core::ptr::write_bytes(phys_to_virt(paddr), 0, PAGE_SIZE << order) };
+
+    let obj = ShmObject { paddr, order, size, creator: me, handles: 0, mappings: 0 };
+    let mut table = SHM.lock();
+    let result = match table.objects.iter().position(|o| o.is_none()) {
+        Some(object) => {
+            table.objects[object] = Some(obj);
+            let handle = table.handle_open(me, object);
+            if handle.is_err() {
+                table.objects[object] = None;
+            }
+            handle
+        }
+        None => Err(KError::OutOfMemory),
+    };
+    drop(table);
+    if result.is_err() {
+        free_object(obj);
+    }
+    result
+}
+
+/// `resource_release` yolu: çağıranın handle'ını kapatır.
+pub fn shm_release(handle_value: u64) -> Result<(), KError> {
+    let freed = {
+        let mut table = SHM.lock();
+        let index = table.handle_index(ktask::current_task_id(), handle_value)?;
+        table.handle_close(index)
+    };
+    if let Some(obj) = freed {
+        free_object(obj);
+    }
+    Ok(())
+}
+
+/// `resource_control` yolu: paylaşımlı bellek handle'ı üzerinde bir SHM_CONTROL_* isteği yürütür.
+pub fn shm_control(handle_value: u64, request: u64, arg: u64) -> Result<u64, KError> {
+    let table = SHM.lock();
+    let index = table.handle_index(ktask::current_task_id(), handle_value)?;
+    let obj = table.objects[table.handles[index].object].ok_or(KError::InternalError)?;
+    match request {
+        SHM_CONTROL_SIZE => Ok(obj.size as u64),
+        _ => Err(KError::NotSupported),
+    }
+}
+
+/// `handle_value` çağırana ait geçerli bir paylaşımlı bellek handle'ı mı.
+pub fn check_shm_handle(handle_value: u64) -> Result<(), KError> {
+    SHM.lock().handle_index(ktask::current_task_id(), handle_value).map(|_| ())
+}
+
+/// Çağıranın handle'ının nesnesine `task` adına yeni bir handle açar (task_spawn devri).
+pub fn share_shm_handle(handle_value: u64, task: KTaskId) -> Result<KHandle, KError> {
+    let mut table = SHM.lock();
+    let index = table.handle_index(ktask::current_task_id(), handle_value)?;
+    let object = table.handles[index].object;
+    table.handle_open(task, object)
+}
+
+/// Toplanan `task` görevinin açık paylaşımlı bellek handle'larını kapatır.
+pub fn release_task_shm(task: KTaskId) {
+    for index in 0..MAX_SHM_HANDLES {
+        let freed = {
+            let mut table = SHM.lock();
+            if table.handles[index].owner != Some(task) {
+                continue;
+            }
+            table.handle_close(index)
+        };
+        if let Some(obj) = freed {
+            free_object(obj);
+        }
+    }
+}
+
+/// Çağıranın `handle_value` handle'ının nesnesinde [`offset`, `offset + len`) aralığı için bir
+/// eşleme referansı alır ve nesnenin tablo indeksini döner.
+pub(super) fn shm_map_ref(handle_value: u64, offset: u64, len: usize) -> Result<usize, KError> {
+    let mut table = SHM.lock();
+    let index = table.handle_index(ktask::current_task_id(), handle_value)?;
+    let object = table.handles[index].object;
+    let obj = table.objects[object].as_mut().ok_or(KError::InternalError)?;
+    match offset.checked_add(len as u64) {
+        Some(end) if end <= obj.size as u64 => {}
+        _ => return Err(KError::InvalidArgument),
+    }
+    obj.mappings += 1;
+    Ok(object)
+}
+
+/// Nesneye bir eşleme referansı daha ekler (VMA bölünmesi).
+pub(super) fn shm_get(object: usize) {
+    if let Some(obj) = SHM.lock().objects[object].as_mut() {
+        obj.mappings += 1;
+    }
+}
+
+/// Bir eşleme referansını bırakır; nesne artık kullanılmıyorsa serbest bırakılır.
+pub(super) fn shm_put(object: usize) {
+    let freed = {
+        let mut table = SHM.lock();
+        if let Some(obj) = table.objects[object].as_mut() {
+            obj.mappings -= 1;
+        }
+        table.take_if_unused(object)
+    };
+    if let Some(obj) = freed {
+        free_object(obj);
+    }
+}
+
+/// Nesnenin `offset` baytındaki sayfanın fiziksel adresi.
+pub(super) fn shm_frame(object: usize, offset: u64) -> Result<u64, KError> {
+    let table = SHM.lock();
+    let obj = table.objects[object].ok_or(KError::InternalError)?;
+    if offset >= obj.size as u64 {
+        return Err(KError::InvalidArgument);
+    }
+    Ok(obj.paddr + (offset & !(PAGE_SIZE as u64 - 1)))
+}
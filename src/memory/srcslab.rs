@@ -0,0 +1,421 @@
+// Karnal64 kmemory: çekirdek yığını (slab ayırıcı).
+// Nesneler, çerçeve ayırıcıdan alınan tek sayfalık slab'lara bölünür. Her slab'ın başında bir
+// başlık (SlabHeader) bulunur: slab'ın ait olduğu önbellek, boş nesne listesi ve kullanımdaki
+// nesne sayısı. Bir nesnenin önbelleği adresinin sayfa tabanından bulunduğu için bırakırken boyut
+// gerekmez. Sayfanın başı başlık olduğundan slab nesneleri hiçbir zaman sayfa hizalı değildir;
+// sayfa hizalı adresler doğrudan çerçeve ayırıcıdan alınmış büyük tahsislerdir.
+// - Boyut sınıfları: 16..=KMALLOC_MAX_SIZE baytlık 2'nin kuvveti önbellekler (`kmalloc`/`kfree`);
+//   daha büyük veya sayfa hizası isteyen tahsisler 2^order sayfa olarak buddy'den alınır.
+// - Türlü önbellekler: `ObjectCache<T>`; alt sistemler sık ayrılan yapıları için kendi
+//   önbelleklerini tanımlar (IPC kanalları ve mesajları, bkz. srcipc.rs).
+// - CPU başına magazin: her önbellek CPU başına MAGAZINE_SIZE nesnelik bir yığın tutar. Hızlı yol
+//   yalnızca CPU'nun kendi magazin kilidini alır; magazin boşalınca/dolunca yarısı önbelleğin
+//   paylaşılan slab'larıyla takas edilir. Kilit sırası: magazin → önbellek → çerçeve ayırıcı.
+// - `feature = "alloc"` ile `#[global_allocator]` olarak kaydedilir (Box, Vec, BTreeMap...).
+
+use core::alloc::Layout;
+use core::marker::PhantomData;
+use core::mem::size_of;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use super::super::ksync::Spinlock;
+use super::super::ktask::{current_cpu, MAX_CPUS};
+use super::super::KError;
+use super::frame::{frame_allocator, phys_to_virt, MAX_ORDER, PHYS_MAP_OFFSET};
+use super::PAGE_SIZE;
+
+/// En küçük boyut sınıfı.
+pub const KMALLOC_MIN_SIZE: usize = 16;
+/// En büyük boyut sınıfı; daha büyük tahsisler doğrudan sayfa olarak alınır.
+pub const KMALLOC_MAX_SIZE: usize = 1024;
+/// Boyut sınıfı sayısı (16, 32, ..., 1024).
+pub const KMALLOC_CLASSES: usize = 7;
+/// Boyut sınıfı nesnelerinin garanti edilen en büyük hizası; daha büyük hiza sayfa yolundan gider.
+pub const SLAB_MAX_ALIGN: usize = 64;
+/// CPU başına magazin kapasitesi (nesne).
+pub const MAGAZINE_SIZE: usize = 16;
+/// Önbellek başına elde tutulan en fazla boş slab; fazlası çerçeve ayırıcıya geri verilir.
+const MAX_EMPTY_SLABS: usize = 2;
+
+/// Slab sayfasının başındaki başlık.
+#[repr(C)]
+struct SlabHeader {
+    cache: *const SlabCache,
+    /// İlk boş nesnenin adresi; her boş nesnenin ilk kelimesi bir sonrakini gösterir. 0 = dolu.
+    free: usize,
+    in_use: usize,
+    /// Önbelleğin boş nesnesi olan slab'lar listesindeki komşular (0 = yok).
+    next: usize,
+    prev: usize,
+}
+
+fn header(slab: usize) -> *mut SlabHeader {
+    slab as *mut SlabHeader
+}
+
+/// Önbellek istatistikleri. Kullanımdaki nesne sayısı `allocs - frees`'tir.
+#[derive(Debug, Copy, Clone)]
+pub struct SlabStats {
+    pub name: &'static str,
+    pub object_size: usize,
+    pub objects_per_slab: usize,
+    pub slabs: u64,
+    pub allocs: u64,
+    pub frees: u64,
+    /// Magazinden karşılanan tahsisler (paylaşılan kilit alınmadan).
+    pub magazine_hits: u64,
+    pub failures: u64,
+}
+
+/// `heap_stats` sonucu.
+#[derive(Debug, Copy, Clone)]
+pub struct HeapStats {
+    pub classes: [SlabStats; KMALLOC_CLASSES],
+    /// Sayfa yolundan yapılan tahsisler ve bunlara ayrılmış sayfa sayısı.
+    pub large_allocs: u64,
+    pub large_frees: u64,
+    pub large_pages: u64,
+}
+
+struct Magazine {
+    objs: [usize; MAGAZINE_SIZE],
+    len: usize,
+}
+
+struct CacheState {
+    /// Boş nesnesi olan slab'lar (tamamen boşlar dahil).
+    partial: usize,
+    empty_slabs: usize,
+    slabs: u64,
+}
+
+const EMPTY_MAGAZINE: Spinlock<Magazine> =
+    Spinlock::named(Magazine { objs: [0; MAGAZINE_SIZE], len: 0 }, "kmemory::slab magazine");
+
+/// Sabit boyutlu nesne önbelleği. Slab başlıkları önbelleğe pointer tuttuğundan önbellekler
+/// `static` olmalıdır.
+pub struct SlabCache {
+    name: &'static str,
+    size: usize,
+    /// İlk nesnenin sayfa içindeki konumu (başlıktan sonra, hizalı).
+    first: usize,
+    per_slab: usize,
+    state: Spinlock<CacheState>,
+    magazines: [Spinlock<Magazine>; MAX_CPUS],
+    allocs: AtomicU64,
+    frees: AtomicU64,
+    hits: AtomicU64,
+    failures: AtomicU64,
+}
+
+impl SlabCache {
+    /// `size` baytlık, `align` hizalı nesneler için önbellek. Nesne başlıkla birlikte bir sayfaya
+    /// sığmalıdır (derleme zamanında denetlenir).
+    pub const fn new(name: &'static str, size: usize, align: usize) -> Self {
+        assert!(align.is_power_of_two(), "slab: hiza 2'nin kuvveti olmalı");
+        let size = if size < size_of::<usize>() { size_of::<usize>() } else { size };
+        let size = size.next_multiple_of(align);
+        let first = size_of::<SlabHeader>().next_multiple_of(align);
+        assert!(first + size <= PAGE_SIZE, "slab: nesne bir sayfaya sığmıyor");
+        SlabCache {
+            name,
+            size,
+            first,
+            per_slab: (PAGE_SIZE - first) / size,
+            state: Spinlock::named(CacheState { partial: 0, empty_slabs: 0, slabs: 0 }, "kmemory::SlabCache"),
+            magazines: [EMPTY_MAGAZINE; MAX_CPUS],
+            allocs: AtomicU64::new(0),
+            frees: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+        }
+    }
+
+    /// Bir nesne ayırır; içeriği tanımsızdır.
+    pub fn alloc(&'static self) -> Result<NonNull<u8>, KError> {
+        let mut mag = self.magazines[current_cpu()].lock_irqsave();
+        if mag.len > 0 {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else if let Err(e) = self.refill(&mut mag) {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+            return Err(e);
+        }
+        mag.len -= 1;
+        self.allocs.fetch_add(1, Ordering::Relaxed);
+        NonNull::new(mag.objs[mag.len] as *mut u8).ok_or(KError::InternalError)
+    }
+
+    /// Nesneyi önbelleğe geri verir.
+    ///
+    /// # Safety
+    /// `obj` bu önbellekten `alloc` ile alınmış olmalı ve artık kullanılmamalıdır.
+    pub unsafe fn free(&'static self, obj: NonNull<u8>) {
+        let mut mag = self.magazines[current_cpu()].lock_irqsave();
+        if mag.len == MAGAZINE_SIZE {
+            self.flush(&mut mag, MAGAZINE_SIZE / 2);
+        }
+        let len = mag.len;
+        mag.objs[len] = obj.as_ptr() as usize;
+        mag.len += 1;
+        self.frees.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> SlabStats {
+        SlabStats {
+            name: self.name,
+            object_size: self.size,
+            objects_per_slab: self.per_slab,
+            slabs: self.state.lock_irqsave().slabs,
+            allocs: self.allocs.load(Ordering::Relaxed),
+            frees: self.frees.load(Ordering::Relaxed),
+            magazine_hits: self.hits.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Boş magazini paylaşılan slab'lardan yarıya kadar doldurur; en az bir nesne alınamazsa hata.
+    fn refill(&'static self, mag: &mut Magazine) -> Result<(), KError> {
+        let mut state = self.state.lock_irqsave();
+        while mag.len < MAGAZINE_SIZE / 2 {
+            match self.take(&mut state) {
+                Ok(obj) => {
+                    mag.objs[mag.len] = obj;
+                    mag.len += 1;
+                }
+                Err(e) if mag.len == 0 => return Err(e),
+                Err(_) => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Magazinin en eski `count` nesnesini slab'larına geri verir.
+    fn flush(&'static self, mag: &mut Magazine, count: usize) {
+        let mut state = self.state.lock_irqsave();
+        for i in 0..count {
+            self.give(&mut state, mag.objs[i]);
+        }
+        mag.objs.copy_within(count..mag.len, 0);
+        mag.len -= count;
+    }
+
+    fn take(&'static self, state: &mut CacheState) -> Result<usize, KError> {
+        if state.partial == 0 {
+            self.grow(state)?;
+        }
+        let slab = state.partial;
+        unsafe {
+            let hdr = &mut *header(slab);
+            let obj = hdr.free;
+            hdr.free = ptr::read_unaligned(obj as *const usize);
+            if hdr.in_use == 0 {
+                state.empty_slabs -= 1;
+            }
+            hdr.in_use += 1;
+            if hdr.free == 0 {
+                Self::unlink(state, slab);
+            }
+            Ok(obj)
+        }
+    }
+
+    fn give(&'static self, state: &mut CacheState, obj: usize) {
+        let slab = obj & !(PAGE_SIZE - 1);
+        unsafe {
+            let hdr = &mut *header(slab);
+            if !ptr::eq(hdr.cache, self) || (obj - slab) < self.first || (obj - slab - self.first) % self.size != 0 {
+                println!("Karnal64: slab: {} önbelleğine ait olmayan nesne bırakıldı: {:#x}", self.name, obj);
+                return;
+            }
+            let was_full = hdr.free == 0;
+            ptr::write_unaligned(obj as *mut usize, hdr.free);
+            hdr.free = obj;
+            hdr.in_use -= 1;
+            if was_full {
+                Self::push(state, slab);
+            }
+            if hdr.in_use == 0 {
+                if state.empty_slabs < MAX_EMPTY_SLABS {
+                    state.empty_slabs += 1;
+                } else {
+                    Self::unlink(state, slab);
+                    state.slabs -= 1;
+                    frame_allocator().free_frame(slab as u64 - PHYS_MAP_OFFSET);
+                }
+            }
+        }
+    }
+
+    /// Yeni bir slab sayfası alır, nesnelerini boş listeye dizer ve listenin başına koyar.
+    fn grow(&'static self, state: &mut CacheState) -> Result<(), KError> {
+        let slab = phys_to_virt(frame_allocator().allocate_frame()?) as usize;
+        let mut free = 0;
+        for i in (0..self.per_slab).rev() {
+            let obj = slab + self.first + i * self.size;
+            unsafe { ptr::write_unaligned(obj as *mut usize, free) };
+            free = obj;
+        }
+        unsafe { *header(slab) = SlabHeader { cache: self, free, in_use: 0, next: 0, prev: 0 } };
+        Self::push(state, slab);
+        state.empty_slabs += 1;
+        state.slabs += 1;
+        Ok(())
+    }
+
+    fn push(state: &mut CacheState, slab: usize) {
+        unsafe {
+            (*header(slab)).prev = 0;
+            (*header(slab)).next = state.partial;
+            if state.partial != 0 {
+                (*header(state.partial)).prev = slab;
+            }
+        }
+        state.partial = slab;
+    }
+
+    fn unlink(state: &mut CacheState, slab: usize) {
+        unsafe {
+            let (next, prev) = ((*header(slab)).next, (*header(slab)).prev);
+            if prev != 0 {
+                (*header(prev)).next = next;
+            } else {
+                state.partial = next;
+            }
+            if next != 0 {
+                (*header(next)).prev = prev;
+            }
+        }
+    }
+}
+
+/// `T` türündeki nesneler için ayrı bir slab önbelleği.
+pub struct ObjectCache<T> {
+    cache: SlabCache,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> ObjectCache<T> {
+    pub const fn new(name: &'static str) -> Self {
+        ObjectCache { cache: SlabCache::new(name, size_of::<T>(), core::mem::align_of::<T>()), _marker: PhantomData }
+    }
+
+    /// `value`'yu önbellekten alınan bir nesneye taşır.
+    pub fn alloc(&'static self, value: T) -> Result<NonNull<T>, KError> {
+        let obj = self.cache.alloc()?.cast::<T>();
+        unsafe { obj.as_ptr().write(value) };
+        Ok(obj)
+    }
+
+    /// Nesneyi düşürür (drop) ve önbelleğe geri verir.
+    ///
+    /// # Safety
+    /// `obj` bu önbellekten `alloc` ile alınmış olmalı ve başka başvurusu kalmamalıdır.
+    pub unsafe fn free(&'static self, obj: NonNull<T>) {
+        ptr::drop_in_place(obj.as_ptr());
+        self.cache.free(obj.cast());
+    }
+
+    /// Nesneyi `Box` olarak döner. Box düşürüldüğünde global ayırıcı nesneyi slab başlığından
+    /// bulduğu bu önbelleğe geri verir.
+    #[cfg(feature = "alloc")]
+    pub fn alloc_box(&'static self, value: T) -> Result<alloc::boxed::Box<T>, KError> {
+        Ok(unsafe { alloc::boxed::Box::from_raw(self.alloc(value)?.as_ptr()) })
+    }
+
+    pub fn stats(&self) -> SlabStats {
+        self.cache.stats()
+    }
+}
+
+static KMALLOC_CACHES: [SlabCache; KMALLOC_CLASSES] = [
+    SlabCache::new("kmalloc-16", 16, 16),
+    SlabCache::new("kmalloc-32", 32, 32),
+    SlabCache::new("kmalloc-64", 64, 64),
+    SlabCache::new("kmalloc-128", 128, SLAB_MAX_ALIGN),
+    SlabCache::new("kmalloc-256", 256, SLAB_MAX_ALIGN),
+    SlabCache::new("kmalloc-512", 512, SLAB_MAX_ALIGN),
+    SlabCache::new("kmalloc-1024", 1024, SLAB_MAX_ALIGN),
+];
+
+static LARGE_ALLOCS: AtomicU64 = AtomicU64::new(0);
+static LARGE_FREES: AtomicU64 = AtomicU64::new(0);
+static LARGE_PAGES: AtomicU64 = AtomicU64::new(0);
+
+/// `size` baytı karşılayan en küçük buddy order'ı.
+fn large_order(size: usize) -> Result<usize, KError> {
+    let pages = size.div_ceil(PAGE_SIZE).max(1).next_power_of_two();
+    let order = pages.trailing_zeros() as usize;
+    if order > MAX_ORDER {
+        return Err(KError::OutOfMemory);
+    }
+    Ok(order)
+}
+
+/// Çekirdek yığınından `layout`'a uygun bellek ayırır. KMALLOC_MAX_SIZE'a kadar ve en fazla
+/// SLAB_MAX_ALIGN hizalı istekler boyut sınıflarından, diğerleri 2^order sayfa olarak karşılanır.
+/// Sayfadan büyük hiza desteklenmez (`KError::InvalidArgument`).
+pub fn kmalloc(layout: Layout) -> Result<NonNull<u8>, KError> {
+    if layout.size() <= KMALLOC_MAX_SIZE && layout.align() <= SLAB_MAX_ALIGN {
+        let size = layout.size().max(layout.align()).max(KMALLOC_MIN_SIZE).next_power_of_two();
+        let class = (size.trailing_zeros() - KMALLOC_MIN_SIZE.trailing_zeros()) as usize;
+        return KMALLOC_CACHES[class].alloc();
+    }
+    if layout.align() > PAGE_SIZE {
+        return Err(KError::InvalidArgument);
+    }
+    let order = large_order(layout.size())?;
+    let paddr = frame_allocator().allocate_frames(order)?;
+    LARGE_ALLOCS.fetch_add(1, Ordering::Relaxed);
+    LARGE_PAGES.fetch_add(1 << order, Ordering::Relaxed);
+    NonNull::new(phys_to_virt(paddr)).ok_or(KError::InternalError)
+}
+
+/// `kmalloc` veya bir `ObjectCache` ile alınmış belleği bırakır. Slab nesnelerinin önbelleği
+/// slab başlığından bulunur; `layout` yalnızca sayfa yolundaki tahsislerin boyutu için kullanılır.
+///
+/// # Safety
+/// `ptr` aynı `layout` ile ayrılmış olmalı ve artık kullanılmamalıdır.
+pub unsafe fn kfree(ptr: NonNull<u8>, layout: Layout) {
+    let addr = ptr.as_ptr() as usize;
+    if addr % PAGE_SIZE != 0 {
+        let cache = &*(*header(addr & !(PAGE_SIZE - 1))).cache;
+        cache.free(ptr);
+        return;
+    }
+    let Ok(order) = large_order(layout.size()) else { return };
+    LARGE_FREES.fetch_add(1, Ordering::Relaxed);
+    LARGE_PAGES.fetch_sub(1 << order, Ordering::Relaxed);
+    frame_allocator().free_frames(addr as u64 - PHYS_MAP_OFFSET, order);
+}
+
+pub fn heap_stats() -> HeapStats {
+    HeapStats {
+        classes: core::array::from_fn(|i| KMALLOC_CACHES[i].stats()),
+        large_allocs: LARGE_ALLOCS.load(Ordering::Relaxed),
+        large_frees: LARGE_FREES.load(Ordering::Relaxed),
+        large_pages: LARGE_PAGES.load(Ordering::Relaxed),
+    }
+}
+
+/// `alloc` crate'inin (Box, Vec, BTreeMap) kullandığı çekirdek ayırıcısı.
+#[cfg(feature = "alloc")]
+struct KernelHeap;
+
+#[cfg(feature = "alloc")]
+unsafe impl core::alloc::GlobalAlloc for KernelHeap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        kmalloc(layout).map_or(ptr::null_mut(), |p| p.as_ptr())
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if let Some(ptr) = NonNull::new(ptr) {
+            kfree(ptr, layout)
+        }
+    }
+}
+
+// Barındırıcıdaki birim testleri std'nin ayırıcısını kullanır.
+#[cfg(all(feature = "alloc", not(test)))]
+#[global_allocator]
+static KERNEL_HEAP: KernelHeap = KernelHeap;
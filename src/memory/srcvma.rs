@@ -0,0 +1,467 @@
+// Karnal64 kmemory: sanal bellek alanları (VMA) ve VMA ağacı.
+// Bir adres alanının kullanıcı yarısı, başlangıç adresine göre sıralı ve çakışmayan VMA'lardan
+// oluşur. Her VMA sayfa hizalı bir [`start`, `end`) aralığını, korumasını (PROT_*), haritalama
+// bayraklarını (MAP_*) ve sayfaların nereden geldiğini (`VmaBacking`) tutar.
+// VMA'lar başlangıç adresine göre bir AVL ağacında tutulur; düğümler slab önbelleğinden
+// (VMA_CACHE) ayrılır. VMA'lar çakışmadığından bitiş adresleri de aynı sıradadır; "adresi içeren"
+// ve "adresten sonraki ilk" VMA aramaları tek bir iniş ile yapılır. Ağacın kendi kilidi yoktur,
+// adres alanının kilidi altında kullanılır (bkz. srcvmspace.rs).
+
+use core::ptr::NonNull;
+
+use super::super::{KError, ResourceProvider};
+use super::slab::ObjectCache;
+
+// Koruma bayrakları (SYSCALL_MEMORY_ALLOC/PROTECT/MAP, Sahne64 MemoryProtection ile eşleşir)
+/// Erişim yok: sayfa kullanıcıdan erişilemez, içeriği korunur.
+pub const PROT_NONE: u32 = 0;
+pub const PROT_READ: u32 = 1 << 0;
+pub const PROT_WRITE: u32 = 1 << 1;
+pub const PROT_EXEC: u32 = 1 << 2;
+pub(super) const PROT_MASK: u32 = PROT_READ | PROT_WRITE | PROT_EXEC;
+
+// Haritalama bayrakları (SYSCALL_MEMORY_MAP, Sahne64 MemoryMapFlags ile eşleşir)
+/// Eşleme paylaşımlı bir nesnenin çerçevelerini gösterir; yazılanlar diğer eşlemelerde görünür.
+pub const MAP_SHARED: u32 = 1 << 0;
+/// Eşleme göreve özeldir (kaynak içeriği eşlenirken kopyalanır).
+pub const MAP_PRIVATE: u32 = 1 << 1;
+/// Adres bir ipucu değil, aynen kullanılır; aralık boş olmalıdır.
+pub const MAP_FIXED: u32 = 1 << 2;
+/// Handle yok; sayfalar sıfırla doldurulur.
+pub const MAP_ANONYMOUS: u32 = 1 << 3;
+pub(super) const MAP_MASK: u32 = MAP_SHARED | MAP_PRIVATE | MAP_FIXED | MAP_ANONYMOUS;
+
+/// VMA sayfalarının kaynağı.
+#[derive(Copy, Clone)]
+pub enum VmaBacking {
+    /// Sıfırla doldurulmuş, göreve özel sayfalar.
+    Anonymous,
+    /// Kaynak sağlayıcısının `offset`ten (VMA başlangıcına karşılık gelen ofset) itibaren
+    /// içeriğinin göreve özel kopyası.
+    Resource { provider: &'static dyn ResourceProvider, offset: u64 },
+    /// Paylaşımlı bellek nesnesinin (srcshm.rs tablo indeksi) `offset`ten itibaren çerçeveleri.
+    Shared { object: usize, offset: u64 },
+}
+
+impl VmaBacking {
+    /// VMA başlangıcı `delta` bayt ilerletildiğinde aynı sayfaları gösteren kaynak.
+    fn advanced(self, delta: usize) -> Self {
+        match self {
+            VmaBacking::Anonymous => VmaBacking::Anonymous,
+            VmaBacking::Resource { provider, offset } => VmaBacking::Resource { provider, offset: offset + delta as u64 },
+            VmaBacking::Shared { object, offset } => VmaBacking::Shared { object, offset: offset + delta as u64 },
+        }
+    }
+}
+
+/// Tek bir sanal bellek alanı.
+#[derive(Copy, Clone)]
+pub struct Vma {
+    pub start: usize,
+    pub end: usize,
+    pub prot: u32,
+    pub flags: u32,
+    pub backing: VmaBacking,
+}
+
+impl Vma {
+    pub fn contains(&self, addr: usize) -> bool {
+        self.start <= addr && addr < self.end
+    }
+
+    /// `addr` sayfasının kaynak içindeki ofseti (anonim VMA'lar için 0).
+    pub fn backing_offset(&self, addr: usize) -> u64 {
+        match self.backing.advanced(addr - self.start) {
+            VmaBacking::Anonymous => 0,
+            VmaBacking::Resource { offset, .. } | VmaBacking::Shared { offset, .. } => offset,
+        }
+    }
+
+    /// VMA'yı `addr` (sayfa hizalı, `start < addr < end`) noktasından ikiye böler.
+    pub(super) fn split_at(&self, addr: usize) -> (Vma, Vma) {
+        let left = Vma { end: addr, ..*self };
+        let right = Vma { start: addr, backing: self.backing.advanced(addr - self.start), ..*self };
+        (left, right)
+    }
+}
+
+type Link = Option<NonNull<VmaNode>>;
+
+struct VmaNode {
+    vma: Vma,
+    left: Link,
+    right: Link,
+    height: u8,
+}
+
+static VMA_CACHE: ObjectCache<VmaNode> = ObjectCache::new("vma");
+
+/// Başlangıç adresine göre sıralı, çakışmayan VMA'ların AVL ağacı.
+pub struct VmaTree {
+    root: Link,
+    count: usize,
+}
+
+// Düğümler yalnızca sahibi olan adres alanının kilidi altında erişilir. Kaynak sağlayıcıları
+// çekirdek ömrü boyunca kayıtlıdır ve her CPU'dan çağrılabilir.
+unsafe impl Send for VmaTree {}
+
+impl Default for VmaTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VmaTree {
+    pub const fn new() -> Self {
+        VmaTree { root: None, count: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Bitişi `addr`'den büyük olan ilk VMA (yani `addr`'yi içeren veya ondan sonraki ilk VMA).
+    pub fn first_ending_after(&self, addr: usize) -> Option<Vma> {
+        self.first_ending_after_node(addr).map(|n| unsafe { n.as_ref().vma })
+    }
+
+    /// `addr`'yi içeren VMA.
+    pub fn find(&self, addr: usize) -> Option<Vma> {
+        self.first_ending_after(addr).filter(|vma| vma.contains(addr))
+    }
+
+    /// `addr`'yi içeren VMA'nın yerinde değiştirilebilir hali. Başlangıç ve bitiş (ağaç anahtarı)
+    /// değiştirilmemelidir.
+    pub fn find_mut(&mut self, addr: usize) -> Option<&mut Vma> {
+        let mut node = self.first_ending_after_node(addr)?;
+        let vma = unsafe { &mut node.as_mut().vma };
+        if vma.contains(addr) {
+            Some(vma)
+        } else {
+            None
+        }
+    }
+
+    /// [`from`, `limit`) içinde `len` baytlık boş bir aralığın en düşük başlangıcı.
+    pub fn find_gap(&self, len: usize, from: usize, limit: usize) -> Option<usize> {
+        let mut cursor = from;
+        loop {
+            let end = cursor.checked_add(len)?;
+            if end > limit {
+                return None;
+            }
+            match self.first_ending_after(cursor) {
+                Some(vma) if vma.start < end => cursor = vma.end,
+                _ => return Some(cursor),
+            }
+        }
+    }
+
+    /// [`start`, `end`) aralığı hiçbir VMA ile kesişmiyor mu?
+    pub fn is_free(&self, start: usize, end: usize) -> bool {
+        !matches!(self.first_ending_after(start), Some(vma) if vma.start < end)
+    }
+
+    /// `vma`'yı ekler. Aralık mevcut bir VMA ile kesişiyorsa `KError::AlreadyExists`, düğüm
+    /// ayrılamazsa `KError::OutOfMemory` döner.
+    pub fn insert(&mut self, vma: Vma) -> Result<(), KError> {
+        if vma.start >= vma.end || !self.is_free(vma.start, vma.end) {
+            return Err(KError::AlreadyExists);
+        }
+        let node = VMA_CACHE.alloc(VmaNode { vma, left: None, right: None, height: 1 })?;
+        self.root = Some(unsafe { insert(self.root, node) });
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Başlangıcı `start` olan VMA'yı çıkarır.
+    pub fn remove(&mut self, start: usize) -> Option<Vma> {
+        let (root, removed) = unsafe { remove(self.root, start) };
+        self.root = root;
+        let node = removed?;
+        self.count -= 1;
+        let vma = unsafe { node.as_ref().vma };
+        unsafe { VMA_CACHE.free(node) };
+        Some(vma)
+    }
+
+    /// En düşük adresli VMA'yı çıkarır (adres alanı yıkımı için).
+    pub fn pop_first(&mut self) -> Option<Vma> {
+        let first = self.first_ending_after(0)?;
+        self.remove(first.start)
+    }
+
+    fn first_ending_after_node(&self, addr: usize) -> Link {
+        let mut best = None;
+        let mut cursor = self.root;
+        while let Some(node) = cursor {
+            let n = unsafe { node.as_ref() };
+            if n.vma.end > addr {
+                best = Some(node);
+                cursor = n.left;
+            } else {
+                cursor = n.right;
+            }
+        }
+        best
+    }
+}
+
+// --- AVL yardımcıları ---
+// Derinlik 1.44·log2(VMA sayısı) ile sınırlı olduğundan özyineleme çekirdek yığınında güvenlidir.
+
+fn height(link: Link) -> u8 {
+    link.map_or(0, |n| unsafe { n.as_ref().height })
+}
+
+unsafe fn update(mut node: NonNull<VmaNode>) {
+    let n = node.as_mut();
+    n.height = 1 + height(n.left).max(height(n.right));
+}
+
+unsafe fn rotate_right(mut node: NonNull<VmaNode>) -> NonNull<VmaNode> {
+    let mut left = node.as_ref().left.expect("AVL: sol çocuk yok");
+    node.as_mut().left = left.as_ref().right;
+    update(node);
+    left.as_mut().right = Some(node);
+    update(left);
+    left
+}
+
+unsafe fn rotate_left(mut node: NonNull<VmaNode>) -> NonNull<VmaNode> {
+    let mut right = node.as_ref().right.expect("AVL: sağ çocuk yok");
+    node.as_mut().right = right.as_ref().left;
+    update(node);
+    right.as_mut().left = Some(node);
+    update(right);
+    right
+}
+
+unsafe fn rebalance(mut node: NonNull<VmaNode>) -> NonNull<VmaNode> {
+    update(node);
+    let (left, right) = (node.as_ref().left, node.as_ref().right);
+    let balance = height(left) as i32 - height(right) as i32;
+    if balance > 1 {
+        let l = left.expect("AVL: sol çocuk yok");
+        if height(l.as_ref().left) < height(l.as_ref().right) {
+            node.as_mut().left = Some(rotate_left(l));
+        }
+        return rotate_right(node);
+    }
+    if balance < -1 {
+        let r = right.expect("AVL: sağ çocuk yok");
+        if height(r.as_ref().right) < height(r.as_ref().left) {
+            node.as_mut().right = Some(rotate_right(r));
+        }
+        return rotate_left(node);
+    }
+    node
+}
+
+unsafe fn insert(link: Link, new: NonNull<VmaNode>) -> NonNull<VmaNode> {
+    let mut node = match link {
+        Some(node) => node,
+        None => return new,
+    };
+    if new.as_ref().vma.start < node.as_ref().vma.start {
+        node.as_mut().left = Some(insert(node.as_ref().left, new));
+    } else {
+        node.as_mut().right = Some(insert(node.as_ref().right, new));
+    }
+    rebalance(node)
+}
+
+/// Alt ağacın en küçük düğümünü çıkarır: (yeni alt ağaç kökü, çıkarılan düğüm).
+unsafe fn remove_min(mut node: NonNull<VmaNode>) -> (Link, NonNull<VmaNode>) {
+    match node.as_ref().left {
+        None => (node.as_ref().right, node),
+        Some(left) => {
+            let (left, min) = remove_min(left);
+            node.as_mut().left = left;
+            (Some(rebalance(node)), min)
+        }
+    }
+}
+
+/// Başlangıcı `start` olan düğümü alt ağaçtan çıkarır: (yeni alt ağaç kökü, çıkarılan düğüm).
+unsafe fn remove(link: Link, start: usize) -> (Link, Link) {
+    let mut node = match link {
+        Some(node) => node,
+        None => return (None, None),
+    };
+    let key = node.as_ref().vma.start;
+    if start < key {
+        let (left, removed) = remove(node.as_ref().left, start);
+        node.as_mut().left = left;
+        return (Some(rebalance(node)), removed);
+    }
+    if start > key {
+        let (right, removed) = remove(node.as_ref().right, start);
+        node.as_mut().right = right;
+        return (Some(rebalance(node)), removed);
+    }
+    let replacement = match (node.as_ref().left, node.as_ref().right) {
+        (None, right) => right,
+        (left, None) => left,
+        (left, Some(right)) => {
+            let (right, mut min) = remove_min(right);
+            min.as_mut().left = left;
+            min.as_mut().right = right;
+            Some(rebalance(min))
+        }
+    };
+    (replacement, Some(node))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::frame::{add_physical_memory_region, init_frame_allocator, MAX_ORDER};
+    use super::super::PAGE_SIZE;
+    use super::*;
+    use std::sync::{Mutex, MutexGuard, Once};
+
+    // Dummy düşük seviyeli fonksiyonlar (Test amaçlı): tek CPU, kesmeler kapalı.
+    #[no_mangle]
+    extern "C" fn low_level_interrupt_save() -> u64 {
+        0
+    }
+
+    #[no_mangle]
+    extern "C" fn low_level_interrupt_restore(_flags: u64) {}
+
+    #[no_mangle]
+    extern "C" fn low_level_interrupts_enabled() -> i32 {
+        0
+    }
+
+    #[no_mangle]
+    extern "C" fn low_level_cpu_id() -> u32 {
+        0
+    }
+
+    #[no_mangle]
+    extern "C" fn low_level_monotonic_time_ns() -> u64 {
+        0
+    }
+
+    #[no_mangle]
+    extern "C" fn low_level_stack_trace(_frames: *mut u64, _max_frames: usize) -> usize {
+        0
+    }
+
+    // Dummy çekirdek imajı sınırları (bağlayıcı betiği yerine); tek sayfalık boş bir aralık.
+    core::arch::global_asm!(
+        ".pushsection .data",
+        ".balign 4096",
+        ".globl __kernel_start",
+        "__kernel_start:",
+        ".zero 4096",
+        ".globl __kernel_end",
+        "__kernel_end:",
+        ".popsection",
+    );
+
+    /// VMA düğümleri çekirdek slab önbelleğinden ayrılır. Testler tek CPU'lu kabul edildiğinden
+    /// (low_level_cpu_id) sırayla çalıştırılır; fiziksel bellek ilk testte barındırıcıdan alınır.
+    fn kernel_heap() -> MutexGuard<'static, ()> {
+        static SERIAL: Mutex<()> = Mutex::new(());
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            let size = 256 * PAGE_SIZE;
+            let layout = std::alloc::Layout::from_size_align(size, PAGE_SIZE << MAX_ORDER).unwrap();
+            let base = unsafe { std::alloc::alloc_zeroed(layout) } as u64;
+            add_physical_memory_region(base, size as u64).unwrap();
+            init_frame_allocator().unwrap();
+        });
+        SERIAL.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn anon(start: usize, end: usize) -> Vma {
+        Vma { start, end, prot: PROT_READ | PROT_WRITE, flags: MAP_PRIVATE | MAP_ANONYMOUS, backing: VmaBacking::Anonymous }
+    }
+
+    #[test]
+    fn test_find_and_gaps() {
+        let _heap = kernel_heap();
+        let mut tree = VmaTree::new();
+        tree.insert(anon(0x20000, 0x30000)).unwrap();
+        tree.insert(anon(0x10000, 0x12000)).unwrap();
+        tree.insert(anon(0x40000, 0x41000)).unwrap();
+        assert_eq!(tree.len(), 3);
+
+        assert_eq!(tree.find(0x10000).map(|v| v.start), Some(0x10000));
+        assert_eq!(tree.find(0x2FFFF).map(|v| v.start), Some(0x20000));
+        assert!(tree.find(0x12000).is_none());
+        assert_eq!(tree.first_ending_after(0x12000).map(|v| v.start), Some(0x20000));
+        assert!(tree.first_ending_after(0x41000).is_none());
+
+        // Çakışan ve boş aralıklar eklenmez.
+        assert_eq!(tree.insert(anon(0x11000, 0x13000)).err(), Some(KError::AlreadyExists));
+        assert_eq!(tree.insert(anon(0x50000, 0x50000)).err(), Some(KError::AlreadyExists));
+        assert!(tree.is_free(0x12000, 0x20000));
+        assert!(!tree.is_free(0x12000, 0x20001));
+
+        assert_eq!(tree.find_gap(0x8000, 0x10000, 0x50000), Some(0x12000));
+        assert_eq!(tree.find_gap(0xF000, 0x10000, 0x50000), Some(0x30000));
+        assert_eq!(tree.find_gap(0x20000, 0x10000, 0x50000), None);
+
+        tree.find_mut(0x40000).unwrap().prot = PROT_NONE;
+        assert_eq!(tree.find(0x40800).map(|v| v.prot), Some(PROT_NONE));
+
+        while tree.pop_first().is_some() {}
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_remove_keeps_tree_balanced() {
+        let _heap = kernel_heap();
+        let mut tree = VmaTree::new();
+        let page = PAGE_SIZE;
+        // 64 VMA karışık sırada eklenir; AVL yüksekliği 1.44·log2(64) ile sınırlıdır.
+        for i in (0..64).map(|i| (i * 37) % 64) {
+            tree.insert(anon(0x100000 + 2 * i * page, 0x100000 + (2 * i + 1) * page)).unwrap();
+        }
+        assert_eq!(tree.len(), 64);
+        assert!(height(tree.root) <= 8);
+
+        for i in (0..64).step_by(2) {
+            assert!(tree.remove(0x100000 + 2 * i * page).is_some());
+        }
+        assert!(tree.remove(0x100000).is_none());
+        assert_eq!(tree.len(), 32);
+        assert!(height(tree.root) <= 7);
+
+        let mut previous = 0;
+        let mut count = 0;
+        while let Some(vma) = tree.pop_first() {
+            assert!(vma.start > previous);
+            assert_eq!((vma.start - 0x100000) / page % 4, 2);
+            previous = vma.start;
+            count += 1;
+        }
+        assert_eq!(count, 32);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_split_advances_backing_offset() {
+        let vma = Vma {
+            start: 0x10000,
+            end: 0x14000,
+            prot: PROT_READ,
+            flags: MAP_SHARED,
+            backing: VmaBacking::Shared { object: 3, offset: 0x1000 },
+        };
+        let (left, right) = vma.split_at(0x12000);
+        assert_eq!((left.start, left.end), (0x10000, 0x12000));
+        assert_eq!((right.start, right.end), (0x12000, 0x14000));
+        assert_eq!(left.backing_offset(0x11000), 0x2000);
+        assert_eq!(right.backing_offset(0x12000), 0x3000);
+        assert_eq!(anon(0x10000, 0x11000).backing_offset(0x10000), 0);
+    }
+}
@@ -0,0 +1,514 @@
+// Karnal64 kmemory: görev adres alanları.
+// Her kullanıcı görevinin adres alanı bir sayfa tablosu kökü (ktask'taki `address_space`) ve
+// kullanıcı yarısını tanımlayan bir VMA ağacıdır (bkz. srcvma.rs). Adres alanı görevler arasında
+// değil, bir görevin iş parçacıkları arasında paylaşılır.
+// - Ayırma (SYSCALL_MEMORY_ALLOC/MAP): MAP_FIXED değilse boş aralık VMA ağacında aranır; önce
+//   `user_mmap_base()`'den yukarısı, sığmazsa tüm kullanıcı alanı taranır.
+// - Kullanıcı alanının sonu porta özeldir (`ArchMmu::user_space_end`; Sv39'da 2^38, amd64'te 2^47).
+// - Kaldırma ve koruma (SYSCALL_MEMORY_FREE/UNMAP/PROTECT): aralığın sınırlarına düşen VMA'lar
+//   bölünür; yalnızca aralık içindeki parçalar etkilenir. Göreve özel çerçeveler, TLB'ler tüm
+//   CPU'larda temizlendikten sonra (`ktask::tlb_shootdown`) çerçeve ayırıcıya geri verilir.
+// - Sayfalar talep üzerine eşlenir: VMA oluşturulurken çerçeve ayrılmaz, sayfa ilk erişimdeki
+//   sayfa hatasında (`handle_page_fault`) doldurulur. Anonim sayfalar sıfırlanmış çerçevelerle,
+//   kaynak sayfaları sağlayıcıdan okunan kopyalarla, paylaşımlı sayfalar nesnenin kendi
+//   çerçeveleriyle. Bu yüzden büyük yığın ve heap ayırmaları yalnızca dokunulan kadar bellek tutar.
+// - Göreve özel (anonim ve kaynak) eşlemelerin tüm boyutu eşleme anında görevin bellek kotasından
+//   düşülür (kaydedilmiş bellek) ve kaldırılınca geri verilir; kota aşılırsa eşleme
+//   `KError::OutOfMemory` ile reddedilir. Talep üzerine olan yalnızca çerçeve ayırmadır.
+// Sayfa tablosu biçimi porta özeldir: portlar `ArchMmu`'yu implemente edip `register_arch_mmu` ile
+// kaydeder; kayıtlı port yoksa adres alanı işlemleri `KError::NotSupported` döner.
+// Her adres alanının uyuyan bir mutex'i vardır (sağlayıcıdan okuma bloklayabilir). Kilit sırası:
+// adres alanı → paylaşımlı nesne tablosu → çerçeve ayırıcı.
+
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use super::super::ksync::{Mutex, Spinlock};
+use super::super::ktask::{self, KERNEL_ADDRESS_SPACE, MAX_TASKS};
+use super::super::{KError, ResourceProvider};
+use super::frame::{frame_allocator, phys_to_virt};
+use super::shm::{shm_frame, shm_get, shm_map_ref, shm_put};
+use super::vma::{Vma, VmaBacking, VmaTree, MAP_ANONYMOUS, MAP_FIXED, MAP_MASK, MAP_PRIVATE, MAP_SHARED, PROT_MASK};
+use super::{PAGE_SIZE, PROT_WRITE, USER_SPACE_START};
+
+/// Aynı anda var olabilecek en fazla kullanıcı adres alanı (her kullanıcı görevinin bir tane).
+pub const MAX_ADDRESS_SPACES: usize = MAX_TASKS;
+
+
+/// Eşleme kaldırırken TLB temizliğinden önce biriktirilen en fazla çerçeve.
+const UNMAP_BATCH: usize = 32;
+
+/// Bir portun adres alanı yöneticisine sağladığı sayfa tablosu işlemleri.
+/// TLB temizliği bu işlemlerin parçası değildir; çağıran, eşleme kaldırdıktan veya izin
+/// daralttıktan sonra `ktask::tlb_shootdown` çağırır.
+pub trait ArchMmu: Sync {
+    /// Kullanıcı yarısı boş, çekirdek eşlemelerini içeren yeni bir sayfa tablosu kurar ve kökünü
+    /// (`ktask::ArchTask::activate_address_space`'e verilecek değer) döner.
+    unsafe fn create_page_tables(&self) -> Result<u64, KError>;
+
+    /// `root` tablosunda `vaddr` sayfasını `paddr` çerçevesine `prot` (PROT_*) korumasıyla eşler.
+    /// PROT_NONE sayfa yalnızca çekirdekten erişilebilir eşlenir: içerik korunur, kullanıcı
+    /// erişimi hata verir. Sayfa zaten eşliyse `KError::AlreadyExists` döner.
+    unsafe fn map_page(&self, root: u64, vaddr: u64, paddr: u64, prot: u32) -> Result<(), KError>;
+
+    /// `vaddr` sayfasının eşlemesini kaldırır ve çerçevesini döner (eşli değilse `None`).
+    unsafe fn unmap_page(&self, root: u64, vaddr: u64) -> Option<u64>;
+
+    /// Eşli `vaddr` sayfasının korumasını `prot` yapar; sayfa eşli değilse `false` döner.
+    unsafe fn protect_page(&self, root: u64, vaddr: u64, prot: u32) -> bool;
+
+    /// `root` tablosunda `vaddr`'ı fiziksel adrese (sayfa + sayfa içi ofset) çevirir. Sayfa eşli
+    /// değilse, kullanıcıya açık değilse veya `write_access` istenip yazılabilir değilse `None` döner.
+    unsafe fn translate(&self, root: u64, vaddr: u64, write_access: bool) -> Option<u64>;
+
+    /// Portun kullanıcı alanının sonu (dahil değil): kullanıcı sayfa tablolarıyla çevrilebilen en
+    /// yüksek adresin bir fazlası. Çekirdek `USER_SPACE_START` ile bu değer arasını kullanıcıya verir.
+    fn user_space_end(&self) -> usize;
+
+    /// Kullanıcı yarısının ara tablolarını bırakır ve kökteki kullanıcı girdilerini temizler.
+    /// Tüm eşlemeler önceden kaldırılmıştır. Kök tablonun kendisi bazı CPU'larda hâlâ yüklü
+    /// olabileceği için bırakılmaz; onu `ktask::ArchTask::destroy_address_space` bırakır.
+    unsafe fn destroy_page_tables(&self, root: u64);
+}
+
+static ARCH_MMU: Spinlock<Option<&'static dyn ArchMmu>> = Spinlock::named(None, "kmemory::ARCH_MMU");
+/// Kayıtlı portun `ArchMmu::user_space_end` değeri; port kaydedilene kadar kullanıcı alanı boştur.
+static USER_SPACE_END: AtomicUsize = AtomicUsize::new(USER_SPACE_START);
+
+/// Portun sayfa tablosu işlemlerini kaydeder. Platform başlatma kodu ilk görev oluşturulmadan
+/// önce çağırır.
+pub fn register_arch_mmu(mmu: &'static dyn ArchMmu) {
+    *ARCH_MMU.lock() = Some(mmu);
+    USER_SPACE_END.store(mmu.user_space_end(), Ordering::Release);
+}
+
+/// Kullanıcı alanının sonu (dahil değil), bkz. `ArchMmu::user_space_end`.
+pub fn user_space_end() -> usize {
+    USER_SPACE_END.load(Ordering::Acquire)
+}
+
+/// Sabit olmayan eşlemeler için boş aralık aramasının başladığı adres: kullanıcı alanının dörtte
+/// biri (Sv39'da 0x10_0000_0000). Altı, görev imajlarının segmentlerine ve MAP_FIXED eşlemelere
+/// bırakılır.
+pub fn user_mmap_base() -> usize {
+    user_space_end() / 4
+}
+
+fn arch_mmu() -> Result<&'static dyn ArchMmu, KError> {
+    (*ARCH_MMU.lock()).ok_or(KError::NotSupported)
+}
+
+// Adres alanı tablosu: yuvanın kökü (boşsa KERNEL_ADDRESS_SPACE) kilitsiz aranır, VMA ağacı
+// yuvanın mutex'i altındadır.
+const NO_ROOT: AtomicU64 = AtomicU64::new(KERNEL_ADDRESS_SPACE);
+static SPACE_ROOTS: [AtomicU64; MAX_ADDRESS_SPACES] = [NO_ROOT; MAX_ADDRESS_SPACES];
+const EMPTY_SPACE: Mutex<VmaTree> = Mutex::new(VmaTree::new());
+static SPACES: [Mutex<VmaTree>; MAX_ADDRESS_SPACES] = [EMPTY_SPACE; MAX_ADDRESS_SPACES];
+
+/// Sayfa tabloları kurulurken yuvayı ayırmak için kullanılan geçici kök değeri.
+const RESERVED_ROOT: u64 = u64::MAX;
+
+fn space_index(root: u64) -> Result<usize, KError> {
+    if root == KERNEL_ADDRESS_SPACE {
+        return Err(KError::PermissionDenied);
+    }
+    SPACE_ROOTS.iter().position(|r| r.load(Ordering::Acquire) == root).ok_or(KError::NotFound)
+}
+
+/// Yeni, boş bir kullanıcı adres alanı oluşturur ve sayfa tablosu kökünü döner
+/// (`ktask::create_task`'a verilir). Görev toplanırken `destroy_address_space` ile yok edilir.
+pub fn create_address_space() -> Result<u64, KError> {
+    let mmu = arch_mmu()?;
+    let slot = SPACE_ROOTS
+        .iter()
+        .find(|r| r.compare_exchange(KERNEL_ADDRESS_SPACE, RESERVED_ROOT, Ordering::AcqRel, Ordering::Relaxed).is_ok())
+        .ok_or(KError::OutOfMemory)?;
+    match unsafe { mmu.create_page_tables() } {
+        Ok(root) => {
+            slot.store(root, Ordering::Release);
+            Ok(root)
+        }
+        Err(err) => {
+            slot.store(KERNEL_ADDRESS_SPACE, Ordering::Release);
+            Err(err)
+        }
+    }
+}
+
+/// Toplanan bir görevin adres alanındaki tüm VMA'ları kaldırır, göreve özel çerçeveleri ve
+/// kullanıcı yarısının sayfa tablolarını bırakır. Göreve özel VMA'ların kota kullanımı görevin
+/// kotasıyla birlikte toplanırken düşülür. Görevin hiçbir iş parçacığı artık çalışmıyor olmalıdır.
+pub fn destroy_address_space(root: u64) {
+    let index = match space_index(root) {
+        Ok(index) => index,
+        Err(_) => return,
+    };
+    let mmu = match arch_mmu() {
+        Ok(mmu) => mmu,
+        Err(_) => return,
+    };
+    {
+        // Görevin iş parçacığı kalmadığından kilit çekişmesiz alınır.
+        let mut vmas = match SPACES[index].lock() {
+            Ok(vmas) => vmas,
+            Err(_) => return,
+        };
+        while let Some(vma) = vmas.pop_first() {
+            release_pages(mmu, root, &vma, vma.start, vma.end);
+            if let VmaBacking::Shared { object, .. } = vma.backing {
+                shm_put(object);
+            }
+        }
+    }
+    unsafe { mmu.destroy_page_tables(root) };
+    SPACE_ROOTS[index].store(KERNEL_ADDRESS_SPACE, Ordering::Release);
+}
+
+/// `memory_map`'in eşleyeceği sayfaların kaynağı.
+#[derive(Copy, Clone)]
+pub enum MapSource {
+    /// Sıfırla doldurulmuş sayfalar (MAP_ANONYMOUS).
+    Anonymous,
+    /// Kaynak handle'ının sağlayıcısı; içerik göreve özel kopyalanır (MAP_PRIVATE).
+    Resource(&'static dyn ResourceProvider),
+    /// Paylaşımlı bellek handle'ı (MAP_SHARED, bkz. srcshm.rs).
+    Shared(u64),
+}
+
+/// `size`'ı sayfaya yuvarlar; 0 veya taşma için `KError::InvalidArgument`.
+fn page_len(size: usize) -> Result<usize, KError> {
+    if size == 0 {
+        return Err(KError::InvalidArgument);
+    }
+    size.checked_next_multiple_of(PAGE_SIZE).ok_or(KError::InvalidArgument)
+}
+
+/// Sayfa hizalı [`addr`, `addr + len`) aralığı kullanıcı alanında mı?
+fn check_user_range(addr: usize, len: usize) -> Result<(), KError> {
+    if addr % PAGE_SIZE != 0 {
+        return Err(KError::InvalidArgument);
+    }
+    match addr.checked_add(len) {
+        Some(end) if addr >= USER_SPACE_START && end <= user_space_end() => Ok(()),
+        _ => Err(KError::BadAddress),
+    }
+}
+
+/// VMA göreve özel mi: boyutu eşleme anında görevin kotasından düşülür, çerçeveleri kaldırılınca
+/// bırakılır. Paylaşımlı nesnelerin çerçeveleri nesneye aittir ve oluşturanın kotasından düşülür.
+fn is_private(vma: &Vma) -> bool {
+    !matches!(vma.backing, VmaBacking::Shared { .. })
+}
+
+/// Karnal64 API fn memory_allocate karşılığı: mevcut görevin adres alanında `size` baytlık
+/// (sayfaya yuvarlanmış) `prot` korumalı anonim bellek ayırır ve adresini döner.
+pub fn memory_alloc(size: usize, prot: u32) -> Result<usize, KError> {
+    memory_map(0, size, prot, MAP_PRIVATE | MAP_ANONYMOUS, MapSource::Anonymous, 0)
+}
+
+/// Karnal64 API fn memory_map karşılığı: `source`'un `offset`ten başlayan `size` baytını
+/// (sayfaya yuvarlanmış) mevcut görevin adres alanına `prot` korumasıyla eşler ve adresini döner.
+/// `flags` MAP_FIXED içeriyorsa eşleme tam `addr`'ye yapılır ve aralık boş olmalıdır
+/// (`KError::AlreadyExists`); değilse sıfır olmayan `addr` bir ipucudur.
+/// Kaynak eşlemeleri MAP_PRIVATE, paylaşımlı nesne eşlemeleri MAP_SHARED olmalıdır
+/// (aksi halde `KError::NotSupported`). Göreve özel eşlemelerin tüm boyutu burada görevin bellek
+/// kotasından düşülür (sınır aşılırsa `KError::OutOfMemory`); çerçeveler ilk erişimde ayrılır.
+pub fn memory_map(addr: usize, size: usize, prot: u32, flags: u32, source: MapSource, offset: u64) -> Result<usize, KError> {
+    let len = page_len(size)?;
+    let shared = flags & MAP_SHARED != 0;
+    if prot & !PROT_MASK != 0
+        || flags & !MAP_MASK != 0
+        || (shared && flags & MAP_PRIVATE != 0)
+        || offset % PAGE_SIZE as u64 != 0
+        || matches!(source, MapSource::Anonymous) != (flags & MAP_ANONYMOUS != 0)
+    {
+        return Err(KError::InvalidArgument);
+    }
+    match source {
+        MapSource::Resource(_) if shared => return Err(KError::NotSupported),
+        MapSource::Shared(_) if !shared => return Err(KError::NotSupported),
+        _ => {}
+    }
+    if flags & MAP_FIXED != 0 {
+        check_user_range(addr, len)?;
+    }
+
+    // Sayfalar ilk erişimde eşleneceğinden port burada kullanılmaz, yalnızca kayıtlı olmalıdır.
+    arch_mmu()?;
+    let root = ktask::current_address_space();
+    let index = space_index(root)?;
+    let mut vmas = SPACES[index].lock()?;
+
+    let start = if flags & MAP_FIXED != 0 {
+        if !vmas.is_free(addr, addr + len) {
+            return Err(KError::AlreadyExists);
+        }
+        addr
+    } else {
+        let hint = Some(addr).filter(|&a| a != 0 && check_user_range(a, len).is_ok() && vmas.is_free(a, a + len));
+        hint.or_else(|| vmas.find_gap(len, user_mmap_base(), user_space_end()))
+            .or_else(|| vmas.find_gap(len, USER_SPACE_START, user_space_end()))
+            .ok_or(KError::OutOfMemory)?
+    };
+    let backing = match source {
+        MapSource::Anonymous => VmaBacking::Anonymous,
+        MapSource::Resource(provider) => VmaBacking::Resource { provider, offset },
+        MapSource::Shared(handle) => VmaBacking::Shared { object: shm_map_ref(handle, offset, len)?, offset },
+    };
+    let vma = Vma { start, end: start + len, prot, flags: flags & !MAP_FIXED, backing };
+    if is_private(&vma) {
+        ktask::quota_charge(ktask::QUOTA_MEMORY, len as u64)?;
+    }
+
+    if let Err(err) = vmas.insert(vma) {
+        match backing {
+            VmaBacking::Shared { object, .. } => shm_put(object),
+            _ => ktask::quota_uncharge(ktask::QUOTA_MEMORY, len as u64),
+        }
+        return Err(err);
+    }
+    Ok(start)
+}
+
+/// Mevcut görevin `addr` adresine `access` (PROT_READ, PROT_WRITE veya PROT_EXEC) erişimindeki
+/// sayfa hatasını çözer: adres bir VMA'daysa ve VMA bu erişime izin veriyorsa sayfa doldurulup
+/// eşlenir ve erişim yeniden denenebilir. Sayfa zaten eşliyse (başka bir iş parçacığı az önce
+/// çözmüş ya da eski bir TLB girdisi kalmış) yine başarı döner.
+/// Adres hiçbir VMA'da değilse `KError::BadAddress`, VMA erişime izin vermiyorsa
+/// `KError::PermissionDenied`; sayfa doldurulamazsa (bellek, sağlayıcı hatası) o hata döner.
+/// Hatayı alan görevin bağlamında, kesmeler açıkken çağrılır; sağlayıcıdan okurken bloklayabilir.
+pub fn handle_page_fault(addr: usize, access: u32) -> Result<(), KError> {
+    if !(USER_SPACE_START..user_space_end()).contains(&addr) {
+        return Err(KError::BadAddress);
+    }
+    let mmu = arch_mmu()?;
+    let root = ktask::current_address_space();
+    let index = space_index(root).map_err(|_| KError::BadAddress)?;
+    let vmas = SPACES[index].lock()?;
+    fault_in(mmu, root, &vmas, addr, access)
+}
+
+/// `handle_page_fault`'un adres alanı kilidi tutulurken çalışan kısmı.
+fn fault_in(mmu: &'static dyn ArchMmu, root: u64, vmas: &VmaTree, addr: usize, access: u32) -> Result<(), KError> {
+    let vma = vmas.find(addr).ok_or(KError::BadAddress)?;
+    if vma.prot & access == 0 {
+        return Err(KError::PermissionDenied);
+    }
+    match fill_page(mmu, root, &vma, addr & !(PAGE_SIZE - 1)) {
+        Err(KError::AlreadyExists) => Ok(()),
+        result => result,
+    }
+}
+
+/// Mevcut adres alanının kilidini alır, kullanıcı alanındaki [`addr`, `addr + len`) aralığının her
+/// sayfasını `access` (PROT_READ veya PROT_WRITE) erişimiyle eşli hale getirir (gerekirse sayfa
+/// hatası gibi doldurur) ve kilit hâlâ tutulurken `copy`'yi çağırır. memory_unmap/memory_protect
+/// aynı kilidi aldığından aralık doğrulama ile kopyalama arasında kaldırılamaz veya daraltılamaz.
+/// Aralıktaki bir sayfa bu erişime açık değilse `KError::BadAddress` döner ve `copy` çağrılmaz.
+pub(super) fn with_user_range<R>(addr: usize, len: usize, access: u32, copy: impl FnOnce() -> R) -> Result<R, KError> {
+    let mmu = arch_mmu().map_err(|_| KError::BadAddress)?;
+    let root = ktask::current_address_space();
+    let index = space_index(root).map_err(|_| KError::BadAddress)?;
+    let vmas = SPACES[index].lock()?;
+    let write_access = access == PROT_WRITE;
+    for page in (addr & !(PAGE_SIZE - 1)..addr + len).step_by(PAGE_SIZE) {
+        if unsafe { mmu.translate(root, page as u64, write_access) }.is_none() {
+            fault_in(mmu, root, &vmas, page, access).map_err(|_| KError::BadAddress)?;
+        }
+    }
+    Ok(copy())
+}
+
+/// Mevcut adres alanında `vaddr` kullanıcı adresini portun sayfa tablosu yürüyüşüyle fiziksel
+/// adrese çevirir; sayfa doldurmaz. Sayfa eşli değilse veya erişime izin vermiyorsa
+/// `KError::BadAddress` döner.
+pub(super) fn translate_user(vaddr: usize, write_access: bool) -> Result<u64, KError> {
+    let root = ktask::current_address_space();
+    if root == KERNEL_ADDRESS_SPACE {
+        return Err(KError::BadAddress);
+    }
+    let mmu = arch_mmu().map_err(|_| KError::BadAddress)?;
+    unsafe { mmu.translate(root, vaddr as u64, write_access) }.ok_or(KError::BadAddress)
+}
+
+/// VMA'nın `page` sayfası için çerçeveyi hazırlar (sıfırlanmış, sağlayıcıdan okunmuş veya
+/// paylaşımlı nesnenin çerçevesi) ve VMA'nın korumasıyla eşler. Göreve özel VMA'nın kotası
+/// eşleme anında düşüldüğünden burada yalnızca çerçeve ayrılır.
+fn fill_page(mmu: &'static dyn ArchMmu, root: u64, vma: &Vma, page: usize) -> Result<(), KError> {
+    let offset = vma.backing_offset(page);
+    let (paddr, owned) = match vma.backing {
+        VmaBacking::Shared { object, .. } => (shm_frame(object, offset)?, false),
+        VmaBacking::Anonymous | VmaBacking::Resource { .. } => {
+            let paddr = frame_allocator().allocate_frame()?;
+            let frame = unsafe { core::slice::from_raw_parts_mut(phys_to_virt(paddr), PAGE_SIZE) };
+            frame.fill(0);
+            // Kaynağın sonunu aşan kısım sıfır kalır.
+            if let VmaBacking::Resource { provider, .. } = vma.backing {
+                if let Err(err) = provider.read(frame, offset) {
+                    unsafe { frame_allocator().free_frame(paddr) };
+                    return Err(err);
+                }
+            }
+            (paddr, true)
+        }
+    };
+    if let Err(err) = unsafe { mmu.map_page(root, page as u64, paddr, vma.prot) } {
+        if owned {
+            unsafe { frame_allocator().free_frame(paddr) };
+        }
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// VMA'nın [`start`, `end`) bölümündeki eşlemeleri kaldırır. Göreve özel çerçeveler, bölüm
+/// TLB'lerden temizlendikten sonra çerçeve ayırıcıya geri verilir (kotayı çağıran düşer).
+/// Hiç dokunulmamış sayfalar atlanır.
+fn release_pages(mmu: &'static dyn ArchMmu, root: u64, vma: &Vma, start: usize, end: usize) {
+    let private = is_private(vma);
+    let mut batch_start = start;
+    while batch_start < end {
+        let batch_end = end.min(batch_start + UNMAP_BATCH * PAGE_SIZE);
+        let mut frames = [0u64; UNMAP_BATCH];
+        let mut count = 0;
+        for page in (batch_start..batch_end).step_by(PAGE_SIZE) {
+            if let Some(paddr) = unsafe { mmu.unmap_page(root, page as u64) } {
+                frames[count] = paddr;
+                count += 1;
+            }
+        }
+        if count > 0 {
+            ktask::tlb_shootdown(root, batch_start as u64, (batch_end - batch_start) as u64);
+            if private {
+                for &paddr in &frames[..count] {
+                    unsafe { frame_allocator().free_frame(paddr) };
+                }
+            }
+        }
+        batch_start = batch_end;
+    }
+}
+
+/// `vma`'yı ağaçta `pieces` ile değiştirir (bölme). Bir parça eklenemezse ağaç eski haline döner.
+/// Paylaşımlı nesnenin eşleme referansları parça sayısına göre güncellenir.
+fn replace_vma(vmas: &mut VmaTree, vma: &Vma, pieces: &[Vma]) -> Result<(), KError> {
+    vmas.remove(vma.start);
+    for (i, piece) in pieces.iter().enumerate() {
+        if let Err(err) = vmas.insert(*piece) {
+            for inserted in &pieces[..i] {
+                vmas.remove(inserted.start);
+            }
+            // Az önce bırakılan düğümler önbellekte olduğundan eski VMA geri eklenebilir.
+            let _ = vmas.insert(*vma);
+            return Err(err);
+        }
+    }
+    if let VmaBacking::Shared { object, .. } = vma.backing {
+        match pieces.len() {
+            0 => shm_put(object),
+            n => (1..n).for_each(|_| shm_get(object)),
+        }
+    }
+    Ok(())
+}
+
+/// Karnal64 API fn memory_unmap karşılığı: mevcut görevin adres alanında [`addr`, `addr + size`)
+/// aralığındaki (sayfaya yuvarlanmış) tüm eşlemeleri kaldırır. Aralıktaki boşluklar yok sayılır.
+pub fn memory_unmap(addr: usize, size: usize) -> Result<(), KError> {
+    let len = page_len(size)?;
+    check_user_range(addr, len)?;
+    let end = addr + len;
+    let mmu = arch_mmu()?;
+    let root = ktask::current_address_space();
+    let index = space_index(root)?;
+    let mut vmas = SPACES[index].lock()?;
+
+    let mut cursor = addr;
+    while let Some(vma) = vmas.first_ending_after(cursor).filter(|v| v.start < end) {
+        let cut_start = vma.start.max(addr);
+        let cut_end = vma.end.min(end);
+        let mut pieces = [vma; 2];
+        let mut count = 0;
+        if vma.start < cut_start {
+            pieces[count] = vma.split_at(cut_start).0;
+            count += 1;
+        }
+        if cut_end < vma.end {
+            pieces[count] = vma.split_at(cut_end).1;
+            count += 1;
+        }
+        replace_vma(&mut vmas, &vma, &pieces[..count])?;
+        release_pages(mmu, root, &vma, cut_start, cut_end);
+        if is_private(&vma) {
+            ktask::quota_uncharge(ktask::QUOTA_MEMORY, (cut_end - cut_start) as u64);
+        }
+        cursor = cut_end;
+    }
+    Ok(())
+}
+
+/// Karnal64 API fn memory_protect karşılığı: mevcut görevin adres alanında [`addr`, `addr + size`)
+/// aralığının (sayfaya yuvarlanmış) korumasını `prot` yapar. Aralığın tamamı eşli olmalıdır
+/// (aksi halde `KError::BadAddress`, hiçbir şey değişmez).
+pub fn memory_protect(addr: usize, size: usize, prot: u32) -> Result<(), KError> {
+    let len = page_len(size)?;
+    check_user_range(addr, len)?;
+    if prot & !PROT_MASK != 0 {
+        return Err(KError::InvalidArgument);
+    }
+    let end = addr + len;
+    let mmu = arch_mmu()?;
+    let root = ktask::current_address_space();
+    let index = space_index(root)?;
+    let mut vmas = SPACES[index].lock()?;
+
+    // Önce aralığın boşluksuz kaplandığını doğrula.
+    let mut cursor = addr;
+    while cursor < end {
+        match vmas.first_ending_after(cursor) {
+            Some(vma) if vma.start <= cursor => cursor = vma.end,
+            _ => return Err(KError::BadAddress),
+        }
+    }
+
+    let mut narrowed = false;
+    let mut cursor = addr;
+    while let Some(vma) = vmas.first_ending_after(cursor).filter(|v| v.start < end) {
+        let cut_start = vma.start.max(addr);
+        let cut_end = vma.end.min(end);
+        if vma.prot != prot {
+            let mut pieces = [vma; 3];
+            let mut count = 0;
+            let mut middle = vma;
+            if vma.start < cut_start {
+                let (left, right) = middle.split_at(cut_start);
+                pieces[count] = left;
+                count += 1;
+                middle = right;
+            }
+            let tail = if cut_end < vma.end {
+                let (left, right) = middle.split_at(cut_end);
+                middle = left;
+                Some(right)
+            } else {
+                None
+            };
+            middle.prot = prot;
+            pieces[count] = middle;
+            count += 1;
+            if let Some(right) = tail {
+                pieces[count] = right;
+                count += 1;
+            }
+            replace_vma(&mut vmas, &vma, &pieces[..count])?;
+            for page in (cut_start..cut_end).step_by(PAGE_SIZE) {
+                unsafe { mmu.protect_page(root, page as u64, prot) };
+            }
+            narrowed |= vma.prot & !prot != 0;
+        }
+        cursor = cut_end;
+    }
+    if narrowed {
+        ktask::tlb_shootdown(root, addr as u64, len as u64);
+    }
+    Ok(())
+}
@@ -16,6 +16,52 @@ const MODE_READ: u32 = 1 << 0; // Genellikle 1
 const MODE_WRITE: u32 = 1 << 1; // Genellikle 2
 const MODE_EXECUTE: u32 = 1 << 2; // Genellikle 4 (Varsayım)
 
+// task_spawn isteğinin yapıları; ktask::SpawnRequest ve arkadaşlarıyla AYNI düzende olmalıdır
+// (bkz. karnal.h, karnal_spawn_request_t).
+const QUOTA_COUNT: usize = 5; // ktask::QUOTA_COUNT
+const SPAWN_SLOT_STDIN: u32 = 0;
+const SPAWN_SLOT_STDOUT: u32 = 1;
+const SPAWN_SLOT_STDERR: u32 = 2;
+
+#[repr(C)]
+struct SpawnString {
+    ptr: u64,
+    len: u64,
+}
+
+impl SpawnString {
+    fn new(s: &str) -> Self {
+        SpawnString { ptr: s.as_ptr() as u64, len: s.len() as u64 }
+    }
+}
+
+#[repr(C)]
+struct SpawnEnvVar {
+    key: SpawnString,
+    value: SpawnString,
+}
+
+#[repr(C)]
+struct SpawnHandle {
+    handle: u64,
+    slot: u32,
+    flags: u32,
+}
+
+#[repr(C)]
+struct SpawnRequest {
+    code_handle: u64,
+    argv: u64,
+    argc: u64,
+    envp: u64,
+    envc: u64,
+    handles: u64,
+    handle_count: u64,
+    priority: u64, // 0: varsayılan öncelik
+    limit_mask: u64, // 0: tüm sınırlar bizden devralınır
+    limits: [u64; QUOTA_COUNT],
+}
+
 // Çekirdek tarafından sağlanan ham sistem çağrısı fonksiyonlarının dış bildirimleri.
 // Bu fonksiyonlar doğrudan işlemci seviyesindeki 'syscall' komutunu tetikler
 // ve kontrolü çekirdekteki handle_syscall fonksiyonuna devreder.
@@ -28,9 +74,9 @@ extern "C" {
     // Başarı: yazılan_byte_sayısı (usize pozitif), Hata: KError (-i64 negatif)
     fn sys_resource_write(handle: u64, buf_ptr: *const u8, buf_len: usize) -> i64;
 
-    // arg1: code_handle (u64), arg2: args_ptr (*const u8), arg3: args_len (usize)
+    // arg1: request (*const SpawnRequest), arg2: request_len (usize)
     // Başarı: task_id (u64 pozitif), Hata: KError (-i64 negatif)
-    fn sys_task_spawn(code_handle: u64, args_ptr: *const u8, args_len: usize) -> i64;
+    fn sys_task_spawn(request: *const SpawnRequest, request_len: usize) -> i64;
 
      arg1: exit_code (i32)
     // Bu fonksiyon geri dönmez (!), doğrudan görevi sonlandırır.
@@ -109,11 +155,32 @@ pub extern "C" fn main() -> ! {
 
 
     // 3. '/sbin/init' programını yeni bir görev (task) olarak başlat.
-    // Şu an için argüman veya ortam değişkeni geçmiyoruz.
-    let args: [u8; 0] = []; // Argümanlar için boş byte slice
+    // argv[0] programın yolu; konsol handle'ı standart giriş/çıkış/hata yuvalarına devredilir.
+    let argv = [SpawnString::new(main_init_path)];
+    let envp = [
+        SpawnEnvVar { key: SpawnString::new("PATH"), value: SpawnString::new("/bin:/sbin") },
+        SpawnEnvVar { key: SpawnString::new("TERM"), value: SpawnString::new("karnal") },
+    ];
+    let handles = [
+        SpawnHandle { handle: console_handle, slot: SPAWN_SLOT_STDIN, flags: 0 },
+        SpawnHandle { handle: console_handle, slot: SPAWN_SLOT_STDOUT, flags: 0 },
+        SpawnHandle { handle: console_handle, slot: SPAWN_SLOT_STDERR, flags: 0 },
+    ];
+    let request = SpawnRequest {
+        code_handle: init_handle,
+        argv: argv.as_ptr() as u64,
+        argc: argv.len() as u64,
+        envp: envp.as_ptr() as u64,
+        envc: envp.len() as u64,
+        handles: handles.as_ptr() as u64,
+        handle_count: handles.len() as u64,
+        priority: 0,
+        limit_mask: 0,
+        limits: [0; QUOTA_COUNT],
+    };
 
     let spawn_result = unsafe {
-        sys_task_spawn(init_handle, args.as_ptr(), args.len())
+        sys_task_spawn(&request, core::mem::size_of::<SpawnRequest>())
     };
 
     if spawn_result < 0 {
@@ -7,6 +7,7 @@ use super::{
     KTaskId,
     kresource, // Assuming IPC channels might be managed via resource handles
     ksync,     // For synchronization (blocking send/receive)
+    ktask,     // Current task id for message senders
     kmemory,   // For safe user buffer access and copying
     // Add other necessary imports from super:: if needed (like KThreadId)
 };
@@ -22,11 +23,11 @@ struct Message {
     data: alloc::vec::Vec<u8>,
 }
 
-// Represents a message channel/queue.
-struct IpcChannel {
+// The queue part of a channel, protected by the channel's spinlock.
+struct ChannelQueue {
     // Messages waiting to be received
     #[cfg(feature = "alloc")]
-    message_queue: alloc::collections::VecDeque<Message>, // Or a linked list, fixed array, etc.
+    message_queue: alloc::collections::VecDeque<alloc::boxed::Box<Message>>, // Messages come from MESSAGE_CACHE
     #[cfg(not(feature = "alloc"))]
     // Placeholder for fixed-size queue if no_std with alloc is not used
     message_queue: [u8; 1024], // Example fixed buffer
@@ -38,14 +39,21 @@ struct IpcChannel {
     count: usize,
     #[cfg(not(feature = "alloc"))]
     capacity: usize,
+}
+
+// Represents a message channel/queue.
+struct IpcChannel {
+    // The task that created the channel and receives from it. The channel's handle and the bytes
+    // of every queued message are charged to its quota (ktask::QUOTA_HANDLES / QUOTA_IPC_BYTES).
+    owner: KTaskId,
+
+    // Queue state; the lock is handed to the wait queues while blocking
+    queue: ksync::Spinlock<ChannelQueue>,
 
     // Tasks waiting to send (queue is full)
-    waiting_senders: ksync::WaitQueue, // Assuming ksync provides a WaitQueue struct
+    waiting_senders: ksync::WaitQueue,
     // Tasks waiting to receive (queue is empty)
-    waiting_receivers: ksync::WaitQueue, // Assuming ksync provides a WaitQueue struct
-
-    // Mutex to protect access to the channel data
-    lock: ksync::Mutex, // Assuming ksync provides a Mutex
+    waiting_receivers: ksync::WaitQueue,
 }
 
 // --- Internal IPC Manager State ---
@@ -58,7 +66,7 @@ static mut IPC_MANAGER: Option<alloc::boxed::Box<IpcManager>> = None;
 #[cfg(not(feature = "alloc"))]
 static mut IPC_CHANNELS: [Option<IpcChannel>; 32] = [None; 32]; // Example: fixed number of channels
 #[cfg(not(feature = "alloc"))]
-static mut IPC_MANAGER_LOCK: ksync::Spinlock = ksync::Spinlock::new();
+static IPC_MANAGER_LOCK: ksync::Spinlock = ksync::Spinlock::named((), "IPC_MANAGER_LOCK");
 
 
 #[cfg(feature = "alloc")]
@@ -69,6 +77,13 @@ struct IpcManager {
     next_handle_value: u64, // Simple handle counter
 }
 
+// Typed slab caches for channels and queued messages (see kmemory::ObjectCache).
+// Boxes handed out by `alloc_box` return their object to the owning cache when dropped.
+#[cfg(feature = "alloc")]
+static CHANNEL_CACHE: kmemory::ObjectCache<IpcChannel> = kmemory::ObjectCache::new("ipc_channel");
+#[cfg(feature = "alloc")]
+static MESSAGE_CACHE: kmemory::ObjectCache<Message> = kmemory::ObjectCache::new("ipc_message");
+
 // --- kmessaging Module Implementation (Called by Karnal64 API/Syscall Handler) ---
 
 pub mod kmessaging {
@@ -92,12 +107,11 @@ pub mod kmessaging {
         }
         #[cfg(not(feature = "alloc"))]
         unsafe {
-             // Initialize fixed-size structures and lock
-             IPC_MANAGER_LOCK.lock();
+             // Initialize fixed-size structures under the manager lock
+             let _lock = IPC_MANAGER_LOCK.lock();
              for i in 0..IPC_CHANNELS.len() {
                  IPC_CHANNELS[i] = None; // Ensure all entries are None
              }
-             IPC_MANAGER_LOCK.unlock();
         }
         // In a real kernel, this would also register an IPC resource type with kresource
         // For example: kresource::register_resource_type("ipc", Box::new(IpcResourceType));
@@ -111,18 +125,31 @@ pub mod kmessaging {
     // This might not be a direct syscall, but potentially done via resource_acquire on an "ipc" resource type.
     // However, for simplicity, let's add a direct kernel function here for now.
     // A proper implementation would integrate with the kresource resource acquisition flow.
+    // The new handle counts against the caller's handle quota (KError::Busy when over the limit).
     pub fn create_channel() -> Result<KHandle, KError> {
+        let owner = ktask::current_task_id();
+        ktask::quota_charge_task(owner, ktask::QUOTA_HANDLES, 1)?;
+        let result = create_channel_for(owner);
+        if result.is_err() {
+            ktask::quota_uncharge_task(owner, ktask::QUOTA_HANDLES, 1);
+        }
+        result
+    }
+
+    fn create_channel_for(owner: KTaskId) -> Result<KHandle, KError> {
         #[cfg(feature = "alloc")]
         unsafe {
             let manager = IPC_MANAGER.as_mut().ok_or(KError::InternalError)?; // Get mutable ref to manager
 
             // Create a new channel instance
-            let new_channel = Box::new(super::IpcChannel {
-                message_queue: VecDeque::new(),
+            let new_channel = super::CHANNEL_CACHE.alloc_box(super::IpcChannel {
+                owner,
+                queue: ksync::Spinlock::named(super::ChannelQueue {
+                    message_queue: VecDeque::new(),
+                }, "IpcChannel.queue"),
                 waiting_senders: ksync::WaitQueue::new(),
                 waiting_receivers: ksync::WaitQueue::new(),
-                lock: ksync::Mutex::new(),
-            });
+            })?;
 
             // Generate a unique handle value
             let handle_value = manager.next_handle_value;
@@ -141,11 +168,13 @@ pub mod kmessaging {
                  if IPC_CHANNELS[i].is_none() {
                      // Initialize the channel in this slot
                      IPC_CHANNELS[i] = Some(super::IpcChannel {
-                         message_queue: [0; 1024], // Init fixed buffer
-                         head: 0, tail: 0, count: 0, capacity: 1024,
+                         owner,
+                         queue: ksync::Spinlock::named(super::ChannelQueue {
+                             message_queue: [0; 1024], // Init fixed buffer
+                             head: 0, tail: 0, count: 0, capacity: 1024,
+                         }, "IpcChannel.queue"),
                          waiting_senders: ksync::WaitQueue::new(),
                          waiting_receivers: ksync::WaitQueue::new(),
-                         lock: ksync::Mutex::new(),
                      });
                      // Use the index as the handle (simple approach for fixed array)
                      return Ok(KHandle(i as u64 + 1)); // Handle 0 might be reserved
@@ -160,6 +189,8 @@ pub mod kmessaging {
     /// `user_buffer_ptr`: Pointer to the user-space buffer containing the message data.
     /// `user_buffer_len`: Length of the message data.
     /// Returns Ok(()) on success, KError on failure. Can block if the queue is full.
+    /// The message bytes are charged to the receiving task's IPC quota until they are received;
+    /// KError::OutOfMemory if that would exceed its limit.
     pub fn send(handle_value: u64, user_buffer_ptr: *const u8, user_buffer_len: usize) -> Result<(), KError> {
         // 1. Validate user pointer and length
         // In a real kernel, this would involve checking if the user_buffer_ptr + user_buffer_len
@@ -190,64 +221,75 @@ pub mod kmessaging {
 
 
         // 3. Acquire the channel's internal lock
-        let _channel_lock = channel.lock.lock(); // Assuming Mutex::lock blocks and returns a guard
+        let mut queue = channel.queue.lock();
 
         // 4. Check if the queue is full (if applicable) and wait if needed
         // For simplicity in this example, let's assume an unbounded queue if alloc is used,
         // or handle the fixed-size queue case.
         #[cfg(not(feature = "alloc"))]
-        while channel.count == channel.capacity {
-            // Queue is full, task needs to wait
-            // Release channel lock before waiting!
-            channel.lock.unlock(); // Explicit unlock if guard doesn't do it on wait
-            channel.waiting_senders.wait(&_channel_lock); // Wait and re-acquire lock on wake
-            // Re-acquire the channel lock after waking up (WaitQueue handles this)
+        while queue.count == queue.capacity {
+            // Queue is full: the wait queue releases the channel lock while we sleep
+            // and hands it back once a receiver makes room.
+            queue = channel.waiting_senders.wait(queue);
         }
 
         // 5. Copy data from user buffer to kernel buffer/message structure
         #[cfg(feature = "alloc")]
         {
+            // Charge the receiver before allocating anything for the message
+            ktask::quota_charge_task(channel.owner, ktask::QUOTA_IPC_BYTES, user_buffer_len as u64)?;
+
             // Create a kernel buffer and copy data from user space
             let mut kernel_buffer = alloc::vec::Vec::with_capacity(user_buffer_len);
-            unsafe {
+            let copied = unsafe {
                 // Security: This assumes kmemory::copy_from_user is safe and validates!
-                kmemory::copy_from_user(kernel_buffer.as_mut_ptr(), user_buffer_ptr, user_buffer_len)?;
-                kernel_buffer.set_len(user_buffer_len); // Set the actual length after copy
-            }
+                kmemory::copy_from_user(kernel_buffer.as_mut_ptr(), user_buffer_ptr, user_buffer_len)
+            };
 
             // Create a message and add to the queue
-            let message = super::Message {
-                sender_task: ktask::current_task_id(), // Get current task ID (placeholder)
-                data: kernel_buffer,
-            };
-            channel.message_queue.push_back(message);
+            let message = copied.and_then(|_| {
+                unsafe { kernel_buffer.set_len(user_buffer_len) }; // Set the actual length after copy
+                super::MESSAGE_CACHE.alloc_box(super::Message {
+                    sender_task: ktask::current_task_id(), // Get current task ID (placeholder)
+                    data: kernel_buffer,
+                })
+            });
+            match message {
+                Ok(message) => queue.message_queue.push_back(message),
+                Err(err) => {
+                    ktask::quota_uncharge_task(channel.owner, ktask::QUOTA_IPC_BYTES, user_buffer_len as u64);
+                    return Err(err);
+                }
+            }
              super::kkernel::println!("IPC: Message sent to handle {}. Size: {}", handle_value, user_buffer_len);
 
         }
         #[cfg(not(feature = "alloc"))]
         {
              // Handle fixed-size buffer copy (more complex, needs circular buffer logic)
-             if user_buffer_len > (channel.capacity - channel.count) {
+             if user_buffer_len > (queue.capacity - queue.count) {
                  // Message is too large for available space (or queue is full, already handled above)
                  // This is a simplified check. Real fixed queue is complex.
                  return Err(KError::OutOfMemory); // Or similar error
              }
+             ktask::quota_charge_task(channel.owner, ktask::QUOTA_IPC_BYTES, user_buffer_len as u64)?;
              unsafe {
                  // Copy data byte by byte or in chunks, wrapping around the buffer
                  // Placeholder for copy logic into fixed buffer
-                 ptr::copy_nonoverlapping(user_buffer_ptr, channel.message_queue.as_mut_ptr().add(channel.tail), user_buffer_len);
-                 channel.tail = (channel.tail + user_buffer_len) % channel.capacity;
-                 channel.count += user_buffer_len;
+                 let tail = queue.tail;
+                 ptr::copy_nonoverlapping(user_buffer_ptr, queue.message_queue.as_mut_ptr().add(tail), user_buffer_len);
+                 queue.tail = (tail + user_buffer_len) % queue.capacity;
+                 queue.count += user_buffer_len;
              }
              super::kkernel::println!("IPC: Message sent to handle {} (fixed buffer). Size: {}", handle_value, user_buffer_len);
         }
 
 
-        // 6. Wake up any waiting receivers
+        // 6. Wake up any waiting receivers (still under the channel lock, so no wakeup is lost)
         channel.waiting_receivers.wake_one(); // Wake one receiver
 
         // 7. Release the channel's internal lock
-        channel.lock.unlock();
+        drop(queue);
 
         Ok(()) // Success
     }
@@ -284,29 +326,26 @@ pub mod kmessaging {
          };
 
         // 3. Acquire the channel's internal lock
-        let _channel_lock = channel.lock.lock();
+        let mut queue = channel.queue.lock();
 
         // 4. Check if the queue is empty and wait if needed
+        // The wait queue releases the channel lock while blocked and re-acquires it on wake.
         #[cfg(feature = "alloc")]
-        while channel.message_queue.is_empty() {
-            // Queue is empty, task needs to wait
-            // Release channel lock before waiting!
-            channel.lock.unlock(); // Explicit unlock
-            channel.waiting_receivers.wait(&_channel_lock); // Wait and re-acquire lock
-            // Re-acquire channel lock after waking
+        while queue.message_queue.is_empty() {
+            queue = channel.waiting_receivers.wait(queue);
         }
          #[cfg(not(feature = "alloc"))]
-         while channel.count == 0 {
-             // Queue is empty, task needs to wait
-             channel.lock.unlock();
-             channel.waiting_receivers.wait(&_channel_lock);
+         while queue.count == 0 {
+             queue = channel.waiting_receivers.wait(queue);
          }
 
 
         // 5. Get the next message from the queue and copy data to user buffer
         #[cfg(feature = "alloc")]
         {
-            let message = channel.message_queue.pop_front().ok_or(KError::InternalError)?; // Should not fail due to while loop check
+            let message = queue.message_queue.pop_front().ok_or(KError::InternalError)?; // Should not fail due to while loop check
+            // The message leaves the queue (even if the copy below fails): return its bytes to the receiver's quota
+            ktask::quota_uncharge_task(channel.owner, ktask::QUOTA_IPC_BYTES, message.data.len() as u64);
 
             let bytes_to_copy = core::cmp::min(user_buffer_len, message.data.len());
             unsafe {
@@ -321,7 +360,7 @@ pub mod kmessaging {
             channel.waiting_senders.wake_one();
 
             // 7. Release the channel's internal lock
-            channel.lock.unlock();
+            drop(queue);
 
             Ok(bytes_to_copy) // Return number of bytes received
         }
@@ -331,191 +370,80 @@ pub mod kmessaging {
             // This requires knowing the message boundaries in the fixed buffer.
             // A simple fixed buffer queue usually needs message headers or fixed message sizes.
             // For this placeholder, let's just simulate reading some bytes.
-             let bytes_available = channel.count;
+             let bytes_available = queue.count;
              let bytes_to_copy = core::cmp::min(user_buffer_len, bytes_available);
 
              if bytes_to_copy > 0 {
                  unsafe {
                      // Placeholder for copy logic from fixed buffer, wrapping around
-                     ptr::copy_nonoverlapping(channel.message_queue.as_ptr().add(channel.head), user_buffer_ptr, bytes_to_copy);
-                     channel.head = (channel.head + bytes_to_copy) % channel.capacity;
-                     channel.count -= bytes_to_copy;
+                     let head = queue.head;
+                     ptr::copy_nonoverlapping(queue.message_queue.as_ptr().add(head), user_buffer_ptr, bytes_to_copy);
+                     queue.head = (head + bytes_to_copy) % queue.capacity;
+                     queue.count -= bytes_to_copy;
                  }
+                 ktask::quota_uncharge_task(channel.owner, ktask::QUOTA_IPC_BYTES, bytes_to_copy as u64);
              }
              super::kkernel::println!("IPC: Message received from handle {} (fixed buffer). Size: {}", handle_value, bytes_to_copy);
 
              // 6. Wake up senders if space is now available
              // Simplified: Wake one sender if the queue wasn't full and now has space
-             if bytes_available == channel.capacity && channel.count < channel.capacity {
+             if bytes_available == queue.capacity && queue.count < queue.capacity {
                  channel.waiting_senders.wake_one();
              }
 
 
              // 7. Release the channel's internal lock
-             channel.lock.unlock();
+             drop(queue);
 
              Ok(bytes_to_copy) // Return number of bytes received
         }
 
     }
 
-    // TODO: Add a close_channel function to release the channel handle and resources.
-    // This should likely be tied to the kresource::resource_release mechanism for IPC handles.
-
-     // --- Placeholder/Example implementations for dependencies ---
-     // These would exist in their respective modules (kmemory, ktask, ksync, kkernel)
-     // but are included here for illustration.
-
-     #[cfg(not(feature = "alloc"))]
-     mod ksync {
-         use core::cell::UnsafeCell;
-         use core::ops::{Deref, DerefMut};
-         use core::sync::atomic::{AtomicBool, Ordering};
-         // Need a way to block/wake tasks - this depends on the scheduler (ktask)
-         // Placeholder:
-         pub struct WaitQueue;
-         impl WaitQueue {
-             pub const fn new() -> Self { WaitQueue }
-             // Need the mutex guard or a reference to the mutex to ensure lock is held before waiting
-             // and reacquired after waking. Signature depends on actual ksync implementation.
-             pub fn wait<T>(&self, mutex_guard: &MutexGuard<T>) {
-                 super::super::kkernel::println!("WaitQueue: Task {} waiting...", super::super::ktask::current_task_id().0);
-                 // TODO: Call scheduler to block current task and add to this queue
-                 // TODO: Release mutex_guard's lock before blocking
-                 // TODO: Reacquire mutex_guard's lock after waking
-                  super::super::ktask::yield_now(); // Simple placeholder: just yield
-             }
-             pub fn wake_one(&self) {
-                 super::super::kkernel::println!("WaitQueue: Waking one task...");
-                 // TODO: Wake one task from this queue and add to scheduler's run queue
-             }
-         }
-
-         // Basic Spinlock - WARNING: Not suitable for complex critical sections where waits happen!
-         // A proper kernel Mutex that interacts with the scheduler is needed.
-         pub struct Spinlock(AtomicBool);
-         impl Spinlock {
-             pub const fn new() -> Self { Spinlock(AtomicBool::new(false)) }
-             pub fn lock(&self) -> SpinlockGuard {
-                 while self.0.swap(true, Ordering::Acquire) {
-                     core::hint::spin_loop(); // Busy wait
-                 }
-                 SpinlockGuard(self)
-             }
-             pub fn unlock(&self) {
-                 self.0.store(false, Ordering::Release);
-             }
-         }
-         pub struct SpinlockGuard<'a>(&'a Spinlock);
-         impl<'a> Drop for SpinlockGuard<'a> {
-             fn drop(&mut self) { self.0.unlock(); }
-         }
-
-
-         // Basic Mutex - Placeholder, needs scheduler interaction for blocking
-         // Should integrate with WaitQueue
-         pub struct Mutex<T: ?Sized> {
-             locked: AtomicBool, // Simple state
-             data: UnsafeCell<T>, // The protected data (not used in IpcChannel's lock)
-             // Need a wait queue here!
-         }
-
-         impl<T> Mutex<T> {
-             pub const fn new(data: T) -> Self {
-                 Mutex {
-                     locked: AtomicBool::new(false),
-                     data: UnsafeCell::new(data),
-                 }
-             }
-             pub fn lock(&self) -> MutexGuard<T> {
-                 while self.locked.swap(true, Ordering::Acquire) {
-                    // In a real kernel, this would involve waiting on a WaitQueue,
-                    // yielding to the scheduler. Busy waiting is bad!
-                     core::hint::spin_loop();
-                 }
-                 MutexGuard(self)
-             }
-             // Unlock is usually handled by the MutexGuard Drop impl
-         }
-
-         pub struct MutexGuard<'a, T: ?Sized>(&'a Mutex<T>);
-         impl<'a, T: ?Sized> Drop for MutexGuard<'a, T> {
-             fn drop(&mut self) {
-                 self.0.locked.store(false, Ordering::Release);
-             }
-         }
-
-         impl<'a, T: ?Sized> Deref for MutexGuard<'a, T> {
-             type Target = T;
-             fn deref(&self) -> &Self::Target {
-                 unsafe { &*self.0.data.get() }
-             }
-         }
-
-         impl<'a, T: ?Sized> DerefMut for MutexGuard<'a, T> {
-             fn deref_mut(&mut self) -> &mut Self::Target {
-                 unsafe { &mut *self.0.data.get() }
-             }
-         }
-
-     }
-
-     // Placeholder kmemory module functions for user buffer validation and copying
-     mod kmemory {
-         use super::*;
-         use core::ptr;
-
-         // WARNING: These are INSECURE placeholders!
-         // Real kernel implementation MUST validate the address range against the current task's virtual memory map.
-         pub fn is_user_buffer_valid_and_readable(ptr: *const u8, len: usize) -> bool {
-             // Placeholder: Assume any non-null pointer with non-zero length is "valid" for now.
-             // A real check would compare ptr and ptr+len against user space bounds and permissions.
-             !ptr.is_null() || len == 0
-         }
-
-         pub fn is_user_buffer_valid_and_writable(ptr: *mut u8, len: usize) -> bool {
-             // Placeholder: Assume any non-null pointer with non-zero length is "valid" for now.
-             !ptr.is_null() || len == 0
-         }
-
-         // WARNING: This is an INSECURE placeholder!
-         // Real implementation MUST handle page faults and ensure the source buffer is mapped and readable.
-         pub fn copy_from_user(dest: *mut u8, src: *const u8, len: usize) -> Result<(), KError> {
-             if len == 0 { return Ok(()); }
-             if dest.is_null() || src.is_null() { return Err(KError::InvalidArgument); }
-             // Real copy might need page table walks, fault handling, etc.
-             unsafe { ptr::copy_nonoverlapping(src, dest, len); }
-             Ok(())
-         }
+    /// Destroy an IPC channel created by the calling task.
+    /// Messages still queued are dropped; their bytes and the channel's handle are returned to the
+    /// owner's quota. Fails with KError::Busy while tasks are blocked on the channel.
+    // TODO: Tie this to the kresource::resource_release mechanism for IPC handles.
+    pub fn close_channel(handle_value: u64) -> Result<(), KError> {
+        if handle_value == 0 { return Err(KError::BadHandle); }
 
-         // WARNING: This is an INSECURE placeholder!
-         // Real implementation MUST handle page faults and ensure the destination buffer is mapped and writable.
-         pub fn copy_to_user(dest: *mut u8, src: *const u8, len: usize) -> Result<(), KError> {
-              if len == 0 { return Ok(()); }
-              if dest.is_null() || src.is_null() { return Err(KError::InvalidArgument); }
-              // Real copy might need page table walks, fault handling, etc.
-              unsafe { ptr::copy_nonoverlapping(src, dest, len); }
-              Ok(())
-         }
-     }
-
-     // Placeholder ktask module for current task ID and yielding
-     mod ktask {
-         use super::*;
+        #[cfg(feature = "alloc")]
+        let (owner, queued_bytes) = unsafe {
+            let manager = IPC_MANAGER.as_mut().ok_or(KError::InternalError)?;
+            let channel = manager.channels.get(&handle_value).ok_or(KError::BadHandle)?;
+            if channel.owner != ktask::current_task_id() { return Err(KError::PermissionDenied); }
+            if !channel.waiting_senders.is_empty() || !channel.waiting_receivers.is_empty() {
+                return Err(KError::Busy);
+            }
+            let queued_bytes: usize = channel.queue.lock().message_queue.iter().map(|m| m.data.len()).sum();
+            let owner = channel.owner;
+            manager.channels.remove(&handle_value); // Drops the channel and its queued messages
+            (owner, queued_bytes)
+        };
+        #[cfg(not(feature = "alloc"))]
+        let (owner, queued_bytes) = unsafe {
+            let _lock = IPC_MANAGER_LOCK.lock();
+            let index = (handle_value - 1) as usize;
+            if index >= IPC_CHANNELS.len() { return Err(KError::BadHandle); }
+            let channel = IPC_CHANNELS[index].as_ref().ok_or(KError::BadHandle)?;
+            if channel.owner != ktask::current_task_id() { return Err(KError::PermissionDenied); }
+            if !channel.waiting_senders.is_empty() || !channel.waiting_receivers.is_empty() {
+                return Err(KError::Busy);
+            }
+            let queued_bytes = channel.queue.lock().count;
+            let owner = channel.owner;
+            IPC_CHANNELS[index] = None;
+            (owner, queued_bytes)
+        };
 
-         // WARNING: Placeholder!
-         pub fn current_task_id() -> KTaskId {
-             // In a real kernel, this would read from the current task's control block,
-             // likely stored in a CPU-local register or structure.
-             KTaskId(1) // Dummy ID
-         }
+        ktask::quota_uncharge_task(owner, ktask::QUOTA_IPC_BYTES, queued_bytes as u64);
+        ktask::quota_uncharge_task(owner, ktask::QUOTA_HANDLES, 1);
+        Ok(())
+    }
 
-         // WARNING: Placeholder!
-         pub fn yield_now() {
-             // In a real kernel, this would invoke the scheduler to switch to another task.
-             super::super::kkernel::println!("Task {} yielding...", current_task_id().0);
-         }
-     }
+     // --- Placeholder/Example implementations for dependencies ---
+     // These would exist in their respective modules (kkernel)
+     // but are included here for illustration. ksync, ktask and kmemory come from the Karnal64 core.
 
     // Placeholder kkernel module for printing (requires a kernel console driver)
     mod kkernel {
@@ -608,28 +536,6 @@ pub mod kmessaging {
          pub const MODE_WRITE: u32 = 1 << 1;
      }
 
-    // Required for the dummy Mutex/Spinlock
-    #[cfg(not(feature = "alloc"))]
-    impl ksync::Spinlock {
-        pub const fn new() -> Self { ksync::Spinlock(core::sync::atomic::AtomicBool::new(false)) }
-    }
-
-    // Required for the dummy Mutex/WaitQueue
-    #[cfg(not(feature = "alloc"))]
-    impl ksync::WaitQueue {
-        pub const fn new() -> Self { ksync::WaitQueue }
-    }
-
-    #[cfg(not(feature = "alloc"))]
-    impl<T> ksync::Mutex<T> {
-         pub const fn new(data: T) -> Self {
-             ksync::Mutex {
-                 locked: core::sync::atomic::AtomicBool::new(false),
-                 data: core::cell::UnsafeCell::new(data),
-             }
-         }
-    }
-
 } // end mod kmessaging
 
 // Add dummy structs/enums from karnal64.rs that are needed by the placeholders above
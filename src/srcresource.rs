@@ -0,0 +1,204 @@
+// Karnal64 kresource: kaynak sağlayıcı kaydı ve görev başına kaynak handle'ları.
+// Çekirdek bileşenleri ResourceProvider implementasyonlarını bir isimle ("karnal://device/console"
+// gibi) kaydeder. `resource_acquire` ismi bir sağlayıcıya çözer ve çağıran görev adına bir handle
+// açar; handle erişim modunu ve okuma/yazma için güncel ofseti tutar.
+// Handle'lar açan göreve aittir: başka bir görev aynı değeri kullanamaz. Her handle sahibinin
+// handle kotasından sayılır, task_spawn ile çocuk göreve kopyalanabilir (mod ve ofset birlikte)
+// ve görev toplanınca kapanır.
+
+use super::ksync::Spinlock;
+use super::ktask;
+use super::{KError, KHandle, KTaskId, ResourceProvider};
+
+/// Kaydedilebilecek en fazla kaynak sağlayıcı.
+pub const MAX_PROVIDERS: usize = 32;
+/// Tüm görevlerin açık tutabileceği en fazla kaynak handle'ı.
+const MAX_RESOURCE_HANDLES: usize = 128;
+
+// Kaynak modları (resource_acquire `mode` bayrakları)
+pub const MODE_READ: u32 = 1 << 0;
+pub const MODE_WRITE: u32 = 1 << 1;
+pub const MODE_CREATE: u32 = 1 << 2;
+const MODE_ALL: u32 = MODE_READ | MODE_WRITE | MODE_CREATE;
+
+const RESOURCE_HANDLE_TAG: u64 = 0x52 << 56; // 'R'
+
+#[derive(Copy, Clone)]
+struct Provider {
+    name: &'static str,
+    provider: &'static dyn ResourceProvider,
+}
+
+#[derive(Copy, Clone)]
+struct ResourceHandle {
+    generation: u16,
+    owner: Option<KTaskId>,
+    provider: usize,
+    mode: u32,
+    /// Sonraki okuma/yazmanın ofseti.
+    offset: u64,
+}
+
+struct ResourceTable {
+    providers: [Option<Provider>; MAX_PROVIDERS],
+    handles: [ResourceHandle; MAX_RESOURCE_HANDLES],
+}
+
+impl ResourceTable {
+    /// Yeni bir handle açar; `owner`'ın handle kotasından düşülür.
+    fn handle_open(&mut self, owner: KTaskId, provider: usize, mode: u32, offset: u64) -> Result<KHandle, KError> {
+        let index = self.handles.iter().position(|h| h.owner.is_none()).ok_or(KError::OutOfMemory)?;
+        ktask::quota_charge_task(owner, ktask::QUOTA_HANDLES, 1)?;
+        let handle = &mut self.handles[index];
+        handle.owner = Some(owner);
+        handle.provider = provider;
+        handle.mode = mode;
+        handle.offset = offset;
+        Ok(KHandle(RESOURCE_HANDLE_TAG | ((handle.generation as u64) << 16) | (index as u64 + 1)))
+    }
+
+    /// `handle_value`'yu `owner`'a ait bir handle'ın tablo indeksine çözer.
+    fn handle_index(&self, owner: KTaskId, handle_value: u64) -> Result<usize, KError> {
+        if !is_resource_handle(handle_value) {
+            return Err(KError::BadHandle);
+        }
+        let generation = ((handle_value >> 16) & 0xFFFF) as u16;
+        let index = (handle_value & 0xFFFF) as usize;
+        if index == 0 || index > MAX_RESOURCE_HANDLES {
+            return Err(KError::BadHandle);
+        }
+        let handle = self.handles[index - 1];
+        if handle.owner != Some(owner) || handle.generation != generation {
+            return Err(KError::BadHandle);
+        }
+        Ok(index - 1)
+    }
+
+    /// Handle'ı kapatır; neslini artırarak eski değeri geçersiz kılar.
+    fn handle_close(&mut self, index: usize) {
+        let handle = self.handles[index];
+        if let Some(owner) = handle.owner {
+            self.handles[index] = ResourceHandle { generation: handle.generation.wrapping_add(1), ..NO_HANDLE };
+            ktask::quota_uncharge_task(owner, ktask::QUOTA_HANDLES, 1);
+        }
+    }
+
+    /// Çağıranın `handle_value` handle'ının sağlayıcısı ve ofseti; handle `mode` izinlerini içermelidir.
+    fn access(&self, handle_value: u64, mode: u32) -> Result<(&'static dyn ResourceProvider, u64), KError> {
+        let index = self.handle_index(ktask::current_task_id(), handle_value)?;
+        let handle = self.handles[index];
+        if handle.mode & mode != mode {
+            return Err(KError::PermissionDenied);
+        }
+        let provider = self.providers[handle.provider].ok_or(KError::InternalError)?;
+        Ok((provider.provider, handle.offset))
+    }
+
+    /// G/Ç sonrası ofseti ilerletir. Handle bu arada kapatıldıysa bir şey yapılmaz.
+    fn advance(&mut self, handle_value: u64, amount: usize) {
+        if let Ok(index) = self.handle_index(ktask::current_task_id(), handle_value) {
+            let handle = &mut self.handles[index];
+            handle.offset = handle.offset.wrapping_add(amount as u64);
+        }
+    }
+}
+
+const NO_HANDLE: ResourceHandle = ResourceHandle { generation: 0, owner: None, provider: 0, mode: 0, offset: 0 };
+static RESOURCES: Spinlock<ResourceTable> = Spinlock::named(
+    ResourceTable { providers: [None; MAX_PROVIDERS], handles: [NO_HANDLE; MAX_RESOURCE_HANDLES] },
+    "kresource::RESOURCES",
+);
+
+pub fn init_manager() {
+    println!("Karnal64: Kaynak Yöneticisi Başlatıldı");
+}
+
+/// Bir çekirdek bileşeninin kaynağını `name` ile kaydeder. İsim zaten kayıtlıysa
+/// `KError::AlreadyExists`, kayıt tablosu doluysa `KError::OutOfMemory` döner.
+pub fn register_provider(name: &'static str, provider: &'static dyn ResourceProvider) -> Result<(), KError> {
+    let mut table = RESOURCES.lock();
+    if table.providers.iter().flatten().any(|p| p.name == name) {
+        return Err(KError::AlreadyExists);
+    }
+    let slot = table.providers.iter().position(|p| p.is_none()).ok_or(KError::OutOfMemory)?;
+    table.providers[slot] = Some(Provider { name, provider });
+    Ok(())
+}
+
+/// `handle_value` bir kaynak handle'ı mı (geçerliliği kontrol edilmez).
+pub fn is_resource_handle(handle_value: u64) -> bool {
+    handle_value & (0xFF << 56) == RESOURCE_HANDLE_TAG
+}
+
+/// `resource_acquire` yolu: `name` ile kayıtlı sağlayıcıya çağıran adına `mode` erişimli bir handle açar.
+pub fn issue_handle(name: &str, mode: u32) -> Result<KHandle, KError> {
+    if mode == 0 || mode & !MODE_ALL != 0 {
+        return Err(KError::InvalidArgument);
+    }
+    let mut table = RESOURCES.lock();
+    let provider = table
+        .providers
+        .iter()
+        .position(|p| matches!(p, Some(p) if p.name == name))
+        .ok_or(KError::NotFound)?;
+    table.handle_open(ktask::current_task_id(), provider, mode, 0)
+}
+
+/// Çağıranın `handle_value` handle'ının sağlayıcısı (memory_map kaynak eşlemeleri için okuma izni gerekir).
+pub fn get_provider_by_handle(handle_value: u64) -> Result<&'static dyn ResourceProvider, KError> {
+    RESOURCES.lock().access(handle_value, MODE_READ).map(|(provider, _)| provider)
+}
+
+/// Handle'ın ofsetinden `buffer`'a okur ve ofseti okunan bayt kadar ilerletir.
+/// Sağlayıcı, tablo kilidi dışında çağrılır.
+pub fn handle_read(handle_value: u64, buffer: &mut [u8]) -> Result<usize, KError> {
+    let (provider, offset) = RESOURCES.lock().access(handle_value, MODE_READ)?;
+    let read = provider.read(buffer, offset)?;
+    RESOURCES.lock().advance(handle_value, read);
+    Ok(read)
+}
+
+/// `buffer`'ı handle'ın ofsetine yazar ve ofseti yazılan bayt kadar ilerletir.
+pub fn handle_write(handle_value: u64, buffer: &[u8]) -> Result<usize, KError> {
+    let (provider, offset) = RESOURCES.lock().access(handle_value, MODE_WRITE)?;
+    let written = provider.write(buffer, offset)?;
+    RESOURCES.lock().advance(handle_value, written);
+    Ok(written)
+}
+
+/// `resource_control` yolu: isteği handle'ın sağlayıcısına iletir.
+pub fn handle_control(handle_value: u64, request: u64, arg: u64) -> Result<u64, KError> {
+    let (provider, _) = RESOURCES.lock().access(handle_value, 0)?;
+    provider.control(request, arg).map(|value| value as u64)
+}
+
+/// `resource_release` yolu: çağıranın handle'ını kapatır.
+pub fn release_handle(handle_value: u64) -> Result<(), KError> {
+    let mut table = RESOURCES.lock();
+    let index = table.handle_index(ktask::current_task_id(), handle_value)?;
+    table.handle_close(index);
+    Ok(())
+}
+
+/// `handle_value` çağırana ait geçerli bir kaynak handle'ı mı.
+pub fn check_handle(handle_value: u64) -> Result<(), KError> {
+    RESOURCES.lock().handle_index(ktask::current_task_id(), handle_value).map(|_| ())
+}
+
+/// Çağıranın handle'ını `task` adına aynı mod ve ofsetle kopyalar (task_spawn devri).
+pub fn share_handle(handle_value: u64, task: KTaskId) -> Result<KHandle, KError> {
+    let mut table = RESOURCES.lock();
+    let index = table.handle_index(ktask::current_task_id(), handle_value)?;
+    let handle = table.handles[index];
+    table.handle_open(task, handle.provider, handle.mode, handle.offset)
+}
+
+/// Toplanan `task` görevinin açık kaynak handle'larını kapatır.
+pub fn release_task_handles(task: KTaskId) {
+    let mut table = RESOURCES.lock();
+    for index in 0..MAX_RESOURCE_HANDLES {
+        if table.handles[index].owner == Some(task) {
+            table.handle_close(index);
+        }
+    }
+}
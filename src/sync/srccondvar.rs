@@ -0,0 +1,112 @@
+// Karnal64 ksync: bir kilit handle'ı ile eşlenmiş koşul değişkeni (condition variable).
+// - Bekleyen, eşli kilidi tutuyor olmalıdır. Kilit bekleme başlarken atomik olarak bırakılır
+//   ve bekleme bitince (sinyal veya zaman aşımı) tekrar alınır.
+// - `notify_one` bir, `notify_all` o an bekleyen tüm iş parçacıklarını geçirir. Bildirim,
+//   bekleyen yoksa kaybolur (klasik condvar semantiği).
+// Çekirdek içi kod için aynı desen doğrudan Spinlock + WaitQueue ile kurulur (bkz. srcsync.rs).
+
+use super::super::KError;
+use super::super::ktask;
+use super::mutex::lookup_lock;
+use super::{wait_any, Spinlock, WaitQueue, Waitable};
+
+struct CondState {
+    /// Bekleme başlatmış (begin_wait) ve henüz bitirmemiş iş parçacığı sayısı.
+    waiters: u32,
+    /// Verilmiş ama henüz tüketilmemiş bildirim sayısı. Hiçbir zaman `waiters`'ı aşmaz.
+    tokens: u32,
+}
+
+pub struct CondVar {
+    state: Spinlock<CondState>,
+    /// Eşli kilidin handle değeri. Her kullanımda çözülür; kilit yok edilmişse BadHandle döner.
+    lock_handle: u64,
+    waiters: WaitQueue,
+}
+
+impl CondVar {
+    pub const fn new(lock_handle: u64) -> Self {
+        CondVar {
+            state: Spinlock::named(CondState { waiters: 0, tokens: 0 }, "CondVar.state"),
+            lock_handle,
+            waiters: WaitQueue::new(),
+        }
+    }
+
+    pub fn lock_handle(&self) -> u64 {
+        self.lock_handle
+    }
+
+    /// Eşli kilidi bırakıp bildirim gelene veya `timeout_ns` dolana kadar bekler.
+    /// Dönüşte kilit tekrar tutulmaktadır.
+    pub fn wait(&self, timeout_ns: u64) -> Result<(), KError> {
+        wait_any(&[self], timeout_ns).map(|_| ())
+    }
+
+    /// Bekleyenlerden birini uyandırır.
+    pub fn notify_one(&self) {
+        let mut state = self.state.lock();
+        if state.tokens < state.waiters {
+            state.tokens += 1;
+            drop(state);
+            self.waiters.wake_one();
+        }
+    }
+
+    /// O an bekleyen tüm iş parçacıklarını uyandırır.
+    pub fn notify_all(&self) {
+        let mut state = self.state.lock();
+        if state.waiters > 0 {
+            state.tokens = state.waiters;
+            drop(state);
+            self.waiters.wake_all();
+        }
+    }
+}
+
+impl Waitable for CondVar {
+    fn wait_queue(&self) -> &WaitQueue {
+        &self.waiters
+    }
+
+    fn try_consume(&self) -> bool {
+        let mut state = self.state.lock();
+        if state.tokens > 0 {
+            state.tokens -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_signaled(&self) -> bool {
+        self.state.lock().tokens > 0
+    }
+
+    /// Bekleyici olarak kaydolur, sonra eşli kilidi bırakır. Bu sırayla, kilit bırakıldıktan
+    /// hemen sonra gelen bir bildirim bu bekleyiciyi de hesaba katar.
+    fn begin_wait(&self) -> Result<(), KError> {
+        let lock = lookup_lock(self.lock_handle)?;
+        if lock.owner() != Some(ktask::current_thread_id()) {
+            return Err(KError::PermissionDenied);
+        }
+        self.state.lock().waiters += 1;
+        lock.release()
+    }
+
+    fn end_wait(&self) {
+        {
+            let mut state = self.state.lock();
+            state.waiters -= 1;
+            // Zaman aşımıyla ayrılan bekleyici için verilmiş bildirim artık kimseye ait değil.
+            if state.tokens > state.waiters {
+                state.tokens = state.waiters;
+            }
+        }
+        // Eşli kilit bekleme sırasında yok edildiyse tekrar alınamaz; çağıran bir sonraki
+        // kilit işleminde BadHandle alır.
+        if let Ok(lock) = lookup_lock(self.lock_handle) {
+            let _ = lock.acquire();
+        }
+    }
+}
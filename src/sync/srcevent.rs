@@ -0,0 +1,72 @@
+// Karnal64 ksync: olay (event) nesnesi.
+// - Manuel sıfırlamalı olay: `set` tüm bekleyenleri geçirir, `reset` çağrılana kadar sinyalli kalır.
+// - Otomatik sıfırlamalı olay: `set` tek bir bekleyeni geçirir, olay o bekleyen tarafından sıfırlanır.
+
+use super::super::KError;
+use super::{wait_any, Spinlock, WaitQueue, Waitable};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EventMode {
+    ManualReset,
+    AutoReset,
+}
+
+pub struct Event {
+    signaled: Spinlock<bool>,
+    mode: EventMode,
+    waiters: WaitQueue,
+}
+
+impl Event {
+    pub const fn new(mode: EventMode, initially_set: bool) -> Self {
+        Event { signaled: Spinlock::named(initially_set, "Event.signaled"), mode, waiters: WaitQueue::new() }
+    }
+
+    pub fn mode(&self) -> EventMode {
+        self.mode
+    }
+
+    /// Olay sinyallenene kadar en fazla `timeout_ns` nanosaniye bekler.
+    pub fn wait(&self, timeout_ns: u64) -> Result<(), KError> {
+        wait_any(&[self], timeout_ns).map(|_| ())
+    }
+
+    /// Olayı sinyaller. Manuel modda tüm bekleyenler, otomatik modda biri uyandırılır.
+    pub fn set(&self) {
+        *self.signaled.lock() = true;
+        match self.mode {
+            EventMode::ManualReset => {
+                self.waiters.wake_all();
+            }
+            EventMode::AutoReset => {
+                self.waiters.wake_one();
+            }
+        }
+    }
+
+    /// Olayı sinyalsiz duruma getirir.
+    pub fn reset(&self) {
+        *self.signaled.lock() = false;
+    }
+}
+
+impl Waitable for Event {
+    fn wait_queue(&self) -> &WaitQueue {
+        &self.waiters
+    }
+
+    fn try_consume(&self) -> bool {
+        let mut signaled = self.signaled.lock();
+        if !*signaled {
+            return false;
+        }
+        if self.mode == EventMode::AutoReset {
+            *signaled = false;
+        }
+        true
+    }
+
+    fn is_signaled(&self) -> bool {
+        *self.signaled.lock()
+    }
+}
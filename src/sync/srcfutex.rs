@@ -0,0 +1,174 @@
+// Karnal64 ksync: kullanıcı adresleri üzerinde futex tarzı bekle/uyandır.
+// - Anahtar, kullanıcı sanal adresinin çevrildiği fiziksel adrestir (sayfa çerçevesi + sayfa içi ofset);
+//   böylece aynı sayfayı farklı sanal adreslerde eşleyen görevler aynı futex'te buluşur.
+// - Anahtarlar sabit sayıda kovaya hash'lenir. Her bekleyen, anahtarıyla birlikte kovanın ortak
+//   listesine bir düğüm ekler; böylece bir kovada beklenebilecek anahtar sayısı sınırlı değildir.
+// - Değer karşılaştırması kova kilidi altında yapılır. FUTEX_WAKE de aynı kilidi aldığından
+//   "değeri kontrol et ve uyu" ile uyandırma arasında bir yarış olmaz.
+// - Kullanıcı alanına SYSCALL_FUTEX_WAIT/WAKE ile açılır.
+
+use core::ptr;
+
+use super::{Spinlock, WaitQueue, WaitResult};
+use super::super::{KError, KThreadId};
+use super::super::kmemory::{self, PAGE_SIZE};
+use super::super::ktask;
+
+/// `futex_wait` için sonsuz bekleme değeri.
+pub const FUTEX_WAIT_FOREVER: u64 = u64::MAX;
+
+const FUTEX_BUCKETS: usize = 32;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct FutexKey {
+    /// Fiziksel sayfa çerçevesinin başlangıç adresi.
+    page: u64,
+    /// Sayfa içi ofset.
+    offset: u32,
+}
+
+impl FutexKey {
+    fn from_user_address(addr: u64) -> Result<Self, KError> {
+        if addr % 4 != 0 {
+            return Err(KError::InvalidArgument);
+        }
+        let paddr = kmemory::user_virt_to_phys(addr as usize, false)?;
+        let page_mask = PAGE_SIZE as u64 - 1;
+        Ok(FutexKey { page: paddr & !page_mask, offset: (paddr & page_mask) as u32 })
+    }
+
+    fn bucket(&self) -> usize {
+        let mixed = (self.page | self.offset as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        (mixed >> 32) as usize % FUTEX_BUCKETS
+    }
+}
+
+/// Bekleyen bir iş parçacığının düğümü. Düğüm bekleyenin yığınında yaşar ve kovanın listesine
+/// bağlanır; liste yalnızca kova kilidi altında gezilir ve değiştirilir. Bekleyen, düğümü listeden
+/// çıkarılmadan (uyandırılarak veya kendisi çıkararak) futex_wait'ten dönmez.
+struct FutexWaiter {
+    key: FutexKey,
+    thread: KThreadId,
+    /// futex_wake düğümü listeden çıkardı; bekleme başarıyla biter.
+    woken: bool,
+    next: *mut FutexWaiter,
+}
+
+/// Kovadaki tüm anahtarların bekleyenleri, geliş sırasıyla (FIFO) tek bir bağlı listede.
+struct WaiterList {
+    head: *mut FutexWaiter,
+}
+
+// Güvenlik: düğümlere yalnızca kova kilidi altında erişilir (bkz. FutexWaiter).
+unsafe impl Send for WaiterList {}
+
+impl WaiterList {
+    /// `node`'u listenin sonuna ekler.
+    unsafe fn push(&mut self, node: *mut FutexWaiter) {
+        let mut link = &mut self.head;
+        while !link.is_null() {
+            link = &mut (**link).next;
+        }
+        *link = node;
+    }
+
+    /// `node` listedeyse çıkarır.
+    unsafe fn remove(&mut self, node: *mut FutexWaiter) {
+        let mut link = &mut self.head;
+        while !link.is_null() {
+            if *link == node {
+                *link = (*node).next;
+                return;
+            }
+            link = &mut (**link).next;
+        }
+    }
+}
+
+struct FutexBucket {
+    waiters: Spinlock<WaiterList>,
+    /// Kovanın bekleyenleri bu kuyrukta uyur; futex_wake yalnızca eşleşen düğümlerin
+    /// iş parçacıklarını uyandırır (`WaitQueue::wake_thread`).
+    queue: WaitQueue,
+}
+
+impl FutexBucket {
+    const fn new() -> Self {
+        FutexBucket {
+            waiters: Spinlock::named(WaiterList { head: ptr::null_mut() }, "futex_bucket"),
+            queue: WaitQueue::new(),
+        }
+    }
+}
+
+const EMPTY_BUCKET: FutexBucket = FutexBucket::new();
+static FUTEX_TABLE: [FutexBucket; FUTEX_BUCKETS] = [EMPTY_BUCKET; FUTEX_BUCKETS];
+
+/// SYSCALL_FUTEX_WAIT: `addr`'deki 32-bit değer hâlâ `expected` ise uyandırılana veya
+/// `timeout_ns` dolana kadar bekler.
+/// Değer farklıysa `KError::Busy`, süre dolarsa `KError::TimedOut` döner.
+pub fn futex_wait(addr: u64, expected: u32, timeout_ns: u64) -> Result<(), KError> {
+    let key = FutexKey::from_user_address(addr)?;
+    let bucket = &FUTEX_TABLE[key.bucket()];
+    let deadline = match timeout_ns {
+        FUTEX_WAIT_FOREVER => None,
+        timeout => Some(ktask::now_ns().saturating_add(timeout)),
+    };
+
+    let mut waiters = bucket.waiters.lock();
+    if kmemory::read_user_u32(addr as usize)? != expected {
+        return Err(KError::Busy);
+    }
+
+    let mut node = FutexWaiter { key, thread: ktask::current_thread_id(), woken: false, next: ptr::null_mut() };
+    let node: *mut FutexWaiter = &mut node;
+    unsafe { waiters.push(node) };
+    loop {
+        // Güvenlik: düğüm bu çerçevede yaşar ve kova kilidi tutuluyor.
+        if unsafe { (*node).woken } {
+            return Ok(());
+        }
+        let result = match deadline {
+            None => {
+                waiters = bucket.queue.wait(waiters);
+                WaitResult::Woken
+            }
+            Some(deadline) => {
+                let (guard, result) = bucket.queue.wait_until(waiters, deadline);
+                waiters = guard;
+                result
+            }
+        };
+        // Zaman aşımıyla futex_wake arasında yarış varsa uyandırma kazanır (düğüm çıkarılmıştır).
+        if result == WaitResult::TimedOut && !unsafe { (*node).woken } {
+            unsafe { waiters.remove(node) };
+            return Err(KError::TimedOut);
+        }
+    }
+}
+
+/// SYSCALL_FUTEX_WAKE: `addr`'de bekleyen en fazla `count` iş parçacığını uyandırır.
+/// Uyandırılan iş parçacığı sayısını döner.
+pub fn futex_wake(addr: u64, count: u64) -> Result<u64, KError> {
+    let key = FutexKey::from_user_address(addr)?;
+    let bucket = &FUTEX_TABLE[key.bucket()];
+
+    let mut waiters = bucket.waiters.lock();
+    let mut woken = 0;
+    let mut node = waiters.head;
+    while woken < count && !node.is_null() {
+        // Güvenlik: listedeki düğümlerin sahipleri kova kilidi tutulurken dönemez.
+        unsafe {
+            let next = (*node).next;
+            if (*node).key == key {
+                waiters.remove(node);
+                (*node).woken = true;
+                // Zaman aşımıyla zaten uyanmışsa kilidi alınca `woken`'ı görür ve başarıyla döner.
+                bucket.queue.wake_thread((*node).thread);
+                woken += 1;
+            }
+            node = next;
+        }
+    }
+    Ok(woken)
+}
@@ -0,0 +1,480 @@
+// Karnal64 ksync: kilit sırası doğrulayıcısı (lockdep). Yalnızca "lockdep" özelliğiyle derlenir.
+// - Her kilit bir sınıfa aittir: isimli kilitler (Spinlock::named, RawMutex::named) aynı isimli
+//   tüm örneklerle sınıfı paylaşır, isimsiz kilitlerin sınıfı kendi adresidir.
+// - Her CPU, tuttuğu kilitlerin yığınını izler. Bağlam değişiminde bu yığın iş parçacığının
+//   kaydına saklanır ve sıradaki iş parçacığınınki yüklenir (uyuyan kilitler iş parçacığıyla gider).
+// - "A tutulurken B alındı" bilgisi sınıflar arası bir sıra grafiğine eklenir. Ters yönde bir yol
+//   varken alma denemesi, sıra terslemesi (olası kilitlenme) olarak raporlanır.
+// - Ayrıca raporlananlar: spinlock tutulurken bloklayan bekleme ve hem kesme bağlamında hem de
+//   kesmeler açıkken alınan (IRQ-güvensiz) spinlock sınıfları.
+// Raporlar çekirdek günlüğüne her iki yığın izi ile yazılır. Doğrulayıcının kendi durumu Spinlock
+// kullanmaz (kendini izlemesin diye), ham atomik bir kilitle korunur.
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+
+use super::super::ktask::{self, MAX_CPUS, MAX_THREADS};
+
+extern "C" {
+    /// Çağıranın yığınındaki dönüş adreslerini (çerçeve işaretçisi yürüyüşü) `frames`'e yazar.
+    /// Yazılan çerçeve sayısını döner (bkz. hardware_specific.h).
+    fn low_level_stack_trace(frames: *mut u64, max_frames: usize) -> usize;
+
+    /// Mevcut CPU'da kesmeler açık mı?
+    fn low_level_interrupts_enabled() -> i32;
+}
+
+/// İzlenebilecek en fazla kilit sınıfı sayısı. Dolunca yeni sınıflar izlenmez (bir kez uyarılır).
+const MAX_CLASSES: usize = 512;
+/// Bir bağlamın aynı anda tutabileceği en fazla kilit sayısı.
+const MAX_HELD: usize = 12;
+/// Saklanan yığın izi derinliği.
+const TRACE_DEPTH: usize = 8;
+/// Yığın izi saklanan en fazla sıra kenarı sayısı (ilk görülenler saklanır).
+const MAX_EDGE_TRACES: usize = 256;
+/// Bu sayıdan sonra raporlar bastırılır; günlük taşmasın diye.
+const MAX_REPORTS: usize = 32;
+
+const NO_CLASS: u16 = u16::MAX;
+
+/// Kilit türü: dönen kilitler bloklayan beklemelerde tutulamaz, uyuyan kilitler tutulabilir.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LockKind {
+    Spin,
+    Sleep,
+}
+
+/// Bir kilidin sınıf bilgisi. Kilit yapısına gömülür.
+pub struct LockClass {
+    name: Option<&'static str>,
+    /// Tablodaki indeks + 1 (0: henüz kaydedilmedi).
+    index: AtomicU32,
+}
+
+impl LockClass {
+    pub const fn new(name: Option<&'static str>) -> Self {
+        LockClass { name, index: AtomicU32::new(0) }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct StackTrace {
+    frames: [u64; TRACE_DEPTH],
+    len: usize,
+}
+
+impl StackTrace {
+    const EMPTY: StackTrace = StackTrace { frames: [0; TRACE_DEPTH], len: 0 };
+
+    fn capture() -> Self {
+        let mut trace = StackTrace::EMPTY;
+        trace.len = unsafe { low_level_stack_trace(trace.frames.as_mut_ptr(), TRACE_DEPTH) }.min(TRACE_DEPTH);
+        trace
+    }
+
+    fn print(&self) {
+        if self.len == 0 {
+            println!("      (yığın izi yok)");
+        }
+        for (i, pc) in self.frames[..self.len].iter().enumerate() {
+            println!("      #{} {:#018x}", i, pc);
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct ClassInfo {
+    /// İsimli sınıflarda isim işaretçisi, isimsizlerde kilit adresi.
+    key: usize,
+    name: Option<&'static str>,
+    kind: LockKind,
+    /// Kesme bağlamında alındığı ilk yer.
+    irq_trace: Option<StackTrace>,
+    /// Kesme dışında, kesmeler açıkken alındığı ilk yer.
+    irqs_enabled_trace: Option<StackTrace>,
+    irq_unsafe_reported: bool,
+}
+
+struct EdgeTrace {
+    from: u16,
+    to: u16,
+    /// `to` alınırken (from tutulurken) yakalanan yığın.
+    trace: StackTrace,
+}
+
+struct Graph {
+    classes: [Option<ClassInfo>; MAX_CLASSES],
+    class_count: usize,
+    /// edges[a] içindeki b biti: a tutulurken b alındı.
+    edges: [[u64; MAX_CLASSES / 64]; MAX_CLASSES],
+    edge_traces: [Option<EdgeTrace>; MAX_EDGE_TRACES],
+    edge_trace_count: usize,
+    full_warned: bool,
+}
+
+#[derive(Copy, Clone)]
+struct HeldLock {
+    class: u16,
+    kind: LockKind,
+    trace: StackTrace,
+}
+
+/// Bir yürütme bağlamının (CPU'da çalışan veya kaydedilmiş iş parçacığı) tuttuğu kilitler.
+#[derive(Copy, Clone)]
+struct HeldLocks {
+    locks: [HeldLock; MAX_HELD],
+    depth: usize,
+}
+
+impl HeldLocks {
+    const EMPTY: HeldLocks = HeldLocks {
+        locks: [HeldLock { class: NO_CLASS, kind: LockKind::Spin, trace: StackTrace::EMPTY }; MAX_HELD],
+        depth: 0,
+    };
+
+    fn held(&self) -> &[HeldLock] {
+        &self.locks[..self.depth]
+    }
+}
+
+struct LockdepState {
+    graph: UnsafeCell<Graph>,
+    /// CPU başına tutulan kilitler. Yalnızca o CPU tarafından değiştirilir.
+    cpu_held: UnsafeCell<[HeldLocks; MAX_CPUS]>,
+    /// Bağlam değişiminde saklanan iş parçacığı kilit yığınları (zamanlayıcı yuva indeksiyle).
+    thread_held: UnsafeCell<[HeldLocks; MAX_THREADS]>,
+}
+
+unsafe impl Sync for LockdepState {}
+
+static STATE: LockdepState = LockdepState {
+    graph: UnsafeCell::new(Graph {
+        classes: [None; MAX_CLASSES],
+        class_count: 0,
+        edges: [[0; MAX_CLASSES / 64]; MAX_CLASSES],
+        edge_traces: [NO_EDGE_TRACE; MAX_EDGE_TRACES],
+        edge_trace_count: 0,
+        full_warned: false,
+    }),
+    cpu_held: UnsafeCell::new([HeldLocks::EMPTY; MAX_CPUS]),
+    thread_held: UnsafeCell::new([HeldLocks::EMPTY; MAX_THREADS]),
+};
+
+const NO_EDGE_TRACE: Option<EdgeTrace> = None;
+const ZERO_DEPTH: AtomicU32 = AtomicU32::new(0);
+const NOT_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+static GRAPH_LOCK: AtomicBool = AtomicBool::new(false);
+static IRQ_DEPTH: [AtomicU32; MAX_CPUS] = [ZERO_DEPTH; MAX_CPUS];
+/// Doğrulayıcı bu CPU'da zaten çalışıyor mu? Rapor yazarken alınan kilitler (örn. konsol)
+/// tekrar doğrulayıcıya girmesin diye kancalar bu durumda hiçbir şey yapmaz.
+static ACTIVE: [AtomicBool; MAX_CPUS] = [NOT_ACTIVE; MAX_CPUS];
+static REPORTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Sıra grafiğine özel erişim. Spinlock kullanılmaz: doğrulayıcı kendi kilidini izlememeli.
+fn with_graph<R>(f: impl FnOnce(&mut Graph) -> R) -> R {
+    while GRAPH_LOCK.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+        core::hint::spin_loop();
+    }
+    let result = f(unsafe { &mut *STATE.graph.get() });
+    GRAPH_LOCK.store(false, Ordering::Release);
+    result
+}
+
+/// Kancaların gövdesini özyinelemeye karşı korur.
+fn guarded(f: impl FnOnce()) {
+    let active = &ACTIVE[ktask::current_cpu()];
+    if active.swap(true, Ordering::Acquire) {
+        return;
+    }
+    f();
+    active.store(false, Ordering::Release);
+}
+
+fn cpu_held() -> &'static mut HeldLocks {
+    // Güvenlik: her CPU yalnızca kendi girdisine erişir. Kesme işleyicileri aldıkları kilitleri
+    // dönmeden bırakır, bu yüzden yığın iç içe kullanımda tutarlı kalır.
+    unsafe { &mut (*STATE.cpu_held.get())[ktask::current_cpu()] }
+}
+
+fn in_irq() -> bool {
+    IRQ_DEPTH[ktask::current_cpu()].load(Ordering::Relaxed) > 0
+}
+
+/// Rapor başlığını yazar; rapor sınırı aşıldıysa `false` döner.
+fn begin_report(title: &str) -> bool {
+    let n = REPORTS.fetch_add(1, Ordering::Relaxed);
+    if n >= MAX_REPORTS {
+        if n == MAX_REPORTS {
+            println!("Karnal64 lockdep: rapor sınırına ulaşıldı, sonraki raporlar bastırılıyor");
+        }
+        return false;
+    }
+    println!("Karnal64 lockdep: UYARI: {} (cpu {})", title, ktask::current_cpu());
+    true
+}
+
+fn print_class(graph: &Graph, class: u16) {
+    match graph.classes[class as usize] {
+        Some(ClassInfo { name: Some(name), .. }) => println!("    {}", name),
+        Some(ClassInfo { key, .. }) => println!("    kilit@{:#x}", key),
+        None => println!("    (bilinmeyen sınıf)"),
+    }
+}
+
+impl Graph {
+    /// Sınıfı kaydeder veya bulur. Tablo doluysa `None` (bu kilit izlenmez).
+    fn class_index(&mut self, class: &LockClass, key: usize, kind: LockKind) -> Option<u16> {
+        let cached = class.index.load(Ordering::Relaxed);
+        if cached != 0 {
+            return Some((cached - 1) as u16);
+        }
+        // İsimli sınıflar isim işaretçisiyle anahtarlanır; aynı metnin farklı kopyaları da eşleşsin diye
+        // isim ayrıca içerikle karşılaştırılır.
+        let key = class.name.map_or(key, |name| name.as_ptr() as usize);
+        let found = self.classes[..self.class_count].iter().position(|c| match (c, class.name) {
+            (Some(c), Some(name)) => c.name == Some(name),
+            (Some(c), None) => c.name.is_none() && c.key == key,
+            (None, _) => false,
+        });
+        let index = match found {
+            Some(index) => index,
+            None if self.class_count < MAX_CLASSES => {
+                let index = self.class_count;
+                self.classes[index] = Some(ClassInfo {
+                    key,
+                    name: class.name,
+                    kind,
+                    irq_trace: None,
+                    irqs_enabled_trace: None,
+                    irq_unsafe_reported: false,
+                });
+                self.class_count += 1;
+                index
+            }
+            None => {
+                if !self.full_warned {
+                    self.full_warned = true;
+                    println!("Karnal64 lockdep: sınıf tablosu doldu, yeni kilit sınıfları izlenmiyor");
+                }
+                return None;
+            }
+        };
+        class.index.store(index as u32 + 1, Ordering::Relaxed);
+        Some(index as u16)
+    }
+
+    fn has_edge(&self, from: u16, to: u16) -> bool {
+        self.edges[from as usize][to as usize / 64] & (1 << (to % 64)) != 0
+    }
+
+    fn add_edge(&mut self, from: u16, to: u16, trace: &StackTrace) {
+        if self.has_edge(from, to) {
+            return;
+        }
+        self.edges[from as usize][to as usize / 64] |= 1 << (to % 64);
+        if self.edge_trace_count < MAX_EDGE_TRACES {
+            self.edge_traces[self.edge_trace_count] = Some(EdgeTrace { from, to, trace: *trace });
+            self.edge_trace_count += 1;
+        }
+    }
+
+    fn edge_trace(&self, from: u16, to: u16) -> Option<&StackTrace> {
+        self.edge_traces[..self.edge_trace_count]
+            .iter()
+            .flatten()
+            .find(|e| e.from == from && e.to == to)
+            .map(|e| &e.trace)
+    }
+
+    /// `from`'dan `to`'ya sıra grafiğinde yol varsa yoldaki ilk kenarın hedefini döner.
+    fn find_path(&self, from: u16, to: u16) -> Option<u16> {
+        let mut visited = [0u64; MAX_CLASSES / 64];
+        // (düğüm, yolun ilk adımı)
+        let mut stack = [(0u16, 0u16); MAX_CLASSES];
+        let mut top = 0;
+        visited[from as usize / 64] |= 1 << (from % 64);
+        for next in 0..self.class_count as u16 {
+            if self.has_edge(from, next) && visited[next as usize / 64] & (1 << (next % 64)) == 0 {
+                visited[next as usize / 64] |= 1 << (next % 64);
+                stack[top] = (next, next);
+                top += 1;
+            }
+        }
+        while top > 0 {
+            top -= 1;
+            let (node, first) = stack[top];
+            if node == to {
+                return Some(first);
+            }
+            for next in 0..self.class_count as u16 {
+                if self.has_edge(node, next) && visited[next as usize / 64] & (1 << (next % 64)) == 0 {
+                    visited[next as usize / 64] |= 1 << (next % 64);
+                    stack[top] = (next, first);
+                    top += 1;
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Kilit alınmaya başlanmadan (dönme/bloklama öncesi) çağrılır: sıra terslemesini denetler.
+pub fn lock_acquiring(class: &LockClass, key: usize, kind: LockKind) {
+    guarded(|| {
+        let held = *cpu_held();
+        with_graph(|graph| {
+            let new = match graph.class_index(class, key, kind) {
+                Some(index) => index,
+                None => return,
+            };
+            for lock in held.held() {
+                if lock.class == new {
+                    if kind == LockKind::Spin && begin_report("özyinelemeli spinlock alma (kesin kilitlenme)") {
+                        print_class(graph, new);
+                        println!("  ilk alındığı yer:");
+                        lock.trace.print();
+                        println!("  tekrar alındığı yer:");
+                        StackTrace::capture().print();
+                    }
+                    continue;
+                }
+                // Yeni kilitten tutulan kilide giden bir yol varsa, daha önce ters sırada alınmışlardır.
+                if let Some(first) = graph.find_path(new, lock.class) {
+                    if begin_report("kilit sırası terslemesi (olası kilitlenme)") {
+                        println!("  tutulan kilit:");
+                        print_class(graph, lock.class);
+                        println!("  alınmaya çalışılan kilit:");
+                        print_class(graph, new);
+                        println!("  mevcut yığın (tutulan kilit varken alma):");
+                        StackTrace::capture().print();
+                        println!("  önceki ters sıra (aşağıdaki kilit, yukarıdaki tutulurken alındı):");
+                        print_class(graph, first);
+                        match graph.edge_trace(new, first) {
+                            Some(trace) => trace.print(),
+                            None => println!("      (yığın izi saklanmadı)"),
+                        }
+                    }
+                }
+            }
+        });
+    });
+}
+
+/// Kilit alındıktan sonra çağrılır: kilidi bağlamın yığınına ekler ve sıra kenarlarını kaydeder.
+/// `trylock` ise kenar eklenmez (beklemeyen alma kilitlenmeye yol açamaz).
+pub fn lock_acquired(class: &LockClass, key: usize, kind: LockKind, trylock: bool) {
+    guarded(|| {
+        let trace = StackTrace::capture();
+        let irqs_enabled = unsafe { low_level_interrupts_enabled() } != 0;
+        let irq = in_irq();
+        let held = cpu_held();
+
+        let index = with_graph(|graph| {
+            let index = graph.class_index(class, key, kind)?;
+            if !trylock {
+                for lock in held.held() {
+                    if lock.class != index {
+                        graph.add_edge(lock.class, index, &trace);
+                    }
+                }
+            }
+            if kind == LockKind::Spin {
+                check_irq_safety(graph, index, irq, irqs_enabled, &trace);
+            }
+            Some(index)
+        });
+
+        if let Some(index) = index {
+            if held.depth < MAX_HELD {
+                held.locks[held.depth] = HeldLock { class: index, kind, trace };
+                held.depth += 1;
+            } else if begin_report("tutulan kilit yığını taştı") {
+                trace.print();
+            }
+        }
+    });
+}
+
+fn check_irq_safety(graph: &mut Graph, index: u16, irq: bool, irqs_enabled: bool, trace: &StackTrace) {
+    let info = match graph.classes[index as usize].as_mut() {
+        Some(info) => info,
+        None => return,
+    };
+    if irq && info.irq_trace.is_none() {
+        info.irq_trace = Some(*trace);
+    } else if !irq && irqs_enabled && info.irqs_enabled_trace.is_none() {
+        info.irqs_enabled_trace = Some(*trace);
+    } else {
+        return;
+    }
+    if info.irq_unsafe_reported {
+        return;
+    }
+    if let (Some(irq_trace), Some(enabled_trace)) = (info.irq_trace, info.irqs_enabled_trace) {
+        info.irq_unsafe_reported = true;
+        if begin_report("IRQ-güvensiz spinlock: kesme bağlamında ve kesmeler açıkken alınıyor") {
+            print_class(graph, index);
+            println!("  kesmeler açıkken alındığı yer:");
+            enabled_trace.print();
+            println!("  kesme bağlamında alındığı yer:");
+            irq_trace.print();
+        }
+    }
+}
+
+/// Kilit bırakıldığında çağrılır. Sıra dışı bırakma desteklenir.
+pub fn lock_release(class: &LockClass, key: usize) {
+    guarded(|| {
+        let index = class.index.load(Ordering::Relaxed);
+        if index == 0 {
+            return;
+        }
+        let index = (index - 1) as u16;
+        let held = cpu_held();
+        if let Some(pos) = held.held().iter().rposition(|l| l.class == index) {
+            held.locks.copy_within(pos + 1..held.depth, pos);
+            held.depth -= 1;
+        }
+    });
+}
+
+/// Bloklayan bir beklemeye girmeden hemen önce çağrılır (WaitQueue, wait_any).
+/// Bu noktada tutulan spinlock'lar raporlanır; uyuyan kilitlerin tutulması serbesttir.
+pub fn check_blocking() {
+    guarded(|| {
+        let held = *cpu_held();
+        if let Some(lock) = held.held().iter().find(|l| l.kind == LockKind::Spin) {
+            with_graph(|graph| {
+                if begin_report("spinlock tutulurken bloklayan bekleme") {
+                    print_class(graph, lock.class);
+                    println!("  kilidin alındığı yer:");
+                    lock.trace.print();
+                    println!("  bekleme yeri:");
+                    StackTrace::capture().print();
+                }
+            });
+        }
+    });
+}
+
+/// ktask::schedule tarafından bağlam değişiminden hemen önce çağrılır. CPU'nun kilit yığınını
+/// giden iş parçacığının kaydına saklar ve gelen iş parçacığınınkini yükler.
+pub fn context_switch(prev_slot: usize, next_slot: usize) {
+    let held = cpu_held();
+    // Güvenlik: bir iş parçacığı yuvası yalnızca o iş parçacığının çalıştığı CPU'dan, zamanlayıcı
+    // onu seçtikten sonra kullanılır.
+    let saved = unsafe { &mut *STATE.thread_held.get() };
+    saved[prev_slot] = *held;
+    *held = saved[next_slot];
+}
+
+/// Mimariye özel kesme girişinde çağrılır.
+pub fn irq_enter() {
+    IRQ_DEPTH[ktask::current_cpu()].fetch_add(1, Ordering::Relaxed);
+}
+
+/// Mimariye özel kesme çıkışında çağrılır.
+pub fn irq_exit() {
+    IRQ_DEPTH[ktask::current_cpu()].fetch_sub(1, Ordering::Relaxed);
+}
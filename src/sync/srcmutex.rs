@@ -0,0 +1,453 @@
+// Karnal64 ksync: uyuyan (bloklayan) çekirdek Mutex'i.
+// - Sahibini (KThreadId) kaydeder; özyinelemeli kilitleme ve sahibi olmayanın bırakması hata döner.
+// - Öncelik mirası (priority inheritance): bekleyen yüksek öncelikli iş parçacığı,
+//   kilidi tutan düşük öncelikli iş parçacığının önceliğini kilit bırakılana kadar yükseltir.
+//   Sahip de başka bir mutex'i bekliyorsa miras zincir boyunca iletilir; bırakılan kilidin mirası
+//   düşer, hâlâ tutulan kilitlerin bekleyenlerinden gelen miras korunur.
+// - Kullanıcı alanına SYSCALL_LOCK_CREATE/ACQUIRE/RELEASE ile handle olarak açılır; SYSCALL_WAIT_MANY
+//   ile diğer senkronizasyon nesneleriyle birlikte beklenebilir.
+
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+
+use super::lockdep::{self, LockClass, LockKind};
+use super::{Spinlock, WaitQueue, Waitable};
+use super::super::{KError, KHandle, KTaskId, KThreadId, LockProvider};
+use super::super::ktask::{self, Priority, PRIORITY_IDLE};
+
+/// Öncelik mirasının iletildiği en uzun bekleme zinciri. Zincirdeki bir döngü (kilitlenme)
+/// iletimi sonsuza kadar sürdürmesin diye sınırlıdır.
+const PI_CHAIN_MAX: usize = 16;
+
+struct MutexState {
+    owner: Option<KThreadId>,
+    /// Bekleyenler arasındaki en yüksek öncelik. Sahibe miras olarak verilir.
+    /// Bekleyen kalmadığında PRIORITY_IDLE'a döner.
+    top_waiter_priority: Priority,
+    waiter_count: usize,
+    /// Kullanıcı kilidi yok edildi (`lock_destroy`); alma girişimleri `KError::BadHandle` döner.
+    destroyed: bool,
+}
+
+/// Ham (veri sarmalamayan) uyuyan mutex. LockProvider trait'ini implemente eder.
+pub struct RawMutex {
+    state: Spinlock<MutexState>,
+    waiters: WaitQueue,
+    class: LockClass,
+}
+
+impl Default for RawMutex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RawMutex {
+    pub const fn new() -> Self {
+        RawMutex::with_class(LockClass::new(None))
+    }
+
+    /// İsimli mutex; aynı isimli mutex'ler lockdep'te tek sınıf olarak izlenir.
+    pub const fn named(name: &'static str) -> Self {
+        RawMutex::with_class(LockClass::new(Some(name)))
+    }
+
+    const fn with_class(class: LockClass) -> Self {
+        RawMutex {
+            state: Spinlock::named(
+                MutexState { owner: None, top_waiter_priority: PRIORITY_IDLE, waiter_count: 0, destroyed: false },
+                "RawMutex.state",
+            ),
+            waiters: WaitQueue::new(),
+            class,
+        }
+    }
+
+    fn lockdep_key(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    /// Öncelik mirası anahtarı: mutex'in adresi (bkz. `propagate_priority`).
+    fn pi_key(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    /// Kilidin şu anki sahibi.
+    pub fn owner(&self) -> Option<KThreadId> {
+        self.state.lock().owner
+    }
+
+    /// Kilidi almaya çalışır, başka bir iş parçacığı tutuyorsa uyur.
+    /// Çağıran zaten sahipse `KError::Deadlock` döner.
+    pub fn acquire(&self) -> Result<(), KError> {
+        let me = ktask::current_thread_id();
+        let my_priority = ktask::thread_priority(me).unwrap_or(PRIORITY_IDLE);
+        // Sahiplik yalnızca sahibin kendisi tarafından değiştirilir; kontrol lockdep'e haber
+        // vermeden önce yapılır ki `lock_acquiring` eşleşmesiz kalmasın.
+        if self.owner() == Some(me) {
+            return Err(KError::Deadlock);
+        }
+
+        // lockdep kancaları state spinlock'ı tutulmadan çağrılır; aksi halde iç spinlock
+        // mutex sınıflarıyla sıra ilişkisine girer ve sahte terslemeler raporlanır.
+        lockdep::lock_acquiring(&self.class, self.lockdep_key(), LockKind::Sleep);
+        let mut state = self.state.lock();
+        loop {
+            if state.destroyed {
+                return Err(KError::BadHandle);
+            }
+            match state.owner {
+                None => {
+                    state.owner = Some(me);
+                    // Hâlâ bekleyen varsa yeni sahip onların önceliğini miras alır.
+                    let inherited = (state.waiter_count > 0).then_some(state.top_waiter_priority);
+                    if inherited.is_none() {
+                        state.top_waiter_priority = PRIORITY_IDLE;
+                    }
+                    drop(state);
+                    if let Some(priority) = inherited {
+                        ktask::boost_priority(me, self.pi_key(), priority);
+                    }
+                    lockdep::lock_acquired(&self.class, self.lockdep_key(), LockKind::Sleep, false);
+                    return Ok(());
+                }
+                Some(owner) => {
+                    if state.top_waiter_priority < my_priority {
+                        state.top_waiter_priority = my_priority;
+                    }
+                    state.waiter_count += 1;
+                    // Öncelik mirası: sahibi (ve onun beklediği kilitlerin sahiplerini) en az bizim
+                    // önceliğimize yükselt. Zincirdeki mutex'lerin state kilitleri alınacağı için
+                    // bu kilit önce bırakılır.
+                    drop(state);
+                    ktask::set_blocked_on_lock(self.pi_key());
+                    propagate_priority(owner, self.pi_key(), my_priority);
+
+                    // Arada kilit bırakıldıysa uyanma kaçırılmış olabilir; sahip yoksa uyumadan yeniden dene.
+                    state = self.state.lock();
+                    if state.owner.is_some() {
+                        state = self.waiters.wait(state);
+                    }
+                    ktask::set_blocked_on_lock(0);
+                    state.waiter_count -= 1;
+                    if state.waiter_count == 0 {
+                        state.top_waiter_priority = PRIORITY_IDLE;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Beklemeden almayı dener. Kilit meşgulse `KError::Busy` döner.
+    pub fn try_acquire(&self) -> Result<(), KError> {
+        let me = ktask::current_thread_id();
+        let mut state = self.state.lock();
+        if state.destroyed {
+            return Err(KError::BadHandle);
+        }
+        match state.owner {
+            None => {
+                state.owner = Some(me);
+                drop(state);
+                lockdep::lock_acquired(&self.class, self.lockdep_key(), LockKind::Sleep, true);
+                Ok(())
+            }
+            Some(owner) if owner == me => Err(KError::Deadlock),
+            Some(_) => Err(KError::Busy),
+        }
+    }
+
+    /// Kilidi bırakır. Çağıran sahip değilse `KError::PermissionDenied` döner.
+    pub fn release(&self) -> Result<(), KError> {
+        let me = ktask::current_thread_id();
+        let mut state = self.state.lock();
+        if state.owner != Some(me) {
+            return Err(KError::PermissionDenied);
+        }
+        // Sıradaki sahip kilit altında belirlenir: bekleyen varsa kuyruğun başındaki uyandırılır ve
+        // kilidi o alır. Öncelik geri yükleme ve uyandırma zamanlayıcı kilidini aldığından state
+        // kilidi önce bırakılır; böylece propagate_priority ile sıra terslemesi oluşmaz.
+        state.owner = None;
+        let wake_next = state.waiter_count > 0;
+        drop(state);
+        lockdep::lock_release(&self.class, self.lockdep_key());
+        // Bu kilitten miras alınan öncelik bırakılır; diğer tutulan kilitlerinki korunur.
+        ktask::restore_priority(me, self.pi_key());
+        if wake_next {
+            self.waiters.wake_one();
+        }
+        Ok(())
+    }
+
+    /// Kullanıcı kilidini yok edilmiş olarak işaretler. Kilit tutuluyorsa veya bekleyeni varsa
+    /// `KError::Busy` döner. Kontrol ve işaretleme aynı state kilidi altında yapılır: bekleyenler
+    /// uyumadan önce bu kilit altında sayıldığından, sonradan gelenler `KError::BadHandle` alır.
+    fn destroy(&self) -> Result<(), KError> {
+        let mut state = self.state.lock();
+        if state.destroyed {
+            return Err(KError::BadHandle);
+        }
+        if state.owner.is_some() || state.waiter_count > 0 {
+            return Err(KError::Busy);
+        }
+        state.destroyed = true;
+        Ok(())
+    }
+}
+
+/// Öncelik mirasını `owner`'dan başlayarak bekleme zinciri boyunca iletir: `owner`, `lock`
+/// anahtarlı kilit için en az `priority`'ye yükseltilir; kendisi de bir mutex bekliyorsa o mutex'in
+/// sahibi de yükseltilir ve bu böyle devam eder.
+fn propagate_priority(mut owner: KThreadId, mut lock: usize, priority: Priority) {
+    for _ in 0..PI_CHAIN_MAX {
+        let next = match ktask::boost_priority(owner, lock, priority) {
+            Some(next) => next,
+            None => return,
+        };
+        // Güvenlik: anahtar, bir iş parçacığının üzerinde uyuduğu RawMutex'in adresidir; bekleyeni
+        // olan mutex yerinde kalır (kullanıcı kilitleri statik tabloda, çekirdek kilitleri
+        // sarmaladıkları verinin içinde).
+        let mutex = unsafe { &*(next as *const RawMutex) };
+        let mut state = mutex.state.lock();
+        // Sahip bu arada kilidi almış ve bekleyen kalmamışsa zincir burada biter.
+        if state.waiter_count == 0 {
+            return;
+        }
+        // Bekleyen yükseltildi; mutex'in sonraki sahibi de bu önceliği miras almalı.
+        if state.top_waiter_priority < priority {
+            state.top_waiter_priority = priority;
+        }
+        owner = match state.owner {
+            Some(next_owner) => next_owner,
+            None => return,
+        };
+        lock = next;
+    }
+}
+
+impl LockProvider for RawMutex {
+    fn acquire(&self) -> Result<(), KError> {
+        RawMutex::acquire(self)
+    }
+
+    fn release(&self) -> Result<(), KError> {
+        RawMutex::release(self)
+    }
+}
+
+// wait_any ile beklenen kilit, sinyallendiğinde alınır. Bekleyen, acquire'daki gibi sayılır ve
+// önceliğini sahibe miras bırakır; birden fazla kilit beklenirse zincir sonuncusu üzerinden iletilir.
+impl Waitable for RawMutex {
+    fn wait_queue(&self) -> &WaitQueue {
+        &self.waiters
+    }
+
+    fn begin_wait(&self) -> Result<(), KError> {
+        let me = ktask::current_thread_id();
+        let my_priority = ktask::thread_priority(me).unwrap_or(PRIORITY_IDLE);
+        let mut state = self.state.lock();
+        if state.destroyed {
+            return Err(KError::BadHandle);
+        }
+        if state.owner == Some(me) {
+            return Err(KError::Deadlock);
+        }
+        if state.top_waiter_priority < my_priority {
+            state.top_waiter_priority = my_priority;
+        }
+        state.waiter_count += 1;
+        let owner = state.owner;
+        drop(state);
+        if let Some(owner) = owner {
+            ktask::set_blocked_on_lock(self.pi_key());
+            propagate_priority(owner, self.pi_key(), my_priority);
+        }
+        Ok(())
+    }
+
+    fn end_wait(&self) {
+        let me = ktask::current_thread_id();
+        ktask::set_blocked_on_lock(0);
+        let mut state = self.state.lock();
+        state.waiter_count -= 1;
+        if state.waiter_count == 0 {
+            state.top_waiter_priority = PRIORITY_IDLE;
+        }
+        // Kilidi bu bekleme aldıysa kalan bekleyenlerin önceliği miras alınır (bkz. acquire).
+        let inherited = (state.owner == Some(me) && state.waiter_count > 0).then_some(state.top_waiter_priority);
+        drop(state);
+        if let Some(priority) = inherited {
+            ktask::boost_priority(me, self.pi_key(), priority);
+        }
+    }
+
+    fn try_consume(&self) -> bool {
+        self.try_acquire().is_ok()
+    }
+
+    fn is_signaled(&self) -> bool {
+        self.owner().is_none()
+    }
+}
+
+/// Veriyi sarmalayan uyuyan mutex. Guard düşürülünce kilit bırakılır.
+pub struct Mutex<T: ?Sized = ()> {
+    raw: RawMutex,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
+unsafe impl<T: ?Sized + Send> Send for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    pub const fn new(data: T) -> Self {
+        Mutex { raw: RawMutex::new(), data: UnsafeCell::new(data) }
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// Kilidi alır (gerekirse uyur). Özyinelemeli kilitlemede `KError::Deadlock` döner.
+    pub fn lock(&self) -> Result<MutexGuard<'_, T>, KError> {
+        self.raw.acquire()?;
+        Ok(MutexGuard { mutex: self })
+    }
+
+    pub fn try_lock(&self) -> Result<MutexGuard<'_, T>, KError> {
+        self.raw.try_acquire()?;
+        Ok(MutexGuard { mutex: self })
+    }
+}
+
+pub struct MutexGuard<'a, T: ?Sized> {
+    mutex: &'a Mutex<T>,
+}
+
+impl<'a, T: ?Sized> Drop for MutexGuard<'a, T> {
+    fn drop(&mut self) {
+        // Guard yalnızca sahip iş parçacığında var olabilir, bu yüzden release başarısız olamaz.
+        let _ = self.mutex.raw.release();
+    }
+}
+
+impl<'a, T: ?Sized> Deref for MutexGuard<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized> DerefMut for MutexGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+// --- Kullanıcı Alanı Kilit Handle'ları (SYSCALL_LOCK_*) ---
+
+/// Aynı anda var olabilecek en fazla kullanıcı kilidi sayısı.
+const MAX_LOCKS: usize = 128;
+
+/// Kilit handle değerleri bu etiketle başlar; diğer handle türleriyle (IPC kanalı vb.) çakışmaz.
+const LOCK_HANDLE_TAG: u64 = 0x4C << 56; // 'L'
+
+/// Statik kilit yuvası. Mutex yerinde kalır (taşınmaz), böylece bekleyiciler ona güvenle
+/// referans tutabilir. Nesil (generation) sayacı, serbest bırakılıp yeniden kullanılan
+/// yuvalara eski handle'larla erişilmesini engeller. `owner`, kilidi oluşturan görevdir.
+struct LockSlot {
+    in_use: AtomicBool,
+    generation: AtomicU32,
+    owner: AtomicU64,
+    mutex: RawMutex,
+}
+
+impl LockSlot {
+    const fn new() -> Self {
+        LockSlot {
+            in_use: AtomicBool::new(false),
+            generation: AtomicU32::new(0),
+            owner: AtomicU64::new(0),
+            mutex: RawMutex::new(),
+        }
+    }
+}
+
+const EMPTY_LOCK_SLOT: LockSlot = LockSlot::new();
+static LOCK_TABLE: [LockSlot; MAX_LOCKS] = [EMPTY_LOCK_SLOT; MAX_LOCKS];
+
+fn encode_lock_handle(index: usize, generation: u32) -> KHandle {
+    KHandle(LOCK_HANDLE_TAG | ((generation as u64) << 16) | (index as u64 + 1))
+}
+
+pub(super) fn lookup_lock(handle_value: u64) -> Result<&'static RawMutex, KError> {
+    if handle_value & (0xFF << 56) != LOCK_HANDLE_TAG {
+        return Err(KError::BadHandle);
+    }
+    let index = (handle_value & 0xFFFF) as usize;
+    let generation = ((handle_value >> 16) & 0xFFFF_FFFF) as u32;
+    if index == 0 || index > MAX_LOCKS {
+        return Err(KError::BadHandle);
+    }
+    let slot = &LOCK_TABLE[index - 1];
+    if !slot.in_use.load(Ordering::Acquire) || slot.generation.load(Ordering::Acquire) != generation {
+        return Err(KError::BadHandle);
+    }
+    Ok(&slot.mutex)
+}
+
+/// SYSCALL_LOCK_CREATE: yeni bir kilit oluşturur ve handle'ını döner. Handle, oluşturan görevin
+/// handle kotasından düşülür (`ktask::QUOTA_HANDLES`).
+pub fn lock_create() -> Result<KHandle, KError> {
+    ktask::quota_charge(ktask::QUOTA_HANDLES, 1)?;
+    for (index, slot) in LOCK_TABLE.iter().enumerate() {
+        if slot
+            .in_use
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Relaxed)
+            .is_ok()
+        {
+            slot.owner.store(ktask::current_task_id().0, Ordering::Release);
+            slot.mutex.state.lock().destroyed = false;
+            let generation = slot.generation.load(Ordering::Acquire);
+            return Ok(encode_lock_handle(index, generation));
+        }
+    }
+    ktask::quota_uncharge(ktask::QUOTA_HANDLES, 1);
+    Err(KError::OutOfMemory)
+}
+
+/// SYSCALL_LOCK_ACQUIRE: kilidi alır, gerekirse çağıranı bloklar.
+pub fn lock_acquire(handle_value: u64) -> Result<(), KError> {
+    lookup_lock(handle_value)?.acquire()
+}
+
+/// SYSCALL_LOCK_RELEASE: kilidi bırakır. Yalnızca sahip bırakabilir.
+pub fn lock_release(handle_value: u64) -> Result<(), KError> {
+    lookup_lock(handle_value)?.release()
+}
+
+/// Kilit handle'ını yok eder. Kilit tutuluyorsa veya bekleyeni varsa `KError::Busy` döner.
+/// kresource::release_handle tarafından kilit handle'ları için çağrılır.
+pub fn lock_destroy(handle_value: u64) -> Result<(), KError> {
+    lookup_lock(handle_value)?.destroy()?;
+    let index = (handle_value & 0xFFFF) as usize - 1;
+    let slot = &LOCK_TABLE[index];
+    // Nesli artırarak eski handle'ları geçersiz kıl, sonra yuvayı serbest bırak.
+    slot.generation.fetch_add(1, Ordering::AcqRel);
+    ktask::quota_uncharge_task(KTaskId(slot.owner.load(Ordering::Acquire)), ktask::QUOTA_HANDLES, 1);
+    slot.in_use.store(false, Ordering::Release);
+    Ok(())
+}
+
+/// `task` görevinin oluşturduğu kilit handle'larını yok eder; ktask görevi toplarken çağırır.
+/// Hâlâ tutulan kilitler (handle başka bir göreve geçmiş) çekirdek görevine devredilir.
+pub(super) fn release_task_locks(task: KTaskId) {
+    for (index, slot) in LOCK_TABLE.iter().enumerate() {
+        if !slot.in_use.load(Ordering::Acquire) || slot.owner.load(Ordering::Acquire) != task.0 {
+            continue;
+        }
+        let handle = encode_lock_handle(index, slot.generation.load(Ordering::Acquire));
+        if lock_destroy(handle.0) == Err(KError::Busy) {
+            slot.owner.store(0, Ordering::Release);
+        }
+    }
+}
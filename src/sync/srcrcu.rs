@@ -0,0 +1,225 @@
+// Karnal64 ksync: RCU (read-copy-update).
+// - Okuyucular kilit almaz: `read_lock` yalnızca CPU başına bir derinlik sayacını artırır,
+//   yayınlanmış sürüm tek bir atomik yükleme ile okunur.
+// - Yazıcı yeni sürümü hazırlar ve `RcuCell::publish` ile atomik olarak yayınlar. Eski sürüm,
+//   o an okuma bölümünde olan tüm okuyucular çıkana kadar (grace period) geri kullanılamaz.
+// - Grace period, çevrimiçi her CPU'nun bir "sessiz durumdan" (quiescent state) geçmesiyle biter.
+//   Sessiz durum, okuma bölümü dışında yapılan bağlam değişimidir (ktask::schedule) veya idle
+//   iş parçacığındayken gelen zamanlayıcı kesmesidir (ktask::timer_tick).
+// - Eski sürüm ya `synchronize` ile beklenerek ya da `call_rcu` ile ertelenerek geri alınır.
+// Çekirdekte yığın (heap) olmadığından sürümlerin belleği çağıran tarafından yönetilir
+// (statik tablolar, handle yuvaları vb.); RCU yalnızca ne zaman geri kullanılabileceğini söyler.
+//
+// Kurallar:
+// - Okuma bölümünde bloklanılmaz ve schedule çağrılmaz.
+// - Aynı RcuCell'e yazan yazıcılar kendi aralarında ayrı bir kilitle sıralanmalıdır.
+
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicPtr, AtomicU32, AtomicU64, Ordering};
+
+use super::super::KError;
+use super::super::ktask::{self, MAX_CPUS};
+use super::{lockdep, Spinlock};
+
+/// call_rcu ile aynı anda bekleyebilecek en fazla geri çağırma sayısı.
+const MAX_CALLBACKS: usize = 64;
+
+/// CPU başına okuma bölümü iç içelik derinliği.
+const ZERO_DEPTH: AtomicU32 = AtomicU32::new(0);
+static READ_DEPTH: [AtomicU32; MAX_CPUS] = [ZERO_DEPTH; MAX_CPUS];
+
+/// RCU'ya katılan (sessiz durum bildirmesi beklenen) CPU'ların maskesi. Boot CPU'su başlangıçta çevrimiçidir.
+static ONLINE_CPUS: AtomicU64 = AtomicU64::new(1);
+
+/// Devam eden grace period için henüz sessiz durum bildirmemiş CPU'lar.
+/// Hızlı yol (bildirecek bir şey yok) kilit almadan bu maske üzerinden karar verir.
+static QS_PENDING: AtomicU64 = AtomicU64::new(0);
+
+/// Tamamlanmış grace period sayısı. Kilitsiz okunabilir; yalnızca GP kilidi altında artırılır.
+static GP_COMPLETED: AtomicU64 = AtomicU64::new(0);
+
+struct GpState {
+    /// Bir grace period şu an sürüyor mu.
+    in_progress: bool,
+    /// İstenen en yüksek grace period sırası. `GP_COMPLETED` buna ulaşana kadar yenileri başlatılır.
+    requested: u64,
+}
+
+static GP: Spinlock<GpState> = Spinlock::named(GpState { in_progress: false, requested: 0 }, "rcu::GP");
+
+#[derive(Copy, Clone)]
+struct RcuCallback {
+    func: fn(usize),
+    arg: usize,
+    /// Bu grace period tamamlanınca çalıştırılır.
+    target: u64,
+}
+
+static CALLBACKS: Spinlock<[Option<RcuCallback>; MAX_CALLBACKS]> =
+    Spinlock::named([None; MAX_CALLBACKS], "rcu::CALLBACKS");
+
+// --- Okuyucu tarafı ---
+
+/// Okuma bölümü guard'ı. Düşürülünce (drop) bölüm biter.
+/// Bölüm CPU'ya bağlı olduğu için guard başka bir iş parçacığına verilemez.
+pub struct RcuReadGuard {
+    cpu: usize,
+    _not_send: PhantomData<*const ()>,
+}
+
+/// Okuma bölümüne girer. İç içe çağrılabilir.
+pub fn read_lock() -> RcuReadGuard {
+    let cpu = ktask::current_cpu();
+    READ_DEPTH[cpu].fetch_add(1, Ordering::Acquire);
+    RcuReadGuard { cpu, _not_send: PhantomData }
+}
+
+impl Drop for RcuReadGuard {
+    fn drop(&mut self) {
+        READ_DEPTH[self.cpu].fetch_sub(1, Ordering::Release);
+    }
+}
+
+/// Mevcut CPU bir okuma bölümündeyse `true` döner. Zamanlayıcı, okuma bölümündeki bir iş
+/// parçacığını kesintiye uğratıp başka birine geçmemelidir.
+pub fn in_read_section() -> bool {
+    READ_DEPTH[ktask::current_cpu()].load(Ordering::Relaxed) > 0
+}
+
+/// RCU ile korunan, yayınlanmış bir sürüme işaretçi.
+pub struct RcuCell<T: Sync + 'static> {
+    ptr: AtomicPtr<T>,
+}
+
+impl<T: Sync + 'static> RcuCell<T> {
+    pub const fn new(initial: &'static T) -> Self {
+        RcuCell { ptr: AtomicPtr::new(initial as *const T as *mut T) }
+    }
+
+    /// Yayınlanmış sürümü okur. Dönen referans okuma bölümü boyunca geçerlidir.
+    pub fn read<'g>(&self, _guard: &'g RcuReadGuard) -> &'g T {
+        unsafe { &*self.ptr.load(Ordering::Acquire) }
+    }
+
+    /// `new`'i yayınlar ve önceki sürümü döner. Önceki sürüm, `synchronize` dönene veya
+    /// `call_rcu` ile verilen geri çağırma çalışana kadar değiştirilmemeli ve geri kullanılmamalıdır.
+    pub fn publish(&self, new: &'static T) -> &'static T {
+        unsafe { &*self.ptr.swap(new as *const T as *mut T, Ordering::AcqRel) }
+    }
+
+    /// `publish` + `synchronize`: dönen önceki sürüm artık hiçbir okuyucu tarafından görülmez.
+    pub fn replace(&self, new: &'static T) -> Result<&'static T, KError> {
+        let old = self.publish(new);
+        synchronize()?;
+        Ok(old)
+    }
+}
+
+// --- Grace period ---
+
+/// Yeni bir grace period ister ve bu isteği karşılayacak sıra numarasını döner.
+/// Zaten süren bir grace period, istekten önce başlamış olabileceği için yeterli sayılmaz.
+fn request_grace_period() -> u64 {
+    let mut gp = GP.lock_irqsave();
+    let completed = GP_COMPLETED.load(Ordering::Acquire);
+    let target = if gp.in_progress { completed + 2 } else { completed + 1 };
+    if target > gp.requested {
+        gp.requested = target;
+    }
+    if !gp.in_progress {
+        start_grace_period(&mut gp);
+    }
+    target
+}
+
+fn start_grace_period(gp: &mut GpState) {
+    gp.in_progress = true;
+    QS_PENDING.store(ONLINE_CPUS.load(Ordering::Acquire), Ordering::Release);
+}
+
+/// Mevcut CPU için sessiz durum bildirir. ktask::schedule (okuma bölümü dışında) ve idle
+/// iş parçacığındaki timer_tick tarafından çağrılır. Süresi dolan geri çağırmaları da çalıştırır.
+pub fn quiescent_state() {
+    let cpu = ktask::current_cpu();
+    if READ_DEPTH[cpu].load(Ordering::Relaxed) > 0 {
+        return;
+    }
+    let bit = 1u64 << cpu;
+    if QS_PENDING.load(Ordering::Acquire) & bit != 0 {
+        report_quiescent(bit);
+    }
+    run_ready_callbacks();
+}
+
+fn report_quiescent(bit: u64) {
+    let mut gp = GP.lock_irqsave();
+    let remaining = QS_PENDING.fetch_and(!bit, Ordering::AcqRel) & !bit;
+    if remaining != 0 || !gp.in_progress {
+        return;
+    }
+    let completed = GP_COMPLETED.fetch_add(1, Ordering::AcqRel) + 1;
+    gp.in_progress = false;
+    if gp.requested > completed {
+        start_grace_period(&mut gp);
+    }
+}
+
+/// İstekten önce başlamış tüm okuma bölümleri bitene kadar bekler.
+/// Okuma bölümü içinden çağrılırsa kendini bekleyeceği için `KError::Deadlock` döner.
+pub fn synchronize() -> Result<(), KError> {
+    if in_read_section() {
+        return Err(KError::Deadlock);
+    }
+    lockdep::check_blocking();
+    let target = request_grace_period();
+    while GP_COMPLETED.load(Ordering::Acquire) < target {
+        // schedule kendi CPU'muz için sessiz durumu bildirir; diğer CPU'lar kendi geçişlerinde bildirir.
+        ktask::yield_now()?;
+    }
+    Ok(())
+}
+
+/// Bir sonraki tam grace period'dan sonra `func(arg)`'ı çalıştırır (örn. eski sürümün yuvasını
+/// serbest bırakmak için). Geri çağırma kesmeler kapalıyken çalışabilir; kısa olmalı ve bloklamamalıdır.
+/// Kuyruk doluysa `KError::OutOfMemory` döner; çağıran `synchronize`'a geri dönebilir.
+pub fn call_rcu(func: fn(usize), arg: usize) -> Result<(), KError> {
+    let target = request_grace_period();
+    let mut callbacks = CALLBACKS.lock_irqsave();
+    let slot = callbacks.iter_mut().find(|c| c.is_none()).ok_or(KError::OutOfMemory)?;
+    *slot = Some(RcuCallback { func, arg, target });
+    Ok(())
+}
+
+fn run_ready_callbacks() {
+    let completed = GP_COMPLETED.load(Ordering::Acquire);
+    loop {
+        // Geri çağırmalar kilit dışında çalıştırılır; içlerinden call_rcu çağrılabilir.
+        let ready = {
+            let mut callbacks = CALLBACKS.lock_irqsave();
+            callbacks
+                .iter_mut()
+                .find(|c| matches!(c, Some(c) if c.target <= completed))
+                .and_then(|c| c.take())
+        };
+        match ready {
+            Some(callback) => (callback.func)(callback.arg),
+            None => break,
+        }
+    }
+}
+
+// --- CPU yönetimi ---
+
+/// `cpu`'yu RCU'ya katar. Sonraki grace period'lar bu CPU'nun sessiz durumunu da bekler.
+pub fn cpu_online(cpu: usize) {
+    ONLINE_CPUS.fetch_or(1u64 << cpu, Ordering::AcqRel);
+}
+
+/// `cpu`'yu RCU'dan çıkarır; süren grace period onu artık beklemez.
+pub fn cpu_offline(cpu: usize) {
+    let bit = 1u64 << cpu;
+    ONLINE_CPUS.fetch_and(!bit, Ordering::AcqRel);
+    if QS_PENDING.load(Ordering::Acquire) & bit != 0 {
+        report_quiescent(bit);
+    }
+}
@@ -0,0 +1,116 @@
+// Karnal64 ksync: okuyucu-yazıcı kilidi.
+// - Aynı anda birden fazla okuyucu veya tek bir yazıcı.
+// - Yazıcı öncelikli: bekleyen bir yazıcı varken yeni okuyucular kabul edilmez (yazıcı açlığı önlenir).
+// - wait_any ile beklendiğinde yazma (özel) kilidi alınır.
+
+use super::super::{KError, KThreadId};
+use super::super::ktask;
+use super::{wait_any, Spinlock, WaitQueue, Waitable};
+
+struct RwState {
+    readers: u32,
+    writer: Option<KThreadId>,
+    waiting_writers: u32,
+}
+
+pub struct RwLock {
+    state: Spinlock<RwState>,
+    waiters: WaitQueue,
+}
+
+impl Default for RwLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RwLock {
+    pub const fn new() -> Self {
+        RwLock {
+            state: Spinlock::named(RwState { readers: 0, writer: None, waiting_writers: 0 }, "RwLock.state"),
+            waiters: WaitQueue::new(),
+        }
+    }
+
+    /// Okuma (paylaşımlı) kilidini alır. Çağıran yazma kilidini tutuyorsa `KError::Deadlock` döner.
+    pub fn read_acquire(&self) -> Result<(), KError> {
+        let me = ktask::current_thread_id();
+        let mut state = self.state.lock();
+        loop {
+            match state.writer {
+                Some(writer) if writer == me => return Err(KError::Deadlock),
+                None if state.waiting_writers == 0 => {
+                    state.readers += 1;
+                    return Ok(());
+                }
+                _ => state = self.waiters.wait(state),
+            }
+        }
+    }
+
+    /// Yazma (özel) kilidini alır; en fazla `timeout_ns` nanosaniye bekler.
+    pub fn write_acquire(&self, timeout_ns: u64) -> Result<(), KError> {
+        if self.state.lock().writer == Some(ktask::current_thread_id()) {
+            return Err(KError::Deadlock);
+        }
+        wait_any(&[self], timeout_ns).map(|_| ())
+    }
+
+    /// Tutulan kilidi bırakır. Çağıran yazıcıysa yazma kilidi, değilse bir okuma kilidi bırakılır.
+    /// Hiçbir kilit tutulmuyorsa `KError::PermissionDenied` döner.
+    pub fn release(&self) -> Result<(), KError> {
+        let me = ktask::current_thread_id();
+        let mut state = self.state.lock();
+        if state.writer == Some(me) {
+            state.writer = None;
+        } else if state.writer.is_none() && state.readers > 0 {
+            // Not: Okuyucular tek tek izlenmez; herhangi bir iş parçacığı bir okuma kilidini bırakabilir.
+            state.readers -= 1;
+            if state.readers > 0 {
+                return Ok(());
+            }
+        } else {
+            return Err(KError::PermissionDenied);
+        }
+        drop(state);
+        // Okuyucular ve yazıcılar aynı kuyrukta bekler; hepsi uyandırılır, koşulu sağlayanlar geçer.
+        self.waiters.wake_all();
+        Ok(())
+    }
+}
+
+impl Waitable for RwLock {
+    fn wait_queue(&self) -> &WaitQueue {
+        &self.waiters
+    }
+
+    fn try_consume(&self) -> bool {
+        let mut state = self.state.lock();
+        if state.readers == 0 && state.writer.is_none() {
+            state.writer = Some(ktask::current_thread_id());
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_signaled(&self) -> bool {
+        let state = self.state.lock();
+        state.readers == 0 && state.writer.is_none()
+    }
+
+    fn begin_wait(&self) -> Result<(), KError> {
+        self.state.lock().waiting_writers += 1;
+        Ok(())
+    }
+
+    fn end_wait(&self) {
+        let mut state = self.state.lock();
+        state.waiting_writers -= 1;
+        // Son bekleyen yazıcı vazgeçtiyse (zaman aşımı), yazıcı yüzünden bekleyen okuyucuları serbest bırak.
+        if state.waiting_writers == 0 && state.writer.is_none() {
+            drop(state);
+            self.waiters.wake_all();
+        }
+    }
+}
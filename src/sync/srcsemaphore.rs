@@ -0,0 +1,61 @@
+// Karnal64 ksync: sayan semafor.
+// - `wait` sayaç sıfırsa bloklar, `post` sayacı artırıp bekleyenleri uyandırır.
+// - Sayaç `max_count`'u aşamaz; aşacak bir post `KError::InvalidArgument` döner.
+
+use super::super::KError;
+use super::{wait_any, Spinlock, WaitQueue, Waitable};
+
+pub struct Semaphore {
+    count: Spinlock<u32>,
+    max_count: u32,
+    waiters: WaitQueue,
+}
+
+impl Semaphore {
+    pub const fn new(initial_count: u32, max_count: u32) -> Self {
+        Semaphore { count: Spinlock::named(initial_count, "Semaphore.count"), max_count, waiters: WaitQueue::new() }
+    }
+
+    pub fn count(&self) -> u32 {
+        *self.count.lock()
+    }
+
+    /// Sayacı bir azaltır; sıfırsa en fazla `timeout_ns` nanosaniye bekler.
+    pub fn wait(&self, timeout_ns: u64) -> Result<(), KError> {
+        wait_any(&[self], timeout_ns).map(|_| ())
+    }
+
+    /// Sayacı `n` artırır ve en fazla `n` bekleyeni uyandırır.
+    pub fn post(&self, n: u32) -> Result<(), KError> {
+        let mut count = self.count.lock();
+        let new_count = count.checked_add(n).filter(|&c| c <= self.max_count).ok_or(KError::InvalidArgument)?;
+        *count = new_count;
+        drop(count);
+        for _ in 0..n {
+            if !self.waiters.wake_one() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Waitable for Semaphore {
+    fn wait_queue(&self) -> &WaitQueue {
+        &self.waiters
+    }
+
+    fn try_consume(&self) -> bool {
+        let mut count = self.count.lock();
+        if *count > 0 {
+            *count -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_signaled(&self) -> bool {
+        *self.count.lock() > 0
+    }
+}
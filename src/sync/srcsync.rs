@@ -0,0 +1,478 @@
+// Geliştirme sırasında kullanılmayan kod veya argümanlar için izinler
+#![allow(dead_code)]
+#![allow(unused_variables)]
+
+// Karnal64 ksync modülü: çekirdek içi senkronizasyon primitifleri.
+// - Spinlock<T>: kısa kritik bölümler için dönen kilit (veriyi sarmalar); kesme işleyicileriyle
+//   paylaşılan kilitler için lock_irqsave ve irq_save/IrqGuard
+// - WaitQueue: ktask zamanlayıcısıyla entegre bekleme kuyruğu
+// - Mutex/RawMutex: sahip takipli, öncelik mirası destekli uyuyan kilit (srcmutex.rs)
+// - futex_wait/futex_wake: kullanıcı adresleri üzerinde bekle/uyandır (srcfutex.rs)
+// - lockdep: "lockdep" özelliğiyle etkinleşen kilit sırası doğrulayıcısı (srclockdep.rs)
+// - Semaphore, CondVar, RwLock, Event ve çoklu bekleme (wait_any): srcsemaphore.rs,
+//   srccondvar.rs, srcrwlock.rs, srcevent.rs, srcwait.rs; handle tablosu srcsyncobj.rs
+// - rcu: kilitsiz okuyucular, sürüm yayınlama ve grace period sonrası geri alma (srcrcu.rs)
+// Bloklayan IPC, kilitler ve uyku (sleep_for) bu kuyruklar üzerine kuruludur.
+
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::{KError, KTaskId, KThreadId};
+use super::ktask::{self, TaskState, WakeReason, MAX_THREADS};
+
+// Kilit sırası doğrulayıcısı. Özellik kapalıyken kancalar boştur ve derleyici tarafından silinir.
+#[cfg(feature = "lockdep")]
+#[path = "srclockdep.rs"]
+pub mod lockdep;
+
+#[cfg(not(feature = "lockdep"))]
+pub mod lockdep {
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum LockKind {
+        Spin,
+        Sleep,
+    }
+
+    pub struct LockClass;
+
+    impl LockClass {
+        pub const fn new(_name: Option<&'static str>) -> Self {
+            LockClass
+        }
+    }
+
+    #[inline(always)]
+    pub fn lock_acquiring(_class: &LockClass, _key: usize, _kind: LockKind) {}
+    #[inline(always)]
+    pub fn lock_acquired(_class: &LockClass, _key: usize, _kind: LockKind, _trylock: bool) {}
+    #[inline(always)]
+    pub fn lock_release(_class: &LockClass, _key: usize) {}
+    #[inline(always)]
+    pub fn check_blocking() {}
+    #[inline(always)]
+    pub fn context_switch(_prev_slot: usize, _next_slot: usize) {}
+    #[inline(always)]
+    pub fn irq_enter() {}
+    #[inline(always)]
+    pub fn irq_exit() {}
+}
+
+use lockdep::{LockClass, LockKind};
+
+// Uyuyan Mutex ve kullanıcı kilit handle'ları (SYSCALL_LOCK_*)
+#[path = "srcmutex.rs"]
+mod mutex;
+pub use mutex::{lock_acquire, lock_create, lock_destroy, lock_release, Mutex, MutexGuard, RawMutex};
+
+// Kullanıcı adresleri üzerinde futex tarzı bekle/uyandır (SYSCALL_FUTEX_*)
+#[path = "srcfutex.rs"]
+mod futex;
+pub use futex::{futex_wait, futex_wake, FUTEX_WAIT_FOREVER};
+
+// Çoklu nesne bekleme (wait_any) ve Waitable trait'i
+#[path = "srcwait.rs"]
+mod wait;
+pub use wait::{wait_any, Waitable, WAIT_FOREVER};
+
+#[path = "srcsemaphore.rs"]
+mod semaphore;
+pub use semaphore::Semaphore;
+
+#[path = "srccondvar.rs"]
+mod condvar;
+pub use condvar::CondVar;
+
+#[path = "srcrwlock.rs"]
+mod rwlock;
+pub use rwlock::RwLock;
+
+#[path = "srcevent.rs"]
+mod event;
+pub use event::{Event, EventMode};
+
+// Sık okunan, seyrek değişen tablolar için read-copy-update
+#[path = "srcrcu.rs"]
+pub mod rcu;
+
+// Senkronizasyon nesnesi handle'ları (SYSCALL_SEMAPHORE_*, CONDVAR_*, RWLOCK_*, EVENT_*, WAIT_MANY)
+#[path = "srcsyncobj.rs"]
+mod syncobj;
+pub use syncobj::{
+    condvar_create, condvar_notify, event_create, event_reset, event_set, rwlock_acquire_read,
+    rwlock_acquire_write, rwlock_create, rwlock_release, semaphore_create, semaphore_post,
+    sync_object_destroy, wait_many,
+};
+
+/// Toplanan `task` görevinin oluşturduğu kilit ve senkronizasyon nesnesi handle'larını yok eder
+/// (ktask reaping). Başka görevlerin hâlâ kullandığı nesneler çekirdek görevine devredilir.
+pub fn release_task_objects(task: KTaskId) {
+    mutex::release_task_locks(task);
+    syncobj::release_task_sync_objects(task);
+}
+
+pub fn init_manager() {
+    println!("Karnal64: Senkronizasyon Yöneticisi Başlatıldı");
+}
+
+// --- Spinlock ---
+
+/// Basit dönen kilit. Koruduğu veriyi sarmalar; kilit guard düşürülünce (drop) bırakılır.
+/// Kilit tutulduğu sürece mevcut CPU'da kesintiye uğratma (preemption) kapalıdır.
+/// UYARI: Kilit tutulurken bloklanılmamalıdır. Beklemek gerekiyorsa guard
+/// `WaitQueue::wait`'e verilmelidir, kuyruk kilidi atomik olarak bırakıp geri alır.
+pub struct Spinlock<T: ?Sized = ()> {
+    locked: AtomicBool,
+    class: LockClass,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Sync for Spinlock<T> {}
+unsafe impl<T: ?Sized + Send> Send for Spinlock<T> {}
+
+impl<T> Spinlock<T> {
+    pub const fn new(data: T) -> Self {
+        Spinlock {
+            locked: AtomicBool::new(false),
+            class: LockClass::new(None),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// İsimli kilit. Aynı isimli tüm kilitler lockdep'te tek bir sınıf olarak izlenir
+    /// (örn. her kanalın kuyruk kilidi); isimsiz kilitlerin her biri ayrı bir sınıftır.
+    pub const fn named(data: T, name: &'static str) -> Self {
+        Spinlock {
+            locked: AtomicBool::new(false),
+            class: LockClass::new(Some(name)),
+            data: UnsafeCell::new(data),
+        }
+    }
+}
+
+impl<T: ?Sized> Spinlock<T> {
+    pub fn lock(&self) -> SpinlockGuard<'_, T> {
+        ktask::preempt_disable();
+        lockdep::lock_acquiring(&self.class, self.lockdep_key(), LockKind::Spin);
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop(); // Busy wait
+        }
+        lockdep::lock_acquired(&self.class, self.lockdep_key(), LockKind::Spin, false);
+        SpinlockGuard { lock: self }
+    }
+
+    pub fn try_lock(&self) -> Option<SpinlockGuard<'_, T>> {
+        ktask::preempt_disable();
+        match self.locked.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed) {
+            Ok(_) => {
+                lockdep::lock_acquired(&self.class, self.lockdep_key(), LockKind::Spin, true);
+                Some(SpinlockGuard { lock: self })
+            }
+            Err(_) => {
+                ktask::preempt_enable();
+                None
+            }
+        }
+    }
+
+    /// Kilidi bırakır. Yalnızca guard'ın Drop implementasyonu tarafından kullanılır.
+    fn force_unlock(&self) {
+        lockdep::lock_release(&self.class, self.lockdep_key());
+        self.locked.store(false, Ordering::Release);
+        // Son kilit bırakıldıysa bekleyen bir yeniden zamanlama burada yapılır.
+        ktask::preempt_enable();
+    }
+
+    /// İsimsiz kilitlerin lockdep sınıf anahtarı (kilidin adresi).
+    fn lockdep_key(&self) -> usize {
+        self as *const Self as *const () as usize
+    }
+}
+
+pub struct SpinlockGuard<'a, T: ?Sized> {
+    lock: &'a Spinlock<T>,
+}
+
+impl<'a, T: ?Sized> Drop for SpinlockGuard<'a, T> {
+    fn drop(&mut self) {
+        self.lock.force_unlock();
+    }
+}
+
+impl<'a, T: ?Sized> Deref for SpinlockGuard<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized> DerefMut for SpinlockGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+// --- Kesme maskeleme ---
+
+// Düşük seviye mimari kancaları (bkz. karnal64/hardware_specific.h)
+extern "C" {
+    /// Kesmeleri kapatır ve önceki, mimariye özel kesme durumunu döndürür.
+    fn low_level_interrupt_save() -> u64;
+    /// `low_level_interrupt_save` ile kaydedilen durumu geri yükler.
+    fn low_level_interrupt_restore(flags: u64);
+}
+
+/// Kaydedilmiş kesme durumu. Düşürülünce (drop) kesmeler bu duruma geri döner.
+/// İç içe kullanılabilir: yalnızca en dıştaki guard kesmeleri tekrar açar.
+pub struct IrqGuard {
+    flags: u64,
+}
+
+/// Mevcut CPU'da kesmeleri kapatır; guard düşürülünce önceki durum geri yüklenir.
+pub fn irq_save() -> IrqGuard {
+    IrqGuard { flags: unsafe { low_level_interrupt_save() } }
+}
+
+impl Drop for IrqGuard {
+    fn drop(&mut self) {
+        unsafe { low_level_interrupt_restore(self.flags) };
+        // Kesmeler kapalıyken istenmiş bir yeniden zamanlama, kesmeler açılınca yapılır.
+        ktask::maybe_preempt();
+    }
+}
+
+impl<T: ?Sized> Spinlock<T> {
+    /// Kesmeleri kapatıp kilidi alır. Kesme işleyicilerinden de alınan kilitler için kullanılmalıdır;
+    /// aksi halde kilidi tutan kod aynı CPU'da kesilip işleyici kilide dönerse CPU kilitlenir.
+    /// Kesmeler kilitten önce kapatılır ve kilit bırakıldıktan sonra geri açılır.
+    pub fn lock_irqsave(&self) -> SpinlockIrqGuard<'_, T> {
+        let irq = irq_save();
+        SpinlockIrqGuard { guard: self.lock(), _irq: irq }
+    }
+}
+
+/// `lock_irqsave` guard'ı. Alan sırası önemlidir: önce kilit bırakılır, sonra kesme durumu geri yüklenir.
+pub struct SpinlockIrqGuard<'a, T: ?Sized> {
+    guard: SpinlockGuard<'a, T>,
+    _irq: IrqGuard,
+}
+
+impl<'a, T: ?Sized> Deref for SpinlockIrqGuard<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<'a, T: ?Sized> DerefMut for SpinlockIrqGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+// --- WaitQueue ---
+
+/// Bir bekleme işleminin sonucu.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WaitResult {
+    /// wake_one/wake_all ile uyandırıldı.
+    Woken,
+    /// Belirtilen süre doldu.
+    TimedOut,
+}
+
+/// Bekleyen iş parçacıklarının FIFO listesi.
+/// Bir iş parçacığı aynı kuyrukta en fazla bir kez bulunabildiği için (wait_any ile birden
+/// fazla kuyrukta bekleyebilir) kapasite MAX_THREADS ile sınırlıdır ve taşma olmaz.
+struct Waiters {
+    ids: [KThreadId; MAX_THREADS],
+    head: usize,
+    len: usize,
+}
+
+impl Waiters {
+    const fn new() -> Self {
+        Waiters { ids: [KThreadId(0); MAX_THREADS], head: 0, len: 0 }
+    }
+
+    fn push(&mut self, id: KThreadId) {
+        debug_assert!(self.len < MAX_THREADS);
+        let tail = (self.head + self.len) % MAX_THREADS;
+        self.ids[tail] = id;
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<KThreadId> {
+        if self.len == 0 {
+            return None;
+        }
+        let id = self.ids[self.head];
+        self.head = (self.head + 1) % MAX_THREADS;
+        self.len -= 1;
+        Some(id)
+    }
+
+    /// `id`'yi sıradan çıkarır (zaman aşımına uğrayan bekleyiciler için). Sıra korunur.
+    fn remove(&mut self, id: KThreadId) -> bool {
+        let pos = match (0..self.len).find(|&i| self.ids[(self.head + i) % MAX_THREADS] == id) {
+            Some(pos) => pos,
+            None => return false,
+        };
+        for i in pos..self.len - 1 {
+            self.ids[(self.head + i) % MAX_THREADS] = self.ids[(self.head + i + 1) % MAX_THREADS];
+        }
+        self.len -= 1;
+        true
+    }
+}
+
+/// Zamanlayıcıyla entegre bekleme kuyruğu.
+///
+/// Tipik kullanım (koşul değişkeni deseni):
+/// ```ignore
+/// let mut guard = channel.state.lock();
+/// while guard.is_empty() {
+///     guard = channel.waiting_receivers.wait(guard);
+/// }
+/// ```
+/// Uyandıran taraf koşulu aynı kilit altında değiştirip `wake_one`/`wake_all` çağırmalıdır.
+pub struct WaitQueue {
+    // Kesme işleyicileri de wake_one/wake_all çağırabildiği için yalnızca lock_irqsave ile alınır.
+    waiters: Spinlock<Waiters>,
+}
+
+impl Default for WaitQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaitQueue {
+    pub const fn new() -> Self {
+        WaitQueue { waiters: Spinlock::named(Waiters::new(), "WaitQueue.waiters") }
+    }
+
+    /// Mevcut iş parçacığını uyandırılana kadar bloklar.
+    /// `guard` ile tutulan kilit, iş parçacığı kuyruğa eklendikten sonra atomik olarak bırakılır
+    /// ve uyanınca tekrar alınarak geri döndürülür.
+    pub fn wait<'a, T: ?Sized>(&self, guard: SpinlockGuard<'a, T>) -> SpinlockGuard<'a, T> {
+        self.block(guard, None, TaskState::Blocked).0
+    }
+
+    /// `wait` ile aynıdır, ancak en fazla `timeout_ns` nanosaniye bekler.
+    pub fn wait_timeout<'a, T: ?Sized>(
+        &self,
+        guard: SpinlockGuard<'a, T>,
+        timeout_ns: u64,
+    ) -> (SpinlockGuard<'a, T>, WaitResult) {
+        let deadline = ktask::now_ns().saturating_add(timeout_ns);
+        self.block(guard, Some(deadline), TaskState::Blocked)
+    }
+
+    /// `wait_timeout`'un mutlak monoton zaman (nanosaniye) alan hali.
+    pub fn wait_until<'a, T: ?Sized>(
+        &self,
+        guard: SpinlockGuard<'a, T>,
+        deadline_ns: u64,
+    ) -> (SpinlockGuard<'a, T>, WaitResult) {
+        self.block(guard, Some(deadline_ns), TaskState::Blocked)
+    }
+
+    fn block<'a, T: ?Sized>(
+        &self,
+        guard: SpinlockGuard<'a, T>,
+        deadline_ns: Option<u64>,
+        state: TaskState,
+    ) -> (SpinlockGuard<'a, T>, WaitResult) {
+        let me = ktask::current_thread_id();
+        let lock = guard.lock;
+
+        // 1. Önce kuyruğa gir ve Blocked/Sleeping olarak işaretlen; böylece kilidi bıraktıktan sonra
+        //    gelen bir wake kaybolmaz (iş parçacığı Ready'ye döner, schedule hemen geri döner).
+        self.waiters.lock_irqsave().push(me);
+        ktask::prepare_to_block(state, deadline_ns);
+
+        // 2. Korunan kilidi bırak ve işlemciyi devret.
+        drop(guard);
+        lockdep::check_blocking();
+        ktask::schedule();
+
+        // 3. Uyandık: zaman aşımıysa kendimizi kuyruktan temizle.
+        let result = match ktask::current_wake_reason() {
+            WakeReason::TimedOut => {
+                self.waiters.lock_irqsave().remove(me);
+                WaitResult::TimedOut
+            }
+            WakeReason::Signaled => WaitResult::Woken,
+        };
+
+        (lock.lock(), result)
+    }
+
+    /// `id`'yi kuyruğa ekler, ancak bloklamaz. wait_any tarafından birden fazla kuyruğa
+    /// kaydolmak için kullanılır; çağıran `ktask::prepare_to_block`/`schedule` ile kendisi bloklar.
+    fn enqueue(&self, id: KThreadId) {
+        self.waiters.lock_irqsave().push(id);
+    }
+
+    /// `id` hâlâ kuyruktaysa çıkarır (wait_any'de uyandırmayan diğer kuyruklar için).
+    fn dequeue(&self, id: KThreadId) {
+        self.waiters.lock_irqsave().remove(id);
+    }
+
+    /// Kuyruktaki ilk bekleyeni uyandırır. Birini uyandırdıysa `true` döner.
+    pub fn wake_one(&self) -> bool {
+        let mut waiters = self.waiters.lock_irqsave();
+        // Zaman aşımıyla zaten uyanmış (ama henüz kendini çıkarmamış) bekleyiciler atlanır.
+        while let Some(id) = waiters.pop() {
+            if ktask::wake(id, WakeReason::Signaled) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// `id` kuyruktaysa çıkarır ve uyandırır (bekleyenlerden yalnızca bazılarını seçen futex gibi
+    /// kullanıcılar için). Uyandırdıysa `true` döner.
+    pub fn wake_thread(&self, id: KThreadId) -> bool {
+        let mut waiters = self.waiters.lock_irqsave();
+        waiters.remove(id) && ktask::wake(id, WakeReason::Signaled)
+    }
+
+    /// Tüm bekleyenleri uyandırır. Uyandırılan iş parçacığı sayısını döner.
+    pub fn wake_all(&self) -> usize {
+        let mut waiters = self.waiters.lock_irqsave();
+        let mut woken = 0;
+        while let Some(id) = waiters.pop() {
+            if ktask::wake(id, WakeReason::Signaled) {
+                woken += 1;
+            }
+        }
+        woken
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.waiters.lock_irqsave().len == 0
+    }
+}
+
+// --- Uyku ---
+
+// Uyuyan iş parçacıkları bu kuyrukta bekler; kimse wake etmez, yalnızca süre dolunca uyanırlar
+// (ktask'ın CPU başına zamanlayıcı kuyruğu, tek seferlik zamanlayıcı kesmesiyle).
+static SLEEP_QUEUE: WaitQueue = WaitQueue::new();
+static SLEEP_LOCK: Spinlock = Spinlock::named((), "SLEEP_LOCK");
+
+/// Mevcut iş parçacığını en az `duration_ms` milisaniye uyutur.
+pub fn sleep_for(duration_ms: u64) -> Result<(), KError> {
+    if duration_ms == 0 {
+        return ktask::yield_now();
+    }
+    let deadline = ktask::now_ns().saturating_add(duration_ms.saturating_mul(1_000_000));
+    let (_guard, _) = SLEEP_QUEUE.block(SLEEP_LOCK.lock(), Some(deadline), TaskState::Sleeping);
+    Ok(())
+}
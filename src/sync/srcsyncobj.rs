@@ -0,0 +1,331 @@
+// Karnal64 ksync: kullanıcı alanına açılan senkronizasyon nesnesi handle'ları.
+// - Semafor, koşul değişkeni, okuyucu-yazıcı kilidi ve olay nesneleri tek bir statik tabloda tutulur.
+// - SYSCALL_WAIT_MANY, bu nesnelerin ve kilit handle'larının (SYSCALL_LOCK_*) herhangi bir
+//   karışımı üzerinde bekler.
+
+use core::cell::UnsafeCell;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+
+use super::super::{kmemory, ktask, KError, KHandle, KTaskId};
+use super::mutex::lookup_lock;
+use super::{wait_any, CondVar, Event, EventMode, RwLock, Semaphore, WaitQueue, Waitable};
+
+/// Aynı anda var olabilecek en fazla senkronizasyon nesnesi sayısı.
+const MAX_SYNC_OBJECTS: usize = 256;
+
+/// Tek bir SYSCALL_WAIT_MANY çağrısında beklenebilecek en fazla handle sayısı.
+pub const MAX_WAIT_HANDLES: usize = 16;
+
+/// Senkronizasyon nesnesi handle değerleri bu etiketle başlar (kilit handle'ları 'L' kullanır).
+const SYNC_HANDLE_TAG: u64 = 0x53 << 56; // 'S'
+
+enum SyncObject {
+    Empty,
+    Semaphore(Semaphore),
+    CondVar(CondVar),
+    RwLock(RwLock),
+    Event(Event),
+}
+
+impl SyncObject {
+    fn as_waitable(&self) -> Option<&dyn Waitable> {
+        match self {
+            SyncObject::Empty => None,
+            SyncObject::Semaphore(s) => Some(s),
+            SyncObject::CondVar(c) => Some(c),
+            SyncObject::RwLock(r) => Some(r),
+            SyncObject::Event(e) => Some(e),
+        }
+    }
+}
+
+/// Statik nesne yuvası. `object` yalnızca referans sayısı sıfırken yazılır: `sync_object_create`
+/// içinde handle dağıtılmadan önce ve son referans bırakılırken (`put`). Aradaki her erişim bir
+/// `SyncRef` tuttuğundan `object`'e kilitsiz erişim güvenlidir.
+/// Nesil sayacı, yok edilmiş (veya yok edilip yeniden kullanılan) yuvalara eski handle'larla
+/// erişimi engeller. Sahip görev toplanırken (ktask reaping) nesne `release_task_sync_objects`
+/// ile yok edilir.
+struct SyncSlot {
+    in_use: AtomicBool,
+    generation: AtomicU32,
+    /// Handle'ın referansı (yok edilene kadar 1) ve nesneyi o an kullanan çağrıların referansları.
+    refs: AtomicU32,
+    owner: AtomicU64,
+    object: UnsafeCell<SyncObject>,
+}
+
+unsafe impl Sync for SyncSlot {}
+
+impl SyncSlot {
+    /// Referans sayısı sıfır değilse (nesne yaşıyorsa) bir referans alır.
+    fn try_get(&self) -> bool {
+        let mut refs = self.refs.load(Ordering::Acquire);
+        loop {
+            if refs == 0 {
+                return false;
+            }
+            match self.refs.compare_exchange_weak(refs, refs + 1, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return true,
+                Err(current) => refs = current,
+            }
+        }
+    }
+
+    /// Bir referansı bırakır. Son referanssa nesne yok edilir ve yuva yeniden kullanılabilir olur.
+    fn put(&self) {
+        if self.refs.fetch_sub(1, Ordering::AcqRel) == 1 {
+            unsafe { *self.object.get() = SyncObject::Empty };
+            self.in_use.store(false, Ordering::Release);
+        }
+    }
+}
+
+/// Yuvadaki nesneye sayılmış referans. Düşürülünce referans bırakılır; handle bu arada yok
+/// edilse bile nesne referans bırakılana kadar yerinde kalır.
+struct SyncRef(&'static SyncSlot);
+
+impl Deref for SyncRef {
+    type Target = SyncObject;
+    fn deref(&self) -> &SyncObject {
+        unsafe { &*self.0.object.get() }
+    }
+}
+
+impl Drop for SyncRef {
+    fn drop(&mut self) {
+        self.0.put();
+    }
+}
+
+const EMPTY_SYNC_SLOT: SyncSlot = SyncSlot {
+    in_use: AtomicBool::new(false),
+    generation: AtomicU32::new(0),
+    refs: AtomicU32::new(0),
+    owner: AtomicU64::new(0),
+    object: UnsafeCell::new(SyncObject::Empty),
+};
+static SYNC_TABLE: [SyncSlot; MAX_SYNC_OBJECTS] = [EMPTY_SYNC_SLOT; MAX_SYNC_OBJECTS];
+
+/// Nesneyi boş bir yuvaya koyar ve handle'ını döner; handle oluşturan görevin handle kotasından
+/// düşülür (`ktask::QUOTA_HANDLES`).
+fn sync_object_create(object: SyncObject) -> Result<KHandle, KError> {
+    ktask::quota_charge(ktask::QUOTA_HANDLES, 1)?;
+    for (index, slot) in SYNC_TABLE.iter().enumerate() {
+        if slot
+            .in_use
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Relaxed)
+            .is_ok()
+        {
+            unsafe { *slot.object.get() = object };
+            slot.owner.store(ktask::current_task_id().0, Ordering::Release);
+            slot.refs.store(1, Ordering::Release);
+            let generation = slot.generation.load(Ordering::Acquire);
+            return Ok(KHandle(SYNC_HANDLE_TAG | ((generation as u64) << 16) | (index as u64 + 1)));
+        }
+    }
+    ktask::quota_uncharge(ktask::QUOTA_HANDLES, 1);
+    Err(KError::OutOfMemory)
+}
+
+fn handle_generation(handle_value: u64) -> u32 {
+    ((handle_value >> 16) & 0xFFFF_FFFF) as u32
+}
+
+/// Handle'ı nesnesine çözer ve nesneye bir referans alır.
+fn lookup_object(handle_value: u64) -> Result<SyncRef, KError> {
+    if handle_value & (0xFF << 56) != SYNC_HANDLE_TAG {
+        return Err(KError::BadHandle);
+    }
+    let index = (handle_value & 0xFFFF) as usize;
+    if index == 0 || index > MAX_SYNC_OBJECTS {
+        return Err(KError::BadHandle);
+    }
+    let slot = &SYNC_TABLE[index - 1];
+    if !slot.try_get() {
+        return Err(KError::BadHandle);
+    }
+    // Nesil referans alındıktan sonra kontrol edilir; uyuşmazsa referans `object` düşerken bırakılır.
+    let object = SyncRef(slot);
+    if slot.generation.load(Ordering::Acquire) != handle_generation(handle_value) {
+        return Err(KError::BadHandle);
+    }
+    Ok(object)
+}
+
+/// Handle'ı (senkronizasyon nesnesi veya kilit) beklenebilir nesneye çözer. Senkronizasyon
+/// nesnesinin referansı `holder`'a konur; dönen nesne `holder` yaşadıkça geçerlidir.
+fn lookup_waitable(handle_value: u64, holder: &mut Option<SyncRef>) -> Result<&dyn Waitable, KError> {
+    match lookup_object(handle_value) {
+        Ok(object) => holder.insert(object).as_waitable().ok_or(KError::BadHandle),
+        Err(_) => lookup_lock(handle_value).map(|lock| lock as &dyn Waitable),
+    }
+}
+
+/// SYSCALL_SEMAPHORE_CREATE
+pub fn semaphore_create(initial_count: u32, max_count: u32) -> Result<KHandle, KError> {
+    if max_count == 0 || initial_count > max_count {
+        return Err(KError::InvalidArgument);
+    }
+    sync_object_create(SyncObject::Semaphore(Semaphore::new(initial_count, max_count)))
+}
+
+/// SYSCALL_SEMAPHORE_POST
+pub fn semaphore_post(handle_value: u64, n: u32) -> Result<(), KError> {
+    match &*lookup_object(handle_value)? {
+        SyncObject::Semaphore(semaphore) => semaphore.post(n),
+        _ => Err(KError::BadHandle),
+    }
+}
+
+/// SYSCALL_CONDVAR_CREATE: `lock_handle_value` ile eşlenmiş bir koşul değişkeni oluşturur.
+pub fn condvar_create(lock_handle_value: u64) -> Result<KHandle, KError> {
+    lookup_lock(lock_handle_value)?;
+    sync_object_create(SyncObject::CondVar(CondVar::new(lock_handle_value)))
+}
+
+/// SYSCALL_CONDVAR_NOTIFY: `all` ise tüm bekleyenleri, değilse birini uyandırır.
+pub fn condvar_notify(handle_value: u64, all: bool) -> Result<(), KError> {
+    match &*lookup_object(handle_value)? {
+        SyncObject::CondVar(condvar) if all => condvar.notify_all(),
+        SyncObject::CondVar(condvar) => condvar.notify_one(),
+        _ => return Err(KError::BadHandle),
+    }
+    Ok(())
+}
+
+/// SYSCALL_RWLOCK_CREATE
+pub fn rwlock_create() -> Result<KHandle, KError> {
+    sync_object_create(SyncObject::RwLock(RwLock::new()))
+}
+
+/// SYSCALL_RWLOCK_ACQUIRE_READ
+pub fn rwlock_acquire_read(handle_value: u64) -> Result<(), KError> {
+    match &*lookup_object(handle_value)? {
+        SyncObject::RwLock(rwlock) => rwlock.read_acquire(),
+        _ => Err(KError::BadHandle),
+    }
+}
+
+/// SYSCALL_RWLOCK_ACQUIRE_WRITE: yazma kilidini en fazla `timeout_ns` nanosaniye bekleyerek alır
+/// (`WAIT_FOREVER`: süresiz). Süre dolarsa `KError::TimedOut` döner.
+pub fn rwlock_acquire_write(handle_value: u64, timeout_ns: u64) -> Result<(), KError> {
+    match &*lookup_object(handle_value)? {
+        SyncObject::RwLock(rwlock) => rwlock.write_acquire(timeout_ns),
+        _ => Err(KError::BadHandle),
+    }
+}
+
+/// SYSCALL_RWLOCK_RELEASE: okuma veya yazma kilidini bırakır.
+pub fn rwlock_release(handle_value: u64) -> Result<(), KError> {
+    match &*lookup_object(handle_value)? {
+        SyncObject::RwLock(rwlock) => rwlock.release(),
+        _ => Err(KError::BadHandle),
+    }
+}
+
+/// SYSCALL_EVENT_CREATE
+pub fn event_create(manual_reset: bool, initially_set: bool) -> Result<KHandle, KError> {
+    let mode = if manual_reset { EventMode::ManualReset } else { EventMode::AutoReset };
+    sync_object_create(SyncObject::Event(Event::new(mode, initially_set)))
+}
+
+/// SYSCALL_EVENT_SET
+pub fn event_set(handle_value: u64) -> Result<(), KError> {
+    match &*lookup_object(handle_value)? {
+        SyncObject::Event(event) => event.set(),
+        _ => return Err(KError::BadHandle),
+    }
+    Ok(())
+}
+
+/// SYSCALL_EVENT_RESET
+pub fn event_reset(handle_value: u64) -> Result<(), KError> {
+    match &*lookup_object(handle_value)? {
+        SyncObject::Event(event) => event.reset(),
+        _ => return Err(KError::BadHandle),
+    }
+    Ok(())
+}
+
+// wait_many'de kullanılmayan dizi elemanları için yer tutucu; asla beklenmez.
+struct Unused;
+
+impl Waitable for Unused {
+    fn wait_queue(&self) -> &WaitQueue {
+        static NEVER: WaitQueue = WaitQueue::new();
+        &NEVER
+    }
+
+    fn try_consume(&self) -> bool {
+        false
+    }
+
+    fn is_signaled(&self) -> bool {
+        false
+    }
+}
+
+/// SYSCALL_WAIT_MANY: kullanıcı alanındaki `count` handle'dan herhangi biri sinyallenene kadar bekler.
+/// Sinyallenen (ve tüketilen) handle'ın dizideki indeksini döner.
+/// Semafor: sayaç azaltılır. Olay: otomatik modda sıfırlanır. Kilit ve rwlock: (yazma) kilidi alınır.
+/// Koşul değişkeni: eşli kilit bekleme süresince bırakılır ve dönüşte tekrar alınır.
+pub fn wait_many(handles_ptr: *const u64, count: usize, timeout_ns: u64) -> Result<u64, KError> {
+    if count == 0 || count > MAX_WAIT_HANDLES {
+        return Err(KError::InvalidArgument);
+    }
+    let mut handles = [0u64; MAX_WAIT_HANDLES];
+    kmemory::copy_from_user(
+        handles.as_mut_ptr() as *mut u8,
+        handles_ptr as *const u8,
+        count * core::mem::size_of::<u64>(),
+    )?;
+
+    // Referanslar bekleme bitene kadar tutulur; bu sırada yok edilen handle'ın nesnesi yerinde kalır.
+    let mut holders: [Option<SyncRef>; MAX_WAIT_HANDLES] = Default::default();
+    let mut objects: [&dyn Waitable; MAX_WAIT_HANDLES] = [&Unused; MAX_WAIT_HANDLES];
+    for ((object, holder), &handle) in objects.iter_mut().zip(holders.iter_mut()).zip(&handles[..count]) {
+        *object = lookup_waitable(handle, holder)?;
+    }
+    wait_any(&objects[..count], timeout_ns).map(|index| index as u64)
+}
+
+/// Senkronizasyon nesnesi handle'ını yok eder. Nesnede bekleyen varsa `KError::Busy` döner.
+/// Nesneyi o an kullanan çağrılar (bloklamadan önceki `wait_many` gibi) bitene kadar nesne
+/// yerinde kalır; yuva son referansla birlikte boşalır.
+/// kresource::release_handle tarafından bu türdeki handle'lar için çağrılır.
+pub fn sync_object_destroy(handle_value: u64) -> Result<(), KError> {
+    let object = lookup_object(handle_value)?;
+    if object.as_waitable().is_some_and(|o| !o.wait_queue().is_empty()) {
+        return Err(KError::Busy);
+    }
+    let slot = object.0;
+    // Nesli artırarak eski handle'ları geçersiz kıl; eşzamanlı iki yok etmeden yalnızca biri başarır.
+    let generation = handle_generation(handle_value);
+    if slot
+        .generation
+        .compare_exchange(generation, generation.wrapping_add(1), Ordering::AcqRel, Ordering::Acquire)
+        .is_err()
+    {
+        return Err(KError::BadHandle);
+    }
+    ktask::quota_uncharge_task(KTaskId(slot.owner.load(Ordering::Acquire)), ktask::QUOTA_HANDLES, 1);
+    // Handle'ın referansı bırakılır; `object` da düşünce kullanan kalmadıysa yuva boşalır.
+    slot.put();
+    Ok(())
+}
+
+/// `task` görevinin oluşturduğu nesneleri yok eder; ktask görevi toplarken çağırır.
+/// Bekleyeni olan nesneler (handle başka bir göreve geçmiş) çekirdek görevine devredilir.
+pub(super) fn release_task_sync_objects(task: KTaskId) {
+    for (index, slot) in SYNC_TABLE.iter().enumerate() {
+        if !slot.in_use.load(Ordering::Acquire) || slot.owner.load(Ordering::Acquire) != task.0 {
+            continue;
+        }
+        let generation = slot.generation.load(Ordering::Acquire);
+        let handle = SYNC_HANDLE_TAG | ((generation as u64) << 16) | (index as u64 + 1);
+        if sync_object_destroy(handle) == Err(KError::Busy) {
+            slot.owner.store(0, Ordering::Release);
+        }
+    }
+}
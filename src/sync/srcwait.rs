@@ -0,0 +1,109 @@
+// Karnal64 ksync: birden fazla senkronizasyon nesnesi üzerinde bekleme (wait_any).
+// - Her beklenebilir nesne (semafor, olay, rwlock, condvar, kilit) `Waitable` trait'ini implemente eder.
+// - Bekleyen iş parçacığı önce tüm nesnelerin WaitQueue'larına kaydolur, sonra nesneleri tekrar
+//   kontrol eder; böylece kayıt ile bloklama arasında gelen bir sinyal kaybolmaz.
+// - Sinyal, nesnenin kendi durumunda kalır ve bekleyen tarafından `try_consume` ile alınır;
+//   uyandırma yalnızca "tekrar dene" anlamına gelir.
+
+use super::super::KError;
+use super::super::ktask::{self, TaskState, WakeReason};
+use super::{lockdep, WaitQueue};
+
+/// Sınırsız bekleme için zaman aşımı değeri.
+pub const WAIT_FOREVER: u64 = u64::MAX;
+
+/// wait_any ile beklenebilen nesne.
+pub trait Waitable: Sync {
+    /// Nesnenin bekleyicilerinin kaydolduğu kuyruk. Nesne sinyallenince bu kuyruktan uyandırır.
+    fn wait_queue(&self) -> &WaitQueue;
+
+    /// Nesne sinyalliyse sinyali tüketir (sayaç azaltma, auto-reset, kilidi alma vb.) ve `true` döner.
+    fn try_consume(&self) -> bool;
+
+    /// Sinyali tüketmeden nesnenin şu an alınabilir olup olmadığını döner.
+    fn is_signaled(&self) -> bool;
+
+    /// Bekleme başlamadan önce bir kez çağrılır (örn. condvar bekleyici sayısını artırır ve
+    /// eşli kilidi bırakır). Hata dönerse bekleme yapılmaz.
+    fn begin_wait(&self) -> Result<(), KError> {
+        Ok(())
+    }
+
+    /// Bekleme bittiğinde (başarı, zaman aşımı veya hata) bir kez çağrılır.
+    fn end_wait(&self) {}
+}
+
+/// `objects` içinden herhangi biri sinyallenene kadar bekler ve o nesneyi tüketir.
+/// Tüketilen nesnenin indeksini döner; `timeout_ns` dolarsa `KError::TimedOut` döner.
+/// `timeout_ns == 0` yalnızca bir kez yoklar, `WAIT_FOREVER` sınırsız bekler.
+/// Birden fazla nesne hazırsa en düşük indeksli olan seçilir.
+pub fn wait_any(objects: &[&dyn Waitable], timeout_ns: u64) -> Result<usize, KError> {
+    if objects.is_empty() {
+        return Err(KError::InvalidArgument);
+    }
+    let deadline = match timeout_ns {
+        WAIT_FOREVER => None,
+        timeout => Some(ktask::now_ns().saturating_add(timeout)),
+    };
+
+    for (i, object) in objects.iter().enumerate() {
+        if let Err(e) = object.begin_wait() {
+            objects[..i].iter().for_each(|o| o.end_wait());
+            return Err(e);
+        }
+    }
+    let result = wait_any_inner(objects, deadline);
+    objects.iter().for_each(|o| o.end_wait());
+    result
+}
+
+fn wait_any_inner(objects: &[&dyn Waitable], deadline: Option<u64>) -> Result<usize, KError> {
+    let me = ktask::current_thread_id();
+    loop {
+        if let Some(index) = objects.iter().position(|o| o.try_consume()) {
+            return Ok(index);
+        }
+        if matches!(deadline, Some(d) if d <= ktask::now_ns()) {
+            return Err(KError::TimedOut);
+        }
+
+        // 1. Tüm kuyruklara kaydol ve bloklanmaya hazırlan.
+        objects.iter().for_each(|o| o.wait_queue().enqueue(me));
+        ktask::prepare_to_block(TaskState::Blocked, deadline);
+
+        // 2. Kayıttan sonra tekrar kontrol et: arada sinyallenen nesne varsa bloklama.
+        let ready = objects.iter().position(|o| o.try_consume());
+        if ready.is_none() || !ktask::cancel_block() {
+            lockdep::check_blocking();
+            ktask::schedule();
+        }
+
+        // 3. Uyandık (veya hiç bloklanmadık): hâlâ kayıtlı olduğumuz kuyruklardan çık.
+        objects.iter().for_each(|o| o.wait_queue().dequeue(me));
+
+        if let Some(index) = ready {
+            pass_on_wakeups(objects, index);
+            return Ok(index);
+        }
+        if ktask::current_wake_reason() == WakeReason::TimedOut {
+            // Süre dolarken son bir kez yokla; sinyal zaman aşımıyla aynı anda gelmiş olabilir.
+            return match objects.iter().position(|o| o.try_consume()) {
+                Some(index) => {
+                    pass_on_wakeups(objects, index);
+                    Ok(index)
+                }
+                None => Err(KError::TimedOut),
+            };
+        }
+    }
+}
+
+/// Tüketmediğimiz bir nesnenin uyandırması bize düşmüş olabilir. O nesne hâlâ sinyalliyse,
+/// uyandırmayı kuyruğundaki bir sonraki bekleyene aktar; aksi halde sinyal kimse uyanmadan kalırdı.
+fn pass_on_wakeups(objects: &[&dyn Waitable], consumed: usize) {
+    for (i, object) in objects.iter().enumerate() {
+        if i != consumed && object.is_signaled() {
+            object.wait_queue().wake_one();
+        }
+    }
+}
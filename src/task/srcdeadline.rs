@@ -0,0 +1,335 @@
+// Karnal64 ktask: deadline (EDF) gerçek zamanlı sınıfı.
+// Bir iş parçacığı çalışma süresi (runtime), son tarih (deadline) ve periyot bildirir
+// (`set_thread_deadline`): her periyotta, periyodun başından itibaren `deadline` içinde en fazla
+// `runtime` kadar CPU ister. Kabul denetimi (admission control) bunu bir CPU'nun deadline
+// kapasitesine (DL_UTIL_MAX) sığdırabiliyorsa iş parçacığı o CPU'ya bağlanır (bölümlenmiş EDF);
+// sığmıyorsa `KError::Busy` döner ve iş parçacığı öncelik sınıfında kalır.
+// Deadline sınıfı öncelik zamanlayıcısının üstündedir: bir CPU'da hazır deadline iş parçacığı
+// varsa, mutlak son tarihi en erken olan çalışır (Earliest Deadline First); öncelik seviyeleri
+// yalnızca hazır deadline iş parçacığı yokken çalışır.
+// Bütçe: çalışılan süre bütçeden düşülür; bütçe biten iş parçacığı bir sonraki periyoda kadar
+// kısıtlanır (throttle) ve hazır kuyruğuna girmez. Bütçe bitişi ve yenileme anları CPU'nun
+// zamanlayıcı kuyruğu ve tek seferlik zamanlayıcısıyla (srctimer.rs) tam zamanında işlenir.
+// Uyanmada CBS (Constant Bandwidth Server) kuralı uygulanır: kalan bütçe kalan sürede bildirilen
+// bant genişliğini aşacaksa son tarih ve bütçe yenilenir; böylece bloklanıp uyanan bir iş
+// parçacığı diğerlerinin garantisini bozamaz.
+// Deadline iş parçacığının `task_yield` çağrısı bu periyodun kalan bütçesini bırakır.
+
+use super::super::{KError, KThreadId};
+use super::{now_ns, Scheduler, TaskState, MAX_CPUS, SCHEDULER};
+
+/// Kullanım oranlarının sabit noktalı gösteriminin kesir bit sayısı (1 << DL_UTIL_SHIFT = tam CPU).
+const DL_UTIL_SHIFT: u32 = 20;
+/// Bir CPU'da deadline iş parçacıklarına ayrılabilecek en büyük pay (%95); kalanı öncelik
+/// sınıfının aç kalmaması içindir.
+const DL_UTIL_MAX: u64 = (95 << DL_UTIL_SHIFT) / 100;
+/// En küçük çalışma süresi ve en büyük periyot (nanosaniye).
+pub const DL_RUNTIME_MIN_NS: u64 = 100_000;
+pub const DL_PERIOD_MAX_NS: u64 = 1_000_000_000;
+
+/// Deadline sınıfındaki bir iş parçacığının parametreleri ve EDF durumu.
+#[derive(Debug, Copy, Clone)]
+pub(super) struct DeadlineEntity {
+    runtime_ns: u64,
+    /// Periyot başından itibaren göreli son tarih.
+    deadline_ns: u64,
+    period_ns: u64,
+    /// `runtime_ns / period_ns`, DL_UTIL_SHIFT sabit noktalı.
+    util: u64,
+    /// Geçerli işin mutlak son tarihi (monoton saat). EDF anahtarı.
+    abs_deadline_ns: u64,
+    /// Geçerli periyotta kalan çalışma süresi.
+    budget_ns: u64,
+    /// Bütçenin en son güncellendiği (çalışmaya başladığı) an.
+    exec_start_ns: u64,
+    /// Bütçe bitti; bir sonraki periyoda kadar çalışamaz.
+    pub(super) throttled: bool,
+    /// Kabul edildiği (payının ayrıldığı ve bağlı olduğu) CPU.
+    cpu: usize,
+    /// Sınıfa girmeden önceki afinite maskesi; sınıftan çıkınca geri yüklenir, oluşturduğu iş
+    /// parçacıklarına bu maske miras kalır.
+    pub(super) saved_affinity: u64,
+}
+
+impl DeadlineEntity {
+    /// Geçerli periyodun bittiği (bütçenin yenileneceği) an.
+    fn next_period_ns(&self) -> u64 {
+        self.abs_deadline_ns
+            .saturating_sub(self.deadline_ns)
+            .saturating_add(self.period_ns)
+    }
+}
+
+impl Scheduler {
+    /// `cpu`'nun EDF kuyruğundaki en erken mutlak son tarih ve sahibi.
+    fn dl_earliest(&self, cpu: usize) -> Option<(u64, usize)> {
+        self.cpus[cpu]
+            .dl_ready
+            .iter()
+            .filter_map(|slot| match self.threads[slot] {
+                Some(t) if t.state == TaskState::Ready => t.dl.map(|dl| (dl.abs_deadline_ns, slot)),
+                _ => None,
+            })
+            .min()
+    }
+
+    /// `cpu`'nun EDF kuyruğundan son tarihi en erken hazır iş parçacığını çıkarır.
+    pub(super) fn dl_pick(&mut self, cpu: usize) -> Option<usize> {
+        let (_, slot) = self.dl_earliest(cpu)?;
+        self.cpus[cpu].dl_ready.remove(slot);
+        Some(slot)
+    }
+
+    /// Hazır deadline iş parçacığını CPU'sunun EDF kuyruğuna koyar. Kısıtlanmışsa kuyruğa girmez;
+    /// bekleyen bir yenileme zamanlayıcısı yoksa kurulur.
+    pub(super) fn dl_enqueue(&mut self, slot: usize, cpu: usize) {
+        let (dl, timer_armed) = match self.threads[slot] {
+            Some(t) => match t.dl {
+                Some(dl) => (dl, t.timer_cpu.is_some()),
+                None => return,
+            },
+            None => return,
+        };
+        if dl.throttled {
+            if !timer_armed {
+                self.set_deadline(slot, Some(dl.next_period_ns()));
+            }
+            return;
+        }
+        self.cpus[cpu].dl_ready.push(slot);
+    }
+
+    /// Bloklanmış deadline iş parçacığı uyanırken (CBS kuralı): son tarihi geçmişse veya kalan
+    /// bütçe kalan sürede bildirilen bant genişliğinden fazlasını kullandıracaksa yeni bir son
+    /// tarih ve tam bütçe verilir. Bütçesi kalmamışsa bir sonraki periyoda kadar kısıtlanır.
+    pub(super) fn dl_wakeup(&mut self, slot: usize, now: u64) {
+        if let Some(dl) = self.threads[slot].as_mut().and_then(|t| t.dl.as_mut()) {
+            dl.throttled = false;
+            let remaining = dl.abs_deadline_ns.saturating_sub(now);
+            let overrun = (dl.budget_ns as u128) * (dl.period_ns as u128)
+                > (dl.runtime_ns as u128) * (remaining as u128);
+            if now >= dl.abs_deadline_ns || overrun {
+                dl.abs_deadline_ns = now.saturating_add(dl.deadline_ns);
+                dl.budget_ns = dl.runtime_ns;
+            }
+            if dl.budget_ns == 0 {
+                dl.throttled = true;
+            }
+        }
+    }
+
+    /// `cpu`'da çalışan (veya bloklanmak üzere CPU'yu bırakan) deadline iş parçacığının
+    /// bütçesinden son güncellemeden beri geçen süreyi düşer. Bütçe biterse iş parçacığı
+    /// kısıtlanır ve CPU yeniden zamanlanır.
+    pub(super) fn dl_update_curr(&mut self, cpu: usize, now: u64) {
+        let current = self.cpus[cpu].current;
+        let exhausted = match self.threads[current].as_mut().and_then(|t| t.dl.as_mut()) {
+            Some(dl) if !dl.throttled => {
+                dl.budget_ns = dl.budget_ns.saturating_sub(now.saturating_sub(dl.exec_start_ns));
+                dl.exec_start_ns = now;
+                dl.throttled = dl.budget_ns == 0;
+                dl.throttled
+            }
+            _ => false,
+        };
+        if exhausted {
+            self.resched_cpu(cpu);
+        }
+    }
+
+    /// `slot` bu CPU'da çalışmaya başlıyor: bütçe bu andan itibaren düşülür.
+    pub(super) fn dl_start(&mut self, slot: usize, now: u64) {
+        if let Some(dl) = self.threads[slot].as_mut().and_then(|t| t.dl.as_mut()) {
+            dl.exec_start_ns = now;
+        }
+    }
+
+    /// Kısıtlanmış `slot`'un yenileme zamanı geldi: bir sonraki periyodun son tarihi ve tam
+    /// bütçe verilir, hazırsa EDF kuyruğuna döner.
+    pub(super) fn dl_replenish(&mut self, slot: usize, now: u64) {
+        let (state, cpu) = match self.threads[slot].as_mut() {
+            Some(t) => match t.dl.as_mut() {
+                Some(dl) if dl.throttled => {
+                    dl.abs_deadline_ns = dl.abs_deadline_ns.saturating_add(dl.period_ns);
+                    // Yenileme gecikip yeni son tarih de geçmişse (kaçırılan periyotlar) şimdiden başlar.
+                    if dl.abs_deadline_ns <= now {
+                        dl.abs_deadline_ns = now.saturating_add(dl.deadline_ns);
+                    }
+                    dl.budget_ns = dl.runtime_ns;
+                    dl.exec_start_ns = now;
+                    dl.throttled = false;
+                    (t.state, t.cpu)
+                }
+                _ => return,
+            },
+            None => return,
+        };
+        // İşi askıdaysa enqueue kuyruğa koymaz.
+        if state == TaskState::Ready {
+            self.enqueue(slot, cpu);
+        }
+        self.check_preempt(cpu);
+    }
+
+    /// `cpu`'da deadline sınıfına göre kesintiye uğratma kararı; karar öncelik sınıfına
+    /// kalıyorsa `None`. Çalışan deadline iş parçacığını yalnızca son tarihi daha erken olan
+    /// bir deadline iş parçacığı keser; bütçesi biten her zaman CPU'yu bırakır.
+    pub(super) fn dl_should_preempt(&self, cpu: usize) -> Option<bool> {
+        let c = &self.cpus[cpu];
+        let running = match self.threads[c.current] {
+            Some(t) if c.current != c.idle && t.state == TaskState::Running => t.dl,
+            _ => None,
+        };
+        if matches!(running, Some(dl) if dl.throttled) {
+            return Some(true);
+        }
+        match (self.dl_earliest(cpu), running) {
+            (Some((earliest, _)), Some(dl)) => Some(earliest < dl.abs_deadline_ns),
+            (Some(_), None) => Some(true),
+            (None, Some(_)) => Some(false),
+            (None, None) => None,
+        }
+    }
+
+    /// `cpu`'da çalışan deadline iş parçacığının bütçesinin biteceği an (zamanlayıcı olayı).
+    pub(super) fn dl_budget_end(&self, cpu: usize) -> Option<u64> {
+        let c = &self.cpus[cpu];
+        if c.current == c.idle {
+            return None;
+        }
+        match self.threads[c.current]?.dl {
+            Some(dl) if !dl.throttled => Some(dl.exec_start_ns.saturating_add(dl.budget_ns)),
+            _ => None,
+        }
+    }
+
+    /// Mevcut deadline iş parçacığı bu periyodun kalan bütçesini bırakır (`task_yield`).
+    pub(super) fn dl_yield(&mut self, slot: usize) {
+        if let Some(dl) = self.threads[slot].as_mut().and_then(|t| t.dl.as_mut()) {
+            dl.budget_ns = 0;
+            dl.throttled = true;
+        }
+    }
+
+    /// `slot`'u deadline sınıfından çıkarır: CPU payı bırakılır, afinitesi geri yüklenir ve
+    /// hazırsa öncelik seviyesinin kuyruğuna döner.
+    pub(super) fn dl_leave(&mut self, slot: usize) {
+        let (dl, state, cpu, on_cpu) = match self.threads[slot] {
+            Some(t) => match t.dl {
+                Some(dl) => (dl, t.state, t.cpu, t.on_cpu),
+                None => return,
+            },
+            None => return,
+        };
+        // Kısıtlanmış hazır iş parçacığı kuyrukta değildir, yenileme zamanlayıcısını bekler.
+        let requeue = state == TaskState::Ready && (self.dequeue(slot, cpu) || dl.throttled);
+        if state == TaskState::Ready && dl.throttled {
+            self.set_deadline(slot, None);
+        }
+        self.dl_release(slot);
+        if let Some(t) = self.threads[slot].as_mut() {
+            t.affinity = dl.saved_affinity;
+        }
+        if requeue && !on_cpu {
+            self.migrate(slot);
+        } else if requeue && self.allowed(slot, cpu) {
+            // Bağlamı kaydedilmekte; izinli olmayan CPU'dan finish_switch taşır.
+            self.enqueue(slot, cpu);
+        }
+        self.check_preempt(cpu);
+    }
+
+    /// Sonlanan veya sınıftan çıkan `slot`'un deadline durumunu siler ve CPU payını bırakır.
+    pub(super) fn dl_release(&mut self, slot: usize) {
+        if let Some(dl) = self.threads[slot].as_mut().and_then(|t| t.dl.take()) {
+            self.cpus[dl.cpu].dl_util = self.cpus[dl.cpu].dl_util.saturating_sub(dl.util);
+        }
+    }
+
+    /// `slot` için `util` payına yer olan bir CPU seçer: izinli ve çevrimiçi CPU'lar arasında
+    /// deadline yükü en az olan (worst-fit, payları CPU'lara yayar).
+    fn dl_admit_cpu(&self, mask: u64, util: u64) -> Option<usize> {
+        (0..MAX_CPUS)
+            .filter(|&cpu| mask & (1 << cpu) != 0 && self.cpus[cpu].online)
+            .filter(|&cpu| self.cpus[cpu].dl_util + util <= DL_UTIL_MAX)
+            .min_by_key(|&cpu| self.cpus[cpu].dl_util)
+    }
+}
+
+/// Karnal64 API fn thread_set_deadline karşılığı: çağıranın görevindeki `thread` iş parçacığını
+/// (`KThreadId(0)`: çağıran iş parçacığı) her `period_ns`'te, periyot başından itibaren
+/// `deadline_ns` içinde `runtime_ns` CPU süresi garantili deadline sınıfına alır.
+/// Koşullar: DL_RUNTIME_MIN_NS <= runtime <= deadline <= period <= DL_PERIOD_MAX_NS; aksi
+/// halde `KError::InvalidArgument`. Afinite maskesindeki hiçbir CPU'da yer yoksa `KError::Busy`.
+/// `runtime_ns` 0 ise iş parçacığı öncelik sınıfına geri döner. Kabul edilen iş parçacığı
+/// seçilen CPU'ya bağlanır (sınıftayken afinitesi değiştirilemez).
+pub fn set_thread_deadline(thread: KThreadId, runtime_ns: u64, deadline_ns: u64, period_ns: u64) -> Result<(), KError> {
+    let mut sched = SCHEDULER.lock_irqsave();
+    let slot = sched.own_thread_slot(thread)?;
+    if runtime_ns == 0 {
+        sched.dl_leave(slot);
+        return Ok(());
+    }
+    if runtime_ns < DL_RUNTIME_MIN_NS
+        || runtime_ns > deadline_ns
+        || deadline_ns > period_ns
+        || period_ns > DL_PERIOD_MAX_NS
+    {
+        return Err(KError::InvalidArgument);
+    }
+    let util = ((runtime_ns as u128) << DL_UTIL_SHIFT).div_ceil(period_ns as u128) as u64;
+
+    let t = sched.threads[slot].ok_or(KError::NotFound)?;
+    let mask = t.dl.map(|dl| dl.saved_affinity).unwrap_or(t.affinity);
+    // Parametre değişikliğinde eski pay önce bırakılır; yeni pay sığmazsa geri alınır.
+    if let Some(dl) = t.dl {
+        sched.cpus[dl.cpu].dl_util -= dl.util;
+    }
+    let target = match sched.dl_admit_cpu(mask, util) {
+        Some(cpu) => cpu,
+        None => {
+            if let Some(dl) = t.dl {
+                sched.cpus[dl.cpu].dl_util += dl.util;
+            }
+            return Err(KError::Busy);
+        }
+    };
+    sched.cpus[target].dl_util += util;
+
+    // Hazır kuyruğundaysa eski sınıfının kuyruğundan çıkarılır, yeni sınıfına göre yeniden konur.
+    // Kısıtlanmış hazır iş parçacığı kuyrukta değildir; eski yenileme zamanlayıcısı iptal edilir.
+    let old_throttled = t.state == TaskState::Ready && matches!(t.dl, Some(dl) if dl.throttled);
+    let requeue = t.state == TaskState::Ready && (sched.dequeue(slot, t.cpu) || old_throttled);
+    if old_throttled {
+        sched.set_deadline(slot, None);
+    }
+    let now = now_ns();
+    if let Some(thread) = sched.threads[slot].as_mut() {
+        thread.affinity = 1 << target;
+        thread.dl = Some(DeadlineEntity {
+            runtime_ns,
+            deadline_ns,
+            period_ns,
+            util,
+            abs_deadline_ns: now.saturating_add(deadline_ns),
+            budget_ns: runtime_ns,
+            exec_start_ns: now,
+            throttled: false,
+            cpu: target,
+            saved_affinity: mask,
+        });
+    }
+    match t.state {
+        TaskState::Ready if requeue && !t.on_cpu => sched.migrate(slot),
+        // Bağlamı kaydedilmekte: aynı CPU'daysa kuyruğa döner, değilse finish_switch taşır.
+        TaskState::Ready if requeue && t.cpu == target => {
+            sched.enqueue(slot, target);
+            sched.check_preempt(target);
+        }
+        // Yeniden zamanlanınca bütçe zamanlayıcısı kurulur; başka CPU'ya kabul edildiyse taşınır.
+        TaskState::Running => sched.resched_cpu(t.cpu),
+        // Bloklanmış iş parçacığı uyanınca bağlı olduğu CPU'ya döner (select_wake_cpu).
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,279 @@
+// Karnal64 ktask: istisna portları (exception port).
+// Bir görevin istisna portu atanmışsa, kullanıcı modunda çözülemeyen bir istisna görevi hemen
+// sonlandırmaz: hatalı iş parçacığı askıya alınır ve port sahibi işleyici görev için bir hata
+// mesajı (`FaultMessage`: neden, hatalı adres, PC ve yazmaç görüntüsü) kuyruğa konur. İşleyici
+// mesajı `exception_receive` ile alır, ardından `exception_reply` ile iş parçacığını devam
+// ettirir (isteğe bağlı olarak yazmaçlarını değiştirerek) veya öldürür. Hata ayıklayıcılar ve
+// çökme raporlayıcıları bunu kullanır.
+// Port atanmamışsa, işleyici görev sonlanmışsa veya işleyici yanıt vermeden sonlanırsa eski
+// davranış uygulanır: görev `terminate_current_task` ile sonlandırılır.
+// Mimari istisna işleyicileri yalnızca `deliver_fault`'u çağırır; kendi tuzak çerçevelerini
+// `FaultRegisters`'a çevirip, dönüşte işleyicinin değiştirdiği değerleri geri yazarlar.
+
+use super::super::{kmemory, KError, KTaskId, KThreadId};
+use super::super::ksync::{self, Spinlock};
+use super::{current_task_id, current_thread_id, exit_current, is_user_address, TaskState, MAX_THREADS, SCHEDULER};
+
+/// `FaultRegisters::gpr` boyutu; tüm portların genel amaçlı yazmaç dosyası buna sığar.
+pub const FAULT_GPR_COUNT: usize = 32;
+
+/// `exception_reply` eylemi: iş parçacığını (varsa değiştirilmiş yazmaçlarla) devam ettir.
+pub const EXCEPTION_RESUME: u64 = 0;
+/// `exception_reply` eylemi: iş parçacığını öldür. Görevin son iş parçacığıysa görev hata
+/// nedeniyle sonlanır.
+pub const EXCEPTION_KILL: u64 = 1;
+
+/// `exception_receive` bayrağı: bekleyen hata yoksa bloklamadan `KError::Busy` dön.
+pub const EXCEPTION_RECEIVE_NOHANG: u64 = 1 << 0;
+const EXCEPTION_RECEIVE_FLAGS: u64 = EXCEPTION_RECEIVE_NOHANG;
+
+/// Hatalı iş parçacığının kullanıcı modu yazmaç görüntüsü.
+/// `gpr` sıralaması mimarinin kendi numaralandırmasıdır (mips64/loongarch64/rv64i'de `gpr[n]` =
+/// rn/xn, amd64'te rax, rbx, rcx, rdx, rsi, rdi, rbp, r8-r15). `status` mimariye özel durum
+/// yazmacıdır; portlar geri yazarken yalnızca kullanıcının değiştirebileceği bitleri alır.
+/// `sp`, yığın işaretçisinin `gpr`'deki kopyasıyla aynıdır; geri yazarken `sp` esas alınır.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct FaultRegisters {
+    pub pc: u64,
+    pub sp: u64,
+    pub status: u64,
+    pub gpr: [u64; FAULT_GPR_COUNT],
+}
+
+/// İşleyiciye `exception_receive` ile kopyalanan hata mesajı (kullanıcı ABI'si, bkz. karnal.h).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct FaultMessage {
+    pub task: u64,
+    pub thread: u64,
+    /// Hata nedeni (KError değeri; örn. BadAddress, PermissionDenied).
+    pub reason: i64,
+    /// Mimariye özel istisna kodu (x86 vektörü, MIPS ExcCode, LoongArch Ecode, RISC-V scause).
+    pub cause: u64,
+    /// Hatalı adres (adres/sayfa hatalarında; diğerlerinde 0).
+    pub address: u64,
+    pub regs: FaultRegisters,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum FaultState {
+    /// Kuyrukta, işleyici henüz almadı.
+    Posted,
+    /// İşleyici aldı, yanıt bekleniyor.
+    Delivered,
+    /// İşleyici yanıt verdi; hatalı iş parçacığı uyanınca eylemi uygular.
+    Replied(u64),
+}
+
+#[derive(Debug, Copy, Clone)]
+struct PendingFault {
+    handler: KTaskId,
+    state: FaultState,
+    message: FaultMessage,
+}
+
+// Bir iş parçacığının en fazla bir bekleyen hatası olabilir; tablo MAX_THREADS ile sınırlıdır.
+static FAULTS: Spinlock<[Option<PendingFault>; MAX_THREADS]> =
+    Spinlock::named([None; MAX_THREADS], "ktask::FAULTS");
+// İşleyiciler yeni bir hata için, hatalı iş parçacıkları yanıt için bekler. Hatalar seyrek
+// olduğundan her iki kuyrukta da wake_all ile uyandırılıp koşul yeniden kontrol edilir.
+static FAULT_POSTED: ksync::WaitQueue = ksync::WaitQueue::new();
+static FAULT_REPLIED: ksync::WaitQueue = ksync::WaitQueue::new();
+
+/// Mevcut görevin istisna portunu (işleyici görevi) döner.
+fn current_port() -> Option<KTaskId> {
+    let sched = SCHEDULER.lock_irqsave();
+    let task_slot = sched.threads[sched.current()]?.task_slot;
+    sched.tasks[task_slot]?.exception_port
+}
+
+/// `id` görevi var ve sonlanmamışsa `true`.
+fn task_alive(id: KTaskId) -> bool {
+    let sched = SCHEDULER.lock_irqsave();
+    matches!(sched.task_slot_of(id).and_then(|slot| sched.tasks[slot]), Some(t) if t.state != TaskState::Exited)
+}
+
+/// Kullanıcı modunda çözülemeyen bir istisnayı mevcut görevin istisna portuna iletir.
+/// İşleyici iş parçacığını devam ettirirse `regs` işleyicinin (değiştirmiş olabileceği)
+/// yazmaçlarıyla güncellenmiş olarak döner; çağıran bunları tuzak çerçevesine geri yazmalıdır.
+/// Port yoksa, işleyici sonlanmışsa veya iş parçacığını öldürürse geri dönmez.
+pub fn deliver_fault(reason: KError, cause: u64, address: u64, regs: &mut FaultRegisters) {
+    let handler = match current_port() {
+        Some(handler) if task_alive(handler) => handler,
+        _ => super::terminate_current_task(reason),
+    };
+    let me = current_thread_id();
+    let message = FaultMessage {
+        task: current_task_id().0,
+        thread: me.0,
+        reason: reason as i64,
+        cause,
+        address,
+        regs: *regs,
+    };
+
+    let mut faults = FAULTS.lock();
+    let index = match faults.iter().position(|f| f.is_none()) {
+        Some(index) => index,
+        None => {
+            drop(faults);
+            super::terminate_current_task(reason)
+        }
+    };
+    faults[index] = Some(PendingFault { handler, state: FaultState::Posted, message });
+    drop(faults);
+    // Bekleyen işleyiciler kilit bırakıldıktan sonra uyandırılır; yanıt bu arada gelse bile
+    // döngü durumu kilit altında yeniden kontrol eder.
+    FAULT_POSTED.wake_all();
+
+    let mut faults = FAULTS.lock();
+    loop {
+        match faults[index].map(|f| f.state) {
+            Some(FaultState::Replied(action)) => {
+                let pending = faults[index].take();
+                drop(faults);
+                match pending {
+                    Some(p) if action == EXCEPTION_RESUME => {
+                        *regs = p.message.regs;
+                        return;
+                    }
+                    _ => exit_current(reason as i64 as i32, Some(reason), false),
+                }
+            }
+            // İşleyici yanıt vermeden sonlandıysa port yokmuş gibi davranılır.
+            Some(_) if !task_alive(handler) => {
+                faults[index] = None;
+                drop(faults);
+                super::terminate_current_task(reason);
+            }
+            _ => faults = FAULT_REPLIED.wait(faults),
+        }
+    }
+}
+
+/// Karnal64 API fn exception_port_set karşılığı: `task` görevinin (`KTaskId(0)`: çağıran görev)
+/// hatalarını `handler` görevine yönlendirir; `KTaskId(0)` portu kaldırır. Yalnızca görevin
+/// kendisi veya ebeveyni değiştirebilir. Yeni oluşturulan görevler ebeveynin portunu devralır.
+pub fn exception_port_set(task: KTaskId, handler: KTaskId) -> Result<(), KError> {
+    let me = current_task_id();
+    let task = if task == KTaskId(0) { me } else { task };
+    // Görev kendi hatalarını işleyemez: hatalı iş parçacığı askıdayken diğerleri de hata yapabilir.
+    if task == KTaskId(0) || handler == task {
+        return Err(KError::InvalidArgument);
+    }
+    let mut sched = SCHEDULER.lock_irqsave();
+    if handler != KTaskId(0) {
+        match sched.task_slot_of(handler).and_then(|slot| sched.tasks[slot]) {
+            Some(t) if t.state != TaskState::Exited => {}
+            _ => return Err(KError::NotFound),
+        }
+    }
+    let slot = sched.task_slot_of(task).ok_or(KError::NotFound)?;
+    let target = sched.tasks[slot].as_mut().ok_or(KError::NotFound)?;
+    if target.id != me && target.parent != me {
+        return Err(KError::PermissionDenied);
+    }
+    target.exception_port = if handler == KTaskId(0) { None } else { Some(handler) };
+    Ok(())
+}
+
+/// Karnal64 API fn exception_receive karşılığı: çağıran göreve iletilmiş bir hata gelene kadar
+/// bloklar, mesajı `buffer`'a kopyalar ve hatalı iş parçacığının ID'sini döner. İş parçacığı
+/// `exception_reply` çağrılana kadar askıda kalır.
+/// `EXCEPTION_RECEIVE_NOHANG` ile bekleyen hata yoksa `KError::Busy` döner.
+pub fn exception_receive(buffer: *mut FaultMessage, flags: u64) -> Result<KThreadId, KError> {
+    if flags & !EXCEPTION_RECEIVE_FLAGS != 0 {
+        return Err(KError::InvalidArgument);
+    }
+    let me = current_task_id();
+    let mut faults = FAULTS.lock();
+    loop {
+        let found = faults
+            .iter_mut()
+            .flatten()
+            .find(|f| f.handler == me && f.state == FaultState::Posted);
+        if let Some(fault) = found {
+            // Kopyalama kilit dışında yapılır; bu sürede başka bir alıcı aynı mesajı alamaz.
+            fault.state = FaultState::Delivered;
+            let message = fault.message;
+            drop(faults);
+            let copied = kmemory::copy_to_user(
+                buffer as *mut u8,
+                &message as *const FaultMessage as *const u8,
+                core::mem::size_of::<FaultMessage>(),
+            );
+            if let Err(e) = copied {
+                let mut faults = FAULTS.lock();
+                if let Some(f) = faults.iter_mut().flatten().find(|f| f.message.thread == message.thread) {
+                    f.state = FaultState::Posted;
+                }
+                return Err(e);
+            }
+            return Ok(KThreadId(message.thread));
+        }
+        if flags & EXCEPTION_RECEIVE_NOHANG != 0 {
+            return Err(KError::Busy);
+        }
+        faults = FAULT_POSTED.wait(faults);
+    }
+}
+
+/// Karnal64 API fn exception_reply karşılığı: `exception_receive` ile alınan `thread` hatasını
+/// yanıtlar. `EXCEPTION_RESUME` ile iş parçacığı devam eder; `regs` boş değilse önce yazmaçları
+/// bu görüntüyle değiştirilir. `EXCEPTION_KILL` ile iş parçacığı sonlandırılır.
+pub fn exception_reply(thread: KThreadId, action: u64, regs: *const FaultRegisters) -> Result<(), KError> {
+    if action != EXCEPTION_RESUME && action != EXCEPTION_KILL {
+        return Err(KError::InvalidArgument);
+    }
+    // Yazmaçlar kilit dışında kopyalanır (kullanıcı belleğine erişim sayfa hatası verebilir).
+    let new_regs = if action == EXCEPTION_RESUME && !regs.is_null() {
+        let mut new_regs = FaultRegisters::default();
+        kmemory::copy_from_user(
+            &mut new_regs as *mut FaultRegisters as *mut u8,
+            regs as *const u8,
+            core::mem::size_of::<FaultRegisters>(),
+        )?;
+        // Devam adresi kullanıcı alanında olmalı (amd64'te kanonik olmayan bir RIP'e iretq
+        // çekirdek modunda #GP üretir).
+        if !is_user_address(new_regs.pc) {
+            return Err(KError::BadAddress);
+        }
+        Some(new_regs)
+    } else {
+        None
+    };
+    let me = current_task_id();
+    let mut faults = FAULTS.lock();
+    let fault = faults
+        .iter_mut()
+        .flatten()
+        .find(|f| f.message.thread == thread.0 && f.handler == me && f.state == FaultState::Delivered)
+        .ok_or(KError::NotFound)?;
+    if let Some(new_regs) = new_regs {
+        fault.message.regs = new_regs;
+    }
+    fault.state = FaultState::Replied(action);
+    drop(faults);
+    FAULT_REPLIED.wake_all();
+    Ok(())
+}
+
+/// Sonlanan `task` görevine iletilmiş, henüz yanıtlanmamış hataların iş parçacıklarını uyandırır;
+/// işleyicisi olmadığını görüp görevlerini sonlandırırlar (ktask çıkış yolu).
+pub(super) fn handler_exited(task: KTaskId) {
+    let pending = FAULTS.lock().iter().flatten().any(|f| f.handler == task);
+    if pending {
+        FAULT_REPLIED.wake_all();
+    }
+}
+
+/// Toplanan `task` görevinin iş parçacıklarına ait, artık kimsenin beklemediği hata
+/// kayıtlarını siler (ktask reaping).
+pub(super) fn release_task_faults(task: KTaskId) {
+    for fault in FAULTS.lock().iter_mut() {
+        if matches!(fault, Some(f) if f.message.task == task.0) {
+            *fault = None;
+        }
+    }
+}
@@ -0,0 +1,644 @@
+// Karnal64 ktask: hiyerarşik görev grupları (job).
+// Her görev bir işe aittir; yeni görev oluşturan görevin işine girer. İşler iç içedir: kök iş
+// (JOB_ROOT) çekirdek görevini ve onun doğrudan oluşturduğu görevleri tutar, diğer her işin bir
+// ebeveyn işi vardır. Bir işin alt ağacı kendisi ve tüm alt işleridir; toplu işlemler alt ağaçtaki
+// tüm görevlere uygulanır:
+// - öldürme: alt ağaçtaki görevler tüm iş parçacıklarıyla sonlanır (çağıran dahilse en son)
+// - askıya alma/devam ettirme: askıdaki bir işin iş parçacıkları hazır olsalar da kuyruğa girmez
+//   (`ThreadSlot::suspended`), çalışanlar hemen CPU'yu bırakır
+// - muhasebe: yaşayan görevlerin sayaçları ile toplanmış görevlerden/işlerden biriken sayaçlar
+// - kotalar: her işin görevlerinkiyle aynı türden sınırları vardır (bkz. srcquota.rs); görev adına
+//   yazılan kullanım işin ve tüm üst işlerin kotasına da yazılır, CPU payı alt ağacın toplamıdır.
+// İşlere kaynak handle'larıyla erişilir: `resource_acquire("karnal://job/...")` ile alınan handle
+// `resource_control` ile yönetilir (JOB_CONTROL_*). Handle'lar alan göreve aittir ve onun handle
+// kotasından sayılır; görev toplanınca bırakılır. Görevi, handle'ı veya alt işi kalmayan iş
+// serbest bırakılır ve sayaçları ebeveynine eklenir. Bir görev yalnızca kendi işinin alt ağacındaki
+// işleri yönetebilir; askıya alma ve sınır belirleme yalnızca kesin alt işlere (kendi işi hariç)
+// uygulanabilir.
+
+use super::super::{kmemory, KError, KHandle, KTaskId};
+use super::quota::{
+    TaskQuota, QUOTA_COUNT, QUOTA_COUNTED, QUOTA_CPU, QUOTA_HANDLES, QUOTA_THREADS, QUOTA_UNLIMITED,
+};
+use super::{
+    excport, exit_current, now_ns, stats_to_user, QuotaState, Scheduler, TaskState, ThreadStats, CHILD_EXIT,
+    KERNEL_TASK_SLOT, MAX_CPUS, MAX_TASKS, MAX_THREADS, SCHEDULER, WAIT_LOCK,
+};
+
+/// Aynı anda var olabilecek en fazla iş sayısı (kök dahil).
+pub const MAX_JOBS: usize = 32;
+
+/// Tüm görevlerin açık tutabileceği en fazla iş handle'ı sayısı.
+pub(super) const MAX_JOB_HANDLES: usize = 64;
+
+/// Kök işin tablo indeksi.
+pub(super) const JOB_ROOT: usize = 0;
+
+/// İş handle'larının `resource_acquire` adı öneki. Ardından `self` (çağıranın işi), `new`
+/// (çağıranın işinin yeni bir alt işi) veya onluk iş ID'si gelir.
+pub const JOB_RESOURCE_PREFIX: &str = "karnal://job/";
+
+// resource_control istekleri (iş handle'ları için)
+/// İşin ID'sini döner.
+pub const JOB_CONTROL_ID: u64 = 0;
+/// Alt ağaçtaki tüm görevleri `arg` çıkış koduyla sonlandırır.
+pub const JOB_CONTROL_KILL: u64 = 1;
+/// Alt ağaçtaki tüm iş parçacıklarını askıya alır.
+pub const JOB_CONTROL_SUSPEND: u64 = 2;
+/// Askıya alınmış işi devam ettirir.
+pub const JOB_CONTROL_RESUME: u64 = 3;
+/// Alt ağacın toplam sayaçlarını `arg`'daki ThreadStats tamponuna yazar.
+pub const JOB_CONTROL_STATS: u64 = 4;
+/// İşin sınırlarını ve alt ağacın kullanımını `arg`'daki TaskQuota tamponuna yazar.
+pub const JOB_CONTROL_QUOTA: u64 = 5;
+/// `arg` görevini (0: çağıran görev; aksi halde çağıranın çocuğu) bu işe taşır.
+pub const JOB_CONTROL_ADD_TASK: u64 = 6;
+/// Bu işin altında yeni bir iş oluşturur ve handle'ını döner.
+pub const JOB_CONTROL_CREATE_CHILD: u64 = 7;
+/// `JOB_CONTROL_SET_LIMIT + QUOTA_*`: kaynağın sınırını `arg` yapar.
+pub const JOB_CONTROL_SET_LIMIT: u64 = 0x100;
+
+const JOB_HANDLE_TAG: u64 = 0x4A << 56; // 'J'
+
+/// Tek bir işin durumu.
+#[derive(Debug, Copy, Clone)]
+pub(super) struct JobSlot {
+    id: u64,
+    /// Ebeveyn işin tablo indeksi; kök iş için `None`.
+    parent: Option<usize>,
+    /// Doğrudan bu işe ait (toplanmamış) görev sayısı.
+    tasks: u32,
+    /// Doğrudan alt iş sayısı.
+    children: u32,
+    /// Bu işe açık handle sayısı.
+    handles: u32,
+    suspended: bool,
+    /// Toplanmış görevlerin ve serbest bırakılmış alt işlerin toplam sayaçları.
+    exited_stats: ThreadStats,
+    /// İşin sınırları ve alt ağacının kullanımı.
+    quota: QuotaState,
+}
+
+impl JobSlot {
+    /// Kök iş: çekirdek görevini tutar, sınırsızdır.
+    pub(super) fn root() -> Self {
+        JobSlot {
+            id: 1,
+            parent: None,
+            tasks: 1,
+            children: 0,
+            handles: 0,
+            suspended: false,
+            exited_stats: ThreadStats::default(),
+            quota: QuotaState::unlimited(),
+        }
+    }
+}
+
+/// Bir iş handle'ı: sahibi görev ve işin tablo indeksi. Nesil sayacı, bırakılıp yeniden
+/// kullanılan yuvaya eski handle değeriyle erişilmesini önler.
+#[derive(Debug, Copy, Clone)]
+pub(super) struct JobHandle {
+    generation: u16,
+    owner: Option<KTaskId>,
+    job: usize,
+}
+
+impl JobHandle {
+    pub(super) const EMPTY: JobHandle = JobHandle { generation: 0, owner: None, job: 0 };
+}
+
+impl Scheduler {
+    fn job_parent(&self, job: usize) -> Option<usize> {
+        self.jobs[job].and_then(|j| j.parent)
+    }
+
+    /// `job` ve tüm üst işleri, `job`'dan köke doğru.
+    fn job_chain(&self, job: usize) -> impl Iterator<Item = usize> + '_ {
+        core::iter::successors(Some(job), move |&j| self.job_parent(j))
+    }
+
+    /// `job`, `ancestor`'ın alt ağacında mı (kendisi dahil).
+    fn job_contains(&self, ancestor: usize, job: usize) -> bool {
+        self.job_chain(job).any(|j| j == ancestor)
+    }
+
+    /// `task_slot`'taki görev `job`'ın alt ağacında mı.
+    fn task_in_job(&self, task_slot: usize, job: usize) -> bool {
+        matches!(self.tasks[task_slot], Some(t) if self.job_contains(job, t.job))
+    }
+
+    /// `slot`'un görevinin işi veya bir üst işi askıda mı.
+    pub(super) fn thread_held(&self, slot: usize) -> bool {
+        let task_slot = match self.threads[slot] {
+            Some(t) => t.task_slot,
+            None => return false,
+        };
+        match self.tasks[task_slot] {
+            Some(t) => self.job_chain(t.job).any(|j| matches!(self.jobs[j], Some(job) if job.suspended)),
+            None => false,
+        }
+    }
+
+    /// `job`'ın alt ağacındaki yaşayan iş parçacığı sayısı.
+    fn job_live_threads(&self, job: usize) -> u64 {
+        (0..MAX_TASKS)
+            .filter(|&slot| self.task_in_job(slot, job))
+            .map(|slot| self.tasks[slot].map(|t| t.live_threads as u64).unwrap_or(0))
+            .sum()
+    }
+
+    /// `job` ile üst işlerinin kotalarına, `until`'e (hariç; `None`: köke kadar) varana dek
+    /// `resource` kullanımı olarak `amount` yazar. Birinde sınır aşılacaksa hiçbiri değişmez.
+    pub(super) fn job_charge(
+        &mut self,
+        job: usize,
+        until: Option<usize>,
+        resource: usize,
+        amount: u64,
+    ) -> Result<(), KError> {
+        let mut next = Some(job);
+        while let Some(j) = next.filter(|&j| Some(j) != until) {
+            let slot = match self.jobs[j].as_mut() {
+                Some(slot) => slot,
+                None => break,
+            };
+            if let Err(err) = slot.quota.charge(resource, amount) {
+                // Bu işe kadar yazılanlar geri alınır.
+                self.job_uncharge(job, Some(j), resource, amount);
+                return Err(err);
+            }
+            next = slot.parent;
+        }
+        Ok(())
+    }
+
+    /// `job_charge`'ın tersi.
+    pub(super) fn job_uncharge(&mut self, job: usize, until: Option<usize>, resource: usize, amount: u64) {
+        let mut next = Some(job);
+        while let Some(j) = next.filter(|&j| Some(j) != until) {
+            next = match self.jobs[j].as_mut() {
+                Some(slot) => {
+                    slot.quota.uncharge(resource, amount);
+                    slot.parent
+                }
+                None => None,
+            };
+        }
+    }
+
+    /// `task_slot`'taki görev bir iş parçacığı daha ekleyebilir mi (işlerin sınırları).
+    pub(super) fn job_check_thread(&self, task_slot: usize) -> Result<(), KError> {
+        let job = self.tasks[task_slot].map(|t| t.job).ok_or(KError::NotFound)?;
+        for j in self.job_chain(job) {
+            let limit = self.jobs[j].map(|s| s.quota.limit(QUOTA_THREADS)).unwrap_or(QUOTA_UNLIMITED);
+            if limit != QUOTA_UNLIMITED && self.job_live_threads(j) >= limit {
+                return Err(KError::Busy);
+            }
+        }
+        Ok(())
+    }
+
+    /// `since`..`now` arasında çalışan `task_slot` görevinin süresini işinin ve üst işlerinin CPU
+    /// pencerelerine yazar. Birinin payı yeni aşıldıysa `true`.
+    pub(super) fn job_charge_cpu(&mut self, task_slot: usize, since: u64, now: u64) -> bool {
+        let mut exceeded = false;
+        let mut next = self.tasks[task_slot].map(|t| t.job);
+        while let Some(j) = next {
+            next = match self.jobs[j].as_mut() {
+                Some(slot) => {
+                    exceeded |= slot.quota.charge_cpu(since, now);
+                    slot.parent
+                }
+                None => None,
+            };
+        }
+        exceeded
+    }
+
+    /// `task_slot`'taki görevin işlerinden birinin CPU payı tükendiyse en geç yeniden çalışma anı.
+    pub(super) fn job_throttled(&self, task_slot: usize, now: u64) -> Option<u64> {
+        let job = self.tasks[task_slot]?.job;
+        self.job_chain(job).filter_map(|j| self.jobs[j]?.quota.throttled(now)).max()
+    }
+
+    /// `parent` altında yeni bir iş oluşturur. Henüz görevi ve handle'ı yoktur; çağıran hemen
+    /// bir handle açmazsa `job_release_if_empty` ile bırakılmalıdır.
+    fn job_create(&mut self, parent: usize) -> Result<usize, KError> {
+        let job = (0..MAX_JOBS).find(|&j| j != JOB_ROOT && self.jobs[j].is_none()).ok_or(KError::OutOfMemory)?;
+        let id = self.next_job_id;
+        self.next_job_id += 1;
+        self.jobs[job] = Some(JobSlot {
+            id,
+            parent: Some(parent),
+            tasks: 0,
+            children: 0,
+            handles: 0,
+            suspended: false,
+            exited_stats: ThreadStats::default(),
+            quota: QuotaState::unlimited(),
+        });
+        if let Some(p) = self.jobs[parent].as_mut() {
+            p.children += 1;
+        }
+        Ok(job)
+    }
+
+    /// Görevi, handle'ı ve alt işi kalmayan `job`'ı (ve aynı duruma düşen üst işlerini) serbest
+    /// bırakır; sayaçları ebeveynine eklenir. Kök iş hiç bırakılmaz.
+    fn job_release_if_empty(&mut self, mut job: usize) {
+        while job != JOB_ROOT {
+            let slot = match self.jobs[job] {
+                Some(s) if s.tasks == 0 && s.children == 0 && s.handles == 0 => s,
+                _ => return,
+            };
+            self.jobs[job] = None;
+            let parent = match slot.parent {
+                Some(parent) => parent,
+                None => return,
+            };
+            if let Some(p) = self.jobs[parent].as_mut() {
+                p.children -= 1;
+                p.exited_stats.accumulate(&slot.exited_stats);
+            }
+            job = parent;
+        }
+    }
+
+    /// Yeni görev `job`'a girdi.
+    pub(super) fn job_task_added(&mut self, job: usize) {
+        if let Some(slot) = self.jobs[job].as_mut() {
+            slot.tasks += 1;
+        }
+    }
+
+    /// Toplanan görev işinden çıkar: sayaçları (`stats`) işte birikir, işlere yazılmış kalan
+    /// kullanımı geri alınır (görevin kaynakları toplandıktan sonra bırakılır).
+    pub(super) fn job_task_reaped(&mut self, job: usize, quota: &QuotaState, stats: &ThreadStats) {
+        for &resource in QUOTA_COUNTED.iter() {
+            self.job_uncharge(job, None, resource, quota.usage(resource));
+        }
+        if let Some(slot) = self.jobs[job].as_mut() {
+            slot.exited_stats.accumulate(stats);
+            slot.tasks -= 1;
+        }
+        self.job_release_if_empty(job);
+    }
+
+    /// `task_slot`'taki görevi alt ağacındaki `target` işine taşır. Kullanımı aradaki işlerin
+    /// kotalarına yazılır; birinin sınırı aşılacaksa görev taşınmaz.
+    fn job_move_task(&mut self, task_slot: usize, target: usize) -> Result<(), KError> {
+        let task = self.tasks[task_slot].ok_or(KError::NotFound)?;
+        let from = task.job;
+        if from == target {
+            return Ok(());
+        }
+        for j in self.job_chain(target).take_while(|&j| j != from) {
+            let limit = self.jobs[j].map(|s| s.quota.limit(QUOTA_THREADS)).unwrap_or(QUOTA_UNLIMITED);
+            if limit != QUOTA_UNLIMITED && self.job_live_threads(j) + task.live_threads as u64 > limit {
+                return Err(KError::Busy);
+            }
+        }
+        for (index, &resource) in QUOTA_COUNTED.iter().enumerate() {
+            if let Err(err) = self.job_charge(target, Some(from), resource, task.quota.usage(resource)) {
+                for &done in QUOTA_COUNTED[..index].iter() {
+                    self.job_uncharge(target, Some(from), done, task.quota.usage(done));
+                }
+                return Err(err);
+            }
+        }
+        if let Some(slot) = self.jobs[from].as_mut() {
+            slot.tasks -= 1;
+        }
+        self.job_task_added(target);
+        if let Some(t) = self.tasks[task_slot].as_mut() {
+            t.job = target;
+        }
+        // Askıdaki bir işe taşındıysa iş parçacıkları hemen bekletilir.
+        self.job_hold_threads();
+        Ok(())
+    }
+
+    /// Askıdaki işlerin hazır iş parçacıklarını kuyruklardan çıkarır ve çalışanlarının CPU'larını
+    /// yeniden zamanlatır (onlar kuyruğa dönerken `enqueue` bekletir).
+    fn job_hold_threads(&mut self) {
+        for slot in 0..MAX_THREADS {
+            let t = match self.threads[slot] {
+                Some(t) if !t.suspended && !self.is_idle(slot) => t,
+                _ => continue,
+            };
+            if !self.thread_held(slot) {
+                continue;
+            }
+            match t.state {
+                // Kuyrukta değilse (CPU payı veya deadline bütçesi bekleniyor) süre dolunca enqueue bekletir.
+                TaskState::Ready if self.dequeue(slot, t.cpu) => {
+                    if let Some(thread) = self.threads[slot].as_mut() {
+                        thread.suspended = true;
+                    }
+                }
+                TaskState::Running => self.resched_cpu(t.cpu),
+                // Bloklanmış iş parçacıkları uyandırılınca bekletilir.
+                _ => {}
+            }
+        }
+    }
+
+    /// Artık askıda bir işte olmayan bekletilmiş iş parçacıklarını kuyruğa geri koyar.
+    fn job_release_threads(&mut self) {
+        for slot in 0..MAX_THREADS {
+            let t = match self.threads[slot] {
+                Some(t) if t.suspended => t,
+                _ => continue,
+            };
+            if t.state == TaskState::Ready && self.thread_held(slot) {
+                continue;
+            }
+            if let Some(thread) = self.threads[slot].as_mut() {
+                thread.suspended = false;
+            }
+            if t.state == TaskState::Ready {
+                self.enqueue(slot, t.cpu);
+                self.check_preempt(t.cpu);
+            }
+        }
+    }
+
+    /// `job`'ın alt ağacındaki görevlerin (zombiler dahil) ve işlerin toplam sayaçları.
+    fn job_stats(&self, job: usize, now: u64) -> ThreadStats {
+        let mut total = ThreadStats::default();
+        for slot in (0..MAX_TASKS).filter(|&slot| self.task_in_job(slot, job)) {
+            if let Some(stats) = self.task_total_stats(slot, now) {
+                total.accumulate(&stats);
+            }
+        }
+        for j in (0..MAX_JOBS).filter(|&j| self.job_contains(job, j)) {
+            if let Some(slot) = self.jobs[j] {
+                total.accumulate(&slot.exited_stats);
+            }
+        }
+        total
+    }
+
+    /// `job`'ın kotası; iş parçacığı kullanımı alt ağacın yaşayan iş parçacıklarıdır.
+    fn job_quota(&self, job: usize) -> Option<TaskQuota> {
+        let mut quota = self.jobs[job]?.quota.snapshot();
+        quota.usage[QUOTA_THREADS] = self.job_live_threads(job);
+        Some(quota)
+    }
+
+    /// `owner` adına `job`'a bir handle açar; handle, sahibinin handle kotasından sayılır.
+    fn job_handle_open(&mut self, owner: KTaskId, job: usize) -> Result<KHandle, KError> {
+        self.quota_charge(owner, QUOTA_HANDLES, 1)?;
+        let index = match self.job_handles.iter().position(|h| h.owner.is_none()) {
+            Some(index) => index,
+            None => {
+                self.quota_uncharge(owner, QUOTA_HANDLES, 1);
+                return Err(KError::OutOfMemory);
+            }
+        };
+        let handle = &mut self.job_handles[index];
+        handle.owner = Some(owner);
+        handle.job = job;
+        let generation = handle.generation;
+        if let Some(slot) = self.jobs[job].as_mut() {
+            slot.handles += 1;
+        }
+        Ok(KHandle(JOB_HANDLE_TAG | ((generation as u64) << 16) | (index as u64 + 1)))
+    }
+
+    /// `handle_value`'yu çağıran göreve (`owner`) ait bir iş handle'ının tablo indeksine çözer.
+    fn job_handle_index(&self, owner: KTaskId, handle_value: u64) -> Result<usize, KError> {
+        if !is_job_handle(handle_value) {
+            return Err(KError::BadHandle);
+        }
+        let generation = ((handle_value >> 16) & 0xFFFF) as u16;
+        let index = (handle_value & 0xFFFF) as usize;
+        if index == 0 || index > MAX_JOB_HANDLES {
+            return Err(KError::BadHandle);
+        }
+        let handle = self.job_handles[index - 1];
+        if handle.owner != Some(owner) || handle.generation != generation {
+            return Err(KError::BadHandle);
+        }
+        Ok(index - 1)
+    }
+
+    fn job_handle_close(&mut self, index: usize) {
+        let handle = self.job_handles[index];
+        let owner = match handle.owner {
+            Some(owner) => owner,
+            None => return,
+        };
+        self.job_handles[index] = JobHandle { generation: handle.generation.wrapping_add(1), owner: None, job: 0 };
+        if let Some(slot) = self.jobs[handle.job].as_mut() {
+            slot.handles -= 1;
+        }
+        self.job_release_if_empty(handle.job);
+        self.quota_uncharge(owner, QUOTA_HANDLES, 1);
+    }
+
+    /// Çağıran görevin ID'si ve işi.
+    fn current_job(&self) -> Result<(KTaskId, usize), KError> {
+        let task_slot = self.threads[self.current()].map(|t| t.task_slot).ok_or(KError::InternalError)?;
+        self.tasks[task_slot].map(|t| (t.id, t.job)).ok_or(KError::InternalError)
+    }
+}
+
+/// `handle_value` bir iş handle'ı mı (geçerliliği kontrol edilmez).
+pub fn is_job_handle(handle_value: u64) -> bool {
+    handle_value & (0xFF << 56) == JOB_HANDLE_TAG
+}
+
+/// `resource_acquire` yolu: JOB_RESOURCE_PREFIX'ten sonraki `name` ile bir iş handle'ı açar.
+/// ID ile yalnızca çağıranın işinin alt ağacındaki işler açılabilir.
+pub fn job_acquire(name: &str) -> Result<KHandle, KError> {
+    let mut sched = SCHEDULER.lock_irqsave();
+    let (own, own_job) = sched.current_job()?;
+    match name {
+        "self" => sched.job_handle_open(own, own_job),
+        "new" => {
+            let job = sched.job_create(own_job)?;
+            let handle = sched.job_handle_open(own, job);
+            if handle.is_err() {
+                sched.job_release_if_empty(job);
+            }
+            handle
+        }
+        id => {
+            let id: u64 = id.parse().map_err(|_| KError::InvalidArgument)?;
+            let job = (0..MAX_JOBS)
+                .find(|&j| matches!(sched.jobs[j], Some(slot) if slot.id == id))
+                .ok_or(KError::NotFound)?;
+            if !sched.job_contains(own_job, job) {
+                return Err(KError::PermissionDenied);
+            }
+            sched.job_handle_open(own, job)
+        }
+    }
+}
+
+/// `resource_release` yolu: çağıranın iş handle'ını kapatır.
+pub fn job_release(handle_value: u64) -> Result<(), KError> {
+    let mut sched = SCHEDULER.lock_irqsave();
+    let (own, _) = sched.current_job()?;
+    let index = sched.job_handle_index(own, handle_value)?;
+    sched.job_handle_close(index);
+    Ok(())
+}
+
+/// `handle_value` çağırana ait geçerli bir iş handle'ı mı.
+pub(super) fn check_job_handle(handle_value: u64) -> Result<(), KError> {
+    let sched = SCHEDULER.lock_irqsave();
+    let (own, _) = sched.current_job()?;
+    sched.job_handle_index(own, handle_value).map(|_| ())
+}
+
+/// Çağıranın iş handle'ının işine `task` adına yeni bir handle açar (task_spawn devri).
+pub(super) fn share_job_handle(handle_value: u64, task: KTaskId) -> Result<KHandle, KError> {
+    let mut sched = SCHEDULER.lock_irqsave();
+    let (own, _) = sched.current_job()?;
+    let index = sched.job_handle_index(own, handle_value)?;
+    let job = sched.job_handles[index].job;
+    sched.job_handle_open(task, job)
+}
+
+/// Toplanan `task` görevinin açık iş handle'larını kapatır.
+pub(super) fn release_task_jobs(task: KTaskId) {
+    let mut sched = SCHEDULER.lock_irqsave();
+    for index in 0..MAX_JOB_HANDLES {
+        if sched.job_handles[index].owner == Some(task) {
+            sched.job_handle_close(index);
+        }
+    }
+}
+
+/// `resource_control` yolu: iş handle'ı üzerinde bir JOB_CONTROL_* isteği yürütür.
+pub fn job_control(handle_value: u64, request: u64, arg: u64) -> Result<u64, KError> {
+    let (own, own_job, job) = {
+        let sched = SCHEDULER.lock_irqsave();
+        let (own, own_job) = sched.current_job()?;
+        let index = sched.job_handle_index(own, handle_value)?;
+        let job = sched.job_handles[index].job;
+        // Çağıran, handle'ı aldıktan sonra başka bir işe taşınmış olabilir.
+        if !sched.job_contains(own_job, job) {
+            return Err(KError::PermissionDenied);
+        }
+        (own, own_job, job)
+    };
+    match request {
+        JOB_CONTROL_ID => {
+            let sched = SCHEDULER.lock_irqsave();
+            sched.jobs[job].map(|slot| slot.id).ok_or(KError::BadHandle)
+        }
+        JOB_CONTROL_KILL => job_kill(job, arg as i32).map(|_| 0),
+        JOB_CONTROL_SUSPEND | JOB_CONTROL_RESUME => {
+            if job == own_job {
+                return Err(KError::PermissionDenied);
+            }
+            let mut sched = SCHEDULER.lock_irqsave();
+            let suspend = request == JOB_CONTROL_SUSPEND;
+            if let Some(slot) = sched.jobs[job].as_mut() {
+                slot.suspended = suspend;
+            }
+            if suspend {
+                sched.job_hold_threads();
+            } else {
+                sched.job_release_threads();
+            }
+            Ok(0)
+        }
+        JOB_CONTROL_STATS => {
+            let stats = SCHEDULER.lock_irqsave().job_stats(job, now_ns());
+            stats_to_user(&stats, arg as *mut ThreadStats).map(|_| 0)
+        }
+        JOB_CONTROL_QUOTA => {
+            let quota = SCHEDULER.lock_irqsave().job_quota(job).ok_or(KError::BadHandle)?;
+            kmemory::copy_to_user(
+                arg as *mut u8,
+                &quota as *const TaskQuota as *const u8,
+                core::mem::size_of::<TaskQuota>(),
+            )
+            .map(|_| 0)
+        }
+        JOB_CONTROL_ADD_TASK => {
+            let mut sched = SCHEDULER.lock_irqsave();
+            let task = if arg == 0 { own } else { KTaskId(arg) };
+            let task_slot = sched.task_slot_of(task).ok_or(KError::NotFound)?;
+            match sched.tasks[task_slot] {
+                Some(t) if t.state == TaskState::Exited => return Err(KError::NotFound),
+                Some(t) if task != own && t.parent != own => return Err(KError::PermissionDenied),
+                // Görev yalnızca bulunduğu işin alt ağacına taşınabilir.
+                Some(t) if !sched.job_contains(t.job, job) => return Err(KError::PermissionDenied),
+                Some(_) => {}
+                None => return Err(KError::NotFound),
+            }
+            sched.job_move_task(task_slot, job).map(|_| 0)
+        }
+        JOB_CONTROL_CREATE_CHILD => {
+            let mut sched = SCHEDULER.lock_irqsave();
+            let child = sched.job_create(job)?;
+            let handle = sched.job_handle_open(own, child);
+            if handle.is_err() {
+                sched.job_release_if_empty(child);
+            }
+            handle.map(|h| h.0)
+        }
+        request if request >= JOB_CONTROL_SET_LIMIT && request < JOB_CONTROL_SET_LIMIT + QUOTA_COUNT as u64 => {
+            if job == own_job {
+                return Err(KError::PermissionDenied);
+            }
+            let resource = (request - JOB_CONTROL_SET_LIMIT) as usize;
+            if resource == QUOTA_CPU && arg != QUOTA_UNLIMITED && (arg == 0 || arg > 100 * MAX_CPUS as u64) {
+                return Err(KError::InvalidArgument);
+            }
+            let mut sched = SCHEDULER.lock_irqsave();
+            if let Some(slot) = sched.jobs[job].as_mut() {
+                slot.quota.set_limit(resource, arg);
+            }
+            if resource == QUOTA_CPU {
+                sched.quota_unpark_all(now_ns());
+            }
+            Ok(0)
+        }
+        _ => Err(KError::InvalidArgument),
+    }
+}
+
+/// `job`'ın alt ağacındaki tüm görevleri `code` ile sonlandırır. Çağıranın görevi de içindeyse
+/// diğerleri sonlandırıldıktan sonra o da sonlanır ve çağrı geri dönmez.
+fn job_kill(job: usize, code: i32) -> Result<(), KError> {
+    // exit_current'taki gibi: ebeveynler uyandırılmadan önce kesintiye uğranmaz.
+    let wait_guard = WAIT_LOCK.lock();
+    let mut killed = [None; MAX_TASKS];
+    let includes_caller = {
+        let mut sched = SCHEDULER.lock_irqsave();
+        let own_slot = sched.threads[sched.current()].map(|t| t.task_slot).ok_or(KError::InternalError)?;
+        let mut includes_caller = false;
+        for slot in (0..MAX_TASKS).filter(|&slot| slot != KERNEL_TASK_SLOT) {
+            if !matches!(sched.tasks[slot], Some(t) if t.state != TaskState::Exited) || !sched.task_in_job(slot, job) {
+                continue;
+            }
+            if slot == own_slot {
+                includes_caller = true;
+                continue;
+            }
+            if let Some(id) = sched.kill_task(slot, code, None) {
+                sched.reparent_children(id);
+                killed[slot] = Some(id);
+            }
+        }
+        includes_caller
+    };
+    if killed.iter().any(Option::is_some) {
+        CHILD_EXIT.wake_all();
+    }
+    for id in killed.iter().flatten() {
+        excport::handler_exited(*id);
+    }
+    drop(wait_guard);
+    if includes_caller {
+        exit_current(code, None, true);
+    }
+    Ok(())
+}
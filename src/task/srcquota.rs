@@ -0,0 +1,422 @@
+// Karnal64 ktask: görev başına kaynak kotaları.
+// Her görevin kaydedilmiş (committed) bellek, handle sayısı, IPC kuyruk baytı, iş parçacığı sayısı
+// ve CPU payı için bir sınırı vardır (`TaskQuota`). Yeni görev sınırları oluşturan görevden
+// miras alır; ebeveyn (denetleyici) çocuklarının sınırlarını `set_task_limit` ile kendi
+// sınırlarını aşmamak kaydıyla belirler, bir görev kendi sınırlarını yalnızca düşürebilir.
+// Kaynağı ayıran alt sistem kullanımı görev adına yazar (`quota_charge`) ve bırakırken geri
+// alır (`quota_uncharge`); sınırı aşan ayırma bellek ve IPC baytlarında `KError::OutOfMemory`,
+// handle ve iş parçacıklarında `KError::Busy` ile reddedilir. İş parçacığı kullanımı görevin
+// yaşayan iş parçacığı sayısıdır.
+// CPU payı, bir CPU'nun yüzdesi olarak QUOTA_CPU_WINDOW_NS pencerelerinde ölçülür (200: iki tam
+// CPU). Payını tüketen görevin iş parçacıkları pencere bitene kadar hazır kuyruğuna girmez; bekleme
+// anı CPU'nun zamanlayıcı kuyruğundadır. Kullanım bağlam değişimi ve zamanlayıcı kesmesi
+// hassasiyetinde ölçülür. Deadline sınıfındaki iş parçacıkları kendi kabul denetimleriyle
+// sınırlandığından CPU payı nedeniyle bekletilmez.
+// İşlerin (bkz. srcjob.rs) de aynı türden kotaları vardır: görev adına yazılan kullanım görevin
+// işine ve tüm üst işlerine de yazılır; ayırma ancak hepsinin sınırı içinde kalırsa kabul edilir.
+
+use super::super::{kmemory, KError, KTaskId};
+use super::{current_task_id, now_ns, Scheduler, TaskState, MAX_CPUS, MAX_THREADS, SCHEDULER};
+
+pub const QUOTA_MEMORY: usize = 0;
+pub const QUOTA_HANDLES: usize = 1;
+pub const QUOTA_IPC_BYTES: usize = 2;
+pub const QUOTA_THREADS: usize = 3;
+pub const QUOTA_CPU: usize = 4;
+pub const QUOTA_COUNT: usize = 5;
+
+/// Alt sistemlerin yazdığı (CPU ve iş parçacığı dışındaki) kaynaklar.
+pub(super) const QUOTA_COUNTED: [usize; 3] = [QUOTA_MEMORY, QUOTA_HANDLES, QUOTA_IPC_BYTES];
+
+/// Sınırsız kota değeri.
+pub const QUOTA_UNLIMITED: u64 = u64::MAX;
+
+/// CPU payının ölçüldüğü pencere.
+pub const QUOTA_CPU_WINDOW_NS: u64 = 100_000_000;
+
+/// Bir görevin sınırları ve kullanımı (kullanıcı alanına aynen kopyalanır). İndeksler QUOTA_*.
+/// `usage[QUOTA_CPU]`, son tamamlanan pencerede kullanılan CPU yüzdesidir.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TaskQuota {
+    pub limit: [u64; QUOTA_COUNT],
+    pub usage: [u64; QUOTA_COUNT],
+}
+
+/// Görev ve iş yuvalarında tutulan kota durumu.
+#[derive(Debug, Copy, Clone)]
+pub(super) struct QuotaState {
+    quota: TaskQuota,
+    /// Geçerli CPU penceresinin başlangıcı ve bu pencerede kullanılan süre.
+    cpu_window_start_ns: u64,
+    cpu_window_used_ns: u64,
+    /// CPU payı tükendiyse iş parçacıklarının yeniden çalışabileceği an.
+    throttled_until_ns: u64,
+}
+
+impl QuotaState {
+    /// Sınırsız kota (çekirdek görevi).
+    pub(super) const fn unlimited() -> Self {
+        QuotaState {
+            quota: TaskQuota { limit: [QUOTA_UNLIMITED; QUOTA_COUNT], usage: [0; QUOTA_COUNT] },
+            cpu_window_start_ns: 0,
+            cpu_window_used_ns: 0,
+            throttled_until_ns: 0,
+        }
+    }
+
+    /// `parent`'ın sınırlarını devralan, kullanımı sıfır yeni görev kotası.
+    pub(super) fn inherit(parent: &QuotaState) -> Self {
+        let mut state = QuotaState::unlimited();
+        state.quota.limit = parent.quota.limit;
+        state
+    }
+
+    pub(super) fn limit(&self, resource: usize) -> u64 {
+        self.quota.limit[resource]
+    }
+
+    pub(super) fn usage(&self, resource: usize) -> u64 {
+        self.quota.usage[resource]
+    }
+
+    /// Sınırlar ve kullanım (iş parçacığı kullanımı çağıran tarafından doldurulur).
+    pub(super) fn snapshot(&self) -> TaskQuota {
+        self.quota
+    }
+
+    /// `resource` sınırını `limit` yapar. Yeni CPU sınırı bir sonraki ölçümde uygulanır; eski
+    /// kısıtlama kaldırılır.
+    pub(super) fn set_limit(&mut self, resource: usize, limit: u64) {
+        self.quota.limit[resource] = limit;
+        if resource == QUOTA_CPU {
+            self.throttled_until_ns = 0;
+        }
+    }
+
+    /// `resource` kullanımına `amount` ekler; sınır aşılacaksa hiçbir şey yazılmaz.
+    pub(super) fn charge(&mut self, resource: usize, amount: u64) -> Result<(), KError> {
+        let usage = self.quota.usage[resource].checked_add(amount).ok_or(quota_error(resource))?;
+        if usage > self.quota.limit[resource] {
+            return Err(quota_error(resource));
+        }
+        self.quota.usage[resource] = usage;
+        Ok(())
+    }
+
+    pub(super) fn uncharge(&mut self, resource: usize, amount: u64) {
+        let usage = &mut self.quota.usage[resource];
+        *usage = usage.saturating_sub(amount);
+    }
+
+    /// `since`..`now` arasında çalışılan süreyi CPU penceresine yazar. Pay bu ölçümle aşıldıysa
+    /// pencere sonuna kadar kısıtlanır ve `true` döner.
+    pub(super) fn charge_cpu(&mut self, since: u64, now: u64) -> bool {
+        if now >= self.cpu_window_start_ns.saturating_add(QUOTA_CPU_WINDOW_NS) {
+            // Önceki pencere hemen bitmişse kullanımı raporlanır; daha eskiyse görev boştaydı.
+            let previous = now < self.cpu_window_start_ns.saturating_add(2 * QUOTA_CPU_WINDOW_NS);
+            self.quota.usage[QUOTA_CPU] =
+                if previous { self.cpu_window_used_ns * 100 / QUOTA_CPU_WINDOW_NS } else { 0 };
+            self.cpu_window_start_ns = now;
+            self.cpu_window_used_ns = 0;
+        }
+        self.cpu_window_used_ns += now.saturating_sub(since.max(self.cpu_window_start_ns));
+        let limit = self.quota.limit[QUOTA_CPU];
+        let exceeded = limit != QUOTA_UNLIMITED
+            && self.throttled_until_ns <= now
+            && self.cpu_window_used_ns.saturating_mul(100) > limit.saturating_mul(QUOTA_CPU_WINDOW_NS);
+        if exceeded {
+            self.throttled_until_ns = self.cpu_window_start_ns + QUOTA_CPU_WINDOW_NS;
+        }
+        exceeded
+    }
+
+    /// CPU payı tükendiyse yeniden çalışılabilecek an.
+    pub(super) fn throttled(&self, now: u64) -> Option<u64> {
+        if self.throttled_until_ns > now {
+            Some(self.throttled_until_ns)
+        } else {
+            None
+        }
+    }
+}
+
+/// Sınırı aşan ayırmanın hatası.
+fn quota_error(resource: usize) -> KError {
+    match resource {
+        QUOTA_MEMORY | QUOTA_IPC_BYTES => KError::OutOfMemory,
+        _ => KError::Busy,
+    }
+}
+
+impl Scheduler {
+    /// `cpu`'da son ölçümden beri geçen süreyi orada çalışan iş parçacığının görevinin ve
+    /// işlerinin CPU pencerelerine yazar. Biri payını aştıysa pencere sonuna kadar kısıtlanır ve
+    /// artık kısıtlı iş parçacıklarını çalıştıran CPU'lar yeniden zamanlanır.
+    pub(super) fn quota_charge_cpu(&mut self, cpu: usize, now: u64) {
+        let since = core::mem::replace(&mut self.cpus[cpu].quota_since_ns, now);
+        let current = self.cpus[cpu].current;
+        if self.is_idle(current) {
+            return;
+        }
+        let task_slot = match self.threads[current] {
+            Some(t) => t.task_slot,
+            None => return,
+        };
+        let task_exceeded = match self.tasks[task_slot].as_mut() {
+            Some(task) => task.quota.charge_cpu(since, now),
+            None => return,
+        };
+        let job_exceeded = self.job_charge_cpu(task_slot, since, now);
+        if task_exceeded || job_exceeded {
+            for other in 0..MAX_CPUS {
+                let running = self.cpus[other].current;
+                if self.cpus[other].online && self.quota_throttled(running, now).is_some() {
+                    self.resched_cpu(other);
+                }
+            }
+        }
+    }
+
+    /// `slot`'un görevi veya işlerinden biri CPU payını tükettiyse yeniden çalışabileceği an.
+    /// Idle ve deadline sınıfındaki iş parçacıkları kısıtlanmaz.
+    pub(super) fn quota_throttled(&self, slot: usize, now: u64) -> Option<u64> {
+        let thread = self.threads[slot]?;
+        if thread.dl.is_some() || self.is_idle(slot) {
+            return None;
+        }
+        let task = self.tasks[thread.task_slot]?.quota.throttled(now);
+        task.max(self.job_throttled(thread.task_slot, now))
+    }
+
+    /// `task`'ın ve işlerinin `resource` kullanımına `amount` ekler; birinin sınırı aşılacaksa
+    /// hiçbir şey yazılmaz.
+    pub(super) fn quota_charge(&mut self, task: KTaskId, resource: usize, amount: u64) -> Result<(), KError> {
+        let task_slot = self.task_slot_of(task).ok_or(KError::NotFound)?;
+        let job = match self.tasks[task_slot].as_mut() {
+            Some(t) => {
+                t.quota.charge(resource, amount)?;
+                t.job
+            }
+            None => return Err(KError::NotFound),
+        };
+        if let Err(err) = self.job_charge(job, None, resource, amount) {
+            if let Some(t) = self.tasks[task_slot].as_mut() {
+                t.quota.uncharge(resource, amount);
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// `task`'ın ve işlerinin `resource` kullanımından `amount` düşer. Görev toplanmışsa
+    /// etkisizdir (kalan kullanımı işlerinden toplanırken düşülür).
+    pub(super) fn quota_uncharge(&mut self, task: KTaskId, resource: usize, amount: u64) {
+        let task_slot = match self.task_slot_of(task) {
+            Some(task_slot) => task_slot,
+            None => return,
+        };
+        if let Some(t) = self.tasks[task_slot].as_mut() {
+            t.quota.uncharge(resource, amount);
+            let job = t.job;
+            self.job_uncharge(job, None, resource, amount);
+        }
+    }
+
+    /// `task_slot`'taki görev bir iş parçacığı daha ekleyebilir mi (görevin ve işlerinin sınırları).
+    pub(super) fn quota_check_thread(&self, task_slot: usize) -> Result<(), KError> {
+        match self.tasks[task_slot] {
+            Some(t) if t.live_threads as u64 >= t.quota.limit(QUOTA_THREADS) => return Err(KError::Busy),
+            Some(_) => {}
+            None => return Err(KError::NotFound),
+        }
+        self.job_check_thread(task_slot)
+    }
+
+    /// CPU sınırı değişti: kısıtlaması kalkan, zamanlayıcı kuyruğunda bekleyen (hazır, kuyrukta
+    /// değil) iş parçacıkları kuyruğa döner.
+    pub(super) fn quota_unpark_all(&mut self, now: u64) {
+        for slot in 0..MAX_THREADS {
+            let parked = matches!(
+                self.threads[slot],
+                Some(t) if t.state == TaskState::Ready && t.dl.is_none() && t.timer_cpu.is_some()
+            );
+            if parked && self.quota_throttled(slot, now).is_none() {
+                self.set_deadline(slot, None);
+                self.unpark(slot, now);
+            }
+        }
+    }
+
+    /// `task_slot`'taki görevin kotası; kullanım alanları o anki değerlerle doldurulur.
+    fn task_quota(&self, task_slot: usize) -> Option<TaskQuota> {
+        let task = self.tasks[task_slot]?;
+        let mut quota = task.quota.snapshot();
+        quota.usage[QUOTA_THREADS] = task.live_threads as u64;
+        Some(quota)
+    }
+}
+
+/// Sayılan (CPU ve iş parçacığı dışındaki) bir kaynak türü mü.
+fn is_counted(resource: usize) -> bool {
+    QUOTA_COUNTED.contains(&resource)
+}
+
+/// `task` görevinin `resource` kullanımına `amount` ekler (bellek ve IPC için bayt, handle için
+/// adet). Sınır aşılacaksa `KError::OutOfMemory`/`KError::Busy` döner ve kullanım değişmez.
+pub fn quota_charge_task(task: KTaskId, resource: usize, amount: u64) -> Result<(), KError> {
+    if !is_counted(resource) {
+        return Err(KError::InvalidArgument);
+    }
+    SCHEDULER.lock_irqsave().quota_charge(task, resource, amount)
+}
+
+/// `quota_charge_task`'ın çağıran görev için kısaltması.
+pub fn quota_charge(resource: usize, amount: u64) -> Result<(), KError> {
+    quota_charge_task(current_task_id(), resource, amount)
+}
+
+/// `task` görevinin `resource` kullanımından `amount` düşer. Görev toplanmışsa etkisizdir.
+pub fn quota_uncharge_task(task: KTaskId, resource: usize, amount: u64) {
+    if !is_counted(resource) {
+        return;
+    }
+    SCHEDULER.lock_irqsave().quota_uncharge(task, resource, amount)
+}
+
+/// `quota_uncharge_task`'ın çağıran görev için kısaltması.
+pub fn quota_uncharge(resource: usize, amount: u64) {
+    quota_uncharge_task(current_task_id(), resource, amount)
+}
+
+/// Karnal64 API fn task_set_limit karşılığı: `task` görevinin (`KTaskId(0)`: çağıran görev)
+/// `resource` (QUOTA_*) sınırını `limit` yapar. Çağıran, çocuklarının sınırlarını kendi sınırını
+/// aşmayacak şekilde belirleyebilir; kendi sınırını yalnızca düşürebilir. Aksi halde
+/// `KError::PermissionDenied`. CPU sınırı 1..=100*MAX_CPUS yüzde veya QUOTA_UNLIMITED olmalıdır.
+/// Kullanımın altına düşen sınır mevcut kaynakları geri almaz, yeni ayırmaları reddeder.
+pub fn set_task_limit(task: KTaskId, resource: usize, limit: u64) -> Result<(), KError> {
+    if resource >= QUOTA_COUNT {
+        return Err(KError::InvalidArgument);
+    }
+    if resource == QUOTA_CPU && limit != QUOTA_UNLIMITED && (limit == 0 || limit > 100 * MAX_CPUS as u64) {
+        return Err(KError::InvalidArgument);
+    }
+    let mut sched = SCHEDULER.lock_irqsave();
+    let own = sched.threads[sched.current()].map(|t| t.task).ok_or(KError::InternalError)?;
+    let task = if task.0 == 0 { own } else { task };
+    let own_slot = sched.task_slot_of(own).ok_or(KError::InternalError)?;
+    let task_slot = sched.task_slot_of(task).ok_or(KError::NotFound)?;
+    let own_limit = sched.tasks[own_slot].map(|t| t.quota.limit(resource)).ok_or(KError::InternalError)?;
+    match sched.tasks[task_slot] {
+        Some(t) if t.state == TaskState::Exited => return Err(KError::NotFound),
+        Some(t) if task != own && t.parent != own => return Err(KError::PermissionDenied),
+        Some(_) if limit > own_limit => return Err(KError::PermissionDenied),
+        Some(_) => {}
+        None => return Err(KError::NotFound),
+    }
+    if let Some(t) = sched.tasks[task_slot].as_mut() {
+        t.quota.set_limit(resource, limit);
+    }
+    if resource == QUOTA_CPU {
+        sched.quota_unpark_all(now_ns());
+    }
+    Ok(())
+}
+
+/// Karnal64 API fn task_quota karşılığı: `task` görevinin (`KTaskId(0)`: çağıran görev; aksi halde
+/// çağıranın çocuğu olmalı) sınırları ve kullanımı.
+pub fn task_quota(task: KTaskId) -> Result<TaskQuota, KError> {
+    let sched = SCHEDULER.lock_irqsave();
+    let own = sched.threads[sched.current()].map(|t| t.task).ok_or(KError::InternalError)?;
+    let task = if task.0 == 0 { own } else { task };
+    let task_slot = sched.task_slot_of(task).ok_or(KError::NotFound)?;
+    match sched.tasks[task_slot] {
+        Some(t) if task != own && t.parent != own => Err(KError::PermissionDenied),
+        Some(_) => sched.task_quota(task_slot).ok_or(KError::NotFound),
+        None => Err(KError::NotFound),
+    }
+}
+
+/// `task_quota`'yı kullanıcı `buffer`'ına yazar.
+pub fn task_quota_to_user(task: KTaskId, buffer: *mut TaskQuota) -> Result<(), KError> {
+    let quota = task_quota(task)?;
+    kmemory::copy_to_user(
+        buffer as *mut u8,
+        &quota as *const TaskQuota as *const u8,
+        core::mem::size_of::<TaskQuota>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charge_within_limit() {
+        let mut quota = QuotaState::unlimited();
+        quota.set_limit(QUOTA_MEMORY, 8192);
+        quota.set_limit(QUOTA_HANDLES, 2);
+
+        assert_eq!(quota.charge(QUOTA_MEMORY, 4096), Ok(()));
+        assert_eq!(quota.charge(QUOTA_MEMORY, 4096), Ok(()));
+        // Sınırı aşan ayırma reddedilir ve kullanımı değiştirmez.
+        assert_eq!(quota.charge(QUOTA_MEMORY, 1), Err(KError::OutOfMemory));
+        assert_eq!(quota.usage(QUOTA_MEMORY), 8192);
+        assert_eq!(quota.charge(QUOTA_HANDLES, 3), Err(KError::Busy));
+        assert_eq!(quota.usage(QUOTA_HANDLES), 0);
+        assert_eq!(quota.charge(QUOTA_IPC_BYTES, u64::MAX), Ok(()));
+        assert_eq!(quota.charge(QUOTA_IPC_BYTES, 1), Err(KError::OutOfMemory));
+
+        quota.uncharge(QUOTA_MEMORY, 4096);
+        assert_eq!(quota.usage(QUOTA_MEMORY), 4096);
+        quota.uncharge(QUOTA_MEMORY, 8192);
+        assert_eq!(quota.usage(QUOTA_MEMORY), 0);
+    }
+
+    #[test]
+    fn test_inherit_copies_limits_only() {
+        let mut parent = QuotaState::unlimited();
+        parent.set_limit(QUOTA_THREADS, 4);
+        parent.set_limit(QUOTA_MEMORY, 1 << 20);
+        parent.charge(QUOTA_MEMORY, 4096).unwrap();
+
+        let child = QuotaState::inherit(&parent);
+        assert_eq!(child.limit(QUOTA_THREADS), 4);
+        assert_eq!(child.limit(QUOTA_MEMORY), 1 << 20);
+        assert_eq!(child.limit(QUOTA_CPU), QUOTA_UNLIMITED);
+        assert_eq!(child.snapshot().usage, [0; QUOTA_COUNT]);
+    }
+
+    #[test]
+    fn test_cpu_share_throttles_until_window_end() {
+        let mut quota = QuotaState::unlimited();
+        quota.set_limit(QUOTA_CPU, 50);
+        let start = 1_000_000_000;
+        // İlk ölçüm pencereyi açar.
+        assert!(!quota.charge_cpu(start, start));
+
+        // Pencerenin yarısı kadar çalışmak sınırı aşmaz, fazlası aşar.
+        assert!(!quota.charge_cpu(start, start + QUOTA_CPU_WINDOW_NS / 2));
+        assert_eq!(quota.throttled(start + QUOTA_CPU_WINDOW_NS / 2), None);
+        let now = start + QUOTA_CPU_WINDOW_NS / 2 + 1;
+        assert!(quota.charge_cpu(now - 1, now));
+        assert_eq!(quota.throttled(now), Some(start + QUOTA_CPU_WINDOW_NS));
+
+        // Yeni pencerede önceki pencerenin kullanımı raporlanır ve kısıtlama kalkar.
+        let next = start + QUOTA_CPU_WINDOW_NS;
+        assert_eq!(quota.throttled(next), None);
+        assert!(!quota.charge_cpu(next, next));
+        assert_eq!(quota.usage(QUOTA_CPU), 50);
+
+        // Boşta geçen pencerelerden sonra kullanım sıfırdır.
+        assert!(!quota.charge_cpu(next + 3 * QUOTA_CPU_WINDOW_NS, next + 3 * QUOTA_CPU_WINDOW_NS));
+        assert_eq!(quota.usage(QUOTA_CPU), 0);
+    }
+
+    #[test]
+    fn test_unlimited_cpu_is_never_throttled() {
+        let mut quota = QuotaState::unlimited();
+        assert!(!quota.charge_cpu(0, 10 * QUOTA_CPU_WINDOW_NS));
+        assert_eq!(quota.throttled(0), None);
+    }
+}
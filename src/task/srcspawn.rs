@@ -0,0 +1,402 @@
+// Karnal64 ktask: yapılandırılmış görev başlatma (task_spawn).
+// Çağıran, `SpawnRequest` ile çalıştırılabilir kod kaynağını, argüman vektörünü, ortam
+// değişkenlerini (anahtar/değer), yeni göreve belirli yuva numaralarında devredilecek handle'ları,
+// başlangıç önceliğini ve kota sınırlarını verir. Çekirdek bunları doğrular ve yeni görevin ilk
+// iş parçacığının kullanıcı yığınının tepesine belgelenmiş bir başlangıç bloğu olarak yerleştirir:
+//
+//   yığın işaretçisi (16 bayt hizalı), iş parçacığının ilk argümanı da bu adrestir
+//   → SpawnStartInfo                       (magic, ebeveyn, argc/argv, envc/envp, handle listesi)
+//     argv[argc], NULL                     (u64 kullanıcı adresleri)
+//     envp[envc], NULL                     ("ANAHTAR=değer" dizelerinin adresleri)
+//     SpawnStartHandle[handle_count]       (yuva, handle değeri)
+//     dizeler                              (NUL ile biten; önce argv, sonra envp sırasıyla)
+//   yığın tepesi
+//
+// Yuva numaraları çekirdek için anlamsızdır; gelenek SPAWN_SLOT_STDIN/STDOUT/STDERR ve
+// ebeveyne açılan kanal için SPAWN_SLOT_PARENT'tır. İş handle'ları yeni görev adına yeniden açılır
+// (kendi handle kotasından sayılır), paylaşımlı bellek handle'ları da öyle; kaynak handle'ları
+// mod ve ofsetleriyle çocuğun handle'larına kopyalanır; senkronizasyon handle'ları genel olduğundan
+// aynen geçer.
+// Belirtilmeyen kota sınırları çağırandan devralınır; verilenler çağıranın sınırını aşamaz.
+
+use super::super::{kmemory, kresource, ksync, KError, KTaskId, KThreadId};
+use super::quota::QUOTA_COUNT;
+use super::{
+    create_task, create_thread, current_task_id, is_job_handle, job, release_task_resources, set_task_limit,
+    Priority, TaskState, PRIORITY_DEFAULT, PRIORITY_IDLE, PRIORITY_USER_MAX, SCHEDULER,
+};
+
+/// En fazla argüman sayısı.
+pub const SPAWN_MAX_ARGS: usize = 128;
+/// En fazla ortam değişkeni sayısı.
+pub const SPAWN_MAX_ENV: usize = 128;
+/// En fazla devredilen handle sayısı.
+pub const SPAWN_MAX_HANDLES: usize = 16;
+/// Handle yuva numaraları bu değerden küçük olmalıdır.
+pub const SPAWN_MAX_SLOT: u32 = 64;
+/// Başlangıç bloğunun (dizeler dahil) en büyük boyutu; yükleyici ilk yığının en az bu kadarını
+/// eşlemelidir.
+pub const SPAWN_BLOCK_MAX: usize = 16 * 1024;
+
+/// `SpawnStartInfo::magic`: "KSPN" ve biçim sürümü 1.
+pub const SPAWN_START_MAGIC: u64 = 0x4B53_504E_0000_0001;
+
+// Handle yuvaları için gelenek
+pub const SPAWN_SLOT_STDIN: u32 = 0;
+pub const SPAWN_SLOT_STDOUT: u32 = 1;
+pub const SPAWN_SLOT_STDERR: u32 = 2;
+pub const SPAWN_SLOT_PARENT: u32 = 3;
+
+/// Kullanıcı alanındaki bir bayt dizisi (NUL içermez, NUL ile bitmesi gerekmez).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SpawnString {
+    pub ptr: u64,
+    pub len: u64,
+}
+
+/// Ortam değişkeni. Anahtar boş olamaz ve '=' içeremez.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SpawnEnvVar {
+    pub key: SpawnString,
+    pub value: SpawnString,
+}
+
+/// Yeni göreve `slot` yuvasında devredilecek handle. `flags` ileride kullanılmak üzere sıfırdır.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SpawnHandle {
+    pub handle: u64,
+    pub slot: u32,
+    pub flags: u32,
+}
+
+/// SYSCALL_TASK_SPAWN isteği (kullanıcı alanından aynen kopyalanır). Dizi alanları kullanıcı
+/// adresleridir.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SpawnRequest {
+    /// Çalıştırılabilir kod kaynağının handle'ı.
+    pub code_handle: u64,
+    /// `argc` elemanlı SpawnString dizisi.
+    pub argv: u64,
+    pub argc: u64,
+    /// `envc` elemanlı SpawnEnvVar dizisi.
+    pub envp: u64,
+    pub envc: u64,
+    /// `handle_count` elemanlı SpawnHandle dizisi.
+    pub handles: u64,
+    pub handle_count: u64,
+    /// İş parçacıklarının taban önceliği (0: PRIORITY_DEFAULT, aksi halde 1..=PRIORITY_USER_MAX).
+    pub priority: u64,
+    /// Bit `i` ayarlıysa `limits[i]` yeni görevin QUOTA_i sınırı olur.
+    pub limit_mask: u64,
+    pub limits: [u64; QUOTA_COUNT],
+}
+
+/// Başlangıç bloğunun başı (yığın işaretçisindeki yapı).
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct SpawnStartInfo {
+    pub magic: u64,
+    /// Görevi başlatan görevin ID'si.
+    pub parent: u64,
+    pub argc: u64,
+    pub argv: u64,
+    pub envc: u64,
+    pub envp: u64,
+    pub handle_count: u64,
+    pub handles: u64,
+}
+
+/// Başlangıç bloğundaki devredilmiş handle: yeni görevde geçerli handle değeri.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SpawnStartHandle {
+    pub slot: u32,
+    pub reserved: u32,
+    pub handle: u64,
+}
+
+/// Yükleyicinin yeni adres alanına yerleştirdiği imaj.
+struct LoadedImage {
+    /// Sayfa tablosu kökü (create_task'a verilir).
+    address_space: u64,
+    /// Kullanıcı modu giriş noktası.
+    entry: u64,
+    /// İlk iş parçacığının yığın tepesi (16 bayt hizalı).
+    stack_top: u64,
+    /// `stack_top - SPAWN_BLOCK_MAX` adresinden başlayan, yığının tepesindeki SPAWN_BLOCK_MAX
+    /// baytın çekirdekten yazılabilir eşlemesi.
+    stack_window: *mut u8,
+}
+
+// Başlangıç bloğu çekirdek yığınına sığmaz; bloklar bu tamponda sırayla kurulur. Kullanıcı
+// belleğinden kopyalanırken tutulduğu için uyuyan bir mutex'tir.
+static SPAWN_BLOCK: ksync::Mutex<[u8; SPAWN_BLOCK_MAX]> = ksync::Mutex::new([0; SPAWN_BLOCK_MAX]);
+
+/// Kullanıcı alanındaki `addr` adresinden bir `T` okur.
+fn read_user<T: Copy + Default>(addr: u64) -> Result<T, KError> {
+    let mut value = T::default();
+    kmemory::copy_from_user(&mut value as *mut T as *mut u8, addr as *const u8, core::mem::size_of::<T>())?;
+    Ok(value)
+}
+
+/// `array`'deki `index`. elemanın kullanıcı adresi.
+fn element<T>(array: u64, index: usize) -> Result<u64, KError> {
+    ((index * core::mem::size_of::<T>()) as u64).checked_add(array).ok_or(KError::BadAddress)
+}
+
+/// Kullanıcı dizisini `block[*len..]`'e ekler. Dizi NUL içeremez.
+fn append_user(block: &mut [u8], len: &mut usize, s: SpawnString) -> Result<(), KError> {
+    let n = usize::try_from(s.len).map_err(|_| KError::InvalidArgument)?;
+    // Sonlandırıcı NUL için yer kalmalı.
+    let end = len.checked_add(n).filter(|&end| end < block.len()).ok_or(KError::InvalidArgument)?;
+    kmemory::copy_from_user(block[*len..end].as_mut_ptr(), s.ptr as *const u8, n)?;
+    if block[*len..end].contains(&0) {
+        return Err(KError::InvalidArgument);
+    }
+    *len = end;
+    Ok(())
+}
+
+fn append_byte(block: &mut [u8], len: &mut usize, byte: u8) -> Result<(), KError> {
+    if *len >= block.len() {
+        return Err(KError::InvalidArgument);
+    }
+    block[*len] = byte;
+    *len += 1;
+    Ok(())
+}
+
+/// Argümanları ve "ANAHTAR=değer" ortam değişkenlerini NUL ile bitirerek bloğun başına sırayla
+/// kopyalar; kullanılan bayt sayısını döner. Sığmazsa `KError::InvalidArgument`.
+fn copy_strings(block: &mut [u8], request: &SpawnRequest) -> Result<usize, KError> {
+    let mut len = 0;
+    for i in 0..request.argc as usize {
+        let arg: SpawnString = read_user(element::<SpawnString>(request.argv, i)?)?;
+        append_user(block, &mut len, arg)?;
+        append_byte(block, &mut len, 0)?;
+    }
+    for i in 0..request.envc as usize {
+        let var: SpawnEnvVar = read_user(element::<SpawnEnvVar>(request.envp, i)?)?;
+        let key_start = len;
+        append_user(block, &mut len, var.key)?;
+        if len == key_start || block[key_start..len].contains(&b'=') {
+            return Err(KError::InvalidArgument);
+        }
+        append_byte(block, &mut len, b'=')?;
+        append_user(block, &mut len, var.value)?;
+        append_byte(block, &mut len, 0)?;
+    }
+    Ok(len)
+}
+
+/// Devredilecek handle'ları okur ve doğrular: yuvalar SPAWN_MAX_SLOT'tan küçük ve tekil; iş,
+/// paylaşımlı bellek ve kaynak handle'ları çağırana ait olmalıdır.
+fn read_handles(request: &SpawnRequest, out: &mut [SpawnHandle; SPAWN_MAX_HANDLES]) -> Result<usize, KError> {
+    let count = request.handle_count as usize;
+    let mut used = 0u64;
+    for (i, slot) in out.iter_mut().enumerate().take(count) {
+        let handle: SpawnHandle = read_user(element::<SpawnHandle>(request.handles, i)?)?;
+        if handle.slot >= SPAWN_MAX_SLOT || handle.flags != 0 || used & (1 << handle.slot) != 0 {
+            return Err(KError::InvalidArgument);
+        }
+        if is_job_handle(handle.handle) {
+            job::check_job_handle(handle.handle)?;
+        } else if kmemory::is_shm_handle(handle.handle) {
+            kmemory::check_shm_handle(handle.handle)?;
+        } else if kresource::is_resource_handle(handle.handle) {
+            kresource::check_handle(handle.handle)?;
+        }
+        used |= 1 << handle.slot;
+        *slot = handle;
+    }
+    Ok(count)
+}
+
+/// Çağıranın `handle` değerini `child` görevinde geçerli bir handle'a çevirir.
+fn inherit_handle(child: KTaskId, handle: u64) -> Result<u64, KError> {
+    if is_job_handle(handle) {
+        return job::share_job_handle(handle, child).map(|h| h.0);
+    }
+    if kmemory::is_shm_handle(handle) {
+        return kmemory::share_shm_handle(handle, child).map(|h| h.0);
+    }
+    if kresource::is_resource_handle(handle) {
+        return kresource::share_handle(handle, child).map(|h| h.0);
+    }
+    // Senkronizasyon handle'ları genel olduğundan aynen geçerlidir.
+    Ok(handle)
+}
+
+/// `block[..strings_len]`'deki dizeleri bloğun sonuna taşır ve altına başlangıç bilgisini,
+/// argv/envp dizilerini ve handle listesini yazar. Bloğun son baytı `stack_top - 1` adresine
+/// karşılık gelir. Başlangıç bilgisinin (yığın işaretçisinin) bloktaki ofsetini döner.
+fn layout(
+    block: &mut [u8; SPAWN_BLOCK_MAX],
+    strings_len: usize,
+    argc: usize,
+    envc: usize,
+    handles: &[SpawnStartHandle],
+    parent: KTaskId,
+    stack_top: u64,
+) -> Result<usize, KError> {
+    let strings = SPAWN_BLOCK_MAX - strings_len;
+    block.copy_within(0..strings_len, strings);
+    let header = core::mem::size_of::<SpawnStartInfo>()
+        + 8 * (argc + 1)
+        + 8 * (envc + 1)
+        + core::mem::size_of_val(handles);
+    let start = strings.checked_sub(header).ok_or(KError::InvalidArgument)? & !15;
+    block[start..strings].fill(0);
+
+    let base = stack_top - SPAWN_BLOCK_MAX as u64;
+    let addr = |offset: usize| base + offset as u64;
+    let argv = start + core::mem::size_of::<SpawnStartInfo>();
+    let envp = argv + 8 * (argc + 1);
+    let handle_list = envp + 8 * (envc + 1);
+    let info = SpawnStartInfo {
+        magic: SPAWN_START_MAGIC,
+        parent: parent.0,
+        argc: argc as u64,
+        argv: addr(argv),
+        envc: envc as u64,
+        envp: addr(envp),
+        handle_count: handles.len() as u64,
+        handles: addr(handle_list),
+    };
+    let put = |block: &mut [u8; SPAWN_BLOCK_MAX], offset: usize, value: u64| {
+        block[offset..offset + 8].copy_from_slice(&value.to_ne_bytes());
+    };
+    unsafe { core::ptr::write_unaligned(block[start..].as_mut_ptr() as *mut SpawnStartInfo, info) };
+
+    // Dizeler copy_strings'in yazdığı sırada ardışıktır: önce argv, sonra envp.
+    let mut cursor = strings;
+    for i in 0..argc + envc {
+        let slot = if i < argc { argv + 8 * i } else { envp + 8 * (i - argc) };
+        put(block, slot, addr(cursor));
+        let len = block[cursor..].iter().position(|&b| b == 0).ok_or(KError::InternalError)?;
+        cursor += len + 1;
+    }
+    for (i, handle) in handles.iter().enumerate() {
+        let offset = handle_list + i * core::mem::size_of::<SpawnStartHandle>();
+        unsafe { core::ptr::write_unaligned(block[offset..].as_mut_ptr() as *mut SpawnStartHandle, *handle) };
+    }
+    Ok(start)
+}
+
+/// `code_handle` kaynağındaki çalıştırılabilir imajı yeni bir adres alanına yükler.
+fn load_image(code_handle: u64) -> Result<LoadedImage, KError> {
+    // TODO: kresource'tan imajı oku (ELF), kmemory::create_address_space ile yeni bir adres alanı
+    // kur, segmentleri ve SPAWN_BLOCK_MAX'ı kapsayan ilk yığını MAP_FIXED ile eşle. Eşlemeler
+    // mevcut görevin adres alanına yapıldığından yeni alana eşleyen bir kmemory yolu gerekir.
+    Err(KError::NotSupported)
+}
+
+/// Yeni `child` görevine sınırlarını, önceliğini ve handle'larını verir, başlangıç bloğunu ilk
+/// yığına yazar ve ilk iş parçacığını başlatır.
+fn start_child(
+    child: KTaskId,
+    request: &SpawnRequest,
+    priority: Priority,
+    handles: &[SpawnHandle],
+    block: &mut [u8; SPAWN_BLOCK_MAX],
+    strings_len: usize,
+    image: &LoadedImage,
+) -> Result<KThreadId, KError> {
+    for resource in (0..QUOTA_COUNT).filter(|&r| request.limit_mask & (1 << r) != 0) {
+        set_task_limit(child, resource, request.limits[resource])?;
+    }
+    {
+        let mut sched = SCHEDULER.lock_irqsave();
+        let slot = sched.task_slot_of(child).ok_or(KError::InternalError)?;
+        if let Some(t) = sched.tasks[slot].as_mut() {
+            t.priority = priority;
+        }
+    }
+    let mut inherited = [SpawnStartHandle::default(); SPAWN_MAX_HANDLES];
+    for (out, handle) in inherited.iter_mut().zip(handles.iter()) {
+        *out = SpawnStartHandle { slot: handle.slot, reserved: 0, handle: inherit_handle(child, handle.handle)? };
+    }
+    let start = layout(
+        block,
+        strings_len,
+        request.argc as usize,
+        request.envc as usize,
+        &inherited[..handles.len()],
+        current_task_id(),
+        image.stack_top,
+    )?;
+    unsafe {
+        core::ptr::copy_nonoverlapping(block[start..].as_ptr(), image.stack_window.add(start), SPAWN_BLOCK_MAX - start);
+    }
+    let sp = image.stack_top - (SPAWN_BLOCK_MAX - start) as u64;
+    create_thread(child, image.entry, sp, sp, 0, true)
+}
+
+/// Başlatılamayan (iş parçacığı olmayan) `task` görevini zombi olmadan toplar.
+fn abort_spawn(task: KTaskId) {
+    let (child, arch) = {
+        let mut sched = SCHEDULER.lock_irqsave();
+        let slot = match sched.task_slot_of(task) {
+            Some(slot) => slot,
+            None => return,
+        };
+        if let Some(t) = sched.tasks[slot].as_mut() {
+            t.state = TaskState::Exited;
+        }
+        (sched.reap(slot), sched.arch)
+    };
+    if let Some(child) = child {
+        release_task_resources(&child, arch);
+    }
+}
+
+/// Karnal64 API fn task_spawn karşılığı: `request` (kullanıcı adresi, `request_len` ==
+/// size_of::<SpawnRequest>()) ile yeni bir görev başlatır. Yeni görev çağıranın çocuğudur ve
+/// onun işine girer; ilk iş parçacığı imajın giriş noktasından, yığın işaretçisi ve ilk argümanı
+/// başlangıç bloğunu (`SpawnStartInfo`) gösterecek şekilde başlar.
+pub fn task_spawn(request: *const SpawnRequest, request_len: usize) -> Result<KTaskId, KError> {
+    if request_len != core::mem::size_of::<SpawnRequest>() {
+        return Err(KError::InvalidArgument);
+    }
+    let request: SpawnRequest = read_user(request as u64)?;
+    if request.argc as usize > SPAWN_MAX_ARGS
+        || request.envc as usize > SPAWN_MAX_ENV
+        || request.handle_count as usize > SPAWN_MAX_HANDLES
+        || request.limit_mask >> QUOTA_COUNT != 0
+    {
+        return Err(KError::InvalidArgument);
+    }
+    let priority = match request.priority {
+        0 => PRIORITY_DEFAULT,
+        p if p > PRIORITY_IDLE as u64 && p <= PRIORITY_USER_MAX as u64 => p as Priority,
+        _ => return Err(KError::InvalidArgument),
+    };
+    let mut handles = [SpawnHandle::default(); SPAWN_MAX_HANDLES];
+    let handle_count = read_handles(&request, &mut handles)?;
+
+    let mut block = SPAWN_BLOCK.lock()?;
+    let strings_len = copy_strings(&mut *block, &request)?;
+    let image = load_image(request.code_handle)?;
+    let child = match create_task(image.address_space) {
+        Ok(child) => child,
+        Err(err) => {
+            kmemory::destroy_address_space(image.address_space);
+            if let Some(arch) = SCHEDULER.lock_irqsave().arch {
+                unsafe { arch.destroy_address_space(image.address_space) };
+            }
+            return Err(err);
+        }
+    };
+
+    let started = start_child(child, &request, priority, &handles[..handle_count], &mut block, strings_len, &image);
+    if let Err(err) = started {
+        // Devredilen iş handle'ları ve adres alanı görevle birlikte bırakılır.
+        abort_spawn(child);
+        return Err(err);
+    }
+    Ok(child)
+}
@@ -0,0 +1,2541 @@
+// Geliştirme sırasında kullanılmayan kod veya argümanlar için izinler
+#![allow(dead_code)]
+#![allow(unused_variables)]
+
+// Karnal64 ktask modülü: mimariden bağımsız görev/iş parçacığı tabloları ve zamanlayıcı.
+// - Görevler (adres alanı, handle'lar, çıkış durumu, ebeveyn/zombi toplama) ve iş parçacıkları
+// - Öncelik seviyelerinde round-robin, zaman dilimleri ve preemption; EDF sınıfı (bkz. srcdeadline.rs)
+// - CPU başına hazır kuyrukları, afinite, yük dengeleme ve uyanma kuyrukları (bkz. srctimer.rs)
+// - ksync::WaitQueue için bloklama/uyandırma: `prepare_to_block`, `schedule`, `wake`
+// - Süre muhasebesi, kotalar ve işler (bkz. srcquota.rs, srcjob.rs)
+// - İstisna portları, ertelenmiş iş ve görev başlatma (bkz. srcexcport.rs, srcworkqueue.rs, srcspawn.rs)
+// Mimariler yalnızca `ArchTask`'ı implemente edip boot sırasında `register_arch` ile kaydeder
+// (bkz. src/arch/<arch>/srctask_<arch>.rs).
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+
+use super::{kmemory, kresource, KError, KTaskId, KThreadId};
+use super::ksync::{self, Spinlock};
+
+// Hataların işleyici göreve iletilmesi (SYSCALL_EXCEPTION_*)
+#[path = "srcexcport.rs"]
+mod excport;
+pub use excport::{
+    deliver_fault, exception_port_set, exception_receive, exception_reply, FaultMessage, FaultRegisters,
+    EXCEPTION_KILL, EXCEPTION_RECEIVE_NOHANG, EXCEPTION_RESUME, FAULT_GPR_COUNT,
+};
+
+// Zaman aşımıyla bloklanan iş parçacıklarının CPU başına uyanma kuyrukları
+#[path = "srctimer.rs"]
+mod timer;
+use timer::TimerQueue;
+
+// Deadline (EDF) gerçek zamanlı sınıfı (SYSCALL_THREAD_SET_DEADLINE)
+#[path = "srcdeadline.rs"]
+mod deadline;
+use deadline::DeadlineEntity;
+pub use deadline::{set_thread_deadline, DL_PERIOD_MAX_NS, DL_RUNTIME_MIN_NS};
+
+// Görev başına kaynak kotaları (SYSCALL_TASK_SET_LIMIT, SYSCALL_TASK_QUOTA)
+#[path = "srcquota.rs"]
+mod quota;
+use quota::QuotaState;
+pub use quota::{
+    quota_charge, quota_charge_task, quota_uncharge, quota_uncharge_task, set_task_limit, task_quota,
+    task_quota_to_user, TaskQuota, QUOTA_COUNT, QUOTA_CPU, QUOTA_CPU_WINDOW_NS, QUOTA_HANDLES, QUOTA_IPC_BYTES,
+    QUOTA_MEMORY, QUOTA_THREADS, QUOTA_UNLIMITED,
+};
+
+// İç içe görev grupları (job)
+#[path = "srcjob.rs"]
+mod job;
+use job::{JobHandle, JobSlot, JOB_ROOT};
+pub use job::{
+    is_job_handle, job_acquire, job_control, job_release, JOB_CONTROL_ADD_TASK, JOB_CONTROL_CREATE_CHILD,
+    JOB_CONTROL_ID, JOB_CONTROL_KILL, JOB_CONTROL_QUOTA, JOB_CONTROL_RESUME, JOB_CONTROL_SET_LIMIT,
+    JOB_CONTROL_STATS, JOB_CONTROL_SUSPEND, JOB_RESOURCE_PREFIX, MAX_JOBS,
+};
+
+// Argüman, ortam ve handle devriyle görev başlatma
+#[path = "srcspawn.rs"]
+mod spawn;
+pub use spawn::{
+    task_spawn, SpawnEnvVar, SpawnHandle, SpawnRequest, SpawnStartHandle, SpawnStartInfo, SpawnString,
+    SPAWN_BLOCK_MAX, SPAWN_MAX_ARGS, SPAWN_MAX_ENV, SPAWN_MAX_HANDLES, SPAWN_MAX_SLOT, SPAWN_SLOT_PARENT,
+    SPAWN_SLOT_STDERR, SPAWN_SLOT_STDIN, SPAWN_SLOT_STDOUT, SPAWN_START_MAGIC,
+};
+
+// Kesme işleyicilerinden ertelenen işler için CPU başına çalışanlar (alt yarılar)
+#[path = "srcworkqueue.rs"]
+mod workqueue;
+pub use workqueue::{
+    cancel_delayed_work, queue_delayed_work, queue_delayed_work_on, queue_work, queue_work_on, DelayedWorkId,
+    MAX_DELAYED_WORK, WORKER_PRIORITY, WORK_QUEUE_DEPTH,
+};
+
+/// Aynı anda var olabilecek en fazla iş parçacığı sayısı.
+/// WaitQueue kapasiteleri de bu değere göre boyutlandırılır (bir iş parçacığı bir kuyrukta
+/// en fazla bir kez bulunabilir, bu yüzden kuyruklar asla taşmaz).
+pub const MAX_THREADS: usize = 64;
+
+/// Aynı anda var olabilecek en fazla görev (adres alanı) sayısı. Görev 0 çekirdeğin kendisidir.
+pub const MAX_TASKS: usize = 32;
+
+/// Desteklenen en fazla işlemci (çekirdek/hart) sayısı. CPU başına tablolar buna göre boyutlanır.
+pub const MAX_CPUS: usize = 8;
+
+/// Tüm CPU'lara izin veren afinite maskesi (bit `cpu`: mantıksal CPU indeksi).
+pub const CPU_MASK_ALL: u64 = (1 << MAX_CPUS) - 1;
+
+/// En güçlü CPU'ların göreli işlem kapasitesi. Küçük (LITTLE) çekirdekler DTB'de
+/// `capacity-dmips-mhz` ile daha düşük bir değer bildirir.
+pub const CPU_CAPACITY_MAX: u32 = 1024;
+
+/// Mimariye özel bağlam yapısı için ayrılan alan (bayt). Her port bağlamının buna sığdığını
+/// derleme zamanında doğrulamalıdır.
+pub const ARCH_CONTEXT_SIZE: usize = 512;
+
+/// Her iş parçacığının çekirdek yığını boyutu (bayt).
+pub const KERNEL_STACK_SIZE: usize = 16 * 1024;
+
+/// Boot CPU'sunun idle iş parçacığının tablo indeksi. Diğer CPU'ların idle iş parçacıkları
+/// `prepare_cpu` ile ayrılır.
+const IDLE_SLOT: usize = 0;
+
+/// Çekirdek görevinin tablo indeksi. Çekirdek iş parçacıkları ve idle bu göreve aittir.
+const KERNEL_TASK_SLOT: usize = 0;
+
+/// Yalnızca çekirdek eşlemelerini içeren adres alanı (çekirdek görevleri için).
+pub const KERNEL_ADDRESS_SPACE: u64 = 0;
+
+/// İş parçacığı önceliği. Büyük değer daha önemli demektir.
+pub type Priority = u8;
+
+/// Öncelik seviyesi sayısı. Her seviyenin kendi hazır kuyruğu vardır.
+pub const NUM_PRIORITIES: usize = 32;
+
+/// Idle iş parçacığının önceliği (en düşük).
+pub const PRIORITY_IDLE: Priority = 0;
+/// Yeni iş parçacıklarının varsayılan önceliği.
+pub const PRIORITY_DEFAULT: Priority = 8;
+/// Kullanıcı görevlerinin SYSCALL_TASK_SET_PRIORITY ile alabileceği en yüksek öncelik.
+/// Üstündeki seviyeler çekirdek iş parçacıklarına ayrılmıştır.
+pub const PRIORITY_USER_MAX: Priority = 23;
+/// En yüksek öncelik.
+pub const PRIORITY_MAX: Priority = (NUM_PRIORITIES - 1) as Priority;
+
+/// Bir iş parçacığının mirasını ayrı ayrı izleyebildiği, bekleyeni olan en fazla kilit sayısı.
+const PI_MAX_LOCKS: usize = 8;
+
+/// Bir iş parçacığının, aynı öncelikteki bir diğerine sıra vermeden önce kesintisiz çalışabileceği süre.
+pub const TIME_SLICE_NS: u64 = 10_000_000;
+
+/// Her CPU'nun periyodik yük dengelemesi arasındaki süre.
+const BALANCE_INTERVAL_NS: u64 = 100_000_000;
+
+/// IPI nedenleri (CPU başına bekleyen maske bitleri).
+pub const IPI_RESCHEDULE: u32 = 1 << 0;
+pub const IPI_TLB_SHOOTDOWN: u32 = 1 << 1;
+
+/// `task_wait` bayrağı: çocuk henüz sonlanmadıysa bloklamadan `KError::Busy` döner.
+pub const TASK_WAIT_NOHANG: u64 = 1 << 0;
+const TASK_WAIT_FLAGS: u64 = TASK_WAIT_NOHANG;
+
+/// `thread_create`'e yığın boyutu olarak 0 verildiğinde ayrılan kullanıcı yığını boyutu.
+pub const DEFAULT_USER_STACK_SIZE: usize = 64 * 1024;
+/// `thread_create` ile istenebilecek en büyük kullanıcı yığını.
+pub const MAX_USER_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Görev/İş Parçacığı Durumu
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TaskState {
+    Ready,    // Çalışmaya hazır, hazır kuyruğunda bekliyor
+    Running,  // Şu anda işlemcide çalışıyor
+    Sleeping, // Belirli bir süre bekliyor (ksync::sleep_for)
+    Blocked,  // Bir bekleme kuyruğunda (kilit, mesaj, olay) bekliyor
+    Exited,   // Çalışması tamamlandı
+}
+
+/// Bloklanmış bir iş parçacığının neden uyandırıldığı.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WakeReason {
+    /// Bir WaitQueue::wake_one/wake_all çağrısıyla uyandırıldı.
+    Signaled,
+    /// Bekleme süresi (deadline) doldu.
+    TimedOut,
+}
+
+/// Bir CPU'nun topolojideki yeri (SYSCALL_CPU_TOPOLOGY). Mimari kod DTB `cpu-map`'ten veya
+/// ACPI MADT'den `set_cpu_topology` ile bildirir; bildirilmeyen CPU'lar tek paketteki ayrı
+/// çekirdekler sayılır.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CpuTopology {
+    /// Donanım kimliği (hart ID, APIC ID, MPIDR afinitesi).
+    pub hw_id: u64,
+    /// Fiziksel paket (soket).
+    pub package: u32,
+    /// Paket içindeki küme (big.LITTLE'da aynı türden çekirdek grubu); kümesiz sistemlerde 0.
+    pub cluster: u32,
+    /// Küme içindeki çekirdek.
+    pub core: u32,
+    /// Çekirdek içindeki donanım iş parçacığı (SMT); SMT yoksa 0.
+    pub thread: u32,
+    /// Göreli işlem kapasitesi (1..=CPU_CAPACITY_MAX).
+    pub capacity: u32,
+    /// CPU çevrimiçiyse 1. `set_cpu_topology` bunu yok sayar.
+    pub online: u32,
+}
+
+/// Bir iş parçacığının (veya görevin tüm iş parçacıklarının) zamanlama sayaçları
+/// (SYSCALL_THREAD_STATS, SYSCALL_TASK_STATS). Süreler nanosaniyedir.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ThreadStats {
+    /// Kullanıcı modunda geçen CPU süresi.
+    pub user_ns: u64,
+    /// Çekirdek modunda (sistem çağrıları; idle iş parçacıkları için boşta) geçen CPU süresi.
+    pub kernel_ns: u64,
+    /// İş parçacığının bloklanarak, bırakarak (yield) veya sonlanarak verdiği CPU sayısı.
+    pub voluntary_switches: u64,
+    /// Kesintiye uğratılarak (daha önemli biri hazır, zaman dilimi bitti) kaybedilen CPU sayısı.
+    pub involuntary_switches: u64,
+    /// Bloklanmışken uyandırılma sayısı.
+    pub wakeups: u64,
+    /// Uyandırılmasından çalışmaya başlamasına kadar geçen sürelerin toplamı ve en büyüğü.
+    pub wakeup_latency_total_ns: u64,
+    pub wakeup_latency_max_ns: u64,
+    /// Hazır kuyruğunda (Ready) beklenen toplam süre.
+    pub run_wait_ns: u64,
+}
+
+impl ThreadStats {
+    /// `other`'ı bu sayaçlara ekler (en büyük gecikme için en büyüğü alınır).
+    pub fn accumulate(&mut self, other: &ThreadStats) {
+        self.user_ns += other.user_ns;
+        self.kernel_ns += other.kernel_ns;
+        self.voluntary_switches += other.voluntary_switches;
+        self.involuntary_switches += other.involuntary_switches;
+        self.wakeups += other.wakeups;
+        self.wakeup_latency_total_ns += other.wakeup_latency_total_ns;
+        self.wakeup_latency_max_ns = self.wakeup_latency_max_ns.max(other.wakeup_latency_max_ns);
+        self.run_wait_ns += other.run_wait_ns;
+    }
+}
+
+// Düşük seviye mimari kancaları (bkz. karnal64/hardware_specific.h)
+extern "C" {
+    /// Boot'tan beri geçen monoton süre (nanosaniye).
+    fn low_level_monotonic_time_ns() -> u64;
+
+    /// Çağıran işlemcinin 0 tabanlı indeksi (< MAX_CPUS).
+    fn low_level_cpu_id() -> u32;
+
+    /// Mevcut CPU'da kesmeler açıksa 1, kapalıysa 0.
+    fn low_level_interrupts_enabled() -> i32;
+}
+
+// --- Mimari katmanı ---
+
+/// Yeni bir iş parçacığının ilk kez çalıştırıldığında başlayacağı durum.
+#[derive(Debug, Copy, Clone)]
+pub struct ThreadStart {
+    /// Başlangıç adresi. `user` ise kullanıcı alanı giriş noktası, değilse
+    /// `extern "C" fn(u64) -> !` imzalı bir çekirdek fonksiyonu.
+    pub entry: u64,
+    /// Giriş noktasına ilk argüman yazmacında geçirilen değer.
+    pub arg: u64,
+    /// Kullanıcı yığınının tepesi (yalnızca `user` için anlamlıdır).
+    pub user_stack_top: u64,
+    /// İş parçacığının çekirdek yığınının tepesi (16 bayt hizalı). Kullanıcı modundan gelen
+    /// tuzaklar (trap) ve sistem çağrıları bu yığında işlenir.
+    pub kernel_stack_top: u64,
+    /// Kullanıcı TLS işaretçisi (amd64 fs_base, armv9 tpidr_el0, rv64i/loongarch64 tp,
+    /// mips64 UserLocal, powerpc64 r13, sparcv9 %g7). Yalnızca `user` için anlamlıdır; port,
+    /// iş parçacığı başına ayrı tutulan yazmaçları bağlam değişiminde kaydedip yükler.
+    pub tls: u64,
+    /// `true`: kullanıcı modunda başlar, `false`: çekirdek iş parçacığı.
+    pub user: bool,
+}
+
+/// Bir portun zamanlayıcıya sağladığı mimariye özel işlemler.
+/// Görev/iş parçacığı tabloları, durumlar, kuyruklar ve politika tamamen bu dosyadadır.
+pub trait ArchTask: Sync {
+    /// `ctx` (ARCH_CONTEXT_SIZE bayt, 16 bayt hizalı, sıfırlanmış) içine yeni bir bağlam kurar.
+    /// Bağlama ilk geçişte iş parçacığı kesmeler açık olarak `start.entry`'den başlamalıdır
+    /// (zamanlayıcı bağlam değişimi boyunca kesmeleri kapalı tutar).
+    unsafe fn init_context(&self, ctx: *mut u8, start: &ThreadStart);
+
+    /// Mevcut bağlamı `prev`'e kaydeder ve `next`'i yükler. `prev` tekrar seçildiğinde geri döner.
+    /// Kesmeler kapalıyken çağrılır.
+    unsafe fn switch_context(&self, prev: *mut u8, next: *const u8);
+
+    /// Sayfa tablosu kökü `root` olan adres alanını etkinleştirir. Değerin anlamı porta özeldir
+    /// (sayfa tablosunun fiziksel adresi, MMU bağlam numarası vb.).
+    /// `KERNEL_ADDRESS_SPACE` için çekirdek eşlemeleri her adres alanında bulunduğundan port
+    /// mevcut adres alanını olduğu gibi bırakabilir.
+    unsafe fn activate_address_space(&self, root: u64);
+
+    /// Bu CPU'nun TLB'sinden `root` adres alanındaki [`start`, `start + len`) aralığının
+    /// çevirilerini siler (`KERNEL_ADDRESS_SPACE`: çekirdek eşlemeleri). Port daha fazlasını
+    /// silebilir. Varsayılan implementasyon hiçbir şey yapmaz; eşleme kaldıran bellek kodu
+    /// olan portlar bunu implemente etmelidir.
+    unsafe fn flush_tlb(&self, root: u64, start: u64, len: u64) {}
+
+    /// Donanım kimliği `hw_id` olan CPU'ya IPI gönderir. Hedefte IPI işleyicisi `handle_ipi`'yi
+    /// çağırmalıdır. Tek işlemcili portlarda hiç çağrılmaz.
+    unsafe fn send_ipi(&self, hw_id: u64) {}
+
+    /// Bu CPU'nun tek seferlik zamanlayıcısını, monoton saat (`now_ns`) `deadline_ns`'e
+    /// ulaştığında kesme üretecek şekilde kurar; önceki kurulumun yerine geçer ve geçmişteki bir
+    /// an hemen kesme üretir. Kesme işleyicisi `timer_tick` çağırmalıdır. Kesmeler kapalıyken
+    /// çağrılır. Varsayılan implementasyon hiçbir şey yapmaz: port periyodik tick kullanır ve
+    /// uyanmalar bir sonraki tick'e kadar gecikir.
+    unsafe fn program_timer(&self, deadline_ns: u64) {}
+
+    /// Toplanan (reaped) bir görevin adres alanının kök tablosunu serbest bırakır. VMA'lar,
+    /// eşlenmiş çerçeveler ve kullanıcı yarısının ara tabloları daha önce
+    /// `kmemory::destroy_address_space` ile bırakılmıştır. Görevin hiçbir iş parçacığı artık bir
+    /// CPU'da değildir, ancak çekirdek iş parçacıklarına geçişte kök değiştirilmediği için `root`
+    /// bazı CPU'larda hâlâ yüklü olabilir; kök tabloyu serbest bırakan port bunu hesaba katmalıdır.
+    /// Varsayılan implementasyon hiçbir şey yapmaz (kullanıcı sayfa tablosu ayırmayan portlar).
+    unsafe fn destroy_address_space(&self, root: u64) {}
+
+    /// Kesmeler kapalıyken çağrılır; bir kesme beklemeye girene kadar CPU'yu bekletir ve döner
+    /// (kesme, çağıran kesmeleri açınca işlenir). Varsayılan: kısa bir meşgul bekleme.
+    unsafe fn wait_for_interrupt(&self) {
+        core::hint::spin_loop();
+    }
+}
+
+/// Bir iş parçacığı tablosu yuvasına ait bağlam alanı.
+#[repr(C, align(16))]
+struct ContextArea(UnsafeCell<[u8; ARCH_CONTEXT_SIZE]>);
+
+/// Bir iş parçacığı tablosu yuvasına ait çekirdek yığını.
+#[repr(C, align(16))]
+struct KernelStack(UnsafeCell<[u8; KERNEL_STACK_SIZE]>);
+
+// Bağlam alanlarına ve yığınlara yalnızca sahibi olan iş parçacığı veya zamanlayıcı kilidi
+// altında (yuva oluşturulurken/bağlam değişiminde) erişilir.
+unsafe impl Sync for ContextArea {}
+unsafe impl Sync for KernelStack {}
+
+const EMPTY_CONTEXT: ContextArea = ContextArea(UnsafeCell::new([0; ARCH_CONTEXT_SIZE]));
+const EMPTY_STACK: KernelStack = KernelStack(UnsafeCell::new([0; KERNEL_STACK_SIZE]));
+static CONTEXTS: [ContextArea; MAX_THREADS] = [EMPTY_CONTEXT; MAX_THREADS];
+// Idle yuvası boot yığınını kullanır; onun çekirdek yığını boş kalır.
+static KERNEL_STACKS: [KernelStack; MAX_THREADS] = [EMPTY_STACK; MAX_THREADS];
+
+fn context_ptr(slot: usize) -> *mut u8 {
+    CONTEXTS[slot].0.get() as *mut u8
+}
+
+fn kernel_stack_top(slot: usize) -> u64 {
+    KERNEL_STACKS[slot].0.get() as u64 + KERNEL_STACK_SIZE as u64
+}
+
+// --- Tablolar ---
+
+/// Tek bir görevin (adres alanı + iş parçacıkları) zamanlayıcı tarafından tutulan durumu.
+#[derive(Debug, Copy, Clone)]
+struct TaskSlot {
+    id: KTaskId,
+    /// Görevi oluşturan (veya ebeveyni sonlandıysa devralan) görev. Çekirdek görevi için kendisi.
+    parent: KTaskId,
+    /// Running: en az bir iş parçacığı yaşıyor veya henüz iş parçacığı eklenmedi.
+    /// Exited: sonlandı; toplanana kadar (zombi) yuva ayrılmış kalır.
+    state: TaskState,
+    /// Mimariye özel sayfa tablosu kökü (ArchTask::activate_address_space'e aynen geçirilir).
+    address_space: u64,
+    /// Henüz sonlanmamış iş parçacığı sayısı.
+    live_threads: u32,
+    exit_code: i32,
+    /// Görev bir hata (fault) nedeniyle sonlandırıldıysa nedeni (`terminate_current_task`).
+    fault: Option<KError>,
+    /// Hatalarının iletildiği işleyici görev (`exception_port_set`); oluşturulurken ebeveynden devralınır.
+    exception_port: Option<KTaskId>,
+    /// Yuvaları yeniden kullanılmış (sonlanmış) iş parçacıklarının toplam sayaçları.
+    exited_stats: ThreadStats,
+    /// Kaynak sınırları ve kullanımı; oluşturulurken ebeveynden devralınır.
+    quota: QuotaState,
+    /// Ait olduğu işin tablo indeksi; oluşturulurken oluşturan görevin işi.
+    job: usize,
+    /// Yeni iş parçacıklarının taban önceliği (`set_task_priority`, task_spawn isteği).
+    priority: Priority,
+}
+
+/// Sonlanmış bir çocuk görevin `task_wait` ile alınan çıkış durumu.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ExitStatus {
+    pub task: KTaskId,
+    /// `task_exit` koduna verilen değer (hata ile sonlandıysa `fault` değeri).
+    pub code: i32,
+    pub fault: Option<KError>,
+}
+
+impl ExitStatus {
+    /// SYSCALL_TASK_WAIT dönüş değeri: bit 0-31 çıkış kodu veya KError değeri, bit 32 hata
+    /// bayrağı, bit 33-62 görev ID'si. Bit 63 her zaman sıfırdır (negatif dönüşler hata kodudur).
+    pub fn encode(&self) -> u64 {
+        let fault = self.fault.is_some() as u64;
+        ((self.task.0 & 0x3FFF_FFFF) << 33) | (fault << 32) | (self.code as u32 as u64)
+    }
+}
+
+/// `task_wait`'in aradığı çocuğun durumu.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ChildState {
+    /// Sonlanmış ve toplanabilir; görev tablo indeksi.
+    Zombie(usize),
+    /// Sonlanmış, ancak bir iş parçacığı hâlâ bir CPU'dan çıkıyor.
+    Exiting,
+    /// Hâlâ çalışıyor.
+    Running,
+}
+
+/// Tek bir iş parçacığının zamanlayıcı tarafından tutulan durumu.
+#[derive(Debug, Copy, Clone)]
+struct ThreadSlot {
+    id: KThreadId,
+    task: KTaskId,
+    /// Ait olduğu görevin tablo indeksi.
+    task_slot: usize,
+    state: TaskState,
+    wake_reason: WakeReason,
+    /// Blocked/Sleeping durumunda, monoton saatte uyandırılacağı an (varsa).
+    deadline_ns: Option<u64>,
+    /// `deadline_ns` varsa, girdisinin bulunduğu zamanlayıcı kuyruğunun CPU'su.
+    timer_cpu: Option<usize>,
+    /// Kullanıcının/çekirdeğin atadığı öncelik.
+    base_priority: Priority,
+    /// Öncelik mirası (priority inheritance) dahil geçerli öncelik. Her zaman >= base_priority.
+    priority: Priority,
+    /// Tuttuğu kilitlerden (anahtar, bekleyenlerin en yüksek önceliği) miras alınan öncelikler;
+    /// anahtar 0 boş girdidir. Geçerli öncelik tabanla bunların en yükseğidir.
+    pi_boosts: [(usize, Priority); PI_MAX_LOCKS],
+    /// Sahibine öncelik miras bırakarak beklediği kilidin anahtarı (0: yok). Miras zincir boyunca
+    /// bu alan üzerinden iletilir.
+    pi_blocked_on: usize,
+    /// Running durumunda, zaman diliminin bittiği an (monoton saat).
+    slice_end_ns: u64,
+    /// Son çalıştığı (veya hazır kuyruğunda beklediği) CPU.
+    cpu: usize,
+    /// Çalışabileceği CPU'ların maskesi (bit `cpu`). Idle iş parçacıkları kendi CPU'larına bağlıdır.
+    affinity: u64,
+    /// Bir CPU'da çalışıyor ya da bağlamı henüz kaydediliyor. Bu sürede başka bir CPU onu seçemez.
+    on_cpu: bool,
+    /// `thread_create`'in görevin adres alanında ayırdığı kullanıcı yığını (taban, boyut);
+    /// iş parçacığı `thread_exit` ile sonlanınca bırakılır. Çağıranın yönettiği yığınlarda `None`.
+    user_stack: Option<(u64, usize)>,
+    stats: ThreadStats,
+    /// Son kez Ready yapıldığı an (hazır kuyruğu bekleme süresi ve uyanma gecikmesi için).
+    ready_since_ns: u64,
+    /// Bloklanmışken uyandırıldı ve henüz çalışmaya başlamadı.
+    woken: bool,
+    /// Şu an kullanıcı modunda mı (sistem çağrısı dışında). Süre bu moda yazılır.
+    in_user: bool,
+    /// Kullanıcı/çekirdek süresinin en son yazıldığı an.
+    mode_since_ns: u64,
+    /// Deadline sınıfındaysa parametreleri ve EDF durumu. Kısıtlanmışken (bütçesi bitmiş)
+    /// `deadline_ns`/`timer_cpu` bütçe yenileme anını tutar.
+    dl: Option<DeadlineEntity>,
+    /// Ready, ancak işi askıda olduğu için kuyruğa konmadı; iş devam ettirilince konur.
+    suspended: bool,
+}
+
+impl ThreadSlot {
+    /// Çekirdek (boot/idle) iş parçacığı yuvası.
+    fn idle(id: KThreadId, cpu: usize, now: u64) -> Self {
+        ThreadSlot {
+            id,
+            task: KTaskId(0),
+            task_slot: KERNEL_TASK_SLOT,
+            state: TaskState::Running,
+            wake_reason: WakeReason::Signaled,
+            deadline_ns: None,
+            timer_cpu: None,
+            base_priority: PRIORITY_IDLE,
+            priority: PRIORITY_IDLE,
+            pi_boosts: [(0, PRIORITY_IDLE); PI_MAX_LOCKS],
+            pi_blocked_on: 0,
+            slice_end_ns: 0,
+            cpu,
+            affinity: 1 << cpu,
+            on_cpu: true,
+            user_stack: None,
+            stats: ThreadStats::default(),
+            ready_since_ns: now,
+            woken: false,
+            in_user: false,
+            mode_since_ns: now,
+            dl: None,
+            suspended: false,
+        }
+    }
+
+    /// Taban öncelik ile tutulan kilitlerden miras alınanların en yükseği.
+    fn inherited_priority(&self) -> Priority {
+        self.pi_boosts.iter().filter(|(lock, _)| *lock != 0).map(|(_, p)| *p).fold(self.base_priority, Priority::max)
+    }
+
+    /// `mode_since_ns`'ten `now`'a kadar geçen süreyi mevcut moda yazar.
+    fn charge(&mut self, now: u64) {
+        let elapsed = now.saturating_sub(self.mode_since_ns);
+        if self.in_user {
+            self.stats.user_ns += elapsed;
+        } else {
+            self.stats.kernel_ns += elapsed;
+        }
+        self.mode_since_ns = now;
+    }
+}
+
+/// Tek bir öncelik seviyesinin FIFO hazır kuyruğu (dairesel tampon, iş parçacığı tablo indeksleri tutar).
+/// Her CPU'da 32 seviye bulunduğundan indeksler bayt olarak saklanır.
+struct ReadyQueue {
+    slots: [u8; MAX_THREADS],
+    head: usize,
+    len: usize,
+}
+
+impl ReadyQueue {
+    const fn new() -> Self {
+        ReadyQueue { slots: [0; MAX_THREADS], head: 0, len: 0 }
+    }
+
+    fn push(&mut self, slot: usize) {
+        // Her iş parçacığı kuyrukta en fazla bir kez bulunur, bu yüzden kapasite aşılmaz.
+        debug_assert!(self.len < MAX_THREADS);
+        let tail = (self.head + self.len) % MAX_THREADS;
+        self.slots[tail] = slot as u8;
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        let slot = self.slots[self.head] as usize;
+        self.head = (self.head + 1) % MAX_THREADS;
+        self.len -= 1;
+        Some(slot)
+    }
+
+    /// Kuyruktaki indeksler, baştan sona.
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).map(move |i| self.slots[(self.head + i) % MAX_THREADS] as usize)
+    }
+
+    /// `slot`'u sıradan çıkarır (önceliği değişen hazır iş parçacıkları için). Sıra korunur.
+    fn remove(&mut self, slot: usize) -> bool {
+        let pos = match self.iter().position(|s| s == slot) {
+            Some(pos) => pos,
+            None => return false,
+        };
+        for i in pos..self.len - 1 {
+            self.slots[(self.head + i) % MAX_THREADS] = self.slots[(self.head + i + 1) % MAX_THREADS];
+        }
+        self.len -= 1;
+        true
+    }
+}
+
+/// Öncelik seviyesi başına bir hazır kuyruğu. Her zaman en yüksek dolu seviyeden seçilir;
+/// aynı seviyedekiler sırayla (round-robin) çalışır.
+struct RunQueue {
+    levels: [ReadyQueue; NUM_PRIORITIES],
+    /// Bit `p`: `p` seviyesinin kuyruğu boş değil.
+    nonempty: u32,
+    /// Tüm seviyelerdeki toplam iş parçacığı sayısı (yük dengeleme için).
+    count: usize,
+}
+
+impl RunQueue {
+    const fn new() -> Self {
+        const EMPTY_LEVEL: ReadyQueue = ReadyQueue::new();
+        RunQueue { levels: [EMPTY_LEVEL; NUM_PRIORITIES], nonempty: 0, count: 0 }
+    }
+
+    fn push(&mut self, slot: usize, priority: Priority) {
+        self.levels[priority as usize].push(slot);
+        self.nonempty |= 1 << priority;
+        self.count += 1;
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        let priority = self.highest()?;
+        let level = &mut self.levels[priority as usize];
+        let slot = level.pop();
+        if level.len == 0 {
+            self.nonempty &= !(1 << priority);
+        }
+        self.count -= 1;
+        slot
+    }
+
+    fn remove(&mut self, slot: usize, priority: Priority) -> bool {
+        let level = &mut self.levels[priority as usize];
+        let removed = level.remove(slot);
+        if level.len == 0 {
+            self.nonempty &= !(1 << priority);
+        }
+        if removed {
+            self.count -= 1;
+        }
+        removed
+    }
+
+    /// Kuyruktaki en yüksek öncelik.
+    fn highest(&self) -> Option<Priority> {
+        if self.nonempty == 0 {
+            None
+        } else {
+            Some((31 - self.nonempty.leading_zeros()) as Priority)
+        }
+    }
+}
+
+/// Tek bir işlemcinin zamanlayıcı durumu.
+struct CpuState {
+    /// Çevrimiçi: iş parçacığı çalıştırıyor, kuyruğuna iş konabilir.
+    online: bool,
+    /// Bu CPU'nun idle iş parçacığının tablo indeksi.
+    idle: usize,
+    /// Bu CPU'da çalışan iş parçacığının tablo indeksi.
+    current: usize,
+    /// Bu CPU'nun son bağlam değişiminde bıraktığı iş parçacığı. Bağlamının kaydı bitene
+    /// (`finish_switch`) kadar `on_cpu` kalır.
+    switched_out: Option<usize>,
+    /// Son periyodik yük dengelemesinin zamanı.
+    last_balance_ns: u64,
+    ready: RunQueue,
+    /// Bu CPU'da zaman aşımıyla bloklanan iş parçacıklarının uyanma anları.
+    timers: TimerQueue,
+    /// Tek seferlik zamanlayıcının en son kurulduğu an (`u64::MAX`: kurulmadı).
+    timer_armed_ns: u64,
+    /// Bu CPU'ya bağlı hazır deadline iş parçacıkları; aralarından son tarihi en erken olan seçilir.
+    dl_ready: ReadyQueue,
+    /// Bu CPU'ya kabul edilmiş deadline iş parçacıklarının toplam kullanım payı (bkz. srcdeadline.rs).
+    dl_util: u64,
+    /// Çalışan iş parçacığının süresinin görevinin CPU kotasına en son yazıldığı an.
+    quota_since_ns: u64,
+}
+
+impl CpuState {
+    const fn new() -> Self {
+        CpuState {
+            online: false,
+            idle: IDLE_SLOT,
+            current: IDLE_SLOT,
+            switched_out: None,
+            last_balance_ns: 0,
+            ready: RunQueue::new(),
+            timers: TimerQueue::new(),
+            timer_armed_ns: u64::MAX,
+            dl_ready: ReadyQueue::new(),
+            dl_util: 0,
+            quota_since_ns: 0,
+        }
+    }
+}
+
+struct Scheduler {
+    tasks: [Option<TaskSlot>; MAX_TASKS],
+    threads: [Option<ThreadSlot>; MAX_THREADS],
+    cpus: [CpuState; MAX_CPUS],
+    next_task_id: u64,
+    next_thread_id: u64,
+    jobs: [Option<JobSlot>; MAX_JOBS],
+    job_handles: [JobHandle; job::MAX_JOB_HANDLES],
+    next_job_id: u64,
+    /// Boot sırasında kaydedilen mimari katmanı. Kaydedilene kadar bağlam değiştirilmez.
+    arch: Option<&'static dyn ArchTask>,
+}
+
+// Zamanlayıcı durumunu koruyan kilit. Kesme bağlamından (timer_tick, wake) da alındığı için
+// her zaman lock_irqsave ile alınır.
+static SCHEDULER: Spinlock<Scheduler> = Spinlock::named(
+    Scheduler {
+        tasks: [None; MAX_TASKS],
+        threads: [None; MAX_THREADS],
+        cpus: {
+            const OFFLINE: CpuState = CpuState::new();
+            [OFFLINE; MAX_CPUS]
+        },
+        next_task_id: 1,
+        next_thread_id: 1,
+        jobs: [None; MAX_JOBS],
+        job_handles: [JobHandle::EMPTY; job::MAX_JOB_HANDLES],
+        next_job_id: 2,
+        arch: None,
+    },
+    "ktask::SCHEDULER",
+);
+
+impl Scheduler {
+    fn slot_of(&self, id: KThreadId) -> Option<usize> {
+        self.threads.iter().position(|t| matches!(t, Some(t) if t.id == id))
+    }
+
+    fn task_slot_of(&self, id: KTaskId) -> Option<usize> {
+        self.tasks.iter().position(|t| matches!(t, Some(t) if t.id == id))
+    }
+
+    /// Çağıranın görevindeki canlı `id` iş parçacığının (`KThreadId(0)`: çağıran iş parçacığı)
+    /// tablo indeksi. Başka bir görevin iş parçacıkları ve idle iş parçacıkları için
+    /// `KError::PermissionDenied`.
+    fn own_thread_slot(&self, id: KThreadId) -> Result<usize, KError> {
+        let current = self.current();
+        let own = self.threads[current].map(|t| t.task).ok_or(KError::InternalError)?;
+        let slot = if id.0 == 0 { current } else { self.slot_of(id).ok_or(KError::NotFound)? };
+        match self.threads[slot] {
+            Some(t) if t.state == TaskState::Exited => Err(KError::NotFound),
+            Some(t) if t.task != own || self.is_idle(slot) => Err(KError::PermissionDenied),
+            Some(_) => Ok(slot),
+            None => Err(KError::NotFound),
+        }
+    }
+
+    /// Çağıran CPU'da çalışan iş parçacığının tablo indeksi.
+    fn current(&self) -> usize {
+        self.cpus[current_cpu()].current
+    }
+
+    /// `slot` bir CPU'nun idle iş parçacığı mı (başlatılmakta olan CPU'lar dahil).
+    fn is_idle(&self, slot: usize) -> bool {
+        self.cpus.iter().any(|c| c.idle == slot)
+    }
+
+    /// `cpu`'nun yükü: hazır kuyruklarındakiler artı (idle değilse) çalışan iş parçacığı.
+    fn load(&self, cpu: usize) -> usize {
+        let c = &self.cpus[cpu];
+        c.ready.count + c.dl_ready.len + (c.current != c.idle) as usize
+    }
+
+    /// `slot`'un afinite maskesi `cpu`'yu içeriyor mu.
+    fn allowed(&self, slot: usize, cpu: usize) -> bool {
+        matches!(self.threads[slot], Some(t) if t.affinity & (1 << cpu) != 0)
+    }
+
+    /// `mask`'taki en az yüklü çevrimiçi CPU. Eşitlikte çağıran CPU tercih edilir. Maskede
+    /// çevrimiçi CPU yoksa tüm çevrimiçi CPU'lar arasından seçilir.
+    fn least_loaded_cpu(&self, mask: u64) -> usize {
+        let me = current_cpu();
+        let pick = |mask: u64| {
+            (0..MAX_CPUS)
+                .filter(|&cpu| self.cpus[cpu].online && mask & (1 << cpu) != 0)
+                .min_by_key(|&cpu| (self.load(cpu), cpu != me))
+        };
+        pick(mask).or_else(|| pick(CPU_MASK_ALL)).unwrap_or(me)
+    }
+
+    /// Ready `slot`'u `cpu`'nun kuyruğuna koyar: deadline sınıfındakiler EDF kuyruğuna,
+    /// diğerleri önceliklerinin seviyesinin sonuna. Görevi CPU payını tükettiyse kuyruğa girmez,
+    /// pencere sonuna kadar zamanlayıcı kuyruğunda bekler (`park`). İşi askıdaysa hiç kuyruğa
+    /// girmez (`ThreadSlot::suspended`).
+    fn enqueue(&mut self, slot: usize, cpu: usize) {
+        if self.thread_held(slot) {
+            if let Some(t) = self.threads[slot].as_mut() {
+                t.suspended = true;
+            }
+            return;
+        }
+        match self.threads[slot] {
+            Some(t) if t.dl.is_some() => self.dl_enqueue(slot, cpu),
+            Some(t) => match self.quota_throttled(slot, now_ns()) {
+                Some(until) => self.park(slot, until),
+                None => self.cpus[cpu].ready.push(slot, t.priority),
+            },
+            None => {}
+        }
+    }
+
+    /// Ready `slot`'u `until` anına kadar hazır kuyruğunun dışında bekletir.
+    fn park(&mut self, slot: usize, until: u64) {
+        if matches!(self.threads[slot], Some(t) if t.timer_cpu.is_none()) {
+            self.set_deadline(slot, Some(until));
+        }
+    }
+
+    /// Zamanlayıcı kuyruğunda bekleyen Ready `slot`'un süresi doldu: deadline iş parçacığının
+    /// bütçesi yenilenir, diğerleri kuyruğa döner.
+    fn unpark(&mut self, slot: usize, now: u64) {
+        let (cpu, deadline_class) = match self.threads[slot] {
+            Some(t) => (t.cpu, t.dl.is_some()),
+            None => return,
+        };
+        if deadline_class {
+            self.dl_replenish(slot, now);
+        } else {
+            self.enqueue(slot, cpu);
+            self.check_preempt(cpu);
+        }
+    }
+
+    /// `slot`'u `cpu`'nun kuyruğundan (varsa) çıkarır.
+    fn dequeue(&mut self, slot: usize, cpu: usize) -> bool {
+        match self.threads[slot] {
+            Some(t) if t.dl.is_some() => self.cpus[cpu].dl_ready.remove(slot),
+            Some(t) => self.cpus[cpu].ready.remove(slot, t.priority),
+            None => false,
+        }
+    }
+
+    /// `slot`'u Ready yapar ve CPU'sunun kuyruğuna ekler (bkz. `enqueue`). Uyanan deadline iş
+    /// parçacığının son tarihi ve bütçesi CBS kuralıyla güncellenir. Idle iş parçacıkları
+    /// kuyruğa girmez.
+    fn make_ready(&mut self, slot: usize, reason: WakeReason) {
+        if self.is_idle(slot) {
+            if let Some(thread) = self.threads[slot].as_mut() {
+                thread.state = TaskState::Ready;
+            }
+            return;
+        }
+        self.set_deadline(slot, None);
+        let now = now_ns();
+        let (cpu, waking) = match self.threads[slot].as_mut() {
+            Some(thread) => {
+                let waking = thread.state == TaskState::Blocked || thread.state == TaskState::Sleeping;
+                thread.state = TaskState::Ready;
+                thread.wake_reason = reason;
+                thread.ready_since_ns = now;
+                (thread.cpu, waking)
+            }
+            None => return,
+        };
+        if waking {
+            self.dl_wakeup(slot, now);
+        }
+        self.enqueue(slot, cpu);
+    }
+
+    /// Uyanan `slot` için afinite maskesindeki CPU'lardan birini seçer: son çalıştığı CPU boşsa
+    /// o, değilse boştaki (idle çalıştıran) bir CPU varsa o. İş parçacığı hâlâ son CPU'sunda
+    /// kaydediliyorsa orada kalır; maske o CPU'yu dışlıyorsa `finish_switch` taşır.
+    fn select_wake_cpu(&mut self, slot: usize) {
+        let (last, on_cpu, mask) = match self.threads[slot] {
+            Some(t) => (t.cpu, t.on_cpu, t.affinity),
+            None => return,
+        };
+        let allowed = |cpu: usize| mask & (1 << cpu) != 0;
+        let last_usable = allowed(last) && self.cpus[last].online;
+        if on_cpu || (last_usable && self.cpus[last].current == self.cpus[last].idle) {
+            return;
+        }
+        let idle_cpu = (0..MAX_CPUS).find(|&cpu| {
+            let c = &self.cpus[cpu];
+            allowed(cpu) && c.online && c.current == c.idle && c.ready.count == 0 && c.dl_ready.len == 0
+        });
+        let target = match idle_cpu {
+            Some(cpu) => cpu,
+            None if last_usable => last,
+            None => self.least_loaded_cpu(mask),
+        };
+        if let Some(thread) = self.threads[slot].as_mut() {
+            thread.cpu = target;
+        }
+    }
+
+    /// `slot`'un uyanma (deadline sınıfında kısıtlanmışsa bütçe yenileme) anını `deadline` yapar:
+    /// eski girdisi kuyruğundan çıkarılır, yenisi çağıran CPU'nun kuyruğuna eklenir ve bu CPU'nun
+    /// zamanlayıcısı gerekirse öne çekilir.
+    fn set_deadline(&mut self, slot: usize, deadline: Option<u64>) {
+        let old = match self.threads[slot].as_mut() {
+            Some(thread) => {
+                thread.deadline_ns = deadline;
+                thread.timer_cpu.take()
+            }
+            None => return,
+        };
+        if let Some(cpu) = old {
+            self.cpus[cpu].timers.remove(slot);
+        }
+        if let Some(deadline) = deadline {
+            let cpu = current_cpu();
+            self.cpus[cpu].timers.push(deadline, slot);
+            if let Some(thread) = self.threads[slot].as_mut() {
+                thread.timer_cpu = Some(cpu);
+            }
+            if deadline < self.cpus[cpu].timer_armed_ns {
+                self.program_timer(cpu, deadline);
+            }
+        }
+    }
+
+    /// `cpu`'nun bir sonraki zamanlayıcı olayı: en erken uyanma, çalışan iş parçacığının zaman
+    /// dilimi veya deadline bütçesi sonu ya da periyodik yük dengeleme.
+    fn next_timer_event(&self, cpu: usize) -> u64 {
+        let c = &self.cpus[cpu];
+        let mut next = c.last_balance_ns.saturating_add(BALANCE_INTERVAL_NS);
+        if let Some((deadline, _)) = c.timers.peek() {
+            next = next.min(deadline);
+        }
+        if c.current != c.idle {
+            if let Some(thread) = self.threads[c.current] {
+                next = next.min(thread.slice_end_ns);
+            }
+        }
+        if let Some(budget_end) = self.dl_budget_end(cpu) {
+            next = next.min(budget_end);
+        }
+        next
+    }
+
+    /// Çağıran CPU'nun (`cpu`) tek seferlik zamanlayıcısını `deadline`'a kurar.
+    fn program_timer(&mut self, cpu: usize, deadline: u64) {
+        self.cpus[cpu].timer_armed_ns = deadline;
+        if let Some(arch) = self.arch {
+            unsafe { arch.program_timer(deadline) };
+        }
+    }
+
+    /// Çağıran CPU'nun zamanlayıcısını, değiştiyse bir sonraki olaya kurar.
+    fn rearm_timer(&mut self, cpu: usize) {
+        let next = self.next_timer_event(cpu);
+        if next != self.cpus[cpu].timer_armed_ns {
+            self.program_timer(cpu, next);
+        }
+    }
+
+    /// Süresi `now` anına kadar dolmuş tüm uyanmaları (her CPU'nun kuyruğundan) TimedOut
+    /// nedeniyle uyandırır; bekletilen Ready iş parçacıklarını kuyruğa döndürür (bkz. `unpark`). Tüm
+    /// kuyruklara bakılır; periyodik tick'i yalnızca boot CPU'sunda olan portlarda da uyanmalar
+    /// kaçmaz.
+    fn expire_timers(&mut self, now: u64) {
+        for cpu in 0..MAX_CPUS {
+            while let Some((deadline, slot)) = self.cpus[cpu].timers.peek() {
+                if deadline > now {
+                    break;
+                }
+                self.cpus[cpu].timers.pop();
+                let state = match self.threads[slot].as_mut() {
+                    Some(thread) => {
+                        thread.timer_cpu = None;
+                        thread.deadline_ns = None;
+                        thread.state
+                    }
+                    None => continue,
+                };
+                match state {
+                    TaskState::Blocked | TaskState::Sleeping => self.wake_slot(slot, WakeReason::TimedOut),
+                    TaskState::Ready => self.unpark(slot, now),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// `slot`'un geçerli önceliğini değiştirir. Hazır kuyruğundaysa yeni seviyesine taşınır.
+    fn set_effective_priority(&mut self, slot: usize, priority: Priority) {
+        let (old, state, cpu) = match self.threads[slot] {
+            Some(t) => (t.priority, t.state, t.cpu),
+            None => return,
+        };
+        if old == priority {
+            return;
+        }
+        if let Some(thread) = self.threads[slot].as_mut() {
+            thread.priority = priority;
+        }
+        if state == TaskState::Ready && self.cpus[cpu].ready.remove(slot, old) {
+            self.cpus[cpu].ready.push(slot, priority);
+        }
+    }
+
+    /// `cpu`'nun hazır kuyruğunda, orada çalışan iş parçacığını kesmesi gereken biri var mı.
+    /// `slice_expired` ise aynı öncelikteki bir iş parçacığı da yeterlidir (round-robin).
+    /// Çalışan iş parçacığı sonlandırılmışsa (başka CPU'dan task_exit) veya işi askıya
+    /// alındıysa her zaman `true`.
+    /// Deadline sınıfı öncelik seviyelerinden önce gelir (bkz. `dl_should_preempt`).
+    fn should_preempt(&self, cpu: usize, slice_expired: bool) -> bool {
+        let c = &self.cpus[cpu];
+        if matches!(self.threads[c.current], Some(t) if t.state == TaskState::Exited) {
+            return true;
+        }
+        if self.thread_held(c.current) {
+            return true;
+        }
+        if let Some(preempt) = self.dl_should_preempt(cpu) {
+            return preempt;
+        }
+        if self.quota_throttled(c.current, now_ns()).is_some() {
+            return true;
+        }
+        let top = match c.ready.highest() {
+            Some(top) => top,
+            None => return false,
+        };
+        match self.threads[c.current] {
+            Some(t) if c.current != c.idle && t.state == TaskState::Running => {
+                top > t.priority || (slice_expired && top == t.priority)
+            }
+            _ => true,
+        }
+    }
+
+    /// `cpu`'yu yeniden zamanlama için işaretler; uzak bir CPU ise IPI ile uyarır.
+    fn resched_cpu(&self, cpu: usize) {
+        NEED_RESCHED[cpu].store(true, Ordering::Relaxed);
+        if cpu != current_cpu() {
+            if let Some(arch) = self.arch {
+                send_ipi(arch, cpu, IPI_RESCHEDULE);
+            }
+        }
+    }
+
+    /// `cpu`'nun hazır kuyruğu değiştikten sonra gerekirse onu yeniden zamanlatır.
+    fn check_preempt(&self, cpu: usize) {
+        if self.should_preempt(cpu, false) {
+            self.resched_cpu(cpu);
+        }
+    }
+
+    /// Bloklanmış `slot`'u uyandırır: bir CPU seçer, oranın kuyruğuna koyar ve oradaki iş
+    /// parçacığından daha önemliyse o CPU'yu yeniden zamanlatır.
+    fn wake_slot(&mut self, slot: usize, reason: WakeReason) {
+        self.select_wake_cpu(slot);
+        self.make_ready(slot, reason);
+        if let Some(thread) = self.threads[slot].as_mut() {
+            thread.woken = true;
+        }
+        if let Some(cpu) = self.threads[slot].map(|t| t.cpu) {
+            self.check_preempt(cpu);
+        }
+    }
+
+    /// Bu CPU'nun bir önceki bağlam değişiminde bıraktığı iş parçacığının bağlamı artık
+    /// kaydedilmiştir; başka CPU'lar onu seçebilir. Hazırsa ve afinitesi bulunduğu CPU'yu
+    /// dışlıyorsa izinli bir CPU'ya taşınır.
+    fn finish_switch(&mut self, cpu: usize) {
+        let prev = match self.cpus[cpu].switched_out.take() {
+            Some(prev) => prev,
+            None => return,
+        };
+        let (state, last) = match self.threads[prev].as_mut() {
+            Some(thread) => {
+                thread.on_cpu = false;
+                (thread.state, thread.cpu)
+            }
+            None => return,
+        };
+        if state == TaskState::Ready && !self.allowed(prev, last) {
+            // schedule onu kuyruğa koymadan bırakmış veya kaydedilirken uyandırılmış olabilir.
+            self.dequeue(prev, last);
+            self.migrate(prev);
+        }
+    }
+
+    /// Hazır kuyruğunda olmayan Ready `slot`'u afinite maskesindeki en az yüklü CPU'nun
+    /// kuyruğuna koyar ve gerekirse o CPU'yu yeniden zamanlatır.
+    fn migrate(&mut self, slot: usize) {
+        let mask = match self.threads[slot] {
+            Some(t) => t.affinity,
+            None => return,
+        };
+        let target = self.least_loaded_cpu(mask);
+        if let Some(thread) = self.threads[slot].as_mut() {
+            thread.cpu = target;
+        }
+        self.enqueue(slot, target);
+        self.check_preempt(target);
+    }
+
+    /// `from`'un kuyruğundan taşınabilir (başka CPU'da çalışmayan ve afinitesi `to`'yu içeren)
+    /// en yüksek öncelikli iş parçacığını `to`'nun kuyruğuna taşır.
+    fn pull_one(&mut self, from: usize, to: usize) -> bool {
+        for priority in (0..NUM_PRIORITIES).rev() {
+            let candidate = self.cpus[from].ready.levels[priority].iter().find(|&slot| {
+                matches!(
+                    self.threads[slot],
+                    Some(t) if t.state == TaskState::Ready && !t.on_cpu && t.affinity & (1 << to) != 0
+                )
+            });
+            if let Some(slot) = candidate {
+                let priority = priority as Priority;
+                self.cpus[from].ready.remove(slot, priority);
+                if let Some(thread) = self.threads[slot].as_mut() {
+                    thread.cpu = to;
+                }
+                self.cpus[to].ready.push(slot, priority);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// En yüklü çevrimiçi CPU'nun yükü `cpu`'nunkinden en az `threshold` fazlaysa oradan bir
+    /// iş parçacığı çeker.
+    fn balance(&mut self, cpu: usize, threshold: usize) -> bool {
+        let busiest = (0..MAX_CPUS)
+            .filter(|&other| other != cpu && self.cpus[other].online && self.cpus[other].ready.count > 0)
+            .max_by_key(|&other| self.load(other));
+        match busiest {
+            Some(busiest) if self.load(busiest) >= self.load(cpu) + threshold => self.pull_one(busiest, cpu),
+            _ => false,
+        }
+    }
+
+    /// Boş veya yeniden kullanılabilir (sonlanmış ve hiçbir CPU'da olmayan) bir iş parçacığı yuvası bulur.
+    fn free_thread_slot(&self) -> Option<usize> {
+        (0..MAX_THREADS).filter(|&slot| !self.is_idle(slot)).find(|&slot| match self.threads[slot] {
+            None => true,
+            Some(t) => t.state == TaskState::Exited && !t.on_cpu,
+        })
+    }
+
+    /// Boş bir görev yuvası bulur. Zombi görevlerin yuvaları toplanana kadar kullanılmaz.
+    fn free_task_slot(&self) -> Option<usize> {
+        (0..MAX_TASKS).filter(|&slot| slot != KERNEL_TASK_SLOT).find(|&slot| self.tasks[slot].is_none())
+    }
+
+    /// `task_slot`'taki görev sonlanmışsa ve hiçbir iş parçacığı artık bir CPU'da değilse
+    /// (bağlamları kaydedildi, çekirdek yığınları kullanılmıyor) `true`.
+    fn reapable(&self, task_slot: usize) -> bool {
+        let id = match self.tasks[task_slot] {
+            Some(t) if t.state == TaskState::Exited => t.id,
+            _ => return false,
+        };
+        !self.threads.iter().flatten().any(|t| t.task == id && t.on_cpu)
+    }
+
+    /// `parent`'ın `task` çocuğunun (`KTaskId(0)`: herhangi bir çocuğunun) durumu.
+    /// Böyle bir çocuk yoksa `KError::NotFound` döner.
+    fn find_child(&self, parent: KTaskId, task: KTaskId) -> Result<ChildState, KError> {
+        let mut state = None;
+        for slot in (0..MAX_TASKS).filter(|&slot| slot != KERNEL_TASK_SLOT) {
+            let child = match self.tasks[slot] {
+                Some(t) if t.parent == parent && (task.0 == 0 || t.id == task) => t,
+                _ => continue,
+            };
+            if self.reapable(slot) {
+                return Ok(ChildState::Zombie(slot));
+            }
+            state = match (state, child.state) {
+                (_, TaskState::Exited) => Some(ChildState::Exiting),
+                (None, _) => Some(ChildState::Running),
+                (state, _) => state,
+            };
+        }
+        state.ok_or(KError::NotFound)
+    }
+
+    /// Toplanabilir (`reapable`) görevi tablodan kaldırır: iş parçacığı yuvaları (ve onlarla
+    /// birlikte çekirdek yığınları ve bağlam alanları) ile görev yuvası boşaltılır. Adres alanı
+    /// ve handle'lar, zamanlayıcı kilidi bırakıldıktan sonra `release_task_resources` ile bırakılır.
+    /// Görevin sayaçları işinde birikir.
+    fn reap(&mut self, task_slot: usize) -> Option<TaskSlot> {
+        let stats = self.task_total_stats(task_slot, now_ns())?;
+        let task = self.tasks[task_slot].take()?;
+        for thread in self.threads.iter_mut() {
+            if matches!(thread, Some(t) if t.task == task.id) {
+                *thread = None;
+            }
+        }
+        self.job_task_reaped(task.job, &task.quota, &stats);
+        Some(task)
+    }
+
+    /// `prev`'den `next`'e geçiş anında (`now`) sayaçları günceller: `prev`'in süresi moduna yazılır
+    /// ve bağlam değişimi sayılır, `next`'in hazır kuyruğunda ve uyandırıldıktan sonra beklediği
+    /// süre eklenir. `next == prev` ise (başka hazır iş parçacığı yok) değişim sayılmaz.
+    fn account_switch(&mut self, prev: usize, next: usize, now: u64, preempted: bool) {
+        if let Some(thread) = self.threads[prev].as_mut() {
+            thread.charge(now);
+            if next != prev {
+                if preempted {
+                    thread.stats.involuntary_switches += 1;
+                } else {
+                    thread.stats.voluntary_switches += 1;
+                }
+            }
+        }
+        let idle = self.is_idle(next);
+        if let Some(thread) = self.threads[next].as_mut() {
+            thread.mode_since_ns = now;
+            // Idle iş parçacıkları kuyrukta beklemez; boşta geçen süreleri çekirdek süresidir.
+            if idle {
+                return;
+            }
+            let waited = now.saturating_sub(thread.ready_since_ns);
+            thread.stats.run_wait_ns += waited;
+            if thread.woken {
+                thread.woken = false;
+                thread.stats.wakeups += 1;
+                thread.stats.wakeup_latency_total_ns += waited;
+                thread.stats.wakeup_latency_max_ns = thread.stats.wakeup_latency_max_ns.max(waited);
+            }
+        }
+    }
+
+    /// `slot`'un `now` anındaki sayaçları: bir CPU'da çalışıyorsa son mod geçişinden beri geçen
+    /// süre de eklenir.
+    fn thread_stats(&self, slot: usize, now: u64) -> Option<ThreadStats> {
+        let thread = self.threads[slot]?;
+        let mut stats = thread.stats;
+        if self.cpus[thread.cpu].online && self.cpus[thread.cpu].current == slot {
+            let pending = now.saturating_sub(thread.mode_since_ns);
+            if thread.in_user {
+                stats.user_ns += pending;
+            } else {
+                stats.kernel_ns += pending;
+            }
+        }
+        Some(stats)
+    }
+
+    /// `task_slot`'taki görevin sonlanmış ve yaşayan tüm iş parçacıklarının `now` anındaki toplam sayaçları.
+    fn task_total_stats(&self, task_slot: usize, now: u64) -> Option<ThreadStats> {
+        let task = self.tasks[task_slot]?;
+        let mut total = task.exited_stats;
+        for slot in 0..MAX_THREADS {
+            if matches!(self.threads[slot], Some(t) if t.task == task.id) {
+                if let Some(stats) = self.thread_stats(slot, now) {
+                    total.accumulate(&stats);
+                }
+            }
+        }
+        Some(total)
+    }
+
+    /// Yeniden kullanılmak üzere olan `slot`'taki sonlanmış iş parçacığının sayaçlarını görevine ekler.
+    fn retire_thread_slot(&mut self, slot: usize) {
+        let (task, task_slot, stats) = match self.threads[slot] {
+            Some(t) => (t.task, t.task_slot, t.stats),
+            None => return,
+        };
+        if let Some(owner) = self.tasks[task_slot].as_mut() {
+            if owner.id == task {
+                owner.exited_stats.accumulate(&stats);
+            }
+        }
+    }
+
+    /// `slot`'taki iş parçacığını sonlandırır. Görevin son iş parçacığıysa görev de sonlanır.
+    fn exit_thread(&mut self, slot: usize, exit_code: i32) {
+        let (task_slot, was_ready, cpu) = match self.threads[slot].as_mut() {
+            Some(thread) if thread.state != TaskState::Exited => {
+                let was_ready = thread.state == TaskState::Ready;
+                thread.state = TaskState::Exited;
+                (thread.task_slot, was_ready, thread.cpu)
+            }
+            _ => return,
+        };
+        self.set_deadline(slot, None);
+        // Hazır kuyruğunda kalan bir girdi, yuva yeniden kullanıldığında iş parçacığını iki kez kuyruğa sokar.
+        if was_ready {
+            self.dequeue(slot, cpu);
+        }
+        self.dl_release(slot);
+        if let Some(task) = self.tasks[task_slot].as_mut() {
+            task.live_threads -= 1;
+            if task.live_threads == 0 && task.state != TaskState::Exited {
+                task.state = TaskState::Exited;
+                task.exit_code = exit_code;
+            }
+        }
+    }
+
+    /// `task_slot`'taki görevi tüm iş parçacıklarıyla sonlandırır; başka CPU'larda çalışan iş
+    /// parçacıkları orada hemen bırakılır. Sonlanan görevin ID'si döner.
+    fn kill_task(&mut self, task_slot: usize, code: i32, fault: Option<KError>) -> Option<KTaskId> {
+        let task = self.tasks[task_slot].as_mut()?;
+        task.state = TaskState::Exited;
+        task.exit_code = code;
+        task.fault = fault;
+        let id = task.id;
+        for slot in 0..MAX_THREADS {
+            if matches!(self.threads[slot], Some(t) if t.task_slot == task_slot) {
+                self.exit_thread(slot, code);
+            }
+        }
+        for cpu in 0..MAX_CPUS {
+            let c = &self.cpus[cpu];
+            if cpu != current_cpu() && c.online && matches!(self.threads[c.current], Some(t) if t.task_slot == task_slot) {
+                self.resched_cpu(cpu);
+            }
+        }
+        Some(id)
+    }
+
+    /// Sonlanan `id` görevinin çocukları (zombiler dahil) çekirdek görevine devredilir; idle
+    /// döngüsü onları toplar.
+    fn reparent_children(&mut self, id: KTaskId) {
+        for task in self.tasks.iter_mut().flatten() {
+            if task.parent == id && task.id != id {
+                task.parent = KTaskId(0);
+            }
+        }
+    }
+}
+
+/// Görev yöneticisini başlatır. Çağıran bağlam, çekirdek görevinin idle iş parçacığı (ID 0) olarak kaydedilir.
+pub fn init_manager() {
+    let mut sched = SCHEDULER.lock_irqsave();
+    sched.tasks[KERNEL_TASK_SLOT] = Some(TaskSlot {
+        id: KTaskId(0),
+        parent: KTaskId(0),
+        state: TaskState::Running,
+        address_space: KERNEL_ADDRESS_SPACE,
+        live_threads: 1,
+        exit_code: 0,
+        fault: None,
+        exception_port: None,
+        exited_stats: ThreadStats::default(),
+        quota: QuotaState::unlimited(),
+        job: JOB_ROOT,
+        priority: PRIORITY_DEFAULT,
+    });
+    sched.jobs[JOB_ROOT] = Some(JobSlot::root());
+    sched.threads[IDLE_SLOT] = Some(ThreadSlot::idle(KThreadId(0), 0, now_ns()));
+    // Idle bağlamı ilk bağlam değişiminde doldurulur.
+    let boot = &mut sched.cpus[0];
+    boot.online = true;
+    boot.idle = IDLE_SLOT;
+    boot.current = IDLE_SLOT;
+    CPUS_ONLINE.fetch_or(1, Ordering::AcqRel);
+    println!("Karnal64: Görev Yöneticisi Başlatıldı");
+}
+
+/// Portun mimari katmanını kaydeder. Mimari başlatma kodu, ilk iş parçacığı oluşturulmadan önce çağırmalıdır.
+pub fn register_arch(arch: &'static dyn ArchTask) {
+    SCHEDULER.lock_irqsave().arch = Some(arch);
+}
+
+// --- SMP: CPU kaydı, ikincil CPU başlatma, IPI ---
+
+// Mantıksal CPU indeksinden donanım kimliğine (hart ID, LAPIC ID, MPIDR afinitesi) eşleme.
+// Kesme bağlamından ve low_level_cpu_id içinden kilitsiz okunur.
+const NO_CPU: AtomicU64 = AtomicU64::new(u64::MAX);
+static CPU_HW_IDS: [AtomicU64; MAX_CPUS] = [NO_CPU; MAX_CPUS];
+/// Kaydedilmiş (add_cpu) CPU sayısı. Boot CPU'su her zaman 0 indeksidir.
+static NR_CPUS: AtomicUsize = AtomicUsize::new(1);
+/// Çevrimiçi CPU'ların maskesi (bit `cpu`).
+static CPUS_ONLINE: AtomicU32 = AtomicU32::new(0);
+
+// CPU başına bekleyen IPI nedenleri (IPI_RESCHEDULE | IPI_TLB_SHOOTDOWN).
+const NO_IPI: AtomicU32 = AtomicU32::new(0);
+static IPI_PENDING: [AtomicU32; MAX_CPUS] = [NO_IPI; MAX_CPUS];
+
+// CPU başına en son etkinleştirilen kullanıcı adres alanı. Çekirdek görevine geçişte eski alan
+// etkin kalır (ArchTask::activate_address_space), bu yüzden TLB'de onun girdileri bulunabilir.
+const NO_SPACE: AtomicU64 = AtomicU64::new(KERNEL_ADDRESS_SPACE);
+static ACTIVE_SPACE: [AtomicU64; MAX_CPUS] = [NO_SPACE; MAX_CPUS];
+
+// Süren TLB temizleme isteği. İstekler SHOOTDOWN_LOCK ile sıralanır; hedef CPU'lar IPI
+// işleyicisinde parametreleri okur ve SHOOTDOWN_ACKS'i azaltır.
+static SHOOTDOWN_LOCK: Spinlock = Spinlock::named((), "ktask::SHOOTDOWN_LOCK");
+static SHOOTDOWN_ROOT: AtomicU64 = AtomicU64::new(0);
+static SHOOTDOWN_START: AtomicU64 = AtomicU64::new(0);
+static SHOOTDOWN_LEN: AtomicU64 = AtomicU64::new(0);
+static SHOOTDOWN_ACKS: AtomicU32 = AtomicU32::new(0);
+
+/// Boot CPU'sunun donanım kimliğini 0 indeksine kaydeder. CPU taraması (DTB) daha önce
+/// yapıldıysa ve boot CPU'su başka bir indekse eklendiyse, o indeks son kayıtla doldurulur.
+/// İkincil CPU'lar başlatılmadan önce çağrılmalıdır.
+pub fn register_boot_cpu(hw_id: u64) {
+    if let Some(cpu) = cpu_index(hw_id).filter(|&cpu| cpu != 0) {
+        let last = NR_CPUS.fetch_sub(1, Ordering::AcqRel) - 1;
+        let moved = CPU_HW_IDS[last].swap(u64::MAX, Ordering::AcqRel);
+        if cpu != last {
+            CPU_HW_IDS[cpu].store(moved, Ordering::Release);
+        }
+    }
+    CPU_HW_IDS[0].store(hw_id, Ordering::Release);
+}
+
+/// Donanım kimliği `hw_id` olan bir CPU'yu kaydeder (DTB/ACPI/firmware taraması) ve mantıksal
+/// indeksini döner. Aynı CPU ikinci kez eklenirse (örn. boot CPU'su) mevcut indeksi döner.
+/// MAX_CPUS dolduysa `KError::OutOfMemory`.
+pub fn add_cpu(hw_id: u64) -> Result<usize, KError> {
+    if let Some(cpu) = cpu_index(hw_id) {
+        return Ok(cpu);
+    }
+    let cpu = NR_CPUS.fetch_add(1, Ordering::AcqRel);
+    if cpu >= MAX_CPUS {
+        NR_CPUS.fetch_sub(1, Ordering::AcqRel);
+        return Err(KError::OutOfMemory);
+    }
+    CPU_HW_IDS[cpu].store(hw_id, Ordering::Release);
+    Ok(cpu)
+}
+
+/// Kaydedilmiş CPU sayısı (boot CPU'su dahil).
+pub fn possible_cpus() -> usize {
+    NR_CPUS.load(Ordering::Acquire).min(MAX_CPUS)
+}
+
+/// Çevrimiçi CPU sayısı.
+pub fn online_cpus() -> usize {
+    CPUS_ONLINE.load(Ordering::Acquire).count_ones() as usize
+}
+
+/// Donanım kimliği `hw_id` olan CPU'nun mantıksal indeksi. Portların low_level_cpu_id
+/// implementasyonları kullanır; kayıtsız bir kimlik için `None`.
+pub fn cpu_index(hw_id: u64) -> Option<usize> {
+    (0..possible_cpus()).find(|&cpu| CPU_HW_IDS[cpu].load(Ordering::Acquire) == hw_id)
+}
+
+/// Mantıksal `cpu`'nun donanım kimliği.
+pub fn cpu_hw_id(cpu: usize) -> Option<u64> {
+    match CPU_HW_IDS.get(cpu)?.load(Ordering::Acquire) {
+        u64::MAX => None,
+        id => Some(id),
+    }
+}
+
+// Mimari kodun bildirdiği topoloji girdileri. Boot CPU'su kaydedilirken mantıksal indeksler
+// yer değiştirebildiğinden (register_boot_cpu) girdiler donanım kimliğiyle tutulur.
+static CPU_TOPOLOGY: Spinlock<[Option<CpuTopology>; MAX_CPUS]> =
+    Spinlock::named([None; MAX_CPUS], "ktask::CPU_TOPOLOGY");
+
+/// Donanım kimliği `topology.hw_id` olan CPU'nun topolojisini kaydeder. DTB `cpu-map`/ACPI MADT
+/// taraması çağırır; CPU'nun `add_cpu` ile daha önce kaydedilmiş olması gerekmez. Kapasite 0
+/// ise CPU_CAPACITY_MAX sayılır. Farklı CPU sayısı MAX_CPUS'u aşarsa `KError::OutOfMemory`.
+pub fn set_cpu_topology(topology: CpuTopology) -> Result<(), KError> {
+    let entry = CpuTopology {
+        capacity: match topology.capacity {
+            0 => CPU_CAPACITY_MAX,
+            capacity => capacity.min(CPU_CAPACITY_MAX),
+        },
+        online: 0,
+        ..topology
+    };
+    let mut table = CPU_TOPOLOGY.lock();
+    let index = table
+        .iter()
+        .position(|t| matches!(t, Some(t) if t.hw_id == topology.hw_id))
+        .or_else(|| table.iter().position(|t| t.is_none()))
+        .ok_or(KError::OutOfMemory)?;
+    table[index] = Some(entry);
+    Ok(())
+}
+
+/// Mantıksal `cpu`'nun topolojisi. Bildirilmemişse tek paketteki ayrı bir çekirdek sayılır.
+/// Kayıtlı olmayan bir indeks için `KError::NotFound`.
+pub fn cpu_topology(cpu: usize) -> Result<CpuTopology, KError> {
+    let hw_id = cpu_hw_id(cpu).filter(|_| cpu < possible_cpus()).ok_or(KError::NotFound)?;
+    let reported = CPU_TOPOLOGY.lock().iter().flatten().find(|t| t.hw_id == hw_id).copied();
+    let topology = reported.unwrap_or(CpuTopology {
+        hw_id,
+        package: 0,
+        cluster: 0,
+        core: cpu as u32,
+        thread: 0,
+        capacity: CPU_CAPACITY_MAX,
+        online: 0,
+    });
+    let online = CPUS_ONLINE.load(Ordering::Acquire) & (1 << cpu) != 0;
+    Ok(CpuTopology { online: online as u32, ..topology })
+}
+
+/// Karnal64 API fn cpu_topology karşılığı: `cpu`'nun topolojisini kullanıcı `buffer`'ına yazar
+/// ve kayıtlı CPU sayısını döner (kullanıcı 0'dan başlayıp tüm CPU'ları gezebilir).
+pub fn cpu_topology_to_user(cpu: usize, buffer: *mut CpuTopology) -> Result<usize, KError> {
+    let topology = cpu_topology(cpu)?;
+    kmemory::copy_to_user(
+        buffer as *mut u8,
+        &topology as *const CpuTopology as *const u8,
+        core::mem::size_of::<CpuTopology>(),
+    )?;
+    Ok(possible_cpus())
+}
+
+/// İkincil `cpu`'yu başlatmaya hazırlar: ona bir idle iş parçacığı yuvası ayırır ve o yuvanın
+/// çekirdek yığınının tepesini döner. Mimari kod CPU'yu bu yığınla başlatır (SIPI, PSCI CPU_ON,
+/// SBI HSM hart_start) ve CPU orada kendi başlatmasını bitirip `cpu_online` çağırır.
+pub fn prepare_cpu(cpu: usize) -> Result<u64, KError> {
+    if cpu == 0 || cpu >= possible_cpus() {
+        return Err(KError::InvalidArgument);
+    }
+    let mut sched = SCHEDULER.lock_irqsave();
+    if sched.cpus[cpu].online {
+        return Err(KError::AlreadyExists);
+    }
+    let slot = sched.free_thread_slot().ok_or(KError::OutOfMemory)?;
+    sched.retire_thread_slot(slot);
+    let id = KThreadId(sched.next_thread_id);
+    sched.next_thread_id += 1;
+    sched.threads[slot] = Some(ThreadSlot::idle(id, cpu, now_ns()));
+    if let Some(kernel) = sched.tasks[KERNEL_TASK_SLOT].as_mut() {
+        kernel.live_threads += 1;
+    }
+    sched.cpus[cpu].idle = slot;
+    sched.cpus[cpu].current = slot;
+    Ok(kernel_stack_top(slot))
+}
+
+/// İkincil bir CPU, mimariye özel başlatmasını (tuzak vektörü, kesme denetleyicisi, zamanlayıcı)
+/// bitirince `prepare_cpu`'nun verdiği yığın üzerinden çağırır. Çağıran bağlam bu CPU'nun idle
+/// iş parçacığı olur; CPU iş almaya başlar ve fonksiyon geri dönmez.
+pub fn cpu_online() -> ! {
+    let cpu = current_cpu();
+    {
+        let mut sched = SCHEDULER.lock_irqsave();
+        let now = now_ns();
+        let c = &mut sched.cpus[cpu];
+        c.online = true;
+        c.last_balance_ns = now;
+    }
+    CPUS_ONLINE.fetch_or(1 << cpu, Ordering::AcqRel);
+    ksync::rcu::cpu_online(cpu);
+    println!("Karnal64: CPU {} çevrimiçi ({} / {})", cpu, online_cpus(), possible_cpus());
+    idle_loop()
+}
+
+/// Idle iş parçacığının döngüsü: hazır iş varsa ona geçer, yoksa bir kesme gelene kadar
+/// CPU'yu bekletir. İkincil CPU'lar `cpu_online` üzerinden girer; boot CPU'su başlatmayı
+/// bitirdikten sonra çağırır. Girerken CPU'nun iş kuyruğu çalışanını başlatır.
+pub fn idle_loop() -> ! {
+    workqueue::start_worker(current_cpu());
+    loop {
+        schedule();
+        reap_orphans();
+        let _irq = ksync::irq_save();
+        let cpu = current_cpu();
+        let (arch, idle) = {
+            let mut sched = SCHEDULER.lock_irqsave();
+            // Boşta beklerken uyandıracak bir zamanlayıcı olayı kurulu olmalıdır.
+            sched.rearm_timer(cpu);
+            (sched.arch, sched.cpus[cpu].ready.count == 0 && sched.cpus[cpu].dl_ready.len == 0)
+        };
+        if idle && !NEED_RESCHED[cpu].load(Ordering::Relaxed) {
+            if let Some(arch) = arch {
+                unsafe { arch.wait_for_interrupt() };
+            }
+        }
+        // _irq burada düşer: bekleyen kesme işlenir, gerekirse maybe_preempt geçişi yapar.
+    }
+}
+
+fn send_ipi(arch: &dyn ArchTask, cpu: usize, kind: u32) {
+    if let Some(hw_id) = cpu_hw_id(cpu) {
+        IPI_PENDING[cpu].fetch_or(kind, Ordering::AcqRel);
+        unsafe { arch.send_ipi(hw_id) };
+    }
+}
+
+/// Mimari IPI işleyicisinden (kesmeler kapalı) çağrılır: bu CPU'ya gönderilmiş istekleri yerine
+/// getirir. İşleyici ardından `preempt_on_interrupt_exit` çağırmalıdır.
+pub fn handle_ipi() {
+    let cpu = current_cpu();
+    let pending = IPI_PENDING[cpu].swap(0, Ordering::AcqRel);
+    if pending & IPI_TLB_SHOOTDOWN != 0 {
+        if let Some(arch) = SCHEDULER.lock_irqsave().arch {
+            unsafe {
+                arch.flush_tlb(
+                    SHOOTDOWN_ROOT.load(Ordering::Acquire),
+                    SHOOTDOWN_START.load(Ordering::Acquire),
+                    SHOOTDOWN_LEN.load(Ordering::Acquire),
+                );
+            }
+        }
+        SHOOTDOWN_ACKS.fetch_sub(1, Ordering::AcqRel);
+    }
+    if pending & IPI_RESCHEDULE != 0 {
+        NEED_RESCHED[cpu].store(true, Ordering::Relaxed);
+    }
+}
+
+/// `root` adres alanındaki [`start`, `start + len`) aralığının çevirilerini tüm CPU'ların
+/// TLB'lerinden siler ve hepsi bitirene kadar bekler. Eşleme kaldıran veya izin daraltan bellek
+/// kodu, sayfayı yeniden kullanmadan önce çağırmalıdır. Yalnızca o adres alanını etkinleştirmiş
+/// CPU'lara (çekirdek eşlemeleri için hepsine) IPI gönderilir.
+/// Diğer CPU'lar IPI'ye yanıt verebilsin diye kesmeler açıkken çağrılmalıdır.
+pub fn tlb_shootdown(root: u64, start: u64, len: u64) {
+    let arch = match SCHEDULER.lock_irqsave().arch {
+        Some(arch) => arch,
+        None => return,
+    };
+    let me = current_cpu();
+    unsafe { arch.flush_tlb(root, start, len) };
+    let others = CPUS_ONLINE.load(Ordering::Acquire) & !(1 << me);
+    if others == 0 {
+        return;
+    }
+    debug_assert!(unsafe { low_level_interrupts_enabled() } != 0);
+
+    let _serial = SHOOTDOWN_LOCK.lock();
+    SHOOTDOWN_ROOT.store(root, Ordering::Release);
+    SHOOTDOWN_START.store(start, Ordering::Release);
+    SHOOTDOWN_LEN.store(len, Ordering::Release);
+    let targets: u32 = (0..MAX_CPUS)
+        .filter(|&cpu| others & (1 << cpu) != 0)
+        .filter(|&cpu| root == KERNEL_ADDRESS_SPACE || ACTIVE_SPACE[cpu].load(Ordering::Acquire) == root)
+        .fold(0, |mask, cpu| mask | (1 << cpu));
+    SHOOTDOWN_ACKS.store(targets.count_ones(), Ordering::Release);
+    for cpu in (0..MAX_CPUS).filter(|&cpu| targets & (1 << cpu) != 0) {
+        send_ipi(arch, cpu, IPI_TLB_SHOOTDOWN);
+    }
+    while SHOOTDOWN_ACKS.load(Ordering::Acquire) != 0 {
+        core::hint::spin_loop();
+    }
+}
+
+/// Sayfa tablosu kökü `address_space` olan boş bir görev oluşturur. İş parçacıkları
+/// `create_thread` ile eklenir. Yeni görevin ebeveyni çağıran görevdir ve onun işine girer; adres
+/// alanı görevle birlikte toplanırken `ArchTask::destroy_address_space` ile yok edilir.
+pub fn create_task(address_space: u64) -> Result<KTaskId, KError> {
+    // Tablo dolmadan önce sahipsiz zombilerin yuvaları geri alınır.
+    reap_orphans();
+    let parent = current_task_id();
+    let mut sched = SCHEDULER.lock_irqsave();
+    let slot = sched.free_task_slot().ok_or(KError::OutOfMemory)?;
+    let creator = sched.task_slot_of(parent).and_then(|p| sched.tasks[p]);
+    let exception_port = creator.and_then(|p| p.exception_port);
+    let quota = creator.map(|p| QuotaState::inherit(&p.quota)).unwrap_or(QuotaState::unlimited());
+    let job = creator.map(|p| p.job).unwrap_or(JOB_ROOT);
+    let id = KTaskId(sched.next_task_id);
+    sched.next_task_id += 1;
+    sched.tasks[slot] = Some(TaskSlot {
+        id,
+        parent,
+        state: TaskState::Running,
+        address_space,
+        live_threads: 0,
+        exit_code: 0,
+        fault: None,
+        exception_port,
+        exited_stats: ThreadStats::default(),
+        quota,
+        job,
+        priority: PRIORITY_DEFAULT,
+    });
+    sched.job_task_added(job);
+    Ok(id)
+}
+
+/// `task` görevine yeni bir iş parçacığı ekler ve hazır kuyruğuna koyar.
+/// `user` ise iş parçacığı kullanıcı modunda `entry`'den `user_stack_top` yığınıyla ve `tls`
+/// TLS işaretçisiyle başlar; değilse `entry` bir `extern "C" fn(u64) -> !` çekirdek fonksiyonudur.
+pub fn create_thread(
+    task: KTaskId,
+    entry: u64,
+    arg: u64,
+    user_stack_top: u64,
+    tls: u64,
+    user: bool,
+) -> Result<KThreadId, KError> {
+    add_thread(task, ThreadStart { entry, arg, user_stack_top, kernel_stack_top: 0, tls, user }, None, None)
+}
+
+/// `create_thread` ve `thread_create` ortak yolu. `start.kernel_stack_top` yuvaya göre doldurulur;
+/// `user_stack`, iş parçacığı sonlanınca bırakılacak kullanıcı yığınıdır. `pinned` verilirse
+/// iş parçacığı ilk çalışmasından itibaren o CPU'ya bağlıdır ve o öncelikle başlar.
+fn add_thread(
+    task: KTaskId,
+    mut start: ThreadStart,
+    user_stack: Option<(u64, usize)>,
+    pinned: Option<(usize, Priority)>,
+) -> Result<KThreadId, KError> {
+    let mut sched = SCHEDULER.lock_irqsave();
+    let arch = sched.arch.ok_or(KError::NotSupported)?;
+    let task_slot = sched.task_slot_of(task).ok_or(KError::NotFound)?;
+    if matches!(sched.tasks[task_slot], Some(t) if t.state == TaskState::Exited) {
+        return Err(KError::InvalidArgument);
+    }
+    sched.quota_check_thread(task_slot)?;
+    let slot = sched.free_thread_slot().ok_or(KError::OutOfMemory)?;
+    sched.retire_thread_slot(slot);
+    let priority = match pinned {
+        Some((_, priority)) => priority,
+        None => sched.tasks[task_slot].map(|t| t.priority).unwrap_or(PRIORITY_DEFAULT),
+    };
+    // Afinite, oluşturan iş parçacığından miras alınır; idle'ın tek CPU'luk maskesi hariç.
+    let creator = sched.current();
+    let affinity = match (pinned, sched.threads[creator]) {
+        (Some((cpu, _)), _) => 1 << cpu,
+        (None, Some(t)) if !sched.is_idle(creator) => t.dl.map(|dl| dl.saved_affinity).unwrap_or(t.affinity),
+        _ => CPU_MASK_ALL,
+    };
+
+    start.kernel_stack_top = kernel_stack_top(slot);
+    unsafe {
+        core::ptr::write_bytes(context_ptr(slot), 0, ARCH_CONTEXT_SIZE);
+        arch.init_context(context_ptr(slot), &start);
+    }
+
+    let id = KThreadId(sched.next_thread_id);
+    sched.next_thread_id += 1;
+    sched.threads[slot] = Some(ThreadSlot {
+        id,
+        task,
+        task_slot,
+        state: TaskState::Ready,
+        wake_reason: WakeReason::Signaled,
+        deadline_ns: None,
+        timer_cpu: None,
+        base_priority: priority,
+        priority,
+        pi_boosts: [(0, PRIORITY_IDLE); PI_MAX_LOCKS],
+        pi_blocked_on: 0,
+        slice_end_ns: 0,
+        cpu: sched.least_loaded_cpu(affinity),
+        affinity,
+        on_cpu: false,
+        user_stack,
+        stats: ThreadStats::default(),
+        ready_since_ns: 0,
+        woken: false,
+        in_user: start.user,
+        mode_since_ns: 0,
+        dl: None,
+        suspended: false,
+    });
+    if let Some(t) = sched.tasks[task_slot].as_mut() {
+        t.live_threads += 1;
+    }
+    sched.make_ready(slot, WakeReason::Signaled);
+    if let Some(cpu) = sched.threads[slot].map(|t| t.cpu) {
+        sched.check_preempt(cpu);
+    }
+    Ok(id)
+}
+
+/// Çekirdek görevinde `entry(arg)`'ı çalıştıran bir çekirdek iş parçacığı oluşturur.
+pub fn spawn_kernel_thread(entry: extern "C" fn(u64) -> !, arg: u64) -> Result<KThreadId, KError> {
+    create_thread(KTaskId(0), entry as usize as u64, arg, 0, 0, false)
+}
+
+/// `cpu`'ya bağlı, `priority` öncelikli bir çekirdek iş parçacığı oluşturur (CPU başına çalışanlar
+/// gibi). Afinite sonradan `set_thread_affinity` ile değiştirilebilir.
+pub fn spawn_kernel_thread_on(
+    cpu: usize,
+    priority: Priority,
+    entry: extern "C" fn(u64) -> !,
+    arg: u64,
+) -> Result<KThreadId, KError> {
+    if cpu >= possible_cpus() || priority > PRIORITY_MAX {
+        return Err(KError::InvalidArgument);
+    }
+    let start = ThreadStart {
+        entry: entry as usize as u64,
+        arg,
+        user_stack_top: 0,
+        kernel_stack_top: 0,
+        tls: 0,
+        user: false,
+    };
+    add_thread(KTaskId(0), start, None, Some((cpu, priority)))
+}
+
+fn is_user_address(addr: u64) -> bool {
+    (kmemory::USER_SPACE_START as u64..kmemory::user_space_end() as u64).contains(&addr)
+}
+
+/// Karnal64 API fn thread_create karşılığı: çağıran göreve, onun adres alanını ve handle'larını
+/// paylaşan yeni bir kullanıcı iş parçacığı ekler. İş parçacığı `entry(arg)` ile, kendi yığını
+/// ve `tls` TLS işaretçisiyle başlar.
+/// `stack_base` 0 ise görevin adres alanında `stack_size` baytlık (0: DEFAULT_USER_STACK_SIZE)
+/// bir yığın ayrılır ve iş parçacığı `thread_exit` ile sonlanınca bırakılır; değilse
+/// [`stack_base`, `stack_base + stack_size`) çağıranın yönettiği yığındır.
+pub fn thread_create(entry: u64, stack_size: usize, arg: u64, tls: u64, stack_base: u64) -> Result<KThreadId, KError> {
+    let task = current_task_id();
+    // Çekirdek iş parçacıkları spawn_kernel_thread ile oluşturulur.
+    if task == KTaskId(0) {
+        return Err(KError::PermissionDenied);
+    }
+    if !is_user_address(entry) || (tls != 0 && !is_user_address(tls)) {
+        return Err(KError::BadAddress);
+    }
+    let size = if stack_size == 0 { DEFAULT_USER_STACK_SIZE } else { stack_size };
+    if size > MAX_USER_STACK_SIZE {
+        return Err(KError::InvalidArgument);
+    }
+    let (stack_top, owned) = if stack_base == 0 {
+        let base = kmemory::allocate_user_memory(size)? as u64;
+        (base + size as u64, Some((base, size)))
+    } else {
+        let end = stack_base.checked_add(size as u64).ok_or(KError::BadAddress)?;
+        if !is_user_address(stack_base) || end > kmemory::user_space_end() as u64 {
+            return Err(KError::BadAddress);
+        }
+        (end, None)
+    };
+    // Tüm portların ABI'leri giriş noktasında 16 bayt hizalı yığın ister.
+    let start = ThreadStart { entry, arg, user_stack_top: stack_top & !0xF, kernel_stack_top: 0, tls, user: true };
+    add_thread(task, start, owned, None).inspect_err(|e| {
+        if let Some((base, size)) = owned {
+            let _ = kmemory::free_user_memory(base as *mut u8, size);
+        }
+    })
+}
+
+/// Karnal64 API fn thread_exit karşılığı: yalnızca mevcut iş parçacığını sonlandırır. Geri dönmez.
+/// Görevin son iş parçacığıysa görev `code` çıkış koduyla sonlanır (bkz. `task_exit`).
+pub fn thread_exit(code: i32) -> ! {
+    let stack = {
+        let mut sched = SCHEDULER.lock_irqsave();
+        let current = sched.current();
+        sched.threads[current].as_mut().and_then(|t| t.user_stack.take())
+    };
+    // Bu noktada çekirdek yığınındayız; kullanıcı yığınına bir daha dönülmeyecek.
+    if let Some((base, size)) = stack {
+        let _ = kmemory::free_user_memory(base as *mut u8, size);
+    }
+    exit_current(code, None, false)
+}
+
+// Sonlanan görevler ebeveynlerini bu kuyruktan uyandırır. WAIT_LOCK, bekleyenin "çocuk
+// sonlanmadı" kontrolü ile kuyruğa girişi arasında gelen bir çıkışın kaybolmasını önler.
+static CHILD_EXIT: ksync::WaitQueue = ksync::WaitQueue::new();
+static WAIT_LOCK: Spinlock = Spinlock::named((), "ktask::WAIT_LOCK");
+
+/// Karnal64 API fn task_exit karşılığı: mevcut görevi tüm iş parçacıklarıyla sonlandırır. Geri dönmez.
+/// Bekleme kuyruklarındaki diğer iş parçacıkları Exited olduğundan bir daha uyandırılmaz.
+/// Görev, ebeveyni `task_wait` ile toplayana kadar zombi olarak kalır.
+pub fn task_exit(code: i32) -> ! {
+    exit_current(code, None, true)
+}
+
+/// Mevcut görevi bir hata (fault) nedeniyle sonlandırır; ebeveynin `task_wait`'i nedeni alır.
+/// Kullanıcı modundaki çözülemeyen istisnalar için mimari istisna işleyicileri çağırır. Geri dönmez.
+pub fn terminate_current_task(reason: KError) -> ! {
+    println!("Karnal64: Görev {} hata nedeniyle sonlandırılıyor: {:?}", current_task_id().0, reason);
+    exit_current(reason as i64 as i32, Some(reason), true)
+}
+
+/// Mevcut iş parçacığını (`whole_task` ise görevin tüm iş parçacıklarını) sonlandırır. Görev
+/// sonlandıysa çocukları çekirdek görevine devredilir ve ebeveyni uyandırılır.
+fn exit_current(code: i32, fault: Option<KError>, whole_task: bool) -> ! {
+    // WAIT_LOCK tutulduğu sürece preemption kapalıdır: Exited olan bu iş parçacığı ebeveyni
+    // uyandırmadan önce kesintiye uğrayıp bir daha seçilmemek üzere bırakılamaz.
+    let wait_guard = WAIT_LOCK.lock();
+    let exited = {
+        let mut sched = SCHEDULER.lock_irqsave();
+        let current = sched.current();
+        let exited = match sched.threads[current].map(|t| t.task_slot) {
+            Some(task_slot) if whole_task => sched.kill_task(task_slot, code, fault),
+            Some(task_slot) => {
+                sched.exit_thread(current, code);
+                match sched.tasks[task_slot].as_mut() {
+                    Some(task) if task.state == TaskState::Exited => {
+                        // Son iş parçacığı bir hata nedeniyle öldürüldüyse (EXCEPTION_KILL) görev de öyle sonlanır.
+                        if task.live_threads == 0 && fault.is_some() {
+                            task.fault = fault;
+                        }
+                        Some(task.id)
+                    }
+                    _ => None,
+                }
+            }
+            None => None,
+        };
+        if let Some(id) = exited {
+            sched.reparent_children(id);
+        }
+        exited
+    };
+    if let Some(id) = exited {
+        CHILD_EXIT.wake_all();
+        excport::handler_exited(id);
+    }
+    drop(wait_guard);
+    schedule();
+    // Sonlanmış bir iş parçacığı bir daha seçilmez.
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+/// Karnal64 API fn task_wait karşılığı: çağıran görevin `task` çocuğu (`KTaskId(0)`: herhangi bir
+/// çocuğu) sonlanana kadar bloklar, onu toplar ve çıkış durumunu döner.
+/// `TASK_WAIT_NOHANG` ile çocuk henüz sonlanmadıysa `KError::Busy` döner. Çağıranın böyle bir
+/// çocuğu yoksa (veya çocuğu başka bir bekleyen zaten topladıysa) `KError::NotFound` döner.
+pub fn task_wait(task: KTaskId, flags: u64) -> Result<ExitStatus, KError> {
+    if flags & !TASK_WAIT_FLAGS != 0 {
+        return Err(KError::InvalidArgument);
+    }
+    let me = current_task_id();
+    let mut guard = WAIT_LOCK.lock();
+    loop {
+        let mut sched = SCHEDULER.lock_irqsave();
+        let child = sched.find_child(me, task)?;
+        match child {
+            ChildState::Zombie(slot) => {
+                let arch = sched.arch;
+                let child = sched.reap(slot).ok_or(KError::InternalError)?;
+                drop(sched);
+                drop(guard);
+                release_task_resources(&child, arch);
+                return Ok(ExitStatus { task: child.id, code: child.exit_code, fault: child.fault });
+            }
+            // Son iş parçacığının bağlamı başka bir CPU'da henüz kaydediliyor; kısa bir bekleme yeter.
+            ChildState::Exiting => {
+                drop(sched);
+                drop(guard);
+                yield_now()?;
+                guard = WAIT_LOCK.lock();
+            }
+            ChildState::Running if flags & TASK_WAIT_NOHANG != 0 => return Err(KError::Busy),
+            ChildState::Running => {
+                drop(sched);
+                guard = CHILD_EXIT.wait(guard);
+            }
+        }
+    }
+}
+
+/// Toplanan bir görevin zamanlayıcı tablosu dışındaki kaynaklarını bırakır: sahip olduğu
+/// senkronizasyon, iş, paylaşımlı bellek ve kaynak handle'ları ve adres alanı (sayfa tabloları,
+/// kullanıcı yığınları dahil).
+fn release_task_resources(task: &TaskSlot, arch: Option<&'static dyn ArchTask>) {
+    ksync::release_task_objects(task.id);
+    job::release_task_jobs(task.id);
+    excport::release_task_faults(task.id);
+    kmemory::release_task_shm(task.id);
+    kresource::release_task_handles(task.id);
+    if task.address_space != KERNEL_ADDRESS_SPACE {
+        kmemory::destroy_address_space(task.address_space);
+        if let Some(arch) = arch {
+            unsafe { arch.destroy_address_space(task.address_space) };
+        }
+    }
+}
+
+/// Ebeveyni çekirdek görevi olan (sahipsiz) zombileri toplar. Bunları bekleyecek bir görev
+/// olmadığından idle döngüsü ve `create_task` çağırır.
+fn reap_orphans() {
+    loop {
+        let (child, arch) = {
+            let mut sched = SCHEDULER.lock_irqsave();
+            let orphan = (0..MAX_TASKS).filter(|&slot| slot != KERNEL_TASK_SLOT).find(|&slot| {
+                matches!(sched.tasks[slot], Some(t) if t.parent == KTaskId(0)) && sched.reapable(slot)
+            });
+            match orphan.and_then(|slot| sched.reap(slot)) {
+                Some(child) => (child, sched.arch),
+                None => return,
+            }
+        };
+        release_task_resources(&child, arch);
+    }
+}
+
+/// Şu anda çalışan iş parçacığının ID'si.
+pub fn current_thread_id() -> KThreadId {
+    let sched = SCHEDULER.lock_irqsave();
+    sched.threads[sched.current()].map(|t| t.id).unwrap_or(KThreadId(0))
+}
+
+/// Şu anda çalışan iş parçacığının ait olduğu görevin ID'si.
+pub fn current_task_id() -> KTaskId {
+    let sched = SCHEDULER.lock_irqsave();
+    sched.threads[sched.current()].map(|t| t.task).unwrap_or(KTaskId(0))
+}
+
+/// Karnal64 API fn get_task_id karşılığı.
+pub fn get_current_task_id() -> Result<KTaskId, KError> {
+    Ok(current_task_id())
+}
+
+/// Mevcut görevin sayfa tablosu kökü (kullanıcı adreslerini çeviren mimari kod için).
+pub fn current_address_space() -> u64 {
+    let sched = SCHEDULER.lock_irqsave();
+    sched.threads[sched.current()]
+        .and_then(|t| sched.tasks[t.task_slot])
+        .map(|t| t.address_space)
+        .unwrap_or(KERNEL_ADDRESS_SPACE)
+}
+
+/// `id` iş parçacığının geçerli (miras dahil) önceliği.
+pub fn thread_priority(id: KThreadId) -> Option<Priority> {
+    let sched = SCHEDULER.lock_irqsave();
+    sched.slot_of(id).and_then(|slot| sched.threads[slot]).map(|t| t.priority)
+}
+
+/// Öncelik mirası: `id`'nin `lock` anahtarlı kilitten miras aldığı önceliği en az `priority`
+/// yapar ve geçerli önceliği yeniden hesaplar. `id`'nin beklediği kilidin anahtarını döner
+/// (çağıran mirası o kilidin sahibine iletir). Girdiler doluysa en düşük miras, daha yüksek
+/// olan yenisiyle değiştirilir; kaybolan miras kilidin bir sonraki bekleyeniyle geri gelir.
+pub fn boost_priority(id: KThreadId, lock: usize, priority: Priority) -> Option<usize> {
+    let mut sched = SCHEDULER.lock_irqsave();
+    let slot = sched.slot_of(id)?;
+    let thread = sched.threads[slot].as_mut()?;
+    let boosts = &mut thread.pi_boosts;
+    let index = match boosts.iter().position(|(l, _)| *l == lock) {
+        Some(index) => index,
+        None => match boosts.iter().position(|(l, _)| *l == 0) {
+            Some(index) => index,
+            None => (0..PI_MAX_LOCKS).min_by_key(|&i| boosts[i].1).unwrap_or(0),
+        },
+    };
+    if boosts[index].0 != lock {
+        if boosts[index].1 >= priority {
+            return None;
+        }
+        boosts[index] = (lock, PRIORITY_IDLE);
+    }
+    boosts[index].1 = boosts[index].1.max(priority);
+    let (effective, cpu, blocked_on) = (thread.inherited_priority(), thread.cpu, thread.pi_blocked_on);
+    sched.set_effective_priority(slot, effective);
+    sched.check_preempt(cpu);
+    Some(blocked_on).filter(|&l| l != 0)
+}
+
+/// `lock` anahtarlı kilitten miras alınan önceliği bırakır: geçerli öncelik, taban öncelik ile
+/// hâlâ tutulan kilitlerden gelen mirasın en yükseğine döner.
+pub fn restore_priority(id: KThreadId, lock: usize) {
+    let mut sched = SCHEDULER.lock_irqsave();
+    if let Some(slot) = sched.slot_of(id) {
+        if let Some(thread) = sched.threads[slot].as_mut() {
+            for entry in thread.pi_boosts.iter_mut().filter(|(l, _)| *l == lock) {
+                *entry = (0, PRIORITY_IDLE);
+            }
+            let (effective, cpu) = (thread.inherited_priority(), thread.cpu);
+            sched.set_effective_priority(slot, effective);
+            // Çalışan iş parçacığı hazır bekleyen birinin altına düştüyse yer değiştirilir.
+            sched.check_preempt(cpu);
+        }
+    }
+}
+
+/// Mevcut iş parçacığının öncelik miras bırakarak beklediği kilidi kaydeder (`0`: beklemiyor).
+pub fn set_blocked_on_lock(lock: usize) {
+    let mut sched = SCHEDULER.lock_irqsave();
+    let slot = sched.current();
+    if let Some(thread) = sched.threads[slot].as_mut() {
+        thread.pi_blocked_on = lock;
+    }
+}
+
+/// Karnal64 API fn task_set_priority karşılığı. `task` görevinin tüm (ve sonradan oluşturacağı)
+/// iş parçacıklarının taban önceliğini `priority` yapar; `task` 0 ise çağıran görev kastedilir.
+/// Kullanıcı görevleri yalnızca 1..=PRIORITY_USER_MAX aralığını kullanabilir (0 idle'a,
+/// üstü çekirdek iş parçacıklarına ayrılmıştır). Miras alınmış daha yüksek bir öncelik korunur.
+/// Yalnızca görevin kendisi veya ebeveyni değiştirebilir.
+pub fn set_task_priority(task: KTaskId, priority: Priority) -> Result<(), KError> {
+    if priority == PRIORITY_IDLE || priority > PRIORITY_USER_MAX {
+        return Err(KError::InvalidArgument);
+    }
+    let mut sched = SCHEDULER.lock_irqsave();
+    let own = sched.threads[sched.current()].map(|t| t.task).ok_or(KError::InternalError)?;
+    let task = if task.0 == 0 { own } else { task };
+    let task_slot = sched.task_slot_of(task).ok_or(KError::NotFound)?;
+    let t = sched.tasks[task_slot].as_mut().ok_or(KError::NotFound)?;
+    if task != own && t.parent != own {
+        return Err(KError::PermissionDenied);
+    }
+    t.priority = priority;
+    for slot in 0..MAX_THREADS {
+        let effective = match sched.threads[slot].as_mut() {
+            Some(t) if t.task_slot == task_slot && t.state != TaskState::Exited => {
+                t.base_priority = priority;
+                t.inherited_priority()
+            }
+            _ => continue,
+        };
+        sched.set_effective_priority(slot, effective);
+    }
+    for cpu in (0..MAX_CPUS).filter(|&cpu| sched.cpus[cpu].online) {
+        sched.check_preempt(cpu);
+    }
+    Ok(())
+}
+
+/// Karnal64 API fn thread_set_affinity karşılığı: çağıranın görevindeki `thread` iş parçacığının
+/// (`KThreadId(0)`: çağıran iş parçacığı) çalışabileceği CPU'ları `mask` yapar (bit `cpu`:
+/// mantıksal CPU indeksi, bkz. `cpu_topology`). Maske çevrimiçi CPU'larla kesiştirilir; kesişim
+/// boşsa `KError::InvalidArgument`. Maskenin dışındaki bir CPU'da bekleyen iş parçacığı hemen,
+/// orada çalışan iş parçacığı yeniden zamanlandığında taşınır. Maske, iş parçacığının sonradan
+/// oluşturduğu iş parçacıklarına ve görevlere miras kalır. Deadline sınıfındaki iş parçacıkları
+/// kabul edildikleri CPU'ya bağlıdır; afiniteleri değiştirilemez (`KError::Busy`).
+pub fn set_thread_affinity(thread: KThreadId, mask: u64) -> Result<(), KError> {
+    let mask = mask & CPUS_ONLINE.load(Ordering::Acquire) as u64;
+    if mask == 0 {
+        return Err(KError::InvalidArgument);
+    }
+    let mut sched = SCHEDULER.lock_irqsave();
+    let slot = sched.own_thread_slot(thread)?;
+    let (state, cpu, on_cpu) = match sched.threads[slot].as_mut() {
+        Some(t) if t.dl.is_some() => return Err(KError::Busy),
+        Some(t) => {
+            t.affinity = mask;
+            (t.state, t.cpu, t.on_cpu)
+        }
+        None => return Err(KError::NotFound),
+    };
+    if mask & (1 << cpu) != 0 {
+        return Ok(());
+    }
+    match state {
+        TaskState::Ready if !on_cpu && sched.dequeue(slot, cpu) => sched.migrate(slot),
+        TaskState::Running => sched.resched_cpu(cpu),
+        // Bloklanmış iş parçacığına uyanınca izinli bir CPU seçilir (select_wake_cpu); bağlamı
+        // kaydedilmekte olanı finish_switch taşır.
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Karnal64 API fn thread_get_affinity karşılığı: çağıranın görevindeki `thread` iş parçacığının
+/// (`KThreadId(0)`: çağıran iş parçacığı) afinite maskesi.
+pub fn thread_affinity(thread: KThreadId) -> Result<u64, KError> {
+    let sched = SCHEDULER.lock_irqsave();
+    let slot = sched.own_thread_slot(thread)?;
+    sched.threads[slot].map(|t| t.affinity).ok_or(KError::NotFound)
+}
+
+// --- CPU süresi muhasebesi ---
+
+/// Mevcut iş parçacığının o ana kadarki süresini mevcut moda yazar ve modunu değiştirir.
+fn account_mode(in_user: bool) {
+    let now = now_ns();
+    let mut sched = SCHEDULER.lock_irqsave();
+    let current = sched.current();
+    if let Some(thread) = sched.threads[current].as_mut() {
+        thread.charge(now);
+        thread.in_user = in_user;
+    }
+}
+
+/// Sistem çağrısı girişinde çağrılır: bundan sonraki süre çekirdek süresidir.
+pub fn account_kernel_entry() {
+    account_mode(false);
+}
+
+/// Sistem çağrısından kullanıcı moduna dönmeden hemen önce çağrılır.
+pub fn account_user_return() {
+    account_mode(true);
+}
+
+/// Karnal64 API fn thread_stats karşılığı: çağıranın görevindeki `thread` iş parçacığının
+/// (`KThreadId(0)`: çağıran iş parçacığı) sayaçları.
+pub fn thread_stats(thread: KThreadId) -> Result<ThreadStats, KError> {
+    let now = now_ns();
+    let sched = SCHEDULER.lock_irqsave();
+    let slot = sched.own_thread_slot(thread)?;
+    sched.thread_stats(slot, now).ok_or(KError::NotFound)
+}
+
+/// Karnal64 API fn task_stats karşılığı: `task` görevinin (`KTaskId(0)`: çağıran görev) sonlanmış
+/// ve yaşayan tüm iş parçacıklarının toplam sayaçları. Yalnızca görevin kendisi veya ebeveyni
+/// sorgulayabilir; zombi çocuklar toplanana kadar sorgulanabilir.
+pub fn task_stats(task: KTaskId) -> Result<ThreadStats, KError> {
+    let now = now_ns();
+    let sched = SCHEDULER.lock_irqsave();
+    let own = sched.threads[sched.current()].map(|t| t.task).ok_or(KError::InternalError)?;
+    let task = if task.0 == 0 { own } else { task };
+    let task_slot = sched.task_slot_of(task).ok_or(KError::NotFound)?;
+    let slot = sched.tasks[task_slot].ok_or(KError::NotFound)?;
+    if task != own && slot.parent != own {
+        return Err(KError::PermissionDenied);
+    }
+    sched.task_total_stats(task_slot, now).ok_or(KError::NotFound)
+}
+
+fn stats_to_user(stats: &ThreadStats, buffer: *mut ThreadStats) -> Result<(), KError> {
+    kmemory::copy_to_user(
+        buffer as *mut u8,
+        stats as *const ThreadStats as *const u8,
+        core::mem::size_of::<ThreadStats>(),
+    )
+}
+
+/// SYSCALL_THREAD_STATS: `thread_stats` sonucunu kullanıcı `buffer`'ına yazar.
+pub fn thread_stats_to_user(thread: KThreadId, buffer: *mut ThreadStats) -> Result<(), KError> {
+    stats_to_user(&thread_stats(thread)?, buffer)
+}
+
+/// SYSCALL_TASK_STATS: `task_stats` sonucunu kullanıcı `buffer`'ına yazar.
+pub fn task_stats_to_user(task: KTaskId, buffer: *mut ThreadStats) -> Result<(), KError> {
+    stats_to_user(&task_stats(task)?, buffer)
+}
+
+/// Çağıran işlemcinin indeksi.
+pub fn current_cpu() -> usize {
+    unsafe { low_level_cpu_id() as usize }
+}
+
+/// Monoton saat (nanosaniye).
+pub fn now_ns() -> u64 {
+    unsafe { low_level_monotonic_time_ns() }
+}
+
+// --- Kesintiye uğratma (preemption) ---
+
+// CPU başına iç içe spinlock/preempt_disable sayısı. Sıfırdan büyükken o CPU'da bağlam değiştirilmez.
+const PREEMPT_ZERO: AtomicU32 = AtomicU32::new(0);
+static PREEMPT_COUNT: [AtomicU32; MAX_CPUS] = [PREEMPT_ZERO; MAX_CPUS];
+
+// CPU başına "ilk güvenli noktada yeniden zamanla" bayrağı. timer_tick/wake kurar, schedule temizler.
+const RESCHED_CLEAR: AtomicBool = AtomicBool::new(false);
+static NEED_RESCHED: [AtomicBool; MAX_CPUS] = [RESCHED_CLEAR; MAX_CPUS];
+
+fn set_need_resched() {
+    NEED_RESCHED[current_cpu()].store(true, Ordering::Relaxed);
+}
+
+/// Mevcut CPU'da kesintiye uğratmayı kapatır. İç içe çağrılabilir; ksync::Spinlock her kilitte çağırır.
+pub fn preempt_disable() {
+    PREEMPT_COUNT[current_cpu()].fetch_add(1, Ordering::Relaxed);
+}
+
+/// `preempt_disable`'ı geri alır. Sayaç sıfıra inerse bekleyen bir yeniden zamanlama hemen yapılır.
+pub fn preempt_enable() {
+    if PREEMPT_COUNT[current_cpu()].fetch_sub(1, Ordering::Relaxed) == 1 {
+        maybe_preempt();
+    }
+}
+
+/// Kesintiye uğratmanın güvenli olup olmadığı (kesme durumu hariç).
+fn preemptible(cpu: usize) -> bool {
+    NEED_RESCHED[cpu].load(Ordering::Relaxed)
+        && PREEMPT_COUNT[cpu].load(Ordering::Relaxed) == 0
+        && !ksync::rcu::in_read_section()
+}
+
+/// Yeniden zamanlama bekliyorsa ve güvenliyse (kesmeler açık, spinlock tutulmuyor, RCU okuma
+/// bölümünde değil) mevcut iş parçacığını kesintiye uğratır. Kesmeleri tekrar açan
+/// ksync::IrqGuard ve son spinlock'u bırakan `preempt_enable` tarafından çağrılır.
+pub fn maybe_preempt() {
+    let cpu = current_cpu();
+    if preemptible(cpu) && unsafe { low_level_interrupts_enabled() } != 0 {
+        preempt_schedule(cpu);
+    }
+}
+
+/// Mimari kesme yolunun, işleyiciden kesilen bağlama dönmeden hemen önce çağırdığı kanca.
+/// Kesilen bağlam bir spinlock tutmuyorsa ve yeniden zamanlama istendiyse başka bir iş
+/// parçacığına geçilir; kesilen iş parçacığı tekrar seçildiğinde buradan geri dönülür.
+/// Kesmeler kapalıyken çağrılmalıdır.
+pub fn preempt_on_interrupt_exit() {
+    let cpu = current_cpu();
+    if preemptible(cpu) {
+        preempt_schedule(cpu);
+    }
+}
+
+fn preempt_schedule(cpu: usize) {
+    // Zamanlayıcı kilidi bırakılırken preempt_enable/IrqGuard buraya tekrar girmesin.
+    PREEMPT_COUNT[cpu].fetch_add(1, Ordering::Relaxed);
+    let running = {
+        let sched = SCHEDULER.lock_irqsave();
+        matches!(
+            sched.threads[sched.current()],
+            Some(t) if t.state == TaskState::Running || t.state == TaskState::Exited
+        )
+    };
+    PREEMPT_COUNT[cpu].fetch_sub(1, Ordering::Relaxed);
+    // Bloklanmaya hazırlanan (prepare_to_block) bir iş parçacığı kendisi schedule çağıracaktır.
+    // Başka bir CPU'dan sonlandırılan (task_exit) iş parçacığı burada bırakılır.
+    if running {
+        switch(true);
+    }
+}
+
+/// Mevcut iş parçacığını bloklanmaya hazırlar: durumunu `state` (Blocked veya Sleeping) yapar
+/// ve isteğe bağlı bir uyanma zamanı kaydeder. Bağlam değiştirmez; çağıran, korunan kilidi
+/// bıraktıktan sonra `schedule()` çağırmalıdır. Arada gelen bir `wake` kaybolmaz: iş parçacığı
+/// Ready olarak işaretlenir ve `schedule()` onu hemen tekrar seçebilir.
+pub fn prepare_to_block(state: TaskState, deadline_ns: Option<u64>) {
+    debug_assert!(state == TaskState::Blocked || state == TaskState::Sleeping);
+    let mut sched = SCHEDULER.lock_irqsave();
+    let current = sched.current();
+    if let Some(thread) = sched.threads[current].as_mut() {
+        thread.state = state;
+    }
+    sched.set_deadline(current, deadline_ns);
+}
+
+/// `prepare_to_block` çağrısını geri alır: mevcut iş parçacığı hâlâ Blocked/Sleeping ise tekrar
+/// Running yapılır ve `true` döner. Arada bir `wake` gelmişse (iş parçacığı zaten Ready ve hazır
+/// kuyruğunda) `false` döner; bu durumda çağıran yine `schedule()` çağırmalıdır.
+/// Birden fazla kuyrukta bekleyen (ksync::wait_any) iş parçacıkları, kayıt sonrası
+/// tekrar kontrolde hazır bir nesne bulduklarında kullanır.
+pub fn cancel_block() -> bool {
+    let mut sched = SCHEDULER.lock_irqsave();
+    let current = sched.current();
+    match sched.threads[current].as_mut() {
+        Some(thread) if thread.state == TaskState::Blocked || thread.state == TaskState::Sleeping => {
+            thread.state = TaskState::Running;
+        }
+        _ => return false,
+    }
+    sched.set_deadline(current, None);
+    true
+}
+
+/// Bloklanmış veya uyuyan `id` iş parçacığını uyandırır.
+/// İş parçacığı gerçekten bekliyorduysa `true` döner; zaten uyanmışsa (örneğin süresi dolduysa) `false`.
+pub fn wake(id: KThreadId, reason: WakeReason) -> bool {
+    let mut sched = SCHEDULER.lock_irqsave();
+    let slot = match sched.slot_of(id) {
+        Some(slot) => slot,
+        None => return false,
+    };
+    match sched.threads[slot].map(|t| t.state) {
+        Some(TaskState::Blocked) | Some(TaskState::Sleeping) => {
+            sched.wake_slot(slot, reason);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Mevcut iş parçacığının son uyandırılma nedeni (WaitQueue zaman aşımı tespiti için).
+pub fn current_wake_reason() -> WakeReason {
+    let sched = SCHEDULER.lock_irqsave();
+    sched.threads[sched.current()].map(|t| t.wake_reason).unwrap_or(WakeReason::Signaled)
+}
+
+/// Bir sonraki hazır iş parçacığına geçer: en yüksek öncelikli dolu seviyenin başındaki.
+/// Mevcut iş parçacığı Running ise kendi seviyesinin sonuna eklenir (yield/kesintiye uğrama).
+/// Blocked/Sleeping ise, `wake` ile tekrar hazır kuyruğuna konana kadar seçilmez.
+pub fn schedule() {
+    switch(false);
+}
+
+/// `schedule` ve kesintiye uğratma ortak yolu. `preempted`: mevcut iş parçacığı CPU'yu kendisi
+/// bırakmıyor (zorunlu bağlam değişimi olarak sayılır).
+fn switch(preempted: bool) {
+    // schedule okuma bölümü dışında çağrılır; bu CPU için bir RCU sessiz durumudur.
+    ksync::rcu::quiescent_state();
+    // Kesmeler bağlam değişimi boyunca kapalı kalır. Geri dönüldüğünde (bu iş parçacığı tekrar
+    // seçilince) guard bu iş parçacığının kaydettiği durumu geri yükler.
+    // Yeni iş parçacıklarının bağlamı (ArchTask::init_context) kesmeler açık olarak başlar.
+    let _irq = ksync::irq_save();
+    let (arch, prev, next, space) = {
+        let mut sched = SCHEDULER.lock_irqsave();
+        // Mimari katmanı kaydedilmeden (erken boot) geçiş yapılamaz.
+        let arch = match sched.arch {
+            Some(arch) => arch,
+            None => return,
+        };
+        let cpu = current_cpu();
+        sched.finish_switch(cpu);
+        let prev = sched.cpus[cpu].current;
+        NEED_RESCHED[cpu].store(false, Ordering::Relaxed);
+        let now = now_ns();
+        // Deadline iş parçacığının bu çalışmada kullandığı süre bütçesinden düşülür; bütçesi
+        // bittiyse make_ready onu kuyruğa koymaz, yenileme zamanlayıcısını kurar.
+        sched.dl_update_curr(cpu, now);
+        sched.quota_charge_cpu(cpu, now);
+
+        if let Some(TaskState::Running) = sched.threads[prev].map(|t| t.state) {
+            if sched.allowed(prev, cpu) {
+                sched.make_ready(prev, WakeReason::Signaled);
+            } else if let Some(thread) = sched.threads[prev].as_mut() {
+                // Afinitesi bu CPU'yu artık içermiyor: bağlamı kaydedilince finish_switch taşır.
+                thread.state = TaskState::Ready;
+            }
+        }
+
+        // Kuyruklar boşaldıysa başka bir CPU'dan iş çek.
+        if sched.cpus[cpu].ready.count == 0 && sched.cpus[cpu].dl_ready.len == 0 {
+            sched.balance(cpu, 1);
+        }
+
+        // Önce son tarihi en erken hazır deadline iş parçacığı; yoksa hazır kuyruğundan sıradaki
+        // iş parçacığı, o da yoksa idle.
+        let next = match sched.dl_pick(cpu) {
+            Some(slot) => slot,
+            None => loop {
+                match sched.cpus[cpu].ready.pop() {
+                    // Kuyruktayken sonlanmış (Exited) iş parçacıkları atlanır.
+                    Some(slot) if matches!(sched.threads[slot], Some(t) if t.state == TaskState::Ready) => {
+                        // Kuyruktayken görevi CPU payını tüketenler pencere sonuna kadar bekletilir.
+                        if let Some(until) = sched.quota_throttled(slot, now) {
+                            sched.park(slot, until);
+                            continue;
+                        }
+                        if sched.allowed(slot, cpu) {
+                            break slot;
+                        }
+                        // Kuyruktayken afinitesi değişmiş iş parçacıkları izinli bir CPU'ya taşınır.
+                        sched.migrate(slot);
+                    }
+                    Some(_) => continue,
+                    None => break sched.cpus[cpu].idle,
+                }
+            },
+        };
+
+        sched.account_switch(prev, next, now, preempted);
+        sched.dl_start(next, now);
+        let slice_end = now.saturating_add(TIME_SLICE_NS);
+        if let Some(thread) = sched.threads[next].as_mut() {
+            thread.state = TaskState::Running;
+            thread.slice_end_ns = slice_end;
+            thread.cpu = cpu;
+            thread.on_cpu = true;
+        }
+        if next == prev {
+            sched.rearm_timer(cpu);
+            return;
+        }
+        sched.cpus[cpu].current = next;
+        sched.cpus[cpu].switched_out = Some(prev);
+        sched.rearm_timer(cpu);
+
+        // Farklı bir göreve geçiliyorsa onun adres alanı etkinleştirilir.
+        let prev_task = sched.threads[prev].map(|t| t.task_slot);
+        let next_task = sched.threads[next].map(|t| t.task_slot);
+        let space = match next_task {
+            Some(task) if next_task != prev_task => sched.tasks[task].map(|t| t.address_space),
+            _ => None,
+        };
+        (arch, prev, next, space)
+        // Zamanlayıcı kilidi burada bırakılır; kesmeler hâlâ kapalıdır (_irq).
+    };
+
+    ksync::lockdep::context_switch(prev, next);
+    unsafe {
+        if let Some(root) = space {
+            if root != KERNEL_ADDRESS_SPACE {
+                ACTIVE_SPACE[current_cpu()].store(root, Ordering::Release);
+            }
+            arch.activate_address_space(root);
+        }
+        arch.switch_context(context_ptr(prev), context_ptr(next));
+    }
+
+    // Bu iş parçacığı tekrar seçildi (belki başka bir CPU'da): o CPU'nun bıraktığı iş parçacığının
+    // bağlamı artık kaydedilmiştir.
+    SCHEDULER.lock_irqsave().finish_switch(current_cpu());
+}
+
+/// Karnal64 API fn task_yield karşılığı. Deadline sınıfındaki iş parçacığı bu periyodun kalan
+/// bütçesini bırakır ve bir sonraki periyoda kadar çalışmaz.
+pub fn yield_now() -> Result<(), KError> {
+    {
+        let mut sched = SCHEDULER.lock_irqsave();
+        let current = sched.current();
+        sched.dl_yield(current);
+    }
+    schedule();
+    Ok(())
+}
+
+/// Mevcut bağlam bloklanabilir mi: mimari katmanı kaydedilmiş ve çağıran bir idle iş parçacığı
+/// değil. Değilse (erken boot, idle döngüsü) bekleme meşgul bekleme ile yapılmalıdır.
+pub fn can_block() -> bool {
+    let sched = SCHEDULER.lock_irqsave();
+    sched.arch.is_some() && !sched.is_idle(sched.current())
+}
+
+/// Karnal64 API fn task_sleep karşılığı. Uyku, ksync bekleme kuyrukları üzerine kuruludur.
+pub fn task_sleep(duration_ms: u64) -> Result<(), KError> {
+    super::ksync::sleep_for(duration_ms)
+}
+
+/// Zamanlayıcı kesmesinden çağrılır: süresi dolmuş bekleyenleri TimedOut nedeniyle uyandırır ve
+/// daha yüksek öncelikli biri hazırsa ya da zaman dilimi bitmiş ve aynı seviyede sırada bekleyen
+/// varsa CPU'yu yeniden zamanlama için işaretler. Geçişi mimari kesme çıkışı yapar
+/// (`preempt_on_interrupt_exit`). Son olarak CPU'nun tek seferlik zamanlayıcısını bir sonraki
+/// olaya kurar.
+pub fn timer_tick(now_ns: u64) {
+    let cpu = current_cpu();
+    let mut sched = SCHEDULER.lock_irqsave();
+    // Yeni başlatılan bir iş parçacığına geçildiyse (schedule'a geri dönülmedi) bırakılanı serbest bırak.
+    sched.finish_switch(cpu);
+    sched.expire_timers(now_ns);
+    sched.dl_update_curr(cpu, now_ns);
+    sched.quota_charge_cpu(cpu, now_ns);
+
+    // Periyodik yük dengeleme: bu CPU en yüklü CPU'dan en az iki iş parçacığı gerideyse bir tane çek.
+    if now_ns.saturating_sub(sched.cpus[cpu].last_balance_ns) >= BALANCE_INTERVAL_NS {
+        sched.cpus[cpu].last_balance_ns = now_ns;
+        sched.balance(cpu, 2);
+    }
+
+    let current = sched.cpus[cpu].current;
+    let slice_expired = matches!(sched.threads[current], Some(t) if t.slice_end_ns <= now_ns);
+    if sched.should_preempt(cpu, slice_expired) {
+        set_need_resched();
+    }
+    if slice_expired {
+        // Dilim yenilenir: aynı seviyede bekleyen yoksa iş parçacığı çalışmaya devam eder; varsa ve
+        // geçiş gecikirse (kesilen bağlam spinlock tutuyor) zamanlayıcı geçmiş bir ana kurulmaz.
+        if let Some(thread) = sched.threads[current].as_mut() {
+            thread.slice_end_ns = now_ns.saturating_add(TIME_SLICE_NS);
+        }
+    }
+    // Kesme geldiyse kurulu olay tüketilmiştir; aynı an tekrar seçilse de zamanlayıcı yeniden kurulur.
+    sched.cpus[cpu].timer_armed_ns = u64::MAX;
+    sched.rearm_timer(cpu);
+
+    let idle = current == sched.cpus[cpu].idle;
+    drop(sched);
+
+    // Idle iş parçacığı okuma bölümünde olamaz; bağlam değiştirmeyen boş bir CPU grace period'u bekletmez.
+    if idle {
+        ksync::rcu::quiescent_state();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_queue_picks_highest_priority_first() {
+        let mut queue = RunQueue::new();
+        assert_eq!(queue.pop(), None);
+        queue.push(5, PRIORITY_DEFAULT);
+        queue.push(6, PRIORITY_DEFAULT);
+        queue.push(7, PRIORITY_USER_MAX);
+        queue.push(8, PRIORITY_IDLE);
+        assert_eq!(queue.count, 4);
+        assert_eq!(queue.highest(), Some(PRIORITY_USER_MAX));
+
+        // Seviyeler arası öncelik sırası, seviye içinde FIFO.
+        assert_eq!(queue.pop(), Some(7));
+        assert_eq!(queue.highest(), Some(PRIORITY_DEFAULT));
+        assert_eq!(queue.pop(), Some(5));
+        assert_eq!(queue.pop(), Some(6));
+        assert_eq!(queue.pop(), Some(8));
+        assert_eq!(queue.highest(), None);
+        assert_eq!(queue.count, 0);
+    }
+
+    #[test]
+    fn test_run_queue_remove() {
+        let mut queue = RunQueue::new();
+        for slot in 1..=3 {
+            queue.push(slot, PRIORITY_DEFAULT);
+        }
+        queue.push(4, PRIORITY_MAX);
+        assert!(queue.remove(2, PRIORITY_DEFAULT));
+        assert!(!queue.remove(2, PRIORITY_DEFAULT));
+        // Yanlış seviyede aranan iş parçacığı bulunmaz.
+        assert!(!queue.remove(1, PRIORITY_MAX));
+        assert!(queue.remove(4, PRIORITY_MAX));
+        assert_eq!(queue.count, 2);
+        assert_eq!(queue.highest(), Some(PRIORITY_DEFAULT));
+        assert_eq!(queue.pop(), Some(1));
+        assert_eq!(queue.pop(), Some(3));
+        assert_eq!(queue.highest(), None);
+    }
+
+    #[test]
+    fn test_ready_queue_wraps_around() {
+        let mut queue = ReadyQueue::new();
+        // Dairesel tamponun sonundan başına taşan ekleme ve çıkarmalar sırayı bozmaz.
+        for round in 0..3 * MAX_THREADS {
+            queue.push(round % MAX_THREADS);
+            queue.push((round + 1) % MAX_THREADS);
+            assert_eq!(queue.pop(), Some(round % MAX_THREADS));
+            assert!(queue.remove((round + 1) % MAX_THREADS));
+        }
+        assert_eq!(queue.len, 0);
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn test_exit_status_encoding() {
+        let exited = ExitStatus { task: KTaskId(5), code: -1, fault: None };
+        assert_eq!(exited.encode(), (5 << 33) | 0xFFFF_FFFF);
+
+        let faulted = ExitStatus { task: KTaskId(7), code: KError::BadAddress as i32, fault: Some(KError::BadAddress) };
+        let value = faulted.encode();
+        assert_eq!(value >> 33, 7);
+        assert_eq!(value & (1 << 32), 1 << 32);
+        assert_eq!(value as u32 as i32 as i64, KError::BadAddress as i64);
+
+        // Büyük görev ID'leri kesilir; sonuç her zaman pozitif bir i64'tür.
+        let large = ExitStatus { task: KTaskId(u64::MAX), code: i32::MIN, fault: Some(KError::InternalError) };
+        assert!((large.encode() as i64) >= 0);
+        assert_eq!(large.encode() >> 33, 0x3FFF_FFFF);
+    }
+}
@@ -0,0 +1,157 @@
+// Karnal64 ktask: zamanlayıcı kuyruğu (timer queue).
+// Her CPU'nun, o CPU'da zaman aşımıyla bloklanan (uyku, WaitQueue zaman aşımı) iş parçacıklarının
+// uyanma anlarını tutan bir min-heap'i vardır. Anahtar monoton saat (nanosaniye), değer iş
+// parçacığı tablo indeksidir. Bir iş parçacığı en fazla bir kuyrukta bulunur (bkz.
+// ThreadSlot::timer_cpu), bu yüzden kapasite MAX_THREADS yeterlidir ve kuyruk dolmaz.
+// Kuyruğun başı, CPU'nun tek seferlik zamanlayıcısının (ArchTask::program_timer) kurulacağı en
+// erken olaylardan biridir; süresi dolan girdiler timer_tick'te çıkarılır.
+
+use super::MAX_THREADS;
+
+#[derive(Debug, Copy, Clone)]
+struct TimerEntry {
+    deadline_ns: u64,
+    slot: u8,
+}
+
+const EMPTY_ENTRY: TimerEntry = TimerEntry { deadline_ns: 0, slot: 0 };
+
+/// Uyanma anına göre sıralı ikili min-heap.
+pub(super) struct TimerQueue {
+    entries: [TimerEntry; MAX_THREADS],
+    len: usize,
+}
+
+impl TimerQueue {
+    pub(super) const fn new() -> Self {
+        TimerQueue { entries: [EMPTY_ENTRY; MAX_THREADS], len: 0 }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// `slot`'u `deadline_ns` anında uyanmak üzere ekler.
+    pub(super) fn push(&mut self, deadline_ns: u64, slot: usize) {
+        debug_assert!(self.len < MAX_THREADS);
+        if self.len == MAX_THREADS {
+            return;
+        }
+        self.entries[self.len] = TimerEntry { deadline_ns, slot: slot as u8 };
+        self.len += 1;
+        self.sift_up(self.len - 1);
+    }
+
+    /// En erken uyanma anı ve iş parçacığı.
+    pub(super) fn peek(&self) -> Option<(u64, usize)> {
+        if self.len == 0 {
+            return None;
+        }
+        let first = self.entries[0];
+        Some((first.deadline_ns, first.slot as usize))
+    }
+
+    /// En erken girdiyi çıkarır.
+    pub(super) fn pop(&mut self) -> Option<(u64, usize)> {
+        let first = self.peek()?;
+        self.remove_at(0);
+        Some(first)
+    }
+
+    /// `slot`'un girdisini (varsa) çıkarır; iş parçacığı süresi dolmadan uyandırıldığında kullanılır.
+    pub(super) fn remove(&mut self, slot: usize) -> bool {
+        match (0..self.len).find(|&i| self.entries[i].slot as usize == slot) {
+            Some(index) => {
+                self.remove_at(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn remove_at(&mut self, index: usize) {
+        self.len -= 1;
+        if index == self.len {
+            return;
+        }
+        self.entries[index] = self.entries[self.len];
+        // Yerine konan son girdi, bulunduğu yere göre yukarı veya aşağı kayar.
+        if index > 0 && self.entries[index].deadline_ns < self.entries[(index - 1) / 2].deadline_ns {
+            self.sift_up(index);
+        } else {
+            self.sift_down(index);
+        }
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if self.entries[parent].deadline_ns <= self.entries[index].deadline_ns {
+                break;
+            }
+            self.entries.swap(parent, index);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let left = 2 * index + 1;
+            let right = left + 1;
+            let mut smallest = index;
+            if left < self.len && self.entries[left].deadline_ns < self.entries[smallest].deadline_ns {
+                smallest = left;
+            }
+            if right < self.len && self.entries[right].deadline_ns < self.entries[smallest].deadline_ns {
+                smallest = right;
+            }
+            if smallest == index {
+                break;
+            }
+            self.entries.swap(smallest, index);
+            index = smallest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Kuyruğu boşaltır; (uyanma anı, iş parçacığı) çiftlerini çıkış sırasıyla döner.
+    fn drain(queue: &mut TimerQueue) -> Vec<(u64, usize)> {
+        core::iter::from_fn(|| queue.pop()).collect()
+    }
+
+    #[test]
+    fn test_pop_in_deadline_order() {
+        let mut queue = TimerQueue::new();
+        assert_eq!(queue.peek(), None);
+        for (deadline, slot) in [(30, 1), (10, 2), (50, 3), (20, 4), (40, 5)] {
+            queue.push(deadline, slot);
+        }
+        assert_eq!(queue.len(), 5);
+        assert_eq!(queue.peek(), Some((10, 2)));
+        assert_eq!(drain(&mut queue), [(10, 2), (20, 4), (30, 1), (40, 5), (50, 3)]);
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn test_remove_keeps_heap_order() {
+        let mut queue = TimerQueue::new();
+        // Tekrar eden anlar dahil karışık sırada MAX_THREADS girdi.
+        for slot in 0..MAX_THREADS {
+            queue.push(((slot * 37) % 23) as u64, slot);
+        }
+        assert_eq!(queue.len(), MAX_THREADS);
+        for slot in (0..MAX_THREADS).step_by(3) {
+            assert!(queue.remove(slot));
+        }
+        assert!(!queue.remove(0));
+
+        let entries = drain(&mut queue);
+        assert_eq!(entries.len(), MAX_THREADS - MAX_THREADS.div_ceil(3));
+        assert!(entries.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert!(entries.iter().all(|&(deadline, slot)| slot % 3 != 0 && deadline == ((slot * 37) % 23) as u64));
+    }
+}
@@ -0,0 +1,213 @@
+// Karnal64 ktask: ertelenmiş iş kuyrukları (work queue).
+// Kesme işleyicileri (üst yarı) yalnızca donanımı onaylar ve kaybolacak veriyi okur (örn. klavye
+// tarama kodu, PLIC kesme kimliği); geri kalan iş (alt yarı) `queue_work` ile ertelenir.
+// Her CPU'nun bir FIFO'su ve ona bağlı, WORKER_PRIORITY öncelikli bir çekirdek çalışanı (kworker)
+// vardır. İş, kuyruğa alındığı CPU'nun çalışanında iş parçacığı bağlamında çalışır: kesmeler
+// açıktır, bloklanabilir ve kilit alabilir. Aynı CPU'ya alınan işler sırayla çalışır.
+// Gecikmeli işler CPU başına bir tabloda son tarihleriyle tutulur. Çalışan, işi yoksa en erken son
+// tarihe kadar bloklanır; bu uyanma CPU'nun zamanlayıcı kuyruğundan gelir (bkz. srctimer.rs) ve
+// süresi dolan işler FIFO'ya taşınır.
+// Çalışanlar CPU idle döngüsüne girerken başlatılır (`start_worker`); öncesinde kuyruğa alınan
+// işler bekler.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use super::super::ksync::Spinlock;
+use super::super::{KError, KThreadId};
+use super::{
+    cancel_block, current_cpu, current_thread_id, now_ns, possible_cpus, prepare_to_block, schedule, spawn_kernel_thread_on, wake,
+    Priority, TaskState, WakeReason, MAX_CPUS, PRIORITY_USER_MAX,
+};
+
+/// CPU başına kuyrukta bekleyebilecek en fazla iş.
+pub const WORK_QUEUE_DEPTH: usize = 64;
+/// CPU başına en fazla bekleyen gecikmeli iş.
+pub const MAX_DELAYED_WORK: usize = 32;
+/// Çalışanların önceliği: tüm kullanıcı iş parçacıklarının üstünde.
+pub const WORKER_PRIORITY: Priority = PRIORITY_USER_MAX + 1;
+
+#[derive(Copy, Clone)]
+struct WorkItem {
+    func: fn(usize),
+    arg: usize,
+}
+
+/// `queue_delayed_work` ile alınan işin kimliği; süresi dolmadan `cancel_delayed_work` ile iptal edilebilir.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DelayedWorkId(pub u64);
+
+#[derive(Copy, Clone)]
+struct DelayedItem {
+    id: u64,
+    due_ns: u64,
+    work: WorkItem,
+}
+
+struct CpuWorkQueue {
+    items: [Option<WorkItem>; WORK_QUEUE_DEPTH],
+    head: usize,
+    len: usize,
+    delayed: [Option<DelayedItem>; MAX_DELAYED_WORK],
+    /// CPU'nun çalışanı; `start_worker` çağrılana kadar `None`.
+    worker: Option<KThreadId>,
+}
+
+impl CpuWorkQueue {
+    const fn new() -> Self {
+        CpuWorkQueue {
+            items: [None; WORK_QUEUE_DEPTH],
+            head: 0,
+            len: 0,
+            delayed: [None; MAX_DELAYED_WORK],
+            worker: None,
+        }
+    }
+
+    fn push(&mut self, work: WorkItem) -> Result<(), KError> {
+        if self.len == WORK_QUEUE_DEPTH {
+            return Err(KError::OutOfMemory);
+        }
+        self.items[(self.head + self.len) % WORK_QUEUE_DEPTH] = Some(work);
+        self.len += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<WorkItem> {
+        if self.len == 0 {
+            return None;
+        }
+        let work = self.items[self.head].take();
+        self.head = (self.head + 1) % WORK_QUEUE_DEPTH;
+        self.len -= 1;
+        work
+    }
+
+    /// En erken gecikmeli işin son tarihi.
+    fn next_due(&self) -> Option<u64> {
+        self.delayed.iter().flatten().map(|d| d.due_ns).min()
+    }
+
+    /// Son tarihi `now` anına gelmiş gecikmeli işleri son tarih sırasıyla FIFO'ya taşır. FIFO
+    /// doluysa kalanlar tabloda bekler ve çalışan bir sonraki turda tekrar dener.
+    fn release_due(&mut self, now: u64) {
+        while self.len < WORK_QUEUE_DEPTH {
+            let due = self
+                .delayed
+                .iter_mut()
+                .filter(|d| matches!(d, Some(d) if d.due_ns <= now))
+                .min_by_key(|d| d.map(|d| d.due_ns));
+            match due.and_then(|d| d.take()) {
+                Some(item) => {
+                    let _ = self.push(item.work);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+const EMPTY_QUEUE: CpuWorkQueue = CpuWorkQueue::new();
+static QUEUES: Spinlock<[CpuWorkQueue; MAX_CPUS]> = Spinlock::named([EMPTY_QUEUE; MAX_CPUS], "ktask::WORK_QUEUES");
+
+/// Gecikmeli iş kimlikleri; 0 kullanılmaz.
+static NEXT_DELAYED_ID: AtomicU64 = AtomicU64::new(1);
+
+/// `func(arg)`'ı mevcut CPU'nun çalışanında çalıştırılmak üzere kuyruğa alır. Kesme bağlamından
+/// çağrılabilir. Kuyruk doluysa `KError::OutOfMemory` döner.
+pub fn queue_work(func: fn(usize), arg: usize) -> Result<(), KError> {
+    queue_work_on(current_cpu(), func, arg)
+}
+
+/// `queue_work`'ün belirli bir CPU'yu hedefleyen biçimi.
+pub fn queue_work_on(cpu: usize, func: fn(usize), arg: usize) -> Result<(), KError> {
+    if cpu >= possible_cpus() {
+        return Err(KError::InvalidArgument);
+    }
+    let worker = {
+        let mut queues = QUEUES.lock_irqsave();
+        queues[cpu].push(WorkItem { func, arg })?;
+        queues[cpu].worker
+    };
+    if let Some(worker) = worker {
+        wake(worker, WakeReason::Signaled);
+    }
+    Ok(())
+}
+
+/// `func(arg)`'ı en erken `delay_ns` sonra mevcut CPU'nun çalışanında çalıştırır. Kesme
+/// bağlamından çağrılabilir. Tablo doluysa `KError::OutOfMemory` döner.
+pub fn queue_delayed_work(func: fn(usize), arg: usize, delay_ns: u64) -> Result<DelayedWorkId, KError> {
+    queue_delayed_work_on(current_cpu(), func, arg, delay_ns)
+}
+
+/// `queue_delayed_work`'ün belirli bir CPU'yu hedefleyen biçimi.
+pub fn queue_delayed_work_on(cpu: usize, func: fn(usize), arg: usize, delay_ns: u64) -> Result<DelayedWorkId, KError> {
+    if cpu >= possible_cpus() {
+        return Err(KError::InvalidArgument);
+    }
+    let id = NEXT_DELAYED_ID.fetch_add(1, Ordering::Relaxed);
+    let due_ns = now_ns().saturating_add(delay_ns);
+    let worker = {
+        let mut queues = QUEUES.lock_irqsave();
+        let slot = queues[cpu].delayed.iter_mut().find(|d| d.is_none()).ok_or(KError::OutOfMemory)?;
+        *slot = Some(DelayedItem { id, due_ns, work: WorkItem { func, arg } });
+        queues[cpu].worker
+    };
+    // Çalışan daha geç bir son tarihe kadar uyuyor olabilir; uyanıp bekleme süresini yeniden hesaplar.
+    if let Some(worker) = worker {
+        wake(worker, WakeReason::Signaled);
+    }
+    Ok(DelayedWorkId(id))
+}
+
+/// Henüz süresi dolmamış gecikmeli işi iptal eder. İş FIFO'ya taşınmış veya çalışmışsa `false` döner.
+pub fn cancel_delayed_work(id: DelayedWorkId) -> bool {
+    let mut queues = QUEUES.lock_irqsave();
+    for queue in queues.iter_mut() {
+        if let Some(slot) = queue.delayed.iter_mut().find(|d| matches!(d, Some(d) if d.id == id.0)) {
+            *slot = None;
+            return true;
+        }
+    }
+    false
+}
+
+/// `cpu`'nun çalışanını (henüz yoksa) oluşturur. `idle_loop` her CPU için bir kez çağırır.
+pub(super) fn start_worker(cpu: usize) {
+    if QUEUES.lock_irqsave()[cpu].worker.is_some() {
+        return;
+    }
+    if let Err(e) = spawn_kernel_thread_on(cpu, WORKER_PRIORITY, worker_main, cpu as u64) {
+        println!("Karnal64: CPU {} iş kuyruğu çalışanı oluşturulamadı: {:?}", cpu, e);
+    }
+}
+
+extern "C" fn worker_main(cpu: u64) -> ! {
+    let cpu = cpu as usize;
+    // Çalışan kendini kaydeder: oluşturan idle iş parçacığından önce çalışır. Kayıttan önce
+    // alınan işler için uyandırma gerekmez, ilk turda kuyruğa bakılır.
+    QUEUES.lock_irqsave()[cpu].worker = Some(current_thread_id());
+    loop {
+        let (work, due) = {
+            let mut queues = QUEUES.lock_irqsave();
+            let queue = &mut queues[cpu];
+            queue.release_due(now_ns());
+            (queue.pop(), queue.next_due())
+        };
+        if let Some(work) = work {
+            (work.func)(work.arg);
+            continue;
+        }
+
+        // İş yoksa en erken gecikmeli işe kadar (veya queue_work uyandırana kadar) bloklan.
+        prepare_to_block(TaskState::Blocked, due);
+        // Kayıttan önce kuyruğa alınan bir işin uyandırması kaybolmuş olabilir; tekrar bak.
+        let pending = {
+            let queues = QUEUES.lock_irqsave();
+            queues[cpu].len > 0 || queues[cpu].next_due() != due
+        };
+        if !pending || !cancel_block() {
+            schedule();
+        }
+    }
+}